@@ -1440,6 +1440,11 @@ impl BufferDiff {
         cx.emit(BufferDiffEvent::HunksStagedOrUnstaged(
             new_index_text.clone(),
         ));
+        Self::emit_hunks_changed(hunks, cx);
+        new_index_text
+    }
+
+    fn emit_hunks_changed(hunks: &[DiffHunk], cx: &mut Context<Self>) {
         if let Some((first, last)) = hunks.first().zip(hunks.last()) {
             let changed_range = Some(first.buffer_range.start..last.buffer_range.end);
             let base_text_changed_range =
@@ -1450,7 +1455,83 @@ impl BufferDiff {
                 extended_range: changed_range,
             }));
         }
-        new_index_text
+    }
+
+    /// Stages or unstages only the changes on the given buffer rows, splitting
+    /// any hunks that are partially selected. Returns the new index text.
+    pub fn stage_or_unstage_lines(
+        &mut self,
+        stage: bool,
+        rows: &[Range<u32>],
+        buffer: &text::BufferSnapshot,
+        file_exists: bool,
+        cx: &mut Context<Self>,
+    ) -> Option<Rope> {
+        let secondary_diff = self.secondary_diff.clone()?;
+        let snapshot = self.snapshot(cx);
+        let mut hunks = rows
+            .iter()
+            .flat_map(|rows| {
+                let end = buffer.clip_point(Point::new(rows.end, 0), Bias::Left);
+                let range =
+                    buffer.anchor_before(Point::new(rows.start, 0))..buffer.anchor_after(end);
+                snapshot.hunks_intersecting_range(range, buffer)
+            })
+            .collect::<Vec<_>>();
+        hunks.dedup_by(|a, b| a.range == b.range);
+
+        // Deleting or creating the file in the index can't be split by line,
+        // so fall back to staging the intersecting hunks.
+        if !file_exists
+            || !self.inner.base_text_exists
+            || !secondary_diff.read(cx).inner.base_text_exists
+        {
+            return self.stage_or_unstage_hunks(stage, &hunks, buffer, file_exists, cx);
+        }
+
+        // Hunks whose lines are all selected are staged like any other hunk,
+        // which also applies the hunks that are still pending.
+        let selected_hunks = hunks
+            .iter()
+            .filter(|hunk| hunk_is_selected(hunk, rows))
+            .cloned()
+            .collect::<Vec<_>>();
+        let index_text = secondary_diff
+            .update(cx, |secondary_diff, cx| {
+                self.inner.stage_or_unstage_hunks_impl(
+                    &secondary_diff.inner,
+                    stage,
+                    &selected_hunks,
+                    buffer,
+                    file_exists,
+                    cx,
+                )
+            })?
+            .to_string();
+
+        let new_index_text = if selected_hunks.len() == hunks.len() {
+            index_text
+        } else if stage {
+            stage_selected_lines(&index_text, &buffer.text(), rows)
+        } else {
+            let buffer_text = buffer.text();
+            let index_rows = rows
+                .iter()
+                .map(|rows| translate_rows_to_base(&index_text, &buffer_text, rows.clone()))
+                .collect::<Vec<_>>();
+            unstage_selected_lines(
+                &self.inner.base_text.read(cx).text(),
+                &index_text,
+                &index_rows,
+            )
+        };
+
+        let new_index_text = Rope::from(new_index_text.as_str());
+        cx.emit(BufferDiffEvent::HunksStagedOrUnstaged(Some(
+            new_index_text.clone(),
+        )));
+        Self::emit_hunks_changed(&hunks, cx);
+        Some(new_index_text)
     }
 
     pub fn stage_or_unstage_all_hunks(
@@ -1921,6 +2002,107 @@ impl DiffHunkStatus {
     }
 }
 
+fn row_is_selected(selection: &[Range<u32>], row: u32) -> bool {
+    selection.iter().any(|selected| selected.contains(&row))
+}
+
+/// Whether all of a hunk's buffer rows are selected. A hunk that only deletes
+/// lines belongs to the row the lines were removed before.
+fn hunk_is_selected(hunk: &DiffHunk, selection: &[Range<u32>]) -> bool {
+    let start = hunk.range.start.row;
+    let end = if hunk.range.end.column > 0 {
+        hunk.range.end.row + 1
+    } else {
+        hunk.range.end.row
+    };
+    (start..end.max(start + 1)).all(|row| row_is_selected(selection, row))
+}
+
+/// Stages the changes from `index_text` to `buffer_text` on the selected
+/// buffer rows.
+fn stage_selected_lines(index_text: &str, buffer_text: &str, selection: &[Range<u32>]) -> String {
+    apply_selected_lines(index_text, buffer_text, selection, true)
+}
+
+/// Reverts the changes from `head_text` to `index_text` on the selected
+/// index rows.
+fn unstage_selected_lines(head_text: &str, index_text: &str, selection: &[Range<u32>]) -> String {
+    apply_selected_lines(index_text, head_text, selection, false)
+}
+
+/// Applies the selected changes from `base_text` to `target_text`, leaving
+/// every other line as it is in `base_text`. The selection refers to rows of
+/// `target_text` if `select_in_target` is set, and of `base_text` otherwise.
+///
+/// The lines of each change are paired up by position, so that a modified
+/// line is only replaced when it is selected. Lines without a counterpart on
+/// the selected side belong to the last selected-side line of their change,
+/// or to the row they were inserted or removed before.
+fn apply_selected_lines(
+    base_text: &str,
+    target_text: &str,
+    selection: &[Range<u32>],
+    select_in_target: bool,
+) -> String {
+    let base_lines = base_text.split_inclusive('\n').collect::<Vec<_>>();
+    let target_lines = target_text.split_inclusive('\n').collect::<Vec<_>>();
+    let mut result = String::with_capacity(base_text.len());
+    let mut base_row = 0;
+    for (old_rows, new_rows) in language::line_diff(base_text, target_text) {
+        result.extend(
+            base_lines[base_row as usize..old_rows.start as usize]
+                .iter()
+                .copied(),
+        );
+
+        let selected_side = if select_in_target {
+            &new_rows
+        } else {
+            &old_rows
+        };
+        let last_row = selected_side.end.saturating_sub(1).max(selected_side.start);
+        let line_count = old_rows.len().max(new_rows.len()) as u32;
+        for offset in 0..line_count {
+            let row = (selected_side.start + offset).min(last_row);
+            let line = if row_is_selected(selection, row) {
+                let target_row = new_rows.start + offset;
+                (target_row < new_rows.end).then(|| target_lines[target_row as usize])
+            } else {
+                let base_row = old_rows.start + offset;
+                (base_row < old_rows.end).then(|| base_lines[base_row as usize])
+            };
+            result.extend(line);
+        }
+        base_row = old_rows.end;
+    }
+    result.extend(base_lines[base_row as usize..].iter().copied());
+    result
+}
+
+/// Translates a range of rows in `text` to the corresponding rows in
+/// `base_text`. Rows that were changed map to the whole base text range they
+/// replaced.
+fn translate_rows_to_base(base_text: &str, text: &str, rows: Range<u32>) -> Range<u32> {
+    let edits = language::line_diff(base_text, text);
+    let translate_row = |row: u32| -> Range<u32> {
+        let mut delta = 0i64;
+        for (old_rows, new_rows) in &edits {
+            if row < new_rows.start {
+                break;
+            }
+            if row < new_rows.end {
+                return old_rows.clone();
+            }
+            delta = old_rows.end as i64 - new_rows.end as i64;
+        }
+        let row = (row as i64 + delta).max(0) as u32;
+        row..row + 1
+    };
+    let start = translate_row(rows.start).start;
+    let end = translate_row(rows.end.saturating_sub(1).max(rows.start)).end;
+    start..end.max(start)
+}
+
 #[cfg(any(test, feature = "test-support"))]
 #[track_caller]
 pub fn assert_hunks<ExpectedText, HunkIter>(
@@ -2454,6 +2636,302 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_stage_and_unstage_lines(cx: &mut TestAppContext) {
+        struct Example {
+            name: &'static str,
+            stage: bool,
+            head_text: String,
+            index_text: String,
+            buffer_marked_text: String,
+            final_index_text: String,
+        }
+
+        let table = [
+            Example {
+                name: "stage one of two inserted lines",
+                stage: true,
+                head_text: "
+                    one
+                    two
+                "
+                .unindent(),
+                index_text: "
+                    one
+                    two
+                "
+                .unindent(),
+                buffer_marked_text: "
+                    one
+                    «ADDED_1»
+                    ADDED_2
+                    two
+                "
+                .unindent(),
+                final_index_text: "
+                    one
+                    ADDED_1
+                    two
+                "
+                .unindent(),
+            },
+            Example {
+                name: "stage one of two modified lines",
+                stage: true,
+                head_text: "
+                    one
+                    two
+                    three
+                    four
+                "
+                .unindent(),
+                index_text: "
+                    one
+                    two
+                    three
+                    four
+                "
+                .unindent(),
+                buffer_marked_text: "
+                    one
+                    TWO
+                    TH«RE»E
+                    four
+                "
+                .unindent(),
+                final_index_text: "
+                    one
+                    two
+                    THREE
+                    four
+                "
+                .unindent(),
+            },
+            Example {
+                name: "stage deleted lines",
+                stage: true,
+                head_text: "
+                    one
+                    two
+                    three
+                    four
+                "
+                .unindent(),
+                index_text: "
+                    one
+                    two
+                    three
+                    four
+                "
+                .unindent(),
+                buffer_marked_text: "
+                    one
+                    «four»
+                    FIVE
+                "
+                .unindent(),
+                final_index_text: "
+                    one
+                    four
+                "
+                .unindent(),
+            },
+            Example {
+                name: "unstage one of two staged lines",
+                stage: false,
+                head_text: "
+                    one
+                    two
+                "
+                .unindent(),
+                index_text: "
+                    one
+                    STAGED_1
+                    STAGED_2
+                    two
+                "
+                .unindent(),
+                buffer_marked_text: "
+                    one
+                    STAGED_1
+                    «STAGED_2»
+                    two
+                "
+                .unindent(),
+                final_index_text: "
+                    one
+                    STAGED_1
+                    two
+                "
+                .unindent(),
+            },
+            Example {
+                name: "unstage a staged line next to an unstaged line",
+                stage: false,
+                head_text: "
+                    one
+                    two
+                    three
+                "
+                .unindent(),
+                index_text: "
+                    one
+                    TWO
+                    three
+                "
+                .unindent(),
+                buffer_marked_text: "
+                    one
+                    «TWO
+                    THREE»
+                "
+                .unindent(),
+                final_index_text: "
+                    one
+                    two
+                    three
+                "
+                .unindent(),
+            },
+        ];
+
+        for example in table {
+            let (buffer_text, ranges) = marked_text_ranges(&example.buffer_marked_text, false);
+            let buffer = Buffer::new(ReplicaId::LOCAL, BufferId::new(1).unwrap(), buffer_text);
+            let rows = ranges
+                .iter()
+                .map(|range| {
+                    buffer.offset_to_point(range.start).row
+                        ..buffer.offset_to_point(range.end).row + 1
+                })
+                .collect::<Vec<_>>();
+
+            let unstaged_diff =
+                cx.new(|cx| BufferDiff::new_with_base_text(&example.index_text, &buffer, cx));
+            let uncommitted_diff = cx.new(|cx| {
+                let mut diff = BufferDiff::new_with_base_text(&example.head_text, &buffer, cx);
+                diff.set_secondary_diff(unstaged_diff);
+                diff
+            });
+
+            let new_index_text = uncommitted_diff.update(cx, |diff, cx| {
+                diff.stage_or_unstage_lines(example.stage, &rows, &buffer, true, cx)
+                    .unwrap()
+                    .to_string()
+            });
+            pretty_assertions::assert_eq!(
+                new_index_text,
+                example.final_index_text,
+                "example: {}",
+                example.name
+            );
+        }
+    }
+
+    #[gpui::test]
+    async fn test_stage_lines_with_pending_hunks(cx: &mut TestAppContext) {
+        let head_text = "
+            one
+            two
+            three
+            four
+            five
+        "
+        .unindent();
+        let buffer_text = "
+            one
+            TWO
+            three
+            FOUR_A
+            FOUR_B
+            five
+        "
+        .unindent();
+
+        let buffer = Buffer::new(
+            ReplicaId::LOCAL,
+            BufferId::new(1).unwrap(),
+            buffer_text.clone(),
+        );
+        let unstaged_diff = cx.new(|cx| BufferDiff::new_with_base_text(&head_text, &buffer, cx));
+        let uncommitted_diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new_with_base_text(&head_text, &buffer, cx);
+            diff.set_secondary_diff(unstaged_diff.clone());
+            diff
+        });
+        let changed_ranges = Arc::new(std::sync::Mutex::new(Vec::new()));
+        cx.update(|cx| {
+            let changed_ranges = changed_ranges.clone();
+            cx.subscribe(&uncommitted_diff, move |_, event, _| {
+                if let BufferDiffEvent::DiffChanged(changed) = event {
+                    changed_ranges
+                        .lock()
+                        .unwrap()
+                        .push(changed.changed_range.clone());
+                }
+            })
+            .detach();
+        });
+        let secondary_statuses = |diff: &BufferDiff, cx: &App| {
+            diff.snapshot(cx)
+                .hunks(&buffer)
+                .map(|hunk| hunk.secondary_status)
+                .collect::<Vec<_>>()
+        };
+
+        uncommitted_diff.update(cx, |diff, cx| {
+            let first_hunk = diff.snapshot(cx).hunks(&buffer).next().unwrap();
+            diff.stage_or_unstage_hunks(true, &[first_hunk], &buffer, true, cx);
+
+            // The index hasn't been reloaded yet, so the pending hunk is kept
+            // when staging some of the lines of the other hunk.
+            let new_index_text = diff
+                .stage_or_unstage_lines(true, &[3..4], &buffer, true, cx)
+                .unwrap()
+                .to_string();
+            assert_eq!(
+                new_index_text,
+                "
+                    one
+                    TWO
+                    three
+                    FOUR_A
+                    five
+                "
+                .unindent()
+            );
+            assert_eq!(
+                secondary_statuses(diff, cx),
+                [
+                    DiffHunkSecondaryStatus::SecondaryHunkRemovalPending,
+                    DiffHunkSecondaryStatus::HasSecondaryHunk
+                ]
+            );
+
+            // Selecting all of a hunk's lines stages it like any other hunk.
+            let new_index_text = diff
+                .stage_or_unstage_lines(true, &[3..5], &buffer, true, cx)
+                .unwrap()
+                .to_string();
+            assert_eq!(new_index_text, buffer_text);
+            assert_eq!(
+                secondary_statuses(diff, cx),
+                [DiffHunkSecondaryStatus::SecondaryHunkRemovalPending; 2]
+            );
+        });
+
+        let changed_rows = changed_ranges
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|range| {
+                let range = range.as_ref().unwrap().to_point(&buffer);
+                range.start.row..range.end.row
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(changed_rows, [1..2, 3..5, 3..5]);
+    }
+
     #[gpui::test]
     async fn test_stage_all_with_nested_hunks(cx: &mut TestAppContext) {
         // This test reproduces a crash where staging all hunks would cause an underflow
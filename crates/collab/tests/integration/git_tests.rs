@@ -1,7 +1,10 @@
 use std::path::Path;
 
 use call::ActiveCall;
-use git::status::{FileStatus, StatusCode, TrackedStatus};
+use git::{
+    repository::RepoPath,
+    status::{FileStatus, StatusCode, TrackedStatus},
+};
 use git_ui::project_diff::ProjectDiff;
 use gpui::{TestAppContext, VisualTestContext};
 use project::ProjectPath;
@@ -133,3 +136,78 @@ async fn test_project_diff(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
         );
     });
 }
+
+#[gpui::test]
+async fn test_stage_lines_as_guest(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.background_executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    client_a
+        .fs()
+        .insert_tree(
+            path!("/a"),
+            json!({
+                ".git": {},
+                "foo.txt": "one\nADDED_1\nADDED_2\ntwo\n",
+            }),
+        )
+        .await;
+    client_a.fs().set_head_and_index_for_repo(
+        Path::new(path!("/a/.git")),
+        &[("foo.txt", "one\ntwo\n".to_string())],
+    );
+    let (project_a, worktree_id) = client_a.build_local_project(path!("/a"), cx_a).await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    let buffer_b = project_b
+        .update(cx_b, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("foo.txt")), cx)
+        })
+        .await
+        .unwrap();
+    let diff_b = project_b
+        .update(cx_b, |project, cx| {
+            project.open_uncommitted_diff(buffer_b.clone(), cx)
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+
+    let index_text = || {
+        client_a
+            .fs()
+            .with_git_state(Path::new(path!("/a/.git")), false, |state| {
+                state
+                    .index_contents
+                    .get(&RepoPath::from_rel_path(rel_path("foo.txt")))
+                    .cloned()
+            })
+            .unwrap()
+    };
+
+    diff_b.update(cx_b, |diff, cx| {
+        let snapshot = buffer_b.read(cx).text_snapshot();
+        diff.stage_or_unstage_lines(true, &[1..2], &snapshot, true, cx);
+    });
+    cx_b.run_until_parked();
+    cx_a.run_until_parked();
+    assert_eq!(index_text().as_deref(), Some("one\nADDED_1\ntwo\n"));
+
+    diff_b.update(cx_b, |diff, cx| {
+        let snapshot = buffer_b.read(cx).text_snapshot();
+        diff.stage_or_unstage_lines(false, &[1..2], &snapshot, true, cx);
+    });
+    cx_b.run_until_parked();
+    cx_a.run_until_parked();
+    assert_eq!(index_text().as_deref(), Some("one\ntwo\n"));
+}
@@ -33,6 +33,9 @@ pub trait DapLocator: Send + Sync {
 #[derive(Default)]
 struct DapRegistryState {
     adapters: BTreeMap<DebugAdapterName, Arc<dyn DebugAdapter>>,
+    /// Adapters replaced by a later adapter with the same name, such as a built-in adapter
+    /// that an extension also provides. Restored once the replacing adapter is removed.
+    shadowed_adapters: BTreeMap<DebugAdapterName, Arc<dyn DebugAdapter>>,
     locators: FxHashMap<SharedString, Arc<dyn DapLocator>>,
 }
 
@@ -46,9 +49,14 @@ impl DapRegistry {
         cx.default_global::<Self>()
     }
 
+    /// Registers an adapter, taking precedence over any adapter already registered under the
+    /// same name until it is removed.
     pub fn add_adapter(&self, adapter: Arc<dyn DebugAdapter>) {
         let name = adapter.name();
-        let _previous_value = self.0.write().adapters.insert(name, adapter);
+        let mut state = self.0.write();
+        if let Some(previous) = state.adapters.insert(name.clone(), adapter) {
+            state.shadowed_adapters.entry(name).or_insert(previous);
+        }
     }
 
     pub fn add_locator(&self, locator: Arc<dyn DapLocator>) {
@@ -56,7 +64,11 @@ impl DapRegistry {
     }
 
     pub fn remove_adapter(&self, name: &str) {
-        self.0.write().adapters.remove(name);
+        let mut state = self.0.write();
+        state.adapters.remove(name);
+        if let Some(shadowed) = state.shadowed_adapters.remove(name) {
+            state.adapters.insert(shadowed.name(), shadowed);
+        }
     }
 
     pub fn remove_locator(&self, locator: &str) {
@@ -95,3 +107,29 @@ impl DapRegistry {
         self.0.read().adapters.keys().cloned().collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FakeAdapter;
+
+    #[test]
+    fn test_adapter_with_same_name_shadows_existing_adapter() {
+        let registry = DapRegistry::default();
+        let built_in: Arc<dyn DebugAdapter> = Arc::new(FakeAdapter::new());
+        let extension: Arc<dyn DebugAdapter> = Arc::new(FakeAdapter::new());
+
+        registry.add_adapter(built_in.clone());
+        registry.add_adapter(extension.clone());
+        let adapter = registry.adapter(FakeAdapter::ADAPTER_NAME).unwrap();
+        assert!(Arc::ptr_eq(&adapter, &extension));
+        assert_eq!(registry.enumerate_adapters::<Vec<_>>().len(), 1);
+
+        registry.remove_adapter(FakeAdapter::ADAPTER_NAME);
+        let adapter = registry.adapter(FakeAdapter::ADAPTER_NAME).unwrap();
+        assert!(Arc::ptr_eq(&adapter, &built_in));
+
+        registry.remove_adapter(FakeAdapter::ADAPTER_NAME);
+        assert!(registry.adapter(FakeAdapter::ADAPTER_NAME).is_none());
+    }
+}
@@ -2,7 +2,10 @@ mod codelldb;
 mod gdb;
 mod go;
 mod javascript;
+mod lldb_dap;
+mod php;
 mod python;
+mod ruby;
 
 #[cfg(test)]
 use std::path::PathBuf;
@@ -22,7 +25,10 @@ use gdb::GdbDebugAdapter;
 use go::GoDebugAdapter;
 use gpui::{App, BorrowAppContext};
 use javascript::JsDebugAdapter;
+use lldb_dap::LldbDapDebugAdapter;
+use php::PhpDebugAdapter;
 use python::PythonDebugAdapter;
+use ruby::RubyDebugAdapter;
 use serde_json::json;
 use task::{DebugScenario, ZedDebugConfig};
 
@@ -33,6 +39,9 @@ pub fn init(cx: &mut App) {
         registry.add_adapter(Arc::from(JsDebugAdapter::default()));
         registry.add_adapter(Arc::from(GoDebugAdapter::default()));
         registry.add_adapter(Arc::from(GdbDebugAdapter));
+        registry.add_adapter(Arc::from(LldbDapDebugAdapter));
+        registry.add_adapter(Arc::from(RubyDebugAdapter));
+        registry.add_adapter(Arc::from(PhpDebugAdapter::default()));
 
         #[cfg(any(test, feature = "test-support"))]
         {
@@ -0,0 +1,279 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use dap::{StartDebuggingRequestArguments, adapters::DebugTaskDefinition};
+use gpui::AsyncApp;
+use serde_json::Value;
+use std::ffi::OsStr;
+use task::{DebugRequest, DebugScenario, ZedDebugConfig};
+
+use crate::*;
+
+#[derive(Default)]
+pub(crate) struct LldbDapDebugAdapter;
+
+impl LldbDapDebugAdapter {
+    const ADAPTER_NAME: &'static str = "lldb-dap";
+    /// Name of the binary before it was renamed to `lldb-dap` in LLVM 18.
+    const LEGACY_BINARY_NAME: &'static str = "lldb-vscode";
+}
+
+/// Converts an environment object into the `KEY=VALUE` list form, which is understood by all lldb-dap versions.
+fn env_to_list(env: &serde_json::Map<String, Value>) -> Vec<Value> {
+    env.iter()
+        .filter_map(|(key, value)| Some(format!("{key}={}", value.as_str()?).into()))
+        .collect()
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for LldbDapDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let mut configuration = json!({
+            "request": match zed_scenario.request {
+                DebugRequest::Launch(_) => "launch",
+                DebugRequest::Attach(_) => "attach",
+            },
+        });
+        let map = configuration.as_object_mut().unwrap();
+        match &zed_scenario.request {
+            DebugRequest::Attach(attach) => {
+                map.insert("pid".into(), attach.process_id.into());
+            }
+            DebugRequest::Launch(launch) => {
+                map.insert("program".into(), launch.program.clone().into());
+
+                if !launch.args.is_empty() {
+                    map.insert("args".into(), launch.args.clone().into());
+                }
+                if !launch.env.is_empty() {
+                    map.insert("env".into(), launch.env_json());
+                }
+                if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+                    map.insert("stopOnEntry".into(), stop_on_entry.into());
+                }
+                if let Some(cwd) = launch.cwd.as_ref() {
+                    map.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+                }
+            }
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config: configuration,
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["launch"],
+                                    "description": "Request to launch a new process"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "program": {
+                                    "type": "string",
+                                    "description": "Path to the program to debug"
+                                },
+                                "args": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "Command line arguments passed to the program",
+                                    "default": []
+                                },
+                                "cwd": {
+                                    "type": "string",
+                                    "description": "Working directory of the program being debugged"
+                                },
+                                "env": {
+                                    "type": ["array", "object"],
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "Environment variables for the program, either as an object or as a list of `KEY=VALUE` strings"
+                                },
+                                "stopOnEntry": {
+                                    "type": "boolean",
+                                    "description": "Automatically stop after launch",
+                                    "default": false
+                                },
+                                "runInTerminal": {
+                                    "type": "boolean",
+                                    "description": "Launch the program inside a terminal, so that it can read from stdin",
+                                    "default": false
+                                },
+                                "disableASLR": {
+                                    "type": "boolean",
+                                    "description": "Disable address space layout randomization of the launched program",
+                                    "default": true
+                                },
+                                "initCommands": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "LLDB commands executed when the debugger is initialized, before the target is created"
+                                },
+                                "preRunCommands": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "LLDB commands executed just before the program is launched"
+                                },
+                                "stopCommands": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "LLDB commands executed each time the program stops"
+                                },
+                                "exitCommands": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "LLDB commands executed when the program exits"
+                                },
+                                "sourceMap": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "array",
+                                        "items": {
+                                            "type": "string"
+                                        },
+                                        "minItems": 2,
+                                        "maxItems": 2
+                                    },
+                                    "description": "Pairs of `[from, to]` paths used to remap source file locations"
+                                },
+                                "debuggerRoot": {
+                                    "type": "string",
+                                    "description": "Working directory of lldb-dap itself, used to resolve relative paths in debug information"
+                                }
+                            },
+                            "required": ["program"]
+                        }
+                    ]
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["attach"],
+                                    "description": "Request to attach to an existing process"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "pid": {
+                                    "type": "number",
+                                    "description": "The process ID to attach to"
+                                },
+                                "program": {
+                                    "type": "string",
+                                    "description": "Path to the program to attach to, used to find the process when no `pid` is given"
+                                },
+                                "waitFor": {
+                                    "type": "boolean",
+                                    "description": "Wait for a process named like `program` to be launched, then attach to it",
+                                    "default": false
+                                },
+                                "attachCommands": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "LLDB commands that perform the attach instead of the default behavior, e.g. `gdb-remote localhost:1234`"
+                                },
+                                "initCommands": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "LLDB commands executed when the debugger is initialized"
+                                }
+                            },
+                            "anyOf": [
+                                { "required": ["pid"] },
+                                { "required": ["program"] },
+                                { "required": ["attachCommands"] }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        config: &DebugTaskDefinition,
+        user_installed_path: Option<std::path::PathBuf>,
+        user_args: Option<Vec<String>>,
+        user_env: Option<HashMap<String, String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let lldb_dap_path = if let Some(path) = user_installed_path.filter(|path| path.exists()) {
+            path
+        } else if let Some(path) = delegate.which(OsStr::new(Self::ADAPTER_NAME)).await {
+            path
+        } else {
+            delegate
+                .which(OsStr::new(Self::LEGACY_BINARY_NAME))
+                .await
+                .context("Could not find lldb-dap in path. Install LLDB or set `binary` in the `dap.lldb-dap` settings")?
+        };
+
+        let mut configuration = config.config.clone();
+        if let Some(configuration) = configuration.as_object_mut() {
+            configuration
+                .entry("cwd")
+                .or_insert_with(|| delegate.worktree_root_path().to_string_lossy().into());
+
+            if let Some(Value::Object(env)) = configuration.get("env") {
+                let env = env_to_list(env);
+                configuration.insert("env".into(), env.into());
+            }
+        }
+
+        Ok(DebugAdapterBinary {
+            command: Some(lldb_dap_path.to_string_lossy().into_owned()),
+            arguments: user_args.unwrap_or_default(),
+            envs: user_env.unwrap_or_default(),
+            cwd: Some(delegate.worktree_root_path().to_path_buf()),
+            connection: None,
+            request_args: StartDebuggingRequestArguments {
+                request: self.request_kind(&config.config).await?,
+                configuration,
+            },
+        })
+    }
+}
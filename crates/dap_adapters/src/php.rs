@@ -0,0 +1,276 @@
+use adapters::latest_github_release;
+use anyhow::{Context as _, bail};
+use collections::HashMap;
+use dap::{StartDebuggingRequestArguments, adapters::DebugTaskDefinition};
+use gpui::{AsyncApp, SharedString};
+use language::LanguageName;
+use std::{ffi::OsStr, path::PathBuf, sync::OnceLock};
+use task::DebugRequest;
+use util::ResultExt;
+
+use crate::*;
+
+#[derive(Debug, Default)]
+pub(crate) struct PhpDebugAdapter {
+    checked: OnceLock<()>,
+}
+
+impl PhpDebugAdapter {
+    const ADAPTER_NAME: &'static str = "Xdebug";
+    const ADAPTER_REPO: &'static str = "xdebug/vscode-php-debug";
+    const ADAPTER_PATH: &'static str = "extension/out/phpDebug.js";
+
+    async fn fetch_latest_adapter_version(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+    ) -> Result<AdapterVersion> {
+        let release =
+            latest_github_release(Self::ADAPTER_REPO, true, false, delegate.http_client()).await?;
+
+        let asset_name = format!(
+            "php-debug-{}.vsix",
+            release.tag_name.trim_start_matches('v')
+        );
+
+        Ok(AdapterVersion {
+            tag_name: release.tag_name,
+            url: release
+                .assets
+                .iter()
+                .find(|asset| asset.name == asset_name)
+                .with_context(|| format!("no asset found matching {asset_name:?}"))?
+                .browser_download_url
+                .clone(),
+        })
+    }
+
+    async fn get_installed_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        user_args: Option<Vec<String>>,
+        user_env: Option<HashMap<String, String>>,
+    ) -> Result<DebugAdapterBinary> {
+        let tcp_connection = task_definition.tcp_connection.clone().unwrap_or_default();
+        let (host, port, timeout) = crate::configure_tcp_connection(tcp_connection).await?;
+
+        let mut configuration = task_definition.config.clone();
+        if let Some(configuration) = configuration.as_object_mut() {
+            configuration
+                .entry("cwd")
+                .or_insert(delegate.worktree_root_path().to_string_lossy().into());
+
+            // Scripts launched by the adapter are run with `php` from the user's PATH.
+            if configuration.contains_key("program")
+                && !configuration.contains_key("runtimeExecutable")
+                && let Some(php) = delegate.which(OsStr::new("php")).await
+            {
+                configuration.insert(
+                    "runtimeExecutable".to_owned(),
+                    php.to_string_lossy().into_owned().into(),
+                );
+            }
+        }
+
+        let adapter_path = if let Some(user_installed_path) = user_installed_path {
+            user_installed_path
+        } else {
+            let adapter_path = paths::debug_adapters_dir().join(self.name().as_ref());
+
+            let file_name_prefix = format!("{}_", self.name());
+
+            util::fs::find_file_name_in_dir(adapter_path.as_path(), |file_name| {
+                file_name.starts_with(&file_name_prefix)
+            })
+            .await
+            .context("Couldn't find Xdebug dap directory")?
+            .join(Self::ADAPTER_PATH)
+        };
+
+        let mut arguments = vec![
+            adapter_path.to_string_lossy().into_owned(),
+            format!("--server={port}"),
+        ];
+        arguments.extend(user_args.unwrap_or_default());
+
+        Ok(DebugAdapterBinary {
+            command: Some(
+                delegate
+                    .node_runtime()
+                    .binary_path()
+                    .await?
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            arguments,
+            cwd: Some(delegate.worktree_root_path().to_path_buf()),
+            envs: user_env.unwrap_or_default(),
+            connection: Some(adapters::TcpArguments {
+                host,
+                port,
+                timeout,
+            }),
+            request_args: StartDebuggingRequestArguments {
+                configuration,
+                request: self.request_kind(&task_definition.config).await?,
+            },
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for PhpDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("PHP").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let DebugRequest::Launch(launch) = &zed_scenario.request else {
+            bail!(
+                "Xdebug cannot attach to a running process; use a launch configuration that listens for Xdebug connections instead"
+            );
+        };
+
+        let mut configuration = json!({
+            "request": "launch",
+            "program": launch.program,
+        });
+        let map = configuration.as_object_mut().unwrap();
+        if !launch.args.is_empty() {
+            map.insert("args".into(), launch.args.clone().into());
+        }
+        if !launch.env.is_empty() {
+            map.insert("env".into(), launch.env_json());
+        }
+        if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+            map.insert("stopOnEntry".into(), stop_on_entry.into());
+        }
+        if let Some(cwd) = launch.cwd.as_ref() {
+            map.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config: configuration,
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "properties": {
+                "request": {
+                    "type": "string",
+                    "enum": ["launch"],
+                    "description": "Listen for incoming Xdebug connections, optionally launching a script first"
+                },
+                "port": {
+                    "type": "number",
+                    "description": "Port on which to listen for Xdebug connections. Set to 0 to pick a random port",
+                    "default": 9003
+                },
+                "hostname": {
+                    "type": "string",
+                    "description": "Address to bind to when listening for Xdebug connections",
+                    "default": "::"
+                },
+                "program": {
+                    "type": "string",
+                    "description": "PHP script to launch. When omitted, the adapter only listens for connections, e.g. from a web server"
+                },
+                "args": {
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    },
+                    "description": "Command line arguments passed to the script",
+                    "default": []
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Working directory of the launched script"
+                },
+                "env": {
+                    "type": "object",
+                    "description": "Environment variables for the launched script"
+                },
+                "runtimeExecutable": {
+                    "type": "string",
+                    "description": "Path to the PHP binary used to launch the script. Defaults to `php` from PATH"
+                },
+                "runtimeArgs": {
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    },
+                    "description": "Extra arguments passed to the PHP binary, e.g. `-dxdebug.start_with_request=yes`"
+                },
+                "pathMappings": {
+                    "type": "object",
+                    "description": "Maps paths on the server (keys) to local paths (values), for debugging code running in containers or on remote hosts"
+                },
+                "stopOnEntry": {
+                    "type": "boolean",
+                    "description": "Break at the first line of every request or script",
+                    "default": false
+                },
+                "ignore": {
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    },
+                    "description": "Glob patterns of files in which errors and exceptions should not cause a break"
+                },
+                "xdebugSettings": {
+                    "type": "object",
+                    "description": "Xdebug feature settings, e.g. `max_data` or `max_children`"
+                },
+                "maxConnections": {
+                    "type": "number",
+                    "description": "Maximum number of simultaneous Xdebug connections to accept"
+                },
+                "log": {
+                    "type": "boolean",
+                    "description": "Log the communication between the adapter and Xdebug to the debug console",
+                    "default": false
+                }
+            },
+            "required": ["request"]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        config: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        user_args: Option<Vec<String>>,
+        user_env: Option<HashMap<String, String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        if self.checked.set(()).is_ok() && user_installed_path.is_none() {
+            delegate.output_to_console(format!("Checking latest version of {}...", self.name()));
+            if let Some(version) = self.fetch_latest_adapter_version(delegate).await.log_err() {
+                adapters::download_adapter_from_github(
+                    self.name(),
+                    version,
+                    adapters::DownloadedFileType::Vsix,
+                    delegate.as_ref(),
+                )
+                .await?;
+            } else {
+                delegate.output_to_console(format!("{} debug adapter is up to date", self.name()));
+            }
+        }
+
+        self.get_installed_binary(delegate, config, user_installed_path, user_args, user_env)
+            .await
+    }
+}
@@ -0,0 +1,329 @@
+use anyhow::{Context as _, Result, bail};
+use async_trait::async_trait;
+use collections::HashMap;
+use dap::{StartDebuggingRequestArguments, adapters::DebugTaskDefinition};
+use gpui::{AsyncApp, SharedString};
+use language::LanguageName;
+use serde_json::Value;
+use std::{ffi::OsStr, net::Ipv4Addr, path::PathBuf};
+use task::{DebugRequest, DebugScenario, ZedDebugConfig};
+
+use crate::*;
+
+#[derive(Default)]
+pub(crate) struct RubyDebugAdapter;
+
+impl RubyDebugAdapter {
+    const ADAPTER_NAME: &'static str = "rdbg";
+
+    async fn find_rdbg(
+        delegate: &Arc<dyn DapDelegate>,
+        user_installed_path: Option<PathBuf>,
+        use_bundler: bool,
+    ) -> Result<(String, Vec<String>)> {
+        if let Some(path) = user_installed_path.filter(|path| path.exists()) {
+            return Ok((path.to_string_lossy().into_owned(), Vec::new()));
+        }
+
+        if use_bundler {
+            let bundle = delegate
+                .which(OsStr::new("bundle"))
+                .await
+                .context("`useBundler` is set, but `bundle` could not be found in path")?;
+            return Ok((
+                bundle.to_string_lossy().into_owned(),
+                vec!["exec".into(), Self::ADAPTER_NAME.into()],
+            ));
+        }
+
+        let rdbg = delegate
+            .which(OsStr::new(Self::ADAPTER_NAME))
+            .await
+            .context("Could not find rdbg in path. Install it with `gem install debug`")?;
+        Ok((rdbg.to_string_lossy().into_owned(), Vec::new()))
+    }
+}
+
+/// Builds the rdbg arguments that open a DAP server on `host:port` and start the configured program.
+fn rdbg_arguments(configuration: &Value, host: Ipv4Addr, port: u16) -> Result<Vec<String>> {
+    let mut arguments = vec![
+        "--open".to_owned(),
+        format!("--port={port}"),
+        format!("--host={host}"),
+    ];
+    if !configuration
+        .get("stopOnEntry")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        arguments.push("--nonstop".to_owned());
+    }
+
+    if let Some(script) = configuration.get("script").and_then(Value::as_str) {
+        arguments.extend(["--".to_owned(), script.to_owned()]);
+    } else if let Some(command) = configuration.get("command").and_then(Value::as_str) {
+        arguments.extend(["--command".to_owned(), "--".to_owned(), command.to_owned()]);
+    } else {
+        bail!("rdbg launch configurations need either a `script` or a `command`");
+    }
+    arguments.extend(
+        configuration
+            .get("args")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|arg| arg.as_str().map(str::to_owned)),
+    );
+
+    Ok(arguments)
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for RubyDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("Ruby").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let DebugRequest::Launch(launch) = &zed_scenario.request else {
+            bail!(
+                "rdbg cannot attach to a process by its ID; start it with `rdbg --open --port <port>` and connect to that port instead"
+            );
+        };
+
+        let mut configuration = json!({
+            "request": "launch",
+        });
+        let map = configuration.as_object_mut().unwrap();
+        if launch.program.ends_with(".rb") {
+            map.insert("script".into(), launch.program.clone().into());
+        } else {
+            map.insert("command".into(), launch.program.clone().into());
+        }
+        if !launch.args.is_empty() {
+            map.insert("args".into(), launch.args.clone().into());
+        }
+        if !launch.env.is_empty() {
+            map.insert("env".into(), launch.env_json());
+        }
+        if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+            map.insert("stopOnEntry".into(), stop_on_entry.into());
+        }
+        if let Some(cwd) = launch.cwd.as_ref() {
+            map.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config: configuration,
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["launch"],
+                                    "description": "Request to launch a new Ruby process under rdbg"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "script": {
+                                    "type": "string",
+                                    "description": "The Ruby script to debug"
+                                },
+                                "command": {
+                                    "type": "string",
+                                    "description": "A command to debug instead of a script, e.g. `rspec`, `rake` or `bin/rails`"
+                                },
+                                "args": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "Command line arguments passed to the script or command",
+                                    "default": []
+                                },
+                                "cwd": {
+                                    "type": "string",
+                                    "description": "Working directory of the debugged process"
+                                },
+                                "env": {
+                                    "type": "object",
+                                    "description": "Environment variables for the debugged process"
+                                },
+                                "useBundler": {
+                                    "type": "boolean",
+                                    "description": "Run rdbg through `bundle exec`, so that the `debug` gem from the project's Gemfile is used",
+                                    "default": false
+                                },
+                                "stopOnEntry": {
+                                    "type": "boolean",
+                                    "description": "Stop at the first line of the program",
+                                    "default": false
+                                }
+                            },
+                            "oneOf": [
+                                { "required": ["script"] },
+                                { "required": ["command"] }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["attach"],
+                                    "description": "Request to attach to a process started with `rdbg --open --port <port>`; set the port in `tcp_connection`"
+                                }
+                            }
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        config: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        user_args: Option<Vec<String>>,
+        user_env: Option<HashMap<String, String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let tcp_connection = config.tcp_connection.clone().unwrap_or_default();
+        let (host, port, timeout) = crate::configure_tcp_connection(tcp_connection).await?;
+        let connection = Some(adapters::TcpArguments {
+            host,
+            port,
+            timeout,
+        });
+
+        let request = self.request_kind(&config.config).await?;
+        let configuration = config.config.clone();
+        let cwd = configuration
+            .get("cwd")
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| delegate.worktree_root_path().to_path_buf());
+
+        if matches!(request, dap::StartDebuggingRequestArgumentsRequest::Attach) {
+            // The debuggee is already listening, we only need to connect to it.
+            return Ok(DebugAdapterBinary {
+                command: None,
+                arguments: Vec::new(),
+                envs: HashMap::default(),
+                cwd: Some(cwd),
+                connection,
+                request_args: StartDebuggingRequestArguments {
+                    request,
+                    configuration,
+                },
+            });
+        }
+
+        let use_bundler = configuration
+            .get("useBundler")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let (command, mut arguments) =
+            Self::find_rdbg(delegate, user_installed_path, use_bundler).await?;
+
+        arguments.extend(user_args.unwrap_or_default());
+        arguments.extend(rdbg_arguments(&configuration, host, port)?);
+
+        let mut envs = delegate.shell_env().await;
+        envs.extend(user_env.unwrap_or_default());
+        if let Some(env) = configuration.get("env").and_then(Value::as_object) {
+            envs.extend(
+                env.iter()
+                    .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_owned()))),
+            );
+        }
+
+        Ok(DebugAdapterBinary {
+            command: Some(command),
+            arguments,
+            envs,
+            cwd: Some(cwd),
+            connection,
+            request_args: StartDebuggingRequestArguments {
+                request,
+                configuration,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rdbg_arguments() {
+        let host = Ipv4Addr::new(127, 0, 0, 1);
+
+        let configuration = json!({
+            "request": "launch",
+            "script": "main.rb",
+            "args": ["--verbose"],
+        });
+        assert_eq!(
+            rdbg_arguments(&configuration, host, 12345).unwrap(),
+            [
+                "--open",
+                "--port=12345",
+                "--host=127.0.0.1",
+                "--nonstop",
+                "--",
+                "main.rb",
+                "--verbose"
+            ]
+        );
+
+        let configuration = json!({
+            "request": "launch",
+            "command": "rspec",
+            "args": ["spec/models/user_spec.rb:12"],
+            "stopOnEntry": true,
+        });
+        assert_eq!(
+            rdbg_arguments(&configuration, host, 12345).unwrap(),
+            [
+                "--open",
+                "--port=12345",
+                "--host=127.0.0.1",
+                "--command",
+                "--",
+                "rspec",
+                "spec/models/user_spec.rb:12"
+            ]
+        );
+
+        assert!(rdbg_arguments(&json!({ "request": "launch" }), host, 12345).is_err());
+    }
+}
@@ -4,10 +4,11 @@ use crate::session::running::RunningState;
 use crate::session::running::breakpoint_list::BreakpointList;
 
 use crate::{
-    ClearAllBreakpoints, Continue, CopyDebugAdapterArguments, Detach, FocusBreakpointList,
-    FocusConsole, FocusFrames, FocusLoadedSources, FocusModules, FocusTerminal, FocusVariables,
-    NewProcessModal, NewProcessMode, Pause, RerunSession, StepInto, StepOut, StepOver, Stop,
-    ToggleExpandItem, ToggleSessionPicker, ToggleThreadPicker, persistence, spawn_task_or_modal,
+    ClearAllBreakpoints, Continue, CopyDebugAdapterArguments, Detach, ExportBreakpoints,
+    FocusBreakpointList, FocusConsole, FocusFrames, FocusLoadedSources, FocusModules,
+    FocusTerminal, FocusVariables, ImportBreakpoints, NewProcessModal, NewProcessMode, Pause,
+    RerunSession, StepInto, StepOut, StepOver, Stop, ToggleExpandItem, ToggleSessionPicker,
+    ToggleThreadPicker, persistence, spawn_task_or_modal,
 };
 use anyhow::{Context as _, Result, anyhow};
 use collections::IndexMap;
@@ -18,12 +19,13 @@ use editor::{Editor, MultiBufferOffset, ToPoint};
 use feature_flags::{FeatureFlag, FeatureFlagAppExt as _};
 use gpui::{
     Action, App, AsyncWindowContext, ClipboardItem, Context, Corner, DismissEvent, Entity,
-    EntityId, EventEmitter, FocusHandle, Focusable, MouseButton, MouseDownEvent, Point,
-    Subscription, Task, WeakEntity, anchored, deferred,
+    EntityId, EventEmitter, FocusHandle, Focusable, MouseButton, MouseDownEvent, PathPromptOptions,
+    Point, Subscription, Task, WeakEntity, anchored, deferred,
 };
 
 use itertools::Itertools as _;
 use language::Buffer;
+use project::debugger::breakpoint_store::ExportedBreakpoints;
 use project::debugger::session::{Session, SessionQuirks, SessionState, SessionStateEvent};
 use project::{DebugScenarioContext, Fs, ProjectPath, TaskSourceKind, WorktreeId};
 use project::{Project, debugger::session::ThreadStatus};
@@ -39,6 +41,7 @@ use util::rel_path::RelPath;
 use util::{ResultExt, debug_panic, maybe};
 use workspace::SplitDirection;
 use workspace::item::SaveOptions;
+use workspace::notifications::DetachAndPromptErr;
 use workspace::{
     Item, Pane, Workspace,
     dock::{DockPosition, Panel, PanelEvent},
@@ -165,6 +168,12 @@ impl DebugPanel {
                         },
                     )
                 });
+                workspace.register_action(|workspace, _: &ExportBreakpoints, window, cx| {
+                    Self::export_breakpoints(workspace, window, cx);
+                });
+                workspace.register_action(|workspace, _: &ImportBreakpoints, window, cx| {
+                    Self::import_breakpoints(workspace, window, cx);
+                });
 
                 workspace.set_debugger_provider(DebuggerProvider(debug_panel.clone()));
 
@@ -173,6 +182,61 @@ impl DebugPanel {
         })
     }
 
+    fn export_breakpoints(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let project = workspace.project().read(cx);
+        let breakpoints = project.breakpoint_store().read(cx).export_breakpoints(cx);
+        let directory = project
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .unwrap_or_default();
+        let fs = workspace.app_state().fs.clone();
+        let path = cx.prompt_for_new_path(&directory, Some("breakpoints.json"));
+
+        cx.spawn(async move |_, _| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            let contents = serde_json::to_string_pretty(&breakpoints)?;
+            fs.atomic_write(path, contents).await
+        })
+        .detach_and_prompt_err("Failed to export breakpoints", window, cx, |_, _, _| None);
+    }
+
+    fn import_breakpoints(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let breakpoint_store = workspace.project().read(cx).breakpoint_store();
+        let fs = workspace.app_state().fs.clone();
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import Breakpoints".into()),
+        });
+
+        cx.spawn(async move |_, cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let contents = fs.load(&path).await?;
+            let breakpoints: ExportedBreakpoints = serde_json::from_str(&contents)
+                .with_context(|| format!("parsing breakpoints from {path:?}"))?;
+            breakpoint_store
+                .update(cx, |breakpoint_store, cx| {
+                    breakpoint_store.import_breakpoints(breakpoints, cx)
+                })
+                .await
+        })
+        .detach_and_prompt_err("Failed to import breakpoints", window, cx, |_, _, _| None);
+    }
+
     pub fn start_session(
         &mut self,
         scenario: DebugScenario,
@@ -1872,14 +1936,17 @@ impl Render for DebugPanel {
                             }),
                         );
 
-                    let has_breakpoints = self
-                        .project
-                        .read(cx)
-                        .breakpoint_store()
-                        .read(cx)
+                    let breakpoint_store = self.project.read(cx).breakpoint_store();
+                    let breakpoint_store = breakpoint_store.read(cx);
+                    let has_breakpoints = breakpoint_store
                         .all_source_breakpoints(cx)
                         .values()
-                        .any(|breakpoints| !breakpoints.is_empty());
+                        .any(|breakpoints| !breakpoints.is_empty())
+                        || !breakpoint_store.function_breakpoints().is_empty()
+                        || self
+                            .breakpoint_list
+                            .read(cx)
+                            .is_adding_function_breakpoint();
 
                     let breakpoint_list = v_flex()
                         .group("base-breakpoint-list")
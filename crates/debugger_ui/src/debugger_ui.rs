@@ -1,5 +1,6 @@
 use std::any::TypeId;
 
+use anyhow::Context as _;
 use debugger_panel::DebugPanel;
 use editor::{Editor, MultiBufferOffsetUtf16};
 use gpui::{Action, App, DispatchPhase, EntityInputHandler, actions};
@@ -52,12 +53,20 @@ actions!(
         StepOut,
         /// Steps back to the previous statement.
         StepBack,
+        /// Steps into the next machine instruction.
+        StepIntoInstruction,
+        /// Steps over the next machine instruction, without entering calls.
+        StepOverInstruction,
         /// Stops the debugging session.
         Stop,
         /// Toggles whether to ignore all breakpoints.
         ToggleIgnoreBreakpoints,
         /// Clears all breakpoints in the project.
         ClearAllBreakpoints,
+        /// Exports all breakpoints in the project to a JSON file.
+        ExportBreakpoints,
+        /// Imports breakpoints from a JSON file created by `debugger: export breakpoints`.
+        ImportBreakpoints,
         /// Focuses on the debugger console panel.
         FocusConsole,
         /// Focuses on the variables panel.
@@ -162,6 +171,8 @@ pub fn init(cx: &mut App) {
 
                 let caps = running_state.capabilities(cx);
                 let supports_step_back = caps.supports_step_back.unwrap_or_default();
+                let supports_instruction_stepping =
+                    caps.supports_stepping_granularity.unwrap_or_default();
                 let supports_detach = running_state.session().read(cx).is_attached();
                 let status = running_state.thread_status(cx);
 
@@ -199,6 +210,24 @@ pub fn init(cx: &mut App) {
                             active_item.update(cx, |item, cx| item.step_back(cx)).ok();
                         })
                     })
+                    .when(supports_instruction_stepping, |div| {
+                        div.on_action({
+                            let active_item = active_item.clone();
+                            move |_: &StepIntoInstruction, _, cx| {
+                                active_item
+                                    .update(cx, |item, cx| item.step_in_instruction(cx))
+                                    .ok();
+                            }
+                        })
+                        .on_action({
+                            let active_item = active_item.clone();
+                            move |_: &StepOverInstruction, _, cx| {
+                                active_item
+                                    .update(cx, |item, cx| item.step_over_instruction(cx))
+                                    .ok();
+                            }
+                        })
+                    })
                     .on_action({
                         let active_item = active_item.clone();
                         move |_: &Continue, _, cx| {
@@ -383,6 +412,67 @@ pub fn init(cx: &mut App) {
                         },
                     );
 
+                    window.on_action_when(
+                        session.any_stopped_thread()
+                            && session
+                                .capabilities()
+                                .supports_goto_targets_request
+                                .unwrap_or_default(),
+                        TypeId::of::<editor::actions::SetNextStatement>(),
+                        {
+                            let editor = editor.clone();
+                            let active_session = active_session.clone();
+                            move |_, phase, _, cx| {
+                                if phase != DispatchPhase::Bubble {
+                                    return;
+                                }
+                                maybe!({
+                                    let (buffer, position, _) = editor
+                                        .update(cx, |editor, cx| {
+                                            let cursor_point: language::Point = editor
+                                                .selections
+                                                .newest(&editor.display_snapshot(cx))
+                                                .head();
+
+                                            editor
+                                                .buffer()
+                                                .read(cx)
+                                                .point_to_buffer_point(cursor_point, cx)
+                                        })
+                                        .ok()??;
+
+                                    let path =
+                                debugger::breakpoint_store::BreakpointStore::abs_path_from_buffer(
+                                    &buffer, cx,
+                                )?;
+
+                                    let running_state =
+                                        active_session.read(cx).running_state().clone();
+                                    let thread_id = running_state.read(cx).selected_thread_id()?;
+                                    let session = running_state.read(cx).session().clone();
+                                    let targets = session.update(cx, |session, cx| {
+                                        session.goto_targets(path, position.row, cx)
+                                    });
+
+                                    let line = position.row + 1;
+                                    cx.spawn(async move |cx| {
+                                        let target =
+                                            targets.await?.into_iter().next().with_context(
+                                                || format!("no goto targets on line {line}"),
+                                            )?;
+                                        session.update(cx, |session, cx| {
+                                            session.goto(thread_id, target.id, cx)
+                                        });
+                                        anyhow::Ok(())
+                                    })
+                                    .detach_and_log_err(cx);
+
+                                    Some(())
+                                });
+                            }
+                        },
+                    );
+
                     window.on_action(
                         TypeId::of::<editor::actions::EvaluateSelectedText>(),
                         move |_, _, window, cx| {
@@ -1,5 +1,6 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
 pub(crate) mod loaded_source_list;
 pub(crate) mod memory_view;
 pub(crate) mod module_list;
@@ -18,7 +19,7 @@ use crate::{
     attach_modal::{AttachModal, ModalIntent},
     new_process_modal::resolve_path,
     persistence::{self, DebuggerPaneItem, SerializedLayout},
-    session::running::memory_view::MemoryView,
+    session::running::{disassembly_view::DisassemblyView, memory_view::MemoryView},
 };
 
 use anyhow::{Context as _, Result, anyhow, bail};
@@ -26,7 +27,7 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, DapRegistry, RunInTerminalRequestArguments, Thread,
+    Capabilities, DapRegistry, RunInTerminalRequestArguments, SteppingGranularity, Thread,
     adapters::{DebugAdapterName, DebugTaskDefinition},
     client::SessionId,
     debugger_settings::DebuggerSettings,
@@ -60,7 +61,7 @@ use util::ResultExt;
 use variable_list::VariableList;
 use workspace::{
     ActivePaneDecorator, DraggedTab, Item, ItemHandle, Member, Pane, PaneGroup, SplitDirection,
-    Workspace, item::TabContentParams, move_item, pane::Event,
+    Workspace, WorkspaceId, item::TabContentParams, move_item, pane::Event,
 };
 
 static PROCESS_ID_PLACEHOLDER: LazyLock<String> =
@@ -89,6 +90,9 @@ pub struct RunningState {
     pub(crate) scenario: Option<DebugScenario>,
     pub(crate) scenario_context: Option<DebugScenarioContext>,
     memory_view: Entity<MemoryView>,
+    disassembly_view: Entity<DisassemblyView>,
+    workspace_id: Option<WorkspaceId>,
+    serialized_watches: Vec<SharedString>,
 }
 
 impl RunningState {
@@ -751,6 +755,15 @@ impl RunningState {
                 cx,
             )
         });
+        let disassembly_view = cx.new(|cx| {
+            DisassemblyView::new(
+                session.clone(),
+                stack_frame_list.clone(),
+                weak_state.clone(),
+                workspace.clone(),
+                cx,
+            )
+        });
         let variable_list = cx.new(|cx| {
             VariableList::new(
                 session.clone(),
@@ -824,6 +837,7 @@ impl RunningState {
                         let threads = this.session.update(cx, |this, cx| this.threads(cx));
                         this.select_current_thread(&threads, window, cx);
                     }
+                    SessionEvent::Watchers => this.serialize_watches(cx),
                     SessionEvent::CapabilitiesLoaded => {
                         let capabilities = this.capabilities(cx);
                         if !capabilities.supports_modules_request.unwrap_or(false) {
@@ -873,6 +887,7 @@ impl RunningState {
                 &loaded_source_list,
                 &debug_terminal,
                 &memory_view,
+                &disassembly_view,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -900,8 +915,44 @@ impl RunningState {
         };
         let active_pane = panes.first_pane();
 
+        let scenario = {
+            let session = session.read(cx);
+            session.label().map(|label| (session.adapter(), label))
+        };
+        if let Some((adapter_name, scenario_label)) = scenario {
+            let workspace = workspace.clone();
+            cx.spawn(async move |this, cx| {
+                let Some(workspace_id) = workspace
+                    .read_with(cx, |workspace, _| workspace.database_id())
+                    .ok()
+                    .flatten()
+                else {
+                    return;
+                };
+                let mut expressions =
+                    persistence::get_serialized_watches(workspace_id, adapter_name, scenario_label)
+                        .await;
+                expressions.sort();
+
+                this.update(cx, |this, cx| {
+                    this.workspace_id = Some(workspace_id);
+                    if expressions.is_empty() {
+                        this.serialize_watches(cx);
+                        return;
+                    }
+                    this.serialized_watches = expressions.clone();
+                    this.session.update(cx, |session, cx| {
+                        session.restore_watchers(expressions, cx);
+                    });
+                })
+                .ok();
+            })
+            .detach();
+        }
+
         Self {
             memory_view,
+            disassembly_view,
             session,
             workspace,
             focus_handle,
@@ -923,9 +974,37 @@ impl RunningState {
             _schedule_serialize: None,
             scenario: None,
             scenario_context: None,
+            workspace_id: None,
+            serialized_watches: Vec::new(),
         }
     }
 
+    fn serialize_watches(&mut self, cx: &mut Context<Self>) {
+        let Some(workspace_id) = self.workspace_id else {
+            return;
+        };
+        let session = self.session.read(cx);
+        let Some(scenario_label) = session.label() else {
+            return;
+        };
+        let adapter_name = session.adapter();
+
+        let mut expressions = session.watchers().keys().cloned().collect::<Vec<_>>();
+        expressions.sort();
+        if expressions == self.serialized_watches {
+            return;
+        }
+        self.serialized_watches = expressions.clone();
+
+        cx.background_spawn(persistence::serialize_watches(
+            workspace_id,
+            adapter_name,
+            scenario_label,
+            expressions,
+        ))
+        .detach_and_log_err(cx);
+    }
+
     pub(crate) fn remove_pane_item(
         &mut self,
         item_kind: DebuggerPaneItem,
@@ -1268,6 +1347,7 @@ impl RunningState {
             show_summary: false,
             show_command: false,
             show_rerun: false,
+            problem_matchers: Vec::new(),
         };
 
         let workspace = self.workspace.clone();
@@ -1348,6 +1428,12 @@ impl RunningState {
                 item_kind,
                 cx,
             )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly_view.focus_handle(cx),
+                self.disassembly_view.clone().into(),
+                item_kind,
+                cx,
+            )),
         }
     }
 
@@ -1532,6 +1618,11 @@ impl RunningState {
         &self.module_list
     }
 
+    #[cfg(test)]
+    pub(crate) fn disassembly_view(&self) -> &Entity<DisassemblyView> {
+        &self.disassembly_view
+    }
+
     pub(crate) fn activate_item(
         &mut self,
         item: DebuggerPaneItem,
@@ -1650,6 +1741,26 @@ impl RunningState {
         });
     }
 
+    pub(crate) fn step_over_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_over(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
+    pub(crate) fn step_in_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_in(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
     pub(crate) fn step_out(&mut self, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
@@ -37,12 +37,15 @@ actions!(
         /// Navigates to the previous breakpoint property in the list.
         PreviousBreakpointProperty,
         /// Navigates to the next breakpoint property in the list.
-        NextBreakpointProperty
+        NextBreakpointProperty,
+        /// Adds a breakpoint that stops whenever a function with the given name is entered.
+        AddFunctionBreakpoint
     ]
 );
 #[derive(Clone, Copy, PartialEq)]
 pub(crate) enum SelectedBreakpointKind {
     Source,
+    Function,
     Exception,
     Data,
 }
@@ -59,6 +62,7 @@ pub(crate) struct BreakpointList {
     max_width_index: Option<usize>,
     input: Entity<Editor>,
     strip_mode: Option<ActiveBreakpointStripMode>,
+    adding_function_breakpoint: bool,
     serialize_exception_breakpoints_task: Option<Task<anyhow::Result<()>>>,
 }
 
@@ -105,6 +109,7 @@ impl BreakpointList {
                 selected_ix: None,
                 input: cx.new(|cx| Editor::single_line(window, cx)),
                 strip_mode: None,
+                adding_function_breakpoint: false,
                 serialize_exception_breakpoints_task: None,
             };
             if let Some(name) = adapter_name {
@@ -139,6 +144,33 @@ impl BreakpointList {
         })
     }
 
+    fn edit_function_breakpoint(&self, name: Arc<str>, action: BreakpointEditAction, cx: &mut App) {
+        self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.edit_function_breakpoint(name, action, cx);
+        })
+    }
+
+    pub(crate) fn is_adding_function_breakpoint(&self) -> bool {
+        self.adding_function_breakpoint
+    }
+
+    fn add_function_breakpoint(
+        &mut self,
+        _: &AddFunctionBreakpoint,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.strip_mode.take();
+        self.adding_function_breakpoint = true;
+        self.input.update(cx, |this, cx| {
+            this.set_placeholder_text("Function Name", window, cx);
+            this.set_read_only(false);
+            this.set_text("", window, cx);
+        });
+        self.input.focus_handle(cx).focus(window, cx);
+        cx.notify();
+    }
+
     fn go_to_line_breakpoint(
         &mut self,
         path: Arc<Path>,
@@ -179,6 +211,10 @@ impl BreakpointList {
                     bp.breakpoint.state
                         == project::debugger::breakpoint_store::BreakpointState::Enabled,
                 ),
+                BreakpointEntryKind::FunctionBreakpoint(bp) => (
+                    SelectedBreakpointKind::Function,
+                    bp.breakpoint.state.is_enabled(),
+                ),
                 BreakpointEntryKind::ExceptionBreakpoint(bp) => {
                     (SelectedBreakpointKind::Exception, bp.is_enabled)
                 }
@@ -196,16 +232,17 @@ impl BreakpointList {
         cx: &mut App,
     ) {
         self.strip_mode = Some(prop);
+        self.adding_function_breakpoint = false;
         let placeholder = match prop {
             ActiveBreakpointStripMode::Log => "Set Log Message",
             ActiveBreakpointStripMode::Condition => "Set Condition",
             ActiveBreakpointStripMode::HitCondition => "Set Hit Condition",
         };
-        let mut is_exception_breakpoint = true;
+        let mut is_read_only = true;
         let active_value = self.selected_ix.and_then(|ix| {
-            self.breakpoints.get(ix).and_then(|bp| {
-                if let BreakpointEntryKind::LineBreakpoint(bp) = &bp.kind {
-                    is_exception_breakpoint = false;
+            self.breakpoints.get(ix).and_then(|bp| match &bp.kind {
+                BreakpointEntryKind::LineBreakpoint(bp) => {
+                    is_read_only = false;
                     match prop {
                         ActiveBreakpointStripMode::Log => bp.breakpoint.message.clone(),
                         ActiveBreakpointStripMode::Condition => bp.breakpoint.condition.clone(),
@@ -213,15 +250,34 @@ impl BreakpointList {
                             bp.breakpoint.hit_condition.clone()
                         }
                     }
-                } else {
-                    None
                 }
+                BreakpointEntryKind::FunctionBreakpoint(bp) => {
+                    // Function breakpoints can't have log messages.
+                    is_read_only = prop == ActiveBreakpointStripMode::Log;
+                    match prop {
+                        ActiveBreakpointStripMode::Log => None,
+                        ActiveBreakpointStripMode::Condition => bp.breakpoint.condition.clone(),
+                        ActiveBreakpointStripMode::HitCondition => {
+                            bp.breakpoint.hit_condition.clone()
+                        }
+                    }
+                }
+                BreakpointEntryKind::DataBreakpoint(bp) => {
+                    // Data breakpoints can't have log messages.
+                    is_read_only = prop == ActiveBreakpointStripMode::Log;
+                    match prop {
+                        ActiveBreakpointStripMode::Log => None,
+                        ActiveBreakpointStripMode::Condition => bp.0.dap.condition.clone(),
+                        ActiveBreakpointStripMode::HitCondition => bp.0.dap.hit_condition.clone(),
+                    }
+                }
+                BreakpointEntryKind::ExceptionBreakpoint(_) => None,
             })
         });
 
         self.input.update(cx, |this, cx| {
             this.set_placeholder_text(placeholder, window, cx);
-            this.set_read_only(is_exception_breakpoint);
+            this.set_read_only(is_read_only);
             this.set_text(active_value.as_deref().unwrap_or(""), window, cx);
         });
     }
@@ -309,7 +365,11 @@ impl BreakpointList {
     }
 
     fn dismiss(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
-        if self.input.focus_handle(cx).contains_focused(window, cx) {
+        if self.adding_function_breakpoint {
+            self.adding_function_breakpoint = false;
+            self.focus_handle.focus(window, cx);
+            cx.notify();
+        } else if self.input.focus_handle(cx).contains_focused(window, cx) {
             self.focus_handle.focus(window, cx);
         } else if self.strip_mode.is_some() {
             self.strip_mode.take();
@@ -319,6 +379,23 @@ impl BreakpointList {
         }
     }
     fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.adding_function_breakpoint {
+            let name = self.input.read(cx).text(cx);
+            let name = name.trim();
+            let exists = self
+                .breakpoint_store
+                .read(cx)
+                .function_breakpoints()
+                .iter()
+                .any(|breakpoint| &*breakpoint.name == name);
+            if !exists {
+                self.edit_function_breakpoint(name.into(), BreakpointEditAction::Toggle, cx);
+            }
+            self.adding_function_breakpoint = false;
+            self.focus_handle.focus(window, cx);
+            cx.notify();
+            return;
+        }
         let Some(entry) = self.selected_ix.and_then(|ix| self.breakpoints.get_mut(ix)) else {
             return;
         };
@@ -341,8 +418,8 @@ impl BreakpointList {
                             );
                         }
                     }
-                    ActiveBreakpointStripMode::Condition => {
-                        if let BreakpointEntryKind::LineBreakpoint(line_breakpoint) = &entry.kind {
+                    ActiveBreakpointStripMode::Condition => match &entry.kind {
+                        BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
                             Self::edit_line_breakpoint_inner(
                                 &self.breakpoint_store,
                                 line_breakpoint.breakpoint.path.clone(),
@@ -351,9 +428,28 @@ impl BreakpointList {
                                 cx,
                             );
                         }
-                    }
-                    ActiveBreakpointStripMode::HitCondition => {
-                        if let BreakpointEntryKind::LineBreakpoint(line_breakpoint) = &entry.kind {
+                        BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                            let name = function_breakpoint.breakpoint.name.clone();
+                            self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+                                breakpoint_store.edit_function_breakpoint(
+                                    name,
+                                    BreakpointEditAction::EditCondition(Arc::from(text)),
+                                    cx,
+                                );
+                            });
+                        }
+                        BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                            let id = data_breakpoint.0.dap.data_id.clone();
+                            if let Some(session) = &self.session {
+                                session.update(cx, |session, cx| {
+                                    session.set_data_breakpoint_condition(&id, Some(text), cx);
+                                });
+                            }
+                        }
+                        BreakpointEntryKind::ExceptionBreakpoint(_) => {}
+                    },
+                    ActiveBreakpointStripMode::HitCondition => match &entry.kind {
+                        BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
                             Self::edit_line_breakpoint_inner(
                                 &self.breakpoint_store,
                                 line_breakpoint.breakpoint.path.clone(),
@@ -362,7 +458,26 @@ impl BreakpointList {
                                 cx,
                             );
                         }
-                    }
+                        BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                            let name = function_breakpoint.breakpoint.name.clone();
+                            self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+                                breakpoint_store.edit_function_breakpoint(
+                                    name,
+                                    BreakpointEditAction::EditHitCondition(Arc::from(text)),
+                                    cx,
+                                );
+                            });
+                        }
+                        BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                            let id = data_breakpoint.0.dap.data_id.clone();
+                            if let Some(session) = &self.session {
+                                session.update(cx, |session, cx| {
+                                    session.set_data_breakpoint_hit_condition(&id, Some(text), cx);
+                                });
+                            }
+                        }
+                        BreakpointEntryKind::ExceptionBreakpoint(_) => {}
+                    },
                 }
                 self.focus_handle.focus(window, cx);
             } else {
@@ -377,7 +492,8 @@ impl BreakpointList {
                 let row = line_breakpoint.breakpoint.row;
                 self.go_to_line_breakpoint(path, row, window, cx);
             }
-            BreakpointEntryKind::DataBreakpoint(_)
+            BreakpointEntryKind::FunctionBreakpoint(_)
+            | BreakpointEntryKind::DataBreakpoint(_)
             | BreakpointEntryKind::ExceptionBreakpoint(_) => {}
         }
     }
@@ -402,6 +518,10 @@ impl BreakpointList {
                 let row = line_breakpoint.breakpoint.row;
                 self.edit_line_breakpoint(path, row, BreakpointEditAction::InvertState, cx);
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                let name = function_breakpoint.breakpoint.name.clone();
+                self.edit_function_breakpoint(name, BreakpointEditAction::InvertState, cx);
+            }
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => {
                 let id = exception_breakpoint.id.clone();
                 self.toggle_exception_breakpoint(&id, cx);
@@ -424,10 +544,18 @@ impl BreakpointList {
             return;
         };
 
-        if let BreakpointEntryKind::LineBreakpoint(line_breakpoint) = &mut entry.kind {
-            let path = line_breakpoint.breakpoint.path.clone();
-            let row = line_breakpoint.breakpoint.row;
-            self.edit_line_breakpoint(path, row, BreakpointEditAction::Toggle, cx);
+        match &mut entry.kind {
+            BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
+                let path = line_breakpoint.breakpoint.path.clone();
+                let row = line_breakpoint.breakpoint.row;
+                self.edit_line_breakpoint(path, row, BreakpointEditAction::Toggle, cx);
+            }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                let name = function_breakpoint.breakpoint.name.clone();
+                self.edit_function_breakpoint(name, BreakpointEditAction::Toggle, cx);
+            }
+            BreakpointEntryKind::ExceptionBreakpoint(_)
+            | BreakpointEntryKind::DataBreakpoint(_) => {}
         }
         cx.notify();
     }
@@ -584,7 +712,9 @@ impl BreakpointList {
         let focus_handle = self.focus_handle.clone();
 
         let remove_breakpoint_tooltip = selection_kind.map(|(kind, _)| match kind {
-            SelectedBreakpointKind::Source => "Remove breakpoint from a breakpoint list",
+            SelectedBreakpointKind::Source | SelectedBreakpointKind::Function => {
+                "Remove breakpoint from a breakpoint list"
+            }
             SelectedBreakpointKind::Exception => {
                 "Exception Breakpoints cannot be removed from the breakpoint list"
             }
@@ -603,6 +733,28 @@ impl BreakpointList {
         });
 
         h_flex()
+            .child(
+                IconButton::new("add-function-breakpoint-breakpoint-list", IconName::Plus)
+                    .icon_size(IconSize::Small)
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |_window, cx| {
+                            Tooltip::for_action_in(
+                                "Add Function Breakpoint",
+                                &AddFunctionBreakpoint,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click({
+                        let focus_handle = focus_handle.clone();
+                        move |_, window, cx| {
+                            focus_handle.focus(window, cx);
+                            window.dispatch_action(AddFunctionBreakpoint.boxed_clone(), cx)
+                        }
+                    }),
+            )
             .child(
                 IconButton::new(
                     "disable-breakpoint-breakpoint-list",
@@ -649,9 +801,10 @@ impl BreakpointList {
                             }
                         })
                     })
-                    .disabled(
-                        selection_kind.map(|kind| kind.0) != Some(SelectedBreakpointKind::Source),
-                    )
+                    .disabled(!matches!(
+                        selection_kind.map(|kind| kind.0),
+                        Some(SelectedBreakpointKind::Source | SelectedBreakpointKind::Function)
+                    ))
                     .on_click({
                         move |_, window, cx| {
                             focus_handle.focus(window, cx);
@@ -709,6 +862,18 @@ impl Render for BreakpointList {
                 })
             })
         });
+        let function_breakpoints = self
+            .breakpoint_store
+            .read(cx)
+            .function_breakpoints()
+            .iter()
+            .map(|breakpoint| BreakpointEntry {
+                kind: BreakpointEntryKind::FunctionBreakpoint(FunctionBreakpoint {
+                    breakpoint: breakpoint.clone(),
+                }),
+                weak: weak.clone(),
+            })
+            .collect::<Vec<_>>();
         let exception_breakpoints = self.session.as_ref().into_iter().flat_map(|session| {
             session
                 .read(cx)
@@ -733,6 +898,7 @@ impl Render for BreakpointList {
         });
         self.breakpoints.extend(
             breakpoints
+                .chain(function_breakpoints)
                 .chain(data_breakpoints)
                 .chain(exception_breakpoints),
         );
@@ -748,6 +914,9 @@ impl Render for BreakpointList {
                     let dir_len = line_bp.dir.as_ref().map(|d| d.len()).unwrap_or(0);
                     (name_and_line.len() + dir_len) as f32 * text_pixels
                 }
+                BreakpointEntryKind::FunctionBreakpoint(function_bp) => {
+                    function_bp.breakpoint.name.len() as f32 * text_pixels
+                }
                 BreakpointEntryKind::ExceptionBreakpoint(exc_bp) => {
                     exc_bp.data.label.len() as f32 * text_pixels
                 }
@@ -757,6 +926,8 @@ impl Render for BreakpointList {
             })
             .position_max_by(|left, right| left.total_cmp(right));
 
+        let show_input = self.strip_mode.is_some() || self.adding_function_breakpoint;
+
         v_flex()
             .id("breakpoint-list")
             .key_context("BreakpointList")
@@ -771,6 +942,7 @@ impl Render for BreakpointList {
             .on_action(cx.listener(Self::unset_breakpoint))
             .on_action(cx.listener(Self::next_breakpoint_property))
             .on_action(cx.listener(Self::previous_breakpoint_property))
+            .on_action(cx.listener(Self::add_function_breakpoint))
             .size_full()
             .pt_1()
             .child(self.render_list(cx))
@@ -782,7 +954,7 @@ impl Render for BreakpointList {
                 window,
                 cx,
             )
-            .when_some(self.strip_mode, |this, _| {
+            .when(show_input, |this| {
                 this.child(Divider::horizontal().color(DividerColor::Border))
                     .child(
                         h_flex()
@@ -962,6 +1134,122 @@ impl LineBreakpoint {
     }
 }
 
+#[derive(Clone, Debug)]
+struct FunctionBreakpoint {
+    breakpoint: project::debugger::breakpoint_store::FunctionBreakpoint,
+}
+
+impl FunctionBreakpoint {
+    fn render(
+        &self,
+        props: SupportedBreakpointProperties,
+        strip_mode: Option<ActiveBreakpointStripMode>,
+        ix: usize,
+        is_selected: bool,
+        focus_handle: FocusHandle,
+        list: WeakEntity<BreakpointList>,
+    ) -> ListItem {
+        let is_enabled = self.breakpoint.state.is_enabled();
+        let icon_name = if is_enabled {
+            IconName::DebugBreakpoint
+        } else {
+            IconName::DebugDisabledBreakpoint
+        };
+        let name = self.breakpoint.name.clone();
+
+        ListItem::new(SharedString::from(format!(
+            "function-breakpoint-ui-item-{}",
+            self.breakpoint.name
+        )))
+        .toggle_state(is_selected)
+        .inset(true)
+        .on_click({
+            let list = list.clone();
+            move |_, window, cx| {
+                list.update(cx, |list, cx| list.select_ix(Some(ix), window, cx))
+                    .ok();
+            }
+        })
+        .on_secondary_mouse_down(|_, _, cx| {
+            cx.stop_propagation();
+        })
+        .start_slot(
+            div()
+                .id(SharedString::from(format!(
+                    "function-breakpoint-ui-item-{}-click-handler",
+                    self.breakpoint.name
+                )))
+                .child(
+                    Icon::new(icon_name)
+                        .color(Color::Debugger)
+                        .size(IconSize::XSmall),
+                )
+                .tooltip({
+                    let focus_handle = focus_handle.clone();
+                    move |_window, cx| {
+                        Tooltip::for_action_in(
+                            if is_enabled {
+                                "Disable Function Breakpoint"
+                            } else {
+                                "Enable Function Breakpoint"
+                            },
+                            &ToggleEnableBreakpoint,
+                            &focus_handle,
+                            cx,
+                        )
+                    }
+                })
+                .on_click({
+                    let list = list.clone();
+                    move |_, _, cx| {
+                        list.update(cx, |this, cx| {
+                            this.edit_function_breakpoint(
+                                name.clone(),
+                                BreakpointEditAction::InvertState,
+                                cx,
+                            );
+                        })
+                        .ok();
+                    }
+                }),
+        )
+        .child(
+            h_flex()
+                .w_full()
+                .gap_1()
+                .min_h(rems_from_px(26.))
+                .justify_between()
+                .child(
+                    h_flex()
+                        .id(("function-breakpoint-label", ix))
+                        .gap_0p5()
+                        .child(
+                            Label::new(self.breakpoint.name.to_string())
+                                .size(LabelSize::Small)
+                                .line_height_style(ui::LineHeightStyle::UiLabel),
+                        )
+                        .child(
+                            Label::new("function")
+                                .color(Color::Muted)
+                                .size(LabelSize::Small)
+                                .line_height_style(ui::LineHeightStyle::UiLabel),
+                        ),
+                )
+                .child(BreakpointOptionsStrip {
+                    props,
+                    breakpoint: BreakpointEntry {
+                        kind: BreakpointEntryKind::FunctionBreakpoint(self.clone()),
+                        weak: list,
+                    },
+                    is_selected,
+                    focus_handle,
+                    strip_mode,
+                    index: ix,
+                }),
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 struct ExceptionBreakpoint {
     id: String,
@@ -1173,6 +1461,7 @@ impl ExceptionBreakpoint {
 #[derive(Clone, Debug)]
 enum BreakpointEntryKind {
     LineBreakpoint(LineBreakpoint),
+    FunctionBreakpoint(FunctionBreakpoint),
     ExceptionBreakpoint(ExceptionBreakpoint),
     DataBreakpoint(DataBreakpoint),
 }
@@ -1201,6 +1490,15 @@ impl BreakpointEntry {
                 focus_handle,
                 self.weak.clone(),
             ),
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => function_breakpoint
+                .render(
+                    props.for_function_breakpoints(),
+                    strip_mode,
+                    ix,
+                    is_selected,
+                    focus_handle,
+                    self.weak.clone(),
+                ),
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => exception_breakpoint
                 .render(
                     props.for_exception_breakpoints(),
@@ -1228,6 +1526,11 @@ impl BreakpointEntry {
                 line_breakpoint.breakpoint.path, line_breakpoint.breakpoint.row
             )
             .into(),
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => format!(
+                "function-breakpoint-control-strip--{}",
+                function_breakpoint.breakpoint.name
+            )
+            .into(),
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => format!(
                 "exception-breakpoint-control-strip--{}",
                 exception_breakpoint.id
@@ -1255,7 +1558,13 @@ impl BreakpointEntry {
             BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
                 line_breakpoint.breakpoint.condition.is_some()
             }
-            // We don't support conditions on exception/data breakpoints
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                function_breakpoint.breakpoint.condition.is_some()
+            }
+            BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                data_breakpoint.0.dap.condition.is_some()
+            }
+            // We don't support conditions on exception breakpoints
             _ => false,
         }
     }
@@ -1265,6 +1574,12 @@ impl BreakpointEntry {
             BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
                 line_breakpoint.breakpoint.hit_condition.is_some()
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                function_breakpoint.breakpoint.hit_condition.is_some()
+            }
+            BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                data_breakpoint.0.dap.hit_condition.is_some()
+            }
             _ => false,
         }
     }
@@ -1305,13 +1620,17 @@ impl From<&Capabilities> for SupportedBreakpointProperties {
 }
 
 impl SupportedBreakpointProperties {
+    fn for_function_breakpoints(self) -> Self {
+        // DAP function breakpoints have no log message.
+        self.difference(Self::LOG)
+    }
     fn for_exception_breakpoints(self) -> Self {
         // TODO: we don't yet support conditions for exception breakpoints at the data layer, hence all props are disabled here.
         Self::empty()
     }
     fn for_data_breakpoints(self) -> Self {
-        // TODO: we don't yet support conditions for data breakpoints at the data layer, hence all props are disabled here.
-        Self::empty()
+        // DAP data breakpoints have no log message.
+        self.difference(Self::LOG | Self::EXCEPTION_FILTER_OPTIONS)
     }
 }
 #[derive(IntoElement)]
@@ -0,0 +1,573 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use collections::HashMap;
+use dap::DisassembledInstruction;
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, actions, uniform_list,
+};
+use language::{Buffer, Point};
+use project::debugger::session::{Session, SessionEvent};
+use ui::{Tooltip, WithScrollbar, prelude::*};
+use workspace::Workspace;
+
+use crate::{
+    StepIntoInstruction, StepOverInstruction,
+    session::running::{
+        RunningState,
+        stack_frame_list::{StackFrameList, StackFrameListEvent},
+    },
+};
+
+actions!(
+    debugger,
+    [
+        /// Sets or removes a breakpoint on the selected instruction.
+        ToggleInstructionBreakpoint
+    ]
+);
+
+/// How many instructions are disassembled before the instruction pointer.
+const INSTRUCTIONS_BEFORE: u64 = 64;
+/// How many instructions are disassembled starting at the instruction pointer.
+const INSTRUCTIONS_AFTER: u64 = 192;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum DisassemblyEntry {
+    /// A source line that the following instructions were generated from.
+    Source { path: Arc<Path>, row: u32 },
+    Instruction {
+        address: Option<u64>,
+        instruction: DisassembledInstruction,
+    },
+}
+
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    stack_frame_list: Entity<StackFrameList>,
+    running_state: WeakEntity<RunningState>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    entries: Vec<DisassemblyEntry>,
+    selected_ix: Option<usize>,
+    /// The instruction pointer reference of the frame the entries were built for.
+    memory_reference: Option<String>,
+    instruction_pointer: Option<u64>,
+    source_buffers: HashMap<Arc<Path>, Option<Entity<Buffer>>>,
+    _rebuild_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        running_state: WeakEntity<RunningState>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Disassembly | SessionEvent::HistoricSnapshotSelected => {
+                    if this._rebuild_task.is_some() {
+                        this.schedule_rebuild(cx);
+                    }
+                }
+                _ => {}
+            }),
+            cx.subscribe(
+                &stack_frame_list,
+                |this, _, event: &StackFrameListEvent, cx| match event {
+                    StackFrameListEvent::SelectedStackFrameChanged(_)
+                    | StackFrameListEvent::BuiltEntries => {
+                        if this._rebuild_task.is_some() {
+                            this.schedule_rebuild(cx);
+                        }
+                    }
+                },
+            ),
+        ];
+
+        Self {
+            session,
+            stack_frame_list,
+            running_state,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            entries: Vec::new(),
+            selected_ix: None,
+            memory_reference: None,
+            instruction_pointer: None,
+            source_buffers: HashMap::default(),
+            _rebuild_task: None,
+            _subscriptions,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn entries(&self) -> &[DisassemblyEntry] {
+        &self.entries
+    }
+
+    #[cfg(test)]
+    pub(crate) fn instruction_pointer(&self) -> Option<u64> {
+        self.instruction_pointer
+    }
+
+    fn schedule_rebuild(&mut self, cx: &mut Context<Self>) {
+        self._rebuild_task = Some(cx.spawn(async move |this, cx| {
+            this.update(cx, |this, cx| this.rebuild(cx)).ok();
+        }));
+    }
+
+    fn rebuild(&mut self, cx: &mut Context<Self>) {
+        let Some(memory_reference) = self
+            .stack_frame_list
+            .read(cx)
+            .opened_stack_frame()
+            .and_then(|stack_frame| stack_frame.instruction_pointer_reference.clone())
+        else {
+            self.entries.clear();
+            self.selected_ix = None;
+            self.memory_reference = None;
+            self.instruction_pointer = None;
+            cx.notify();
+            return;
+        };
+
+        // The session emits `SessionEvent::Disassembly` once the adapter responds, which brings us back here.
+        let Some(instructions) = self.session.update(cx, |session, cx| {
+            session.disassemble(
+                memory_reference.clone(),
+                -(INSTRUCTIONS_BEFORE as i64),
+                INSTRUCTIONS_BEFORE + INSTRUCTIONS_AFTER,
+                cx,
+            )
+        }) else {
+            return;
+        };
+
+        let instruction_pointer_changed = self.memory_reference.as_ref() != Some(&memory_reference);
+        self.instruction_pointer = parse_address(&memory_reference);
+        self.memory_reference = Some(memory_reference);
+        self.entries = interleave_source_lines(&instructions);
+        self.load_source_buffers(cx);
+
+        if instruction_pointer_changed {
+            self.selected_ix = self.instruction_pointer_ix();
+            if let Some(ix) = self.selected_ix {
+                self.scroll_handle
+                    .scroll_to_item(ix, ScrollStrategy::Center);
+            }
+        }
+        cx.notify();
+    }
+
+    fn instruction_pointer_ix(&self) -> Option<usize> {
+        let instruction_pointer = self.instruction_pointer?;
+        self.entries.iter().position(|entry| match entry {
+            DisassemblyEntry::Instruction { address, .. } => *address == Some(instruction_pointer),
+            DisassemblyEntry::Source { .. } => false,
+        })
+    }
+
+    fn load_source_buffers(&mut self, cx: &mut Context<Self>) {
+        let Some(project) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.project().clone())
+            .ok()
+        else {
+            return;
+        };
+
+        for entry in &self.entries {
+            let DisassemblyEntry::Source { path, .. } = entry else {
+                continue;
+            };
+            if self.source_buffers.contains_key(path) {
+                continue;
+            }
+            self.source_buffers.insert(path.clone(), None);
+
+            let Some(open_buffer) = project.update(cx, |project, cx| {
+                let project_path = project.find_project_path(path, cx)?;
+                Some(project.open_buffer(project_path, cx))
+            }) else {
+                continue;
+            };
+            let path = path.clone();
+            cx.spawn(async move |this, cx| {
+                let buffer = open_buffer.await?;
+                this.update(cx, |this, cx| {
+                    this.source_buffers.insert(path, Some(buffer));
+                    cx.notify();
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    fn source_line_text(&self, path: &Arc<Path>, row: u32, cx: &App) -> Option<String> {
+        let buffer = self.source_buffers.get(path)?.as_ref()?.read(cx);
+        if row > buffer.max_point().row {
+            return None;
+        }
+        let line = Point::new(row, 0)..Point::new(row, buffer.line_len(row));
+        Some(buffer.text_for_range(line).collect())
+    }
+
+    fn toggle_instruction_breakpoint_at(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some(DisassemblyEntry::Instruction { instruction, .. }) = self.entries.get(ix) else {
+            return;
+        };
+        let instruction_reference = instruction.address.clone();
+        self.session.update(cx, |session, cx| {
+            session.toggle_instruction_breakpoint(instruction_reference, cx);
+        });
+        cx.notify();
+    }
+
+    fn toggle_instruction_breakpoint(
+        &mut self,
+        _: &ToggleInstructionBreakpoint,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_ix {
+            self.toggle_instruction_breakpoint_at(ix, cx);
+        }
+    }
+
+    fn select_ix(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_ix = ix;
+        if let Some(ix) = ix {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        let ix = match self.selected_ix {
+            _ if self.entries.is_empty() => None,
+            None => Some(0),
+            Some(ix) => Some((ix + 1).min(self.entries.len() - 1)),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = match self.selected_ix {
+            _ if self.entries.is_empty() => None,
+            None => Some(self.entries.len() - 1),
+            Some(ix) => Some(ix.saturating_sub(1)),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_first(
+        &mut self,
+        _: &menu::SelectFirst,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = (!self.entries.is_empty()).then_some(0);
+        self.select_ix(ix, cx);
+    }
+
+    fn select_last(&mut self, _: &menu::SelectLast, _window: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.entries.len().checked_sub(1);
+        self.select_ix(ix, cx);
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        match &self.entries[ix] {
+            DisassemblyEntry::Source { path, row } => {
+                let location = format!(
+                    "{}:{}",
+                    path.file_name()
+                        .map(|name| name.to_string_lossy())
+                        .unwrap_or_else(|| path.to_string_lossy()),
+                    row + 1
+                );
+                h_flex()
+                    .id(("disassembly-source", ix))
+                    .w_full()
+                    .gap_2()
+                    .px_1()
+                    .pt_1()
+                    .text_ui_xs(cx)
+                    .buffer_font(cx)
+                    .child(
+                        Label::new(location)
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .when_some(self.source_line_text(path, *row, cx), |this, text| {
+                        this.child(Label::new(text.trim().to_string()).size(LabelSize::XSmall))
+                    })
+                    .into_any_element()
+            }
+            DisassemblyEntry::Instruction {
+                address,
+                instruction,
+            } => {
+                let is_instruction_pointer =
+                    address.is_some() && *address == self.instruction_pointer;
+                let breakpoint = self
+                    .session
+                    .read(cx)
+                    .instruction_breakpoints()
+                    .find(|state| state.dap.instruction_reference == instruction.address)
+                    .map(|state| state.is_enabled);
+                let hover_background = cx.theme().colors().element_hover;
+                let selected_background = cx.theme().colors().element_selected;
+                let active_line_background =
+                    cx.theme().colors().editor_debugger_active_line_background;
+
+                h_flex()
+                    .id(("disassembly-instruction", ix))
+                    .w_full()
+                    .gap_2()
+                    .px_1()
+                    .text_ui_xs(cx)
+                    .buffer_font(cx)
+                    .hover(|this| this.bg(hover_background))
+                    .when(Some(ix) == self.selected_ix, |this| {
+                        this.bg(selected_background)
+                    })
+                    .when(is_instruction_pointer, |this| {
+                        this.bg(active_line_background)
+                    })
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.select_ix(Some(ix), cx);
+                    }))
+                    .child(
+                        div()
+                            .id(("disassembly-breakpoint", ix))
+                            .w_4()
+                            .flex_none()
+                            .cursor_pointer()
+                            .when_some(breakpoint, |this, is_enabled| {
+                                this.child(
+                                    Icon::new(if is_enabled {
+                                        IconName::DebugBreakpoint
+                                    } else {
+                                        IconName::DebugDisabledBreakpoint
+                                    })
+                                    .color(Color::Debugger)
+                                    .size(IconSize::XSmall),
+                                )
+                            })
+                            .tooltip(Tooltip::text(if breakpoint.is_some() {
+                                "Remove Instruction Breakpoint"
+                            } else {
+                                "Set Instruction Breakpoint"
+                            }))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                cx.stop_propagation();
+                                this.toggle_instruction_breakpoint_at(ix, cx);
+                            })),
+                    )
+                    .child(
+                        Label::new(instruction.address.clone())
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                        this.child(
+                            div().w_32().flex_none().overflow_hidden().child(
+                                Label::new(bytes)
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Disabled),
+                            ),
+                        )
+                    })
+                    .child(
+                        Label::new(instruction.instruction.clone())
+                            .size(LabelSize::XSmall)
+                            .when(is_instruction_pointer, |this| this.color(Color::Accent)),
+                    )
+                    .when_some(instruction.symbol.clone(), |this, symbol| {
+                        this.child(
+                            Label::new(format!("<{symbol}>"))
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .into_any_element()
+            }
+        }
+    }
+
+    fn render_control_strip(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let capabilities = self.session.read(cx).capabilities();
+        let supports_instruction_stepping = capabilities
+            .supports_stepping_granularity
+            .unwrap_or_default();
+        let is_stopped = self.session.read(cx).any_stopped_thread();
+        let focus_handle = self.focus_handle.clone();
+
+        h_flex()
+            .w_full()
+            .gap_1()
+            .px_1()
+            .pb_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(self.memory_reference.clone().unwrap_or_default())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1())
+            .child(
+                IconButton::new("disassembly-step-over", IconName::DebugStepOver)
+                    .icon_size(IconSize::Small)
+                    .disabled(!supports_instruction_stepping || !is_stopped)
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |_window, cx| {
+                            Tooltip::for_action_in(
+                                "Step Over Instruction",
+                                &StepOverInstruction,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.running_state
+                            .update(cx, |state, cx| state.step_over_instruction(cx))
+                            .ok();
+                    })),
+            )
+            .child(
+                IconButton::new("disassembly-step-into", IconName::DebugStepInto)
+                    .icon_size(IconSize::Small)
+                    .disabled(!supports_instruction_stepping || !is_stopped)
+                    .tooltip(move |_window, cx| {
+                        Tooltip::for_action_in(
+                            "Step Into Instruction",
+                            &StepIntoInstruction,
+                            &focus_handle,
+                            cx,
+                        )
+                    })
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.running_state
+                            .update(cx, |state, cx| state.step_in_instruction(cx))
+                            .ok();
+                    })),
+            )
+    }
+
+    fn render_list(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        uniform_list(
+            "disassembly-view",
+            self.entries.len(),
+            cx.processor(|this, range: Range<usize>, _window, cx| {
+                range.map(|ix| this.render_entry(ix, cx)).collect()
+            }),
+        )
+        .track_scroll(&self.scroll_handle)
+        .size_full()
+    }
+}
+
+/// Parses a DAP memory reference or instruction address, which adapters report as hex strings.
+fn parse_address(address: &str) -> Option<u64> {
+    let address = address.trim();
+    let digits = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or(address);
+    u64::from_str_radix(digits, 16).ok()
+}
+
+/// Inserts a source line entry before each run of instructions that were generated from it.
+pub(crate) fn interleave_source_lines(
+    instructions: &[DisassembledInstruction],
+) -> Vec<DisassemblyEntry> {
+    let mut entries = Vec::with_capacity(instructions.len());
+    let mut current_path: Option<Arc<Path>> = None;
+    let mut current_line: Option<(Arc<Path>, u32)> = None;
+
+    for instruction in instructions {
+        // Adapters may omit the location when it's the same as the previous instruction's.
+        if let Some(location) = &instruction.location {
+            current_path = location
+                .path
+                .as_deref()
+                .map(|path| Arc::<Path>::from(Path::new(path)));
+        }
+        let row = instruction.line.map(|line| (line as u32).saturating_sub(1));
+        if let Some((path, row)) = current_path.clone().zip(row)
+            && current_line.as_ref() != Some(&(path.clone(), row))
+        {
+            entries.push(DisassemblyEntry::Source {
+                path: path.clone(),
+                row,
+            });
+            current_line = Some((path, row));
+        }
+
+        entries.push(DisassemblyEntry::Instruction {
+            address: parse_address(&instruction.address),
+            instruction: instruction.clone(),
+        });
+    }
+
+    entries
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self._rebuild_task.is_none() {
+            self.schedule_rebuild(cx);
+        }
+
+        v_flex()
+            .key_context("DisassemblyView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::toggle_instruction_breakpoint))
+            .size_full()
+            .p_1()
+            .child(self.render_control_strip(cx))
+            .map(|this| {
+                if self.memory_reference.is_none() {
+                    this.child(
+                        div().p_2().child(
+                            Label::new("The selected stack frame has no instruction pointer")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        div()
+                            .size_full()
+                            .child(self.render_list(cx))
+                            .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+                    )
+                }
+            })
+    }
+}
@@ -218,6 +218,20 @@ impl StackFrameList {
         self.opened_stack_frame_id
     }
 
+    fn all_stack_frames(&self) -> impl Iterator<Item = &dap::StackFrame> {
+        self.entries.iter().flat_map(|entry| match entry {
+            StackFrameEntry::Label(stack_frame) => std::slice::from_ref(stack_frame),
+            StackFrameEntry::Normal(stack_frame) => std::slice::from_ref(stack_frame),
+            StackFrameEntry::Collapsed(stack_frames) => stack_frames.as_slice(),
+        })
+    }
+
+    pub(crate) fn opened_stack_frame(&self) -> Option<&dap::StackFrame> {
+        let stack_frame_id = self.opened_stack_frame_id?;
+        self.all_stack_frames()
+            .find(|stack_frame| stack_frame.id == stack_frame_id)
+    }
+
     pub(super) fn schedule_refresh(
         &mut self,
         select_first: bool,
@@ -370,13 +384,7 @@ impl StackFrameList {
         cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
         let Some(stack_frame) = self
-            .entries
-            .iter()
-            .flat_map(|entry| match entry {
-                StackFrameEntry::Label(stack_frame) => std::slice::from_ref(stack_frame),
-                StackFrameEntry::Normal(stack_frame) => std::slice::from_ref(stack_frame),
-                StackFrameEntry::Collapsed(stack_frames) => stack_frames.as_slice(),
-            })
+            .all_stack_frames()
             .find(|stack_frame| stack_frame.id == stack_frame_id)
             .cloned()
         else {
@@ -393,13 +401,14 @@ impl StackFrameList {
     ) -> Task<Result<()>> {
         let stack_frame_id = stack_frame.id;
         self.opened_stack_frame_id = Some(stack_frame_id);
+        // Frames without a source can still be inspected in the disassembly view.
+        cx.emit(StackFrameListEvent::SelectedStackFrameChanged(
+            stack_frame_id,
+        ));
         let Some(abs_path) = Self::abs_path_from_stack_frame(&stack_frame) else {
             return Task::ready(Err(anyhow!("Project path not found")));
         };
         let row = stack_frame.line.saturating_sub(1) as u32;
-        cx.emit(StackFrameListEvent::SelectedStackFrameChanged(
-            stack_frame_id,
-        ));
         cx.spawn_in(window, async move |this, cx| {
             let (worktree, relative_path) = this
                 .update(cx, |this, cx| {
@@ -649,6 +649,27 @@ impl VariableList {
         });
     }
 
+    /// Describes the data that a data breakpoint on the given entry would watch. Watched
+    /// expressions are evaluated in the selected stack frame.
+    fn data_breakpoint_context(&self, entry: &ListEntry) -> Option<Arc<DataBreakpointContext>> {
+        let context = match &entry.entry {
+            DapEntry::Variable(variable) => DataBreakpointContext::Variable {
+                variables_reference: self
+                    .entry_states
+                    .get(&entry.path)
+                    .map(|state| state.parent_reference)?,
+                name: variable.name.clone(),
+                bytes: None,
+            },
+            DapEntry::Watcher(watcher) => DataBreakpointContext::Expression {
+                expression: watcher.expression.to_string(),
+                frame_id: self.selected_stack_frame_id,
+            },
+            DapEntry::Scope(_) => return None,
+        };
+        Some(Arc::new(context))
+    }
+
     fn deploy_list_entry_context_menu(
         &mut self,
         entry: ListEntry,
@@ -673,25 +694,13 @@ impl VariableList {
                         .unwrap_or_default(),
                 )
             });
-        let can_toggle_data_breakpoint = entry
-            .as_variable()
-            .filter(|_| supports_data_breakpoints)
-            .and_then(|variable| {
-                let variables_reference = self
-                    .entry_states
-                    .get(&entry.path)
-                    .map(|state| state.parent_reference)?;
-                Some(self.session.update(cx, |session, cx| {
-                    session.data_breakpoint_info(
-                        Arc::new(DataBreakpointContext::Variable {
-                            variables_reference,
-                            name: variable.name.clone(),
-                            bytes: None,
-                        }),
-                        None,
-                        cx,
-                    )
-                }))
+        let can_toggle_data_breakpoint = supports_data_breakpoints
+            .then(|| self.data_breakpoint_context(&entry))
+            .flatten()
+            .map(|context| {
+                self.session.update(cx, |session, cx| {
+                    session.data_breakpoint_info(context, None, cx)
+                })
             });
 
         let focus_handle = self.focus_handle.clone();
@@ -713,36 +722,6 @@ impl VariableList {
                                 menu.action("Go To Memory", GoToMemory.boxed_clone())
                             })
                             .action("Watch Variable", AddWatch.boxed_clone())
-                            .when_some(can_toggle_data_breakpoint, |mut menu, data_info| {
-                                menu = menu.separator();
-                                if let Some(access_types) = data_info.access_types {
-                                    for access in access_types {
-                                        menu = menu.action(
-                                            format!(
-                                                "Toggle {} Data Breakpoint",
-                                                match access {
-                                                    dap::DataBreakpointAccessType::Read => "Read",
-                                                    dap::DataBreakpointAccessType::Write => "Write",
-                                                    dap::DataBreakpointAccessType::ReadWrite =>
-                                                        "Read/Write",
-                                                }
-                                            ),
-                                            crate::ToggleDataBreakpoint {
-                                                access_type: Some(access),
-                                            }
-                                            .boxed_clone(),
-                                        );
-                                    }
-
-                                    menu
-                                } else {
-                                    menu.action(
-                                        "Toggle Data Breakpoint",
-                                        crate::ToggleDataBreakpoint { access_type: None }
-                                            .boxed_clone(),
-                                    )
-                                }
-                            })
                     })
                     .when(entry.as_watcher().is_some(), |menu| {
                         menu.action("Copy Name", CopyVariableName.boxed_clone())
@@ -752,6 +731,39 @@ impl VariableList {
                             })
                             .action("Remove Watch", RemoveWatch.boxed_clone())
                     })
+                    .when_some(can_toggle_data_breakpoint, |mut menu, data_info| {
+                        if data_info.data_id.is_none() {
+                            return menu;
+                        }
+
+                        menu = menu.separator();
+                        if let Some(access_types) = data_info.access_types {
+                            for access in access_types {
+                                menu = menu.action(
+                                    format!(
+                                        "Toggle {} Data Breakpoint",
+                                        match access {
+                                            dap::DataBreakpointAccessType::Read => "Read",
+                                            dap::DataBreakpointAccessType::Write => "Write",
+                                            dap::DataBreakpointAccessType::ReadWrite =>
+                                                "Read/Write",
+                                        }
+                                    ),
+                                    crate::ToggleDataBreakpoint {
+                                        access_type: Some(access),
+                                    }
+                                    .boxed_clone(),
+                                );
+                            }
+
+                            menu
+                        } else {
+                            menu.action(
+                                "Toggle Data Breakpoint",
+                                crate::ToggleDataBreakpoint { access_type: None }.boxed_clone(),
+                            )
+                        }
+                    })
                     .context(focus_handle.clone())
                 });
 
@@ -792,19 +804,10 @@ impl VariableList {
             return;
         };
 
-        let Some((name, var_ref)) = entry.as_variable().map(|var| &var.name).zip(
-            self.entry_states
-                .get(&entry.path)
-                .map(|state| state.parent_reference),
-        ) else {
+        let Some(context) = self.data_breakpoint_context(entry) else {
             return;
         };
 
-        let context = Arc::new(DataBreakpointContext::Variable {
-            variables_reference: var_ref,
-            name: name.clone(),
-            bytes: None,
-        });
         let data_breakpoint = self.session.update(cx, |session, cx| {
             session.data_breakpoint_info(context.clone(), None, cx)
         });
@@ -965,8 +968,8 @@ impl VariableList {
             return;
         };
 
-        self.session.update(cx, |session, _| {
-            session.remove_watcher(watcher.expression.clone());
+        self.session.update(cx, |session, cx| {
+            session.remove_watcher(watcher.expression.clone(), cx);
         });
         self.build_entries(cx);
     }
@@ -1221,7 +1224,11 @@ impl VariableList {
             return div().into_any_element();
         };
 
-        let variable_color = self.variable_color(watcher.presentation_hint.as_ref(), cx);
+        let mut variable_color = self.variable_color(watcher.presentation_hint.as_ref(), cx);
+        // Highlight values that changed since the debuggee last stopped.
+        if watcher.changed && !self.disabled {
+            variable_color.value = Some(Color::Modified.color(cx));
+        }
 
         let is_selected = self
             .selection
@@ -9,8 +9,9 @@ use dap::{
     adapters::DebugTaskDefinition,
     client::SessionId,
     requests::{
-        Continue, Disconnect, Launch, Next, RunInTerminal, SetBreakpoints, StackTrace,
-        StartDebugging, StepBack, StepIn, StepOut, Threads,
+        Continue, Disconnect, Goto, GotoTargets, Initialize, Launch, Next, RunInTerminal,
+        SetBreakpoints, SetFunctionBreakpoints, StackTrace, StartDebugging, StepBack, StepIn,
+        StepOut, Threads,
     },
 };
 use editor::{
@@ -20,13 +21,16 @@ use editor::{
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
 use project::{
     FakeFs, Project,
-    debugger::session::{ThreadId, ThreadStatus},
+    debugger::{
+        breakpoint_store::{BreakpointEditAction, ExportedBreakpoints},
+        session::{ThreadId, ThreadStatus},
+    },
 };
 use serde_json::json;
 use std::{
     path::Path,
     sync::{
-        Arc,
+        Arc, Mutex,
         atomic::{AtomicBool, Ordering},
     },
 };
@@ -1363,6 +1367,297 @@ async fn test_unsetting_breakpoints_on_clear_breakpoint_action(
     cx.run_until_parked();
 }
 
+#[gpui::test]
+async fn test_function_breakpoints_are_sent_to_adapter(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let breakpoint_store = project.read_with(cx, |project, _| project.breakpoint_store());
+
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.edit_function_breakpoint("main".into(), BreakpointEditAction::Toggle, cx);
+    });
+
+    let function_breakpoints = Arc::new(Mutex::new(None));
+    let session = start_debug_session(&workspace, cx, {
+        let function_breakpoints = function_breakpoints.clone();
+        move |client| {
+            client.on_request::<Initialize, _>(move |_, _| {
+                Ok(dap::Capabilities {
+                    supports_function_breakpoints: Some(true),
+                    ..Default::default()
+                })
+            });
+            let function_breakpoints = function_breakpoints.clone();
+            client.on_request::<SetFunctionBreakpoints, _>(move |_, args| {
+                *function_breakpoints.lock().unwrap() = Some(
+                    args.breakpoints
+                        .into_iter()
+                        .map(|breakpoint| (breakpoint.name, breakpoint.condition))
+                        .collect::<Vec<_>>(),
+                );
+                Ok(dap::SetFunctionBreakpointsResponse {
+                    breakpoints: Vec::new(),
+                })
+            });
+        }
+    })
+    .unwrap();
+    cx.run_until_parked();
+
+    assert_eq!(
+        function_breakpoints.lock().unwrap().take(),
+        Some(vec![("main".to_string(), None)]),
+        "Function breakpoints should be sent during the configuration sequence"
+    );
+
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.edit_function_breakpoint("parse".into(), BreakpointEditAction::Toggle, cx);
+        breakpoint_store.edit_function_breakpoint(
+            "parse".into(),
+            BreakpointEditAction::EditCondition("argc > 1".into()),
+            cx,
+        );
+        breakpoint_store.edit_function_breakpoint(
+            "main".into(),
+            BreakpointEditAction::InvertState,
+            cx,
+        );
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        function_breakpoints.lock().unwrap().take(),
+        Some(vec![("parse".to_string(), Some("argc > 1".to_string()))]),
+        "Disabled function breakpoints should not be sent"
+    );
+
+    session.update(cx, |session, cx| {
+        session.toggle_ignore_breakpoints(cx).detach();
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        function_breakpoints.lock().unwrap().take(),
+        Some(vec![]),
+        "Ignoring breakpoints should unset function breakpoints"
+    );
+}
+
+#[gpui::test]
+async fn test_set_next_statement(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_goto_targets_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::default(),
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<GotoTargets, _>(move |_, args| {
+        assert_eq!(path!("/project/main.rs"), args.source.path.unwrap());
+        assert_eq!(3, args.line);
+
+        Ok(dap::GotoTargetsResponse {
+            targets: vec![dap::GotoTarget {
+                id: 7,
+                label: "main.rs:3".into(),
+                line: 3,
+                column: None,
+                end_line: None,
+                end_column: None,
+                instruction_pointer_reference: None,
+            }],
+        })
+    });
+
+    let goto_arguments = Arc::new(Mutex::new(None));
+    client.on_request::<Goto, _>({
+        let goto_arguments = goto_arguments.clone();
+        move |_, args| {
+            *goto_arguments.lock().unwrap() = Some((args.thread_id, args.target_id));
+            Ok(())
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    let targets = session
+        .update(cx, |session, cx| {
+            session.goto_targets(Path::new(path!("/project/main.rs")).into(), 2, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![7],
+        targets.iter().map(|target| target.id).collect::<Vec<_>>()
+    );
+
+    session.update(cx, |session, cx| session.goto(ThreadId(1), 7, cx));
+    session.read_with(cx, |session, _| {
+        assert_eq!(
+            Some(ThreadStatus::Stepping),
+            session.thread_state(ThreadId(1)),
+            "Thread should be stepping while the goto request is in flight"
+        );
+    });
+    cx.run_until_parked();
+
+    assert_eq!(Some((1, 7)), goto_arguments.lock().unwrap().take());
+}
+
+#[gpui::test]
+async fn test_export_and_import_breakpoints(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+            "dir": {},
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("main.rs")), cx)
+        })
+        .await
+        .unwrap();
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer, cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.move_down(&zed_actions::editor::MoveDown, window, cx);
+        editor.toggle_breakpoint(&actions::ToggleBreakpoint, window, cx);
+        editor.move_down(&zed_actions::editor::MoveDown, window, cx);
+        editor.move_down(&zed_actions::editor::MoveDown, window, cx);
+        editor.toggle_breakpoint(&actions::ToggleBreakpoint, window, cx);
+    });
+    let breakpoint_store = project.read_with(cx, |project, _| project.breakpoint_store());
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.edit_function_breakpoint("main".into(), BreakpointEditAction::Toggle, cx);
+    });
+    cx.run_until_parked();
+
+    let exported = breakpoint_store.read_with(cx, |breakpoint_store, cx| {
+        breakpoint_store.export_breakpoints(cx)
+    });
+    assert_eq!(
+        exported
+            .source_breakpoints
+            .iter()
+            .map(|breakpoint| (breakpoint.path.as_path(), breakpoint.line))
+            .collect::<Vec<_>>(),
+        vec![(Path::new("main.rs"), 2), (Path::new("main.rs"), 4)],
+        "Exported paths should be relative to the worktree"
+    );
+    assert_eq!(exported.function_breakpoints.len(), 1);
+
+    let json = serde_json::to_string(&exported).unwrap();
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.clear_breakpoints(cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        breakpoint_store.read_with(cx, |breakpoint_store, cx| {
+            breakpoint_store.export_breakpoints(cx)
+        }),
+        ExportedBreakpoints::default()
+    );
+
+    // Breakpoints in files that aren't in the project, or that fail to open,
+    // are skipped without dropping the rest of the set.
+    let mut imported: ExportedBreakpoints = serde_json::from_str(&json).unwrap();
+    for path in ["missing.rs", "dir"] {
+        imported
+            .source_breakpoints
+            .push(serde_json::from_value(json!({ "path": path, "line": 1 })).unwrap());
+    }
+    breakpoint_store
+        .update(cx, |breakpoint_store, cx| {
+            breakpoint_store.import_breakpoints(imported, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    assert_eq!(
+        breakpoint_store.read_with(cx, |breakpoint_store, cx| {
+            breakpoint_store.export_breakpoints(cx)
+        }),
+        exported,
+        "Importing an exported breakpoint set should restore it"
+    );
+}
+
 #[gpui::test]
 async fn test_debug_session_is_shutdown_when_attach_and_launch_request_fails(
     executor: BackgroundExecutor,
@@ -0,0 +1,305 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    persistence::DebuggerPaneItem,
+    session::running::disassembly_view::DisassemblyEntry,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame,
+    requests::{Disassemble, Initialize, Scopes, SetInstructionBreakpoints, StackTrace, Threads},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use std::{
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+use util::path;
+
+fn instruction(
+    address: &str,
+    text: &str,
+    line: Option<u32>,
+    path: Option<&str>,
+) -> dap::DisassembledInstruction {
+    dap::DisassembledInstruction {
+        address: address.into(),
+        instruction_bytes: None,
+        instruction: text.into(),
+        symbol: None,
+        location: path.map(|path| dap::Source {
+            name: None,
+            path: Some(path.into()),
+            source_reference: None,
+            presentation_hint: None,
+            origin: None,
+            sources: None,
+            adapter_data: None,
+            checksums: None,
+        }),
+        line: line.map(Into::into),
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 0,
+                column: 0,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1004".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    let source_path = path!("/project/main.c");
+    let instructions = vec![
+        instruction("0x1000", "push rbp", Some(3), Some(source_path)),
+        instruction("0x1004", "mov rbp, rsp", Some(3), None),
+        instruction("0x1008", "xor eax, eax", Some(4), None),
+    ];
+    client.on_request::<Disassemble, _>({
+        let instructions = instructions.clone();
+        move |_, args| {
+            assert_eq!(args.memory_reference, "0x1004");
+            Ok(dap::DisassembleResponse {
+                instructions: instructions.clone(),
+            })
+        }
+    });
+
+    let instruction_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetInstructionBreakpoints, _>({
+        let instruction_breakpoints = instruction_breakpoints.clone();
+        move |_, args| {
+            *instruction_breakpoints.lock().unwrap() = args
+                .breakpoints
+                .into_iter()
+                .map(|breakpoint| breakpoint.instruction_reference)
+                .collect::<Vec<_>>();
+            Ok(dap::SetInstructionBreakpointsResponse {
+                breakpoints: Vec::new(),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+
+    running_state.update_in(cx, |this, window, cx| {
+        this.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+        cx.refresh_windows();
+    });
+
+    cx.run_until_parked();
+
+    let source_path: Arc<Path> = Arc::from(Path::new(source_path));
+    running_state.update(cx, |state, cx| {
+        let view = state.disassembly_view().read(cx);
+        assert_eq!(view.instruction_pointer(), Some(0x1004));
+        assert_eq!(
+            view.entries(),
+            &[
+                DisassemblyEntry::Source {
+                    path: source_path.clone(),
+                    row: 2,
+                },
+                DisassemblyEntry::Instruction {
+                    address: Some(0x1000),
+                    instruction: instructions[0].clone(),
+                },
+                DisassemblyEntry::Instruction {
+                    address: Some(0x1004),
+                    instruction: instructions[1].clone(),
+                },
+                DisassemblyEntry::Source {
+                    path: source_path.clone(),
+                    row: 3,
+                },
+                DisassemblyEntry::Instruction {
+                    address: Some(0x1008),
+                    instruction: instructions[2].clone(),
+                },
+            ]
+        );
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1008".into(), cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(*instruction_breakpoints.lock().unwrap(), vec!["0x1008"]);
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1008".into(), cx);
+    });
+    cx.run_until_parked();
+    assert!(instruction_breakpoints.lock().unwrap().is_empty());
+}
+
+#[gpui::test]
+async fn test_failed_disassembly_is_retried(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::new(),
+            total_frames: None,
+        })
+    });
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let instructions = vec![instruction("0x1000", "push rbp", None, None)];
+    client.on_request::<Disassemble, _>({
+        let requests = requests.clone();
+        let instructions = instructions.clone();
+        move |_, _| {
+            if requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(dap::ErrorResponse {
+                    error: Some(dap::Message {
+                        id: 1,
+                        format: "memory is not readable".into(),
+                        variables: None,
+                        send_telemetry: None,
+                        show_user: None,
+                        url: None,
+                        url_label: None,
+                    }),
+                })
+            } else {
+                Ok(dap::DisassembleResponse {
+                    instructions: instructions.clone(),
+                })
+            }
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    let disassemble = |cx: &mut VisualTestContext| {
+        session.update(cx, |session, cx| {
+            session.disassemble("0x1000".into(), 0, 1, cx)
+        })
+    };
+
+    assert_eq!(disassemble(cx), None);
+    cx.run_until_parked();
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        disassemble(cx),
+        None,
+        "A failed request shouldn't be cached as an empty disassembly"
+    );
+    cx.run_until_parked();
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert_eq!(disassemble(cx).as_deref(), Some(instructions.as_slice()));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
@@ -356,6 +356,9 @@ async fn test_dap_adapter_config_conversion_and_validation(cx: &mut TestAppConte
         "JavaScript",
         "Delve",
         "GDB",
+        "lldb-dap",
+        "rdbg",
+        "Xdebug",
         "fake-adapter",
     ];
 
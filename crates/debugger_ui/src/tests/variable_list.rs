@@ -1,5 +1,5 @@
 use std::sync::{
-    Arc,
+    Arc, Mutex,
     atomic::{AtomicBool, Ordering},
 };
 
@@ -14,8 +14,12 @@ use crate::{
 use collections::HashMap;
 use dap::{
     Scope, StackFrame, Variable,
-    requests::{Evaluate, Initialize, Launch, Scopes, StackTrace, Variables},
+    requests::{
+        DataBreakpointInfo, Evaluate, Initialize, Launch, Scopes, SetDataBreakpoints, StackTrace,
+        Variables,
+    },
 };
+use db::kvp::KEY_VALUE_STORE;
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
 use menu::{SelectFirst, SelectNext, SelectPrevious};
 use project::{FakeFs, Project};
@@ -2296,6 +2300,7 @@ async fn test_refresh_watchers(executor: BackgroundExecutor, cx: &mut TestAppCon
         assert_eq!("value1", watcher.value.to_string());
         assert_eq!("variable1", watcher.expression.to_string());
         assert_eq!(2, watcher.variables_reference);
+        assert!(!watcher.changed);
     });
 
     client.on_request::<Evaluate, _>({
@@ -2338,5 +2343,230 @@ async fn test_refresh_watchers(executor: BackgroundExecutor, cx: &mut TestAppCon
         assert_eq!("value updated", watcher.value.to_string());
         assert_eq!("variable1", watcher.expression.to_string());
         assert_eq!(3, watcher.variables_reference);
+        // The value differs from the one at the previous stop.
+        assert!(watcher.changed);
+    });
+}
+
+fn stop_in_single_frame(client: &dap::client::DebugAdapterClient) {
+    client.on_request::<dap::requests::Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: Vec::new() }));
+    client.on_request::<Evaluate, _>(move |_, args| {
+        Ok(dap::EvaluateResponse {
+            result: format!("value of {}", args.expression),
+            type_: None,
+            presentation_hint: None,
+            variables_reference: 0,
+            named_variables: None,
+            indexed_variables: None,
+            memory_reference: None,
+            value_location_reference: None,
+        })
+    });
+}
+
+async fn fake_stop(client: &dap::client::DebugAdapterClient) {
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+}
+
+#[gpui::test]
+async fn test_watches_are_restored_for_the_same_scenario(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(path!("/project"), json!({ "main.rs": "fn main() {}" }))
+        .await;
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let workspace_id = workspace
+        .update(cx, |workspace, _, _| {
+            workspace.set_random_database_id();
+            workspace.database_id().unwrap()
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+    stop_in_single_frame(&client);
+    fake_stop(&client).await;
+    cx.run_until_parked();
+
+    session
+        .update(cx, |session, cx| {
+            session.add_watcher("counter".into(), 1, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let key = format!(
+        "debugger_watches_{}-fake-adapter-test",
+        i64::from(workspace_id)
+    );
+    assert_eq!(
+        KEY_VALUE_STORE.read_kvp(&key).unwrap().as_deref(),
+        Some(r#"["counter"]"#)
+    );
+
+    // Running the same scenario again brings the watch back, and evaluates it once stopped.
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+    stop_in_single_frame(&client);
+    cx.run_until_parked();
+    session.read_with(cx, |session, _| {
+        assert_eq!(
+            session.watchers().keys().cloned().collect::<Vec<_>>(),
+            vec![SharedString::from("counter")]
+        );
+    });
+
+    fake_stop(&client).await;
+    cx.run_until_parked();
+    session.read_with(cx, |session, _| {
+        let watcher = session
+            .watchers()
+            .get(&SharedString::from("counter"))
+            .unwrap();
+        assert_eq!("value of counter", watcher.value.to_string());
+    });
+}
+
+#[gpui::test]
+async fn test_data_breakpoint_on_watch(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(path!("/project"), json!({ "main.rs": "fn main() {}" }))
+        .await;
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_data_breakpoints: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+    stop_in_single_frame(&client);
+
+    let info_requests = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<DataBreakpointInfo, _>({
+        let info_requests = info_requests.clone();
+        move |_, args| {
+            info_requests.lock().unwrap().push((
+                args.name.clone(),
+                args.frame_id,
+                args.variables_reference,
+            ));
+            Ok(dap::DataBreakpointInfoResponse {
+                data_id: Some(format!("data-{}", args.name)),
+                description: args.name,
+                access_types: None,
+                can_persist: None,
+            })
+        }
+    });
+    let data_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetDataBreakpoints, _>({
+        let data_breakpoints = data_breakpoints.clone();
+        move |_, args| {
+            *data_breakpoints.lock().unwrap() = args
+                .breakpoints
+                .into_iter()
+                .map(|breakpoint| breakpoint.data_id)
+                .collect::<Vec<_>>();
+            Ok(dap::SetDataBreakpointsResponse {
+                breakpoints: Vec::new(),
+            })
+        }
     });
+
+    fake_stop(&client).await;
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            let running = item.running_state().clone();
+            let variable_list = running.update(cx, |state, cx| {
+                state.activate_item(DebuggerPaneItem::Variables, window, cx);
+                state.variable_list().clone()
+            });
+            variable_list.update(cx, |_, cx| cx.focus_self(window));
+            running
+        });
+    session
+        .update(cx, |session, cx| {
+            session.add_watcher("counter".into(), 1, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.variable_list().update(cx, |_, cx| {
+            cx.dispatch_action(&SelectFirst);
+            cx.dispatch_action(&crate::ToggleDataBreakpoint { access_type: None });
+        });
+    });
+    cx.run_until_parked();
+
+    // The watched expression is described to the adapter in the selected stack frame.
+    assert_eq!(
+        *info_requests.lock().unwrap(),
+        vec![("counter".to_string(), Some(1), None)]
+    );
+    assert_eq!(*data_breakpoints.lock().unwrap(), vec!["data-counter"]);
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.variable_list().update(cx, |_, cx| {
+            cx.dispatch_action(&crate::ToggleDataBreakpoint { access_type: None });
+        });
+    });
+    cx.run_until_parked();
+    assert!(data_breakpoints.lock().unwrap().is_empty());
 }
@@ -375,6 +375,8 @@ actions!(
     [
         /// Runs program execution to the current cursor position.
         RunToCursor,
+        /// Moves the current execution point to the cursor position without running the code in between.
+        SetNextStatement,
         /// Evaluates the selected text in the debugger context.
         EvaluateSelectedText
     ]
@@ -164,6 +164,7 @@ use project::{
         OpenLspBufferHandle,
     },
     project_settings::{DiagnosticSeverity, GoToDiagnosticSeverityFilter, ProjectSettings},
+    search_text::{SearchTextFile, SearchTextPosition},
 };
 use rand::seq::SliceRandom;
 use regex::Regex;
@@ -8646,6 +8647,7 @@ impl Editor {
         };
 
         let run_to_cursor = window.is_action_available(&RunToCursor, cx);
+        let set_next_statement = window.is_action_available(&SetNextStatement, cx);
 
         let toggle_state_msg = breakpoint.as_ref().map_or(None, |bp| match bp.1.state {
             BreakpointState::Enabled => Some("Disable"),
@@ -8674,6 +8676,24 @@ impl Editor {
 
                         window.dispatch_action(Box::new(RunToCursor), cx);
                     })
+                    .when(!set_next_statement, |this| this.separator())
+                })
+                .when(set_next_statement, |this| {
+                    let weak_editor = weak_editor.clone();
+                    this.entry("Set next statement", None, move |window, cx| {
+                        weak_editor
+                            .update(cx, |editor, cx| {
+                                editor.change_selections(
+                                    SelectionEffects::no_scroll(),
+                                    window,
+                                    cx,
+                                    |s| s.select_ranges([Point::new(row, 0)..Point::new(row, 0)]),
+                                );
+                            })
+                            .ok();
+
+                        window.dispatch_action(Box::new(SetNextStatement), cx);
+                    })
                     .separator()
                 })
                 .when_some(toggle_state_msg, |this, msg| {
@@ -20430,6 +20450,86 @@ impl Editor {
         None
     }
 
+    pub fn stage_selected_lines(
+        &mut self,
+        _: &::git::StageSelectedLines,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ranges = self.selections.disjoint_anchor_ranges().collect::<Vec<_>>();
+        self.stage_or_unstage_lines(true, ranges, cx);
+    }
+
+    pub fn unstage_selected_lines(
+        &mut self,
+        _: &::git::UnstageSelectedLines,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ranges = self.selections.disjoint_anchor_ranges().collect::<Vec<_>>();
+        self.stage_or_unstage_lines(false, ranges, cx);
+    }
+
+    /// Stages or unstages only the lines covered by the given ranges, splitting
+    /// any diff hunks they partially overlap.
+    pub fn stage_or_unstage_lines(
+        &mut self,
+        stage: bool,
+        ranges: Vec<Range<Anchor>>,
+        cx: &mut Context<Self>,
+    ) {
+        let task = self.save_buffers_for_ranges_if_needed(&ranges, cx);
+        cx.spawn(async move |this, cx| {
+            task.await?;
+            this.update(cx, |this, cx| {
+                let snapshot = this.buffer.read(cx).snapshot(cx);
+                let mut rows_by_buffer = HashMap::<BufferId, Vec<Range<u32>>>::default();
+                for range in &ranges {
+                    for (buffer_snapshot, range, _) in
+                        snapshot.range_to_buffer_ranges(range.start..=range.end)
+                    {
+                        let start = buffer_snapshot.offset_to_point(range.start.0);
+                        let end = buffer_snapshot.offset_to_point(range.end.0);
+                        // A selection ending at the start of a line doesn't include it.
+                        let end_row = if end.column == 0 && end.row > start.row {
+                            end.row
+                        } else {
+                            end.row + 1
+                        };
+                        rows_by_buffer
+                            .entry(buffer_snapshot.remote_id())
+                            .or_default()
+                            .push(start.row..end_row);
+                    }
+                }
+                for (buffer_id, rows) in rows_by_buffer {
+                    this.do_stage_or_unstage_lines(stage, buffer_id, &rows, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn do_stage_or_unstage_lines(
+        &self,
+        stage: bool,
+        buffer_id: BufferId,
+        rows: &[Range<u32>],
+        cx: &mut App,
+    ) -> Option<()> {
+        let project = self.project()?;
+        let buffer = project.read(cx).buffer_for_id(buffer_id, cx)?;
+        let diff = self.buffer.read(cx).diff_for(buffer_id)?;
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let file_exists = buffer_snapshot
+            .file()
+            .is_some_and(|file| file.disk_state().exists());
+        diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(stage, rows, &buffer_snapshot, file_exists, cx)
+        });
+        None
+    }
+
     pub fn expand_selected_diff_hunks(&mut self, cx: &mut Context<Self>) {
         let ranges: Vec<_> = self
             .selections
@@ -24151,6 +24251,30 @@ impl Editor {
                 };
 
                 for (buffer, (ranges, scroll_offset)) in new_selections_by_buffer {
+                    // Buffers holding the searchable text of another file, such as a notebook,
+                    // open that file in its own item instead.
+                    if let Some(search_text_file) = SearchTextFile::from_dyn(buffer.read(cx).file())
+                    {
+                        let project_path = search_text_file.project_path(cx);
+                        let offset = ranges.first().map_or(0, |range| range.start.0);
+                        let open_item = workspace.open_path(
+                            project_path,
+                            Some(pane.downgrade()),
+                            true,
+                            window,
+                            cx,
+                        );
+                        cx.spawn_in(window, async move |_, cx| {
+                            let item = open_item.await?;
+                            cx.update(|window, cx| {
+                                item.navigate(Arc::new(SearchTextPosition { offset }), window, cx)
+                            })?;
+                            anyhow::Ok(())
+                        })
+                        .detach_and_log_err(cx);
+                        continue;
+                    }
+
                     let buffer_read = buffer.read(cx);
                     let (has_file, is_project_file) = if let Some(file) = buffer_read.file() {
                         (true, project::File::from_dyn(Some(file)).is_some())
@@ -28385,6 +28509,18 @@ fn render_diff_hunk_controls(
     _window: &mut Window,
     cx: &mut App,
 ) -> AnyElement {
+    // When the newest selection spans part of this hunk, stage or unstage just
+    // the selected lines.
+    let selected_lines = {
+        let editor = editor.read(cx);
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        let selection = editor.selections.newest_anchor();
+        (selection.start != selection.end
+            && selection.start.cmp(&hunk_range.end, &snapshot).is_le()
+            && selection.end.cmp(&hunk_range.start, &snapshot).is_ge())
+        .then(|| selection.range())
+    };
+
     h_flex()
         .h(line_height)
         .mr_1()
@@ -28404,24 +28540,38 @@ fn render_diff_hunk_controls(
                 .alpha(if status.is_pending() { 0.66 } else { 1.0 })
                 .tooltip({
                     let focus_handle = editor.focus_handle(cx);
+                    let has_selected_lines = selected_lines.is_some();
                     move |_window, cx| {
-                        Tooltip::for_action_in(
-                            "Stage Hunk",
-                            &::git::ToggleStaged,
-                            &focus_handle,
-                            cx,
-                        )
+                        if has_selected_lines {
+                            Tooltip::for_action_in(
+                                "Stage Selected Lines",
+                                &::git::StageSelectedLines,
+                                &focus_handle,
+                                cx,
+                            )
+                        } else {
+                            Tooltip::for_action_in(
+                                "Stage Hunk",
+                                &::git::ToggleStaged,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
                     }
                 })
                 .on_click({
                     let editor = editor.clone();
                     move |_event, _window, cx| {
                         editor.update(cx, |editor, cx| {
-                            editor.stage_or_unstage_diff_hunks(
-                                true,
-                                vec![hunk_range.start..hunk_range.start],
-                                cx,
-                            );
+                            if let Some(selected_lines) = selected_lines.clone() {
+                                editor.stage_or_unstage_lines(true, vec![selected_lines], cx);
+                            } else {
+                                editor.stage_or_unstage_diff_hunks(
+                                    true,
+                                    vec![hunk_range.start..hunk_range.start],
+                                    cx,
+                                );
+                            }
                         });
                     }
                 })
@@ -28430,24 +28580,38 @@ fn render_diff_hunk_controls(
                 .alpha(if status.is_pending() { 0.66 } else { 1.0 })
                 .tooltip({
                     let focus_handle = editor.focus_handle(cx);
+                    let has_selected_lines = selected_lines.is_some();
                     move |_window, cx| {
-                        Tooltip::for_action_in(
-                            "Unstage Hunk",
-                            &::git::ToggleStaged,
-                            &focus_handle,
-                            cx,
-                        )
+                        if has_selected_lines {
+                            Tooltip::for_action_in(
+                                "Unstage Selected Lines",
+                                &::git::UnstageSelectedLines,
+                                &focus_handle,
+                                cx,
+                            )
+                        } else {
+                            Tooltip::for_action_in(
+                                "Unstage Hunk",
+                                &::git::ToggleStaged,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
                     }
                 })
                 .on_click({
                     let editor = editor.clone();
                     move |_event, _window, cx| {
                         editor.update(cx, |editor, cx| {
-                            editor.stage_or_unstage_diff_hunks(
-                                false,
-                                vec![hunk_range.start..hunk_range.start],
-                                cx,
-                            );
+                            if let Some(selected_lines) = selected_lines.clone() {
+                                editor.stage_or_unstage_lines(false, vec![selected_lines], cx);
+                            } else {
+                                editor.stage_or_unstage_diff_hunks(
+                                    false,
+                                    vec![hunk_range.start..hunk_range.start],
+                                    cx,
+                                );
+                            }
                         });
                     }
                 })
@@ -5,15 +5,18 @@ use futures::future::{self, BoxFuture, join_all};
 use git::{
     Oid, RunHook,
     blame::Blame,
+    line_history::{LineHistory, LineHistoryEntry},
     repository::{
-        AskPassDelegate, Branch, CommitDataReader, CommitDetails, CommitOptions, FetchOptions,
-        GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder,
-        LogSource, PushOptions, Remote, RepoPath, ResetMode, Worktree,
+        AskPassDelegate, Branch, CommitDataReader, CommitDetails, CommitOptions, CommitSignature,
+        FetchOptions, GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint,
+        InitialGraphCommitData, LogOrder, LogSource, MergeStages, PushOptions, Remote, RepoPath,
+        ResetMode, Worktree,
     },
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
         UnmergedStatus,
     },
+    submodule::{Submodule, SubmoduleState},
 };
 use gpui::{AsyncApp, BackgroundExecutor, SharedString, Task};
 use ignore::gitignore::GitignoreBuilder;
@@ -38,12 +41,17 @@ pub struct FakeGitRepository {
 pub struct FakeGitRepositoryState {
     pub event_emitter: smol::channel::Sender<PathBuf>,
     pub unmerged_paths: HashMap<RepoPath, UnmergedStatus>,
+    pub merge_stages: HashMap<RepoPath, MergeStages>,
     pub head_contents: HashMap<RepoPath, String>,
     pub index_contents: HashMap<RepoPath, String>,
     // everything in commit contents is in oids
     pub merge_base_contents: HashMap<RepoPath, Oid>,
     pub oids: HashMap<Oid, String>,
     pub blames: HashMap<RepoPath, Blame>,
+    pub line_histories: HashMap<RepoPath, Vec<LineHistoryEntry>>,
+    pub commit_signatures: HashMap<String, CommitSignature>,
+    /// The options of each commit made, oldest first.
+    pub commit_options: Vec<CommitOptions>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
     /// List of remotes, keys are names and values are URLs
@@ -51,6 +59,7 @@ pub struct FakeGitRepositoryState {
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
     pub graph_commits: Vec<Arc<InitialGraphCommitData>>,
+    pub submodules: Vec<Submodule>,
 }
 
 impl FakeGitRepositoryState {
@@ -60,7 +69,11 @@ impl FakeGitRepositoryState {
             head_contents: Default::default(),
             index_contents: Default::default(),
             unmerged_paths: Default::default(),
+            merge_stages: Default::default(),
             blames: Default::default(),
+            line_histories: Default::default(),
+            commit_signatures: Default::default(),
+            commit_options: Vec::new(),
             current_branch_name: Default::default(),
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
@@ -69,6 +82,7 @@ impl FakeGitRepositoryState {
             oids: Default::default(),
             remotes: HashMap::default(),
             graph_commits: Vec::new(),
+            submodules: Vec::new(),
         }
     }
 }
@@ -122,6 +136,16 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn load_merge_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<MergeStages>> {
+        self.with_state_async(false, move |state| {
+            state
+                .merge_stages
+                .get(&path)
+                .with_context(|| format!("{path:?} has no merge conflicts"))
+                .cloned()
+        })
+    }
+
     fn load_commit(
         &self,
         _commit: String,
@@ -209,6 +233,16 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn commit_signature(&self, commit: String) -> BoxFuture<'_, Result<CommitSignature>> {
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .commit_signatures
+                .get(&commit)
+                .cloned()
+                .unwrap_or_default())
+        })
+    }
+
     fn reset(
         &self,
         _commit: String,
@@ -488,6 +522,32 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn line_history(
+        &self,
+        path: RepoPath,
+        rows: std::ops::Range<u32>,
+        revision: Option<String>,
+    ) -> BoxFuture<'_, Result<LineHistory>> {
+        self.with_state_async(false, move |state| {
+            let mut entries = state.line_histories.get(&path).cloned().unwrap_or_default();
+            // Only `<sha>^` revisions are supported, which skip the history up
+            // to and including `<sha>`.
+            if let Some(sha) = revision.as_deref().and_then(|rev| rev.strip_suffix('^')) {
+                let ix = entries
+                    .iter()
+                    .position(|entry| entry.sha.as_ref() == sha)
+                    .with_context(|| format!("unknown revision {sha}"))?;
+                entries.drain(..=ix);
+            }
+            Ok(LineHistory {
+                path,
+                rows,
+                revision: revision.map(Into::into),
+                entries,
+            })
+        })
+    }
+
     fn stage_paths(
         &self,
         paths: Vec<RepoPath>,
@@ -572,11 +632,14 @@ impl GitRepository for FakeGitRepository {
         &self,
         _message: gpui::SharedString,
         _name_and_email: Option<(gpui::SharedString, gpui::SharedString)>,
-        _options: CommitOptions,
+        options: CommitOptions,
         _askpass: AskPassDelegate,
         _env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>> {
-        async { Ok(()) }.boxed()
+        self.with_state_async(false, move |state| {
+            state.commit_options.push(options);
+            Ok(())
+        })
     }
 
     fn run_hook(
@@ -622,6 +685,32 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        self.with_state_async(false, |state| Ok(state.submodules.clone()))
+    }
+
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        _init: bool,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        self.with_state_async(true, move |state| {
+            for submodule in &mut state.submodules {
+                if paths.is_empty() || paths.contains(&submodule.path) {
+                    submodule.current_sha = submodule.head_sha;
+                    submodule.state = SubmoduleState::UpToDate;
+                }
+            }
+            Ok(git::repository::RemoteCommandOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+            })
+        })
+    }
+
     fn get_all_remotes(&self) -> BoxFuture<'_, Result<Vec<Remote>>> {
         self.with_state_async(false, move |state| {
             let remotes = state
@@ -1993,6 +1993,18 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_merge_stages_for_repo(
+        &self,
+        dot_git: &Path,
+        merge_stages: Vec<(RepoPath, git::repository::MergeStages)>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.merge_stages.clear();
+            state.merge_stages.extend(merge_stages);
+        })
+        .unwrap();
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(RepoPath, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -2001,6 +2013,46 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_line_history_for_repo(
+        &self,
+        dot_git: &Path,
+        path: RepoPath,
+        entries: Vec<git::line_history::LineHistoryEntry>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.line_histories.insert(path, entries);
+        })
+        .unwrap();
+    }
+
+    pub fn set_commit_signatures_for_repo(
+        &self,
+        dot_git: &Path,
+        signatures: Vec<(String, git::repository::CommitSignature)>,
+    ) {
+        self.with_git_state(dot_git, false, |state| {
+            state.commit_signatures.clear();
+            state.commit_signatures.extend(signatures);
+        })
+        .unwrap();
+    }
+
+    pub fn commit_options_for_repo(&self, dot_git: &Path) -> Vec<git::repository::CommitOptions> {
+        self.with_git_state(dot_git, false, |state| state.commit_options.clone())
+            .unwrap()
+    }
+
+    pub fn set_submodules_for_repo(
+        &self,
+        dot_git: &Path,
+        submodules: Vec<git::submodule::Submodule>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.submodules = submodules;
+        })
+        .unwrap();
+    }
+
     pub fn set_graph_commits(&self, dot_git: &Path, commits: Vec<Arc<InitialGraphCommitData>>) {
         self.with_git_state(dot_git, true, |state| {
             state.graph_commits = commits;
@@ -1,10 +1,12 @@
 pub mod blame;
 pub mod commit;
 mod hosting_provider;
+pub mod line_history;
 mod remote;
 pub mod repository;
 pub mod stash;
 pub mod status;
+pub mod submodule;
 
 pub use crate::hosting_provider::*;
 pub use crate::remote::*;
@@ -37,6 +39,10 @@ actions!(
         StageAndNext,
         /// Unstages the current hunk and moves to the next one.
         UnstageAndNext,
+        /// Stages only the selected lines, splitting any hunks they overlap.
+        StageSelectedLines,
+        /// Unstages only the selected lines, splitting any hunks they overlap.
+        UnstageSelectedLines,
         /// Restores the selected hunks to their original state.
         #[action(deprecated_aliases = ["editor::RevertSelectedHunks"])]
         Restore,
@@ -46,6 +52,11 @@ actions!(
         Blame,
         /// Shows the git history for the current file.
         FileHistory,
+        /// Shows every revision of the selected lines, following them across
+        /// renames and moves.
+        LineHistory,
+        /// Opens the three-way merge editor for the current conflicted file.
+        OpenMergeEditor,
         /// Stages the current file.
         StageFile,
         /// Unstages the current file.
@@ -101,6 +112,12 @@ actions!(
         Clone,
         /// Adds a file to .gitignore.
         AddToGitignore,
+        /// Updates all submodules to the commits recorded in the repository.
+        UpdateSubmodules,
+        /// Initializes and updates all submodules, including ones that were never checked out.
+        InitSubmodules,
+        /// Opens the diff of the selected submodule's own changes.
+        OpenSubmoduleDiff,
     ]
 );
 
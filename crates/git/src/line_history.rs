@@ -0,0 +1,277 @@
+use crate::repository::RepoPath;
+use anyhow::{Context as _, Result, anyhow};
+use gpui::SharedString;
+use std::ops::Range;
+
+/// Marks the start of each commit in the `git log -L` output. The patch for the
+/// commit follows its header fields.
+pub(crate) const LINE_HISTORY_COMMIT_START: &str =
+    concat!("<<LINE_HISTORY-", "a41c6f0e-2b7d-4c93-8e15-9d0f3b6a7c28>>");
+
+/// The `--format` argument that pairs with [`parse_line_history`].
+pub(crate) fn line_history_format_arg() -> String {
+    format!("--format={LINE_HISTORY_COMMIT_START}%H%x00%P%x00%s%x00%at%x00%an%x00%ae%x00")
+}
+
+/// One revision of a tracked range of lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineHistoryEntry {
+    pub sha: SharedString,
+    pub parent_sha: Option<SharedString>,
+    pub subject: SharedString,
+    pub commit_timestamp: i64,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// The path of the file containing the lines at this revision.
+    pub path: RepoPath,
+    /// The path of the file in the parent revision, or `None` if the file
+    /// was added by this commit.
+    pub old_path: Option<RepoPath>,
+    /// Zero-based row of the first tracked line at this revision.
+    pub start_row: u32,
+    /// Zero-based row of the first tracked line in the parent revision.
+    pub old_start_row: u32,
+    /// The tracked lines as they appear at this revision.
+    pub text: String,
+    /// The tracked lines as they appear in the parent revision.
+    pub old_text: String,
+}
+
+impl LineHistoryEntry {
+    pub fn row_range(&self) -> Range<u32> {
+        self.start_row..self.start_row + line_count(&self.text)
+    }
+
+    /// The rows the tracked lines occupied in the parent revision, or `None`
+    /// if they were introduced by this commit.
+    pub fn old_row_range(&self) -> Option<Range<u32>> {
+        let count = line_count(&self.old_text);
+        (self.old_path.is_some() && count > 0)
+            .then(|| self.old_start_row..self.old_start_row + count)
+    }
+
+    pub fn is_rename(&self) -> bool {
+        self.old_path
+            .as_ref()
+            .is_some_and(|old_path| old_path != &self.path)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LineHistory {
+    /// The path the history was requested for.
+    pub path: RepoPath,
+    /// The zero-based, end-exclusive rows the history was requested for.
+    pub rows: Range<u32>,
+    /// The revision the history starts from, or `None` for `HEAD`.
+    pub revision: Option<SharedString>,
+    /// Revisions touching the tracked lines, newest first.
+    pub entries: Vec<LineHistoryEntry>,
+}
+
+fn line_count(text: &str) -> u32 {
+    text.lines().count() as u32
+}
+
+/// Parse the output of `git log -L<start>,<end>:<path>` run with
+/// [`line_history_format_arg`].
+pub(crate) fn parse_line_history(output: &str) -> Result<Vec<LineHistoryEntry>> {
+    let mut entries = Vec::new();
+    for block in output.split(LINE_HISTORY_COMMIT_START) {
+        if block.trim().is_empty() {
+            continue;
+        }
+        let mut fields = block.splitn(7, '\0');
+        let mut next_field = || {
+            fields
+                .next()
+                .with_context(|| format!("malformed line history entry {block:?}"))
+        };
+        let sha = next_field()?.trim().to_string();
+        let parent_sha = next_field()?.split_whitespace().next().map(str::to_string);
+        let subject = next_field()?.to_string();
+        let commit_timestamp = next_field()?.trim().parse().unwrap_or(0);
+        let author_name = next_field()?.to_string();
+        let author_email = next_field()?.to_string();
+        let patch = next_field()?;
+        let patch = parse_patch(patch).with_context(|| format!("parsing patch for {sha}"))?;
+
+        entries.push(LineHistoryEntry {
+            sha: sha.into(),
+            parent_sha: parent_sha.map(Into::into),
+            subject: subject.into(),
+            commit_timestamp,
+            author_name: author_name.into(),
+            author_email: author_email.into(),
+            path: patch.path,
+            old_path: patch.old_path,
+            start_row: patch.start_row,
+            old_start_row: patch.old_start_row,
+            text: patch.text,
+            old_text: patch.old_text,
+        });
+    }
+    Ok(entries)
+}
+
+struct LinePatch {
+    path: RepoPath,
+    old_path: Option<RepoPath>,
+    start_row: u32,
+    old_start_row: u32,
+    text: String,
+    old_text: String,
+}
+
+fn parse_patch(patch: &str) -> Result<LinePatch> {
+    let mut path = None;
+    let mut old_path = None;
+    let mut start_row = None;
+    let mut old_start_row = None;
+    let mut text = String::new();
+    let mut old_text = String::new();
+    let mut in_hunk = false;
+
+    for line in patch.lines() {
+        if !in_hunk {
+            if let Some(rest) = line.strip_prefix("--- ") {
+                old_path = rest.strip_prefix("a/").map(RepoPath::new).transpose()?;
+            } else if let Some(rest) = line.strip_prefix("+++ ") {
+                path = rest.strip_prefix("b/").map(RepoPath::new).transpose()?;
+            }
+        }
+
+        if let Some(header) = line.strip_prefix("@@ ") {
+            let (old_start, new_start) = parse_hunk_header(header)?;
+            // A contiguous range usually yields a single hunk. If it was split,
+            // the hunks are concatenated and anchored at the first one.
+            old_start_row.get_or_insert(old_start);
+            start_row.get_or_insert(new_start);
+            in_hunk = true;
+            continue;
+        }
+        if !in_hunk {
+            continue;
+        }
+
+        if let Some(content) = line.strip_prefix(' ') {
+            push_line(&mut text, content);
+            push_line(&mut old_text, content);
+        } else if let Some(content) = line.strip_prefix('+') {
+            push_line(&mut text, content);
+        } else if let Some(content) = line.strip_prefix('-') {
+            push_line(&mut old_text, content);
+        }
+    }
+
+    let path = path
+        .or_else(|| old_path.clone())
+        .context("patch has no file path")?;
+    Ok(LinePatch {
+        path,
+        old_path,
+        start_row: start_row.unwrap_or(0),
+        old_start_row: old_start_row.unwrap_or(0),
+        text,
+        old_text,
+    })
+}
+
+fn push_line(text: &mut String, line: &str) {
+    text.push_str(line);
+    text.push('\n');
+}
+
+/// Parse `-<old_start>[,<count>] +<new_start>[,<count>] @@` into zero-based
+/// starting rows.
+fn parse_hunk_header(header: &str) -> Result<(u32, u32)> {
+    let mut parts = header.split_whitespace();
+    let old = parts
+        .next()
+        .and_then(|part| part.strip_prefix('-'))
+        .ok_or_else(|| anyhow!("malformed hunk header {header:?}"))?;
+    let new = parts
+        .next()
+        .and_then(|part| part.strip_prefix('+'))
+        .ok_or_else(|| anyhow!("malformed hunk header {header:?}"))?;
+    let start_row = |range: &str| -> Result<u32> {
+        let start: u32 = range.split(',').next().unwrap_or(range).parse()?;
+        Ok(start.saturating_sub(1))
+    };
+    Ok((start_row(old)?, start_row(new)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(header: &str, patch: &str) -> String {
+        format!("{LINE_HISTORY_COMMIT_START}{header}\0\n{patch}\n")
+    }
+
+    #[test]
+    fn test_parse_line_history() {
+        let output = [
+            commit(
+                "2222222222222222222222222222222222222222\01111111111111111111111111111111111111111\0Rename helpers\01700000100\0Ada\0ada@example.com",
+                "diff --git a/src/util.rs b/src/helpers.rs\n\
+--- a/src/util.rs\n\
++++ b/src/helpers.rs\n\
+@@ -3,3 +5,3 @@\n\
+ fn helper() {\n\
+-    old_call();\n\
++    new_call();\n\
+ }",
+            ),
+            commit(
+                "1111111111111111111111111111111111111111\0\0Initial commit\01700000000\0Grace\0grace@example.com",
+                "diff --git a/src/util.rs b/src/util.rs\n\
+--- /dev/null\n\
++++ b/src/util.rs\n\
+@@ -0,0 +3,3 @@\n\
++fn helper() {\n\
++    old_call();\n\
++}",
+            ),
+        ]
+        .concat();
+
+        let entries = parse_line_history(&output).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let rename = &entries[0];
+        assert_eq!(
+            rename.sha.as_ref(),
+            "2222222222222222222222222222222222222222"
+        );
+        assert_eq!(
+            rename.parent_sha.as_ref().map(|sha| sha.as_ref()),
+            Some("1111111111111111111111111111111111111111")
+        );
+        assert_eq!(rename.subject.as_ref(), "Rename helpers");
+        assert_eq!(rename.commit_timestamp, 1700000100);
+        assert_eq!(rename.path, RepoPath::new("src/helpers.rs").unwrap());
+        assert_eq!(rename.old_path, Some(RepoPath::new("src/util.rs").unwrap()));
+        assert!(rename.is_rename());
+        assert_eq!(rename.row_range(), 4..7);
+        assert_eq!(rename.old_row_range(), Some(2..5));
+        assert_eq!(rename.text, "fn helper() {\n    new_call();\n}\n");
+        assert_eq!(rename.old_text, "fn helper() {\n    old_call();\n}\n");
+
+        let initial = &entries[1];
+        assert_eq!(initial.parent_sha, None);
+        assert_eq!(initial.old_path, None);
+        assert!(!initial.is_rename());
+        assert_eq!(initial.row_range(), 2..5);
+        assert_eq!(initial.old_row_range(), None);
+        assert_eq!(initial.old_text, "");
+    }
+
+    #[test]
+    fn test_parse_hunk_header() {
+        assert_eq!(parse_hunk_header("-10,4 +12,5 @@").unwrap(), (9, 11));
+        assert_eq!(parse_hunk_header("-1 +1 @@ fn main()").unwrap(), (0, 0));
+        assert_eq!(parse_hunk_header("-0,0 +1,2 @@").unwrap(), (0, 0));
+        assert!(parse_hunk_header("bogus").is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use crate::commit::parse_git_diff_name_status;
+use crate::line_history::{LineHistory, line_history_format_arg, parse_line_history};
 use crate::stash::GitStash;
 use crate::status::{DiffTreeType, GitStatus, StatusCode, TreeDiff};
+use crate::submodule::{Submodule, parse_gitlinks, parse_gitmodules_urls, parse_submodule_status};
 use crate::{Oid, RunHook, SHORT_SHA_LENGTH};
 use anyhow::{Context as _, Result, anyhow, bail};
 use collections::HashMap;
@@ -26,6 +28,7 @@ use std::str::FromStr;
 use std::{
     cmp::Ordering,
     future,
+    ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -256,10 +259,49 @@ impl Upstream {
     }
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct CommitOptions {
     pub amend: bool,
     pub signoff: bool,
+    pub signing: CommitSigning,
+}
+
+/// Whether a new commit should be cryptographically signed.
+///
+/// Signing uses the given key format, or `gpg.format` if `None`. The key is
+/// taken from `user.signingkey`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommitSigning {
+    /// Sign according to the repository's `commit.gpgsign` config.
+    GitConfig(Option<SigningFormat>),
+    /// Always sign.
+    Sign(Option<SigningFormat>),
+    /// Never sign, even if `commit.gpgsign` is set.
+    NoSign,
+}
+
+impl Default for CommitSigning {
+    fn default() -> Self {
+        Self::GitConfig(None)
+    }
+}
+
+/// The kinds of keys git can sign commits with, as in `gpg.format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SigningFormat {
+    OpenPgp,
+    Ssh,
+    X509,
+}
+
+impl SigningFormat {
+    pub fn git_config_value(&self) -> &'static str {
+        match self {
+            SigningFormat::OpenPgp => "openpgp",
+            SigningFormat::Ssh => "ssh",
+            SigningFormat::X509 => "x509",
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -326,6 +368,43 @@ pub struct CommitDetails {
     pub author_name: SharedString,
 }
 
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum SignatureStatus {
+    #[default]
+    Unsigned,
+    /// The signature is good and made by a trusted key.
+    Verified,
+    /// The commit is signed, but the signature is bad, expired, revoked, or
+    /// can't be checked against a trusted key.
+    Unverified,
+}
+
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct CommitSignature {
+    pub status: SignatureStatus,
+    /// The name of the signer, if the signature could be checked.
+    pub signer: SharedString,
+    /// The fingerprint or ID of the signing key.
+    pub key: SharedString,
+}
+
+impl CommitSignature {
+    /// Build a signature from the `%G?`, `%GS` and `%GK` placeholders of
+    /// `git log --format`.
+    pub fn from_format_placeholders(status: &str, signer: &str, key: &str) -> Self {
+        let status = match status.trim() {
+            "G" => SignatureStatus::Verified,
+            "" | "N" => SignatureStatus::Unsigned,
+            _ => SignatureStatus::Unverified,
+        };
+        Self {
+            status,
+            signer: signer.trim().to_string().into(),
+            key: key.trim().to_string().into(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct FileHistoryEntry {
     pub sha: SharedString,
@@ -342,6 +421,17 @@ pub struct FileHistory {
     pub path: RepoPath,
 }
 
+/// The versions of a conflicted file recorded in the index during a merge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeStages {
+    /// The common ancestor (stage 1), or `None` if the file was added on both sides.
+    pub base: Option<String>,
+    /// Our version (stage 2), or `None` if we deleted the file.
+    pub ours: Option<String>,
+    /// Their version (stage 3), or `None` if they deleted the file.
+    pub theirs: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct CommitDiff {
     pub files: Vec<CommitFile>,
@@ -578,6 +668,9 @@ pub trait GitRepository: Send + Sync {
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
     fn load_blob_content(&self, oid: Oid) -> BoxFuture<'_, Result<String>>;
 
+    /// Returns the base, ours and theirs versions of a path that has merge conflicts.
+    fn load_merge_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<MergeStages>>;
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -645,6 +738,10 @@ pub trait GitRepository: Send + Sync {
 
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
+    /// Checks the signature of a commit. This runs gpg or ssh-keygen, so it's
+    /// only done for commits the user is looking at.
+    fn commit_signature(&self, commit: String) -> BoxFuture<'_, Result<CommitSignature>>;
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
     fn blame(
         &self,
@@ -659,6 +756,14 @@ pub trait GitRepository: Send + Sync {
         skip: usize,
         limit: Option<usize>,
     ) -> BoxFuture<'_, Result<FileHistory>>;
+    /// Returns every revision of the given rows, following them across moves
+    /// and renames, starting from `revision` or `HEAD`.
+    fn line_history(
+        &self,
+        path: RepoPath,
+        rows: Range<u32>,
+        revision: Option<String>,
+    ) -> BoxFuture<'_, Result<LineHistory>>;
 
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
     /// worktree's gitdir within the main repository (typically `.git/worktrees/<name>`).
@@ -767,6 +872,21 @@ pub trait GitRepository: Send + Sync {
 
     fn create_remote(&self, name: String, url: String) -> BoxFuture<'_, Result<()>>;
 
+    /// Lists the submodules registered in this repository, along with the
+    /// commits they point to.
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>>;
+
+    /// Runs `git submodule update` for the given paths, or for every submodule
+    /// when `paths` is empty.
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
     /// returns a list of remote branches that contain HEAD
     fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<SharedString>>>;
 
@@ -986,6 +1106,40 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn commit_signature(&self, commit: String) -> BoxFuture<'_, Result<CommitSignature>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let output = new_smol_command(git_binary_path)
+                    .current_dir(&working_directory)
+                    .args([
+                        "--no-optional-locks",
+                        "show",
+                        "--no-patch",
+                        "--format=%G?%x00%GS%x00%GK%x00",
+                        &commit,
+                    ])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "failed to check the signature of {commit:?}: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                let output = std::str::from_utf8(&output.stdout)?;
+                let fields = output.split('\0').collect::<Vec<_>>();
+                if fields.len() != 4 {
+                    bail!("unexpected git-show output for {commit:?}: {output:?}")
+                }
+                Ok(CommitSignature::from_format_placeholders(
+                    fields[0], fields[1], fields[2],
+                ))
+            })
+            .boxed()
+    }
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
         let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
         else {
@@ -1218,6 +1372,42 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_merge_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<MergeStages>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let mut index = repo.index()?;
+                index.read(false)?;
+
+                let load_stage = |stage: i32| -> Result<Option<String>> {
+                    let Some(entry) = index.get_path(path.as_std_path(), stage) else {
+                        return Ok(None);
+                    };
+                    let content = repo.find_blob(entry.id)?.content().to_owned();
+                    Ok(Some(
+                        String::from_utf8(content).context("merge stage is not valid UTF-8")?,
+                    ))
+                };
+
+                const STAGE_BASE: i32 = 1;
+                const STAGE_OURS: i32 = 2;
+                const STAGE_THEIRS: i32 = 3;
+                let stages = MergeStages {
+                    base: load_stage(STAGE_BASE)?,
+                    ours: load_stage(STAGE_OURS)?,
+                    theirs: load_stage(STAGE_THEIRS)?,
+                };
+                anyhow::ensure!(
+                    stages.ours.is_some() || stages.theirs.is_some(),
+                    "{} has no merge conflicts",
+                    path.as_unix_str()
+                );
+                Ok(stages)
+            })
+            .boxed()
+    }
+
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         let repo = self.repository.clone();
         self.executor
@@ -1814,6 +2004,56 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn line_history(
+        &self,
+        path: RepoPath,
+        rows: Range<u32>,
+        revision: Option<String>,
+    ) -> BoxFuture<'_, Result<LineHistory>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                anyhow::ensure!(rows.start < rows.end, "empty line range");
+
+                // `-L` takes one-based, inclusive line numbers.
+                let range_arg = format!("-L{},{}:{}", rows.start + 1, rows.end, path.as_unix_str());
+                let format_arg = line_history_format_arg();
+                let mut command = new_smol_command(&git_binary_path);
+                command.current_dir(&working_directory).args([
+                    "--no-optional-locks",
+                    "-c",
+                    "core.quotePath=false",
+                    "log",
+                    "--no-color",
+                    "--no-ext-diff",
+                    format_arg.as_str(),
+                    range_arg.as_str(),
+                ]);
+                if let Some(revision) = &revision {
+                    // Revisions can come from collaborators, and mustn't be taken for options.
+                    anyhow::ensure!(!revision.starts_with('-'), "invalid revision {revision:?}");
+                    command.arg(revision);
+                }
+
+                let output = command.output().await?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    bail!("git log -L failed: {stderr}");
+                }
+
+                let stdout = std::str::from_utf8(&output.stdout)?;
+                Ok(LineHistory {
+                    entries: parse_line_history(stdout)?,
+                    path,
+                    rows,
+                    revision: revision.map(Into::into),
+                })
+            })
+            .boxed()
+    }
+
     fn diff(&self, diff: DiffType) -> BoxFuture<'_, Result<String>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
@@ -2032,10 +2272,34 @@ impl GitRepository for RealGitRepository {
         // Note: Do not spawn this command on the background thread, it might pop open the credential helper
         // which we want to block on.
         async move {
-            let mut cmd = new_smol_command(git_binary_path);
-            cmd.current_dir(&working_directory?)
-                .envs(env.iter())
-                .args(["commit", "--quiet", "-m"])
+            let working_directory = working_directory?;
+            let mut cmd = new_smol_command(&git_binary_path);
+            cmd.current_dir(&working_directory).envs(env.iter());
+
+            let format = match options.signing {
+                CommitSigning::GitConfig(format) | CommitSigning::Sign(format) => format,
+                CommitSigning::NoSign => None,
+            };
+            if let Some(format) = format {
+                cmd.arg("-c")
+                    .arg(format!("gpg.format={}", format.git_config_value()));
+            }
+
+            // Kept alive until git exits.
+            let _gpg_wrapper = if options.signing != CommitSigning::NoSign {
+                GpgPassphraseWrapper::install(
+                    &git_binary_path,
+                    &working_directory,
+                    format,
+                    &mut cmd,
+                )
+                .await
+                .context("preparing to sign the commit")?
+            } else {
+                None
+            };
+
+            cmd.args(["commit", "--quiet", "-m"])
                 .arg(&message.to_string())
                 .arg("--cleanup=strip")
                 .arg("--no-verify")
@@ -2050,6 +2314,19 @@ impl GitRepository for RealGitRepository {
                 cmd.arg("--signoff");
             }
 
+            // Passphrases for SSH signing keys are requested through askpass,
+            // since ssh-keygen honors SSH_ASKPASS. GPG keys go through the
+            // wrapper installed above.
+            match options.signing {
+                CommitSigning::GitConfig(_) => {}
+                CommitSigning::Sign(_) => {
+                    cmd.arg("--gpg-sign");
+                }
+                CommitSigning::NoSign => {
+                    cmd.arg("--no-gpg-sign");
+                }
+            }
+
             if let Some((name, email)) = name_and_email {
                 cmd.arg("--author").arg(&format!("{name} <{email}>"));
             }
@@ -2273,6 +2550,97 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                if !working_directory.join(".gitmodules").exists() {
+                    return Ok(Vec::new());
+                }
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                let status = git.run(&["submodule", "status"]).await?;
+                let entries = parse_submodule_status(&status)?;
+                if entries.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let urls = git
+                    .run(&[
+                        "config",
+                        "-f",
+                        ".gitmodules",
+                        "--get-regexp",
+                        r"^submodule\..*\.(path|url)$",
+                    ])
+                    .await
+                    .map(|output| parse_gitmodules_urls(&output))
+                    .unwrap_or_default();
+
+                let mut ls_tree_args = vec!["ls-tree", "-z", "HEAD", "--"];
+                ls_tree_args.extend(entries.iter().map(|entry| entry.path.as_str()));
+                // An unborn HEAD has no recorded submodule commits.
+                let gitlinks = git
+                    .run(&ls_tree_args)
+                    .await
+                    .map(|output| parse_gitlinks(&output))
+                    .unwrap_or_default();
+
+                entries
+                    .into_iter()
+                    .map(|entry| {
+                        Ok(Submodule {
+                            path: RepoPath::new(&entry.path)?,
+                            url: urls.get(&entry.path).cloned(),
+                            head_sha: gitlinks.get(&entry.path).copied(),
+                            current_sha: (!entry.sha.is_zero()).then_some(entry.sha),
+                            state: entry.state,
+                        })
+                    })
+                    .collect()
+            })
+            .boxed()
+    }
+
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.system_git_binary_path.clone();
+        let executor = cx.background_executor().clone();
+        // Note: Do not spawn this command on the background thread, it might pop open the credential helper
+        // which we want to block on.
+        async move {
+            let git_binary_path =
+                git_binary_path.context("git not found on $PATH, can't update submodules")?;
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(["submodule", "update", "--recursive"])
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            if init {
+                command.arg("--init");
+            }
+
+            command
+                .arg("--")
+                .args(paths.iter().map(|path| path.as_unix_str()));
+
+            run_git_command(env, ask_pass, command, executor).await
+        }
+        .boxed()
+    }
+
     fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<SharedString>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
@@ -2987,6 +3355,83 @@ struct GitBinaryCommandError {
     status: ExitStatus,
 }
 
+/// Wraps the `gpg.openpgp.program` git signs commits with, so that the signing
+/// key's passphrase is asked for through askpass when gpg-agent doesn't have it
+/// cached. Otherwise gpg would start a pinentry that may not be able to show up.
+struct GpgPassphraseWrapper {
+    _dir: tempfile::TempDir,
+}
+
+impl GpgPassphraseWrapper {
+    const SCRIPT: &str = r#"#!/bin/sh
+data=$(mktemp) || exit 1
+errors=$(mktemp) || exit 1
+trap 'rm -f "$data" "$errors"' EXIT
+cat > "$data"
+if "$ZED_GPG_PROGRAM" --pinentry-mode error "$@" < "$data" 2> "$errors"; then
+    cat "$errors" >&2
+    exit 0
+fi
+askpass=${SSH_ASKPASS:-$GIT_ASKPASS}
+if [ -z "$askpass" ] || ! grep -qi pinentry "$errors"; then
+    cat "$errors" >&2
+    exit 1
+fi
+passphrase=$("$askpass" "Passphrase for the commit signing key:") || exit 1
+printf '%s\n' "$passphrase" |
+    "$ZED_GPG_PROGRAM" --pinentry-mode loopback --passphrase-fd 3 "$@" 3<&0 < "$data"
+"#;
+
+    /// Points `command` at the wrapper if the commit would be signed with gpg.
+    async fn install(
+        git_binary_path: &Path,
+        working_directory: &Path,
+        format: Option<SigningFormat>,
+        command: &mut smol::process::Command,
+    ) -> Result<Option<Self>> {
+        if cfg!(windows) {
+            return Ok(None);
+        }
+        let config = async |key: &str| -> Result<Option<String>> {
+            let output = new_smol_command(git_binary_path)
+                .current_dir(working_directory)
+                .args(["config", "--get", key])
+                .output()
+                .await?;
+            Ok(output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string()))
+        };
+
+        let uses_gpg = match format {
+            Some(format) => format == SigningFormat::OpenPgp,
+            None => config("gpg.format")
+                .await?
+                .is_none_or(|format| format == "openpgp"),
+        };
+        if !uses_gpg {
+            return Ok(None);
+        }
+        let program = match config("gpg.openpgp.program").await? {
+            Some(program) => program,
+            None => config("gpg.program")
+                .await?
+                .unwrap_or_else(|| "gpg".to_string()),
+        };
+
+        let dir = tempfile::Builder::new().prefix("zed-gpg").tempdir()?;
+        let script_path = dir.path().join("gpg.sh");
+        smol::fs::write(&script_path, Self::SCRIPT).await?;
+        util::fs::make_file_executable(&script_path).await?;
+        command
+            .arg("-c")
+            .arg(format!("gpg.openpgp.program={}", script_path.display()))
+            .env("ZED_GPG_PROGRAM", program);
+        Ok(Some(Self { _dir: dir }))
+    }
+}
+
 async fn run_git_command(
     env: Arc<HashMap<String, String>>,
     ask_pass: AskPassDelegate,
@@ -3319,6 +3764,167 @@ mod tests {
         // );
     }
 
+    #[gpui::test]
+    async fn test_line_history_rejects_options(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        smol::fs::write(repo_dir.path().join("file"), "one\ntwo\n")
+            .await
+            .unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+
+        let history = repo
+            .line_history(repo_path("file"), 1..2, Some("HEAD".into()))
+            .await
+            .unwrap();
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.entries[0].text, "two\n");
+
+        let output = repo_dir.path().join("output");
+        let revision = format!("--output={}", output.display());
+        assert!(
+            repo.line_history(repo_path("file"), 1..2, Some(revision))
+                .await
+                .is_err()
+        );
+        assert!(!output.exists());
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_commit_signing(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        let scripts_dir = tempfile::tempdir().unwrap();
+        // Stands in for a gpg whose key has the passphrase "hunter2" and isn't
+        // cached by gpg-agent.
+        let gpg_path = scripts_dir.path().join("gpg");
+        smol::fs::write(
+            &gpg_path,
+            r#"#!/bin/sh
+case "$*" in
+    *"--pinentry-mode error"*)
+        echo "gpg: signing failed: No pinentry" >&2
+        exit 2 ;;
+esac
+read -r passphrase <&3
+[ "$passphrase" = "hunter2" ] || exit 2
+cat > /dev/null
+printf '[GNUPG:] KEY_CONSIDERED KEY 0\n[GNUPG:] SIG_CREATED D 1 8 00 0 KEY\n' >&2
+printf -- '-----BEGIN PGP SIGNATURE-----\n\nc2ln\n-----END PGP SIGNATURE-----\n'
+"#,
+        )
+        .await
+        .unwrap();
+        let askpass_path = scripts_dir.path().join("askpass");
+        smol::fs::write(&askpass_path, "#!/bin/sh\necho hunter2\n")
+            .await
+            .unwrap();
+        util::fs::make_file_executable(&gpg_path).await.unwrap();
+        util::fs::make_file_executable(&askpass_path).await.unwrap();
+
+        let git2_repo = git2::Repository::init(repo_dir.path()).unwrap();
+        let mut config = git2_repo.config().unwrap();
+        config.set_bool("commit.gpgsign", true).unwrap();
+        config.set_str("gpg.format", "ssh").unwrap();
+        config
+            .set_str("gpg.program", gpg_path.to_str().unwrap())
+            .unwrap();
+        config.set_str("user.signingkey", "KEY").unwrap();
+
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let mut env = checkpoint_author_envs();
+        env.insert(
+            "GIT_ASKPASS".into(),
+            askpass_path.to_str().unwrap().to_string(),
+        );
+        let env = Arc::new(env);
+
+        let commit = async |contents: &str, signing: CommitSigning| {
+            smol::fs::write(repo_dir.path().join("file"), contents)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                contents.to_string().into(),
+                None,
+                CommitOptions {
+                    signing,
+                    ..Default::default()
+                },
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await?;
+            let head = git2_repo.head().unwrap().peel_to_commit().unwrap();
+            anyhow::Ok(head.header_field_bytes("gpgsig").is_ok())
+        };
+
+        // The format from settings overrides `gpg.format`, even when signing
+        // is left to `commit.gpgsign`, and the passphrase comes from askpass.
+        assert!(
+            commit(
+                "one",
+                CommitSigning::GitConfig(Some(SigningFormat::OpenPgp))
+            )
+            .await
+            .unwrap()
+        );
+        assert!(
+            commit("two", CommitSigning::Sign(Some(SigningFormat::OpenPgp)))
+                .await
+                .unwrap()
+        );
+        assert!(!commit("three", CommitSigning::NoSign).await.unwrap());
+        assert_eq!(
+            repo.commit_signature("HEAD".into()).await.unwrap().status,
+            SignatureStatus::Unsigned
+        );
+
+        // A wrong passphrase fails the commit.
+        smol::fs::write(&askpass_path, "#!/bin/sh\necho wrong\n")
+            .await
+            .unwrap();
+        assert!(
+            commit("four", CommitSigning::Sign(Some(SigningFormat::OpenPgp)))
+                .await
+                .is_err()
+        );
+    }
+
     #[gpui::test]
     async fn test_checkpoint_empty_repo(cx: &mut TestAppContext) {
         disable_git_global_config();
@@ -3470,6 +4076,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_commit_signature_from_format_placeholders() {
+        let signature = CommitSignature::from_format_placeholders(
+            "G",
+            "Jane Doe <jane@example.com>",
+            "ABCDEF0123456789",
+        );
+        assert_eq!(signature.status, SignatureStatus::Verified);
+        assert_eq!(signature.signer.as_ref(), "Jane Doe <jane@example.com>");
+        assert_eq!(signature.key.as_ref(), "ABCDEF0123456789");
+
+        for status in ["B", "U", "X", "Y", "R", "E"] {
+            assert_eq!(
+                CommitSignature::from_format_placeholders(status, "", "").status,
+                SignatureStatus::Unverified,
+                "status {status:?}"
+            );
+        }
+        assert_eq!(
+            CommitSignature::from_format_placeholders("N", "", "").status,
+            SignatureStatus::Unsigned
+        );
+    }
+
     #[test]
     fn test_branches_parsing() {
         // suppress "help: octal escapes are not supported, `\0` is always null"
@@ -0,0 +1,198 @@
+use crate::{Oid, repository::RepoPath};
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use gpui::SharedString;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SubmoduleState {
+    /// The submodule is registered in `.gitmodules` but hasn't been checked out.
+    Uninitialized,
+    /// The checked out commit matches the commit recorded in the index.
+    UpToDate,
+    /// The checked out commit differs from the commit recorded in the index.
+    Modified,
+    /// The submodule pointer has merge conflicts.
+    Conflicted,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Submodule {
+    pub path: RepoPath,
+    pub url: Option<SharedString>,
+    /// The commit recorded for this submodule in the superproject's `HEAD`.
+    pub head_sha: Option<Oid>,
+    /// The commit currently checked out in the submodule, or the commit
+    /// recorded in the index when the submodule isn't initialized.
+    pub current_sha: Option<Oid>,
+    pub state: SubmoduleState,
+}
+
+impl Submodule {
+    pub fn is_initialized(&self) -> bool {
+        self.state != SubmoduleState::Uninitialized
+    }
+
+    /// Returns the `(old, new)` commits when the submodule's pointer has moved
+    /// relative to the superproject's `HEAD`.
+    pub fn pointer_change(&self) -> Option<(Option<Oid>, Oid)> {
+        let current_sha = self.current_sha?;
+        if self.head_sha == Some(current_sha) {
+            return None;
+        }
+        Some((self.head_sha, current_sha))
+    }
+}
+
+/// A single line of `git submodule status` output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SubmoduleStatusLine {
+    pub path: String,
+    pub sha: Oid,
+    pub state: SubmoduleState,
+}
+
+/// Parse the output of `git submodule status`.
+///
+/// Each line has the format `<state><sha> <path>[ (<describe>)]`, where state is
+/// one of ` `, `-`, `+` or `U`.
+pub(crate) fn parse_submodule_status(output: &str) -> Result<Vec<SubmoduleStatusLine>> {
+    let mut entries = Vec::new();
+    for line in output.lines() {
+        if line.is_empty() {
+            continue;
+        }
+        let mut chars = line.chars();
+        let state = match chars.next() {
+            Some(' ') => SubmoduleState::UpToDate,
+            Some('-') => SubmoduleState::Uninitialized,
+            Some('+') => SubmoduleState::Modified,
+            Some('U') => SubmoduleState::Conflicted,
+            other => return Err(anyhow!("unexpected submodule state {other:?} in {line:?}")),
+        };
+        let rest = chars.as_str();
+        let (sha, path) = rest
+            .split_once(' ')
+            .with_context(|| format!("malformed submodule status line {line:?}"))?;
+        let path = match path.rfind(" (") {
+            Some(ix) if path.ends_with(')') => &path[..ix],
+            _ => path,
+        };
+        entries.push(SubmoduleStatusLine {
+            path: path.to_string(),
+            sha: Oid::from_str(sha)?,
+            state,
+        });
+    }
+    Ok(entries)
+}
+
+/// Parse the output of `git config -f .gitmodules --get-regexp ^submodule\..*\.(path|url)$`
+/// into a map from submodule path to its configured URL.
+pub(crate) fn parse_gitmodules_urls(output: &str) -> HashMap<String, SharedString> {
+    let mut paths_by_name = HashMap::default();
+    let mut urls_by_name = HashMap::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(' ') else {
+            continue;
+        };
+        let Some(key) = key.strip_prefix("submodule.") else {
+            continue;
+        };
+        if let Some(name) = key.strip_suffix(".path") {
+            paths_by_name.insert(name.to_string(), value.to_string());
+        } else if let Some(name) = key.strip_suffix(".url") {
+            urls_by_name.insert(name.to_string(), SharedString::from(value.to_string()));
+        }
+    }
+    paths_by_name
+        .into_iter()
+        .filter_map(|(name, path)| Some((path, urls_by_name.remove(&name)?)))
+        .collect()
+}
+
+/// Parse the NUL-separated output of `git ls-tree -z HEAD -- <paths>`, keeping
+/// only gitlink entries, into a map from path to recorded commit.
+pub(crate) fn parse_gitlinks(output: &str) -> HashMap<String, Oid> {
+    output
+        .split('\0')
+        .filter_map(|entry| {
+            let (info, path) = entry.split_once('\t')?;
+            let mut fields = info.split(' ');
+            let _mode = fields.next()?;
+            if fields.next()? != "commit" {
+                return None;
+            }
+            let sha = Oid::from_str(fields.next()?).ok()?;
+            Some((path.to_string(), sha))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_submodule_status() {
+        let output = "\
+ 1111111111111111111111111111111111111111 libs/clean (v1.0.0)
+-2222222222222222222222222222222222222222 libs/uninit
++3333333333333333333333333333333333333333 libs/with space (heads/main)
+U0000000000000000000000000000000000000000 libs/conflicted
+";
+        let entries = parse_submodule_status(output).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.path.as_str(), entry.state))
+                .collect::<Vec<_>>(),
+            vec![
+                ("libs/clean", SubmoduleState::UpToDate),
+                ("libs/uninit", SubmoduleState::Uninitialized),
+                ("libs/with space", SubmoduleState::Modified),
+                ("libs/conflicted", SubmoduleState::Conflicted),
+            ]
+        );
+        assert_eq!(
+            entries[2].sha.to_string(),
+            "3333333333333333333333333333333333333333"
+        );
+
+        assert!(parse_submodule_status("*abc libs/bad").is_err());
+        assert!(parse_submodule_status("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_gitmodules_urls() {
+        let output = "\
+submodule.first.path libs/first
+submodule.first.url https://example.com/first.git
+submodule.second.url ../second.git
+submodule.second.path libs/second
+submodule.orphan.path libs/orphan
+";
+        let urls = parse_gitmodules_urls(output);
+        assert_eq!(urls.len(), 2);
+        assert_eq!(
+            urls.get("libs/first").map(|url| url.as_ref()),
+            Some("https://example.com/first.git")
+        );
+        assert_eq!(
+            urls.get("libs/second").map(|url| url.as_ref()),
+            Some("../second.git")
+        );
+    }
+
+    #[test]
+    fn test_parse_gitlinks() {
+        let output = "160000 commit 1111111111111111111111111111111111111111\tlibs/first\0\
+100644 blob 2222222222222222222222222222222222222222\tREADME.md\0";
+        let gitlinks = parse_gitlinks(output);
+        assert_eq!(gitlinks.len(), 1);
+        assert_eq!(
+            gitlinks["libs/first"].to_string(),
+            "1111111111111111111111111111111111111111"
+        );
+    }
+}
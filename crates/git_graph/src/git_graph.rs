@@ -3,9 +3,9 @@ use feature_flags::{FeatureFlag, FeatureFlagAppExt as _};
 use git::{
     BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, Oid, ParsedGitRemote,
     parse_git_remote_url,
-    repository::{CommitDiff, InitialGraphCommitData, LogOrder, LogSource},
+    repository::{CommitDiff, CommitSignature, InitialGraphCommitData, LogOrder, LogSource},
 };
-use git_ui::commit_tooltip::CommitAvatar;
+use git_ui::commit_tooltip::{CommitAvatar, render_signature_badge};
 use gpui::{
     AnyElement, App, Bounds, ClipboardItem, Context, Corner, DefiniteLength, ElementId, Entity,
     EventEmitter, FocusHandle, Focusable, FontWeight, Hsla, InteractiveElement, ParentElement,
@@ -588,6 +588,7 @@ pub struct GitGraph {
     log_source: LogSource,
     log_order: LogOrder,
     selected_commit_diff: Option<CommitDiff>,
+    selected_commit_signature: Option<CommitSignature>,
     _commit_diff_task: Option<Task<()>>,
     _load_task: Option<Task<()>>,
 }
@@ -672,6 +673,7 @@ impl GitGraph {
             graph_viewport_width: px(88.),
             selected_entry_idx: None,
             selected_commit_diff: None,
+            selected_commit_signature: None,
             log_source,
             log_order,
         }
@@ -851,6 +853,7 @@ impl GitGraph {
 
         self.selected_entry_idx = Some(idx);
         self.selected_commit_diff = None;
+        self.selected_commit_signature = None;
 
         let Some(commit) = self.graph_data.commits.get(idx) else {
             return;
@@ -865,7 +868,12 @@ impl GitGraph {
             return;
         };
 
-        let diff_receiver = repository.update(cx, |repo, _| repo.load_commit_diff(sha));
+        let (diff_receiver, signature_receiver) = repository.update(cx, |repo, _| {
+            (
+                repo.load_commit_diff(sha.clone()),
+                repo.commit_signature(sha),
+            )
+        });
 
         self._commit_diff_task = Some(cx.spawn(async move |this, cx| {
             if let Ok(Ok(diff)) = diff_receiver.await {
@@ -875,6 +883,13 @@ impl GitGraph {
                 })
                 .ok();
             }
+            if let Ok(Ok(signature)) = signature_receiver.await {
+                this.update(cx, |this, cx| {
+                    this.selected_commit_signature = Some(signature);
+                    cx.notify();
+                })
+                .ok();
+            }
         }));
 
         cx.notify();
@@ -1020,6 +1035,7 @@ impl GitGraph {
                                 .on_click(cx.listener(move |this, _, _, cx| {
                                     this.selected_entry_idx = None;
                                     this.selected_commit_diff = None;
+                                    this.selected_commit_signature = None;
                                     this._commit_diff_task = None;
                                     cx.notify();
                                 })),
@@ -1091,6 +1107,11 @@ impl GitGraph {
                                             })
                                     }),
                             )
+                            .children(
+                                self.selected_commit_signature
+                                    .as_ref()
+                                    .map(render_signature_badge),
+                            )
                             .when_some(remote.clone(), |this, remote| {
                                 let provider_name = remote.host.name();
                                 let icon = match provider_name.as_str() {
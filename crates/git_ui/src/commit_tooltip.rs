@@ -2,11 +2,11 @@ use crate::commit_view::CommitView;
 use editor::hover_markdown_style;
 use futures::Future;
 use git::blame::BlameEntry;
-use git::repository::CommitSummary;
+use git::repository::{CommitSignature, CommitSummary, SignatureStatus};
 use git::{GitRemote, commit::ParsedCommitMessage};
 use gpui::{
     App, Asset, Element, Entity, MouseButton, ParentElement, Render, ScrollHandle,
-    StatefulInteractiveElement, WeakEntity, prelude::*,
+    StatefulInteractiveElement, Task, WeakEntity, prelude::*,
 };
 use markdown::{Markdown, MarkdownElement};
 use project::git_store::Repository;
@@ -14,7 +14,7 @@ use settings::Settings;
 use std::hash::Hash;
 use theme::ThemeSettings;
 use time::{OffsetDateTime, UtcOffset};
-use ui::{Avatar, CopyButton, Divider, prelude::*, tooltip_container};
+use ui::{Avatar, CopyButton, Divider, Tooltip, prelude::*, tooltip_container};
 use workspace::Workspace;
 
 #[derive(Clone, Debug)]
@@ -82,6 +82,33 @@ impl<'a> CommitAvatar<'a> {
     }
 }
 
+/// Renders whether a commit is signed and whether its signature could be
+/// verified.
+pub fn render_signature_badge(signature: &CommitSignature) -> AnyElement {
+    let (icon, color, label) = match signature.status {
+        SignatureStatus::Verified => (IconName::ShieldCheck, Color::Success, "Verified"),
+        SignatureStatus::Unverified => (IconName::Warning, Color::Warning, "Unverified"),
+        SignatureStatus::Unsigned => (IconName::Dash, Color::Muted, "Unsigned"),
+    };
+    let description: SharedString = match signature.status {
+        SignatureStatus::Verified => format!("Signed by {}", signature.signer).into(),
+        SignatureStatus::Unverified => "The signature could not be verified".into(),
+        SignatureStatus::Unsigned => "This commit is not signed".into(),
+    };
+    let key = (!signature.key.is_empty()).then(|| format!("Key {}", signature.key));
+
+    h_flex()
+        .id("commit-signature")
+        .gap_0p5()
+        .child(Icon::new(icon).size(IconSize::XSmall).color(color))
+        .child(Label::new(label).size(LabelSize::Small).color(color))
+        .tooltip(move |_window, cx| match &key {
+            Some(key) => Tooltip::with_meta(description.clone(), None, key.clone(), cx),
+            None => Tooltip::simple(description.clone(), cx),
+        })
+        .into_any_element()
+}
+
 #[derive(Clone, Debug)]
 struct CommitAvatarAsset {
     sha: SharedString,
@@ -123,10 +150,12 @@ impl Asset for CommitAvatarAsset {
 
 pub struct CommitTooltip {
     commit: CommitDetails,
+    signature: Option<CommitSignature>,
     scroll_handle: ScrollHandle,
     markdown: Entity<Markdown>,
     repository: Entity<Repository>,
     workspace: WeakEntity<Workspace>,
+    _load_signature: Task<()>,
 }
 
 impl CommitTooltip {
@@ -178,12 +207,26 @@ impl CommitTooltip {
                 cx,
             )
         });
+        let signature = repository.update(cx, |repository, _| {
+            repository.commit_signature(commit.sha.to_string())
+        });
+        let load_signature = cx.spawn(async move |this, cx| {
+            if let Ok(Ok(signature)) = signature.await {
+                this.update(cx, |this, cx| {
+                    this.signature = Some(signature);
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
         Self {
             commit,
+            signature: None,
             repository,
             workspace,
             scroll_handle: ScrollHandle::new(),
             markdown,
+            _load_signature: load_signature,
         }
     }
 }
@@ -228,6 +271,7 @@ impl Render for CommitTooltip {
             .as_ref()
             .and_then(|details| details.pull_request.clone());
 
+        let signature_badge = self.signature.as_ref().map(render_signature_badge);
         let ui_font_size = ThemeSettings::get_global(cx).ui_font_size(cx);
         let message_max_height = window.line_height() * 12 + (ui_font_size / 0.4);
         let repo = self.repository.clone();
@@ -276,6 +320,7 @@ impl Render for CommitTooltip {
                                             .child(author_email),
                                     )
                                 })
+                                .children(signature_badge)
                                 .border_b_1()
                                 .border_color(cx.theme().colors().border_variant),
                         )
@@ -3,7 +3,7 @@ use buffer_diff::BufferDiff;
 use collections::HashMap;
 use editor::display_map::{BlockPlacement, BlockProperties, BlockStyle};
 use editor::{Addon, Editor, EditorEvent, ExcerptRange, MultiBuffer, multibuffer_context_lines};
-use git::repository::{CommitDetails, CommitDiff, RepoPath, is_binary_content};
+use git::repository::{CommitDetails, CommitDiff, CommitSignature, RepoPath, is_binary_content};
 use git::status::{FileStatus, StatusCode, TrackedStatus};
 use git::{
     BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, ParsedGitRemote,
@@ -39,7 +39,7 @@ use workspace::{
     searchable::SearchableItemHandle,
 };
 
-use crate::commit_tooltip::CommitAvatar;
+use crate::commit_tooltip::{CommitAvatar, render_signature_badge};
 use crate::git_panel::GitPanel;
 
 actions!(git, [ApplyCurrentStash, PopCurrentStash, DropCurrentStash,]);
@@ -66,14 +66,16 @@ pub struct CommitView {
     multibuffer: Entity<MultiBuffer>,
     repository: Entity<Repository>,
     remote: Option<GitRemote>,
+    signature: Option<CommitSignature>,
+    _load_signature: Task<()>,
 }
 
-struct GitBlob {
-    path: RepoPath,
-    worktree_id: WorktreeId,
-    is_deleted: bool,
-    is_binary: bool,
-    display_name: String,
+pub(crate) struct GitBlob {
+    pub(crate) path: RepoPath,
+    pub(crate) worktree_id: WorktreeId,
+    pub(crate) is_deleted: bool,
+    pub(crate) is_binary: bool,
+    pub(crate) display_name: String,
 }
 
 struct CommitDiffAddon {
@@ -388,6 +390,23 @@ impl CommitView {
             })
         });
 
+        let signature = repository.update(cx, |repository, _| {
+            repository.commit_signature(commit.sha.to_string())
+        });
+        let load_signature = cx.spawn(async move |this, cx| {
+            if let Some(signature) = signature
+                .await
+                .log_err()
+                .and_then(|result| result.log_err())
+            {
+                this.update(cx, |this, cx| {
+                    this.signature = Some(signature);
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
+
         Self {
             commit,
             editor,
@@ -395,6 +414,8 @@ impl CommitView {
             stash,
             repository,
             remote,
+            signature: None,
+            _load_signature: load_signature,
         }
     }
 
@@ -608,7 +629,8 @@ impl CommitView {
                                             .color(Color::Ignored)
                                             .size(LabelSize::Small),
                                     )
-                                    .children(commit_diff_stat),
+                                    .children(commit_diff_stat)
+                                    .children(self.signature.as_ref().map(render_signature_badge)),
                             ),
                     )
                     .children(remote_info.map(|(provider_name, url)| {
@@ -831,7 +853,7 @@ impl language::File for GitBlob {
     }
 }
 
-async fn build_buffer(
+pub(crate) async fn build_buffer(
     mut text: String,
     blob: Arc<dyn File>,
     language_registry: &Arc<language::LanguageRegistry>,
@@ -864,7 +886,7 @@ async fn build_buffer(
     Ok(buffer)
 }
 
-async fn build_buffer_diff(
+pub(crate) async fn build_buffer_diff(
     mut old_text: Option<String>,
     buffer: &Entity<Buffer>,
     language_registry: &Arc<LanguageRegistry>,
@@ -7,7 +7,7 @@ use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::{branch_picker, picker_prompt, render_remote_button};
 use crate::{
     file_history_view::FileHistoryView, git_panel_settings::GitPanelSettings, git_status_icon,
-    repository_selector::RepositorySelector,
+    merge_editor::MergeEditor, repository_selector::RepositorySelector,
 };
 use agent_settings::AgentSettings;
 use anyhow::Context as _;
@@ -23,12 +23,13 @@ use editor::{
 use futures::StreamExt as _;
 use git::commit::ParsedCommitMessage;
 use git::repository::{
-    Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, FetchOptions, GitCommitter,
-    PushOptions, Remote, RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking,
+    Branch, CommitDetails, CommitOptions, CommitSigning, CommitSummary, DiffType, FetchOptions,
+    GitCommitter, PushOptions, Remote, RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking,
     UpstreamTrackingStatus, get_git_committer,
 };
 use git::stash::GitStash;
 use git::status::StageStatus;
+use git::submodule::Submodule;
 use git::{Amend, Signoff, ToggleStaged, repository::RepoPath, status::FileStatus};
 use git::{
     ExpandCommitEditor, GitHostingProviderRegistry, RestoreTrackedFiles, StageAll, StashAll,
@@ -59,7 +60,7 @@ use project::{
 };
 use prompt_store::{BuiltInPrompt, PromptId, PromptStore, RULES_FILE_NAMES};
 use serde::{Deserialize, Serialize};
-use settings::{Settings, SettingsStore, StatusStyle};
+use settings::{Settings, SettingsLocation, SettingsStore, StatusStyle};
 use std::future::Future;
 use std::ops::Range;
 use std::path::Path;
@@ -1243,6 +1244,28 @@ impl GitPanel {
         });
     }
 
+    fn open_merge_editor(
+        &mut self,
+        _: &git::OpenMergeEditor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        maybe!({
+            let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
+            let active_repo = self.active_repository.clone()?;
+
+            MergeEditor::open(
+                entry.repo_path.clone(),
+                active_repo,
+                self.workspace.clone(),
+                window,
+                cx,
+            );
+
+            Some(())
+        });
+    }
+
     fn open_file(
         &mut self,
         _: &menu::SecondaryConfirm,
@@ -2070,6 +2093,7 @@ impl GitPanel {
                 CommitOptions {
                     amend: false,
                     signoff: self.signoff_enabled,
+                    ..Default::default()
                 },
                 window,
                 cx,
@@ -2110,6 +2134,7 @@ impl GitPanel {
                         CommitOptions {
                             amend: true,
                             signoff: self.signoff_enabled,
+                            ..Default::default()
                         },
                         window,
                         cx,
@@ -2205,6 +2230,23 @@ impl GitPanel {
         }
     }
 
+    /// Returns how commits to the given repository should be signed, based on
+    /// the settings of the worktree containing it.
+    fn commit_signing(&self, repository: &Entity<Repository>, cx: &App) -> CommitSigning {
+        let work_directory = repository.read(cx).work_directory_abs_path.clone();
+        let worktree_and_path = self.project.read(cx).find_worktree(&work_directory, cx);
+        let location = worktree_and_path
+            .as_ref()
+            .map(|(worktree, path)| SettingsLocation {
+                worktree_id: worktree.read(cx).id(),
+                path,
+            });
+        ProjectSettings::get(location, cx)
+            .git
+            .commit_signing
+            .commit_signing()
+    }
+
     pub(crate) fn commit_changes(
         &mut self,
         options: CommitOptions,
@@ -2231,6 +2273,10 @@ impl GitPanel {
             return;
         }
 
+        let options = CommitOptions {
+            signing: self.commit_signing(&active_repository, cx),
+            ..options
+        };
         let askpass = self.askpass_delegate("git commit", window, cx);
         let commit_message = self.custom_or_suggested_commit_message(window, cx);
 
@@ -2820,6 +2866,90 @@ impl GitPanel {
             .detach_and_log_err(cx);
     }
 
+    pub(crate) fn update_submodules(
+        &mut self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Submodules Updated", init);
+        let askpass = self.askpass_delegate("git submodule update", window, cx);
+        let this = cx.weak_entity();
+
+        window
+            .spawn(cx, async move |cx| {
+                let update = repo.update(cx, |repo, cx| {
+                    repo.update_submodules(paths, init, askpass, cx)
+                });
+
+                let remote_message = update.await?;
+                this.update(cx, |this, cx| {
+                    let action = RemoteAction::UpdateSubmodules;
+                    match remote_message {
+                        Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                        Err(e) => {
+                            log::error!("Error while updating submodules {:?}", e);
+                            this.show_error_toast(action.name(), e, cx)
+                        }
+                    }
+                })
+                .ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn selected_submodule(&self, cx: &App) -> Option<Submodule> {
+        let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
+        self.active_repository
+            .as_ref()?
+            .read(cx)
+            .submodule_for_path(&entry.repo_path)
+            .cloned()
+    }
+
+    fn open_submodule_diff(
+        &mut self,
+        _: &git::OpenSubmoduleDiff,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        maybe!({
+            let submodule = self.selected_submodule(cx)?;
+            let active_repo = self.active_repository.as_ref()?;
+            let submodule_repo = self
+                .project
+                .read(cx)
+                .git_store()
+                .read(cx)
+                .repository_for_submodule(active_repo, &submodule.path, cx);
+            let Some(submodule_repo) = submodule_repo else {
+                self.show_error_toast(
+                    "open submodule diff",
+                    anyhow::anyhow!(
+                        "Submodule {} is not checked out",
+                        submodule.path.display(PathStyle::local())
+                    ),
+                    cx,
+                );
+                return None;
+            };
+
+            submodule_repo.update(cx, |repo, cx| repo.set_as_active_repository(cx));
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    ProjectDiff::deploy_at(workspace, None, window, cx);
+                })
+                .ok();
+
+            Some(())
+        });
+    }
+
     pub(crate) fn git_clone(&mut self, repo: String, window: &mut Window, cx: &mut Context<Self>) {
         let workspace = self.workspace.clone();
 
@@ -4350,7 +4480,11 @@ impl GitPanel {
                         git_panel
                             .update(cx, |git_panel, cx| {
                                 git_panel.commit_changes(
-                                    CommitOptions { amend, signoff },
+                                    CommitOptions {
+                                        amend,
+                                        signoff,
+                                        ..Default::default()
+                                    },
                                     window,
                                     cx,
                                 );
@@ -4813,8 +4947,14 @@ impl GitPanel {
         } else {
             "Discard Changes"
         };
+        let submodule = self
+            .active_repository
+            .as_ref()
+            .and_then(|repo| repo.read(cx).submodule_for_path(&entry.repo_path).cloned());
+        let this = cx.weak_entity();
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
             let is_created = entry.status.is_created();
+            let is_conflicted = entry.status.is_conflicted();
             context_menu
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
@@ -4827,8 +4967,41 @@ impl GitPanel {
                 .separator()
                 .action("Open Diff", menu::Confirm.boxed_clone())
                 .action("Open File", menu::SecondaryConfirm.boxed_clone())
+                .when(is_conflicted, |context_menu| {
+                    context_menu.action("Open Merge Editor", git::OpenMergeEditor.boxed_clone())
+                })
                 .separator()
                 .action_disabled_when(is_created, "View File History", Box::new(git::FileHistory))
+                .when_some(submodule, |context_menu, submodule| {
+                    let is_initialized = submodule.is_initialized();
+                    let path = submodule.path;
+                    context_menu
+                        .separator()
+                        .entry(
+                            if is_initialized {
+                                "Update Submodule"
+                            } else {
+                                "Init Submodule"
+                            },
+                            None,
+                            move |window, cx| {
+                                this.update(cx, |this, cx| {
+                                    this.update_submodules(
+                                        vec![path.clone()],
+                                        !is_initialized,
+                                        window,
+                                        cx,
+                                    );
+                                })
+                                .ok();
+                            },
+                        )
+                        .action_disabled_when(
+                            !is_initialized,
+                            "Open Submodule Diff",
+                            git::OpenSubmoduleDiff.boxed_clone(),
+                        )
+                })
         });
         self.selected_entry = Some(ix);
         self.set_context_menu(context_menu, position, window, cx);
@@ -4972,6 +5145,14 @@ impl GitPanel {
             )
         };
 
+        let submodule_pointer_change = repo
+            .submodule_for_path(&entry.repo_path)
+            .and_then(|submodule| submodule.pointer_change())
+            .map(|(old_sha, new_sha)| {
+                let old_sha = old_sha.map_or_else(|| "none".to_string(), |sha| sha.display_short());
+                SharedString::from(format!("{old_sha} → {}", new_sha.display_short()))
+            });
+
         let name_row = h_flex()
             .min_w_0()
             .flex_1()
@@ -4995,6 +5176,14 @@ impl GitPanel {
                         status.is_deleted(),
                     ))
                 }
+            })
+            .when_some(submodule_pointer_change, |this, pointer_change| {
+                this.child(
+                    Label::new(pointer_change)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .buffer_font(cx),
+                )
             });
 
         h_flex()
@@ -5458,6 +5647,8 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::open_diff))
             .on_action(cx.listener(Self::open_file))
             .on_action(cx.listener(Self::file_history))
+            .on_action(cx.listener(Self::open_merge_editor))
+            .on_action(cx.listener(Self::open_submodule_diff))
             .on_action(cx.listener(Self::focus_changes_list))
             .on_action(cx.listener(Self::focus_editor))
             .on_action(cx.listener(Self::expand_commit_editor))
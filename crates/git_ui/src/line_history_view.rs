@@ -0,0 +1,766 @@
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use editor::{Editor, EditorEvent, MultiBuffer};
+use git::line_history::{LineHistory, LineHistoryEntry};
+use git::repository::RepoPath;
+use gpui::{
+    AnyElement, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, Render, Task, WeakEntity, Window, actions,
+};
+use language::{BufferId, Capability, LanguageRegistry, Point};
+use multi_buffer::PathKey;
+use project::{
+    Project, ProjectPath, WorktreeId,
+    git_store::{GitStore, Repository},
+};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    sync::Arc,
+};
+use theme::ActiveTheme;
+use ui::{Divider, Tooltip, prelude::*};
+use util::{ResultExt, paths::PathStyle, truncate_and_trailoff};
+use workspace::{
+    Item, ItemNavHistory, Workspace,
+    item::{ItemEvent, TabContentParams},
+    notifications::NotifyTaskExt,
+    searchable::SearchableItemHandle,
+};
+
+use crate::commit_view::{CommitView, GitBlob, build_buffer, build_buffer_diff};
+
+actions!(
+    git,
+    [
+        /// Shows the history of the lines under the cursor as they were before
+        /// the revision that last changed them.
+        BlameParent,
+        /// Returns to the line history shown before the last `git: blame parent`.
+        UndoBlameParent,
+        /// Opens the commit for the revision under the cursor.
+        OpenLineHistoryCommit,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+        workspace.register_action(|workspace, _: &git::LineHistory, window, cx| {
+            LineHistoryView::deploy(workspace, window, cx);
+        });
+    })
+    .detach();
+}
+
+pub struct LineHistoryView {
+    history: LineHistory,
+    /// Histories this view was showing before each [`BlameParent`], most
+    /// recent last.
+    previous_histories: Vec<LineHistory>,
+    /// Maps each revision's buffer to its index in `history.entries`.
+    entry_ix_by_buffer: HashMap<BufferId, usize>,
+    editor: Entity<Editor>,
+    multibuffer: Entity<MultiBuffer>,
+    repository: Entity<Repository>,
+    git_store: WeakEntity<GitStore>,
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    loading: bool,
+    _load_task: Task<()>,
+}
+
+impl LineHistoryView {
+    /// Opens the line history for the selected lines of the active editor.
+    fn deploy(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some((buffer, rows)) = editor.update(cx, |editor, cx| selected_rows(editor, cx)) else {
+            return;
+        };
+        let Some(file) = buffer.read(cx).file() else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path().clone(),
+        };
+        let git_store = workspace.project().read(cx).git_store().clone();
+        let Some((repo, repo_path)) = git_store
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        Self::open(
+            repo_path,
+            rows,
+            git_store.downgrade(),
+            repo.downgrade(),
+            workspace.weak_handle(),
+            window,
+            cx,
+        );
+    }
+
+    pub fn open(
+        path: RepoPath,
+        rows: Range<u32>,
+        git_store: WeakEntity<GitStore>,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let line_history_task = git_store.update(cx, |git_store, cx| {
+            repo.upgrade()
+                .map(|repo| git_store.line_history(&repo, path.clone(), rows.clone(), None, cx))
+        });
+
+        window
+            .spawn(cx, async move |cx| {
+                let line_history = line_history_task?
+                    .context("repository was dropped")?
+                    .await?;
+                let repo = repo.upgrade().context("repository was dropped")?;
+
+                workspace.update_in(cx, |workspace, window, cx| {
+                    let weak_workspace = workspace.weak_handle();
+                    let project = workspace.project().clone();
+                    let pane = workspace.active_pane();
+                    pane.update(cx, |pane, cx| {
+                        let ix = pane.items().position(|item| {
+                            item.downcast::<LineHistoryView>().is_some_and(|view| {
+                                let history = &view.read(cx).history;
+                                history.path == path && history.rows == rows
+                            })
+                        });
+                        if let Some(ix) = ix {
+                            pane.activate_item(ix, true, true, window, cx);
+                        } else {
+                            let view = cx.new(|cx| {
+                                LineHistoryView::new(
+                                    line_history,
+                                    git_store,
+                                    repo,
+                                    weak_workspace,
+                                    project,
+                                    window,
+                                    cx,
+                                )
+                            });
+                            pane.add_item(Box::new(view), true, true, None, window, cx);
+                        }
+                    })
+                })
+            })
+            .detach_and_notify_err(window, cx);
+    }
+
+    fn new(
+        history: LineHistory,
+        git_store: WeakEntity<GitStore>,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadOnly));
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            editor.disable_inline_diagnostics();
+            editor.set_show_breakpoints(false, cx);
+            editor.set_expand_all_diff_hunks(cx);
+            editor
+        });
+
+        let mut this = Self {
+            history: history.clone(),
+            previous_histories: Vec::new(),
+            entry_ix_by_buffer: HashMap::default(),
+            editor,
+            multibuffer,
+            repository,
+            git_store,
+            workspace,
+            project,
+            loading: false,
+            _load_task: Task::ready(()),
+        };
+        this.set_history(history, cx);
+        this
+    }
+
+    fn set_history(&mut self, history: LineHistory, cx: &mut Context<Self>) {
+        self.history = history.clone();
+        self.entry_ix_by_buffer.clear();
+        self.multibuffer
+            .update(cx, |multibuffer, cx| multibuffer.clear(cx));
+
+        let language_registry = self.project.read(cx).languages().clone();
+        let repository = self.repository.clone();
+        let first_worktree_id = self
+            .project
+            .read(cx)
+            .worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).id());
+
+        self._load_task = cx.spawn(async move |this, cx| {
+            let result = Self::load_entries(
+                this.clone(),
+                history.entries,
+                repository,
+                language_registry,
+                first_worktree_id,
+                cx,
+            )
+            .await;
+            this.update(cx, |this, cx| {
+                this.loading = false;
+                cx.notify();
+            })
+            .ok();
+            result.log_err();
+        });
+        self.loading = true;
+        cx.notify();
+    }
+
+    async fn load_entries(
+        this: WeakEntity<Self>,
+        entries: Vec<LineHistoryEntry>,
+        repository: Entity<Repository>,
+        language_registry: Arc<LanguageRegistry>,
+        first_worktree_id: Option<WorktreeId>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        for (ix, entry) in entries.into_iter().enumerate() {
+            let worktree_id = repository
+                .update(cx, |repository, cx| {
+                    repository
+                        .repo_path_to_project_path(&entry.path, cx)
+                        .map(|path| path.worktree_id)
+                        .or(first_worktree_id)
+                })
+                .context("project has no worktrees")?;
+            let file = Arc::new(GitBlob {
+                path: entry.path.clone(),
+                worktree_id,
+                is_deleted: false,
+                is_binary: false,
+                display_name: revision_display_name(&entry),
+            }) as Arc<dyn language::File>;
+
+            // Lines introduced by this revision have no previous version to diff against.
+            let old_text = entry.old_path.is_some().then_some(entry.old_text);
+            let buffer = build_buffer(entry.text, file, &language_registry, cx).await?;
+            let buffer_diff = build_buffer_diff(old_text, &buffer, &language_registry, cx).await?;
+
+            this.update(cx, |this, cx| {
+                this.entry_ix_by_buffer
+                    .insert(buffer.read(cx).remote_id(), ix);
+                this.multibuffer.update(cx, |multibuffer, cx| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    let path = snapshot.file().unwrap().path().clone();
+                    multibuffer.set_excerpts_for_path(
+                        PathKey::with_sort_prefix(ix as u64, path),
+                        buffer,
+                        [Point::zero()..snapshot.max_point()],
+                        0,
+                        cx,
+                    );
+                    multibuffer.add_diff(buffer_diff, cx);
+                });
+            })?;
+        }
+        Ok(())
+    }
+
+    fn entry_under_cursor(&self, cx: &App) -> Option<&LineHistoryEntry> {
+        let (_, buffer, _) = self.editor.read(cx).active_excerpt(cx)?;
+        let ix = self.entry_ix_by_buffer.get(&buffer.read(cx).remote_id())?;
+        self.history.entries.get(*ix)
+    }
+
+    fn blame_parent(&mut self, _: &BlameParent, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entry_under_cursor(cx) else {
+            return;
+        };
+        let (Some(old_path), Some(old_rows)) = (entry.old_path.clone(), entry.old_row_range())
+        else {
+            return;
+        };
+        let revision = format!("{}^", entry.sha);
+        let task = self.git_store.update(cx, |git_store, cx| {
+            git_store.line_history(&self.repository, old_path, old_rows, Some(revision), cx)
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let history = task?.await?;
+            this.update(cx, |this, cx| {
+                let previous = std::mem::replace(&mut this.history, history.clone());
+                this.previous_histories.push(previous);
+                this.set_history(history, cx);
+            })
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
+    fn undo_blame_parent(
+        &mut self,
+        _: &UndoBlameParent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(history) = self.previous_histories.pop() {
+            self.set_history(history, cx);
+        }
+    }
+
+    fn open_commit(
+        &mut self,
+        _: &OpenLineHistoryCommit,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entry_under_cursor(cx) else {
+            return;
+        };
+        CommitView::open(
+            entry.sha.to_string(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            None,
+            Some(entry.path.clone()),
+            window,
+            cx,
+        );
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let rows = &self.history.rows;
+        let title = format!(
+            "{}:{}-{}",
+            self.history.path.display(PathStyle::local()),
+            rows.start + 1,
+            rows.end
+        );
+        let revision = self
+            .history
+            .revision
+            .as_ref()
+            .map(|revision| format!("from {revision}"));
+        let entry_count = self.history.entries.len();
+        let can_blame_parent = self
+            .entry_under_cursor(cx)
+            .is_some_and(|entry| entry.old_row_range().is_some());
+
+        h_flex()
+            .h(rems_from_px(41.))
+            .pl_3()
+            .pr_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .child(Label::new(title).color(Color::Muted).buffer_font(cx))
+                    .when_some(revision, |this, revision| {
+                        this.child(
+                            Label::new(revision)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .child(
+                        Label::new(if self.loading {
+                            "Loading…".to_string()
+                        } else {
+                            format!("{entry_count} revisions")
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .mr_1(),
+                    )
+                    .child(Divider::vertical())
+                    .when(!self.previous_histories.is_empty(), |this| {
+                        this.child(
+                            Button::new("undo-blame-parent", "Back")
+                                .label_size(LabelSize::Small)
+                                .icon(IconName::ArrowLeft)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted)
+                                .icon_position(IconPosition::Start)
+                                .tooltip(Tooltip::for_action_title(
+                                    "Return to Previous History",
+                                    &UndoBlameParent,
+                                ))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.undo_blame_parent(&UndoBlameParent, window, cx);
+                                })),
+                        )
+                    })
+                    .child(
+                        Button::new("blame-parent", "Blame Parent")
+                            .disabled(!can_blame_parent)
+                            .label_size(LabelSize::Small)
+                            .icon(IconName::ArrowUp)
+                            .icon_size(IconSize::Small)
+                            .icon_color(Color::Muted)
+                            .icon_position(IconPosition::Start)
+                            .tooltip(Tooltip::for_action_title(
+                                "Show History Before This Revision",
+                                &BlameParent,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.blame_parent(&BlameParent, window, cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+/// Returns the buffer of the editor's newest selection and the rows it spans
+/// in the committed version of the file.
+fn selected_rows(
+    editor: &mut Editor,
+    cx: &mut Context<Editor>,
+) -> Option<(Entity<language::Buffer>, Range<u32>)> {
+    let selection = editor
+        .selections
+        .newest::<Point>(&editor.display_snapshot(cx));
+    let multi_buffer = editor.buffer().read(cx);
+    let buffer = multi_buffer.as_singleton()?;
+    let buffer_snapshot = buffer.read(cx).snapshot();
+
+    let start_row = selection.start.row;
+    let mut end_row = selection.end.row;
+    if selection.end.column == 0 && end_row > start_row {
+        end_row -= 1;
+    }
+
+    let (start_row, end_row) = match multi_buffer.diff_for(buffer_snapshot.remote_id()) {
+        Some(buffer_diff) => {
+            let diff_snapshot = buffer_diff.read(cx).snapshot(cx);
+            let (mut translated, _, _) = diff_snapshot.points_to_base_text_points(
+                [Point::new(start_row, 0), Point::new(end_row, 0)],
+                &buffer_snapshot,
+            );
+            (translated.next()?.start.row, translated.next()?.end.row)
+        }
+        None => (start_row, end_row),
+    };
+
+    Some((buffer, start_row..end_row + 1))
+}
+
+fn revision_display_name(entry: &LineHistoryEntry) -> String {
+    let short_sha = entry.sha.get(0..7).unwrap_or(&*entry.sha);
+    let subject = truncate_and_trailoff(&entry.subject, 50);
+    format!("{short_sha} - {subject}")
+}
+
+impl EventEmitter<EditorEvent> for LineHistoryView {}
+
+impl Focusable for LineHistoryView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for LineHistoryView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let file_name = self.history.path.file_name().unwrap_or("File");
+        let rows = &self.history.rows;
+        format!("History: {file_name}:{}-{}", rows.start + 1, rows.end).into()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(format!("Line history for {}", self.history.path.as_unix_str()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Line History Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for LineHistoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("LineHistory")
+            .on_action(cx.listener(Self::blame_parent))
+            .on_action(cx.listener(Self::undo_blame_parent))
+            .on_action(cx.listener(Self::open_commit))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .when(!self.editor.read(cx).is_empty(cx), |this| {
+                this.child(div().flex_grow().child(self.editor.clone()))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn repo_path(path: &str) -> RepoPath {
+        RepoPath::new(path).unwrap()
+    }
+
+    fn entry(
+        sha: &str,
+        path: &str,
+        old_path: Option<&str>,
+        text: &str,
+        old_text: &str,
+    ) -> LineHistoryEntry {
+        LineHistoryEntry {
+            sha: sha.to_string().into(),
+            parent_sha: None,
+            subject: format!("Commit {sha}").into(),
+            commit_timestamp: 0,
+            author_name: "Ada".into(),
+            author_email: "ada@example.com".into(),
+            path: repo_path(path),
+            old_path: old_path.map(repo_path),
+            start_row: 1,
+            old_start_row: 1,
+            text: text.into(),
+            old_text: old_text.into(),
+        }
+    }
+
+    fn buffer_texts(
+        view: &Entity<LineHistoryView>,
+        cx: &mut gpui::VisualTestContext,
+    ) -> Vec<String> {
+        view.read_with(cx, |view, cx| {
+            let mut texts = view
+                .multibuffer
+                .read(cx)
+                .all_buffers()
+                .into_iter()
+                .map(|buffer| buffer.read(cx).text())
+                .collect::<Vec<_>>();
+            texts.sort();
+            texts
+        })
+    }
+
+    #[gpui::test]
+    async fn test_line_history_and_blame_parent(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "new.rs": "fn a() {}\nfn b() { two() }\n",
+            }),
+        )
+        .await;
+        let renamed = entry(
+            "2222",
+            "new.rs",
+            Some("old.rs"),
+            "fn b() { two() }\n",
+            "fn b() { one() }\n",
+        );
+        let added = entry("1111", "old.rs", None, "fn b() { one() }\n", "");
+        fs.set_line_history_for_repo(
+            path!("/project/.git").as_ref(),
+            repo_path("new.rs"),
+            vec![renamed.clone(), added.clone()],
+        );
+        fs.set_line_history_for_repo(
+            path!("/project/.git").as_ref(),
+            repo_path("old.rs"),
+            vec![renamed, added.clone()],
+        );
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.run_until_parked();
+
+        let open = |cx: &mut gpui::VisualTestContext| {
+            workspace.update_in(cx, |workspace, window, cx| {
+                let repo = project.read(cx).active_repository(cx).unwrap();
+                let git_store = project.read(cx).git_store().downgrade();
+                LineHistoryView::open(
+                    repo_path("new.rs"),
+                    1..2,
+                    git_store,
+                    repo.downgrade(),
+                    workspace.weak_handle(),
+                    window,
+                    cx,
+                );
+            });
+            cx.run_until_parked();
+        };
+        open(cx);
+        let view = workspace
+            .read_with(cx, |workspace, cx| {
+                workspace.active_item_as::<LineHistoryView>(cx)
+            })
+            .unwrap();
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.history.entries.len(), 2);
+            assert!(!view.loading);
+        });
+        assert_eq!(
+            buffer_texts(&view, cx),
+            ["fn b() { one() }\n", "fn b() { two() }\n"]
+        );
+
+        // Opening the same lines again shows the existing view.
+        open(cx);
+        workspace.read_with(cx, |workspace, cx| {
+            assert_eq!(workspace.items_of_type::<LineHistoryView>(cx).count(), 1);
+        });
+
+        // The cursor is in the rename, so blaming its parent follows the lines to the old path.
+        view.update_in(cx, |view, window, cx| {
+            view.blame_parent(&BlameParent, window, cx)
+        });
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.history.path, repo_path("old.rs"));
+            assert_eq!(view.history.rows, 1..2);
+            assert_eq!(view.history.revision.as_deref(), Some("2222^"));
+            assert_eq!(view.history.entries, [added]);
+            assert_eq!(view.previous_histories.len(), 1);
+        });
+        assert_eq!(buffer_texts(&view, cx), ["fn b() { one() }\n"]);
+
+        // The commit that added the lines has no parent to blame.
+        view.update_in(cx, |view, window, cx| {
+            view.blame_parent(&BlameParent, window, cx)
+        });
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.previous_histories.len(), 1);
+        });
+
+        view.update_in(cx, |view, window, cx| {
+            view.undo_blame_parent(&UndoBlameParent, window, cx)
+        });
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.history.path, repo_path("new.rs"));
+            assert_eq!(view.history.entries.len(), 2);
+            assert!(view.previous_histories.is_empty());
+        });
+        assert_eq!(buffer_texts(&view, cx).len(), 2);
+    }
+}
@@ -0,0 +1,1062 @@
+use anyhow::{Context as _, Result};
+use buffer_diff::BufferDiff;
+use editor::{Editor, EditorEvent, MultiBuffer, SelectionEffects, scroll::Autoscroll};
+use git::repository::{MergeStages, RepoPath};
+use gpui::{
+    AnyElement, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, PromptLevel, Render, Subscription, Task, WeakEntity, Window, actions,
+};
+use language::{Buffer, LanguageRegistry};
+use project::{
+    ConflictRegion, ConflictSet, Project, ProjectPath, WorktreeId, git_store::Repository,
+};
+use std::{
+    any::{Any, TypeId},
+    cmp::Ordering,
+    sync::Arc,
+};
+use theme::ActiveTheme;
+use ui::{Divider, Tooltip, prelude::*};
+use util::paths::PathStyle;
+use workspace::{
+    Item, ItemNavHistory, Workspace,
+    item::{ItemEvent, TabContentParams},
+    notifications::NotifyTaskExt,
+    pane::SaveIntent,
+    searchable::SearchableItemHandle,
+};
+
+use crate::commit_view::{GitBlob, build_buffer, build_buffer_diff};
+
+actions!(
+    git,
+    [
+        /// Resolves the conflict under the cursor using our version.
+        AcceptOurs,
+        /// Resolves the conflict under the cursor using their version.
+        AcceptTheirs,
+        /// Resolves the conflict under the cursor by keeping our version
+        /// followed by theirs.
+        AcceptBoth,
+        /// Resolves the conflict under the cursor using the common ancestor's
+        /// version, when the conflict markers include it.
+        AcceptBase,
+        /// Moves the cursor to the next conflict in the merge result.
+        NextConflict,
+        /// Moves the cursor to the previous conflict in the merge result.
+        PreviousConflict,
+        /// Saves the merge result and stages it, marking the conflict as resolved.
+        MarkResolved,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+        workspace.register_action(|workspace, _: &git::OpenMergeEditor, window, cx| {
+            MergeEditor::deploy(workspace, window, cx);
+        });
+    })
+    .detach();
+}
+
+/// A three-way merge view for a conflicted file: the common ancestor, both
+/// sides of the merge, and the editable working copy holding the result.
+pub struct MergeEditor {
+    path: RepoPath,
+    repository: Entity<Repository>,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    buffer: Entity<Buffer>,
+    conflict_set: Entity<ConflictSet>,
+    base_editor: Entity<Editor>,
+    ours_editor: Entity<Editor>,
+    theirs_editor: Entity<Editor>,
+    result_editor: Entity<Editor>,
+    _conflict_set_subscription: Subscription,
+}
+
+/// The buffers backing the read-only panes of a [`MergeEditor`].
+struct StageBuffers {
+    base: Entity<Buffer>,
+    ours: Entity<Buffer>,
+    ours_diff: Entity<BufferDiff>,
+    theirs: Entity<Buffer>,
+    theirs_diff: Entity<BufferDiff>,
+}
+
+impl MergeEditor {
+    /// Opens the merge editor for the file in the active editor.
+    fn deploy(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let Some(file) = buffer.read(cx).file() else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path().clone(),
+        };
+        let Some((repo, repo_path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        Self::open(repo_path, repo, workspace.weak_handle(), window, cx);
+    }
+
+    pub fn open(
+        path: RepoPath,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(project) = workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().clone())
+        else {
+            return;
+        };
+        let Some(project_path) = repository.read(cx).repo_path_to_project_path(&path, cx) else {
+            return;
+        };
+        let worktree_id = project_path.worktree_id;
+        let language_registry = project.read(cx).languages().clone();
+        let stages = repository.update(cx, |repository, _| {
+            repository.load_merge_stages(path.clone())
+        });
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+
+        window
+            .spawn(cx, async move |cx| {
+                let stages = stages.await??;
+                let buffer = open_buffer.await?;
+                let stage_buffers =
+                    load_stage_buffers(stages, &path, worktree_id, &language_registry, cx).await?;
+
+                workspace.update_in(cx, |workspace, window, cx| {
+                    let weak_workspace = workspace.weak_handle();
+                    let pane = workspace.active_pane();
+                    pane.update(cx, |pane, cx| {
+                        let ix = pane.items().position(|item| {
+                            item.downcast::<MergeEditor>()
+                                .is_some_and(|view| view.read(cx).buffer == buffer)
+                        });
+                        if let Some(ix) = ix {
+                            pane.activate_item(ix, true, true, window, cx);
+                        } else {
+                            let view = cx.new(|cx| {
+                                MergeEditor::new(
+                                    path,
+                                    repository,
+                                    project,
+                                    weak_workspace,
+                                    buffer,
+                                    stage_buffers,
+                                    window,
+                                    cx,
+                                )
+                            });
+                            pane.add_item(Box::new(view), true, true, None, window, cx);
+                        }
+                    })
+                })
+            })
+            .detach_and_notify_err(window, cx);
+    }
+
+    fn new(
+        path: RepoPath,
+        repository: Entity<Repository>,
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        buffer: Entity<Buffer>,
+        stage_buffers: StageBuffers,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let base_editor = stage_editor(stage_buffers.base, None, &project, window, cx);
+        let ours_editor = stage_editor(
+            stage_buffers.ours,
+            Some(stage_buffers.ours_diff),
+            &project,
+            window,
+            cx,
+        );
+        let theirs_editor = stage_editor(
+            stage_buffers.theirs,
+            Some(stage_buffers.theirs_diff),
+            &project,
+            window,
+            cx,
+        );
+        let result_editor =
+            cx.new(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx));
+
+        let git_store = project.read(cx).git_store().clone();
+        let conflict_set = git_store.update(cx, |git_store, cx| {
+            git_store.open_conflict_set(buffer.clone(), cx)
+        });
+        let conflict_set_subscription = cx.subscribe(&conflict_set, |_, _, _, cx| cx.notify());
+
+        Self {
+            path,
+            repository,
+            project,
+            workspace,
+            buffer,
+            conflict_set,
+            base_editor,
+            ours_editor,
+            theirs_editor,
+            result_editor,
+            _conflict_set_subscription: conflict_set_subscription,
+        }
+    }
+
+    fn conflicts(&self, cx: &App) -> Arc<[ConflictRegion]> {
+        self.conflict_set.read(cx).snapshot().conflicts
+    }
+
+    fn cursor_ordering(&self, conflict: &ConflictRegion, cx: &App) -> Ordering {
+        let cursor = self
+            .result_editor
+            .read(cx)
+            .selections
+            .newest_anchor()
+            .head()
+            .text_anchor;
+        let snapshot = self.buffer.read(cx).snapshot();
+        if conflict.range.end.cmp(&cursor, &snapshot).is_lt() {
+            Ordering::Less
+        } else if conflict.range.start.cmp(&cursor, &snapshot).is_gt() {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    fn conflict_at_cursor(&self, cx: &App) -> Option<ConflictRegion> {
+        self.conflicts(cx)
+            .iter()
+            .find(|conflict| self.cursor_ordering(conflict, cx).is_eq())
+            .cloned()
+    }
+
+    fn resolve(
+        &mut self,
+        choose: impl FnOnce(&ConflictRegion) -> Option<Vec<std::ops::Range<language::Anchor>>>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(conflict) = self.conflict_at_cursor(cx) else {
+            return;
+        };
+        let Some(ranges) = choose(&conflict) else {
+            return;
+        };
+        conflict.resolve(self.buffer.clone(), &ranges, cx);
+    }
+
+    fn accept_ours(&mut self, _: &AcceptOurs, _window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(|conflict| Some(vec![conflict.ours.clone()]), cx);
+    }
+
+    fn accept_theirs(&mut self, _: &AcceptTheirs, _window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(|conflict| Some(vec![conflict.theirs.clone()]), cx);
+    }
+
+    fn accept_both(&mut self, _: &AcceptBoth, _window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(
+            |conflict| Some(vec![conflict.ours.clone(), conflict.theirs.clone()]),
+            cx,
+        );
+    }
+
+    fn accept_base(&mut self, _: &AcceptBase, _window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(|conflict| conflict.base.clone().map(|base| vec![base]), cx);
+    }
+
+    fn next_conflict(&mut self, _: &NextConflict, window: &mut Window, cx: &mut Context<Self>) {
+        let conflicts = self.conflicts(cx);
+        let target = conflicts
+            .iter()
+            .find(|conflict| self.cursor_ordering(conflict, cx).is_gt())
+            .or_else(|| conflicts.first());
+        if let Some(conflict) = target {
+            self.go_to_conflict(conflict.clone(), window, cx);
+        }
+    }
+
+    fn previous_conflict(
+        &mut self,
+        _: &PreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let conflicts = self.conflicts(cx);
+        let target = conflicts
+            .iter()
+            .rev()
+            .find(|conflict| self.cursor_ordering(conflict, cx).is_lt())
+            .or_else(|| conflicts.last());
+        if let Some(conflict) = target {
+            self.go_to_conflict(conflict.clone(), window, cx);
+        }
+    }
+
+    fn go_to_conflict(
+        &mut self,
+        conflict: ConflictRegion,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            let Some(anchor) = editor
+                .buffer()
+                .read(cx)
+                .snapshot(cx)
+                .as_singleton_anchor(conflict.range.start)
+            else {
+                return;
+            };
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |s| s.select_anchor_ranges([anchor..anchor]),
+            );
+        });
+        window.focus(&self.result_editor.focus_handle(cx), cx);
+    }
+
+    fn mark_resolved(&mut self, _: &MarkResolved, window: &mut Window, cx: &mut Context<Self>) {
+        let remaining = self.conflicts(cx).len();
+        let confirmation = (remaining > 0).then(|| {
+            window.prompt(
+                PromptLevel::Warning,
+                &format!(
+                    "{} still has {remaining} unresolved conflict{}",
+                    self.path.display(PathStyle::local()),
+                    if remaining == 1 { "" } else { "s" }
+                ),
+                Some("The remaining conflict markers will be staged as-is."),
+                &["Mark Resolved", "Cancel"],
+                cx,
+            )
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            if let Some(confirmation) = confirmation
+                && confirmation.await? != 0
+            {
+                return Ok(());
+            }
+
+            this.update(cx, |this, cx| {
+                this.project.update(cx, |project, cx| {
+                    project.save_buffer(this.buffer.clone(), cx)
+                })
+            })?
+            .await?;
+            this.update(cx, |this, cx| {
+                let path = this.path.clone();
+                this.repository.update(cx, |repository, cx| {
+                    repository.stage_entries(vec![path], cx)
+                })
+            })?
+            .await?;
+
+            // The user may have switched panes in the meantime, so close the editor in the pane
+            // that contains it rather than the active one.
+            let workspace = this.read_with(cx, |this, _| this.workspace.clone())?;
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    let Some(merge_editor) = this.upgrade() else {
+                        return Task::ready(Ok(()));
+                    };
+                    let Some(pane) = workspace.pane_for(&merge_editor) else {
+                        return Task::ready(Ok(()));
+                    };
+                    pane.update(cx, |pane, cx| {
+                        pane.close_item_by_id(
+                            merge_editor.entity_id(),
+                            SaveIntent::Skip,
+                            window,
+                            cx,
+                        )
+                    })
+                })?
+                .await
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
+    fn render_pane(
+        &self,
+        title: impl Into<SharedString>,
+        editor: &Entity<Editor>,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .border_r_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(title).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let remaining = self.conflicts(cx).len();
+        let conflict = self.conflict_at_cursor(cx);
+        let has_conflict = conflict.is_some();
+        let has_base = conflict.is_some_and(|conflict| conflict.base.is_some());
+        let focus_handle = self.focus_handle(cx);
+
+        let button = |id: &'static str, label: &'static str, action: &dyn gpui::Action| {
+            Button::new(id, label)
+                .label_size(LabelSize::Small)
+                .tooltip(Tooltip::for_action_title_in(label, action, &focus_handle))
+        };
+
+        h_flex()
+            .h(rems_from_px(41.))
+            .pl_3()
+            .pr_2()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .child(
+                        Label::new(self.path.display(PathStyle::local()).to_string())
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .child(
+                        Label::new(match remaining {
+                            0 => "No conflicts remaining".to_string(),
+                            1 => "1 conflict remaining".to_string(),
+                            n => format!("{n} conflicts remaining"),
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("previous-conflict", IconName::ArrowUp)
+                            .disabled(remaining == 0)
+                            .tooltip(Tooltip::for_action_title(
+                                "Previous Conflict",
+                                &PreviousConflict,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.previous_conflict(&PreviousConflict, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("next-conflict", IconName::ArrowDown)
+                            .disabled(remaining == 0)
+                            .tooltip(Tooltip::for_action_title("Next Conflict", &NextConflict))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.next_conflict(&NextConflict, window, cx)
+                            })),
+                    )
+                    .child(Divider::vertical())
+                    .child(
+                        button("accept-ours", "Accept Ours", &AcceptOurs)
+                            .disabled(!has_conflict)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept_ours(&AcceptOurs, window, cx)
+                            })),
+                    )
+                    .child(
+                        button("accept-theirs", "Accept Theirs", &AcceptTheirs)
+                            .disabled(!has_conflict)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept_theirs(&AcceptTheirs, window, cx)
+                            })),
+                    )
+                    .child(
+                        button("accept-both", "Accept Both", &AcceptBoth)
+                            .disabled(!has_conflict)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept_both(&AcceptBoth, window, cx)
+                            })),
+                    )
+                    .child(
+                        button("accept-base", "Accept Base", &AcceptBase)
+                            .disabled(!has_base)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept_base(&AcceptBase, window, cx)
+                            })),
+                    )
+                    .child(Divider::vertical())
+                    .child(
+                        button("mark-resolved", "Mark Resolved", &MarkResolved)
+                            .style(ButtonStyle::Filled)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.mark_resolved(&MarkResolved, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+async fn load_stage_buffers(
+    stages: MergeStages,
+    path: &RepoPath,
+    worktree_id: WorktreeId,
+    language_registry: &Arc<LanguageRegistry>,
+    cx: &mut AsyncApp,
+) -> Result<StageBuffers> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| path.display(PathStyle::local()).to_string());
+    let blob = |label: &str, text: &Option<String>| {
+        Arc::new(GitBlob {
+            path: path.clone(),
+            worktree_id,
+            is_deleted: text.is_none(),
+            is_binary: false,
+            display_name: format!("{label} - {file_name}"),
+        }) as Arc<dyn language::File>
+    };
+
+    let base_file = blob("Base", &stages.base);
+    let ours_file = blob("Ours", &stages.ours);
+    let theirs_file = blob("Theirs", &stages.theirs);
+
+    let base = build_buffer(
+        stages.base.clone().unwrap_or_default(),
+        base_file,
+        language_registry,
+        cx,
+    )
+    .await?;
+    let ours = build_buffer(
+        stages.ours.unwrap_or_default(),
+        ours_file,
+        language_registry,
+        cx,
+    )
+    .await?;
+    let theirs = build_buffer(
+        stages.theirs.unwrap_or_default(),
+        theirs_file,
+        language_registry,
+        cx,
+    )
+    .await?;
+
+    // Each side is shown as a diff against the common ancestor, so it's clear
+    // what that side changed.
+    let ours_diff = build_buffer_diff(stages.base.clone(), &ours, language_registry, cx)
+        .await
+        .context("diffing our version against the base")?;
+    let theirs_diff = build_buffer_diff(stages.base, &theirs, language_registry, cx)
+        .await
+        .context("diffing their version against the base")?;
+
+    Ok(StageBuffers {
+        base,
+        ours,
+        ours_diff,
+        theirs,
+        theirs_diff,
+    })
+}
+
+fn stage_editor(
+    buffer: Entity<Buffer>,
+    diff: Option<Entity<BufferDiff>>,
+    project: &Entity<Project>,
+    window: &mut Window,
+    cx: &mut Context<MergeEditor>,
+) -> Entity<Editor> {
+    let multibuffer = cx.new(|cx| {
+        let mut multibuffer = MultiBuffer::singleton(buffer, cx);
+        if let Some(diff) = diff {
+            multibuffer.add_diff(diff, cx);
+        }
+        multibuffer
+    });
+    cx.new(|cx| {
+        let mut editor = Editor::for_multibuffer(multibuffer, Some(project.clone()), window, cx);
+        editor.set_read_only(true);
+        editor.disable_inline_diagnostics();
+        editor.set_show_breakpoints(false, cx);
+        editor.set_expand_all_diff_hunks(cx);
+        editor
+    })
+}
+
+impl EventEmitter<EditorEvent> for MergeEditor {}
+
+impl Focusable for MergeEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let file_name = self.path.file_name().unwrap_or("File");
+        format!("Merge: {file_name}").into()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(format!("Resolve conflicts in {}", self.path.as_unix_str()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge Editor Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        options: workspace::item::SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for editor in [
+            &self.base_editor,
+            &self.ours_editor,
+            &self.theirs_editor,
+            &self.result_editor,
+        ] {
+            editor.update(cx, |editor, cx| {
+                editor.added_to_workspace(workspace, window, cx)
+            });
+        }
+    }
+}
+
+impl Render for MergeEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let conflict = self.conflicts(cx).first().cloned();
+        let ours_title = conflict
+            .as_ref()
+            .map(|conflict| format!("Ours ({})", conflict.ours_branch_name))
+            .unwrap_or_else(|| "Ours".to_string());
+        let theirs_title = conflict
+            .as_ref()
+            .map(|conflict| format!("Theirs ({})", conflict.theirs_branch_name))
+            .unwrap_or_else(|| "Theirs".to_string());
+
+        v_flex()
+            .key_context("MergeEditor")
+            .on_action(cx.listener(Self::accept_ours))
+            .on_action(cx.listener(Self::accept_theirs))
+            .on_action(cx.listener(Self::accept_both))
+            .on_action(cx.listener(Self::accept_base))
+            .on_action(cx.listener(Self::next_conflict))
+            .on_action(cx.listener(Self::previous_conflict))
+            .on_action(cx.listener(Self::mark_resolved))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(
+                h_flex()
+                    .h_1_2()
+                    .w_full()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.render_pane(ours_title, &self.ours_editor, cx))
+                    .child(self.render_pane("Base", &self.base_editor, cx))
+                    .child(self.render_pane(theirs_title, &self.theirs_editor, cx)),
+            )
+            .child(v_flex().flex_1().min_h_0().child(self.render_pane(
+                "Result",
+                &self.result_editor,
+                cx,
+            )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git::status::{UnmergedStatus, UnmergedStatusCode};
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use unindent::Unindent as _;
+    use util::{path, rel_path::rel_path};
+    use workspace::SplitDirection;
+
+    use super::*;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    async fn open_merge_editor(
+        cx: &mut TestAppContext,
+    ) -> (
+        Arc<FakeFs>,
+        Entity<Workspace>,
+        Entity<MergeEditor>,
+        &mut VisualTestContext,
+    ) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "foo.txt": "
+                    <<<<<<< HEAD
+                    ours one
+                    =======
+                    theirs one
+                    >>>>>>> feature
+                    middle
+                    <<<<<<< HEAD
+                    ours two
+                    ||||||| base
+                    base two
+                    =======
+                    theirs two
+                    >>>>>>> feature
+                "
+                .unindent(),
+            }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_status_for_repo(
+            dot_git,
+            &[(
+                "foo.txt",
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                }
+                .into(),
+            )],
+        );
+        fs.set_merge_stages_for_repo(
+            dot_git,
+            vec![(
+                RepoPath::from_rel_path(rel_path("foo.txt")),
+                MergeStages {
+                    base: Some("one\nmiddle\nbase two\n".into()),
+                    ours: Some("ours one\nmiddle\nours two\n".into()),
+                    theirs: Some("theirs one\nmiddle\ntheirs two\n".into()),
+                },
+            )],
+        );
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.run_until_parked();
+
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+        cx.update(|window, cx| {
+            MergeEditor::open(
+                RepoPath::from_rel_path(rel_path("foo.txt")),
+                repository,
+                workspace.downgrade(),
+                window,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        let merge_editor = workspace.read_with(cx, |workspace, cx| {
+            workspace.active_item_as::<MergeEditor>(cx).unwrap()
+        });
+        (fs, workspace, merge_editor, cx)
+    }
+
+    fn buffer_text(editor: &Entity<Editor>, cx: &App) -> String {
+        editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .unwrap()
+            .read(cx)
+            .text()
+    }
+
+    #[gpui::test]
+    async fn test_load_stages_and_resolve_conflicts(cx: &mut TestAppContext) {
+        let (fs, workspace, merge_editor, cx) = open_merge_editor(cx).await;
+
+        merge_editor.read_with(cx, |merge_editor, cx| {
+            assert_eq!(
+                buffer_text(&merge_editor.base_editor, cx),
+                "one\nmiddle\nbase two\n"
+            );
+            assert_eq!(
+                buffer_text(&merge_editor.ours_editor, cx),
+                "ours one\nmiddle\nours two\n"
+            );
+            assert_eq!(
+                buffer_text(&merge_editor.theirs_editor, cx),
+                "theirs one\nmiddle\ntheirs two\n"
+            );
+            let conflicts = merge_editor.conflicts(cx);
+            assert_eq!(conflicts.len(), 2);
+            assert_eq!(conflicts[0].ours_branch_name.as_ref(), "HEAD");
+            assert_eq!(conflicts[0].theirs_branch_name.as_ref(), "feature");
+            assert!(conflicts[0].base.is_none());
+            assert!(conflicts[1].base.is_some());
+        });
+
+        // The cursor starts in the first conflict.
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.accept_theirs(&AcceptTheirs, window, cx);
+        });
+        cx.run_until_parked();
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            assert_eq!(merge_editor.conflicts(cx).len(), 1);
+            assert!(merge_editor.conflict_at_cursor(cx).is_none());
+            merge_editor.next_conflict(&NextConflict, window, cx);
+            assert!(merge_editor.conflict_at_cursor(cx).is_some());
+            merge_editor.accept_base(&AcceptBase, window, cx);
+        });
+        cx.run_until_parked();
+        merge_editor.read_with(cx, |merge_editor, cx| {
+            assert!(merge_editor.conflicts(cx).is_empty());
+            assert_eq!(
+                merge_editor.buffer.read(cx).text(),
+                "theirs one\nmiddle\nbase two\n"
+            );
+        });
+
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.mark_resolved(&MarkResolved, window, cx);
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            fs.load(Path::new(path!("/project/foo.txt"))).await.unwrap(),
+            "theirs one\nmiddle\nbase two\n"
+        );
+        let index_text = fs
+            .with_git_state(Path::new(path!("/project/.git")), false, |state| {
+                state
+                    .index_contents
+                    .get(&RepoPath::from_rel_path(rel_path("foo.txt")))
+                    .cloned()
+            })
+            .unwrap();
+        assert_eq!(
+            index_text.as_deref(),
+            Some("theirs one\nmiddle\nbase two\n")
+        );
+        workspace.read_with(cx, |workspace, cx| {
+            assert!(workspace.active_item_as::<MergeEditor>(cx).is_none());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_mark_resolved_from_inactive_pane(cx: &mut TestAppContext) {
+        let (_, workspace, merge_editor, cx) = open_merge_editor(cx).await;
+
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.accept_ours(&AcceptOurs, window, cx);
+        });
+        cx.run_until_parked();
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.next_conflict(&NextConflict, window, cx);
+            merge_editor.accept_ours(&AcceptOurs, window, cx);
+        });
+        cx.run_until_parked();
+        merge_editor.read_with(cx, |merge_editor, cx| {
+            assert!(merge_editor.conflicts(cx).is_empty());
+        });
+
+        let merge_editor_pane =
+            workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.mark_resolved(&MarkResolved, window, cx);
+        });
+        // Switch to another pane while the merge result is being saved and staged.
+        let other_pane = workspace.update_in(cx, |workspace, window, cx| {
+            let other_pane =
+                workspace.split_pane(merge_editor_pane.clone(), SplitDirection::Right, window, cx);
+            window.focus(&other_pane.focus_handle(cx), cx);
+            other_pane
+        });
+        cx.run_until_parked();
+
+        workspace.read_with(cx, |workspace, _| {
+            assert_eq!(workspace.active_pane(), &other_pane);
+        });
+        merge_editor_pane.read_with(cx, |pane, _| {
+            assert!(pane.index_for_item(&merge_editor).is_none());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_mark_resolved_with_remaining_conflicts(cx: &mut TestAppContext) {
+        let (fs, workspace, merge_editor, cx) = open_merge_editor(cx).await;
+
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.accept_both(&AcceptBoth, window, cx);
+        });
+        cx.run_until_parked();
+        merge_editor.read_with(cx, |merge_editor, cx| {
+            assert!(
+                merge_editor
+                    .buffer
+                    .read(cx)
+                    .text()
+                    .starts_with("ours one\ntheirs one\nmiddle\n<<<<<<< HEAD\n")
+            );
+        });
+
+        // Cancelling the confirmation leaves the conflict unresolved.
+        merge_editor.update_in(cx, |merge_editor, window, cx| {
+            merge_editor.mark_resolved(&MarkResolved, window, cx);
+        });
+        cx.run_until_parked();
+        cx.simulate_prompt_answer("Cancel");
+        cx.run_until_parked();
+
+        let index_text = fs
+            .with_git_state(Path::new(path!("/project/.git")), false, |state| {
+                state
+                    .index_contents
+                    .get(&RepoPath::from_rel_path(rel_path("foo.txt")))
+                    .cloned()
+            })
+            .unwrap();
+        assert!(index_text.unwrap().ends_with(" (unmerged)"));
+        workspace.read_with(cx, |workspace, cx| {
+            assert!(workspace.active_item_as::<MergeEditor>(cx).is_some());
+        });
+    }
+}
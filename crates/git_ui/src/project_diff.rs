@@ -1851,7 +1851,8 @@ mod tests {
     use db::indoc;
     use editor::test::editor_test_context::{EditorTestContext, assert_state_with_diff};
     use git::status::{TrackedStatus, UnmergedStatus, UnmergedStatusCode};
-    use gpui::TestAppContext;
+    use gpui::{TestAppContext, VisualTestContext};
+    use language::Point;
     use project::FakeFs;
     use serde_json::json;
     use settings::SettingsStore;
@@ -2652,4 +2653,62 @@ mod tests {
 
         cx.assert_excerpts_with_selections("[EXCERPT]\nˇ# My cool project\nDetails to come.\n");
     }
+
+    #[gpui::test]
+    async fn test_stage_and_unstage_selected_lines(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "foo.txt": "one\nADDED_1\nADDED_2\ntwo\n",
+            }),
+        )
+        .await;
+        fs.set_head_and_index_for_repo(
+            Path::new(path!("/project/.git")),
+            &[("foo.txt", "one\ntwo\n".into())],
+        );
+        let project = Project::test(fs.clone(), [Path::new(path!("/project"))], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.run_until_parked();
+
+        cx.focus(&workspace);
+        cx.update(|window, cx| {
+            window.dispatch_action(project_diff::Diff.boxed_clone(), cx);
+        });
+        cx.run_until_parked();
+        let item = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<ProjectDiff>(cx).unwrap()
+        });
+        cx.focus(&item);
+        let editor = item.read_with(cx, |item, cx| item.editor.read(cx).primary_editor().clone());
+
+        let index_text = |cx: &mut VisualTestContext| {
+            cx.run_until_parked();
+            fs.with_git_state(Path::new(path!("/project/.git")), false, |state| {
+                state
+                    .index_contents
+                    .get(&RepoPath::from_rel_path(rel_path("foo.txt")))
+                    .cloned()
+            })
+            .unwrap()
+        };
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(Default::default(), window, cx, |selections| {
+                selections.select_ranges([Point::new(1, 0)..Point::new(1, 0)])
+            });
+            editor.stage_selected_lines(&git::StageSelectedLines, window, cx);
+        });
+        assert_eq!(index_text(cx).as_deref(), Some("one\nADDED_1\ntwo\n"));
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.unstage_selected_lines(&git::UnstageSelectedLines, window, cx);
+        });
+        assert_eq!(index_text(cx).as_deref(), Some("one\ntwo\n"));
+    }
 }
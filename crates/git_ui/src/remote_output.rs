@@ -10,6 +10,7 @@ pub enum RemoteAction {
     Fetch(Option<Remote>),
     Pull(Remote),
     Push(SharedString, Remote),
+    UpdateSubmodules,
 }
 
 impl RemoteAction {
@@ -18,6 +19,7 @@ impl RemoteAction {
             RemoteAction::Fetch(_) => "fetch",
             RemoteAction::Pull(_) => "pull",
             RemoteAction::Push(_, _) => "push",
+            RemoteAction::UpdateSubmodules => "submodule update",
         }
     }
 }
@@ -157,6 +159,19 @@ pub fn format_output(action: &RemoteAction, output: RemoteCommandOutput) -> Succ
                 style: style.unwrap_or(SuccessStyle::ToastWithLog { output }),
             }
         }
+        RemoteAction::UpdateSubmodules => {
+            if output.is_empty() {
+                SuccessMessage {
+                    message: "Submodules: Already up to date".into(),
+                    style: SuccessStyle::Toast,
+                }
+            } else {
+                SuccessMessage {
+                    message: "Updated submodules".into(),
+                    style: SuccessStyle::ToastWithLog { output },
+                }
+            }
+        }
     }
 }
 
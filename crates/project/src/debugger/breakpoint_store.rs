@@ -13,9 +13,16 @@ use rpc::{
     AnyProtoClient, TypedEnvelope,
     proto::{self},
 };
-use std::{hash::Hash, ops::Range, path::Path, sync::Arc, u32};
+use serde::{Deserialize, Serialize};
+use std::{
+    hash::Hash,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    u32,
+};
 use text::{Point, PointUtf16};
-use util::maybe;
+use util::{maybe, rel_path::RelPath};
 
 use crate::{ProjectPath, buffer_store::BufferStore, worktree_store::WorktreeStore};
 
@@ -152,6 +159,7 @@ pub struct BreakpointStore {
     buffer_store: Entity<BufferStore>,
     worktree_store: Entity<WorktreeStore>,
     breakpoints: BTreeMap<Arc<Path>, BreakpointsInFile>,
+    function_breakpoints: Vec<FunctionBreakpoint>,
     downstream_client: Option<(AnyProtoClient, u64)>,
     active_stack_frame: Option<ActiveStackFrame>,
     // E.g ssh
@@ -162,10 +170,13 @@ impl BreakpointStore {
     pub fn init(client: &AnyProtoClient) {
         client.add_entity_request_handler(Self::handle_toggle_breakpoint);
         client.add_entity_message_handler(Self::handle_breakpoints_for_file);
+        client.add_entity_request_handler(Self::handle_update_function_breakpoints);
+        client.add_entity_message_handler(Self::handle_function_breakpoints);
     }
     pub fn local(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
             mode: BreakpointStoreMode::Local,
             buffer_store,
             worktree_store,
@@ -182,6 +193,7 @@ impl BreakpointStore {
     ) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
             mode: BreakpointStoreMode::Remote(RemoteBreakpointStore {
                 upstream_client,
                 upstream_project_id,
@@ -311,8 +323,51 @@ impl BreakpointStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_function_breakpoints(
+        this: Entity<Self>,
+        message: TypedEnvelope<proto::FunctionBreakpoints>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            this.function_breakpoints = message
+                .payload
+                .breakpoints
+                .into_iter()
+                .map(FunctionBreakpoint::from_proto)
+                .collect();
+            cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+            cx.notify();
+        });
+        Ok(())
+    }
+
+    async fn handle_update_function_breakpoints(
+        this: Entity<Self>,
+        message: TypedEnvelope<proto::UpdateFunctionBreakpoints>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            this.function_breakpoints = message
+                .payload
+                .breakpoints
+                .into_iter()
+                .map(FunctionBreakpoint::from_proto)
+                .collect();
+            this.function_breakpoints_changed(cx);
+        });
+        Ok(proto::Ack {})
+    }
+
     pub(crate) fn broadcast(&self) {
         if let Some((client, project_id)) = &self.downstream_client {
+            let _ = client.send(proto::FunctionBreakpoints {
+                project_id: *project_id,
+                breakpoints: self
+                    .function_breakpoints
+                    .iter()
+                    .map(FunctionBreakpoint::to_proto)
+                    .collect(),
+            });
             for (path, breakpoint_set) in &self.breakpoints {
                 let _ = client.send(proto::BreakpointsForFile {
                     project_id: *project_id,
@@ -606,6 +661,95 @@ impl BreakpointStore {
         let breakpoint_paths = self.breakpoints.keys().cloned().collect();
         self.breakpoints.clear();
         cx.emit(BreakpointStoreEvent::BreakpointsCleared(breakpoint_paths));
+        if !self.function_breakpoints.is_empty() {
+            self.function_breakpoints.clear();
+            self.function_breakpoints_changed(cx);
+        }
+    }
+
+    pub fn function_breakpoints(&self) -> &[FunctionBreakpoint] {
+        &self.function_breakpoints
+    }
+
+    /// Applies `edit_action` to the function breakpoint with the given name.
+    ///
+    /// Function breakpoints do not support log messages, so `EditLogMessage` is ignored.
+    pub fn edit_function_breakpoint(
+        &mut self,
+        name: Arc<str>,
+        edit_action: BreakpointEditAction,
+        cx: &mut Context<Self>,
+    ) {
+        let name: Arc<str> = name.trim().into();
+        if name.is_empty() {
+            return;
+        }
+        let existing = self
+            .function_breakpoints
+            .iter()
+            .position(|breakpoint| breakpoint.name == name);
+
+        match (edit_action, existing) {
+            (BreakpointEditAction::Toggle, Some(ix)) => {
+                self.function_breakpoints.remove(ix);
+            }
+            (BreakpointEditAction::Toggle, None) => {
+                self.function_breakpoints
+                    .push(FunctionBreakpoint::new(name));
+            }
+            (BreakpointEditAction::InvertState, Some(ix)) => {
+                let breakpoint = &mut self.function_breakpoints[ix];
+                breakpoint.state = if breakpoint.state.is_enabled() {
+                    BreakpointState::Disabled
+                } else {
+                    BreakpointState::Enabled
+                };
+            }
+            (BreakpointEditAction::EditCondition(condition), Some(ix)) => {
+                self.function_breakpoints[ix].condition =
+                    (!condition.is_empty()).then_some(condition);
+            }
+            (BreakpointEditAction::EditHitCondition(hit_condition), Some(ix)) => {
+                self.function_breakpoints[ix].hit_condition =
+                    (!hit_condition.is_empty()).then_some(hit_condition);
+            }
+            (BreakpointEditAction::EditLogMessage(_), _)
+            | (
+                BreakpointEditAction::InvertState
+                | BreakpointEditAction::EditCondition(_)
+                | BreakpointEditAction::EditHitCondition(_),
+                None,
+            ) => return,
+        }
+
+        self.function_breakpoints_changed(cx);
+    }
+
+    fn function_breakpoints_changed(&mut self, cx: &mut Context<Self>) {
+        let breakpoints = self
+            .function_breakpoints
+            .iter()
+            .map(FunctionBreakpoint::to_proto)
+            .collect();
+        if let BreakpointStoreMode::Remote(remote) = &self.mode {
+            cx.background_spawn(
+                remote
+                    .upstream_client
+                    .request(proto::UpdateFunctionBreakpoints {
+                        project_id: remote.upstream_project_id,
+                        breakpoints,
+                    }),
+            )
+            .detach();
+        } else if let Some((client, project_id)) = &self.downstream_client {
+            let _ = client.send(proto::FunctionBreakpoints {
+                project_id: *project_id,
+                breakpoints,
+            });
+        }
+
+        cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        cx.notify();
     }
 
     pub fn breakpoints<'a>(
@@ -858,6 +1002,187 @@ impl BreakpointStore {
         }
     }
 
+    pub fn with_serialized_function_breakpoints(
+        &mut self,
+        breakpoints: Vec<FunctionBreakpoint>,
+        cx: &mut Context<BreakpointStore>,
+    ) {
+        if let BreakpointStoreMode::Local = &self.mode {
+            log::debug!("Deserialized {} function breakpoints", breakpoints.len());
+            self.function_breakpoints = breakpoints;
+            cx.notify();
+        }
+    }
+
+    /// Collects every breakpoint into a form that can be written to a file and shared.
+    ///
+    /// Paths inside a visible worktree are stored relative to that worktree, so the
+    /// resulting file can be imported into a checkout of the same project elsewhere.
+    pub fn export_breakpoints(&self, cx: &App) -> ExportedBreakpoints {
+        let worktree_store = self.worktree_store.read(cx);
+        let source_breakpoints = self
+            .all_source_breakpoints(cx)
+            .into_iter()
+            .flat_map(|(abs_path, breakpoints)| {
+                let path = worktree_store
+                    .find_worktree(&abs_path, cx)
+                    .filter(|(worktree, _)| worktree.read(cx).is_visible())
+                    .map(|(_, relative_path)| PathBuf::from(relative_path.as_unix_str()))
+                    .unwrap_or_else(|| abs_path.to_path_buf());
+                breakpoints
+                    .into_iter()
+                    .map(move |breakpoint| ExportedSourceBreakpoint {
+                        path: path.clone(),
+                        line: breakpoint.row + 1,
+                        condition: breakpoint.condition.map(|s| s.to_string()),
+                        hit_condition: breakpoint.hit_condition.map(|s| s.to_string()),
+                        log_message: breakpoint.message.map(|s| s.to_string()),
+                        enabled: breakpoint.state.is_enabled(),
+                    })
+            })
+            .collect();
+        let function_breakpoints = self
+            .function_breakpoints
+            .iter()
+            .map(|breakpoint| ExportedFunctionBreakpoint {
+                name: breakpoint.name.to_string(),
+                condition: breakpoint.condition.as_ref().map(|s| s.to_string()),
+                hit_condition: breakpoint.hit_condition.as_ref().map(|s| s.to_string()),
+                enabled: breakpoint.state.is_enabled(),
+            })
+            .collect();
+
+        ExportedBreakpoints {
+            source_breakpoints,
+            function_breakpoints,
+        }
+    }
+
+    /// Adds previously exported breakpoints to this store.
+    ///
+    /// Breakpoints that already exist are left untouched, and source breakpoints whose path
+    /// can not be found in any worktree are skipped.
+    pub fn import_breakpoints(
+        &mut self,
+        breakpoints: ExportedBreakpoints,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let mut function_breakpoints_changed = false;
+        for breakpoint in breakpoints.function_breakpoints {
+            if self
+                .function_breakpoints
+                .iter()
+                .any(|existing| *existing.name == breakpoint.name)
+            {
+                continue;
+            }
+            self.function_breakpoints.push(FunctionBreakpoint {
+                name: breakpoint.name.into(),
+                condition: breakpoint.condition.map(Into::into),
+                hit_condition: breakpoint.hit_condition.map(Into::into),
+                state: if breakpoint.enabled {
+                    BreakpointState::Enabled
+                } else {
+                    BreakpointState::Disabled
+                },
+            });
+            function_breakpoints_changed = true;
+        }
+        if function_breakpoints_changed {
+            self.function_breakpoints_changed(cx);
+        }
+
+        let mut breakpoints_by_path: HashMap<ProjectPath, Vec<ExportedSourceBreakpoint>> =
+            HashMap::default();
+        for breakpoint in breakpoints.source_breakpoints {
+            let Some(project_path) = self.resolve_exported_path(&breakpoint.path, cx) else {
+                log::warn!(
+                    "skipping imported breakpoint in {:?}: path is not part of the project",
+                    breakpoint.path
+                );
+                continue;
+            };
+            breakpoints_by_path
+                .entry(project_path)
+                .or_default()
+                .push(breakpoint);
+        }
+
+        let buffers = breakpoints_by_path
+            .into_iter()
+            .map(|(project_path, breakpoints)| {
+                let buffer = self.buffer_store.update(cx, |buffer_store, cx| {
+                    buffer_store.open_buffer(project_path, cx)
+                });
+                (buffer, breakpoints)
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |this, cx| {
+            for (buffer, breakpoints) in buffers {
+                let buffer = match buffer.await {
+                    Ok(buffer) => buffer,
+                    Err(error) => {
+                        log::warn!("failed to open buffer for imported breakpoints: {error:#}");
+                        continue;
+                    }
+                };
+                this.update(cx, |this, cx| {
+                    let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+                        return;
+                    };
+                    let snapshot = buffer.read(cx).snapshot();
+                    for breakpoint in breakpoints {
+                        let row = breakpoint.line.saturating_sub(1);
+                        if row > snapshot.max_point_utf16().row {
+                            log::warn!("skipping an imported breakpoint that's out of range");
+                            continue;
+                        }
+                        if this.breakpoint_at_row(&abs_path, row, cx).is_some() {
+                            continue;
+                        }
+                        let position = snapshot.anchor_after(PointUtf16::new(row, 0));
+                        this.toggle_breakpoint(
+                            buffer.clone(),
+                            BreakpointWithPosition {
+                                position,
+                                bp: Breakpoint {
+                                    message: breakpoint.log_message.map(Into::into),
+                                    hit_condition: breakpoint.hit_condition.map(Into::into),
+                                    condition: breakpoint.condition.map(Into::into),
+                                    state: if breakpoint.enabled {
+                                        BreakpointState::Enabled
+                                    } else {
+                                        BreakpointState::Disabled
+                                    },
+                                },
+                            },
+                            BreakpointEditAction::Toggle,
+                            cx,
+                        );
+                    }
+                })?;
+            }
+            Ok(())
+        })
+    }
+
+    fn resolve_exported_path(&self, path: &Path, cx: &App) -> Option<ProjectPath> {
+        let worktree_store = self.worktree_store.read(cx);
+        if path.is_absolute() {
+            return worktree_store.project_path_for_absolute_path(path, cx);
+        }
+
+        let relative_path = RelPath::unix(path.to_str()?).ok()?;
+        let worktree = worktree_store
+            .visible_worktrees(cx)
+            .find(|worktree| worktree.read(cx).entry_for_path(relative_path).is_some())?;
+        Some(ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: relative_path.into_arc(),
+        })
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) fn breakpoint_paths(&self) -> Vec<Arc<Path>> {
         self.breakpoints.keys().cloned().collect()
@@ -875,6 +1200,7 @@ pub enum BreakpointStoreEvent {
     ClearDebugLines,
     BreakpointsUpdated(Arc<Path>, BreakpointUpdatedReason),
     BreakpointsCleared(Vec<Arc<Path>>),
+    FunctionBreakpointsUpdated,
 }
 
 impl EventEmitter<BreakpointStoreEvent> for BreakpointStore {}
@@ -1036,3 +1362,104 @@ impl From<SourceBreakpoint> for dap::SourceBreakpoint {
         }
     }
 }
+
+/// Breakpoint that stops execution when a function with the given name is entered.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FunctionBreakpoint {
+    pub name: Arc<str>,
+    pub condition: Option<Arc<str>>,
+    pub hit_condition: Option<Arc<str>>,
+    pub state: BreakpointState,
+}
+
+impl FunctionBreakpoint {
+    pub fn new(name: Arc<str>) -> Self {
+        Self {
+            name,
+            condition: None,
+            hit_condition: None,
+            state: BreakpointState::Enabled,
+        }
+    }
+
+    fn to_proto(&self) -> proto::FunctionBreakpoint {
+        proto::FunctionBreakpoint {
+            name: self.name.to_string(),
+            state: match self.state {
+                BreakpointState::Enabled => proto::BreakpointState::Enabled.into(),
+                BreakpointState::Disabled => proto::BreakpointState::Disabled.into(),
+            },
+            condition: self.condition.as_ref().map(|s| String::from(s.as_ref())),
+            hit_condition: self
+                .hit_condition
+                .as_ref()
+                .map(|s| String::from(s.as_ref())),
+        }
+    }
+
+    fn from_proto(breakpoint: proto::FunctionBreakpoint) -> Self {
+        Self {
+            name: breakpoint.name.into(),
+            state: match proto::BreakpointState::from_i32(breakpoint.state) {
+                Some(proto::BreakpointState::Disabled) => BreakpointState::Disabled,
+                None | Some(proto::BreakpointState::Enabled) => BreakpointState::Enabled,
+            },
+            condition: breakpoint.condition.map(Into::into),
+            hit_condition: breakpoint.hit_condition.map(Into::into),
+        }
+    }
+}
+
+impl From<FunctionBreakpoint> for dap::FunctionBreakpoint {
+    fn from(bp: FunctionBreakpoint) -> Self {
+        Self {
+            name: String::from(bp.name.as_ref()),
+            condition: bp
+                .condition
+                .map(|condition| String::from(condition.as_ref())),
+            hit_condition: bp
+                .hit_condition
+                .map(|hit_condition| String::from(hit_condition.as_ref())),
+        }
+    }
+}
+
+/// File format used to share a set of breakpoints between machines.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedBreakpoints {
+    #[serde(default)]
+    pub source_breakpoints: Vec<ExportedSourceBreakpoint>,
+    #[serde(default)]
+    pub function_breakpoints: Vec<ExportedFunctionBreakpoint>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedSourceBreakpoint {
+    /// Path relative to the worktree containing the file, or an absolute path for files outside of the project.
+    pub path: PathBuf,
+    /// 1-based line number.
+    pub line: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_message: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedFunctionBreakpoint {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
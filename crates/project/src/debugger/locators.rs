@@ -1,4 +1,6 @@
 pub(crate) mod cargo;
 pub mod go;
 pub(crate) mod node;
+pub mod php;
 pub mod python;
+pub mod ruby;
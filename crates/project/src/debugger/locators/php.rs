@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::{BackgroundExecutor, SharedString};
+
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate};
+
+pub struct PhpLocator;
+
+/// PHP test runners, which are themselves PHP scripts that Xdebug can launch.
+const TEST_RUNNERS: &[&str] = &["phpunit", "pest", "paratest"];
+
+#[async_trait]
+impl DapLocator for PhpLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("PHP")
+    }
+
+    /// Determines whether this locator can generate debug target for given task.
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "Xdebug" {
+            return None;
+        }
+
+        let command_name = Path::new(&build_config.command).file_name()?.to_str()?;
+        let (program, runtime_args, args) = if TEST_RUNNERS.contains(&command_name) {
+            (
+                build_config.command.clone(),
+                &[][..],
+                build_config.args.as_slice(),
+            )
+        } else if command_name.starts_with("php") {
+            // `php [flags] script.php [args]`
+            let script_position = build_config
+                .args
+                .iter()
+                .position(|arg| !arg.starts_with('-'))?;
+            if build_config.args[..script_position]
+                .iter()
+                .any(|arg| arg == "-r")
+            {
+                // We cannot debug inline code.
+                return None;
+            }
+            (
+                build_config.args[script_position].clone(),
+                &build_config.args[..script_position],
+                &build_config.args[script_position + 1..],
+            )
+        } else {
+            return None;
+        };
+
+        let mut config = serde_json::json!({
+            "request": "launch",
+            "program": program,
+            "args": args,
+            "cwd": build_config.cwd.clone(),
+            "env": build_config.env.clone(),
+            // Let the adapter pick a free port and pass it to Xdebug, so that it doesn't clash with a running listener.
+            "port": 0,
+        });
+        if !runtime_args.is_empty()
+            && let Some(config_obj) = config.as_object_mut()
+        {
+            config_obj.insert("runtimeArgs".to_string(), runtime_args.to_vec().into());
+        }
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(&self, _: SpawnInTerminal, _executor: BackgroundExecutor) -> Result<DebugRequest> {
+        bail!("PHP locator should not require DapLocator::run to be ran");
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::{BackgroundExecutor, SharedString};
+
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate};
+
+pub struct RubyLocator;
+
+/// Ruby entry points that can be run under rdbg directly.
+const DEBUGGABLE_COMMANDS: &[&str] = &["rspec", "rake", "rails", "rackup", "ruby"];
+
+fn command_name(command: &str) -> Option<&str> {
+    Path::new(command).file_name()?.to_str()
+}
+
+#[async_trait]
+impl DapLocator for RubyLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("Ruby")
+    }
+
+    /// Determines whether this locator can generate debug target for given task.
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "rdbg" {
+            return None;
+        }
+
+        let mut command = build_config.command.as_str();
+        let mut args = build_config.args.as_slice();
+        let use_bundler = command_name(command) == Some("bundle");
+        if use_bundler {
+            // `bundle exec rspec ...` is debugged as `rspec ...`, with rdbg itself run through bundler.
+            let [exec, bundled_command, rest @ ..] = args else {
+                return None;
+            };
+            if exec.as_str() != "exec" {
+                return None;
+            }
+            command = bundled_command.as_str();
+            args = rest;
+        }
+
+        let name = command_name(command)?;
+        if !DEBUGGABLE_COMMANDS.contains(&name) {
+            return None;
+        }
+
+        let mut config = serde_json::json!({
+            "request": "launch",
+            "cwd": build_config.cwd.clone(),
+            "env": build_config.env.clone(),
+        });
+        let config_obj = config.as_object_mut()?;
+        if name == "ruby" {
+            // Interpreter flags cannot be passed through rdbg, so only plain `ruby script.rb` invocations are supported.
+            let [script, rest @ ..] = args else {
+                return None;
+            };
+            if script.starts_with('-') {
+                return None;
+            }
+            config_obj.insert("script".to_string(), script.clone().into());
+            config_obj.insert("args".to_string(), rest.to_vec().into());
+        } else {
+            config_obj.insert("command".to_string(), command.into());
+            config_obj.insert("args".to_string(), args.to_vec().into());
+        }
+        if use_bundler {
+            config_obj.insert("useBundler".to_string(), true.into());
+        }
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(&self, _: SpawnInTerminal, _executor: BackgroundExecutor) -> Result<DebugRequest> {
+        bail!("Ruby locator should not require DapLocator::run to be ran");
+    }
+}
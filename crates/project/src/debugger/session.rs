@@ -2,12 +2,13 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand,
-    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
-    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, GotoCommand, GotoTargetsCommand,
+    Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand,
+    NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetDataBreakpointsCommand, SetExceptionBreakpoints, SetInstructionBreakpointsCommand,
+    SetVariableValueCommand, StackTraceCommand, StepBackCommand, StepCommand, StepInCommand,
+    StepOutCommand, TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapStore;
 use crate::debugger::breakpoint_store::BreakpointSessionState;
@@ -143,6 +144,8 @@ pub struct Watcher {
     pub value: SharedString,
     pub variables_reference: u64,
     pub presentation_hint: Option<VariablePresentationHint>,
+    /// Whether the value differs from the one the expression had when the debuggee last stopped.
+    pub changed: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -152,6 +155,12 @@ pub struct DataBreakpointState {
     pub context: Arc<DataBreakpointContext>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstructionBreakpointState {
+    pub dap: dap::InstructionBreakpoint,
+    pub is_enabled: bool,
+}
+
 pub enum SessionState {
     /// Represents a session that is building/initializing
     /// even if a session doesn't have a pre build task this state
@@ -178,7 +187,7 @@ pub struct SessionQuirks {
     pub prefer_thread_name: bool,
 }
 
-fn client_source(abs_path: &Path) -> dap::Source {
+pub(super) fn client_source(abs_path: &Path) -> dap::Source {
     dap::Source {
         name: abs_path
             .file_name()
@@ -318,6 +327,27 @@ impl RunningMode {
         })
     }
 
+    fn send_function_breakpoints(
+        &self,
+        ignore_breakpoints: bool,
+        breakpoint_store: &Entity<BreakpointStore>,
+        cx: &App,
+    ) -> Task<Result<Vec<dap::Breakpoint>>> {
+        let breakpoints = if ignore_breakpoints {
+            vec![]
+        } else {
+            breakpoint_store
+                .read(cx)
+                .function_breakpoints()
+                .iter()
+                .filter(|bp| bp.state.is_enabled())
+                .cloned()
+                .map(Into::into)
+                .collect()
+        };
+        self.request(dap_command::SetFunctionBreakpoints { breakpoints })
+    }
+
     fn send_exception_breakpoints(
         &self,
         filters: Vec<ExceptionBreakpointsFilter>,
@@ -447,6 +477,8 @@ impl RunningMode {
         let supports_exception_filters = capabilities
             .supports_exception_filter_options
             .unwrap_or_default();
+        let supports_function_breakpoints =
+            dap_command::SetFunctionBreakpoints::is_supported(capabilities);
         let this = self.clone();
         let worktree = self.worktree().clone();
         let mut filters = capabilities
@@ -495,6 +527,13 @@ impl RunningMode {
                     }
                 })?;
 
+                if supports_function_breakpoints {
+                    cx.update(|cx| this.send_function_breakpoints(false, &breakpoint_store, cx))
+                        .await
+                        .context("failed to set function breakpoints")
+                        .log_err();
+                }
+
                 if should_send_exception_breakpoints {
                     _ = session.update(cx, |this, _| {
                         filters.retain(|filter| {
@@ -703,12 +742,15 @@ pub struct Session {
     output_token: OutputToken,
     output: Box<circular_buffer::CircularBuffer<MAX_TRACKED_OUTPUT_EVENTS, dap::OutputEvent>>,
     watchers: HashMap<SharedString, Watcher>,
+    watcher_values_at_last_stop: HashMap<SharedString, SharedString>,
     is_session_terminated: bool,
     requests: HashMap<TypeId, HashMap<RequestSlot, Shared<Task<Option<()>>>>>,
     pub(crate) breakpoint_store: Entity<BreakpointStore>,
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
     data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    instruction_breakpoints: BTreeMap<String, InstructionBreakpointState>,
+    disassembly: HashMap<DisassembleCommand, Arc<[dap::DisassembledInstruction]>>,
     background_tasks: Vec<Task<()>>,
     restart_task: Option<Task<()>>,
     task_context: TaskContext,
@@ -806,6 +848,7 @@ pub enum SessionEvent {
         sender: mpsc::Sender<Result<u32>>,
     },
     DataBreakpointInfo,
+    Disassembly,
     ConsoleOutput,
     HistoricSnapshotSelected,
 }
@@ -860,6 +903,9 @@ impl Session {
                         local.unset_breakpoints_from_paths(paths, cx).detach();
                     }
                 }
+                BreakpointStoreEvent::FunctionBreakpointsUpdated => {
+                    this.send_function_breakpoints(cx);
+                }
                 BreakpointStoreEvent::SetDebugLine | BreakpointStoreEvent::ClearDebugLines => {}
             })
             .detach();
@@ -874,6 +920,7 @@ impl Session {
                 parent_session,
                 capabilities: Capabilities::default(),
                 watchers: HashMap::default(),
+                watcher_values_at_last_stop: HashMap::default(),
                 output_token: OutputToken(0),
                 output: circular_buffer::CircularBuffer::boxed(),
                 requests: HashMap::default(),
@@ -883,6 +930,8 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 data_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
+                disassembly: HashMap::default(),
                 exception_breakpoints: Default::default(),
                 label,
                 adapter,
@@ -1463,6 +1512,12 @@ impl Session {
 
     fn handle_stopped_event(&mut self, event: StoppedEvent, cx: &mut Context<Self>) {
         self.push_to_history();
+        self.watcher_values_at_last_stop = self
+            .watchers
+            .values()
+            .filter(|watcher| !watcher.value.is_empty())
+            .map(|watcher| (watcher.expression.clone(), watcher.value.clone()))
+            .collect();
 
         self.state.stopped();
         // todo(debugger): Find a clean way to get around the clone
@@ -1604,6 +1659,9 @@ impl Session {
 
                 // todo(debugger): We should only send the invalidate command to downstream clients.
                 // self.invalidate_state(&ModulesCommand.into());
+
+                // Code might have been mapped or unmapped, so previously disassembled ranges can't be trusted.
+                self.invalidate_disassembly(cx);
             }
             Events::LoadedSource(_) => {
                 self.invalidate_state(&LoadedSourcesCommand.into());
@@ -1911,6 +1969,7 @@ impl Session {
                 this.memory.clear(cx.background_executor());
                 this.invalidate_command_type::<ReadMemory>();
                 this.invalidate_command_type::<VariablesCommand>();
+                this.invalidate_disassembly(cx);
                 cx.emit(SessionEvent::Variables);
                 response.ok()
             },
@@ -2001,6 +2060,7 @@ impl Session {
         }
 
         self.ignore_breakpoints = ignore;
+        self.send_function_breakpoints(cx);
 
         if let Some(local) = self.as_running() {
             local.send_source_breakpoints(ignore, &self.breakpoint_store, cx)
@@ -2050,7 +2110,42 @@ impl Session {
     pub fn toggle_data_breakpoint(&mut self, id: &str, cx: &mut Context<'_, Session>) {
         if let Some(state) = self.data_breakpoints.get_mut(id) {
             state.is_enabled = !state.is_enabled;
-            self.send_exception_breakpoints(cx);
+            self.send_data_breakpoints(cx);
+        }
+    }
+
+    pub fn set_data_breakpoint_condition(
+        &mut self,
+        id: &str,
+        condition: Option<String>,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(state) = self.data_breakpoints.get_mut(id) {
+            state.dap.condition = condition.filter(|condition| !condition.is_empty());
+            self.send_data_breakpoints(cx);
+        }
+    }
+
+    pub fn set_data_breakpoint_hit_condition(
+        &mut self,
+        id: &str,
+        hit_condition: Option<String>,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(state) = self.data_breakpoints.get_mut(id) {
+            state.dap.hit_condition = hit_condition.filter(|condition| !condition.is_empty());
+            self.send_data_breakpoints(cx);
+        }
+    }
+
+    fn send_function_breakpoints(&self, cx: &mut App) {
+        if !dap_command::SetFunctionBreakpoints::is_supported(&self.capabilities) {
+            return;
+        }
+        if let Some(local) = self.as_running() {
+            local
+                .send_function_breakpoints(self.ignore_breakpoints, &self.breakpoint_store, cx)
+                .detach_and_log_err(cx);
         }
     }
 
@@ -2086,6 +2181,116 @@ impl Session {
         self.send_data_breakpoints(cx);
     }
 
+    pub fn instruction_breakpoints(&self) -> impl Iterator<Item = &InstructionBreakpointState> {
+        self.instruction_breakpoints.values()
+    }
+
+    pub fn has_instruction_breakpoint(&self, instruction_reference: &str) -> bool {
+        self.instruction_breakpoints
+            .contains_key(instruction_reference)
+    }
+
+    /// Sets a breakpoint on the given instruction, or removes it if one is already set there.
+    pub fn toggle_instruction_breakpoint(
+        &mut self,
+        instruction_reference: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .instruction_breakpoints
+            .remove(&instruction_reference)
+            .is_none()
+        {
+            self.instruction_breakpoints.insert(
+                instruction_reference.clone(),
+                InstructionBreakpointState {
+                    dap: dap::InstructionBreakpoint {
+                        instruction_reference,
+                        offset: None,
+                        condition: None,
+                        hit_condition: None,
+                        mode: None,
+                    },
+                    is_enabled: true,
+                },
+            );
+        }
+        self.send_instruction_breakpoints(cx);
+        cx.notify();
+    }
+
+    pub fn toggle_instruction_breakpoint_enabled(
+        &mut self,
+        instruction_reference: &str,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(state) = self.instruction_breakpoints.get_mut(instruction_reference) {
+            state.is_enabled = !state.is_enabled;
+            self.send_instruction_breakpoints(cx);
+            cx.notify();
+        }
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &mut Context<Self>) {
+        if let Some(mode) = self.as_running() {
+            let breakpoints = self
+                .instruction_breakpoints
+                .values()
+                .filter_map(|state| state.is_enabled.then(|| state.dap.clone()))
+                .collect();
+            let command = SetInstructionBreakpointsCommand { breakpoints };
+            mode.request(command).detach_and_log_err(cx);
+        }
+    }
+
+    /// Disassembles `instruction_count` instructions, starting `instruction_offset` instructions
+    /// away from `memory_reference`. Returns `None` until the adapter has responded successfully;
+    /// failed requests aren't cached and are sent again on the next call.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Option<Arc<[dap::DisassembledInstruction]>> {
+        let command = DisassembleCommand {
+            memory_reference,
+            instruction_offset,
+            instruction_count,
+        };
+        if let Some(instructions) = self.disassembly.get(&command) {
+            return Some(instructions.clone());
+        }
+
+        self.fetch(
+            command.clone(),
+            move |this, instructions, cx| {
+                match instructions.context("disassembling instructions") {
+                    Ok(instructions) => {
+                        this.disassembly.insert(command, instructions.into());
+                        cx.emit(SessionEvent::Disassembly);
+                        cx.notify();
+                    }
+                    Err(error) => {
+                        // Forget the failed request so that the next call retries it.
+                        log::error!("{error:#}");
+                        this.invalidate_state(&command.into());
+                    }
+                }
+            },
+            cx,
+        );
+        None
+    }
+
+    fn invalidate_disassembly(&mut self, cx: &mut Context<Self>) {
+        self.invalidate_command_type::<DisassembleCommand>();
+        if !self.disassembly.is_empty() {
+            self.disassembly.clear();
+            cx.emit(SessionEvent::Disassembly);
+        }
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2178,6 +2383,42 @@ impl Session {
         .detach();
     }
 
+    /// Asks the adapter where execution could be moved to on the given (0-based) row of a file.
+    pub fn goto_targets(
+        &mut self,
+        path: Arc<Path>,
+        row: u32,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<dap::GotoTarget>>> {
+        let task = self.request(
+            GotoTargetsCommand {
+                path,
+                line: row as u64 + 1,
+            },
+            |_, result, _| result.log_err(),
+            cx,
+        );
+
+        cx.background_executor()
+            .spawn(async move { task.await.context("failed to fetch goto targets") })
+    }
+
+    /// Moves execution of a stopped thread to a target returned by [`Self::goto_targets`].
+    pub fn goto(&mut self, thread_id: ThreadId, target_id: u64, cx: &mut Context<Self>) {
+        self.select_historic_snapshot(None, cx);
+
+        self.active_snapshot.thread_states.process_step(thread_id);
+        self.request(
+            GotoCommand {
+                thread_id: thread_id.0,
+                target_id,
+            },
+            Self::on_step_response::<GotoCommand>(thread_id),
+            cx,
+        )
+        .detach();
+    }
+
     pub fn restart(&mut self, args: Option<Value>, cx: &mut Context<Self>) {
         if self.restart_task.is_some() || self.as_running().is_none() {
             return;
@@ -2603,13 +2844,19 @@ impl Session {
             let response = request.await?;
 
             this.update(cx, |session, cx| {
+                let value = SharedString::from(response.result);
+                let changed = session
+                    .watcher_values_at_last_stop
+                    .get(&expression)
+                    .is_some_and(|previous_value| previous_value != &value);
                 session.watchers.insert(
                     expression.clone(),
                     Watcher {
                         expression,
-                        value: response.result.into(),
+                        value,
                         variables_reference: response.variables_reference,
                         presentation_hint: response.presentation_hint,
+                        changed,
                     },
                 );
                 cx.emit(SessionEvent::Watchers);
@@ -2617,6 +2864,27 @@ impl Session {
         })
     }
 
+    /// Adds watch expressions without evaluating them, e.g. when restoring the watches of a
+    /// previous session. They're evaluated the next time the watchers are refreshed.
+    pub fn restore_watchers(
+        &mut self,
+        expressions: impl IntoIterator<Item = SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        for expression in expressions {
+            self.watchers
+                .entry(expression.clone())
+                .or_insert_with(|| Watcher {
+                    expression,
+                    value: SharedString::default(),
+                    variables_reference: 0,
+                    presentation_hint: None,
+                    changed: false,
+                });
+        }
+        cx.emit(SessionEvent::Watchers);
+    }
+
     pub fn refresh_watchers(&mut self, frame_id: u64, cx: &mut Context<Self>) {
         let watches = self.watchers.clone();
         for (_, watch) in watches.into_iter() {
@@ -2625,8 +2893,9 @@ impl Session {
         }
     }
 
-    pub fn remove_watcher(&mut self, expression: SharedString) {
+    pub fn remove_watcher(&mut self, expression: SharedString, cx: &mut Context<Self>) {
         self.watchers.remove(&expression);
+        cx.emit(SessionEvent::Watchers);
     }
 
     pub fn variables(
@@ -27,11 +27,13 @@ use futures::{
 use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, Oid, RunHook,
     blame::Blame,
+    line_history::{LineHistory, LineHistoryEntry},
     parse_git_remote_url,
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, GraphCommitData, InitialGraphCommitData, LogOrder,
-        LogSource, PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode,
+        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, CommitSignature,
+        CommitSigning, DiffType, FetchOptions, GitRepository, GitRepositoryCheckpoint,
+        GraphCommitData, InitialGraphCommitData, LogOrder, LogSource, MergeStages, PushOptions,
+        Remote, RemoteCommandOutput, RepoPath, ResetMode, SignatureStatus, SigningFormat,
         UpstreamTrackingStatus, Worktree as GitWorktree,
     },
     stash::{GitStash, StashEntry},
@@ -39,6 +41,7 @@ use git::{
         DiffTreeType, FileStatus, GitSummary, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
         UnmergedStatus, UnmergedStatusCode,
     },
+    submodule::{Submodule, SubmoduleState},
 };
 use gpui::{
     App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
@@ -53,7 +56,9 @@ use pending_op::{PendingOp, PendingOpId, PendingOps, PendingOpsSummary};
 use postage::stream::Stream as _;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, git_reset, split_repository_update},
+    proto::{
+        self, commit::commit_options, git_commit_signature, git_reset, split_repository_update,
+    },
 };
 use serde::Deserialize;
 use settings::WorktreeId;
@@ -275,6 +280,7 @@ pub struct RepositorySnapshot {
     pub remote_origin_url: Option<String>,
     pub remote_upstream_url: Option<String>,
     pub stash_entries: GitStash,
+    pub submodules: Arc<[Submodule]>,
 }
 
 type JobId = u64;
@@ -396,6 +402,7 @@ pub enum RepositoryEvent {
     MergeHeadsChanged,
     BranchChanged,
     StashEntriesChanged,
+    SubmodulesChanged,
     PendingOpsChanged { pending_ops: SumTree<PendingOps> },
     GitGraphCountUpdated((LogOrder, LogSource), usize),
 }
@@ -511,6 +518,7 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_push);
         client.add_entity_request_handler(Self::handle_pull);
         client.add_entity_request_handler(Self::handle_fetch);
+        client.add_entity_request_handler(Self::handle_update_submodules);
         client.add_entity_request_handler(Self::handle_stage);
         client.add_entity_request_handler(Self::handle_unstage);
         client.add_entity_request_handler(Self::handle_stash);
@@ -521,8 +529,11 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_run_hook);
         client.add_entity_request_handler(Self::handle_reset);
         client.add_entity_request_handler(Self::handle_show);
+        client.add_entity_request_handler(Self::handle_commit_signature);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_file_history);
+        client.add_entity_request_handler(Self::handle_line_history);
+        client.add_entity_request_handler(Self::handle_load_merge_stages);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
@@ -1188,6 +1199,19 @@ impl GitStore {
         cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
     }
 
+    pub fn line_history(
+        &self,
+        repo: &Entity<Repository>,
+        path: RepoPath,
+        rows: Range<u32>,
+        revision: Option<String>,
+        cx: &mut App,
+    ) -> Task<Result<LineHistory>> {
+        let rx = repo.update(cx, |repo, _| repo.line_history(path, rows, revision));
+
+        cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
+    }
+
     pub fn get_permalink_to_line(
         &self,
         buffer: &Entity<Buffer>,
@@ -1742,6 +1766,23 @@ impl GitStore {
             .max_by_key(|(repo, _)| repo.read(cx).work_directory_abs_path.clone())
     }
 
+    /// Returns the repository that was discovered for the given submodule of `repository`.
+    pub fn repository_for_submodule(
+        &self,
+        repository: &Entity<Repository>,
+        submodule_path: &RepoPath,
+        cx: &App,
+    ) -> Option<Entity<Repository>> {
+        let abs_path = repository
+            .read(cx)
+            .snapshot
+            .repo_path_to_abs_path(submodule_path);
+        self.repositories
+            .values()
+            .find(|repo| *repo.read(cx).work_directory_abs_path == *abs_path)
+            .cloned()
+    }
+
     pub fn git_init(
         &self,
         path: Arc<Path>,
@@ -1947,6 +1988,42 @@ impl GitStore {
         })
     }
 
+    async fn handle_update_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitUpdateSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|path| RepoPath::from_proto(path))
+            .collect::<Result<Vec<_>>>()?;
+        let init = envelope.payload.init;
+        let askpass_id = envelope.payload.askpass_id;
+
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.update_submodules(paths, init, askpass, cx)
+            })
+            .await??;
+
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
     async fn handle_push(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::Push>,
@@ -2217,10 +2294,7 @@ impl GitStore {
                 repository_handle.commit(
                     message,
                     name.zip(email),
-                    CommitOptions {
-                        amend: options.amend,
-                        signoff: options.signoff,
-                    },
+                    proto_to_commit_options(&options),
                     askpass,
                     cx,
                 )
@@ -2457,13 +2531,23 @@ impl GitStore {
                 repository_handle.show(envelope.payload.commit)
             })
             .await??;
-        Ok(proto::GitCommitDetails {
-            sha: commit.sha.into(),
-            message: commit.message.into(),
-            commit_timestamp: commit.commit_timestamp,
-            author_email: commit.author_email.into(),
-            author_name: commit.author_name.into(),
-        })
+        Ok(commit_details_to_proto(&commit))
+    }
+
+    async fn handle_commit_signature(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetCommitSignature>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitSignature> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let signature = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.commit_signature(envelope.payload.commit)
+            })
+            .await??;
+        Ok(commit_signature_to_proto(&signature))
     }
 
     async fn handle_load_commit_diff(
@@ -2527,6 +2611,53 @@ impl GitStore {
         })
     }
 
+    async fn handle_line_history(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLineHistory>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLineHistoryResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+        let rows = envelope.payload.start_row..envelope.payload.end_row;
+
+        let line_history = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.line_history(path, rows, envelope.payload.revision)
+            })
+            .await??;
+
+        Ok(proto::GitLineHistoryResponse {
+            entries: line_history
+                .entries
+                .iter()
+                .map(line_history_entry_to_proto)
+                .collect(),
+        })
+    }
+
+    async fn handle_load_merge_stages(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLoadMergeStages>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLoadMergeStagesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        let stages = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_merge_stages(path)
+            })
+            .await??;
+
+        Ok(proto::GitLoadMergeStagesResponse {
+            base: stages.base,
+            ours: stages.ours,
+            theirs: stages.theirs,
+        })
+    }
+
     async fn handle_reset(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitReset>,
@@ -3433,6 +3564,7 @@ impl RepositorySnapshot {
             remote_origin_url: None,
             remote_upstream_url: None,
             stash_entries: Default::default(),
+            submodules: Default::default(),
             path_style,
         }
     }
@@ -3468,6 +3600,7 @@ impl RepositorySnapshot {
                 .collect(),
             remote_upstream_url: self.remote_upstream_url.clone(),
             remote_origin_url: self.remote_origin_url.clone(),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
         }
     }
 
@@ -3539,6 +3672,7 @@ impl RepositorySnapshot {
                 .collect(),
             remote_upstream_url: self.remote_upstream_url.clone(),
             remote_origin_url: self.remote_origin_url.clone(),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
         }
     }
 
@@ -3556,6 +3690,12 @@ impl RepositorySnapshot {
             .cloned()
     }
 
+    pub fn submodule_for_path(&self, path: &RepoPath) -> Option<&Submodule> {
+        self.submodules
+            .iter()
+            .find(|submodule| &submodule.path == path)
+    }
+
     pub fn abs_path_to_repo_path(&self, abs_path: &Path) -> Option<RepoPath> {
         Self::abs_path_to_repo_path_inner(&self.work_directory_abs_path, abs_path, self.path_style)
     }
@@ -3611,6 +3751,85 @@ pub fn stash_to_proto(entry: &StashEntry) -> proto::StashEntry {
     }
 }
 
+fn line_history_entry_to_proto(entry: &LineHistoryEntry) -> proto::LineHistoryEntry {
+    proto::LineHistoryEntry {
+        sha: entry.sha.to_string(),
+        parent_sha: entry.parent_sha.as_ref().map(|sha| sha.to_string()),
+        subject: entry.subject.to_string(),
+        commit_timestamp: entry.commit_timestamp,
+        author_name: entry.author_name.to_string(),
+        author_email: entry.author_email.to_string(),
+        path: entry.path.to_proto(),
+        old_path: entry.old_path.as_ref().map(|path| path.to_proto()),
+        start_row: entry.start_row,
+        old_start_row: entry.old_start_row,
+        text: entry.text.clone(),
+        old_text: entry.old_text.clone(),
+    }
+}
+
+fn proto_to_line_history_entry(entry: proto::LineHistoryEntry) -> Result<LineHistoryEntry> {
+    Ok(LineHistoryEntry {
+        sha: entry.sha.into(),
+        parent_sha: entry.parent_sha.map(SharedString::from),
+        subject: entry.subject.into(),
+        commit_timestamp: entry.commit_timestamp,
+        author_name: entry.author_name.into(),
+        author_email: entry.author_email.into(),
+        path: RepoPath::from_proto(&entry.path)?,
+        old_path: entry
+            .old_path
+            .as_deref()
+            .map(RepoPath::from_proto)
+            .transpose()?,
+        start_row: entry.start_row,
+        old_start_row: entry.old_start_row,
+        text: entry.text,
+        old_text: entry.old_text,
+    })
+}
+
+fn submodule_to_proto(submodule: &Submodule) -> proto::Submodule {
+    proto::Submodule {
+        path: submodule.path.to_proto(),
+        url: submodule.url.as_ref().map(|url| url.to_string()),
+        head_sha: submodule.head_sha.map(|sha| sha.to_string()),
+        current_sha: submodule.current_sha.map(|sha| sha.to_string()),
+        state: match submodule.state {
+            SubmoduleState::Uninitialized => proto::submodule::State::Uninitialized,
+            SubmoduleState::UpToDate => proto::submodule::State::UpToDate,
+            SubmoduleState::Modified => proto::submodule::State::Modified,
+            SubmoduleState::Conflicted => proto::submodule::State::Conflicted,
+        } as i32,
+    }
+}
+
+fn proto_to_submodule(submodule: &proto::Submodule) -> Result<Submodule> {
+    let state = match proto::submodule::State::from_i32(submodule.state)
+        .context("invalid submodule state")?
+    {
+        proto::submodule::State::Uninitialized => SubmoduleState::Uninitialized,
+        proto::submodule::State::UpToDate => SubmoduleState::UpToDate,
+        proto::submodule::State::Modified => SubmoduleState::Modified,
+        proto::submodule::State::Conflicted => SubmoduleState::Conflicted,
+    };
+    Ok(Submodule {
+        path: RepoPath::from_proto(&submodule.path)?,
+        url: submodule.url.clone().map(SharedString::from),
+        head_sha: submodule
+            .head_sha
+            .as_deref()
+            .map(Oid::from_str)
+            .transpose()?,
+        current_sha: submodule
+            .current_sha
+            .as_deref()
+            .map(Oid::from_str)
+            .transpose()?,
+        state,
+    })
+}
+
 pub fn proto_to_stash(entry: &proto::StashEntry) -> Result<StashEntry> {
     Ok(StashEntry {
         oid: Oid::from_bytes(&entry.oid)?,
@@ -4257,13 +4476,34 @@ impl Repository {
                         })
                         .await?;
 
-                    Ok(CommitDetails {
-                        sha: resp.sha.into(),
-                        message: resp.message.into(),
-                        commit_timestamp: resp.commit_timestamp,
-                        author_email: resp.author_email.into(),
-                        author_name: resp.author_name.into(),
-                    })
+                    Ok(proto_to_commit_details(&resp))
+                }
+            }
+        })
+    }
+
+    /// Checks the signature of a commit, which is slow enough to only be done
+    /// on demand rather than as part of [`Self::show`].
+    pub fn commit_signature(
+        &mut self,
+        commit: String,
+    ) -> oneshot::Receiver<Result<CommitSignature>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.commit_signature(commit).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetCommitSignature {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+
+                    Ok(proto_to_commit_signature(&response))
                 }
             }
         })
@@ -4354,6 +4594,69 @@ impl Repository {
         })
     }
 
+    pub fn load_merge_stages(&mut self, path: RepoPath) -> oneshot::Receiver<Result<MergeStages>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.load_merge_stages(path).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
+                    let response = client
+                        .request(proto::GitLoadMergeStages {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
+                        })
+                        .await?;
+                    Ok(MergeStages {
+                        base: response.base,
+                        ours: response.ours,
+                        theirs: response.theirs,
+                    })
+                }
+            }
+        })
+    }
+
+    pub fn line_history(
+        &mut self,
+        path: RepoPath,
+        rows: Range<u32>,
+        revision: Option<String>,
+    ) -> oneshot::Receiver<Result<LineHistory>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.line_history(path, rows, revision).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
+                    let response = client
+                        .request(proto::GitLineHistory {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
+                            start_row: rows.start,
+                            end_row: rows.end,
+                            revision: revision.clone(),
+                        })
+                        .await?;
+                    Ok(LineHistory {
+                        entries: response
+                            .entries
+                            .into_iter()
+                            .map(proto_to_line_history_entry)
+                            .collect::<Result<_>>()?,
+                        path,
+                        rows,
+                        revision: revision.map(Into::into),
+                    })
+                }
+            }
+        })
+    }
+
     pub fn graph_data(
         &mut self,
         log_source: LogSource,
@@ -5066,10 +5369,7 @@ impl Repository {
                             message: String::from(message),
                             name: name.map(String::from),
                             email: email.map(String::from),
-                            options: Some(proto::commit::CommitOptions {
-                                amend: options.amend,
-                                signoff: options.signoff,
-                            }),
+                            options: Some(commit_options_to_proto(options)),
                             askpass_id,
                         })
                         .await
@@ -5124,6 +5424,60 @@ impl Repository {
         })
     }
 
+    pub fn update_submodules(
+        &mut self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+        let job_name = if init {
+            "git submodule update --init"
+        } else {
+            "git submodule update"
+        };
+
+        self.send_job(Some(job_name.into()), move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => {
+                    backend
+                        .update_submodules(paths, init, askpass, environment, cx)
+                        .await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+
+                    let response = client
+                        .request(proto::GitUpdateSubmodules {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            askpass_id,
+                            paths: paths.iter().map(|path| path.to_proto()).collect(),
+                            init,
+                        })
+                        .await
+                        .context("sending submodule update request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                }
+            }
+        })
+    }
+
     pub fn push(
         &mut self,
         branch: SharedString,
@@ -5868,6 +6222,15 @@ impl Repository {
         self.snapshot.stash_entries = new_stash_entries;
         self.snapshot.remote_upstream_url = update.remote_upstream_url;
         self.snapshot.remote_origin_url = update.remote_origin_url;
+        let new_submodules: Arc<[Submodule]> = update
+            .submodules
+            .iter()
+            .filter_map(|submodule| proto_to_submodule(submodule).log_err())
+            .collect();
+        if self.snapshot.submodules != new_submodules {
+            cx.emit(RepositoryEvent::SubmodulesChanged);
+        }
+        self.snapshot.submodules = new_submodules;
 
         let edits = update
             .removed_statuses
@@ -6205,6 +6568,15 @@ impl Repository {
                 }
                 let statuses = backend.status(&paths).await?;
                 let stash_entries = backend.stash_entries().await?;
+                let submodules_changed = paths.iter().any(|path| {
+                    path.as_unix_str() == ".gitmodules"
+                        || prev_snapshot.submodule_for_path(path).is_some()
+                });
+                let submodules = if submodules_changed {
+                    backend.submodules().await.log_err().map(Arc::from)
+                } else {
+                    None
+                };
 
                 let changed_path_statuses = cx
                     .background_spawn(async move {
@@ -6242,6 +6614,13 @@ impl Repository {
                         this.snapshot.stash_entries = stash_entries;
                     }
 
+                    if let Some(submodules) = submodules
+                        && this.snapshot.submodules != submodules
+                    {
+                        cx.emit(RepositoryEvent::SubmodulesChanged);
+                        this.snapshot.submodules = submodules;
+                    }
+
                     if !changed_path_statuses.is_empty() {
                         cx.emit(RepositoryEvent::StatusesChanged);
                         this.snapshot
@@ -6561,6 +6940,31 @@ fn commit_details_to_proto(commit: &CommitDetails) -> proto::GitCommitDetails {
     }
 }
 
+fn commit_signature_to_proto(signature: &CommitSignature) -> proto::GitCommitSignature {
+    proto::GitCommitSignature {
+        status: match signature.status {
+            SignatureStatus::Unsigned => git_commit_signature::Status::Unsigned,
+            SignatureStatus::Verified => git_commit_signature::Status::Verified,
+            SignatureStatus::Unverified => git_commit_signature::Status::Unverified,
+        }
+        .into(),
+        signer: signature.signer.to_string(),
+        signing_key: signature.key.to_string(),
+    }
+}
+
+fn proto_to_commit_signature(proto: &proto::GitCommitSignature) -> CommitSignature {
+    CommitSignature {
+        status: match proto.status() {
+            git_commit_signature::Status::Unsigned => SignatureStatus::Unsigned,
+            git_commit_signature::Status::Verified => SignatureStatus::Verified,
+            git_commit_signature::Status::Unverified => SignatureStatus::Unverified,
+        },
+        signer: proto.signer.clone().into(),
+        key: proto.signing_key.clone().into(),
+    }
+}
+
 fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     CommitDetails {
         sha: proto.sha.clone().into(),
@@ -6571,6 +6975,46 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn commit_options_to_proto(options: CommitOptions) -> proto::commit::CommitOptions {
+    let (signing_mode, signing_format) = match options.signing {
+        CommitSigning::GitConfig(format) => (commit_options::SigningMode::GitConfig, format),
+        CommitSigning::Sign(format) => (commit_options::SigningMode::Sign, format),
+        CommitSigning::NoSign => (commit_options::SigningMode::NoSign, None),
+    };
+    proto::commit::CommitOptions {
+        amend: options.amend,
+        signoff: options.signoff,
+        signing_mode: signing_mode.into(),
+        signing_format: signing_format.map(|format| {
+            match format {
+                SigningFormat::OpenPgp => commit_options::SigningFormat::Openpgp,
+                SigningFormat::Ssh => commit_options::SigningFormat::Ssh,
+                SigningFormat::X509 => commit_options::SigningFormat::X509,
+            }
+            .into()
+        }),
+    }
+}
+
+fn proto_to_commit_options(options: &proto::commit::CommitOptions) -> CommitOptions {
+    let signing_format = options
+        .signing_format
+        .map(|_| match options.signing_format() {
+            commit_options::SigningFormat::Openpgp => SigningFormat::OpenPgp,
+            commit_options::SigningFormat::Ssh => SigningFormat::Ssh,
+            commit_options::SigningFormat::X509 => SigningFormat::X509,
+        });
+    CommitOptions {
+        amend: options.amend,
+        signoff: options.signoff,
+        signing: match options.signing_mode() {
+            commit_options::SigningMode::GitConfig => CommitSigning::GitConfig(signing_format),
+            commit_options::SigningMode::Sign => CommitSigning::Sign(signing_format),
+            commit_options::SigningMode::NoSign => CommitSigning::NoSign,
+        },
+    }
+}
+
 async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
@@ -6621,6 +7065,16 @@ async fn compute_snapshot(
     let remote_origin_url = backend.remote_url("origin").await;
     let remote_upstream_url = backend.remote_url("upstream").await;
 
+    let submodules: Arc<[Submodule]> = backend
+        .submodules()
+        .await
+        .log_err()
+        .unwrap_or_default()
+        .into();
+    if submodules != prev_snapshot.submodules {
+        events.push(RepositoryEvent::SubmodulesChanged);
+    }
+
     let snapshot = RepositorySnapshot {
         id,
         statuses_by_path,
@@ -6633,6 +7087,7 @@ async fn compute_snapshot(
         remote_origin_url,
         remote_upstream_url,
         stash_entries,
+        submodules,
     };
 
     Ok((snapshot, events))
@@ -14,6 +14,7 @@ pub mod json_language_server_ext;
 pub mod log_store;
 pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
+pub mod task_diagnostics;
 pub mod vue_language_server_ext;
 
 mod inlay_hint_cache;
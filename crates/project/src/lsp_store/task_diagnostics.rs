@@ -0,0 +1,152 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use collections::HashMap;
+use gpui::Context;
+use language::{Diagnostic, DiagnosticEntry, DiagnosticSourceKind, PointUtf16, Unclipped};
+use lsp::LanguageServerId;
+use task::{Problem, ProblemSeverity};
+use util::{paths::PathStyle, post_inc, rel_path::RelPath};
+
+use crate::{
+    LspStore,
+    lsp_store::{DocumentDiagnostics, DocumentDiagnosticsUpdate},
+};
+
+/// Diagnostics found in the output of tasks are reported on behalf of this language server id,
+/// which no real language server gets.
+pub const TASK_DIAGNOSTICS_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX - 1);
+
+impl LspStore {
+    /// Replaces the diagnostics previously reported for the task with the given label with the
+    /// given problems, found in its output.
+    ///
+    /// The diagnostics have the task's label as their source, so that tasks don't clear each
+    /// other's diagnostics. Relative paths, reported by tasks without a working directory, are
+    /// resolved against the worktree roots. Problems in files outside of the project's worktrees
+    /// are dropped.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: &str,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            self.mode.is_local(),
+            "task diagnostics are only supported in local projects"
+        );
+
+        // Clear the diagnostics from the previous run, in files that may no longer have any.
+        let mut diagnostics_by_path = HashMap::default();
+        for (worktree_id, summaries) in &self.diagnostic_summaries {
+            let Some(worktree) = self
+                .worktree_store
+                .read(cx)
+                .worktree_for_id(*worktree_id, cx)
+            else {
+                continue;
+            };
+            let worktree = worktree.read(cx);
+            for (path, summaries_by_server_id) in summaries {
+                if summaries_by_server_id.contains_key(&TASK_DIAGNOSTICS_SERVER_ID) {
+                    diagnostics_by_path.insert(worktree.absolutize(path), Vec::new());
+                }
+            }
+        }
+
+        for mut problem in problems {
+            if problem.file.is_relative() {
+                // Without a working directory, the task runs in the worktree root.
+                let Some(abs_path) = self.resolve_task_problem_path(&problem.file, cx) else {
+                    continue;
+                };
+                problem.file = abs_path;
+            } else if self
+                .worktree_store
+                .read(cx)
+                .find_worktree(&problem.file, cx)
+                .is_none()
+            {
+                continue;
+            }
+
+            let row = problem.line.saturating_sub(1);
+            let start = PointUtf16::new(
+                row,
+                problem.column.map_or(0, |column| column.saturating_sub(1)),
+            );
+            let end = match (problem.end_line, problem.end_column) {
+                (end_line, Some(end_column)) => PointUtf16::new(
+                    end_line.map_or(row, |end_line| end_line.saturating_sub(1)),
+                    end_column.saturating_sub(1),
+                ),
+                (Some(end_line), None) => PointUtf16::new(end_line.saturating_sub(1), u32::MAX),
+                // Without a column, the problem spans the whole line.
+                (None, None) if problem.column.is_none() => PointUtf16::new(row, u32::MAX),
+                (None, None) => start,
+            };
+            let severity = match problem.severity {
+                ProblemSeverity::Error => lsp::DiagnosticSeverity::ERROR,
+                ProblemSeverity::Warning => lsp::DiagnosticSeverity::WARNING,
+                ProblemSeverity::Info => lsp::DiagnosticSeverity::INFORMATION,
+                ProblemSeverity::Hint => lsp::DiagnosticSeverity::HINT,
+            };
+            diagnostics_by_path
+                .entry(problem.file)
+                .or_insert_with(Vec::new)
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end.max(start)),
+                    diagnostic: Diagnostic {
+                        source: Some(task_label.to_string()),
+                        source_kind: DiagnosticSourceKind::Other,
+                        code: problem.code.map(lsp::NumberOrString::String),
+                        severity,
+                        message: problem.message,
+                        group_id: post_inc(
+                            &mut self.as_local_mut().unwrap().next_diagnostic_group_id,
+                        ),
+                        is_primary: true,
+                        ..Diagnostic::default()
+                    },
+                });
+        }
+
+        let updates = diagnostics_by_path
+            .into_iter()
+            .map(
+                |(document_abs_path, diagnostics)| DocumentDiagnosticsUpdate {
+                    diagnostics: DocumentDiagnostics {
+                        diagnostics,
+                        document_abs_path,
+                        version: None,
+                    },
+                    result_id: None,
+                    registration_id: None,
+                    server_id: TASK_DIAGNOSTICS_SERVER_ID,
+                    disk_based_sources: Cow::Borrowed(&[]),
+                },
+            )
+            .collect();
+        let task_label = task_label.to_string();
+        self.merge_diagnostic_entries(
+            updates,
+            move |_, diagnostic, _| diagnostic.source.as_ref() != Some(&task_label),
+            cx,
+        )
+    }
+
+    /// Resolves a relative path from a task's output against the root of the first visible
+    /// worktree that contains it.
+    fn resolve_task_problem_path(&self, path: &Path, cx: &Context<Self>) -> Option<PathBuf> {
+        let path = RelPath::new(path, PathStyle::local()).ok()?;
+        self.worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx))
+            .find(|worktree| worktree.entry_for_path(&path).is_some())
+            .map(|worktree| worktree.absolutize(&path))
+    }
+}
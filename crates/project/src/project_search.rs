@@ -1,7 +1,7 @@
 use std::{
     cell::LazyCell,
     collections::BTreeSet,
-    io::{BufRead, BufReader},
+    io::{self, BufRead, BufReader, Read},
     ops::Range,
     path::{Path, PathBuf},
     pin::pin,
@@ -9,13 +9,14 @@ use std::{
     time::Duration,
 };
 
-use anyhow::Context;
+use anyhow::{Context, Result};
+use clock::ReplicaId;
 use collections::HashSet;
 use fs::Fs;
 use futures::FutureExt as _;
 use futures::{SinkExt, StreamExt, select_biased, stream::FuturesOrdered};
 use gpui::{App, AppContext, AsyncApp, BackgroundExecutor, Entity, Priority, Task};
-use language::{Buffer, BufferSnapshot};
+use language::{Buffer, BufferSnapshot, Capability};
 use parking_lot::Mutex;
 use postage::oneshot;
 use rpc::{AnyProtoClient, proto};
@@ -28,6 +29,7 @@ use crate::{
     Project, ProjectItem, ProjectPath, RemotelyCreatedModels,
     buffer_store::BufferStore,
     search::{SearchQuery, SearchResult},
+    search_text::{SearchTextFile, SearchTextProvider, search_text_providers},
     worktree_store::WorktreeStore,
 };
 
@@ -44,6 +46,7 @@ enum SearchKind {
     Local {
         fs: Arc<dyn Fs>,
         worktrees: Vec<Entity<Worktree>>,
+        search_text_providers: Vec<Arc<dyn SearchTextProvider>>,
     },
     /// Query remote host for candidates. As of writing, the host runs a local search in "buffers with matches only" mode.
     Remote {
@@ -99,6 +102,8 @@ enum FindSearchCandidates {
         /// based on disk contents of a buffer. This step is not performed for buffers we already have in memory.
         confirm_contents_will_match_tx: Sender<MatchingEntry>,
         confirm_contents_will_match_rx: Receiver<MatchingEntry>,
+        /// Files handled by these providers are matched against the text they provide, rather than their raw contents.
+        search_text_providers: Arc<[Arc<dyn SearchTextProvider>]>,
     },
     Remote,
     OpenBuffersOnly,
@@ -114,7 +119,11 @@ impl Search {
     ) -> Self {
         let worktrees = worktree_store.read(cx).visible_worktrees(cx).collect();
         Self {
-            kind: SearchKind::Local { fs, worktrees },
+            kind: SearchKind::Local {
+                fs,
+                worktrees,
+                search_text_providers: search_text_providers(cx),
+            },
             buffer_store,
             worktree_store,
             limit,
@@ -178,6 +187,7 @@ impl Search {
         let matching_buffers = grab_buffer_snapshot_rx.clone();
         let trigger_search = Box::new(move |cx: &mut App| {
             cx.spawn(async move |cx| {
+                let should_find_all_matches = !tx.is_closed();
                 for buffer in unnamed_buffers {
                     _ = grab_buffer_snapshot_tx.send(buffer).await;
                 }
@@ -202,7 +212,15 @@ impl Search {
                     SearchKind::Local {
                         fs,
                         ref mut worktrees,
+                        ref mut search_text_providers,
                     } => {
+                        // Buffers holding searchable text aren't part of the buffer store, so
+                        // they can't be shared with the peers that only look for matching buffers.
+                        let search_text_providers: Arc<[_]> = if should_find_all_matches {
+                            std::mem::take(search_text_providers).into()
+                        } else {
+                            Arc::new([])
+                        };
                         let (get_buffer_for_full_scan_tx, get_buffer_for_full_scan_rx) =
                             unbounded();
                         let (confirm_contents_will_match_tx, confirm_contents_will_match_rx) =
@@ -220,6 +238,7 @@ impl Search {
                             .boxed_local(),
                             Self::open_buffers(
                                 self.buffer_store,
+                                search_text_providers.clone(),
                                 get_buffer_for_full_scan_rx,
                                 grab_buffer_snapshot_tx,
                                 cx.clone(),
@@ -238,6 +257,7 @@ impl Search {
                                 confirm_contents_will_match_tx,
                                 confirm_contents_will_match_rx,
                                 input_paths_rx,
+                                search_text_providers,
                             },
                             tasks,
                         )
@@ -326,8 +346,6 @@ impl Search {
                     }
                 };
 
-                let should_find_all_matches = !tx.is_closed();
-
                 let _executor = executor.clone();
                 let worker_pool = executor.spawn(async move {
                     let num_cpus = _executor.num_cpus();
@@ -499,19 +517,29 @@ impl Search {
     /// Background workers cannot open buffers by themselves, hence main thread will do it on their behalf.
     async fn open_buffers(
         buffer_store: Entity<BufferStore>,
+        search_text_providers: Arc<[Arc<dyn SearchTextProvider>]>,
         rx: Receiver<ProjectPath>,
         find_all_matches_tx: Sender<Entity<Buffer>>,
-        mut cx: AsyncApp,
+        cx: AsyncApp,
     ) {
         let mut rx = pin!(rx.ready_chunks(64));
         _ = maybe!(async move {
             while let Some(requested_paths) = rx.next().await {
-                let mut buffers = buffer_store.update(&mut cx, |this, cx| {
-                    requested_paths
-                        .into_iter()
-                        .map(|path| this.open_buffer(path, cx))
-                        .collect::<FuturesOrdered<_>>()
-                });
+                let mut buffers = requested_paths
+                    .into_iter()
+                    .map(|path| {
+                        let search_text_provider = search_text_providers
+                            .iter()
+                            .find(|provider| provider.handles_path(&path.path))
+                            .cloned();
+                        Self::open_buffer_to_search(
+                            buffer_store.clone(),
+                            path,
+                            search_text_provider,
+                            cx.clone(),
+                        )
+                    })
+                    .collect::<FuturesOrdered<_>>();
 
                 while let Some(buffer) = buffers.next().await {
                     if let Some(buffer) = buffer.log_err() {
@@ -524,6 +552,53 @@ impl Search {
         .await;
     }
 
+    /// Opens the buffer at the given path, or for files handled by a [`SearchTextProvider`], a
+    /// read-only buffer holding the text it provides.
+    async fn open_buffer_to_search(
+        buffer_store: Entity<BufferStore>,
+        path: ProjectPath,
+        search_text_provider: Option<Arc<dyn SearchTextProvider>>,
+        mut cx: AsyncApp,
+    ) -> Result<Entity<Buffer>> {
+        let buffer = buffer_store
+            .update(&mut cx, |this, cx| this.open_buffer(path, cx))
+            .await?;
+        let Some(search_text_provider) = search_text_provider else {
+            return Ok(buffer);
+        };
+        let (contents, file) =
+            buffer.read_with(&mut cx, |buffer, _| (buffer.text(), buffer.file().cloned()));
+        let Some(file) = file else {
+            return Ok(buffer);
+        };
+
+        let search_text = cx
+            .background_spawn(async move { search_text_provider.search_text(&contents) })
+            .await;
+        match search_text {
+            Ok(search_text) => Ok(cx.new(|cx| {
+                let text = text::Buffer::new(
+                    ReplicaId::LOCAL,
+                    cx.entity_id().as_non_zero_u64().into(),
+                    search_text,
+                );
+                Buffer::build(
+                    text,
+                    Some(Arc::new(SearchTextFile::new(file))),
+                    Capability::ReadOnly,
+                )
+            })),
+            Err(error) => {
+                // Search the raw contents of files the provider fails to convert.
+                log::debug!(
+                    "failed to get the searchable text of {:?}: {error:#}",
+                    file.path()
+                );
+                Ok(buffer)
+            }
+        }
+    }
+
     async fn grab_buffer_snapshots(
         rx: Receiver<Entity<Buffer>>,
         find_all_matches_tx: Sender<(
@@ -636,21 +711,28 @@ impl Worker {
             confirm_contents_will_match_rx,
             mut confirm_contents_will_match_tx,
             fs,
+            search_text_providers,
         ) = match self.candidates {
             FindSearchCandidates::Local {
                 fs,
                 input_paths_rx,
                 confirm_contents_will_match_rx,
                 confirm_contents_will_match_tx,
+                search_text_providers,
             } => (
                 input_paths_rx,
                 confirm_contents_will_match_rx,
                 confirm_contents_will_match_tx,
                 Some(fs),
+                search_text_providers,
+            ),
+            FindSearchCandidates::Remote | FindSearchCandidates::OpenBuffersOnly => (
+                unbounded().1,
+                unbounded().1,
+                unbounded().0,
+                None,
+                Arc::new([]) as Arc<[_]>,
             ),
-            FindSearchCandidates::Remote | FindSearchCandidates::OpenBuffersOnly => {
-                (unbounded().1, unbounded().1, unbounded().0, None)
-            }
         };
         // WorkerA: grabs a request for "find all matches in file/a" <- takes 5 minutes
         // right after: WorkerB: grabs a request for "find all matches in file/b" <- takes 5 seconds
@@ -664,6 +746,7 @@ impl Worker {
                 open_entries: &self.open_buffers,
                 fs: fs.as_deref(),
                 confirm_contents_will_match_tx: &confirm_contents_will_match_tx,
+                search_text_providers: &search_text_providers,
             };
             // Whenever we notice that some step of a pipeline is closed, we don't want to close subsequent
             // steps straight away. Another worker might be about to produce a value that will
@@ -704,9 +787,17 @@ struct RequestHandler<'worker> {
     fs: Option<&'worker dyn Fs>,
     open_entries: &'worker HashSet<ProjectEntryId>,
     confirm_contents_will_match_tx: &'worker Sender<MatchingEntry>,
+    search_text_providers: &'worker [Arc<dyn SearchTextProvider>],
 }
 
 impl RequestHandler<'_> {
+    fn search_text_provider(&self, path: &RelPath) -> Option<&dyn SearchTextProvider> {
+        self.search_text_providers
+            .iter()
+            .find(|provider| provider.handles_path(path))
+            .map(|provider| provider.as_ref())
+    }
+
     async fn handle_find_all_matches(
         &self,
         (buffer, snapshot, mut report_matches): (
@@ -729,7 +820,22 @@ impl RequestHandler<'_> {
     async fn handle_find_first_match(&self, mut entry: MatchingEntry) {
         _=maybe!(async move {
             let abs_path = entry.worktree_root.join(entry.path.path.as_std_path());
-            let Some(file) = self.fs.context("Trying to query filesystem in remote project search")?.open_sync(&abs_path).await.log_err() else {
+            let fs = self.fs.context("Trying to query filesystem in remote project search")?;
+            if let Some(search_text_provider) = self.search_text_provider(&entry.path.path) {
+                // Like `open_buffer_to_search`, fall back to the raw contents of files the provider fails to convert.
+                let contents = fs.load(&abs_path).await?;
+                let search_text = search_text_provider
+                    .search_text(&contents)
+                    .unwrap_or(contents);
+                let search_text: Box<dyn Read + Send + Sync> =
+                    Box::new(io::Cursor::new(search_text.into_bytes()));
+                if self.query.detect(BufReader::new(search_text)).await.unwrap_or(false) {
+                    entry.should_scan_tx.send(entry.path).await?;
+                }
+                return Ok(());
+            }
+
+            let Some(file) = fs.open_sync(&abs_path).await.log_err() else {
                 return anyhow::Ok(());
             };
 
@@ -780,7 +886,9 @@ impl RequestHandler<'_> {
                 }
             }
 
-            if self.open_entries.contains(&entry.id) {
+            if self.open_entries.contains(&entry.id)
+                && self.search_text_provider(&entry.path).is_none()
+            {
                 // The buffer is already in memory and that's the version we want to scan;
                 // hence skip the dilly-dally and look for all matches straight away.
                 should_scan_tx
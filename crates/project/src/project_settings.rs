@@ -4,6 +4,7 @@ use context_server::ContextServerCommand;
 use dap::adapters::DebugAdapterName;
 use fs::Fs;
 use futures::StreamExt as _;
+use git::repository::{CommitSigning, SigningFormat};
 use gpui::{AsyncApp, BorrowAppContext, Context, Entity, EventEmitter, Subscription, Task};
 use lsp::LanguageServerName;
 use paths::{
@@ -415,6 +416,36 @@ pub struct GitSettings {
     ///
     /// Default: file_name_first
     pub path_style: GitPathStyle,
+    /// How commits made from Zed are signed.
+    pub commit_signing: CommitSigningSettings,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CommitSigningSettings {
+    /// Whether to sign commits.
+    ///
+    /// Default: git_config
+    pub sign: settings::CommitSigningMode,
+    /// The kind of key to sign commits with, or `None` to use git's
+    /// `gpg.format` config.
+    ///
+    /// Default: null
+    pub format: Option<settings::CommitSigningFormat>,
+}
+
+impl CommitSigningSettings {
+    pub fn commit_signing(&self) -> CommitSigning {
+        let format = self.format.map(|format| match format {
+            settings::CommitSigningFormat::Gpg => SigningFormat::OpenPgp,
+            settings::CommitSigningFormat::Ssh => SigningFormat::Ssh,
+            settings::CommitSigningFormat::X509 => SigningFormat::X509,
+        });
+        match self.sign {
+            settings::CommitSigningMode::GitConfig => CommitSigning::GitConfig(format),
+            settings::CommitSigningMode::Always => CommitSigning::Sign(format),
+            settings::CommitSigningMode::Never => CommitSigning::NoSign,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -604,6 +635,13 @@ impl Settings for ProjectSettings {
             },
             hunk_style: git.hunk_style.unwrap(),
             path_style: git.path_style.unwrap().into(),
+            commit_signing: {
+                let commit_signing = git.commit_signing.unwrap();
+                CommitSigningSettings {
+                    sign: commit_signing.sign.unwrap(),
+                    format: commit_signing.format,
+                }
+            },
         };
         Self {
             context_servers: project
@@ -0,0 +1,119 @@
+//! Project search over files whose raw contents aren't meant to be read as text, such as Jupyter
+//! notebooks, which store the sources and outputs of their cells as JSON.
+
+use std::{any::Any, borrow::Borrow, path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use gpui::{App, Global};
+use language::{DiskState, File};
+use util::{paths::PathStyle, rel_path::RelPath};
+use worktree::WorktreeId;
+
+use crate::ProjectPath;
+
+/// Converts the raw contents of some kind of file into the text that project search matches
+/// against.
+pub trait SearchTextProvider: Send + Sync {
+    /// Whether this provider converts the file at the given path.
+    fn handles_path(&self, path: &RelPath) -> bool;
+
+    /// Returns the text to search in a file with the given raw contents.
+    fn search_text(&self, contents: &str) -> Result<String>;
+}
+
+#[derive(Default)]
+struct GlobalSearchTextProviders(Vec<Arc<dyn SearchTextProvider>>);
+
+impl Global for GlobalSearchTextProviders {}
+
+/// Registers a [`SearchTextProvider`] for the app. Searches in local projects then match against
+/// the text it provides instead of the raw contents of the files it handles.
+pub fn register_search_text_provider(provider: Arc<dyn SearchTextProvider>, cx: &mut App) {
+    cx.default_global::<GlobalSearchTextProviders>()
+        .0
+        .push(provider);
+}
+
+pub(crate) fn search_text_providers(cx: &App) -> Vec<Arc<dyn SearchTextProvider>> {
+    cx.try_global::<GlobalSearchTextProviders>()
+        .map(|providers| providers.0.clone())
+        .unwrap_or_default()
+}
+
+/// The file of a read-only buffer holding the text a [`SearchTextProvider`] produced for another
+/// file. Opening a match in such a buffer opens the file it was produced from instead, and
+/// navigates it to a [`SearchTextPosition`].
+pub struct SearchTextFile {
+    file: Arc<dyn File>,
+}
+
+impl SearchTextFile {
+    pub fn new(file: Arc<dyn File>) -> Self {
+        Self { file }
+    }
+
+    pub fn from_dyn(file: Option<&Arc<dyn File>>) -> Option<&Self> {
+        file.and_then(|file| {
+            let file: &dyn File = file.borrow();
+            let file: &dyn Any = file;
+            file.downcast_ref()
+        })
+    }
+
+    /// The path of the file the searchable text was produced from.
+    pub fn project_path(&self, cx: &App) -> ProjectPath {
+        ProjectPath {
+            worktree_id: self.file.worktree_id(cx),
+            path: self.file.path().clone(),
+        }
+    }
+}
+
+impl File for SearchTextFile {
+    fn as_local(&self) -> Option<&dyn language::LocalFile> {
+        None
+    }
+
+    fn disk_state(&self) -> DiskState {
+        self.file.disk_state()
+    }
+
+    fn path(&self) -> &Arc<RelPath> {
+        self.file.path()
+    }
+
+    fn full_path(&self, cx: &App) -> PathBuf {
+        self.file.full_path(cx)
+    }
+
+    fn path_style(&self, cx: &App) -> PathStyle {
+        self.file.path_style(cx)
+    }
+
+    fn file_name<'a>(&'a self, cx: &'a App) -> &'a str {
+        self.file.file_name(cx)
+    }
+
+    fn worktree_id(&self, cx: &App) -> WorktreeId {
+        self.file.worktree_id(cx)
+    }
+
+    fn to_proto(&self, cx: &App) -> rpc::proto::File {
+        self.file.to_proto(cx)
+    }
+
+    fn is_private(&self) -> bool {
+        self.file.is_private()
+    }
+
+    fn can_open(&self) -> bool {
+        true
+    }
+}
+
+/// Navigation data for an item opened from a match in a [`SearchTextFile`] buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchTextPosition {
+    /// The offset of the match in the searchable text.
+    pub offset: usize,
+}
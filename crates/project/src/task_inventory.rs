@@ -21,8 +21,8 @@ use lsp::{LanguageServerId, LanguageServerName};
 use paths::{debug_task_file_name, task_file_name};
 use settings::{InvalidSettingsError, parse_json_with_comments};
 use task::{
-    DebugScenario, ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates, TaskVariables,
-    VariableName,
+    DebugScenario, ResolvedTask, TaskContext, TaskGraph, TaskId, TaskTemplate, TaskTemplates,
+    TaskVariables, VariableName,
 };
 use text::{BufferId, Point, ToPoint};
 use util::{NumericPrefixWithSuffix, ResultExt as _, post_inc, rel_path::RelPath};
@@ -237,6 +237,14 @@ impl TaskSourceKind {
             } => format!("lsp_{language_name}_{server}"),
         }
     }
+
+    /// The worktree the tasks come from, if they were defined in one.
+    pub fn worktree(&self) -> Option<WorktreeId> {
+        match self {
+            Self::Worktree { id, .. } => Some(*id),
+            _ => None,
+        }
+    }
 }
 
 impl Inventory {
@@ -373,6 +381,33 @@ impl Inventory {
         })
     }
 
+    /// Resolves the tasks the given task depends on, recursively, with the same context the task
+    /// was resolved with.
+    ///
+    /// Dependencies are looked up by label among the tasks of the task's worktree and the global
+    /// tasks, worktree tasks first.
+    pub fn task_dependency_graph(
+        &self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        cx: &App,
+    ) -> Task<Result<TaskGraph<(TaskSourceKind, ResolvedTask)>>> {
+        let tasks = self.list_tasks(None, None, task_source_kind.worktree(), cx);
+        cx.background_spawn(async move {
+            let tasks = tasks.await;
+            let task_context = resolved_task.task_context().clone();
+            TaskGraph::build(
+                (task_source_kind, resolved_task),
+                |(_, task)| task.original_task(),
+                |label| {
+                    let (kind, template) = tasks.iter().find(|(_, task)| task.label == label)?;
+                    let resolved = template.resolve_task(&kind.to_id_base(), &task_context)?;
+                    Some((kind.clone(), resolved))
+                },
+            )
+        })
+    }
+
     /// Pulls its task sources relevant to the worktree and the language given,
     /// returns all task templates with their source kinds, worktree tasks first, language tasks second
     /// and global tasks last. No specific order inside source kinds groups.
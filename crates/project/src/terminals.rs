@@ -242,6 +242,7 @@ impl Project {
                         cx,
                         activation_script,
                         path_style,
+                        None,
                     ))
                 })??
                 .await?;
@@ -276,7 +277,17 @@ impl Project {
         cwd: Option<PathBuf>,
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
-        self.create_terminal_shell_internal(cwd, false, cx)
+        self.create_terminal_shell_internal(cwd, false, None, cx)
+    }
+
+    /// Creates a terminal shell with output saved from a previous session shown above its prompt.
+    pub fn restore_terminal_shell(
+        &mut self,
+        cwd: Option<PathBuf>,
+        scrollback: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Terminal>>> {
+        self.create_terminal_shell_internal(cwd, false, scrollback, cx)
     }
 
     /// Creates a local terminal even if the project is remote.
@@ -293,7 +304,7 @@ impl Project {
             // Local project: use project directory like normal terminals
             self.active_project_directory(cx).map(|p| p.to_path_buf())
         };
-        self.create_terminal_shell_internal(working_directory, true, cx)
+        self.create_terminal_shell_internal(working_directory, true, None, cx)
     }
 
     /// Internal method for creating terminal shells.
@@ -303,6 +314,7 @@ impl Project {
         &mut self,
         cwd: Option<PathBuf>,
         force_local: bool,
+        scrollback: Option<String>,
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
         let path = cwd.map(|p| Arc::from(&*p));
@@ -407,6 +419,7 @@ impl Project {
                         cx,
                         activation_script,
                         path_style,
+                        scrollback,
                     ))
                 })??
                 .await?;
@@ -22,6 +22,10 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            problem_matchers: Vec::new(),
+            inputs: Vec::new(),
         };
 
         let scenario = locator
@@ -49,6 +53,10 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            problem_matchers: Vec::new(),
+            inputs: Vec::new(),
         };
 
         let scenario = locator
@@ -187,6 +195,10 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            problem_matchers: Vec::new(),
+            inputs: Vec::new(),
         };
 
         let scenario = locator
@@ -221,6 +233,10 @@ mod python_locator {
             shell: task::Shell::System,
             show_summary: false,
             show_command: false,
+            depends_on: Vec::new(),
+            depends_order: Default::default(),
+            problem_matchers: Vec::new(),
+            inputs: Vec::new(),
         };
 
         let expected_scenario = DebugScenario {
@@ -247,6 +263,164 @@ mod python_locator {
     }
 }
 
+mod ruby_locator {
+    use dap::{DapLocator, adapters::DebugAdapterName};
+    use serde_json::json;
+
+    use project::debugger::locators::ruby::*;
+    use task::TaskTemplate;
+
+    fn task(command: &str, args: &[&str]) -> TaskTemplate {
+        TaskTemplate {
+            label: "test".into(),
+            command: command.into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..TaskTemplate::default()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_ruby_locator() {
+        let adapter = DebugAdapterName("rdbg".into());
+
+        let scenario = RubyLocator
+            .create_scenario(
+                &task("bundle", &["exec", "rspec", "$ZED_RELATIVE_FILE:$ZED_ROW"]),
+                "rspec",
+                &adapter,
+            )
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "command": "rspec",
+                "args": ["$ZED_RELATIVE_FILE:$ZED_ROW"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+                "env": {},
+                "useBundler": true,
+            })
+        );
+
+        let scenario = RubyLocator
+            .create_scenario(&task("ruby", &["$ZED_FILE", "--verbose"]), "ruby", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "script": "$ZED_FILE",
+                "args": ["--verbose"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+                "env": {},
+            })
+        );
+
+        assert!(
+            RubyLocator
+                .create_scenario(&task("ruby", &["-e", "puts 1"]), "ruby", &adapter)
+                .await
+                .is_none()
+        );
+        assert!(
+            RubyLocator
+                .create_scenario(&task("bundle", &["install"]), "bundle", &adapter)
+                .await
+                .is_none()
+        );
+        assert!(
+            RubyLocator
+                .create_scenario(
+                    &task("rspec", &[]),
+                    "rspec",
+                    &DebugAdapterName("CodeLLDB".into())
+                )
+                .await
+                .is_none()
+        );
+    }
+}
+
+mod php_locator {
+    use dap::{DapLocator, adapters::DebugAdapterName};
+    use serde_json::json;
+
+    use project::debugger::locators::php::*;
+    use task::TaskTemplate;
+
+    fn task(command: &str, args: &[&str]) -> TaskTemplate {
+        TaskTemplate {
+            label: "test".into(),
+            command: command.into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..TaskTemplate::default()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_php_locator() {
+        let adapter = DebugAdapterName("Xdebug".into());
+
+        let scenario = PhpLocator
+            .create_scenario(
+                &task("vendor/bin/phpunit", &["--filter", "$ZED_SYMBOL"]),
+                "phpunit",
+                &adapter,
+            )
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "program": "vendor/bin/phpunit",
+                "args": ["--filter", "$ZED_SYMBOL"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+                "env": {},
+                "port": 0,
+            })
+        );
+
+        let scenario = PhpLocator
+            .create_scenario(
+                &task("php", &["-dmemory_limit=-1", "$ZED_FILE", "arg"]),
+                "php",
+                &adapter,
+            )
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "program": "$ZED_FILE",
+                "args": ["arg"],
+                "runtimeArgs": ["-dmemory_limit=-1"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+                "env": {},
+                "port": 0,
+            })
+        );
+
+        assert!(
+            PhpLocator
+                .create_scenario(&task("php", &["-r", "echo 1;"]), "php", &adapter)
+                .await
+                .is_none()
+        );
+        assert!(
+            PhpLocator
+                .create_scenario(&task("composer", &["install"]), "composer", &adapter)
+                .await
+                .is_none()
+        );
+    }
+}
+
 mod memory {
     use project::debugger::{
         MemoryCell,
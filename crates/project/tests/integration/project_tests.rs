@@ -29,9 +29,10 @@ use encoding_rs;
 use fs::FakeFs;
 use futures::{StreamExt, future};
 use git::{
-    GitHostingProviderRegistry,
-    repository::{RepoPath, repo_path},
+    GitHostingProviderRegistry, Oid,
+    repository::{AskPassDelegate, RepoPath, repo_path},
     status::{FileStatus, StatusCode, TrackedStatus},
+    submodule::{Submodule, SubmoduleState},
 };
 use git2::RepositoryInitOptions;
 use gpui::{
@@ -59,6 +60,7 @@ use pretty_assertions::{assert_eq, assert_matches};
 use project::{
     Event, TaskContexts,
     git_store::{GitStoreEvent, Repository, RepositoryEvent, StatusEntry, pending_op},
+    lsp_store::task_diagnostics::TASK_DIAGNOSTICS_SERVER_ID,
     search::{SearchQuery, SearchResult},
     task_store::{TaskSettingsLocation, TaskStore},
     *,
@@ -81,7 +83,7 @@ use std::{
     time::Duration,
 };
 use sum_tree::SumTree;
-use task::{ResolvedTask, ShellKind, TaskContext};
+use task::{Problem, ProblemSeverity, ResolvedTask, ShellKind, TaskContext};
 use text::{Anchor, PointUtf16, ReplicaId, ToOffset, Unclipped};
 use unindent::Unindent as _;
 use util::{
@@ -3548,6 +3550,123 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_task_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "one two three",
+            "b.rs": "four five",
+            "src": { "c.rs": "six seven" },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [Path::new(path!("/dir"))], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let problem = |file: &str, severity: ProblemSeverity| Problem {
+        file: PathBuf::from(file),
+        line: 1,
+        column: Some(5),
+        end_line: None,
+        end_column: None,
+        severity,
+        code: None,
+        message: format!("problem in {file}"),
+    };
+    let task_summaries = |cx: &mut gpui::TestAppContext| {
+        project.read_with(cx, |project, cx| {
+            project
+                .diagnostic_summaries(false, cx)
+                .filter(|(_, server_id, _)| *server_id == TASK_DIAGNOSTICS_SERVER_ID)
+                .map(|(path, _, summary)| {
+                    (
+                        path.path.as_unix_str().to_string(),
+                        summary.error_count,
+                        summary.warning_count,
+                    )
+                })
+                .sorted()
+                .collect::<Vec<_>>()
+        })
+    };
+
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics(
+                "build",
+                vec![
+                    problem(path!("/dir/a.rs"), ProblemSeverity::Error),
+                    problem(path!("/dir/b.rs"), ProblemSeverity::Warning),
+                    problem(path!("/elsewhere/c.rs"), ProblemSeverity::Error),
+                    problem("src/c.rs", ProblemSeverity::Error),
+                    problem("relative/d.rs", ProblemSeverity::Error),
+                ],
+                cx,
+            )
+            .unwrap();
+        lsp_store
+            .update_task_diagnostics(
+                "lint",
+                vec![problem(path!("/dir/a.rs"), ProblemSeverity::Warning)],
+                cx,
+            )
+            .unwrap();
+    });
+    assert_eq!(
+        task_summaries(cx),
+        vec![
+            ("a.rs".to_string(), 1, 1),
+            ("b.rs".to_string(), 0, 1),
+            ("src/c.rs".to_string(), 1, 0),
+        ]
+    );
+
+    // Running a task again replaces its diagnostics, but keeps the ones from other tasks.
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics(
+                "build",
+                vec![problem(path!("/dir/b.rs"), ProblemSeverity::Error)],
+                cx,
+            )
+            .unwrap();
+    });
+    assert_eq!(
+        task_summaries(cx),
+        vec![("a.rs".to_string(), 0, 1), ("b.rs".to_string(), 1, 0)]
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/b.rs"), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .collect::<Vec<_>>();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range, Point::new(0, 4)..Point::new(0, 4));
+        assert_eq!(diagnostics[0].diagnostic.source.as_deref(), Some("build"));
+    });
+
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics("build", Vec::new(), cx)
+            .unwrap();
+        lsp_store
+            .update_task_diagnostics("lint", Vec::new(), cx)
+            .unwrap();
+    });
+    assert_eq!(task_summaries(cx), Vec::new());
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -10918,6 +11037,97 @@ async fn test_git_worktrees_and_submodules(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_submodule_pointer_changes(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {
+                "modules": {
+                    "some-submodule": {
+                        // For is_git_dir
+                        "HEAD": "",
+                        "config": "",
+                    }
+                }
+            },
+            ".gitmodules": "[submodule \"some-submodule\"]\n\tpath = some-submodule\n",
+            "some-submodule": {
+                ".git": "gitdir: ../.git/modules/some-submodule\n",
+                "a.txt": "A",
+            },
+        }),
+    )
+    .await;
+
+    let old_sha = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
+    let new_sha = Oid::from_str("2222222222222222222222222222222222222222").unwrap();
+    fs.set_submodules_for_repo(
+        path!("/project/.git").as_ref(),
+        vec![Submodule {
+            path: repo_path("some-submodule"),
+            url: Some("https://example.com/some-submodule.git".into()),
+            head_sha: Some(old_sha),
+            current_sha: Some(new_sha),
+            state: SubmoduleState::Modified,
+        }],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    let scan_complete = project.update(cx, |project, cx| project.git_scans_complete(cx));
+    scan_complete.await;
+    cx.run_until_parked();
+
+    let (repo, submodule_repo) = project.update(cx, |project, cx| {
+        let git_store = project.git_store().read(cx);
+        let repo = git_store
+            .repositories()
+            .values()
+            .find(|repo| *repo.read(cx).work_directory_abs_path == *Path::new(path!("/project")))
+            .unwrap()
+            .clone();
+        let submodule_repo = git_store
+            .repository_for_submodule(&repo, &repo_path("some-submodule"), cx)
+            .unwrap();
+        (repo, submodule_repo)
+    });
+    submodule_repo.read_with(cx, |submodule_repo, _| {
+        assert_eq!(
+            submodule_repo.work_directory_abs_path,
+            Path::new(path!("/project/some-submodule")).into(),
+        );
+    });
+    repo.read_with(cx, |repo, _| {
+        let submodule = repo
+            .submodule_for_path(&repo_path("some-submodule"))
+            .unwrap();
+        assert!(submodule.is_initialized());
+        assert_eq!(submodule.pointer_change(), Some((Some(old_sha), new_sha)));
+    });
+
+    let update = repo.update(cx, |repo, cx| {
+        repo.update_submodules(
+            vec![repo_path("some-submodule")],
+            false,
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            cx,
+        )
+    });
+    update.await.unwrap().unwrap();
+    cx.run_until_parked();
+
+    repo.read_with(cx, |repo, _| {
+        let submodule = repo
+            .submodule_for_path(&repo_path("some-submodule"))
+            .unwrap();
+        assert_eq!(submodule.state, SubmoduleState::UpToDate);
+        assert_eq!(submodule.pointer_change(), None);
+    });
+}
+
 #[gpui::test]
 async fn test_repository_deduplication(cx: &mut gpui::TestAppContext) {
     init_test(cx);
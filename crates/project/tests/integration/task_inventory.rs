@@ -6,6 +6,7 @@ use serde_json::json;
 use settings::SettingsLocation;
 use std::path::Path;
 use std::sync::Arc;
+use task::{DependsOrder, TaskContext};
 use util::rel_path::rel_path;
 
 use project::task_store::{TaskSettingsLocation, TaskStore};
@@ -560,6 +561,113 @@ async fn test_inventory_static_task_filters(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_task_dependency_graph(cx: &mut TestAppContext) {
+    init_test(cx);
+    let inventory = cx.update(|cx| Inventory::new(cx));
+    let worktree_id = WorktreeId::from_usize(1);
+
+    inventory.update(cx, |inventory, _| {
+        inventory
+            .update_file_based_tasks(
+                TaskSettingsLocation::Global(tasks_file()),
+                Some(
+                    &json!([
+                        { "label": "lint", "command": "cargo clippy" },
+                        { "label": "build", "command": "echo global build" },
+                    ])
+                    .to_string(),
+                ),
+            )
+            .unwrap();
+        inventory
+            .update_file_based_tasks(
+                TaskSettingsLocation::Worktree(SettingsLocation {
+                    worktree_id,
+                    path: rel_path(".zed"),
+                }),
+                Some(
+                    &json!([
+                        { "label": "build", "command": "cargo build" },
+                        { "label": "test", "command": "cargo test", "depends_on": ["build"] },
+                        {
+                            "label": "ci",
+                            "depends_on": ["lint", "test"],
+                            "depends_order": "parallel",
+                        },
+                        { "label": "broken", "depends_on": ["missing"] },
+                    ])
+                    .to_string(),
+                ),
+            )
+            .unwrap();
+    });
+
+    let (kind, ci) = inventory
+        .update(cx, |inventory, cx| {
+            inventory.list_tasks(None, None, Some(worktree_id), cx)
+        })
+        .await
+        .into_iter()
+        .find(|(_, task)| task.label == "ci")
+        .unwrap();
+    assert!(ci.is_compound());
+    let ci = ci
+        .resolve_task(&kind.to_id_base(), &TaskContext::default())
+        .unwrap();
+
+    let graph = inventory
+        .update(cx, |inventory, cx| {
+            inventory.task_dependency_graph(kind.clone(), ci, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(graph.order, DependsOrder::Parallel);
+    let dependencies = graph
+        .dependencies
+        .iter()
+        .map(|dependency| {
+            let (_, task) = &dependency.task;
+            let nested = dependency
+                .dependencies
+                .iter()
+                .map(|nested| nested.task.1.resolved.command_label.clone())
+                .collect::<Vec<_>>();
+            (task.resolved.command_label.clone(), nested)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        dependencies,
+        vec![
+            ("cargo clippy".to_string(), Vec::new()),
+            ("cargo test".to_string(), vec!["cargo build".to_string()]),
+        ],
+        "Worktree tasks should be preferred over global tasks with the same label"
+    );
+
+    let (kind, broken) = inventory
+        .update(cx, |inventory, cx| {
+            inventory.list_tasks(None, None, Some(worktree_id), cx)
+        })
+        .await
+        .into_iter()
+        .find(|(_, task)| task.label == "broken")
+        .unwrap();
+    let broken = broken
+        .resolve_task(&kind.to_id_base(), &TaskContext::default())
+        .unwrap();
+    let error = inventory
+        .update(cx, |inventory, cx| {
+            inventory.task_dependency_graph(kind, broken, cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Task `broken` depends on unknown task `missing`"
+    );
+}
+
 fn init_test(_cx: &mut TestAppContext) {
     zlog::init_test();
     TaskStore::init(None);
@@ -246,6 +246,8 @@ messages!(
     (SyncExtensionsResponse, Background),
     (BreakpointsForFile, Background),
     (ToggleBreakpoint, Foreground),
+    (FunctionBreakpoints, Background),
+    (UpdateFunctionBreakpoints, Foreground),
     (SynchronizeBuffers, Foreground),
     (SynchronizeBuffersResponse, Foreground),
     (SynchronizeContexts, Foreground),
@@ -290,8 +292,14 @@ messages!(
     (GitCheckoutFiles, Background),
     (GitShow, Background),
     (GitCommitDetails, Background),
+    (GitGetCommitSignature, Background),
+    (GitCommitSignature, Background),
     (GitFileHistory, Background),
     (GitFileHistoryResponse, Background),
+    (GitLineHistory, Background),
+    (GitLineHistoryResponse, Background),
+    (GitLoadMergeStages, Background),
+    (GitLoadMergeStagesResponse, Background),
     (SetIndexText, Background),
     (Push, Background),
     (Fetch, Background),
@@ -303,6 +311,7 @@ messages!(
     (AskPassResponse, Background),
     (GitCreateRemote, Background),
     (GitRemoveRemote, Background),
+    (GitUpdateSubmodules, Background),
     (GitCreateBranch, Background),
     (GitChangeBranch, Background),
     (GitRenameBranch, Background),
@@ -502,7 +511,10 @@ request_messages!(
     (InstallExtension, Ack),
     (RegisterBufferWithLanguageServers, Ack),
     (GitShow, GitCommitDetails),
+    (GitGetCommitSignature, GitCommitSignature),
     (GitFileHistory, GitFileHistoryResponse),
+    (GitLineHistory, GitLineHistoryResponse),
+    (GitLoadMergeStages, GitLoadMergeStagesResponse),
     (GitReset, Ack),
     (GitDeleteBranch, Ack),
     (GitCheckoutFiles, Ack),
@@ -514,6 +526,7 @@ request_messages!(
     (AskPassRequest, AskPassResponse),
     (GitCreateRemote, Ack),
     (GitRemoveRemote, Ack),
+    (GitUpdateSubmodules, RemoteMessageResponse),
     (GitCreateBranch, Ack),
     (GitChangeBranch, Ack),
     (GitRenameBranch, Ack),
@@ -521,6 +534,7 @@ request_messages!(
     (GitDiff, GitDiffResponse),
     (GitInit, Ack),
     (ToggleBreakpoint, Ack),
+    (UpdateFunctionBreakpoints, Ack),
     (GetDebugAdapterBinary, DebugAdapterBinary),
     (RunDebugLocators, DebugRequest),
     (GetDocumentDiagnostics, GetDocumentDiagnosticsResponse),
@@ -674,7 +688,10 @@ entity_messages!(
     CancelLanguageServerWork,
     RegisterBufferWithLanguageServers,
     GitShow,
+    GitGetCommitSignature,
     GitFileHistory,
+    GitLineHistory,
+    GitLoadMergeStages,
     GitReset,
     GitDeleteBranch,
     GitCheckoutFiles,
@@ -692,11 +709,14 @@ entity_messages!(
     GitCreateBranch,
     GitCreateRemote,
     GitRemoveRemote,
+    GitUpdateSubmodules,
     CheckForPushedCommits,
     GitDiff,
     GitInit,
     BreakpointsForFile,
     ToggleBreakpoint,
+    FunctionBreakpoints,
+    UpdateFunctionBreakpoints,
     RunDebugLocators,
     GetDebugAdapterBinary,
     LogToDebugConsole,
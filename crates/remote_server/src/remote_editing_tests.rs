@@ -9,8 +9,12 @@ use collections::{HashMap, HashSet};
 use language_model::{LanguageModelToolResultContent, fake_provider::FakeLanguageModel};
 use prompt_store::ProjectContext;
 
+use askpass::AskPassDelegate;
 use extension::ExtensionHostProxy;
 use fs::{FakeFs, Fs};
+use git::repository::{
+    CommitOptions, CommitSignature, CommitSigning, RepoPath, SignatureStatus, SigningFormat,
+};
 use gpui::{AppContext as _, Entity, SharedString, TestAppContext};
 use http_client::{BlockedHttpClient, FakeHttpClient};
 use language::{
@@ -1600,6 +1604,75 @@ async fn test_remote_git_diffs(cx: &mut TestAppContext, server_cx: &mut TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_remote_stage_lines(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "foo.txt": "one\nADDED_1\nADDED_2\ntwo\n",
+            },
+        }),
+    )
+    .await;
+    fs.set_head_and_index_for_repo(
+        Path::new("/code/project1/.git"),
+        &[("foo.txt", "one\ntwo\n".into())],
+    );
+
+    let (project, _headless) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = cx.update(|cx| worktree.read(cx).id());
+    cx.executor().run_until_parked();
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("foo.txt")), cx)
+        })
+        .await
+        .unwrap();
+    let diff = project
+        .update(cx, |project, cx| {
+            project.open_uncommitted_diff(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let index_text = || {
+        fs.with_git_state(Path::new("/code/project1/.git"), false, |state| {
+            state
+                .index_contents
+                .get(&RepoPath::from_rel_path(rel_path("foo.txt")))
+                .cloned()
+        })
+        .unwrap()
+    };
+
+    diff.update(cx, |diff, cx| {
+        let snapshot = buffer.read(cx).text_snapshot();
+        diff.stage_or_unstage_lines(true, &[1..2], &snapshot, true, cx);
+    });
+    cx.executor().run_until_parked();
+    server_cx.executor().run_until_parked();
+    assert_eq!(index_text().as_deref(), Some("one\nADDED_1\ntwo\n"));
+
+    diff.update(cx, |diff, cx| {
+        let snapshot = buffer.read(cx).text_snapshot();
+        diff.stage_or_unstage_lines(false, &[1..2], &snapshot, true, cx);
+    });
+    cx.executor().run_until_parked();
+    server_cx.executor().run_until_parked();
+    assert_eq!(index_text().as_deref(), Some("one\ntwo\n"));
+}
+
 #[gpui::test]
 async fn test_remote_git_diffs_when_recv_update_repository_delay(
     cx: &mut TestAppContext,
@@ -1745,6 +1818,79 @@ async fn test_remote_git_diffs_when_recv_update_repository_delay(
     });
 }
 
+#[gpui::test]
+async fn test_remote_git_commit_signing(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+            },
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/code/project1/.git"));
+    let signature = CommitSignature {
+        status: SignatureStatus::Verified,
+        signer: "Jane Doe <jane@example.com>".into(),
+        key: "ABCDEF0123456789".into(),
+    };
+    fs.set_commit_signatures_for_repo(dot_git, vec![("abc".into(), signature.clone())]);
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let repository = project.update(cx, |project, cx| project.active_repository(cx).unwrap());
+
+    let options = [
+        CommitSigning::GitConfig(None),
+        CommitSigning::GitConfig(Some(SigningFormat::Ssh)),
+        CommitSigning::Sign(Some(SigningFormat::X509)),
+        CommitSigning::NoSign,
+    ]
+    .map(|signing| CommitOptions {
+        amend: false,
+        signoff: true,
+        signing,
+    });
+    for options in options {
+        let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+        repository
+            .update(cx, |repository, cx| {
+                repository.commit("message".into(), None, options, askpass, cx)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    assert_eq!(fs.commit_options_for_repo(dot_git), options);
+
+    let remote_signature = repository
+        .update(cx, |repository, _| {
+            repository.commit_signature("abc".into())
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(remote_signature, signature);
+    let unsigned = repository
+        .update(cx, |repository, _| {
+            repository.commit_signature("def".into())
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(unsigned.status, SignatureStatus::Unsigned);
+}
+
 #[gpui::test]
 async fn test_remote_git_branches(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
@@ -156,9 +156,13 @@ impl PickerDelegate for KernelPickerDelegate {
                 "Python Env",
                 Some(truncate_path(&kernelspec.path(), 42)),
             ),
-            KernelSpecification::Remote(_) => (
+            KernelSpecification::Remote(remote_spec) => (
                 kernelspec.name(),
-                "Remote",
+                if remote_spec.kernel_id.is_some() {
+                    "Running"
+                } else {
+                    "Remote"
+                },
                 Some(truncate_path(&kernelspec.path(), 42)),
             ),
         };
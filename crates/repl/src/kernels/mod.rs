@@ -56,7 +56,7 @@ impl KernelSpecification {
         SharedString::from(match self {
             Self::Jupyter(spec) => spec.path.to_string_lossy().into_owned(),
             Self::PythonEnv(spec) => spec.path.to_string_lossy().into_owned(),
-            Self::Remote(spec) => spec.url.to_string(),
+            Self::Remote(spec) => spec.server.base_url.clone(),
         })
     }
 
@@ -169,6 +169,16 @@ pub trait RunningKernel: Send + Debug {
     fn set_execution_state(&mut self, state: ExecutionState);
     fn kernel_info(&self) -> Option<&KernelInfoReply>;
     fn set_kernel_info(&mut self, info: KernelInfoReply);
+    /// Interrupts the kernel through the server hosting it, for kernels that have one. Other
+    /// kernels are interrupted with an `interrupt_request` message.
+    fn interrupt(&mut self, _cx: &mut App) -> Option<Task<anyhow::Result<()>>> {
+        None
+    }
+    /// Restarts the kernel in place through the server hosting it, for kernels that have one.
+    /// Other kernels are shut down and launched again.
+    fn restart(&mut self, _cx: &mut App) -> Option<Task<anyhow::Result<()>>> {
+        None
+    }
     fn force_shutdown(&mut self, window: &mut Window, cx: &mut App) -> Task<anyhow::Result<()>>;
 }
 
@@ -1,122 +1,466 @@
 use futures::{SinkExt as _, channel::mpsc};
 use gpui::{App, AppContext as _, Entity, Task, Window};
-use http_client::{AsyncBody, HttpClient, Request};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, Method, RedirectPolicy, Request, Response,
+};
 use jupyter_protocol::{ExecutionState, JupyterKernelspec, JupyterMessage, KernelInfoReply};
 
 use async_tungstenite::tokio::connect_async;
 use async_tungstenite::tungstenite::{client::IntoClientRequest, http::HeaderValue};
 
 use futures::StreamExt;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
 use smol::io::AsyncReadExt as _;
 
 use super::{KernelSession, RunningKernel};
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use jupyter_websocket_client::{
     JupyterWebSocket, JupyterWebSocketReader, JupyterWebSocketWriter, KernelLaunchRequest,
-    KernelSpecsResponse, RemoteServer,
 };
+use std::collections::BTreeMap;
 use std::{fmt::Debug, sync::Arc};
 
-#[derive(Debug, Clone)]
-pub struct RemoteKernelSpecification {
+/// A connection to a Jupyter server, such as one started with `jupyter server`.
+#[derive(Clone)]
+pub struct JupyterServer {
     pub name: String,
-    pub url: String,
-    pub token: String,
-    pub kernelspec: JupyterKernelspec,
+    pub base_url: String,
+    credentials: Credentials,
+    http_client: Arc<dyn HttpClient>,
 }
 
-pub async fn launch_remote_kernel(
-    remote_server: &RemoteServer,
-    http_client: Arc<dyn HttpClient>,
-    kernel_name: &str,
-    _path: &str,
-) -> Result<String> {
-    //
-    let kernel_launch_request = KernelLaunchRequest {
-        name: kernel_name.to_string(),
-        // Note: since the path we have locally may not be the same as the one on the remote server,
-        // we don't send it. We'll have to evaluate this decision along the way.
-        path: None,
-    };
-
-    let kernel_launch_request = serde_json::to_string(&kernel_launch_request)?;
-
-    let request = Request::builder()
-        .method("POST")
-        .uri(&remote_server.api_url("/kernels"))
-        .header("Authorization", format!("token {}", remote_server.token))
-        .body(AsyncBody::from(kernel_launch_request))?;
-
-    let response = http_client.send(request).await?;
-
-    if !response.status().is_success() {
+/// How requests to a Jupyter server are authenticated.
+#[derive(Clone, Default)]
+enum Credentials {
+    #[default]
+    None,
+    Token(String),
+    /// The cookies set by logging in with a password, and the XSRF token the server expects on
+    /// requests that change state.
+    Cookies {
+        cookies: String,
+        xsrf_token: Option<String>,
+    },
+}
+
+/// A kernel running on a Jupyter server, as listed by `GET /api/kernels`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RemoteKernel {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub execution_state: Option<String>,
+    #[serde(default)]
+    pub last_activity: Option<String>,
+    #[serde(default)]
+    pub connections: Option<u64>,
+}
+
+/// A session on a Jupyter server, which ties a running kernel to a notebook or console.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RemoteSession {
+    pub id: String,
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub session_type: String,
+    pub kernel: RemoteKernel,
+}
+
+#[derive(Deserialize)]
+struct KernelSpecsResponse {
+    kernelspecs: BTreeMap<String, KernelSpecResponse>,
+}
+
+#[derive(Deserialize)]
+struct KernelSpecResponse {
+    spec: JupyterKernelspec,
+}
+
+impl JupyterServer {
+    /// Connects to the server at `url`, logging in first if a password is given.
+    pub async fn connect(
+        name: String,
+        url: &str,
+        token: Option<String>,
+        password: Option<String>,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Self> {
+        let mut server = Self {
+            name,
+            base_url: url.trim_end_matches('/').to_string(),
+            credentials: token
+                .filter(|token| !token.is_empty())
+                .map(Credentials::Token)
+                .unwrap_or_default(),
+            http_client,
+        };
+
+        if let Some(password) = password {
+            server
+                .log_in(&password)
+                .await
+                .with_context(|| format!("Failed to log in to {}", server.base_url))?;
+        }
+
+        Ok(server)
+    }
+
+    /// Logs in the way Jupyter's login page does: fetch an XSRF cookie, then post the password
+    /// along with it, and keep the session cookies the server sets in return.
+    ///
+    /// Redirects aren't followed, since the redirect is how a successful login is told apart from
+    /// a login page shown again.
+    async fn log_in(&mut self, password: &str) -> Result<()> {
+        let login_url = format!("{}/login", self.base_url);
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(&login_url)
+            .follow_redirects(RedirectPolicy::NoFollow)
+            .body(AsyncBody::default())?;
+        let response = self.http_client.send(request).await?;
+        let mut cookies = set_cookies(&response);
+        let xsrf_token = cookies
+            .iter()
+            .find(|(name, _)| name == "_xsrf")
+            .map(|(_, value)| value.clone());
+
+        let mut form = format!("password={}", form_encode(password));
+        if let Some(xsrf_token) = &xsrf_token {
+            form.push_str(&format!("&_xsrf={}", form_encode(xsrf_token)));
+        }
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(&login_url)
+            .follow_redirects(RedirectPolicy::NoFollow)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Cookie", cookie_header(&cookies))
+            .when_some(xsrf_token.clone(), |builder, xsrf_token| {
+                builder.header("X-XSRFToken", xsrf_token)
+            })
+            .body(AsyncBody::from(form))?;
+        let response = self.http_client.send(request).await?;
+
+        // A successful login redirects away from the login page.
+        anyhow::ensure!(
+            response.status().is_redirection(),
+            "Incorrect password ({})",
+            response.status()
+        );
+
+        for (name, value) in set_cookies(&response) {
+            cookies.retain(|(existing, _)| existing != &name);
+            cookies.push((name, value));
+        }
+
+        self.credentials = Credentials::Cookies {
+            cookies: cookie_header(&cookies),
+            xsrf_token,
+        };
+        Ok(())
+    }
+
+    /// Whether requests carry a token or login cookies.
+    pub fn is_authenticated(&self) -> bool {
+        !matches!(self.credentials, Credentials::None)
+    }
+
+    pub fn api_url(&self, path: &str) -> String {
+        format!("{}/api{}", self.base_url, path)
+    }
+
+    fn authorize(&self, builder: http_client::Builder) -> http_client::Builder {
+        match &self.credentials {
+            Credentials::None => builder,
+            Credentials::Token(token) => builder.header("Authorization", format!("token {token}")),
+            Credentials::Cookies {
+                cookies,
+                xsrf_token,
+            } => builder
+                .header("Cookie", cookies)
+                .when_some(xsrf_token.clone(), |builder, xsrf_token| {
+                    builder.header("X-XSRFToken", xsrf_token)
+                }),
+        }
+    }
+
+    async fn send(&self, method: Method, path: &str, body: Option<String>) -> Result<String> {
+        let builder = Request::builder()
+            .method(method.clone())
+            .uri(self.api_url(path))
+            .when(body.is_some(), |builder| {
+                builder.header("Content-Type", "application/json")
+            });
+        let request = self
+            .authorize(builder)
+            .body(body.map(AsyncBody::from).unwrap_or_default())?;
+
+        let response = self.http_client.send(request).await?;
+        let status = response.status();
+
         let mut body = String::new();
         response.into_body().read_to_string(&mut body).await?;
-        anyhow::bail!("Failed to launch kernel: {body}");
+
+        anyhow::ensure!(
+            status.is_success(),
+            "{method} {path} failed with {status}: {body}"
+        );
+        Ok(body)
     }
 
-    let mut body = String::new();
-    response.into_body().read_to_string(&mut body).await?;
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let body = self.send(Method::GET, path, None).await?;
+        serde_json::from_str(&body).with_context(|| format!("Failed to parse response from {path}"))
+    }
+
+    pub async fn list_kernelspecs(&self) -> Result<Vec<RemoteKernelSpecification>> {
+        let kernel_specs: KernelSpecsResponse = self.get_json("/kernelspecs").await?;
+
+        let remote_kernelspecs = kernel_specs
+            .kernelspecs
+            .into_iter()
+            .map(|(name, spec)| RemoteKernelSpecification {
+                name,
+                server: self.clone(),
+                kernelspec: spec.spec,
+                kernel_id: None,
+            })
+            .collect::<Vec<RemoteKernelSpecification>>();
+
+        anyhow::ensure!(!remote_kernelspecs.is_empty(), "No kernel specs found");
+        Ok(remote_kernelspecs)
+    }
 
-    let response: jupyter_websocket_client::Kernel = serde_json::from_str(&body)?;
+    pub async fn list_kernels(&self) -> Result<Vec<RemoteKernel>> {
+        self.get_json("/kernels").await
+    }
 
-    Ok(response.id)
+    pub async fn list_sessions(&self) -> Result<Vec<RemoteSession>> {
+        self.get_json("/sessions").await
+    }
+
+    pub async fn launch_kernel(&self, kernel_name: &str) -> Result<String> {
+        let kernel_launch_request = KernelLaunchRequest {
+            name: kernel_name.to_string(),
+            // Note: since the path we have locally may not be the same as the one on the remote server,
+            // we don't send it. We'll have to evaluate this decision along the way.
+            path: None,
+        };
+
+        let body = self
+            .send(
+                Method::POST,
+                "/kernels",
+                Some(serde_json::to_string(&kernel_launch_request)?),
+            )
+            .await
+            .context("Failed to launch kernel")?;
+
+        let kernel: RemoteKernel = serde_json::from_str(&body)?;
+        Ok(kernel.id)
+    }
+
+    pub async fn interrupt_kernel(&self, kernel_id: &str) -> Result<()> {
+        self.send(
+            Method::POST,
+            &format!("/kernels/{kernel_id}/interrupt"),
+            None,
+        )
+        .await
+        .context("Failed to interrupt kernel")?;
+        Ok(())
+    }
+
+    pub async fn restart_kernel(&self, kernel_id: &str) -> Result<()> {
+        self.send(Method::POST, &format!("/kernels/{kernel_id}/restart"), None)
+            .await
+            .context("Failed to restart kernel")?;
+        Ok(())
+    }
+
+    pub async fn shutdown_kernel(&self, kernel_id: &str) -> Result<()> {
+        self.send(Method::DELETE, &format!("/kernels/{kernel_id}"), None)
+            .await
+            .context("Failed to shutdown kernel")?;
+        Ok(())
+    }
+
+    /// The request that opens a websocket to a kernel's channels.
+    fn channels_request(&self, kernel_id: &str) -> Result<Request<()>> {
+        let mut ws_url = format!(
+            "{}/api/kernels/{}/channels",
+            self.base_url.replacen("http", "ws", 1),
+            kernel_id,
+        );
+        if let Credentials::Token(token) = &self.credentials {
+            ws_url.push_str(&format!("?token={token}"));
+        }
+
+        let mut request: Request<()> = ws_url.into_client_request()?;
+        let headers = request.headers_mut();
+
+        headers.insert(
+            "User-Agent",
+            HeaderValue::from_str(&format!(
+                "Zed/{} ({}; {})",
+                "repl",
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            ))?,
+        );
+        if let Credentials::Cookies { cookies, .. } = &self.credentials {
+            headers.insert("Cookie", HeaderValue::from_str(cookies)?);
+        }
+
+        Ok(request)
+    }
 }
 
-pub async fn list_remote_kernelspecs(
-    remote_server: RemoteServer,
-    http_client: Arc<dyn HttpClient>,
-) -> Result<Vec<RemoteKernelSpecification>> {
-    let url = remote_server.api_url("/kernelspecs");
+impl PartialEq for JupyterServer {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.base_url == other.base_url
+    }
+}
 
-    let request = Request::builder()
-        .method("GET")
-        .uri(&url)
-        .header("Authorization", format!("token {}", remote_server.token))
-        .body(AsyncBody::default())?;
+impl Eq for JupyterServer {}
 
-    let response = http_client.send(request).await?;
+impl Debug for JupyterServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // custom debug that keeps credentials out of logs
+        f.debug_struct("JupyterServer")
+            .field("name", &self.name)
+            .field("base_url", &self.base_url)
+            .finish()
+    }
+}
 
-    anyhow::ensure!(
-        response.status().is_success(),
-        "Failed to fetch kernel specs: {}",
-        response.status()
-    );
-    let mut body = response.into_body();
+/// Returns the `name=value` pairs of the cookies a response sets.
+fn set_cookies(response: &Response<AsyncBody>) -> Vec<(String, String)> {
+    response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .filter_map(|header| {
+            let cookie = header.split(';').next()?;
+            let (name, value) = cookie.split_once('=')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
 
-    let mut body_bytes = Vec::new();
-    body.read_to_end(&mut body_bytes).await?;
+fn cookie_header(cookies: &[(String, String)]) -> String {
+    cookies
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
 
-    let kernel_specs: KernelSpecsResponse = serde_json::from_slice(&body_bytes)?;
+fn form_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
 
-    let remote_kernelspecs = kernel_specs
-        .kernelspecs
-        .into_iter()
-        .map(|(name, spec)| RemoteKernelSpecification {
-            name,
-            url: remote_server.base_url.clone(),
-            token: remote_server.token.clone(),
-            kernelspec: spec.spec,
+/// Everything a Jupyter server reports about the kernels it can launch and is running.
+#[derive(Debug, Clone)]
+pub struct RemoteServerListing {
+    pub server: JupyterServer,
+    pub kernelspecs: Vec<RemoteKernelSpecification>,
+    pub kernels: Vec<RemoteKernel>,
+    pub sessions: Vec<RemoteSession>,
+}
+
+impl RemoteServerListing {
+    pub async fn fetch(server: JupyterServer) -> Result<Self> {
+        let (kernelspecs, kernels, sessions) = futures::try_join!(
+            server.list_kernelspecs(),
+            server.list_kernels(),
+            server.list_sessions()
+        )?;
+
+        Ok(Self {
+            server,
+            kernelspecs,
+            kernels,
+            sessions,
         })
-        .collect::<Vec<RemoteKernelSpecification>>();
+    }
+
+    /// The session a running kernel belongs to, if any.
+    pub fn session_for_kernel(&self, kernel_id: &str) -> Option<&RemoteSession> {
+        self.sessions
+            .iter()
+            .find(|session| session.kernel.id == kernel_id)
+    }
+
+    /// Returns a name for a running kernel: the notebook or console it belongs to, if any.
+    pub fn kernel_label(&self, kernel: &RemoteKernel) -> String {
+        match self.session_for_kernel(&kernel.id) {
+            Some(session) if !session.path.is_empty() => {
+                format!("{} ({})", session.path, kernel.name)
+            }
+            _ => format!("{} ({})", kernel.name, short_kernel_id(&kernel.id)),
+        }
+    }
+
+    /// Kernel specifications that attach to each of the server's running kernels.
+    pub fn running_kernel_specifications(&self) -> Vec<RemoteKernelSpecification> {
+        self.kernels
+            .iter()
+            .filter_map(|kernel| {
+                let kernelspec = self
+                    .kernelspecs
+                    .iter()
+                    .find(|spec| spec.name == kernel.name)?;
+                Some(RemoteKernelSpecification {
+                    name: self.kernel_label(kernel),
+                    server: self.server.clone(),
+                    kernelspec: kernelspec.kernelspec.clone(),
+                    kernel_id: Some(kernel.id.clone()),
+                })
+            })
+            .collect()
+    }
+}
+
+fn short_kernel_id(kernel_id: &str) -> &str {
+    kernel_id.get(..8).unwrap_or(kernel_id)
+}
 
-    anyhow::ensure!(!remote_kernelspecs.is_empty(), "No kernel specs found");
-    Ok(remote_kernelspecs)
+#[derive(Debug, Clone)]
+pub struct RemoteKernelSpecification {
+    pub name: String,
+    pub server: JupyterServer,
+    pub kernelspec: JupyterKernelspec,
+    /// The already running kernel to attach to, rather than launching a new one.
+    pub kernel_id: Option<String>,
 }
 
 impl PartialEq for RemoteKernelSpecification {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name && self.url == other.url
+        self.name == other.name && self.server == other.server && self.kernel_id == other.kernel_id
     }
 }
 
 impl Eq for RemoteKernelSpecification {}
 
 pub struct RemoteRunningKernel {
-    remote_server: RemoteServer,
+    server: JupyterServer,
     _receiving_task: Task<Result<()>>,
     _routing_task: Task<Result<()>>,
-    http_client: Arc<dyn HttpClient>,
     pub working_directory: std::path::PathBuf,
     pub request_tx: mpsc::Sender<JupyterMessage>,
     pub execution_state: ExecutionState,
@@ -132,43 +476,16 @@ impl RemoteRunningKernel {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Result<Box<dyn RunningKernel>>> {
-        let remote_server = RemoteServer {
-            base_url: kernelspec.url,
-            token: kernelspec.token,
-        };
-
-        let http_client = cx.http_client();
+        let server = kernelspec.server;
 
         window.spawn(cx, async move |cx| {
-            let kernel_id = launch_remote_kernel(
-                &remote_server,
-                http_client.clone(),
-                &kernelspec.name,
-                working_directory.to_str().unwrap_or_default(),
-            )
-            .await?;
-
-            let ws_url = format!(
-                "{}/api/kernels/{}/channels?token={}",
-                remote_server.base_url.replace("http", "ws"),
-                kernel_id,
-                remote_server.token
-            );
-
-            let mut req: Request<()> = ws_url.into_client_request()?;
-            let headers = req.headers_mut();
+            let kernel_id = match kernelspec.kernel_id {
+                Some(kernel_id) => kernel_id,
+                None => server.launch_kernel(&kernelspec.name).await?,
+            };
 
-            headers.insert(
-                "User-Agent",
-                HeaderValue::from_str(&format!(
-                    "Zed/{} ({}; {})",
-                    "repl",
-                    std::env::consts::OS,
-                    std::env::consts::ARCH
-                ))?,
-            );
-
-            let response = connect_async(req).await;
+            let request = server.channels_request(&kernel_id)?;
+            let response = connect_async(request).await;
 
             let (ws_stream, _response) = response?;
 
@@ -214,14 +531,13 @@ impl RemoteRunningKernel {
             anyhow::Ok(Box::new(Self {
                 _routing_task: routing_task,
                 _receiving_task: receiving_task,
-                remote_server,
+                server,
                 working_directory,
                 request_tx,
                 // todo(kyle): pull this from the kernel API to start with
                 execution_state: ExecutionState::Idle,
                 kernel_info: None,
                 kernel_id,
-                http_client: http_client.clone(),
             }) as Box<dyn RunningKernel>)
         })
     }
@@ -231,7 +547,7 @@ impl Debug for RemoteRunningKernel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RemoteRunningKernel")
             // custom debug that keeps tokens out of logs
-            .field("remote_server url", &self.remote_server.base_url)
+            .field("remote_server url", &self.server.base_url)
             .field("working_directory", &self.working_directory)
             .field("request_tx", &self.request_tx)
             .field("execution_state", &self.execution_state)
@@ -265,28 +581,252 @@ impl RunningKernel for RemoteRunningKernel {
         self.kernel_info = Some(info);
     }
 
+    fn interrupt(&mut self, cx: &mut App) -> Option<Task<anyhow::Result<()>>> {
+        let server = self.server.clone();
+        let kernel_id = self.kernel_id.clone();
+        Some(cx.background_spawn(async move { server.interrupt_kernel(&kernel_id).await }))
+    }
+
+    fn restart(&mut self, cx: &mut App) -> Option<Task<anyhow::Result<()>>> {
+        let server = self.server.clone();
+        let kernel_id = self.kernel_id.clone();
+        Some(cx.background_spawn(async move { server.restart_kernel(&kernel_id).await }))
+    }
+
     fn force_shutdown(&mut self, window: &mut Window, cx: &mut App) -> Task<anyhow::Result<()>> {
-        let url = self
-            .remote_server
-            .api_url(&format!("/kernels/{}", self.kernel_id));
-        let token = self.remote_server.token.clone();
-        let http_client = self.http_client.clone();
-
-        window.spawn(cx, async move |_| {
-            let request = Request::builder()
-                .method("DELETE")
-                .uri(&url)
-                .header("Authorization", format!("token {}", token))
-                .body(AsyncBody::default())?;
-
-            let response = http_client.send(request).await?;
-
-            anyhow::ensure!(
-                response.status().is_success(),
-                "Failed to shutdown kernel: {}",
-                response.status()
-            );
-            Ok(())
+        let server = self.server.clone();
+        let kernel_id = self.kernel_id.clone();
+
+        window.spawn(cx, async move |_| server.shutdown_kernel(&kernel_id).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_client::FakeHttpClient;
+    use std::sync::Mutex;
+
+    const PASSWORD: &str = "s3cret pass";
+    const LOGIN_COOKIE: &str = "username-localhost-8888";
+
+    /// Stands in for `jupyter server`, with one python kernel running for a notebook.
+    fn fake_jupyter_server(requests: Arc<Mutex<Vec<String>>>) -> Arc<dyn HttpClient> {
+        FakeHttpClient::create(move |request| {
+            let requests = requests.clone();
+            async move {
+                let method = request.method().clone();
+                let path = request.uri().path().to_string();
+                let header = |name: &str| {
+                    request
+                        .headers()
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                let cookie = header("cookie");
+                let authorized = header("authorization") == "token t0k3n"
+                    || cookie.contains(&format!("{LOGIN_COOKIE}=logged-in"));
+                let has_xsrf = header("x-xsrftoken") == "xsrf-value";
+                let follows_redirects =
+                    request.extensions().get::<RedirectPolicy>() != Some(&RedirectPolicy::NoFollow);
+                let mut body = String::new();
+                request.into_body().read_to_string(&mut body).await?;
+                requests.lock().unwrap().push(format!("{method} {path}"));
+
+                let response = Response::builder();
+                let response = match (method.as_str(), path.as_str()) {
+                    ("GET", "/login") => response
+                        .status(200)
+                        .header("Set-Cookie", "_xsrf=xsrf-value; Path=/")
+                        .body(AsyncBody::from("<form></form>")),
+                    ("POST", "/login") => {
+                        let expected = format!(
+                            "password={}&_xsrf=xsrf-value",
+                            form_encode(PASSWORD)
+                        );
+                        if body != expected || !cookie.contains("_xsrf=xsrf-value") {
+                            response.status(401).body(AsyncBody::default())
+                        } else if follows_redirects {
+                            // What a client that follows redirects ends up with: the page the
+                            // login redirected to, without the cookie that was set along the way.
+                            response.status(200).body(AsyncBody::from("<html></html>"))
+                        } else {
+                            response
+                                .status(302)
+                                .header("Location", "/tree")
+                                .header("Set-Cookie", format!("{LOGIN_COOKIE}=logged-in; Path=/"))
+                                .body(AsyncBody::default())
+                        }
+                    }
+                    _ if !authorized => response.status(403).body(AsyncBody::default()),
+                    // Like Jupyter, reject state changes authenticated with cookies but no XSRF token.
+                    (method, _) if method != "GET" && !cookie.is_empty() && !has_xsrf => {
+                        response.status(403).body(AsyncBody::default())
+                    }
+                    ("GET", "/api/kernelspecs") => response.status(200).body(AsyncBody::from(
+                        serde_json::json!({
+                            "default": "python3",
+                            "kernelspecs": {
+                                "python3": {
+                                    "name": "python3",
+                                    "spec": {
+                                        "argv": ["python", "-m", "ipykernel_launcher", "-f", "{connection_file}"],
+                                        "display_name": "Python 3",
+                                        "language": "python"
+                                    },
+                                    "resources": {}
+                                }
+                            }
+                        })
+                        .to_string(),
+                    )),
+                    ("GET", "/api/kernels") => response.status(200).body(AsyncBody::from(
+                        serde_json::json!([
+                            {
+                                "id": "0123456789abcdef",
+                                "name": "python3",
+                                "last_activity": "2024-01-01T00:00:00Z",
+                                "execution_state": "idle",
+                                "connections": 1
+                            },
+                            {
+                                "id": "fedcba9876543210",
+                                "name": "python3",
+                                "execution_state": "busy",
+                                "connections": 0
+                            }
+                        ])
+                        .to_string(),
+                    )),
+                    ("GET", "/api/sessions") => response.status(200).body(AsyncBody::from(
+                        serde_json::json!([
+                            {
+                                "id": "session-1",
+                                "path": "analysis.ipynb",
+                                "name": "analysis.ipynb",
+                                "type": "notebook",
+                                "kernel": {"id": "0123456789abcdef", "name": "python3"}
+                            }
+                        ])
+                        .to_string(),
+                    )),
+                    ("POST", "/api/kernels") => response.status(201).body(AsyncBody::from(
+                        serde_json::json!({"id": "new-kernel", "name": "python3"}).to_string(),
+                    )),
+                    ("POST", "/api/kernels/0123456789abcdef/interrupt")
+                    | ("POST", "/api/kernels/0123456789abcdef/restart")
+                    | ("DELETE", "/api/kernels/0123456789abcdef") => {
+                        response.status(204).body(AsyncBody::default())
+                    }
+                    _ => response.status(404).body(AsyncBody::default()),
+                };
+                Ok(response?)
+            }
         })
     }
+
+    #[gpui::test]
+    async fn test_token_authentication() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http_client = fake_jupyter_server(requests.clone());
+
+        let server = JupyterServer::connect(
+            "local".into(),
+            "http://localhost:8888/",
+            Some("t0k3n".into()),
+            None,
+            http_client.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(server.base_url, "http://localhost:8888");
+
+        let listing = RemoteServerListing::fetch(server.clone()).await.unwrap();
+        assert_eq!(listing.kernelspecs.len(), 1);
+        assert_eq!(listing.kernelspecs[0].name, "python3");
+        assert_eq!(listing.kernelspecs[0].kernelspec.language, "python");
+        assert_eq!(listing.kernels.len(), 2);
+        assert_eq!(listing.sessions[0].session_type, "notebook");
+
+        let running = listing.running_kernel_specifications();
+        assert_eq!(
+            running
+                .iter()
+                .map(|spec| (spec.name.as_str(), spec.kernel_id.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("analysis.ipynb (python3)", Some("0123456789abcdef")),
+                ("python3 (fedcba98)", Some("fedcba9876543210")),
+            ]
+        );
+
+        assert_eq!(server.launch_kernel("python3").await.unwrap(), "new-kernel");
+        server.interrupt_kernel("0123456789abcdef").await.unwrap();
+        server.restart_kernel("0123456789abcdef").await.unwrap();
+        server.shutdown_kernel("0123456789abcdef").await.unwrap();
+        assert!(server.restart_kernel("missing").await.is_err());
+
+        let unauthorized = JupyterServer::connect(
+            "local".into(),
+            "http://localhost:8888",
+            Some("wrong".into()),
+            None,
+            http_client,
+        )
+        .await
+        .unwrap();
+        assert!(unauthorized.list_kernels().await.is_err());
+    }
+
+    #[gpui::test]
+    async fn test_password_authentication() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http_client = fake_jupyter_server(requests.clone());
+
+        let server = JupyterServer::connect(
+            "local".into(),
+            "http://localhost:8888",
+            None,
+            Some(PASSWORD.into()),
+            http_client.clone(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(server.list_kernels().await.unwrap().len(), 2);
+        server.interrupt_kernel("0123456789abcdef").await.unwrap();
+        assert_eq!(
+            requests.lock().unwrap().as_slice(),
+            [
+                "GET /login",
+                "POST /login",
+                "GET /api/kernels",
+                "POST /api/kernels/0123456789abcdef/interrupt"
+            ]
+        );
+
+        let request = server.channels_request("0123456789abcdef").unwrap();
+        assert_eq!(
+            request.uri().to_string(),
+            "ws://localhost:8888/api/kernels/0123456789abcdef/channels"
+        );
+        assert_eq!(
+            request.headers().get("Cookie").unwrap(),
+            &format!("_xsrf=xsrf-value; {LOGIN_COOKIE}=logged-in")
+        );
+
+        assert!(
+            JupyterServer::connect(
+                "local".into(),
+                "http://localhost:8888",
+                None,
+                Some("wrong".into()),
+                http_client,
+            )
+            .await
+            .is_err()
+        );
+    }
 }
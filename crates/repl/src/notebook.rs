@@ -1,4 +1,6 @@
 mod cell;
+mod notebook_file;
 mod notebook_ui;
+mod search_text;
 pub use cell::*;
 pub use notebook_ui::*;
@@ -19,7 +19,7 @@ use util::ResultExt;
 
 use crate::{
     notebook::{CODE_BLOCK_INSET, GUTTER_WIDTH},
-    outputs::{Output, plain::TerminalOutput, user_error::ErrorView},
+    outputs::{Output, OutputBundle, plain::TerminalOutput, user_error::ErrorView},
 };
 
 #[derive(Copy, Clone, PartialEq, PartialOrd)]
@@ -98,6 +98,11 @@ pub enum Cell {
     Raw(Entity<RawCell>),
 }
 
+/// Splits a cell's source into lines the way Jupyter does, keeping the line endings.
+pub(crate) fn source_lines(source: &str) -> Vec<String> {
+    source.split_inclusive('\n').map(str::to_string).collect()
+}
+
 fn convert_outputs(
     outputs: &Vec<nbformat::v4::Output>,
     window: &mut Window,
@@ -109,12 +114,26 @@ fn convert_outputs(
             nbformat::v4::Output::Stream { text, .. } => Output::Stream {
                 content: cx.new(|cx| TerminalOutput::from(&text.0, window, cx)),
             },
-            nbformat::v4::Output::DisplayData(display_data) => {
-                Output::new(&display_data.data, None, window, cx)
-            }
-            nbformat::v4::Output::ExecuteResult(execute_result) => {
-                Output::new(&execute_result.data, None, window, cx)
-            }
+            nbformat::v4::Output::DisplayData(display_data) => Output::from_bundle(
+                OutputBundle {
+                    data: display_data.data.clone(),
+                    metadata: display_data.metadata.clone(),
+                    execution_count: None,
+                },
+                None,
+                window,
+                cx,
+            ),
+            nbformat::v4::Output::ExecuteResult(execute_result) => Output::from_bundle(
+                OutputBundle {
+                    data: execute_result.data.clone(),
+                    metadata: execute_result.metadata.clone(),
+                    execution_count: Some(execute_result.execution_count),
+                },
+                None,
+                window,
+                cx,
+            ),
             nbformat::v4::Output::Error(error) => Output::ErrorOutput(ErrorView {
                 ename: error.ename.clone(),
                 evalue: error.evalue.clone(),
@@ -134,6 +153,31 @@ impl Cell {
         }
     }
 
+    /// The editor holding the cell's source, if the cell has one.
+    pub fn editor(&self, cx: &App) -> Option<Entity<Editor>> {
+        match self {
+            Cell::Code(code_cell) => Some(code_cell.read(cx).editor().clone()),
+            Cell::Markdown(markdown_cell) => Some(markdown_cell.read(cx).editor().clone()),
+            Cell::Raw(_) => None,
+        }
+    }
+
+    pub fn cell_type(&self) -> CellType {
+        match self {
+            Cell::Code(_) => CellType::Code,
+            Cell::Markdown(_) => CellType::Markdown,
+            Cell::Raw(_) => CellType::Raw,
+        }
+    }
+
+    pub fn metadata(&self, cx: &App) -> CellMetadata {
+        match self {
+            Cell::Code(code_cell) => code_cell.read(cx).metadata.clone(),
+            Cell::Markdown(markdown_cell) => markdown_cell.read(cx).metadata.clone(),
+            Cell::Raw(raw_cell) => raw_cell.read(cx).metadata.clone(),
+        }
+    }
+
     pub fn current_source(&self, cx: &App) -> String {
         match self {
             Cell::Code(code_cell) => code_cell.read(cx).current_source(cx),
@@ -142,6 +186,25 @@ impl Cell {
         }
     }
 
+    /// Replaces the cell's source. For cells with an editor, this is an edit that can be undone.
+    pub fn set_source(&self, source: String, window: &mut Window, cx: &mut App) {
+        match self {
+            Cell::Code(code_cell) => {
+                let editor = code_cell.read(cx).editor().clone();
+                editor.update(cx, |editor, cx| editor.set_text(source, window, cx));
+            }
+            Cell::Markdown(markdown_cell) => {
+                let editor = markdown_cell.read(cx).editor().clone();
+                editor.update(cx, |editor, cx| editor.set_text(source, window, cx));
+                markdown_cell.update(cx, |cell, cx| cell.reparse_markdown(cx));
+            }
+            Cell::Raw(raw_cell) => raw_cell.update(cx, |cell, cx| {
+                cell.source = source;
+                cx.notify();
+            }),
+        }
+    }
+
     pub fn to_nbformat_cell(&self, cx: &App) -> nbformat::v4::Cell {
         match self {
             Cell::Code(code_cell) => code_cell.read(cx).to_nbformat_cell(cx),
@@ -170,19 +233,21 @@ impl Cell {
                 id,
                 metadata,
                 source,
-                ..
+                attachments,
             } => {
                 let source = source.join("");
 
                 let entity = cx.new(|cx| {
-                    MarkdownCell::new(
+                    let mut cell = MarkdownCell::new(
                         id.clone(),
                         metadata.clone(),
                         source,
                         languages.clone(),
                         window,
                         cx,
-                    )
+                    );
+                    cell.attachments = attachments.clone();
+                    cell
                 });
 
                 Cell::Markdown(entity)
@@ -319,6 +384,7 @@ pub trait RunnableCell: RenderableCell {
 pub struct MarkdownCell {
     id: CellId,
     metadata: CellMetadata,
+    attachments: Option<serde_json::Value>,
     image_cache: Entity<RetainAllImageCache>,
     source: String,
     editor: Entity<Editor>,
@@ -399,6 +465,7 @@ impl MarkdownCell {
         Self {
             id,
             metadata,
+            attachments: None,
             image_cache: RetainAllImageCache::new(cx),
             source,
             editor,
@@ -429,14 +496,13 @@ impl MarkdownCell {
     }
 
     pub fn to_nbformat_cell(&self, cx: &App) -> nbformat::v4::Cell {
-        let source = self.current_source(cx);
-        let source_lines: Vec<String> = source.lines().map(|l| format!("{}\n", l)).collect();
+        let source_lines = source_lines(&self.current_source(cx));
 
         nbformat::v4::Cell::Markdown {
             id: self.id.clone(),
             metadata: self.metadata.clone(),
             source: source_lines,
-            attachments: None,
+            attachments: self.attachments.clone(),
         }
     }
 
@@ -606,6 +672,11 @@ pub struct CodeCell {
     execution_start_time: Option<Instant>,
     execution_duration: Option<Duration>,
     is_executing: bool,
+    /// Whether `outputs` and `execution_count` are as they were last loaded or saved.
+    outputs_saved: bool,
+    /// Indices of outputs containing search matches, and the one holding the active match.
+    highlighted_outputs: Vec<usize>,
+    active_highlighted_output: Option<usize>,
 }
 
 impl EventEmitter<CellEvent> for CodeCell {}
@@ -668,6 +739,9 @@ impl CodeCell {
             execution_start_time: None,
             execution_duration: None,
             is_executing: false,
+            outputs_saved: true,
+            highlighted_outputs: Vec::new(),
+            active_highlighted_output: None,
         }
     }
 
@@ -706,7 +780,6 @@ impl CodeCell {
                 ..Default::default()
             };
 
-            editor.set_text(source.clone(), window, cx);
             editor.set_show_gutter(false, cx);
             editor.set_text_style_refinement(refinement);
             editor
@@ -732,6 +805,9 @@ impl CodeCell {
             execution_start_time: None,
             execution_duration: None,
             is_executing: false,
+            outputs_saved: true,
+            highlighted_outputs: Vec::new(),
+            active_highlighted_output: None,
         }
     }
 
@@ -749,12 +825,19 @@ impl CodeCell {
     }
 
     pub fn is_dirty(&self, cx: &App) -> bool {
-        self.editor.read(cx).buffer().read(cx).is_dirty(cx)
+        !self.outputs_saved || self.editor.read(cx).buffer().read(cx).is_dirty(cx)
+    }
+
+    pub fn outputs_saved(&self) -> bool {
+        self.outputs_saved
+    }
+
+    pub fn mark_outputs_saved(&mut self) {
+        self.outputs_saved = true;
     }
 
     pub fn to_nbformat_cell(&self, cx: &App) -> nbformat::v4::Cell {
-        let source = self.current_source(cx);
-        let source_lines: Vec<String> = source.lines().map(|l| format!("{}\n", l)).collect();
+        let source_lines = source_lines(&self.current_source(cx));
 
         let outputs = self.outputs_to_nbformat(cx);
 
@@ -774,13 +857,37 @@ impl CodeCell {
             .collect()
     }
 
+    pub fn outputs(&self) -> &[Output] {
+        &self.outputs
+    }
+
     pub fn has_outputs(&self) -> bool {
         !self.outputs.is_empty()
     }
 
     pub fn clear_outputs(&mut self) {
+        if !self.outputs.is_empty() {
+            self.outputs_saved = false;
+        }
         self.outputs.clear();
         self.execution_duration = None;
+        self.highlighted_outputs.clear();
+        self.active_highlighted_output = None;
+    }
+
+    pub fn set_output_highlights(
+        &mut self,
+        highlighted_outputs: Vec<usize>,
+        active_output: Option<usize>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.highlighted_outputs != highlighted_outputs
+            || self.active_highlighted_output != active_output
+        {
+            self.highlighted_outputs = highlighted_outputs;
+            self.active_highlighted_output = active_output;
+            cx.notify();
+        }
     }
 
     pub fn start_execution(&mut self) {
@@ -823,6 +930,16 @@ impl CodeCell {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if matches!(
+            message.content,
+            JupyterMessageContent::StreamContent(_)
+                | JupyterMessageContent::DisplayData(_)
+                | JupyterMessageContent::ExecuteResult(_)
+                | JupyterMessageContent::ExecuteInput(_)
+                | JupyterMessageContent::ErrorOutput(_)
+        ) {
+            self.outputs_saved = false;
+        }
         match &message.content {
             JupyterMessageContent::StreamContent(stream) => {
                 self.outputs.push(Output::Stream {
@@ -830,12 +947,22 @@ impl CodeCell {
                 });
             }
             JupyterMessageContent::DisplayData(display_data) => {
+                let bundle = OutputBundle {
+                    data: display_data.data.clone(),
+                    metadata: display_data.metadata.clone(),
+                    execution_count: None,
+                };
                 self.outputs
-                    .push(Output::new(&display_data.data, None, window, cx));
+                    .push(Output::from_bundle(bundle, None, window, cx));
             }
             JupyterMessageContent::ExecuteResult(execute_result) => {
+                let bundle = OutputBundle {
+                    data: execute_result.data.clone(),
+                    metadata: execute_result.metadata.clone(),
+                    execution_count: Some(execute_result.execution_count),
+                };
                 self.outputs
-                    .push(Output::new(&execute_result.data, None, window, cx));
+                    .push(Output::from_bundle(bundle, None, window, cx));
             }
             JupyterMessageContent::ExecuteInput(input) => {
                 self.execution_count = serde_json::to_value(&input.execution_count)
@@ -1030,6 +1157,7 @@ impl RunnableCell for CodeCell {
 
     fn set_execution_count(&mut self, count: i32) -> &mut Self {
         self.execution_count = Some(count);
+        self.outputs_saved = false;
         self
     }
 }
@@ -1168,13 +1296,21 @@ impl Render for CodeCell {
                                             },
                                         )
                                         // output at bottom
-                                        .child(div().w_full().children(self.outputs.iter().map(
-                                            |output| {
+                                        .child(div().w_full().children(
+                                            self.outputs.iter().enumerate().map(|(ix, output)| {
                                                 let content = match output {
                                                     Output::Plain { content, .. } => {
                                                         Some(content.clone().into_any_element())
                                                     }
-                                                    Output::Markdown { content, .. } => {
+                                                    Output::Markdown { content, .. }
+                                                    | Output::Html { content, .. } => {
+                                                        Some(content.clone().into_any_element())
+                                                    }
+                                                    Output::Svg { content, .. }
+                                                    | Output::Chart { content, .. } => {
+                                                        Some(content.clone().into_any_element())
+                                                    }
+                                                    Output::Latex { content, .. } => {
                                                         Some(content.clone().into_any_element())
                                                     }
                                                     Output::Stream { content, .. } => {
@@ -1183,7 +1319,8 @@ impl Render for CodeCell {
                                                     Output::Image { content, .. } => {
                                                         Some(content.clone().into_any_element())
                                                     }
-                                                    Output::Message(message) => Some(
+                                                    Output::Message(message)
+                                                    | Output::Unsupported { message, .. } => Some(
                                                         div()
                                                             .child(message.clone())
                                                             .into_any_element(),
@@ -1197,9 +1334,29 @@ impl Render for CodeCell {
                                                     Output::ClearOutputWaitMarker => None,
                                                 };
 
-                                                div().children(content)
-                                            },
-                                        ))),
+                                                let highlight = if self.active_highlighted_output
+                                                    == Some(ix)
+                                                {
+                                                    Some(
+                                                        cx.theme()
+                                                            .colors()
+                                                            .search_active_match_background,
+                                                    )
+                                                } else if self.highlighted_outputs.contains(&ix) {
+                                                    Some(
+                                                        cx.theme().colors().search_match_background,
+                                                    )
+                                                } else {
+                                                    None
+                                                };
+
+                                                div()
+                                                    .when_some(highlight, |this, color| {
+                                                        this.rounded_sm().bg(color)
+                                                    })
+                                                    .children(content)
+                                            }),
+                                        )),
                                 ),
                             ),
                     )
@@ -1220,7 +1377,7 @@ pub struct RawCell {
 
 impl RawCell {
     pub fn to_nbformat_cell(&self) -> nbformat::v4::Cell {
-        let source_lines: Vec<String> = self.source.lines().map(|l| format!("{}\n", l)).collect();
+        let source_lines = source_lines(&self.source);
 
         nbformat::v4::Cell::Raw {
             id: self.id.clone(),
@@ -1298,3 +1455,64 @@ impl Render for RawCell {
             .children(self.cell_position_spacer(false, window, cx))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_outputs_round_trip(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = settings::SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+        let outputs: Vec<nbformat::v4::Output> = serde_json::from_value(json!([
+            {
+                "output_type": "stream",
+                "name": "stdout",
+                "text": ["hello\n"]
+            },
+            {
+                "output_type": "display_data",
+                "data": {
+                    "text/html": ["<b>bold</b>"],
+                    "text/plain": ["bold"]
+                },
+                "metadata": {"isolated": true}
+            },
+            {
+                "output_type": "display_data",
+                "data": {
+                    "image/svg+xml": ["<svg"],
+                    "text/plain": ["<Figure>"]
+                },
+                "metadata": {}
+            },
+            {
+                "output_type": "execute_result",
+                "execution_count": 3,
+                "data": {"text/plain": ["42"]},
+                "metadata": {}
+            }
+        ]))
+        .unwrap();
+
+        // Each output is saved with all of its media types, even when the richest one can't be
+        // shown and its plain text is shown instead.
+        let cx = cx.add_empty_window();
+        let saved = cx.update(|window, cx| {
+            convert_outputs(&outputs, window, cx)
+                .iter()
+                .filter_map(|output| output.to_nbformat(cx))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            serde_json::to_value(&saved).unwrap(),
+            serde_json::to_value(&outputs).unwrap()
+        );
+    }
+}
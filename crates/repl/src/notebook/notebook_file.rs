@@ -0,0 +1,434 @@
+//! Reading and writing `.ipynb` files.
+//!
+//! Notebooks are usually committed to version control, so saving one should only touch the parts
+//! that were actually edited. Rather than serializing the in-memory notebook from scratch, it is
+//! merged into the JSON that was last read from or written to disk:
+//!
+//! - object keys keep their original order, and keys added by the editor are slotted in sorted
+//!   order, as Jupyter does,
+//! - values that didn't change keep their original representation,
+//! - outputs that weren't re-run are written back exactly as they were read,
+//! - the original indentation and trailing newline are reused.
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use nbformat::v4::CellId;
+use serde::Serialize as _;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+/// The indentation Jupyter uses when writing notebooks.
+const JUPYTER_INDENT: &str = " ";
+
+pub struct NotebookFile {
+    /// The notebook JSON as it is on disk, except that every cell has an `id`.
+    json: Value,
+    style: JsonStyle,
+    /// Cell ids were introduced in nbformat 4.5, and are invalid in older notebooks.
+    has_cell_ids: bool,
+}
+
+impl NotebookFile {
+    pub fn parse(text: &str) -> Result<(Self, nbformat::v4::Notebook)> {
+        let mut json: Value = serde_json::from_str(text)?;
+        let has_cell_ids = json
+            .get("nbformat_minor")
+            .and_then(Value::as_i64)
+            .is_some_and(|minor| minor >= 5);
+
+        // Cells are tracked by id, so give every cell one.
+        if let Some(cells) = json.get_mut("cells").and_then(|c| c.as_array_mut()) {
+            for cell in cells {
+                if cell.get("id").is_none() {
+                    cell["id"] = Value::String(Uuid::new_v4().to_string());
+                }
+            }
+        }
+
+        let notebook = match nbformat::parse_notebook(&serde_json::to_string(&json)?) {
+            Ok(nbformat::Notebook::V4(notebook)) => notebook,
+            // 4.1 - 4.4 are converted to 4.5
+            Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
+                nbformat::upgrade_legacy_notebook(legacy_notebook)?
+            }
+            // Bad notebooks and notebooks v4.0 and below are not supported
+            Err(e) => {
+                anyhow::bail!("Failed to parse notebook: {:?}", e);
+            }
+        };
+
+        let file = Self {
+            json,
+            style: JsonStyle::detect(text),
+            has_cell_ids,
+        };
+        Ok((file, notebook))
+    }
+
+    /// Serializes `notebook` for writing to disk, and records the result as the saved state.
+    ///
+    /// The outputs and execution counts of the cells in `unchanged_outputs` are taken from the
+    /// saved state rather than from `notebook`, as they can't be reproduced exactly.
+    pub fn save(
+        &mut self,
+        notebook: &nbformat::v4::Notebook,
+        unchanged_outputs: &HashSet<CellId>,
+    ) -> Result<String> {
+        let updated = serde_json::to_value(notebook).context("Failed to serialize notebook")?;
+        let mut json = merge_notebook(&self.json, updated, unchanged_outputs);
+
+        // Older notebooks are upgraded to 4.5 when they're read, but are written back in their
+        // original version.
+        let mut on_disk = json.clone();
+        if !self.has_cell_ids {
+            if let Some(minor) = self.json.get("nbformat_minor") {
+                json["nbformat_minor"] = minor.clone();
+                on_disk["nbformat_minor"] = minor.clone();
+            }
+            strip_cell_ids(&mut on_disk);
+        }
+        let text = self.style.to_string(&on_disk)?;
+
+        self.json = json;
+        Ok(text)
+    }
+}
+
+/// How the JSON of a notebook was formatted.
+#[derive(Debug, PartialEq)]
+struct JsonStyle {
+    /// `None` for JSON on a single line.
+    indent: Option<String>,
+    trailing_newline: bool,
+}
+
+impl Default for JsonStyle {
+    fn default() -> Self {
+        Self {
+            indent: Some(JUPYTER_INDENT.to_string()),
+            trailing_newline: true,
+        }
+    }
+}
+
+impl JsonStyle {
+    fn detect(text: &str) -> Self {
+        let trimmed = text.trim_end();
+        if trimmed.is_empty() {
+            return Self::default();
+        }
+        let trailing_newline = text.ends_with('\n');
+        let Some(second_line) = trimmed.lines().nth(1) else {
+            return Self {
+                indent: None,
+                trailing_newline,
+            };
+        };
+        let indent = &second_line[..second_line.len() - second_line.trim_start().len()];
+        Self {
+            indent: Some(if indent.is_empty() {
+                JUPYTER_INDENT.to_string()
+            } else {
+                indent.to_string()
+            }),
+            trailing_newline,
+        }
+    }
+
+    fn to_string(&self, json: &Value) -> Result<String> {
+        let mut text = match &self.indent {
+            Some(indent) => {
+                let mut bytes = Vec::new();
+                let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+                let mut serializer = serde_json::Serializer::with_formatter(&mut bytes, formatter);
+                json.serialize(&mut serializer)?;
+                String::from_utf8(bytes)?
+            }
+            None => serde_json::to_string(json)?,
+        };
+        if self.trailing_newline {
+            text.push('\n');
+        }
+        Ok(text)
+    }
+}
+
+fn merge_notebook(
+    original: &Value,
+    mut updated: Value,
+    unchanged_outputs: &HashSet<CellId>,
+) -> Value {
+    let original_cells = original.get("cells").and_then(Value::as_array);
+    if let Some(original_cells) = original_cells
+        && let Some(cells) = updated.get_mut("cells").and_then(Value::as_array_mut)
+    {
+        for cell in cells {
+            let Some(id) = cell
+                .get("id")
+                .and_then(Value::as_str)
+                .and_then(|id| CellId::new(id).ok())
+            else {
+                continue;
+            };
+            if !unchanged_outputs.contains(&id) {
+                continue;
+            }
+            let Some(original_cell) = find_by_id(original_cells, id.as_str()) else {
+                continue;
+            };
+            for key in ["outputs", "execution_count"] {
+                if let (Some(original_value), Some(value)) =
+                    (original_cell.get(key), cell.get_mut(key))
+                {
+                    *value = original_value.clone();
+                }
+            }
+        }
+    }
+    merge(original, updated)
+}
+
+/// Merges `updated` into `original`, keeping as much of `original`'s formatting as possible.
+fn merge(original: &Value, updated: Value) -> Value {
+    if *original == updated {
+        return original.clone();
+    }
+    match (original, updated) {
+        (Value::Object(original), Value::Object(mut updated)) => {
+            let mut merged = Map::new();
+            for (key, original_value) in original {
+                if let Some(value) = updated.remove(key) {
+                    merged.insert(key.clone(), merge(original_value, value));
+                }
+            }
+            // Optional fields that were left out are serialized as `null` by nbformat.
+            updated.retain(|_, value| !value.is_null());
+            if updated.is_empty() {
+                return Value::Object(merged);
+            }
+
+            let was_sorted = original.keys().is_sorted();
+            for (key, value) in updated {
+                merged.insert(key, sort_keys(value));
+            }
+            if was_sorted {
+                merged = sorted(merged);
+            }
+            Value::Object(merged)
+        }
+        (Value::Array(original), Value::Array(updated)) => Value::Array(
+            updated
+                .into_iter()
+                .enumerate()
+                .map(|(ix, value)| {
+                    // Elements with ids (cells) may have moved, so match them by id.
+                    let original_value = match value.get("id").and_then(Value::as_str) {
+                        Some(id) => find_by_id(original, id),
+                        None => original.get(ix),
+                    };
+                    match original_value {
+                        Some(original_value) => merge(original_value, value),
+                        None => sort_keys(value),
+                    }
+                })
+                .collect(),
+        ),
+        (_, updated) => sort_keys(updated),
+    }
+}
+
+fn find_by_id<'a>(values: &'a [Value], id: &str) -> Option<&'a Value> {
+    values
+        .iter()
+        .find(|value| value.get("id").and_then(Value::as_str) == Some(id))
+}
+
+/// Sorts the keys of all objects in `value`, as Jupyter does for the JSON it writes.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(sorted(
+            map.into_iter()
+                .map(|(key, value)| (key, sort_keys(value)))
+                .collect(),
+        )),
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+fn sorted(map: Map<String, Value>) -> Map<String, Value> {
+    let mut entries = map.into_iter().collect::<Vec<_>>();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries.into_iter().collect()
+}
+
+fn strip_cell_ids(json: &mut Value) {
+    if let Some(cells) = json.get_mut("cells").and_then(Value::as_array_mut) {
+        for cell in cells {
+            if let Some(cell) = cell.as_object_mut() {
+                cell.shift_remove("id");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_json_style() {
+        assert_eq!(
+            JsonStyle::detect("{\n \"cells\": []\n}\n"),
+            JsonStyle {
+                indent: Some(" ".into()),
+                trailing_newline: true
+            }
+        );
+        assert_eq!(
+            JsonStyle::detect("{\n\t\"cells\": []\n}"),
+            JsonStyle {
+                indent: Some("\t".into()),
+                trailing_newline: false
+            }
+        );
+        assert_eq!(
+            JsonStyle::detect("{\"cells\": []}"),
+            JsonStyle {
+                indent: None,
+                trailing_newline: false
+            }
+        );
+        assert_eq!(JsonStyle::detect(""), JsonStyle::default());
+    }
+
+    #[test]
+    fn test_merge_keeps_original_formatting() {
+        let original = json!({
+            "metadata": {"zed": {"b": 1, "a": 2}, "kernelspec": {"name": "python3"}},
+            "cells": [
+                {"id": "one", "cell_type": "code", "source": ["x = 1"], "outputs": [], "execution_count": 1.0},
+                {"id": "two", "cell_type": "markdown", "source": ["# Title"], "metadata": {}}
+            ]
+        });
+        // The cells were swapped, one of them was edited and a new one was added.
+        let updated = json!({
+            "cells": [
+                {"cell_type": "markdown", "id": "two", "metadata": {}, "source": ["# Title"]},
+                {"cell_type": "code", "execution_count": 1, "id": "one", "outputs": [], "source": ["x = 2"]},
+                {"source": [], "id": "three", "metadata": {}, "cell_type": "raw"}
+            ],
+            "metadata": {"kernelspec": {"name": "python3"}, "zed": {"a": 2, "b": 1}}
+        });
+
+        let merged = merge(&original, updated);
+        assert_eq!(
+            serde_json::to_string_pretty(&merged).unwrap(),
+            indoc! {r##"
+                {
+                  "metadata": {
+                    "zed": {
+                      "b": 1,
+                      "a": 2
+                    },
+                    "kernelspec": {
+                      "name": "python3"
+                    }
+                  },
+                  "cells": [
+                    {
+                      "id": "two",
+                      "cell_type": "markdown",
+                      "source": [
+                        "# Title"
+                      ],
+                      "metadata": {}
+                    },
+                    {
+                      "id": "one",
+                      "cell_type": "code",
+                      "source": [
+                        "x = 2"
+                      ],
+                      "outputs": [],
+                      "execution_count": 1
+                    },
+                    {
+                      "cell_type": "raw",
+                      "id": "three",
+                      "metadata": {},
+                      "source": []
+                    }
+                  ]
+                }"##}
+        );
+    }
+
+    #[test]
+    fn test_save_round_trip() {
+        let text = indoc! {r#"
+            {
+             "cells": [
+              {
+               "cell_type": "code",
+               "execution_count": 3,
+               "metadata": {
+                "custom": {
+                 "z": true,
+                 "a": false
+                }
+               },
+               "outputs": [
+                {
+                 "name": "stdout",
+                 "output_type": "stream",
+                 "text": [
+                  "hi\n",
+                  "there"
+                 ]
+                }
+               ],
+               "source": [
+                "print('hi')\n",
+                "print('there')"
+               ]
+              }
+             ],
+             "metadata": {
+              "language_info": {
+               "name": "python"
+              },
+              "unknown_tool": {
+               "setting": 1
+              }
+             },
+             "nbformat": 4,
+             "nbformat_minor": 4
+            }
+        "#};
+
+        let (mut file, notebook) = NotebookFile::parse(text).unwrap();
+        let unchanged_outputs = notebook
+            .cells
+            .iter()
+            .map(|cell| cell.id().clone())
+            .collect::<HashSet<_>>();
+        assert_eq!(file.save(&notebook, &unchanged_outputs).unwrap(), text);
+
+        // Saving again only changes what was edited.
+        let mut notebook = notebook;
+        if let nbformat::v4::Cell::Code { source, .. } = &mut notebook.cells[0] {
+            *source = vec!["print('bye')".into()];
+        }
+        let saved = file.save(&notebook, &unchanged_outputs).unwrap();
+        assert_eq!(
+            saved,
+            text.replace(
+                "\"print('hi')\\n\",\n    \"print('there')\"",
+                "\"print('bye')\""
+            )
+        );
+    }
+}
@@ -1,10 +1,12 @@
 #![allow(unused, dead_code)]
 use std::future::Future;
-use std::{path::PathBuf, sync::Arc};
+use std::ops::Range;
+use std::{any::Any, path::PathBuf, sync::Arc};
 
 use anyhow::{Context as _, Result};
 use client::proto::ViewId;
 use collections::HashMap;
+use editor::MultiBufferOffset;
 use feature_flags::{FeatureFlagAppExt as _, NotebookFeatureFlag};
 use futures::FutureExt;
 use futures::future::Shared;
@@ -13,18 +15,26 @@ use gpui::{
     Point, Task, actions, list, prelude::*,
 };
 use jupyter_protocol::JupyterKernelspec;
-use language::{Language, LanguageRegistry};
+use language::{Bias, Buffer, Language, LanguageRegistry};
+use project::search::SearchQuery;
+use project::search_text::SearchTextPosition;
 use project::{Project, ProjectEntryId, ProjectPath};
 use settings::Settings as _;
 use ui::{CommonAnimationExt, Tooltip, prelude::*};
 use workspace::item::{ItemEvent, SaveOptions, TabContentParams};
-use workspace::searchable::SearchableItemHandle;
-use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation};
+use workspace::searchable::{
+    Direction, SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle,
+};
+use workspace::{Item, ItemHandle, ItemNavHistory, Pane, ProjectItem, ToolbarItemLocation};
 
-use super::{Cell, CellEvent, CellPosition, MarkdownCellEvent, RenderableCell};
+use super::notebook_file::NotebookFile;
+use super::search_text::{
+    NotebookSearchTextProvider, SearchTextPart, notebook_search_text, segment_at,
+};
+use super::{Cell, CellEvent, CellPosition, MarkdownCellEvent, RenderableCell, source_lines};
 
-use nbformat::v4::CellId;
 use nbformat::v4::Metadata as NotebookMetadata;
+use nbformat::v4::{CellId, CellMetadata, CellType};
 use serde_json;
 use uuid::Uuid;
 
@@ -36,7 +46,7 @@ use crate::kernels::{
 use crate::repl_store::ReplStore;
 use picker::Picker;
 use runtimelib::{ExecuteRequest, JupyterMessage, JupyterMessageContent};
-use ui::PopoverMenuHandle;
+use ui::{ContextMenu, PopoverMenu, PopoverMenuHandle};
 
 actions!(
     notebook,
@@ -57,6 +67,22 @@ actions!(
         AddMarkdownBlock,
         /// Adds a new code cell.
         AddCodeBlock,
+        /// Deletes the current cell.
+        DeleteCell,
+        /// Splits the current cell in two at the cursor.
+        SplitCell,
+        /// Merges the current cell with the cell below it.
+        MergeCellBelow,
+        /// Changes the current cell into a code cell.
+        ChangeCellToCode,
+        /// Changes the current cell into a markdown cell.
+        ChangeCellToMarkdown,
+        /// Changes the current cell into a raw cell.
+        ChangeCellToRaw,
+        /// Undoes the last change to the notebook's cells.
+        UndoCellChange,
+        /// Redoes the last undone change to the notebook's cells.
+        RedoCellChange,
         /// Restarts the kernel.
         RestartKernel,
         /// Interrupts the current execution.
@@ -74,13 +100,13 @@ pub(crate) const CONTROL_SIZE: f32 = 20.0;
 
 pub fn init(cx: &mut App) {
     if cx.has_flag::<NotebookFeatureFlag>() || std::env::var("LOCAL_NOTEBOOK_DEV").is_ok() {
-        workspace::register_project_item::<NotebookEditor>(cx);
+        register(cx);
     }
 
     cx.observe_flag::<NotebookFeatureFlag, _>({
         move |is_enabled, cx| {
             if is_enabled {
-                workspace::register_project_item::<NotebookEditor>(cx);
+                register(cx);
             } else {
                 // todo: there is no way to unregister a project item, so if the feature flag
                 // gets turned off they need to restart Zed.
@@ -90,6 +116,102 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+fn register(cx: &mut App) {
+    workspace::register_project_item::<NotebookEditor>(cx);
+    // Project search matches the sources and outputs of cells, and opens matches in the notebook.
+    project::search_text::register_search_text_provider(Arc::new(NotebookSearchTextProvider), cx);
+}
+
+/// A search match in a notebook, either in the source of a cell or in the text of one of its outputs.
+#[derive(Clone, Debug)]
+pub enum NotebookMatch {
+    Source {
+        cell_id: CellId,
+        range: Range<editor::Anchor>,
+    },
+    Output {
+        cell_id: CellId,
+        output_index: usize,
+        range: Range<usize>,
+    },
+}
+
+impl NotebookMatch {
+    pub fn cell_id(&self) -> &CellId {
+        match self {
+            NotebookMatch::Source { cell_id, .. } | NotebookMatch::Output { cell_id, .. } => {
+                cell_id
+            }
+        }
+    }
+}
+
+/// The cell that was selected when a navigation history entry was pushed.
+struct NotebookNavigationData {
+    cell_id: CellId,
+}
+
+/// A change to the cells of a notebook, as opposed to an edit within a cell.
+#[derive(Clone)]
+enum CellEdit {
+    Insert {
+        index: usize,
+        cell: Cell,
+    },
+    Remove {
+        index: usize,
+        cell: Cell,
+    },
+    Move {
+        from: usize,
+        to: usize,
+    },
+    /// Swaps a cell for another one with the same id, e.g. to change its type.
+    Replace {
+        old: Cell,
+        new: Cell,
+    },
+    SetSource {
+        cell: Cell,
+        old: String,
+        new: String,
+    },
+}
+
+impl CellEdit {
+    fn inverse(&self) -> Self {
+        match self.clone() {
+            CellEdit::Insert { index, cell } => CellEdit::Remove { index, cell },
+            CellEdit::Remove { index, cell } => CellEdit::Insert { index, cell },
+            CellEdit::Move { from, to } => CellEdit::Move { from: to, to: from },
+            CellEdit::Replace { old, new } => CellEdit::Replace { old: new, new: old },
+            CellEdit::SetSource { cell, old, new } => CellEdit::SetSource {
+                cell,
+                old: new,
+                new: old,
+            },
+        }
+    }
+}
+
+/// A group of cell edits that are undone and redone together.
+struct CellTransaction {
+    edits: Vec<CellEdit>,
+    selection_before: usize,
+    selection_after: usize,
+}
+
+fn cell_types(cell_map: &HashMap<CellId, Cell>) -> HashMap<CellId, CellType> {
+    cell_map
+        .iter()
+        .map(|(cell_id, cell)| (cell_id.clone(), cell.cell_type()))
+        .collect()
+}
+
+fn empty_metadata() -> CellMetadata {
+    serde_json::from_str("{}").expect("empty object should parse")
+}
+
 pub struct NotebookEditor {
     languages: Arc<LanguageRegistry>,
     project: Entity<Project>,
@@ -105,11 +227,16 @@ pub struct NotebookEditor {
     selected_cell_index: usize,
     cell_order: Vec<CellId>,
     original_cell_order: Vec<CellId>,
+    original_cell_types: HashMap<CellId, CellType>,
     cell_map: HashMap<CellId, Cell>,
+    undo_stack: Vec<CellTransaction>,
+    redo_stack: Vec<CellTransaction>,
     kernel: Kernel,
     kernel_specification: Option<KernelSpecification>,
     execution_requests: HashMap<String, CellId>,
     kernel_picker_handle: PopoverMenuHandle<Picker<KernelPickerDelegate>>,
+    nav_history: Option<ItemNavHistory>,
+    has_search_highlights: bool,
 }
 
 impl NotebookEditor {
@@ -118,6 +245,17 @@ impl NotebookEditor {
         notebook_item: Entity<NotebookItem>,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) -> Self {
+        let mut editor = Self::without_kernel(project, notebook_item, window, cx);
+        editor.launch_kernel(window, cx);
+        editor
+    }
+
+    fn without_kernel(
+        project: Entity<Project>,
+        notebook_item: Entity<NotebookItem>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Self {
         let focus_handle = cx.focus_handle();
 
@@ -133,94 +271,11 @@ impl NotebookEditor {
         let mut cell_order = vec![]; // Vec<CellId>
         let mut cell_map = HashMap::default(); // HashMap<CellId, Cell>
 
-        for (index, cell) in notebook_item
-            .read(cx)
-            .notebook
-            .clone()
-            .cells
-            .iter()
-            .enumerate()
-        {
+        for cell in notebook_item.read(cx).notebook.clone().cells.iter() {
             let cell_id = cell.id();
             cell_order.push(cell_id.clone());
             let cell_entity = Cell::load(cell, &languages, notebook_language.clone(), window, cx);
-
-            match &cell_entity {
-                Cell::Code(code_cell) => {
-                    let cell_id_for_focus = cell_id.clone();
-                    cx.subscribe(code_cell, move |this, cell, event, cx| match event {
-                        CellEvent::Run(cell_id) => this.execute_cell(cell_id.clone(), cx),
-                        CellEvent::FocusedIn(_) => {
-                            if let Some(index) = this
-                                .cell_order
-                                .iter()
-                                .position(|id| id == &cell_id_for_focus)
-                            {
-                                this.selected_cell_index = index;
-                                cx.notify();
-                            }
-                        }
-                    })
-                    .detach();
-
-                    let cell_id_for_editor = cell_id.clone();
-                    let editor = code_cell.read(cx).editor().clone();
-                    cx.subscribe(&editor, move |this, _editor, event, cx| {
-                        if let editor::EditorEvent::Focused = event {
-                            if let Some(index) = this
-                                .cell_order
-                                .iter()
-                                .position(|id| id == &cell_id_for_editor)
-                            {
-                                this.selected_cell_index = index;
-                                cx.notify();
-                            }
-                        }
-                    })
-                    .detach();
-                }
-                Cell::Markdown(markdown_cell) => {
-                    let cell_id_for_focus = cell_id.clone();
-                    cx.subscribe(
-                        markdown_cell,
-                        move |_this, cell, event: &MarkdownCellEvent, cx| {
-                            match event {
-                                MarkdownCellEvent::FinishedEditing => {
-                                    cell.update(cx, |cell, cx| {
-                                        cell.reparse_markdown(cx);
-                                    });
-                                }
-                                MarkdownCellEvent::Run(_cell_id) => {
-                                    // run is handled separately by move_to_next_cell
-                                    // Just reparse here
-                                    cell.update(cx, |cell, cx| {
-                                        cell.reparse_markdown(cx);
-                                    });
-                                }
-                            }
-                        },
-                    )
-                    .detach();
-
-                    let cell_id_for_editor = cell_id.clone();
-                    let editor = markdown_cell.read(cx).editor().clone();
-                    cx.subscribe(&editor, move |this, _editor, event, cx| {
-                        if let editor::EditorEvent::Focused = event {
-                            if let Some(index) = this
-                                .cell_order
-                                .iter()
-                                .position(|id| id == &cell_id_for_editor)
-                            {
-                                this.selected_cell_index = index;
-                                cx.notify();
-                            }
-                        }
-                    })
-                    .detach();
-                }
-                Cell::Raw(_) => {}
-            }
-
+            Self::subscribe_to_cell(&cell_entity, cx);
             cell_map.insert(cell_id.clone(), cell_entity);
         }
 
@@ -230,7 +285,7 @@ impl NotebookEditor {
         let this = cx.entity();
         let cell_list = ListState::new(cell_count, gpui::ListAlignment::Top, px(1000.));
 
-        let mut editor = Self {
+        Self {
             project,
             languages: languages.clone(),
             worktree_id,
@@ -242,18 +297,63 @@ impl NotebookEditor {
             selected_cell_index: 0,
             cell_order: cell_order.clone(),
             original_cell_order: cell_order.clone(),
+            original_cell_types: cell_types(&cell_map),
             cell_map: cell_map.clone(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
             kernel: Kernel::StartingKernel(Task::ready(()).shared()),
             kernel_specification: None,
             execution_requests: HashMap::default(),
             kernel_picker_handle: PopoverMenuHandle::default(),
-        };
-        editor.launch_kernel(window, cx);
-        editor
+            nav_history: None,
+            has_search_highlights: false,
+        }
+    }
+
+    fn subscribe_to_cell(cell: &Cell, cx: &mut Context<Self>) {
+        match cell {
+            Cell::Code(code_cell) => {
+                cx.subscribe(code_cell, |this, _cell, event, cx| match event {
+                    CellEvent::Run(cell_id) => this.execute_cell(cell_id.clone(), cx),
+                    CellEvent::FocusedIn(cell_id) => {
+                        if let Some(index) = this.cell_index(cell_id) {
+                            this.selected_cell_index = index;
+                            cx.notify();
+                        }
+                    }
+                })
+                .detach();
+            }
+            Cell::Markdown(markdown_cell) => {
+                cx.subscribe(
+                    markdown_cell,
+                    |_this, cell, event: &MarkdownCellEvent, cx| match event {
+                        // run is handled separately by move_to_next_cell, so just reparse here
+                        MarkdownCellEvent::FinishedEditing | MarkdownCellEvent::Run(_) => {
+                            cell.update(cx, |cell, cx| cell.reparse_markdown(cx));
+                        }
+                    },
+                )
+                .detach();
+            }
+            Cell::Raw(_) => {}
+        }
+
+        if let Some(editor) = cell.editor(cx) {
+            let cell_id = cell.id(cx);
+            cx.subscribe(&editor, move |this, _editor, event, cx| {
+                this.handle_cell_editor_event(&cell_id, event, cx)
+            })
+            .detach();
+        }
     }
 
     fn has_structural_changes(&self) -> bool {
         self.cell_order != self.original_cell_order
+            || self.cell_order.iter().any(|cell_id| {
+                self.cell_map.get(cell_id).map(Cell::cell_type).as_ref()
+                    != self.original_cell_types.get(cell_id)
+            })
     }
 
     fn has_content_changes(&self, cx: &App) -> bool {
@@ -271,23 +371,45 @@ impl NotebookEditor {
             })
             .collect();
 
-        let metadata = self.notebook_item.read(cx).notebook.metadata.clone();
+        let notebook = &self.notebook_item.read(cx).notebook;
 
         nbformat::v4::Notebook {
-            metadata,
-            nbformat: 4,
-            nbformat_minor: 5,
+            metadata: notebook.metadata.clone(),
+            nbformat: notebook.nbformat,
+            nbformat_minor: notebook.nbformat_minor,
             cells,
         }
     }
 
+    /// Serializes the notebook for saving, and marks it as saved.
+    fn save_to_json(&mut self, cx: &mut Context<Self>) -> Result<String> {
+        let notebook = self.to_notebook(cx);
+        let unchanged_outputs = self
+            .cell_map
+            .iter()
+            .filter_map(|(cell_id, cell)| match cell {
+                Cell::Code(code_cell) if code_cell.read(cx).outputs_saved() => {
+                    Some(cell_id.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        let json = self
+            .notebook_item
+            .update(cx, |item, _| item.file.save(&notebook, &unchanged_outputs))?;
+        self.mark_as_saved(cx);
+        Ok(json)
+    }
+
     pub fn mark_as_saved(&mut self, cx: &mut Context<Self>) {
         self.original_cell_order = self.cell_order.clone();
+        self.original_cell_types = cell_types(&self.cell_map);
 
         for cell in self.cell_map.values() {
             match cell {
                 Cell::Code(code_cell) => {
                     code_cell.update(cx, |code_cell, cx| {
+                        code_cell.mark_outputs_saved();
                         let editor = code_cell.editor();
                         editor.update(cx, |editor, cx| {
                             editor.buffer().update(cx, |buffer, cx| {
@@ -580,231 +702,545 @@ impl NotebookEditor {
     }
 
     fn move_cell_up(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell up triggered");
-        if self.selected_cell_index > 0 {
-            self.cell_order
-                .swap(self.selected_cell_index, self.selected_cell_index - 1);
-            self.selected_cell_index -= 1;
-            cx.notify();
+        let index = self.selected_cell_index;
+        if index > 0 && index < self.cell_order.len() {
+            self.edit_cells(
+                vec![CellEdit::Move {
+                    from: index,
+                    to: index - 1,
+                }],
+                index - 1,
+                window,
+                cx,
+            );
         }
     }
 
     fn move_cell_down(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell down triggered");
-        if self.selected_cell_index < self.cell_order.len() - 1 {
-            self.cell_order
-                .swap(self.selected_cell_index, self.selected_cell_index + 1);
-            self.selected_cell_index += 1;
-            cx.notify();
+        let index = self.selected_cell_index;
+        if index + 1 < self.cell_order.len() {
+            self.edit_cells(
+                vec![CellEdit::Move {
+                    from: index,
+                    to: index + 1,
+                }],
+                index + 1,
+                window,
+                cx,
+            );
         }
     }
 
     fn add_markdown_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let new_cell_id: CellId = Uuid::new_v4().into();
-        let languages = self.languages.clone();
-        let metadata: nbformat::v4::CellMetadata =
-            serde_json::from_str("{}").expect("empty object should parse");
-
-        let markdown_cell = cx.new(|cx| {
-            super::MarkdownCell::new(
-                new_cell_id.clone(),
-                metadata,
-                String::new(),
-                languages,
-                window,
-                cx,
-            )
-        });
-
-        let insert_index = self.selected_cell_index + 1;
-        self.cell_order.insert(insert_index, new_cell_id.clone());
-        self.cell_map
-            .insert(new_cell_id.clone(), Cell::Markdown(markdown_cell.clone()));
-        self.selected_cell_index = insert_index;
-
-        cx.subscribe(
-            &markdown_cell,
-            move |_this, cell, event: &MarkdownCellEvent, cx| match event {
-                MarkdownCellEvent::FinishedEditing | MarkdownCellEvent::Run(_) => {
-                    cell.update(cx, |cell, cx| {
-                        cell.reparse_markdown(cx);
-                    });
-                }
-            },
-        )
-        .detach();
-
-        let cell_id_for_editor = new_cell_id.clone();
-        let editor = markdown_cell.read(cx).editor().clone();
-        cx.subscribe(&editor, move |this, _editor, event, cx| {
-            if let editor::EditorEvent::Focused = event {
-                if let Some(index) = this
-                    .cell_order
-                    .iter()
-                    .position(|id| id == &cell_id_for_editor)
-                {
-                    this.selected_cell_index = index;
-                    cx.notify();
-                }
-            }
-        })
-        .detach();
-
-        self.cell_list.reset(self.cell_order.len());
-        cx.notify();
+        self.insert_cell_below(CellType::Markdown, window, cx);
     }
 
     fn add_code_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let new_cell_id: CellId = Uuid::new_v4().into();
-        let notebook_language = self.notebook_language.clone();
-        let metadata: nbformat::v4::CellMetadata =
-            serde_json::from_str("{}").expect("empty object should parse");
-
-        let code_cell = cx.new(|cx| {
-            super::CodeCell::new(
-                new_cell_id.clone(),
-                metadata,
-                String::new(),
-                notebook_language,
-                window,
-                cx,
-            )
-        });
-
-        let insert_index = self.selected_cell_index + 1;
-        self.cell_order.insert(insert_index, new_cell_id.clone());
-        self.cell_map
-            .insert(new_cell_id.clone(), Cell::Code(code_cell.clone()));
-        self.selected_cell_index = insert_index;
-
-        let cell_id_for_run = new_cell_id.clone();
-        cx.subscribe(&code_cell, move |this, _cell, event, cx| match event {
-            CellEvent::Run(cell_id) => this.execute_cell(cell_id.clone(), cx),
-            CellEvent::FocusedIn(_) => {
-                if let Some(index) = this.cell_order.iter().position(|id| id == &cell_id_for_run) {
-                    this.selected_cell_index = index;
-                    cx.notify();
-                }
-            }
-        })
-        .detach();
-
-        let cell_id_for_editor = new_cell_id.clone();
-        let editor = code_cell.read(cx).editor().clone();
-        cx.subscribe(&editor, move |this, _editor, event, cx| {
-            if let editor::EditorEvent::Focused = event {
-                if let Some(index) = this
-                    .cell_order
-                    .iter()
-                    .position(|id| id == &cell_id_for_editor)
-                {
-                    this.selected_cell_index = index;
-                    cx.notify();
-                }
-            }
-        })
-        .detach();
+        self.insert_cell_below(CellType::Code, window, cx);
+    }
 
-        self.cell_list.reset(self.cell_order.len());
-        cx.notify();
+    fn insert_cell_below(
+        &mut self,
+        cell_type: CellType,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let cell = self.create_cell(
+            cell_type,
+            Uuid::new_v4().into(),
+            empty_metadata(),
+            "",
+            window,
+            cx,
+        );
+        let index = (self.selected_cell_index + 1).min(self.cell_order.len());
+        self.edit_cells(vec![CellEdit::Insert { index, cell }], index, window, cx);
+        self.focus_selected_cell(window, cx);
     }
 
-    fn cell_count(&self) -> usize {
-        self.cell_map.len()
+    fn delete_cell(&mut self, _: &DeleteCell, window: &mut Window, cx: &mut Context<Self>) {
+        // Keep at least one cell around to type into.
+        let index = self.selected_cell_index;
+        if self.cell_order.len() < 2 {
+            return;
+        }
+        let Some(cell) = self.selected_cell() else {
+            return;
+        };
+        let selection = index.min(self.cell_order.len() - 2);
+        self.edit_cells(
+            vec![CellEdit::Remove { index, cell }],
+            selection,
+            window,
+            cx,
+        );
+        self.focus_selected_cell(window, cx);
     }
 
-    fn selected_index(&self) -> usize {
-        self.selected_cell_index
+    fn split_cell(&mut self, _: &SplitCell, window: &mut Window, cx: &mut Context<Self>) {
+        let index = self.selected_cell_index;
+        let Some(cell) = self.selected_cell() else {
+            return;
+        };
+        let Some(editor) = cell.editor(cx) else {
+            return;
+        };
+        let Some(offset) = editor.update(cx, |editor, cx| {
+            let cursor = editor
+                .selections
+                .newest::<language::Point>(&editor.display_snapshot(cx))
+                .head();
+            let buffer = editor.buffer().read(cx).as_singleton()?;
+            Some(buffer.read(cx).point_to_offset(cursor))
+        }) else {
+            return;
+        };
+
+        let source = cell.current_source(cx);
+        let (before, after) = source.split_at(offset.min(source.len()));
+        let before = before.strip_suffix('\n').unwrap_or(before).to_string();
+        let after = after.strip_prefix('\n').unwrap_or(after);
+        let new_cell = self.create_cell(
+            cell.cell_type(),
+            Uuid::new_v4().into(),
+            empty_metadata(),
+            after,
+            window,
+            cx,
+        );
+        self.edit_cells(
+            vec![
+                CellEdit::SetSource {
+                    cell,
+                    old: source.clone(),
+                    new: before,
+                },
+                CellEdit::Insert {
+                    index: index + 1,
+                    cell: new_cell,
+                },
+            ],
+            index + 1,
+            window,
+            cx,
+        );
+        self.focus_selected_cell(window, cx);
     }
 
-    pub fn set_selected_index(
+    fn merge_cell_below(
         &mut self,
-        index: usize,
-        jump_to_index: bool,
+        _: &MergeCellBelow,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        // let previous_index = self.selected_cell_index;
-        self.selected_cell_index = index;
-        let current_index = self.selected_cell_index;
-
-        // in the future we may have some `on_cell_change` event that we want to fire here
+        let index = self.selected_cell_index;
+        let Some(cell) = self.selected_cell() else {
+            return;
+        };
+        let Some(next_cell) = self
+            .cell_order
+            .get(index + 1)
+            .and_then(|cell_id| self.cell_map.get(cell_id))
+            .cloned()
+        else {
+            return;
+        };
 
-        if jump_to_index {
-            self.jump_to_cell(current_index, window, cx);
-        }
+        let source = cell.current_source(cx);
+        let next_source = next_cell.current_source(cx);
+        let merged = match (source.is_empty(), next_source.is_empty()) {
+            (_, true) => source.clone(),
+            (true, false) => next_source,
+            (false, false) => format!("{}\n\n{}", source.trim_end_matches('\n'), next_source),
+        };
+        self.edit_cells(
+            vec![
+                CellEdit::SetSource {
+                    cell,
+                    old: source,
+                    new: merged,
+                },
+                CellEdit::Remove {
+                    index: index + 1,
+                    cell: next_cell,
+                },
+            ],
+            index,
+            window,
+            cx,
+        );
+        self.focus_selected_cell(window, cx);
     }
 
-    pub fn select_next(
+    fn change_cell_type(
         &mut self,
-        _: &menu::SelectNext,
+        cell_type: CellType,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let count = self.cell_count();
-        if count > 0 {
-            let index = self.selected_index();
-            let ix = if index == count - 1 {
-                count - 1
-            } else {
-                index + 1
-            };
-            self.set_selected_index(ix, true, window, cx);
-            cx.notify();
+        let Some(cell) = self.selected_cell() else {
+            return;
+        };
+        if cell.cell_type() == cell_type {
+            return;
         }
+        let new_cell = self.create_cell(
+            cell_type,
+            cell.id(cx),
+            cell.metadata(cx),
+            &cell.current_source(cx),
+            window,
+            cx,
+        );
+        self.edit_cells(
+            vec![CellEdit::Replace {
+                old: cell,
+                new: new_cell,
+            }],
+            self.selected_cell_index,
+            window,
+            cx,
+        );
+        self.focus_selected_cell(window, cx);
     }
 
-    pub fn select_previous(
+    fn create_cell(
+        &self,
+        cell_type: CellType,
+        id: CellId,
+        metadata: CellMetadata,
+        source: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Cell {
+        let source = source_lines(source);
+        let cell = match cell_type {
+            CellType::Code => nbformat::v4::Cell::Code {
+                id,
+                metadata,
+                execution_count: None,
+                source,
+                outputs: Vec::new(),
+            },
+            CellType::Markdown => nbformat::v4::Cell::Markdown {
+                id,
+                metadata,
+                source,
+                attachments: None,
+            },
+            CellType::Raw => nbformat::v4::Cell::Raw {
+                id,
+                metadata,
+                source,
+            },
+        };
+        let cell = Cell::load(
+            &cell,
+            &self.languages,
+            self.notebook_language.clone(),
+            window,
+            cx,
+        );
+        Self::subscribe_to_cell(&cell, cx);
+        cell
+    }
+
+    fn selected_cell(&self) -> Option<Cell> {
+        let cell_id = self.cell_order.get(self.selected_cell_index)?;
+        self.cell_map.get(cell_id).cloned()
+    }
+
+    fn focus_selected_cell(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let editor = self.selected_cell().and_then(|cell| match cell {
+            Cell::Code(code_cell) => Some(code_cell.read(cx).editor().clone()),
+            Cell::Markdown(markdown_cell) if markdown_cell.read(cx).is_editing() => {
+                Some(markdown_cell.read(cx).editor().clone())
+            }
+            Cell::Markdown(_) | Cell::Raw(_) => None,
+        });
+        match editor {
+            Some(editor) => window.focus(&editor.focus_handle(cx), cx),
+            None => window.focus(&self.focus_handle, cx),
+        }
+    }
+
+    fn edit_cells(
         &mut self,
-        _: &menu::SelectPrevious,
+        edits: Vec<CellEdit>,
+        selection_after: usize,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let count = self.cell_count();
-        if count > 0 {
-            let index = self.selected_index();
-            let ix = if index == 0 { 0 } else { index - 1 };
-            self.set_selected_index(ix, true, window, cx);
-            cx.notify();
+        for edit in &edits {
+            self.apply_cell_edit(edit, window, cx);
         }
+        self.undo_stack.push(CellTransaction {
+            edits,
+            selection_before: self.selected_cell_index,
+            selection_after,
+        });
+        self.redo_stack.clear();
+        self.cells_changed(selection_after, window, cx);
     }
 
-    pub fn select_first(
+    fn undo_cell_change(
         &mut self,
-        _: &menu::SelectFirst,
+        _: &UndoCellChange,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let count = self.cell_count();
-        if count > 0 {
-            self.set_selected_index(0, true, window, cx);
-            cx.notify();
+        let Some(transaction) = self.undo_stack.pop() else {
+            return;
+        };
+        for edit in transaction.edits.iter().rev() {
+            self.apply_cell_edit(&edit.inverse(), window, cx);
         }
+        let selection = transaction.selection_before;
+        self.redo_stack.push(transaction);
+        self.cells_changed(selection, window, cx);
     }
 
-    pub fn select_last(
+    fn redo_cell_change(
         &mut self,
-        _: &menu::SelectLast,
+        _: &RedoCellChange,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let count = self.cell_count();
-        if count > 0 {
-            self.set_selected_index(count - 1, true, window, cx);
-            cx.notify();
+        let Some(transaction) = self.redo_stack.pop() else {
+            return;
+        };
+        for edit in &transaction.edits {
+            self.apply_cell_edit(edit, window, cx);
         }
+        let selection = transaction.selection_after;
+        self.undo_stack.push(transaction);
+        self.cells_changed(selection, window, cx);
     }
 
-    fn jump_to_cell(&mut self, index: usize, _window: &mut Window, _cx: &mut Context<Self>) {
-        self.cell_list.scroll_to_reveal_item(index);
+    fn apply_cell_edit(&mut self, edit: &CellEdit, window: &mut Window, cx: &mut Context<Self>) {
+        match edit {
+            CellEdit::Insert { index, cell } => {
+                let cell_id = cell.id(cx);
+                self.cell_order.insert(*index, cell_id.clone());
+                self.cell_map.insert(cell_id, cell.clone());
+            }
+            CellEdit::Remove { index, .. } => {
+                let cell_id = self.cell_order.remove(*index);
+                self.cell_map.remove(&cell_id);
+            }
+            CellEdit::Move { from, to } => {
+                let cell_id = self.cell_order.remove(*from);
+                self.cell_order.insert(*to, cell_id);
+            }
+            CellEdit::Replace { new, .. } => {
+                self.cell_map.insert(new.id(cx), new.clone());
+            }
+            CellEdit::SetSource { cell, new, .. } => cell.set_source(new.clone(), window, cx),
+        }
     }
 
-    fn button_group(window: &mut Window, cx: &mut Context<Self>) -> Div {
-        v_flex()
-            .gap(DynamicSpacing::Base04.rems(cx))
-            .items_center()
-            .w(px(CONTROL_SIZE + 4.0))
-            .overflow_hidden()
+    fn cells_changed(&mut self, selection: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.cell_list.reset(self.cell_order.len());
+        self.selected_cell_index = selection.min(self.cell_order.len().saturating_sub(1));
+        self.jump_to_cell(self.selected_cell_index, window, cx);
+        if self.has_search_highlights {
+            cx.emit(SearchEvent::MatchesInvalidated);
+        }
+        cx.notify();
+    }
+
+    fn handle_cell_editor_event(
+        &mut self,
+        cell_id: &CellId,
+        event: &editor::EditorEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            editor::EditorEvent::Focused => {
+                if let Some(index) = self.cell_index(cell_id) {
+                    self.select_cell(index, cx);
+                    cx.notify();
+                }
+            }
+            editor::EditorEvent::BufferEdited => cx.emit(SearchEvent::MatchesInvalidated),
+            editor::EditorEvent::SelectionsChanged { local: true } => {
+                cx.emit(SearchEvent::ActiveMatchChanged)
+            }
+            _ => {}
+        }
+    }
+
+    fn push_to_nav_history(&mut self, cx: &mut Context<Self>) {
+        if let Some(nav_history) = self.nav_history.as_mut()
+            && let Some(cell_id) = self.cell_order.get(self.selected_cell_index)
+        {
+            nav_history.push(
+                Some(NotebookNavigationData {
+                    cell_id: cell_id.clone(),
+                }),
+                cx,
+            );
+        }
+    }
+
+    /// Like an editor, only records jumps in the navigation history, so that stepping through
+    /// adjacent cells doesn't bury the place the user came from.
+    fn select_cell(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index.abs_diff(self.selected_cell_index) > 1 {
+            self.push_to_nav_history(cx);
+        }
+        self.selected_cell_index = index;
+    }
+
+    /// Selects the cell at the given offset in the notebook's searchable text, as laid out for
+    /// project search when the notebook was read from disk, and moves the cursor to the offset
+    /// if it's in the cell's source.
+    fn navigate_to_search_text(
+        &mut self,
+        offset: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let notebook = &self.notebook_item.read(cx).notebook;
+        let (_, segments) = notebook_search_text(notebook);
+        let Some(segment) = segment_at(&segments, offset) else {
+            return false;
+        };
+        let Some(cell_id) = notebook
+            .cells
+            .get(segment.cell_index)
+            .map(|cell| cell.id().clone())
+        else {
+            return false;
+        };
+        let Some(index) = self.cell_index(&cell_id) else {
+            return false;
+        };
+
+        self.set_selected_index(index, true, window, cx);
+        if segment.part == SearchTextPart::Source
+            && let Some(cell) = self.cell_map.get(&cell_id).cloned()
+            && let Some(editor) = cell.editor(cx)
+        {
+            if let Cell::Markdown(markdown_cell) = &cell {
+                markdown_cell.update(cx, |cell, cx| {
+                    cell.set_editing(true);
+                    cx.notify();
+                });
+            }
+            let offset_in_source = offset - segment.range.start;
+            editor.update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let offset = snapshot.clip_offset(MultiBufferOffset(offset_in_source), Bias::Left);
+                editor.change_selections(Default::default(), window, cx, |selections| {
+                    selections.select_ranges([offset..offset])
+                });
+            });
+            window.focus(&editor.focus_handle(cx), cx);
+        }
+        cx.notify();
+        true
+    }
+
+    fn cell_index(&self, cell_id: &CellId) -> Option<usize> {
+        self.cell_order.iter().position(|id| id == cell_id)
+    }
+
+    fn cell_count(&self) -> usize {
+        self.cell_map.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_cell_index
+    }
+
+    pub fn set_selected_index(
+        &mut self,
+        index: usize,
+        jump_to_index: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_cell(index, cx);
+        let current_index = self.selected_cell_index;
+
+        // in the future we may have some `on_cell_change` event that we want to fire here
+
+        if jump_to_index {
+            self.jump_to_cell(current_index, window, cx);
+        }
+    }
+
+    pub fn select_next(
+        &mut self,
+        _: &menu::SelectNext,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let count = self.cell_count();
+        if count > 0 {
+            let index = self.selected_index();
+            let ix = if index == count - 1 {
+                count - 1
+            } else {
+                index + 1
+            };
+            self.set_selected_index(ix, true, window, cx);
+            cx.notify();
+        }
+    }
+
+    pub fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let count = self.cell_count();
+        if count > 0 {
+            let index = self.selected_index();
+            let ix = if index == 0 { 0 } else { index - 1 };
+            self.set_selected_index(ix, true, window, cx);
+            cx.notify();
+        }
+    }
+
+    pub fn select_first(
+        &mut self,
+        _: &menu::SelectFirst,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let count = self.cell_count();
+        if count > 0 {
+            self.set_selected_index(0, true, window, cx);
+            cx.notify();
+        }
+    }
+
+    pub fn select_last(
+        &mut self,
+        _: &menu::SelectLast,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let count = self.cell_count();
+        if count > 0 {
+            self.set_selected_index(count - 1, true, window, cx);
+            cx.notify();
+        }
+    }
+
+    fn jump_to_cell(&mut self, index: usize, _window: &mut Window, _cx: &mut Context<Self>) {
+        self.cell_list.scroll_to_reveal_item(index);
+    }
+
+    fn button_group(window: &mut Window, cx: &mut Context<Self>) -> Div {
+        v_flex()
+            .gap(DynamicSpacing::Base04.rems(cx))
+            .items_center()
+            .w(px(CONTROL_SIZE + 4.0))
+            .overflow_hidden()
             .rounded(px(5.))
             .bg(cx.theme().colors().title_bar_background)
             .p_px()
@@ -939,10 +1375,38 @@ impl NotebookEditor {
                 v_flex()
                     .gap(DynamicSpacing::Base08.rems(cx))
                     .items_center()
-                    .child(
-                        Self::render_notebook_control("more-menu", IconName::Ellipsis, window, cx)
-                            .tooltip(move |window, cx| (Tooltip::text("More options"))(window, cx)),
-                    )
+                    .child({
+                        let focus_handle = self.focus_handle.clone();
+                        PopoverMenu::new("notebook-more-menu")
+                            .trigger_with_tooltip(
+                                Self::render_notebook_control(
+                                    "more-menu",
+                                    IconName::Ellipsis,
+                                    window,
+                                    cx,
+                                ),
+                                Tooltip::text("More options"),
+                            )
+                            .menu(move |window, cx| {
+                                Some(ContextMenu::build(window, cx, |menu, _, _| {
+                                    menu.context(focus_handle.clone())
+                                        .action("Split Cell", Box::new(SplitCell))
+                                        .action("Merge Cell Below", Box::new(MergeCellBelow))
+                                        .action("Delete Cell", Box::new(DeleteCell))
+                                        .separator()
+                                        .action("Change to Code", Box::new(ChangeCellToCode))
+                                        .action(
+                                            "Change to Markdown",
+                                            Box::new(ChangeCellToMarkdown),
+                                        )
+                                        .action("Change to Raw", Box::new(ChangeCellToRaw))
+                                        .separator()
+                                        .action("Undo Cell Change", Box::new(UndoCellChange))
+                                        .action("Redo Cell Change", Box::new(RedoCellChange))
+                                }))
+                            })
+                            .anchor(gpui::Corner::BottomLeft)
+                    })
                     .child(Self::button_group(window, cx).child({
                         let kernel_status = self.kernel.status();
                         let (icon, icon_color) = match &kernel_status {
@@ -1164,6 +1628,20 @@ impl Render for NotebookEditor {
             .on_action(
                 cx.listener(|this, &AddCodeBlock, window, cx| this.add_code_block(window, cx)),
             )
+            .on_action(cx.listener(Self::delete_cell))
+            .on_action(cx.listener(Self::split_cell))
+            .on_action(cx.listener(Self::merge_cell_below))
+            .on_action(cx.listener(|this, &ChangeCellToCode, window, cx| {
+                this.change_cell_type(CellType::Code, window, cx)
+            }))
+            .on_action(cx.listener(|this, &ChangeCellToMarkdown, window, cx| {
+                this.change_cell_type(CellType::Markdown, window, cx)
+            }))
+            .on_action(cx.listener(|this, &ChangeCellToRaw, window, cx| {
+                this.change_cell_type(CellType::Raw, window, cx)
+            }))
+            .on_action(cx.listener(Self::undo_cell_change))
+            .on_action(cx.listener(Self::redo_cell_change))
             .on_action(
                 cx.listener(|this, action, window, cx| this.restart_kernel(action, window, cx)),
             )
@@ -1196,6 +1674,7 @@ pub struct NotebookItem {
     languages: Arc<LanguageRegistry>,
     // Raw notebook data
     notebook: nbformat::v4::Notebook,
+    file: NotebookFile,
     // Store our version of the notebook in memory (cell_order, cell_map)
     id: ProjectEntryId,
 }
@@ -1219,34 +1698,7 @@ impl project::ProjectItem for NotebookItem {
 
                 // todo: watch for changes to the file
                 let file_content = fs.load(abs_path.as_path()).await?;
-
-                // Pre-process to ensure IDs exist
-                let mut json: serde_json::Value = serde_json::from_str(&file_content)?;
-                if let Some(cells) = json.get_mut("cells").and_then(|c| c.as_array_mut()) {
-                    for cell in cells {
-                        if cell.get("id").is_none() {
-                            cell["id"] = serde_json::Value::String(Uuid::new_v4().to_string());
-                        }
-                    }
-                }
-                let file_content = serde_json::to_string(&json)?;
-
-                let notebook = nbformat::parse_notebook(&file_content);
-
-                let notebook = match notebook {
-                    Ok(nbformat::Notebook::V4(notebook)) => notebook,
-                    // 4.1 - 4.4 are converted to 4.5
-                    Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
-                        // TODO: Decide if we want to mutate the notebook by including Cell IDs
-                        // and any other conversions
-
-                        nbformat::upgrade_legacy_notebook(legacy_notebook)?
-                    }
-                    // Bad notebooks and notebooks v4.0 and below are not supported
-                    Err(e) => {
-                        anyhow::bail!("Failed to parse notebook: {:?}", e);
-                    }
-                };
+                let (file, notebook) = NotebookFile::parse(&file_content)?;
 
                 let id = project
                     .update(cx, |project, cx| {
@@ -1259,6 +1711,7 @@ impl project::ProjectItem for NotebookItem {
                     project_path: path,
                     languages,
                     notebook,
+                    file,
                     id,
                 }))
             }))
@@ -1276,7 +1729,8 @@ impl project::ProjectItem for NotebookItem {
     }
 
     fn is_dirty(&self) -> bool {
-        // TODO: Track if notebook metadata or structure has changed
+        // This is the notebook as it is on disk. Unsaved changes to its cells, structure and
+        // outputs are tracked by `NotebookEditor::is_dirty`.
         false
     }
 }
@@ -1312,6 +1766,318 @@ impl NotebookItem {
 
 impl EventEmitter<()> for NotebookEditor {}
 
+impl EventEmitter<SearchEvent> for NotebookEditor {}
+
+impl NotebookEditor {
+    /// The source ranges among `matches` that belong to the given cell.
+    fn source_ranges_in_cell<'a>(
+        matches: impl IntoIterator<Item = &'a NotebookMatch>,
+        cell_id: &CellId,
+    ) -> Vec<Range<editor::Anchor>> {
+        matches
+            .into_iter()
+            .filter_map(|mat| match mat {
+                NotebookMatch::Source {
+                    cell_id: match_cell_id,
+                    range,
+                } if match_cell_id == cell_id => Some(range.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl SearchableItem for NotebookEditor {
+    type Match = NotebookMatch;
+
+    fn supported_options(&self) -> SearchOptions {
+        SearchOptions {
+            case: true,
+            word: true,
+            regex: true,
+            replacement: true,
+            selection: false,
+            find_in_results: false,
+        }
+    }
+
+    fn clear_matches(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for cell in self.cell_map.values() {
+            if let Some(editor) = cell.editor(cx) {
+                editor.update(cx, |editor, cx| editor.clear_matches(window, cx));
+            }
+            if let Cell::Code(code_cell) = cell {
+                code_cell.update(cx, |cell, cx| {
+                    cell.set_output_highlights(Vec::new(), None, cx)
+                });
+            }
+        }
+        if std::mem::take(&mut self.has_search_highlights) {
+            cx.emit(SearchEvent::MatchesInvalidated);
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        matches: &[NotebookMatch],
+        active_match_index: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let active_match = active_match_index.and_then(|ix| matches.get(ix));
+        for (cell_id, cell) in &self.cell_map {
+            let cell_matches = matches.iter().filter(|mat| mat.cell_id() == cell_id);
+
+            if let Some(editor) = cell.editor(cx) {
+                let ranges = Self::source_ranges_in_cell(cell_matches.clone(), cell_id);
+                let active_range = match active_match {
+                    Some(NotebookMatch::Source {
+                        cell_id: active_cell_id,
+                        range,
+                    }) if active_cell_id == cell_id => ranges.iter().position(|r| r == range),
+                    _ => None,
+                };
+                editor.update(cx, |editor, cx| {
+                    editor.update_matches(&ranges, active_range, window, cx)
+                });
+            }
+
+            if let Cell::Code(code_cell) = cell {
+                let mut outputs = cell_matches
+                    .filter_map(|mat| match mat {
+                        NotebookMatch::Output { output_index, .. } => Some(*output_index),
+                        NotebookMatch::Source { .. } => None,
+                    })
+                    .collect::<Vec<_>>();
+                outputs.dedup();
+                let active_output = match active_match {
+                    Some(NotebookMatch::Output {
+                        cell_id: active_cell_id,
+                        output_index,
+                        ..
+                    }) if active_cell_id == cell_id => Some(*output_index),
+                    _ => None,
+                };
+                code_cell.update(cx, |cell, cx| {
+                    cell.set_output_highlights(outputs, active_output, cx)
+                });
+            }
+        }
+        self.has_search_highlights = !matches.is_empty();
+    }
+
+    fn query_suggestion(&mut self, window: &mut Window, cx: &mut Context<Self>) -> String {
+        let Some(editor) = self
+            .cell_order
+            .get(self.selected_cell_index)
+            .and_then(|cell_id| self.cell_map.get(cell_id))
+            .and_then(|cell| cell.editor(cx))
+        else {
+            return String::new();
+        };
+        editor.update(cx, |editor, cx| editor.query_suggestion(window, cx))
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[NotebookMatch],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(mat) = matches.get(index) else {
+            return;
+        };
+        let cell_id = mat.cell_id().clone();
+        let Some(cell_index) = self.cell_index(&cell_id) else {
+            return;
+        };
+        self.set_selected_index(cell_index, true, window, cx);
+
+        if let NotebookMatch::Source { range, .. } = mat
+            && let Some(cell) = self.cell_map.get(&cell_id).cloned()
+            && let Some(editor) = cell.editor(cx)
+        {
+            // Rendered markdown can't show highlights, so switch the cell to its editor.
+            if let Cell::Markdown(markdown_cell) = &cell {
+                markdown_cell.update(cx, |cell, cx| {
+                    cell.set_editing(true);
+                    cx.notify();
+                });
+            }
+
+            let ranges = Self::source_ranges_in_cell(matches, &cell_id);
+            if let Some(range_index) = ranges.iter().position(|r| r == range) {
+                editor.update(cx, |editor, cx| {
+                    editor.activate_match(range_index, &ranges, window, cx)
+                });
+            }
+        }
+        cx.notify();
+    }
+
+    fn select_matches(
+        &mut self,
+        matches: &[NotebookMatch],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for (cell_id, cell) in &self.cell_map {
+            let ranges = Self::source_ranges_in_cell(matches, cell_id);
+            if !ranges.is_empty()
+                && let Some(editor) = cell.editor(cx)
+            {
+                editor.update(cx, |editor, cx| editor.select_matches(&ranges, window, cx));
+            }
+        }
+    }
+
+    fn replace(
+        &mut self,
+        mat: &NotebookMatch,
+        query: &SearchQuery,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Outputs are produced by the kernel and can't be edited.
+        if let NotebookMatch::Source { cell_id, range } = mat
+            && let Some(editor) = self.cell_map.get(cell_id).and_then(|cell| cell.editor(cx))
+        {
+            editor.update(cx, |editor, cx| editor.replace(range, query, window, cx));
+        }
+    }
+
+    fn replace_all(
+        &mut self,
+        matches: &mut dyn Iterator<Item = &NotebookMatch>,
+        query: &SearchQuery,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let matches = matches.collect::<Vec<_>>();
+        for (cell_id, cell) in &self.cell_map {
+            let ranges = Self::source_ranges_in_cell(matches.iter().copied(), cell_id);
+            if !ranges.is_empty()
+                && let Some(editor) = cell.editor(cx)
+            {
+                editor.update(cx, |editor, cx| {
+                    editor.replace_all(&mut ranges.iter(), query, window, cx)
+                });
+            }
+        }
+    }
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<NotebookMatch>> {
+        let mut cell_searches = Vec::new();
+        for cell_id in &self.cell_order {
+            let Some(cell) = self.cell_map.get(cell_id) else {
+                continue;
+            };
+
+            let source_matches = cell.editor(cx).map(|editor| {
+                editor.update(cx, |editor, cx| {
+                    editor.find_matches(query.clone(), window, cx)
+                })
+            });
+
+            let output_texts = match cell {
+                Cell::Code(code_cell) => code_cell
+                    .read(cx)
+                    .outputs()
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(ix, output)| Some((ix, output.searchable_text(cx)?)))
+                    .collect(),
+                Cell::Markdown(_) | Cell::Raw(_) => Vec::new(),
+            };
+            let output_snapshots = output_texts
+                .into_iter()
+                .map(|(ix, text)| (ix, Buffer::build_snapshot(text.into(), None, None, cx)))
+                .collect::<Vec<_>>();
+
+            cell_searches.push((cell_id.clone(), source_matches, output_snapshots));
+        }
+
+        cx.background_spawn(async move {
+            let mut matches = Vec::new();
+            for (cell_id, source_matches, output_snapshots) in cell_searches {
+                if let Some(source_matches) = source_matches {
+                    matches.extend(source_matches.await.into_iter().map(|range| {
+                        NotebookMatch::Source {
+                            cell_id: cell_id.clone(),
+                            range,
+                        }
+                    }));
+                }
+                for (output_index, snapshot) in output_snapshots {
+                    let snapshot = snapshot.await;
+                    matches.extend(
+                        query
+                            .search(&snapshot, None)
+                            .await
+                            .into_iter()
+                            .map(|range| NotebookMatch::Output {
+                                cell_id: cell_id.clone(),
+                                output_index,
+                                range,
+                            }),
+                    );
+                }
+            }
+            matches
+        })
+    }
+
+    fn active_match_index(
+        &mut self,
+        direction: Direction,
+        matches: &[NotebookMatch],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        if matches.is_empty() {
+            return None;
+        }
+        let selected_index = self.selected_cell_index;
+        let cell_id = self.cell_order.get(selected_index)?.clone();
+
+        // Within the selected cell, defer to its editor so the match under the cursor wins.
+        if let Some(first_in_cell) = matches.iter().position(|mat| mat.cell_id() == &cell_id) {
+            let ranges = Self::source_ranges_in_cell(&matches[first_in_cell..], &cell_id);
+            let index_in_cell = if ranges.is_empty() {
+                None
+            } else {
+                self.cell_map
+                    .get(&cell_id)
+                    .and_then(|cell| cell.editor(cx))
+                    .and_then(|editor| {
+                        editor.update(cx, |editor, cx| {
+                            editor.active_match_index(direction, &ranges, window, cx)
+                        })
+                    })
+            };
+            return Some(first_in_cell + index_in_cell.unwrap_or(0));
+        }
+
+        let cell_index_of = |mat: &NotebookMatch| self.cell_index(mat.cell_id());
+        match direction {
+            Direction::Next => matches
+                .iter()
+                .position(|mat| cell_index_of(mat).is_some_and(|ix| ix > selected_index))
+                .or(Some(0)),
+            Direction::Prev => matches
+                .iter()
+                .rposition(|mat| cell_index_of(mat).is_some_and(|ix| ix < selected_index))
+                .or(Some(matches.len() - 1)),
+        }
+    }
+}
+
 // pub struct NotebookControls {
 //     pane_focused: bool,
 //     active_item: Option<Box<dyn ItemHandle>>,
@@ -1414,26 +2180,63 @@ impl Item for NotebookEditor {
     }
 
     fn show_toolbar(&self) -> bool {
-        false
+        true
     }
 
-    // TODO
-    fn pixel_position_of_cursor(&self, _: &App) -> Option<Point<Pixels>> {
-        None
+    fn pixel_position_of_cursor(&self, cx: &App) -> Option<Point<Pixels>> {
+        let cell_id = self.cell_order.get(self.selected_cell_index)?;
+        let editor = self.cell_map.get(cell_id)?.editor(cx)?;
+        editor.read(cx).pixel_position_of_cursor(cx)
     }
 
-    // TODO
-    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
-        None
+    fn as_searchable(
+        &self,
+        handle: &Entity<Self>,
+        _: &App,
+    ) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
     }
 
     fn set_nav_history(
         &mut self,
-        _: workspace::ItemNavHistory,
+        nav_history: ItemNavHistory,
         _window: &mut Window,
         _: &mut Context<Self>,
     ) {
-        // TODO
+        self.nav_history = Some(nav_history);
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if let Some(position) = data.downcast_ref::<SearchTextPosition>() {
+            return self.navigate_to_search_text(position.offset, window, cx);
+        }
+        let Some(data) = data.downcast_ref::<NotebookNavigationData>() else {
+            return false;
+        };
+        let Some(index) = self.cell_index(&data.cell_id) else {
+            return false;
+        };
+        if index == self.selected_cell_index {
+            return false;
+        }
+
+        self.selected_cell_index = index;
+        self.jump_to_cell(index, window, cx);
+        if let Some(Cell::Code(code_cell)) = self.cell_map.get(&data.cell_id) {
+            let editor = code_cell.read(cx).editor().clone();
+            window.focus(&editor.focus_handle(cx), cx);
+        }
+        cx.notify();
+        true
+    }
+
+    fn deactivated(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        self.push_to_nav_history(cx);
     }
 
     fn can_save(&self, _cx: &App) -> bool {
@@ -1447,16 +2250,12 @@ impl Item for NotebookEditor {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        let notebook = self.to_notebook(cx);
+        let json = self.save_to_json(cx);
         let path = self.notebook_item.read(cx).path.clone();
         let fs = project.read(cx).fs().clone();
 
-        self.mark_as_saved(cx);
-
         cx.spawn(async move |_this, _cx| {
-            let json =
-                serde_json::to_string_pretty(&notebook).context("Failed to serialize notebook")?;
-            fs.atomic_write(path, json).await?;
+            fs.atomic_write(path, json?).await?;
             Ok(())
         })
     }
@@ -1468,18 +2267,14 @@ impl Item for NotebookEditor {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        let notebook = self.to_notebook(cx);
+        let json = self.save_to_json(cx);
         let fs = project.read(cx).fs().clone();
 
         let abs_path = project.read(cx).absolute_path(&path, cx);
 
-        self.mark_as_saved(cx);
-
         cx.spawn(async move |_this, _cx| {
             let abs_path = abs_path.context("Failed to get absolute path")?;
-            let json =
-                serde_json::to_string_pretty(&notebook).context("Failed to serialize notebook")?;
-            fs.atomic_write(abs_path, json).await?;
+            fs.atomic_write(abs_path, json?).await?;
             Ok(())
         })
     }
@@ -1497,27 +2292,7 @@ impl Item for NotebookEditor {
 
         cx.spawn_in(window, async move |this, cx| {
             let file_content = fs.load(&path).await?;
-
-            let mut json: serde_json::Value = serde_json::from_str(&file_content)?;
-            if let Some(cells) = json.get_mut("cells").and_then(|c| c.as_array_mut()) {
-                for cell in cells {
-                    if cell.get("id").is_none() {
-                        cell["id"] = serde_json::Value::String(Uuid::new_v4().to_string());
-                    }
-                }
-            }
-            let file_content = serde_json::to_string(&json)?;
-
-            let notebook = nbformat::parse_notebook(&file_content);
-            let notebook = match notebook {
-                Ok(nbformat::Notebook::V4(notebook)) => notebook,
-                Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
-                    nbformat::upgrade_legacy_notebook(legacy_notebook)?
-                }
-                Err(e) => {
-                    anyhow::bail!("Failed to parse notebook: {:?}", e);
-                }
-            };
+            let (file, notebook) = NotebookFile::parse(&file_content)?;
 
             this.update_in(cx, |this, window, cx| {
                 let mut cell_order = vec![];
@@ -1528,12 +2303,21 @@ impl Item for NotebookEditor {
                     cell_order.push(cell_id.clone());
                     let cell_entity =
                         Cell::load(cell, &languages, notebook_language.clone(), window, cx);
+                    Self::subscribe_to_cell(&cell_entity, cx);
                     cell_map.insert(cell_id.clone(), cell_entity);
                 }
 
+                this.notebook_item.update(cx, |item, _| {
+                    item.notebook = notebook;
+                    item.file = file;
+                });
                 this.cell_order = cell_order.clone();
                 this.original_cell_order = cell_order;
+                this.original_cell_types = cell_types(&cell_map);
                 this.cell_map = cell_map;
+                this.undo_stack.clear();
+                this.redo_stack.clear();
+                this.selected_cell_index = 0;
                 this.cell_list =
                     ListState::new(this.cell_order.len(), gpui::ListAlignment::Top, px(1000.));
                 cx.notify();
@@ -1577,6 +2361,9 @@ impl KernelSession for NotebookEditor {
                     cell.update(cx, |cell, cx| {
                         cell.handle_message(message, window, cx);
                     });
+                    if self.has_search_highlights {
+                        cx.emit(SearchEvent::MatchesInvalidated);
+                    }
                 }
             }
         }
@@ -1587,3 +2374,528 @@ impl KernelSession for NotebookEditor {
         cx.notify();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, VisualTestContext};
+    use language::ToOffset as _;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+    use workspace::Workspace;
+
+    use super::*;
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<FakeFs> {
+        let fs = FakeFs::new(cx.executor());
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            ReplStore::init(fs.clone(), cx);
+        });
+        fs
+    }
+
+    fn code_cell(id: &str, source: &str, outputs: serde_json::Value) -> serde_json::Value {
+        json!({
+            "cell_type": "code",
+            "id": id,
+            "metadata": {},
+            "execution_count": null,
+            "source": [source],
+            "outputs": outputs,
+        })
+    }
+
+    fn markdown_cell(id: &str, source: &str) -> serde_json::Value {
+        json!({
+            "cell_type": "markdown",
+            "id": id,
+            "metadata": {},
+            "source": [source],
+        })
+    }
+
+    async fn open_notebook(
+        cells: Vec<serde_json::Value>,
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<Workspace>,
+        Entity<NotebookEditor>,
+        &mut VisualTestContext,
+    ) {
+        let fs = init_test(cx);
+        let notebook = json!({
+            "metadata": {},
+            "nbformat": 4,
+            "nbformat_minor": 5,
+            "cells": cells,
+        });
+        fs.insert_tree(
+            path!("/dir"),
+            json!({ "notebook.ipynb": notebook.to_string() }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let project_path = project
+            .read_with(cx, |project, cx| {
+                project.find_project_path(path!("/dir/notebook.ipynb"), cx)
+            })
+            .unwrap();
+        let item = cx
+            .update(|_, cx| {
+                <NotebookItem as project::ProjectItem>::try_open(&project, &project_path, cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        // Tests don't start a kernel, as that would spawn a real Python process.
+        let notebook = cx.new_window_entity(|window, cx| {
+            NotebookEditor::without_kernel(project.clone(), item, window, cx)
+        });
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.add_item_to_active_pane(Box::new(notebook.clone()), None, true, window, cx)
+        });
+        cx.run_until_parked();
+        (workspace, notebook, cx)
+    }
+
+    fn text_query(query: &str) -> Arc<SearchQuery> {
+        Arc::new(
+            SearchQuery::text(
+                query,
+                false,
+                false,
+                false,
+                Default::default(),
+                Default::default(),
+                false,
+                None,
+            )
+            .unwrap()
+            .with_replacement("baz".to_string()),
+        )
+    }
+
+    async fn find_matches(
+        notebook: &Entity<NotebookEditor>,
+        query: &Arc<SearchQuery>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<NotebookMatch> {
+        notebook
+            .update_in(cx, |notebook, window, cx| {
+                notebook.find_matches(query.clone(), window, cx)
+            })
+            .await
+    }
+
+    fn describe_matches(
+        notebook: &Entity<NotebookEditor>,
+        matches: &[NotebookMatch],
+        cx: &mut VisualTestContext,
+    ) -> Vec<(usize, &'static str)> {
+        notebook.read_with(cx, |notebook, _| {
+            matches
+                .iter()
+                .map(|mat| {
+                    let cell_index = notebook.cell_index(mat.cell_id()).unwrap();
+                    let kind = match mat {
+                        NotebookMatch::Source { .. } => "source",
+                        NotebookMatch::Output { .. } => "output",
+                    };
+                    (cell_index, kind)
+                })
+                .collect()
+        })
+    }
+
+    fn cell_sources(notebook: &Entity<NotebookEditor>, cx: &mut VisualTestContext) -> Vec<String> {
+        notebook.read_with(cx, |notebook, cx| {
+            notebook
+                .cell_order
+                .iter()
+                .map(|cell_id| match &notebook.cell_map[cell_id] {
+                    Cell::Code(cell) => cell.read(cx).current_source(cx),
+                    Cell::Markdown(cell) => cell.read(cx).current_source(cx),
+                    Cell::Raw(_) => String::new(),
+                })
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_search_matches_sources_and_outputs(cx: &mut TestAppContext) {
+        let (_workspace, notebook, cx) = open_notebook(
+            vec![
+                code_cell(
+                    "a",
+                    "print('foo')",
+                    json!([{ "output_type": "stream", "name": "stdout", "text": ["foo\n"] }]),
+                ),
+                markdown_cell("b", "# No match here"),
+                markdown_cell("c", "Some foo and more foo"),
+            ],
+            cx,
+        )
+        .await;
+
+        let matches = find_matches(&notebook, &text_query("foo"), cx).await;
+        assert_eq!(
+            describe_matches(&notebook, &matches, cx),
+            vec![(0, "source"), (0, "output"), (2, "source"), (2, "source")]
+        );
+
+        // Activating a match selects its cell.
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.activate_match(2, &matches, window, cx)
+        });
+        assert_eq!(
+            notebook.read_with(cx, |notebook, _| notebook.selected_index()),
+            2
+        );
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.activate_match(1, &matches, window, cx)
+        });
+        assert_eq!(
+            notebook.read_with(cx, |notebook, _| notebook.selected_index()),
+            0
+        );
+    }
+
+    #[gpui::test]
+    async fn test_search_replace(cx: &mut TestAppContext) {
+        let (_workspace, notebook, cx) = open_notebook(
+            vec![
+                code_cell(
+                    "a",
+                    "foo = 1",
+                    json!([{ "output_type": "stream", "name": "stdout", "text": ["foo\n"] }]),
+                ),
+                code_cell("b", "foo + foo", json!([])),
+            ],
+            cx,
+        )
+        .await;
+        let query = text_query("foo");
+
+        // Replacing a single match only edits that cell.
+        let matches = find_matches(&notebook, &query, cx).await;
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.replace(&matches[2], &query, window, cx)
+        });
+        assert_eq!(cell_sources(&notebook, cx), vec!["foo = 1", "baz + foo"]);
+
+        // Outputs can't be edited, so replacing all leaves them matching.
+        let matches = find_matches(&notebook, &query, cx).await;
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.replace_all(&mut matches.iter(), &query, window, cx)
+        });
+        assert_eq!(cell_sources(&notebook, cx), vec!["baz = 1", "baz + baz"]);
+        let matches = find_matches(&notebook, &query, cx).await;
+        assert_eq!(
+            describe_matches(&notebook, &matches, cx),
+            vec![(0, "output")]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_navigation_history(cx: &mut TestAppContext) {
+        let (workspace, notebook, cx) = open_notebook(
+            (0..5)
+                .map(|ix| code_cell(&format!("cell-{ix}"), &format!("x = {ix}"), json!([])))
+                .collect(),
+            cx,
+        )
+        .await;
+        let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().downgrade());
+        let selected_index = |cx: &mut VisualTestContext| {
+            notebook.read_with(cx, |notebook, _| notebook.selected_index())
+        };
+
+        // Moving to an adjacent cell isn't recorded, but jumping further away is.
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.set_selected_index(1, true, window, cx);
+            notebook.set_selected_index(4, true, window, cx);
+        });
+
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.go_back(pane.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(selected_index(cx), 1);
+
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.go_forward(pane.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(selected_index(cx), 4);
+    }
+
+    #[gpui::test]
+    async fn test_project_search_matches_cells_and_outputs(cx: &mut TestAppContext) {
+        let mut cell = code_cell(
+            "foo-cell",
+            "print('foo')",
+            json!([{
+                "output_type": "execute_result",
+                "execution_count": 1,
+                "metadata": { "foo": "foo" },
+                "data": { "text/plain": ["'foo'"] },
+            }]),
+        );
+        cell["metadata"] = json!({ "tags": ["foo"] });
+        let (_workspace, notebook, cx) = open_notebook(
+            vec![
+                markdown_cell("first", "# Nothing to see"),
+                cell,
+                markdown_cell("last", "The end of foo"),
+            ],
+            cx,
+        )
+        .await;
+        cx.update(|_, cx| {
+            project::search_text::register_search_text_provider(
+                Arc::new(NotebookSearchTextProvider),
+                cx,
+            )
+        });
+
+        let project = notebook.read_with(cx, |notebook, _| notebook.project.clone());
+        let query = SearchQuery::text(
+            "foo",
+            false,
+            false,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap();
+        let search = project.update(cx, |project, cx| project.search(query, cx));
+        let mut results = Vec::new();
+        while let Ok(result) = search.rx.recv().await {
+            if let project::search::SearchResult::Buffer { buffer, ranges } = result {
+                results.push((buffer, ranges));
+            }
+        }
+        assert_eq!(results.len(), 1);
+        let (buffer, ranges) = results.pop().unwrap();
+
+        // Matches are in the searchable text of the notebook, so they only land in cell sources
+        // and outputs rather than in cell ids, metadata or JSON keys.
+        let offsets = buffer.read_with(cx, |buffer, _| {
+            assert!(project::search_text::SearchTextFile::from_dyn(buffer.file()).is_some());
+            ranges
+                .iter()
+                .map(|range| {
+                    let range = range.to_offset(buffer);
+                    assert_eq!(
+                        buffer.text_for_range(range.clone()).collect::<String>(),
+                        "foo"
+                    );
+                    range.start
+                })
+                .collect::<Vec<_>>()
+        });
+        let (_, segments) = notebook.read_with(cx, |notebook, cx| {
+            notebook_search_text(&notebook.notebook_item.read(cx).notebook)
+        });
+        let parts = offsets
+            .iter()
+            .map(|offset| {
+                let segment = segment_at(&segments, *offset).unwrap();
+                (segment.cell_index, segment.part)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parts,
+            vec![
+                (1, SearchTextPart::Source),
+                (1, SearchTextPart::Output { output_index: 0 }),
+                (2, SearchTextPart::Source),
+            ]
+        );
+
+        // Opening a match selects its cell and moves the cursor to it.
+        let navigated = notebook.update_in(cx, |notebook, window, cx| {
+            notebook.navigate(
+                Arc::new(SearchTextPosition { offset: offsets[2] }),
+                window,
+                cx,
+            )
+        });
+        assert!(navigated);
+        notebook.update(cx, |notebook, cx| {
+            assert_eq!(notebook.selected_index(), 2);
+            let editor = notebook.selected_cell().unwrap().editor(cx).unwrap();
+            let cursor = editor.update(cx, |editor, cx| {
+                editor
+                    .selections
+                    .newest::<MultiBufferOffset>(&editor.display_snapshot(cx))
+                    .head()
+            });
+            assert_eq!(cursor, MultiBufferOffset("The end of ".len()));
+        });
+
+        let navigated = notebook.update_in(cx, |notebook, window, cx| {
+            notebook.navigate(
+                Arc::new(SearchTextPosition { offset: offsets[1] }),
+                window,
+                cx,
+            )
+        });
+        assert!(navigated);
+        assert_eq!(
+            notebook.read_with(cx, |notebook, _| notebook.selected_index()),
+            1
+        );
+    }
+
+    fn cell_kinds(
+        notebook: &Entity<NotebookEditor>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<&'static str> {
+        notebook.read_with(cx, |notebook, _| {
+            notebook
+                .cell_order
+                .iter()
+                .map(|cell_id| match notebook.cell_map[cell_id].cell_type() {
+                    CellType::Code => "code",
+                    CellType::Markdown => "markdown",
+                    CellType::Raw => "raw",
+                })
+                .collect()
+        })
+    }
+
+    fn undo(notebook: &Entity<NotebookEditor>, cx: &mut VisualTestContext) {
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.undo_cell_change(&UndoCellChange, window, cx)
+        });
+    }
+
+    fn redo(notebook: &Entity<NotebookEditor>, cx: &mut VisualTestContext) {
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.redo_cell_change(&RedoCellChange, window, cx)
+        });
+    }
+
+    #[gpui::test]
+    async fn test_undo_split_and_merge(cx: &mut TestAppContext) {
+        let (_workspace, notebook, cx) =
+            open_notebook(vec![code_cell("a", "x = 1\ny = 2", json!([]))], cx).await;
+
+        notebook.update_in(cx, |notebook, window, cx| {
+            let editor = notebook.selected_cell().unwrap().editor(cx).unwrap();
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(Default::default(), window, cx, |selections| {
+                    selections
+                        .select_ranges([language::Point::new(1, 0)..language::Point::new(1, 0)])
+                });
+            });
+            notebook.split_cell(&SplitCell, window, cx);
+        });
+        assert_eq!(cell_sources(&notebook, cx), vec!["x = 1", "y = 2"]);
+        assert_eq!(
+            notebook.read_with(cx, |notebook, _| notebook.selected_index()),
+            1
+        );
+
+        undo(&notebook, cx);
+        assert_eq!(cell_sources(&notebook, cx), vec!["x = 1\ny = 2"]);
+        assert_eq!(
+            notebook.read_with(cx, |notebook, _| notebook.selected_index()),
+            0
+        );
+        redo(&notebook, cx);
+        assert_eq!(cell_sources(&notebook, cx), vec!["x = 1", "y = 2"]);
+
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.set_selected_index(0, false, window, cx);
+            notebook.merge_cell_below(&MergeCellBelow, window, cx);
+        });
+        assert_eq!(cell_sources(&notebook, cx), vec!["x = 1\n\ny = 2"]);
+
+        undo(&notebook, cx);
+        assert_eq!(cell_sources(&notebook, cx), vec!["x = 1", "y = 2"]);
+        undo(&notebook, cx);
+        assert_eq!(cell_sources(&notebook, cx), vec!["x = 1\ny = 2"]);
+
+        // Nothing is left to undo, and both changes can be redone in order.
+        undo(&notebook, cx);
+        assert_eq!(cell_sources(&notebook, cx), vec!["x = 1\ny = 2"]);
+        redo(&notebook, cx);
+        redo(&notebook, cx);
+        assert_eq!(cell_sources(&notebook, cx), vec!["x = 1\n\ny = 2"]);
+    }
+
+    #[gpui::test]
+    async fn test_undo_delete_and_change_type(cx: &mut TestAppContext) {
+        let (_workspace, notebook, cx) = open_notebook(
+            vec![
+                code_cell("a", "a = 1", json!([])),
+                markdown_cell("b", "# Title"),
+                code_cell("c", "c = 3", json!([])),
+            ],
+            cx,
+        )
+        .await;
+
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.set_selected_index(1, false, window, cx);
+            notebook.delete_cell(&DeleteCell, window, cx);
+        });
+        assert_eq!(cell_sources(&notebook, cx), vec!["a = 1", "c = 3"]);
+        undo(&notebook, cx);
+        assert_eq!(
+            cell_sources(&notebook, cx),
+            vec!["a = 1", "# Title", "c = 3"]
+        );
+        assert_eq!(cell_kinds(&notebook, cx), vec!["code", "markdown", "code"]);
+        redo(&notebook, cx);
+        assert_eq!(cell_sources(&notebook, cx), vec!["a = 1", "c = 3"]);
+        undo(&notebook, cx);
+
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.set_selected_index(0, false, window, cx);
+            notebook.change_cell_type(CellType::Markdown, window, cx);
+        });
+        assert_eq!(
+            cell_kinds(&notebook, cx),
+            vec!["markdown", "markdown", "code"]
+        );
+        assert_eq!(
+            cell_sources(&notebook, cx),
+            vec!["a = 1", "# Title", "c = 3"]
+        );
+
+        undo(&notebook, cx);
+        assert_eq!(cell_kinds(&notebook, cx), vec!["code", "markdown", "code"]);
+        redo(&notebook, cx);
+        assert_eq!(
+            cell_kinds(&notebook, cx),
+            vec!["markdown", "markdown", "code"]
+        );
+
+        // A new change drops the changes that were undone.
+        undo(&notebook, cx);
+        notebook.update_in(cx, |notebook, window, cx| {
+            notebook.set_selected_index(2, false, window, cx);
+            notebook.change_cell_type(CellType::Raw, window, cx);
+        });
+        redo(&notebook, cx);
+        assert_eq!(cell_kinds(&notebook, cx), vec!["code", "markdown", "raw"]);
+    }
+}
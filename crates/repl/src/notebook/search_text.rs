@@ -0,0 +1,104 @@
+//! The text project search matches against in `.ipynb` files: the sources and outputs of their
+//! cells, rather than the JSON they are stored as.
+
+use std::ops::Range;
+
+use anyhow::Result;
+use nbformat::v4::{Notebook, Output};
+use project::search_text::SearchTextProvider;
+use util::rel_path::RelPath;
+
+use super::notebook_file::NotebookFile;
+use crate::outputs::bundle_search_text;
+
+pub(crate) struct NotebookSearchTextProvider;
+
+impl SearchTextProvider for NotebookSearchTextProvider {
+    fn handles_path(&self, path: &RelPath) -> bool {
+        path.extension() == Some("ipynb")
+    }
+
+    fn search_text(&self, contents: &str) -> Result<String> {
+        let (_, notebook) = NotebookFile::parse(contents)?;
+        Ok(notebook_search_text(&notebook).0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SearchTextPart {
+    Source,
+    Output { output_index: usize },
+}
+
+/// A part of a notebook's searchable text. Cells are referred to by index, as notebooks without
+/// cell ids get new ones each time they are parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SearchTextSegment {
+    pub cell_index: usize,
+    pub part: SearchTextPart,
+    pub range: Range<usize>,
+}
+
+/// Lays out the searchable text of a notebook: the source of each cell followed by the text of
+/// its outputs, with cells separated by blank lines.
+pub(crate) fn notebook_search_text(notebook: &Notebook) -> (String, Vec<SearchTextSegment>) {
+    let mut text = String::new();
+    let mut segments = Vec::new();
+    for (cell_index, cell) in notebook.cells.iter().enumerate() {
+        if cell_index > 0 {
+            text.push('\n');
+        }
+        let (source, outputs) = match cell {
+            nbformat::v4::Cell::Code {
+                source, outputs, ..
+            } => (source, outputs.as_slice()),
+            nbformat::v4::Cell::Markdown { source, .. }
+            | nbformat::v4::Cell::Raw { source, .. } => (source, [].as_slice()),
+        };
+
+        let mut push_segment = |part, segment_text: &str| {
+            let start = text.len();
+            text.push_str(segment_text);
+            segments.push(SearchTextSegment {
+                cell_index,
+                part,
+                range: start..text.len(),
+            });
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+        };
+        push_segment(SearchTextPart::Source, &source.join(""));
+        for (output_index, output) in outputs.iter().enumerate() {
+            if let Some(output_text) = output_search_text(output) {
+                push_segment(SearchTextPart::Output { output_index }, &output_text);
+            }
+        }
+    }
+    (text, segments)
+}
+
+fn output_search_text(output: &Output) -> Option<String> {
+    match output {
+        Output::Stream { text, .. } => Some(text.0.clone()),
+        Output::DisplayData(display_data) => bundle_search_text(&display_data.data),
+        Output::ExecuteResult(execute_result) => bundle_search_text(&execute_result.data),
+        Output::Error(error) => Some(format!(
+            "{}: {}\n{}",
+            error.ename,
+            error.evalue,
+            error.traceback.join("\n")
+        )),
+    }
+}
+
+/// The segment of a notebook's searchable text that contains the given offset.
+pub(crate) fn segment_at(
+    segments: &[SearchTextSegment],
+    offset: usize,
+) -> Option<&SearchTextSegment> {
+    segments
+        .iter()
+        .rev()
+        .find(|segment| segment.range.start <= offset)
+}
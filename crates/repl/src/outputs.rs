@@ -14,7 +14,10 @@
 //! The module supports several output types, including:
 //! - Plain text
 //! - Markdown
-//! - Images (PNG and JPEG)
+//! - HTML (converted to Markdown)
+//! - Images (PNG, JPEG and SVG)
+//! - LaTeX math
+//! - Vega-Lite and Plotly charts (rendered statically)
 //! - Tables
 //! - Error messages
 //!
@@ -33,20 +36,33 @@
 //! This module is designed to work with Jupyter message protocols,
 //! interpreting and displaying various types of Jupyter output.
 
+use std::{cell::RefCell, rc::Rc};
+
+use anyhow::Result;
 use editor::{Editor, MultiBuffer};
 use gpui::{AnyElement, ClipboardItem, Entity, EventEmitter, Render, WeakEntity};
+use jupyter_protocol::ExecutionCount;
 use language::Buffer;
 use runtimelib::{ExecutionState, JupyterMessageContent, MimeBundle, MimeType};
 use ui::{CommonAnimationExt, CopyButton, IconButton, Tooltip, prelude::*};
 
+mod chart;
+use chart::Chart;
+
 mod image;
 use image::ImageView;
 
+mod latex;
+use latex::LatexView;
+
 mod markdown;
 use markdown::MarkdownView;
 
+mod svg;
+use svg::SvgView;
+
 mod table;
-use table::TableView;
+pub(crate) use table::TableView;
 
 pub mod plain;
 use plain::TerminalOutput;
@@ -61,10 +77,15 @@ use settings::Settings;
 /// When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
 fn rank_mime_type(mimetype: &MimeType) -> usize {
     match mimetype {
-        MimeType::DataTable(_) => 6,
-        MimeType::Png(_) => 4,
-        MimeType::Jpeg(_) => 3,
-        MimeType::Markdown(_) => 2,
+        MimeType::DataTable(_) => 10,
+        MimeType::Png(_) => 9,
+        MimeType::Jpeg(_) => 8,
+        MimeType::Svg(_) => 7,
+        MimeType::VegaLiteV4(_) | MimeType::VegaLiteV5(_) => 6,
+        MimeType::Plotly(_) => 5,
+        MimeType::Markdown(_) => 4,
+        MimeType::Latex(_) => 3,
+        MimeType::Html(_) => 2,
         MimeType::Plain(_) => 1,
         // All other media types are not supported in Zed at this time
         _ => 0,
@@ -102,9 +123,45 @@ impl<V: OutputContent + 'static> OutputContent for Entity<V> {
     }
 }
 
+/// The MIME bundle an output was made from. Only one of its media types is shown, so the bundle
+/// is kept to save the output to a notebook as it was.
+#[derive(Clone, Debug, Default)]
+pub struct OutputBundle {
+    pub data: MimeBundle,
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+    /// The execution count of an `execute_result`, `None` for `display_data`.
+    pub execution_count: Option<ExecutionCount>,
+}
+
+impl OutputBundle {
+    pub fn display_data(data: MimeBundle) -> Self {
+        Self {
+            data,
+            ..Self::default()
+        }
+    }
+
+    pub fn to_nbformat(&self) -> nbformat::v4::Output {
+        match self.execution_count {
+            Some(execution_count) => {
+                nbformat::v4::Output::ExecuteResult(nbformat::v4::ExecuteResult {
+                    execution_count,
+                    data: self.data.clone(),
+                    metadata: self.metadata.clone(),
+                })
+            }
+            None => nbformat::v4::Output::DisplayData(nbformat::v4::DisplayData {
+                data: self.data.clone(),
+                metadata: self.metadata.clone(),
+            }),
+        }
+    }
+}
+
 pub enum Output {
     Plain {
         content: Entity<TerminalOutput>,
+        bundle: OutputBundle,
         display_id: Option<String>,
     },
     Stream {
@@ -112,16 +169,46 @@ pub enum Output {
     },
     Image {
         content: Entity<ImageView>,
+        bundle: OutputBundle,
         display_id: Option<String>,
     },
     ErrorOutput(ErrorView),
     Message(String),
+    /// A bundle whose media types couldn't be shown.
+    Unsupported {
+        message: String,
+        bundle: OutputBundle,
+    },
     Table {
         content: Entity<TableView>,
+        bundle: OutputBundle,
         display_id: Option<String>,
     },
     Markdown {
         content: Entity<MarkdownView>,
+        bundle: OutputBundle,
+        display_id: Option<String>,
+    },
+    /// HTML converted to Markdown.
+    Html {
+        content: Entity<MarkdownView>,
+        bundle: OutputBundle,
+        display_id: Option<String>,
+    },
+    Svg {
+        content: Entity<SvgView>,
+        bundle: OutputBundle,
+        display_id: Option<String>,
+    },
+    Latex {
+        content: Entity<LatexView>,
+        bundle: OutputBundle,
+        display_id: Option<String>,
+    },
+    /// A Vega-Lite or Plotly chart, rendered statically to SVG.
+    Chart {
+        content: Entity<SvgView>,
+        bundle: OutputBundle,
         display_id: Option<String>,
     },
     ClearOutputWaitMarker,
@@ -137,17 +224,6 @@ impl Output {
                     text: nbformat::v4::MultilineString(text),
                 })
             }
-            Output::Plain { content, .. } => {
-                let text = content.read(cx).full_text();
-                let mut data = jupyter_protocol::media::Media::default();
-                data.content.push(jupyter_protocol::MediaType::Plain(text));
-                Some(nbformat::v4::Output::DisplayData(
-                    nbformat::v4::DisplayData {
-                        data,
-                        metadata: serde_json::Map::new(),
-                    },
-                ))
-            }
             Output::ErrorOutput(error_view) => {
                 let traceback_text = error_view.traceback.read(cx).full_text();
                 let traceback_lines: Vec<String> =
@@ -158,10 +234,55 @@ impl Output {
                     traceback: traceback_lines,
                 }))
             }
+            Output::Plain { bundle, .. }
+            | Output::Image { bundle, .. }
+            | Output::Unsupported { bundle, .. }
+            | Output::Table { bundle, .. }
+            | Output::Markdown { bundle, .. }
+            | Output::Html { bundle, .. }
+            | Output::Svg { bundle, .. }
+            | Output::Latex { bundle, .. }
+            | Output::Chart { bundle, .. } => Some(bundle.to_nbformat()),
             Output::Message(_) | Output::ClearOutputWaitMarker => None,
-            Output::Image { .. } | Output::Table { .. } | Output::Markdown { .. } => None,
         }
     }
+
+    /// The text of this output that notebook search matches against, if it has any.
+    pub fn searchable_text(&self, cx: &App) -> Option<String> {
+        match self {
+            Output::Plain { content, .. } | Output::Stream { content } => {
+                Some(content.read(cx).full_text())
+            }
+            Output::ErrorOutput(error_view) => Some(format!(
+                "{}: {}\n{}",
+                error_view.ename,
+                error_view.evalue,
+                error_view.traceback.read(cx).full_text()
+            )),
+            Output::Markdown { content, .. } | Output::Html { content, .. } => {
+                Some(content.read(cx).source(cx).to_string())
+            }
+            Output::Latex { content, .. } => Some(content.read(cx).rendered().to_string()),
+            Output::Message(message) | Output::Unsupported { message, .. } => Some(message.clone()),
+            Output::Image { .. }
+            | Output::Table { .. }
+            | Output::Svg { .. }
+            | Output::Chart { .. }
+            | Output::ClearOutputWaitMarker => None,
+        }
+    }
+}
+
+/// The text of an output with the given data that project search matches against, if the media
+/// type it's rendered as has any. Mirrors [`Output::searchable_text`] for outputs read from disk.
+pub(crate) fn bundle_search_text(data: &MimeBundle) -> Option<String> {
+    match data.richest(rank_mime_type) {
+        Some(MimeType::Plain(text) | MimeType::Markdown(text) | MimeType::Latex(text)) => {
+            Some(text.clone())
+        }
+        Some(MimeType::Html(html)) => html_to_markdown(html).ok(),
+        _ => None,
+    }
 }
 
 impl Output {
@@ -245,16 +366,27 @@ impl Output {
     ) -> impl IntoElement + use<> {
         let content = match self {
             Self::Plain { content, .. } => Some(content.clone().into_any_element()),
-            Self::Markdown { content, .. } => Some(content.clone().into_any_element()),
+            Self::Markdown { content, .. } | Self::Html { content, .. } => {
+                Some(content.clone().into_any_element())
+            }
+            Self::Svg { content, .. } | Self::Chart { content, .. } => {
+                Some(content.clone().into_any_element())
+            }
+            Self::Latex { content, .. } => Some(content.clone().into_any_element()),
             Self::Stream { content, .. } => Some(content.clone().into_any_element()),
             Self::Image { content, .. } => Some(content.clone().into_any_element()),
-            Self::Message(message) => Some(div().child(message.clone()).into_any_element()),
+            Self::Message(message) | Self::Unsupported { message, .. } => {
+                Some(div().child(message.clone()).into_any_element())
+            }
             Self::Table { content, .. } => Some(content.clone().into_any_element()),
             Self::ErrorOutput(error_view) => error_view.render(window, cx),
             Self::ClearOutputWaitMarker => None,
         };
 
-        let needs_horizontal_scroll = matches!(self, Self::Table { .. } | Self::Image { .. });
+        let needs_horizontal_scroll = matches!(
+            self,
+            Self::Table { .. } | Self::Image { .. } | Self::Svg { .. } | Self::Chart { .. }
+        );
 
         h_flex()
             .id("output-content")
@@ -272,7 +404,13 @@ impl Output {
                 Self::Plain { content, .. } => {
                     Self::render_output_controls(content.clone(), workspace, window, cx)
                 }
-                Self::Markdown { content, .. } => {
+                Self::Markdown { content, .. } | Self::Html { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace, window, cx)
+                }
+                Self::Svg { content, .. } | Self::Chart { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace, window, cx)
+                }
+                Self::Latex { content, .. } => {
                     Self::render_output_controls(content.clone(), workspace, window, cx)
                 }
                 Self::Stream { content, .. } => {
@@ -338,7 +476,7 @@ impl Output {
                         )
                         .into_any_element(),
                 ),
-                Self::Message(_) => None,
+                Self::Message(_) | Self::Unsupported { .. } => None,
                 Self::Table { content, .. } => {
                     Self::render_output_controls(content.clone(), workspace, window, cx)
                 }
@@ -352,9 +490,13 @@ impl Output {
             Output::Stream { .. } => None,
             Output::Image { display_id, .. } => display_id.clone(),
             Output::ErrorOutput(_) => None,
-            Output::Message(_) => None,
+            Output::Message(_) | Output::Unsupported { .. } => None,
             Output::Table { display_id, .. } => display_id.clone(),
             Output::Markdown { display_id, .. } => display_id.clone(),
+            Output::Html { display_id, .. } => display_id.clone(),
+            Output::Svg { display_id, .. } => display_id.clone(),
+            Output::Latex { display_id, .. } => display_id.clone(),
+            Output::Chart { display_id, .. } => display_id.clone(),
             Output::ClearOutputWaitMarker => None,
         }
     }
@@ -365,35 +507,151 @@ impl Output {
         window: &mut Window,
         cx: &mut App,
     ) -> Self {
-        match data.richest(rank_mime_type) {
+        Self::from_bundle(
+            OutputBundle::display_data(data.clone()),
+            display_id,
+            window,
+            cx,
+        )
+    }
+
+    pub fn from_bundle(
+        bundle: OutputBundle,
+        display_id: Option<String>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        match bundle.data.richest(rank_mime_type) {
             Some(MimeType::Plain(text)) => Output::Plain {
                 content: cx.new(|cx| TerminalOutput::from(text, window, cx)),
+                bundle,
                 display_id,
             },
             Some(MimeType::Markdown(text)) => {
                 let content = cx.new(|cx| MarkdownView::from(text.clone(), cx));
                 Output::Markdown {
                     content,
+                    bundle,
                     display_id,
                 }
             }
             Some(MimeType::Png(data)) | Some(MimeType::Jpeg(data)) => match ImageView::from(data) {
                 Ok(view) => Output::Image {
                     content: cx.new(|_| view),
+                    bundle,
                     display_id,
                 },
-                Err(error) => Output::Message(format!("Failed to load image: {}", error)),
+                Err(error) => Output::Unsupported {
+                    message: format!("Failed to load image: {}", error),
+                    bundle,
+                },
             },
             Some(MimeType::DataTable(data)) => Output::Table {
                 content: cx.new(|cx| TableView::new(data, window, cx)),
+                bundle,
+                display_id,
+            },
+            Some(MimeType::Html(html)) => match html_to_markdown(html) {
+                Ok(text) => Output::Html {
+                    content: cx.new(|cx| MarkdownView::from(text, cx)),
+                    bundle,
+                    display_id,
+                },
+                Err(error) => Self::fallback(bundle, display_id, "HTML", error, window, cx),
+            },
+            Some(MimeType::Svg(source)) => match SvgView::from(source.clone(), cx) {
+                Ok(view) => Output::Svg {
+                    content: cx.new(|_| view),
+                    bundle,
+                    display_id,
+                },
+                Err(error) => Self::fallback(bundle, display_id, "SVG", error, window, cx),
+            },
+            Some(MimeType::Latex(source)) => Output::Latex {
+                content: cx.new(|_| LatexView::from(source.clone())),
+                bundle,
                 display_id,
             },
+            Some(
+                mime_type @ (MimeType::VegaLiteV4(_)
+                | MimeType::VegaLiteV5(_)
+                | MimeType::Plotly(_)),
+            ) => {
+                let chart = match mime_type {
+                    MimeType::Plotly(figure) => serde_json::to_value(figure)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|figure| Chart::from_plotly(&figure)),
+                    MimeType::VegaLiteV4(spec) | MimeType::VegaLiteV5(spec) => {
+                        serde_json::to_value(spec)
+                            .map_err(anyhow::Error::from)
+                            .and_then(|spec| Chart::from_vega_lite(&spec))
+                    }
+                    _ => unreachable!(),
+                };
+                match chart
+                    .and_then(|chart| chart.to_svg())
+                    .and_then(|svg| SvgView::from(svg, cx))
+                {
+                    Ok(view) => Output::Chart {
+                        content: cx.new(|_| view),
+                        bundle,
+                        display_id,
+                    },
+                    Err(error) => Self::fallback(bundle, display_id, "chart", error, window, cx),
+                }
+            }
             // Any other media types are not supported
-            _ => Output::Message("Unsupported media type".to_string()),
+            _ => Output::Unsupported {
+                message: "Unsupported media type".to_string(),
+                bundle,
+            },
+        }
+    }
+
+    /// Shows the plain text representation of a bundle whose richest media type failed to render.
+    fn fallback(
+        bundle: OutputBundle,
+        display_id: Option<String>,
+        kind: &str,
+        error: impl Into<anyhow::Error>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        let error = error.into();
+        log::debug!("failed to render {kind} output: {error:#}");
+        let text = bundle.data.content.iter().find_map(|media| match media {
+            MimeType::Plain(text) => Some(text.clone()),
+            _ => None,
+        });
+        match text {
+            Some(text) => Output::Plain {
+                content: cx.new(|cx| TerminalOutput::from(&text, window, cx)),
+                bundle,
+                display_id,
+            },
+            None => Output::Unsupported {
+                message: format!("Failed to render {kind}: {error}"),
+                bundle,
+            },
         }
     }
 }
 
+fn html_to_markdown(html: &str) -> Result<String> {
+    use html_to_markdown::{TagHandler, convert_html_to_markdown, markdown};
+
+    let mut handlers: Vec<TagHandler> = vec![
+        Rc::new(RefCell::new(markdown::WebpageChromeRemover)),
+        Rc::new(RefCell::new(markdown::ParagraphHandler)),
+        Rc::new(RefCell::new(markdown::HeadingHandler)),
+        Rc::new(RefCell::new(markdown::ListHandler)),
+        Rc::new(RefCell::new(markdown::TableHandler::new())),
+        Rc::new(RefCell::new(markdown::StyledTextHandler)),
+        Rc::new(RefCell::new(markdown::CodeHandler)),
+    ];
+    convert_html_to_markdown(html.as_bytes(), &mut handlers)
+}
+
 #[derive(Default, Clone, Debug)]
 pub enum ExecutionStatus {
     #[default]
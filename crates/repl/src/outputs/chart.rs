@@ -0,0 +1,601 @@
+//! # Chart Output
+//!
+//! Static rendering of Vega-Lite (`application/vnd.vegalite.v*+json`) and Plotly
+//! (`application/vnd.plotly.v1+json`) outputs, as produced by Altair and Plotly.
+//!
+//! Only the common subset of both formats is supported: a single view over inline data, drawn
+//! as bars, lines, areas or points, optionally split into series by a color field. The chart is
+//! laid out as an SVG document and displayed through [`super::svg::SvgView`]. Specs outside of
+//! that subset fail to convert, so that the output falls back to its plain text representation.
+
+use anyhow::{Context as _, Result, bail};
+use serde_json::Value;
+use std::fmt::Write as _;
+
+const WIDTH: f64 = 480.;
+const HEIGHT: f64 = 300.;
+const MARGIN_LEFT: f64 = 56.;
+const MARGIN_RIGHT: f64 = 16.;
+const MARGIN_TOP: f64 = 36.;
+const MARGIN_BOTTOM: f64 = 48.;
+const TICK_COUNT: usize = 5;
+const MAX_CATEGORY_LABELS: usize = 12;
+const TEXT_COLOR: &str = "#888888";
+const PALETTE: [&str; 10] = [
+    "#4c78a8", "#f58518", "#e45756", "#72b7b2", "#54a24b", "#eeca3b", "#b279a2", "#ff9da6",
+    "#9d755d", "#bab0ac",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Mark {
+    Bar,
+    Line,
+    Area,
+    Point,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum XValue {
+    Number(f64),
+    Category(String),
+}
+
+impl XValue {
+    fn label(&self) -> String {
+        match self {
+            XValue::Number(number) => format_number(*number),
+            XValue::Category(category) => category.clone(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Series {
+    pub name: Option<String>,
+    pub mark: Mark,
+    pub points: Vec<(XValue, f64)>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Chart {
+    pub title: Option<String>,
+    pub x_title: Option<String>,
+    pub y_title: Option<String>,
+    pub series: Vec<Series>,
+}
+
+impl Chart {
+    pub fn from_vega_lite(spec: &Value) -> Result<Self> {
+        let values = spec
+            .pointer("/data/values")
+            .and_then(Value::as_array)
+            .context("only Vega-Lite specs with inline `data.values` can be rendered")?;
+        let mark = match spec.get("mark") {
+            Some(Value::String(mark)) => mark.as_str(),
+            Some(Value::Object(mark)) => mark.get("type").and_then(Value::as_str).unwrap_or(""),
+            _ => bail!("only single view Vega-Lite specs can be rendered"),
+        };
+        let mark = match mark {
+            "bar" => Mark::Bar,
+            "line" => Mark::Line,
+            "area" => Mark::Area,
+            "point" | "circle" | "square" | "tick" => Mark::Point,
+            mark => bail!("unsupported Vega-Lite mark {mark:?}"),
+        };
+
+        let encoding = spec
+            .get("encoding")
+            .context("Vega-Lite spec has no encoding")?;
+        let field = |channel: &str| {
+            encoding
+                .get(channel)
+                .and_then(|channel| channel.get("field"))
+                .and_then(Value::as_str)
+        };
+        let title = |channel: &str| {
+            encoding
+                .get(channel)
+                .and_then(|channel| channel.get("title"))
+                .and_then(Value::as_str)
+                .or_else(|| field(channel))
+                .map(str::to_owned)
+        };
+        let x_field = field("x").context("Vega-Lite spec has no x field")?;
+        let y_field = field("y").context("Vega-Lite spec has no y field")?;
+        let color_field = field("color");
+        let x_is_quantitative =
+            encoding.pointer("/x/type").and_then(Value::as_str) == Some("quantitative");
+
+        let mut chart = Chart {
+            title: title_text(spec.get("title")),
+            x_title: title("x"),
+            y_title: title("y"),
+            series: Vec::new(),
+        };
+        for row in values {
+            let Some(y) = row.get(y_field).and_then(Value::as_f64) else {
+                continue;
+            };
+            let Some(x) = row.get(x_field).and_then(|x| x_value(x, x_is_quantitative)) else {
+                continue;
+            };
+            let name = color_field
+                .and_then(|field| row.get(field))
+                .map(value_label);
+            chart.push_point(name, mark, x, y);
+        }
+        Ok(chart)
+    }
+
+    pub fn from_plotly(figure: &Value) -> Result<Self> {
+        let traces = figure
+            .get("data")
+            .and_then(Value::as_array)
+            .context("Plotly figure has no data")?;
+        let layout = figure.get("layout");
+        let axis_title = |axis: &str| title_text(layout?.get(axis)?.get("title"));
+
+        let mut chart = Chart {
+            title: title_text(layout.and_then(|layout| layout.get("title"))),
+            x_title: axis_title("xaxis"),
+            y_title: axis_title("yaxis"),
+            series: Vec::new(),
+        };
+        for trace in traces {
+            let kind = trace
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or("scatter");
+            let mark = match kind {
+                "bar" => Mark::Bar,
+                "scatter" | "scattergl" => {
+                    let mode = trace
+                        .get("mode")
+                        .and_then(Value::as_str)
+                        .unwrap_or("lines+markers");
+                    if trace
+                        .get("fill")
+                        .and_then(Value::as_str)
+                        .is_some_and(|fill| fill != "none")
+                    {
+                        Mark::Area
+                    } else if mode.contains("lines") {
+                        Mark::Line
+                    } else {
+                        Mark::Point
+                    }
+                }
+                kind => bail!("unsupported Plotly trace type {kind:?}"),
+            };
+
+            let ys = trace
+                .get("y")
+                .and_then(Value::as_array)
+                .context("only Plotly traces with inline y values can be rendered")?;
+            let xs = trace.get("x").and_then(Value::as_array);
+            let x_is_quantitative = xs.is_none_or(|xs| xs.iter().all(Value::is_number));
+            let name = trace.get("name").and_then(Value::as_str).map(str::to_owned);
+
+            let mut series = Series {
+                name,
+                mark,
+                points: Vec::new(),
+            };
+            for (ix, y) in ys.iter().enumerate() {
+                let Some(y) = y.as_f64() else {
+                    continue;
+                };
+                let x = match xs {
+                    Some(xs) => xs.get(ix).and_then(|x| x_value(x, x_is_quantitative)),
+                    None => Some(XValue::Number(ix as f64)),
+                };
+                if let Some(x) = x {
+                    series.points.push((x, y));
+                }
+            }
+            chart.series.push(series);
+        }
+        Ok(chart)
+    }
+
+    fn push_point(&mut self, name: Option<String>, mark: Mark, x: XValue, y: f64) {
+        if let Some(series) = self.series.iter_mut().find(|series| series.name == name) {
+            series.points.push((x, y));
+        } else {
+            self.series.push(Series {
+                name,
+                mark,
+                points: vec![(x, y)],
+            });
+        }
+    }
+
+    /// Lays the chart out as a standalone SVG document.
+    pub fn to_svg(&self) -> Result<String> {
+        let points = || self.series.iter().flat_map(|series| series.points.iter());
+        if points().next().is_none() {
+            bail!("chart has no data points");
+        }
+
+        let plot_left = MARGIN_LEFT;
+        let plot_right = WIDTH - MARGIN_RIGHT;
+        let plot_top = MARGIN_TOP;
+        let plot_bottom = HEIGHT - MARGIN_BOTTOM;
+
+        let has_bars = self.series.iter().any(|series| series.mark == Mark::Bar);
+        let starts_at_zero = has_bars || self.series.iter().any(|series| series.mark == Mark::Area);
+        let (mut y_min, mut y_max) = points().fold((f64::MAX, f64::MIN), |(min, max), (_, y)| {
+            (min.min(*y), max.max(*y))
+        });
+        if starts_at_zero {
+            y_min = y_min.min(0.);
+            y_max = y_max.max(0.);
+        }
+        if y_min == y_max {
+            y_min -= 1.;
+            y_max += 1.;
+        }
+        let y_position =
+            |y: f64| plot_bottom - (y - y_min) / (y_max - y_min) * (plot_bottom - plot_top);
+
+        // Bars always get a band per x value. Other marks use a linear axis when all x values
+        // are numbers.
+        let x_scale = if !has_bars && points().all(|(x, _)| matches!(x, XValue::Number(_))) {
+            let (mut min, mut max) = points().fold((f64::MAX, f64::MIN), |(min, max), (x, _)| {
+                let XValue::Number(x) = x else { unreachable!() };
+                (min.min(*x), max.max(*x))
+            });
+            if min == max {
+                min -= 1.;
+                max += 1.;
+            }
+            XScale::Linear { min, max }
+        } else {
+            let mut categories = Vec::<String>::new();
+            for (x, _) in points() {
+                let label = x.label();
+                if !categories.contains(&label) {
+                    categories.push(label);
+                }
+            }
+            XScale::Band { categories }
+        };
+        let x_position = |x: &XValue| match &x_scale {
+            XScale::Linear { min, max } => {
+                let XValue::Number(x) = x else { unreachable!() };
+                plot_left + (x - min) / (max - min) * (plot_right - plot_left)
+            }
+            XScale::Band { categories } => {
+                let band = (plot_right - plot_left) / categories.len() as f64;
+                let label = x.label();
+                let ix = categories
+                    .iter()
+                    .position(|category| *category == label)
+                    .unwrap_or(0);
+                plot_left + band * (ix as f64 + 0.5)
+            }
+        };
+
+        let mut svg = String::new();
+        write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="11" fill="{TEXT_COLOR}">"#
+        )?;
+
+        if let Some(title) = &self.title {
+            write!(
+                svg,
+                r#"<text x="{}" y="20" text-anchor="middle" font-size="14">{}</text>"#,
+                WIDTH / 2.,
+                escape(title)
+            )?;
+        }
+
+        // Axes, ticks and labels.
+        write!(
+            svg,
+            r#"<path d="M{plot_left} {plot_top}V{plot_bottom}H{plot_right}" fill="none" stroke="{TEXT_COLOR}"/>"#
+        )?;
+        for tick in 0..TICK_COUNT {
+            let value = y_min + (y_max - y_min) * tick as f64 / (TICK_COUNT - 1) as f64;
+            let y = y_position(value);
+            write!(
+                svg,
+                r#"<path d="M{} {y}H{plot_left}" stroke="{TEXT_COLOR}"/><text x="{}" y="{}" text-anchor="end">{}</text>"#,
+                plot_left - 4.,
+                plot_left - 6.,
+                y + 4.,
+                format_number(value)
+            )?;
+        }
+        match &x_scale {
+            XScale::Linear { min, max } => {
+                for tick in 0..TICK_COUNT {
+                    let value = min + (max - min) * tick as f64 / (TICK_COUNT - 1) as f64;
+                    let x = x_position(&XValue::Number(value));
+                    write!(
+                        svg,
+                        r#"<path d="M{x} {plot_bottom}V{}" stroke="{TEXT_COLOR}"/><text x="{x}" y="{}" text-anchor="middle">{}</text>"#,
+                        plot_bottom + 4.,
+                        plot_bottom + 16.,
+                        format_number(value)
+                    )?;
+                }
+            }
+            XScale::Band { categories } => {
+                let step = categories.len().div_ceil(MAX_CATEGORY_LABELS);
+                for category in categories.iter().step_by(step) {
+                    let x = x_position(&XValue::Category(category.clone()));
+                    write!(
+                        svg,
+                        r#"<text x="{x}" y="{}" text-anchor="middle">{}</text>"#,
+                        plot_bottom + 16.,
+                        escape(&truncate(category, 12))
+                    )?;
+                }
+            }
+        }
+        if let Some(x_title) = &self.x_title {
+            write!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+                (plot_left + plot_right) / 2.,
+                HEIGHT - 10.,
+                escape(x_title)
+            )?;
+        }
+        if let Some(y_title) = &self.y_title {
+            let y = (plot_top + plot_bottom) / 2.;
+            write!(
+                svg,
+                r#"<text x="14" y="{y}" text-anchor="middle" transform="rotate(-90 14 {y})">{}</text>"#,
+                escape(y_title)
+            )?;
+        }
+
+        // Marks.
+        let bar_series_count = self
+            .series
+            .iter()
+            .filter(|series| series.mark == Mark::Bar)
+            .count();
+        let mut bar_series_ix = 0;
+        for (series_ix, series) in self.series.iter().enumerate() {
+            let color = PALETTE[series_ix % PALETTE.len()];
+            match series.mark {
+                Mark::Bar => {
+                    let XScale::Band { categories } = &x_scale else {
+                        unreachable!()
+                    };
+                    let band = (plot_right - plot_left) / categories.len() as f64;
+                    let bar_width = band * 0.8 / bar_series_count as f64;
+                    let zero = y_position(0.);
+                    for (x, y) in &series.points {
+                        let left = x_position(x) - band * 0.4 + bar_width * bar_series_ix as f64;
+                        let top = y_position(*y).min(zero);
+                        let height = (y_position(*y) - zero).abs();
+                        write!(
+                            svg,
+                            r#"<rect x="{left}" y="{top}" width="{bar_width}" height="{height}" fill="{color}"/>"#
+                        )?;
+                    }
+                    bar_series_ix += 1;
+                }
+                Mark::Line | Mark::Area => {
+                    let coordinates = series
+                        .points
+                        .iter()
+                        .map(|(x, y)| format!("{},{}", x_position(x), y_position(*y)))
+                        .collect::<Vec<_>>();
+                    if series.mark == Mark::Area
+                        && let (Some((first, _)), Some((last, _))) =
+                            (series.points.first(), series.points.last())
+                    {
+                        let zero = y_position(0.);
+                        write!(
+                            svg,
+                            r#"<polygon points="{} {},{zero} {},{zero}" fill="{color}" fill-opacity="0.5" stroke="none"/>"#,
+                            coordinates.join(" "),
+                            x_position(last),
+                            x_position(first),
+                        )?;
+                    }
+                    write!(
+                        svg,
+                        r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="2"/>"#,
+                        coordinates.join(" ")
+                    )?;
+                }
+                Mark::Point => {
+                    for (x, y) in &series.points {
+                        write!(
+                            svg,
+                            r#"<circle cx="{}" cy="{}" r="3" fill="{color}"/>"#,
+                            x_position(x),
+                            y_position(*y)
+                        )?;
+                    }
+                }
+            }
+        }
+
+        // Legend, when there is more than one named series.
+        if self.series.len() > 1 {
+            for (series_ix, series) in self.series.iter().enumerate() {
+                let Some(name) = &series.name else {
+                    continue;
+                };
+                let y = plot_top + 4. + series_ix as f64 * 14.;
+                write!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="8" height="8" fill="{}"/><text x="{}" y="{}" text-anchor="end">{}</text>"#,
+                    plot_right - 8.,
+                    y - 4.,
+                    PALETTE[series_ix % PALETTE.len()],
+                    plot_right - 12.,
+                    y + 4.,
+                    escape(&truncate(name, 24))
+                )?;
+            }
+        }
+
+        svg.push_str("</svg>");
+        Ok(svg)
+    }
+}
+
+enum XScale {
+    Linear { min: f64, max: f64 },
+    Band { categories: Vec<String> },
+}
+
+fn x_value(value: &Value, quantitative: bool) -> Option<XValue> {
+    if quantitative {
+        value.as_f64().map(XValue::Number)
+    } else if value.is_null() {
+        None
+    } else {
+        Some(XValue::Category(value_label(value)))
+    }
+}
+
+fn value_label(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        Value::Number(number) => number
+            .as_f64()
+            .map(format_number)
+            .unwrap_or_else(|| number.to_string()),
+        value => value.to_string(),
+    }
+}
+
+/// Titles are either plain strings or objects with a `text` field, in both formats.
+fn title_text(title: Option<&Value>) -> Option<String> {
+    match title? {
+        Value::String(title) => Some(title.clone()),
+        Value::Object(title) => title.get("text")?.as_str().map(str::to_owned),
+        _ => None,
+    }
+}
+
+fn format_number(number: f64) -> String {
+    if number.fract() == 0. && number.abs() < 1e15 {
+        format!("{}", number as i64)
+    } else {
+        let formatted = format!("{number:.2}");
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        let mut truncated = text.chars().take(max_chars - 1).collect::<String>();
+        truncated.push('…');
+        truncated
+    } else {
+        text.to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_vega_lite_chart() {
+        let spec = json!({
+            "$schema": "https://vega.github.io/schema/vega-lite/v5.json",
+            "title": "Sales",
+            "data": {
+                "values": [
+                    {"year": "2022", "region": "EU", "sales": 10},
+                    {"year": "2022", "region": "US", "sales": 12.5},
+                    {"year": "2023", "region": "EU", "sales": 14},
+                    {"year": "2023", "region": "US", "sales": null}
+                ]
+            },
+            "mark": {"type": "bar", "tooltip": true},
+            "encoding": {
+                "x": {"field": "year", "type": "nominal"},
+                "y": {"field": "sales", "type": "quantitative", "title": "Sales (k)"},
+                "color": {"field": "region", "type": "nominal"}
+            }
+        });
+
+        let chart = Chart::from_vega_lite(&spec).unwrap();
+        assert_eq!(
+            chart,
+            Chart {
+                title: Some("Sales".into()),
+                x_title: Some("year".into()),
+                y_title: Some("Sales (k)".into()),
+                series: vec![
+                    Series {
+                        name: Some("EU".into()),
+                        mark: Mark::Bar,
+                        points: vec![
+                            (XValue::Category("2022".into()), 10.),
+                            (XValue::Category("2023".into()), 14.)
+                        ],
+                    },
+                    Series {
+                        name: Some("US".into()),
+                        mark: Mark::Bar,
+                        points: vec![(XValue::Category("2022".into()), 12.5)],
+                    },
+                ],
+            }
+        );
+
+        let svg = chart.to_svg().unwrap();
+        assert_eq!(svg.matches("<rect x=").count(), 3 + 2);
+        assert!(svg.contains(">Sales</text>"));
+
+        let layered = json!({"layer": [], "data": {"values": []}});
+        assert!(Chart::from_vega_lite(&layered).is_err());
+    }
+
+    #[test]
+    fn test_plotly_chart() {
+        let figure = json!({
+            "data": [
+                {"type": "scatter", "mode": "lines", "x": [1, 2, 3], "y": [2, 4, 3], "name": "a"},
+                {"type": "scatter", "mode": "markers", "y": [1, 1, 1], "name": "b"}
+            ],
+            "layout": {"title": {"text": "Trend"}, "xaxis": {"title": "t"}}
+        });
+
+        let chart = Chart::from_plotly(&figure).unwrap();
+        assert_eq!(chart.title.as_deref(), Some("Trend"));
+        assert_eq!(chart.x_title.as_deref(), Some("t"));
+        assert_eq!(chart.series[0].mark, Mark::Line);
+        assert_eq!(chart.series[1].mark, Mark::Point);
+        assert_eq!(
+            chart.series[1].points,
+            vec![
+                (XValue::Number(0.), 1.),
+                (XValue::Number(1.), 1.),
+                (XValue::Number(2.), 1.)
+            ]
+        );
+
+        let svg = chart.to_svg().unwrap();
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert_eq!(svg.matches("<circle").count(), 3);
+
+        let pie = json!({"data": [{"type": "pie", "values": [1, 2]}]});
+        assert!(Chart::from_plotly(&pie).is_err());
+    }
+}
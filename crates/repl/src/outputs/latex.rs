@@ -0,0 +1,477 @@
+//! # LaTeX Output
+//!
+//! Renders `text/latex` outputs, as produced by SymPy and IPython's `Math` display, by converting
+//! the math to Unicode. This covers the common notation (greek letters, operators, fractions,
+//! roots, super- and subscripts) without needing a TeX engine; anything it doesn't recognise is
+//! shown in its source form.
+
+use gpui::{App, ClipboardItem, Window};
+use std::iter::Peekable;
+use std::str::Chars;
+use ui::{IntoElement, div, prelude::*, v_flex};
+
+use crate::outputs::OutputContent;
+
+pub struct LatexView {
+    source: String,
+    rendered: String,
+}
+
+impl LatexView {
+    pub fn from(source: String) -> Self {
+        let rendered = latex_to_unicode(&source);
+        Self { source, rendered }
+    }
+
+    pub fn rendered(&self) -> &str {
+        &self.rendered
+    }
+}
+
+impl Render for LatexView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().font_buffer(cx).children(
+            self.rendered
+                .lines()
+                .map(|line| div().child(line.to_string())),
+        )
+    }
+}
+
+impl OutputContent for LatexView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.source.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+}
+
+const SYMBOLS: &[(&str, &str)] = &[
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ϵ"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("vartheta", "ϑ"),
+    ("iota", "ι"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("varpi", "ϖ"),
+    ("rho", "ρ"),
+    ("varrho", "ϱ"),
+    ("sigma", "σ"),
+    ("varsigma", "ς"),
+    ("tau", "τ"),
+    ("upsilon", "υ"),
+    ("phi", "ϕ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Xi", "Ξ"),
+    ("Pi", "Π"),
+    ("Sigma", "Σ"),
+    ("Upsilon", "Υ"),
+    ("Phi", "Φ"),
+    ("Psi", "Ψ"),
+    ("Omega", "Ω"),
+    ("times", "×"),
+    ("cdot", "⋅"),
+    ("div", "÷"),
+    ("pm", "±"),
+    ("mp", "∓"),
+    ("leq", "≤"),
+    ("le", "≤"),
+    ("geq", "≥"),
+    ("ge", "≥"),
+    ("neq", "≠"),
+    ("ne", "≠"),
+    ("approx", "≈"),
+    ("equiv", "≡"),
+    ("sim", "∼"),
+    ("simeq", "≃"),
+    ("propto", "∝"),
+    ("infty", "∞"),
+    ("partial", "∂"),
+    ("nabla", "∇"),
+    ("sum", "∑"),
+    ("prod", "∏"),
+    ("int", "∫"),
+    ("iint", "∬"),
+    ("oint", "∮"),
+    ("in", "∈"),
+    ("notin", "∉"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("supset", "⊃"),
+    ("supseteq", "⊇"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("emptyset", "∅"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("neg", "¬"),
+    ("land", "∧"),
+    ("lor", "∨"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("Rightarrow", "⇒"),
+    ("Leftarrow", "⇐"),
+    ("leftrightarrow", "↔"),
+    ("iff", "⟺"),
+    ("implies", "⟹"),
+    ("mapsto", "↦"),
+    ("cdots", "⋯"),
+    ("ldots", "…"),
+    ("dots", "…"),
+    ("vdots", "⋮"),
+    ("ddots", "⋱"),
+    ("langle", "⟨"),
+    ("rangle", "⟩"),
+    ("lfloor", "⌊"),
+    ("rfloor", "⌋"),
+    ("lceil", "⌈"),
+    ("rceil", "⌉"),
+    ("circ", "∘"),
+    ("prime", "′"),
+    ("hbar", "ℏ"),
+    ("ell", "ℓ"),
+    ("Re", "ℜ"),
+    ("Im", "ℑ"),
+    ("aleph", "ℵ"),
+    ("quad", " "),
+    ("qquad", " "),
+];
+
+/// Commands that only affect layout, and are dropped.
+const IGNORED_COMMANDS: &[&str] = &[
+    "left",
+    "right",
+    "big",
+    "Big",
+    "bigg",
+    "Bigg",
+    "displaystyle",
+    "textstyle",
+    "limits",
+    "nolimits",
+];
+
+/// Commands whose argument is shown as plain text.
+const TEXT_COMMANDS: &[&str] = &[
+    "text",
+    "textrm",
+    "textbf",
+    "textit",
+    "mathrm",
+    "mathbf",
+    "mathit",
+    "mathsf",
+    "mathtt",
+    "mathcal",
+    "operatorname",
+    "boldsymbol",
+];
+
+const ACCENTS: &[(&str, char)] = &[
+    ("hat", '\u{302}'),
+    ("bar", '\u{304}'),
+    ("overline", '\u{305}'),
+    ("tilde", '\u{303}'),
+    ("dot", '\u{307}'),
+    ("ddot", '\u{308}'),
+    ("vec", '\u{20D7}'),
+];
+
+/// Converts LaTeX math to its closest plain Unicode representation.
+pub(crate) fn latex_to_unicode(latex: &str) -> String {
+    let mut converter = Converter {
+        chars: strip_math_delimiters(latex).chars().peekable(),
+    };
+    let converted = converter.convert_group();
+
+    converted
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn strip_math_delimiters(latex: &str) -> &str {
+    let latex = latex.trim();
+    for (open, close) in [("$$", "$$"), ("\\[", "\\]"), ("\\(", "\\)"), ("$", "$")] {
+        if let Some(inner) = latex
+            .strip_prefix(open)
+            .and_then(|rest| rest.strip_suffix(close))
+        {
+            return inner.trim();
+        }
+    }
+    latex
+}
+
+struct Converter<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Converter<'_> {
+    /// Converts until the end of the input or the closing brace of the current group.
+    fn convert_group(&mut self) -> String {
+        let mut output = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                '}' => break,
+                '{' => output.push_str(&self.convert_group()),
+                '\\' => output.push_str(&self.convert_command()),
+                '^' => {
+                    let argument = self.convert_argument();
+                    output.push_str(&script(&argument, superscript, '^'));
+                }
+                '_' => {
+                    let argument = self.convert_argument();
+                    output.push_str(&script(&argument, subscript, '_'));
+                }
+                '&' | '~' => output.push(' '),
+                '$' => {}
+                c => output.push(c),
+            }
+        }
+        output
+    }
+
+    /// Converts a single token or a braced group, as taken by `^`, `_` and commands like `\frac`.
+    fn convert_argument(&mut self) -> String {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match self.chars.next() {
+            Some('{') => self.convert_group(),
+            Some('\\') => self.convert_command(),
+            Some(c) => c.to_string(),
+            None => String::new(),
+        }
+    }
+
+    fn convert_command(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+            name.push(c);
+        }
+
+        if name.is_empty() {
+            return match self.chars.next() {
+                Some('\\') => "\n".to_string(),
+                Some(',' | ':' | ';' | ' ') => " ".to_string(),
+                Some('!') | None => String::new(),
+                Some(c) => c.to_string(),
+            };
+        }
+
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.convert_argument();
+                let denominator = self.convert_argument();
+                format!("{}/{}", parenthesize(numerator), parenthesize(denominator))
+            }
+            "sqrt" => {
+                let mut index = String::new();
+                if self.chars.next_if_eq(&'[').is_some() {
+                    while let Some(c) = self.chars.next_if(|c| *c != ']') {
+                        index.push(c);
+                    }
+                    self.chars.next();
+                }
+                let radical = match index.trim() {
+                    "" | "2" => "√".to_string(),
+                    "3" => "∛".to_string(),
+                    "4" => "∜".to_string(),
+                    index => format!("{}√", script(index, superscript, '^')),
+                };
+                format!("{radical}{}", parenthesize(self.convert_argument()))
+            }
+            "mathbb" => self.convert_argument().chars().map(double_struck).collect(),
+            "begin" | "end" => {
+                self.convert_argument();
+                String::new()
+            }
+            name if TEXT_COMMANDS.contains(&name) => self.convert_argument(),
+            name if IGNORED_COMMANDS.contains(&name) => {
+                // `\left.` and `\right.` stand for an invisible delimiter.
+                self.chars.next_if_eq(&'.');
+                String::new()
+            }
+            name => {
+                if let Some((_, accent)) = ACCENTS.iter().find(|(accent, _)| *accent == name) {
+                    let mut argument = self.convert_argument();
+                    argument.push(*accent);
+                    argument
+                } else if let Some((_, symbol)) = SYMBOLS.iter().find(|(symbol, _)| *symbol == name)
+                {
+                    symbol.to_string()
+                } else {
+                    // Function names like `\sin` or `\log`, and anything unknown.
+                    name.to_string()
+                }
+            }
+        }
+    }
+}
+
+fn parenthesize(text: String) -> String {
+    if text.chars().count() <= 1 || text.chars().all(|c| c.is_alphanumeric()) {
+        text
+    } else {
+        format!("({text})")
+    }
+}
+
+fn script(text: &str, map: fn(char) -> Option<char>, marker: char) -> String {
+    if !text.is_empty()
+        && let Some(mapped) = text.chars().map(map).collect::<Option<String>>()
+    {
+        mapped
+    } else {
+        format!("{marker}{}", parenthesize(text.to_string()))
+    }
+}
+
+fn superscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        'T' => 'ᵀ',
+        '′' => '′',
+        _ => return None,
+    })
+}
+
+fn subscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
+
+fn double_struck(c: char) -> char {
+    match c {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latex_to_unicode() {
+        assert_eq!(latex_to_unicode(r"$\frac{1}{2} + x^2$"), "1/2 + x²");
+        assert_eq!(latex_to_unicode(r"\alpha_i \leq \sqrt{x+1}"), "αᵢ ≤ √(x+1)");
+        assert_eq!(
+            latex_to_unicode(r"$$\sum_{n=1}^{\infty} \frac{1}{n^2} = \frac{\pi^2}{6}$$"),
+            "∑ₙ₌₁^∞ 1/n² = π²/6"
+        );
+        assert_eq!(latex_to_unicode(r"\mathbb{R}^{n}"), "ℝⁿ");
+        assert_eq!(
+            latex_to_unicode(r"\left( \frac{a+b}{c} \right)"),
+            "( (a+b)/c )"
+        );
+        assert_eq!(
+            latex_to_unicode(r"\begin{cases} 1 & x > 0 \\ 0 & \text{otherwise} \end{cases}"),
+            "1 x > 0\n0 otherwise"
+        );
+        assert_eq!(
+            latex_to_unicode(r"\sqrt[3]{y} \cdot \hat{x}"),
+            "∛y ⋅ x\u{302}"
+        );
+    }
+}
@@ -14,6 +14,10 @@ impl MarkdownView {
 
         Self { markdown }
     }
+
+    pub fn source<'a>(&self, cx: &'a App) -> &'a str {
+        self.markdown.read(cx).source()
+    }
 }
 
 impl OutputContent for MarkdownView {
@@ -0,0 +1,59 @@
+//! # SVG Output
+//!
+//! Renders `image/svg+xml` outputs, such as plots from matplotlib's SVG backend, by rasterising
+//! them with the same SVG renderer used by the SVG preview. Statically rendered charts are
+//! displayed through this view as well.
+
+use anyhow::Result;
+use gpui::{App, ClipboardItem, Entity, RenderImage, Window, img};
+use language::Buffer;
+use std::sync::Arc;
+use ui::{IntoElement, Styled, div, prelude::*};
+
+use crate::outputs::OutputContent;
+
+pub struct SvgView {
+    source: String,
+    image: Arc<RenderImage>,
+}
+
+impl SvgView {
+    pub fn from(source: String, cx: &App) -> Result<Self> {
+        const SCALE_FACTOR: f32 = 1.0;
+
+        let image = cx
+            .svg_renderer()
+            .render_single_frame(source.as_bytes(), SCALE_FACTOR, true)?;
+        Ok(Self { source, image })
+    }
+}
+
+impl Render for SvgView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div().child(img(self.image.clone()).max_w_full())
+    }
+}
+
+impl OutputContent for SvgView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.source.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, _: &mut Window, cx: &mut App) -> Option<Entity<Buffer>> {
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local(self.source.clone(), cx)
+                .with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
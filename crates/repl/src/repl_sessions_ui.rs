@@ -1,16 +1,19 @@
 use editor::Editor;
 use gpui::{
-    AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, Subscription, actions,
-    prelude::*,
+    AnyElement, App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription,
+    actions, prelude::*,
 };
 use project::ProjectItem as _;
-use ui::{ButtonLike, ElevationIndex, KeyBinding, prelude::*};
+use settings::JupyterServerContent;
+use ui::{ButtonLike, Checkbox, ElevationIndex, KeyBinding, ToggleState, prelude::*};
 use util::ResultExt as _;
 use workspace::item::ItemEvent;
-use workspace::{Workspace, item::Item};
+use workspace::{ModalView, Workspace, item::Item};
 
 use crate::jupyter_settings::JupyterSettings;
-use crate::repl_store::ReplStore;
+use crate::kernels::RemoteServerListing;
+use crate::repl_store::{JupyterSecretKind, RemoteKernelAction, ReplStore};
+use crate::variable_explorer::VariableExplorer;
 
 actions!(
     repl,
@@ -29,6 +32,8 @@ actions!(
         Shutdown,
         /// Restarts the current kernel.
         Restart,
+        /// Toggles focus on the variable explorer panel.
+        Variables,
         /// Refreshes the list of available kernelspecs.
         RefreshKernelspecs
     ]
@@ -58,6 +63,10 @@ pub fn init(cx: &mut App) {
                 }
             });
 
+            workspace.register_action(|workspace, _: &Variables, window, cx| {
+                workspace.toggle_panel_focus::<VariableExplorer>(window, cx);
+            });
+
             workspace.register_action(|_workspace, _: &RefreshKernelspecs, _, cx| {
                 let store = ReplStore::global(cx);
                 store.update(cx, |store, cx| {
@@ -200,15 +209,19 @@ impl Render for ReplSessionsPage {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let store = ReplStore::global(cx);
 
-        let (kernel_specifications, sessions) = store.update(cx, |store, _cx| {
-            (
-                store
-                    .pure_jupyter_kernel_specifications()
-                    .cloned()
-                    .collect::<Vec<_>>(),
-                store.sessions().cloned().collect::<Vec<_>>(),
-            )
-        });
+        let (kernel_specifications, sessions, configured_servers, remote_servers) =
+            store.update(cx, |store, _cx| {
+                (
+                    store
+                        .pure_jupyter_kernel_specifications()
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    store.sessions().cloned().collect::<Vec<_>>(),
+                    store.configured_servers().to_vec(),
+                    store.remote_servers().to_vec(),
+                )
+            });
+        let remote_servers = render_remote_servers(&configured_servers, &remote_servers, cx);
 
         // When there are no kernel specifications, show a link to the Zed docs explaining how to
         // install kernels. It can be assumed they don't have a running kernel if we have no
@@ -231,22 +244,185 @@ impl Render for ReplSessionsPage {
                                 )
                             }),
                     ),
-                );
+                )
+                .children(remote_servers);
         }
 
         // When there are no sessions, show the command to run code in an editor
         if sessions.is_empty() {
             let instructions = "To run code in a Jupyter kernel, select some code and use the 'repl::Run' command.";
 
-            return ReplSessionsContainer::new("No Jupyter Kernel Sessions").child(
-                v_flex()
-                    .child(Label::new(instructions))
-                    .child(KeyBinding::for_action(&Run, cx)),
-            );
+            return ReplSessionsContainer::new("No Jupyter Kernel Sessions")
+                .child(
+                    v_flex()
+                        .child(Label::new(instructions))
+                        .child(KeyBinding::for_action(&Run, cx)),
+                )
+                .children(remote_servers);
         }
 
-        ReplSessionsContainer::new("Jupyter Kernel Sessions").children(sessions)
+        ReplSessionsContainer::new("Jupyter Kernel Sessions")
+            .children(sessions)
+            .children(remote_servers)
+    }
+}
+
+/// Lists the kernels running on each configured Jupyter server, with controls for managing them.
+fn render_remote_servers(
+    configured_servers: &[JupyterServerContent],
+    remote_servers: &[RemoteServerListing],
+    cx: &App,
+) -> Option<AnyElement> {
+    if configured_servers.is_empty() && remote_servers.is_empty() {
+        return None;
     }
+
+    let is_configured = |base_url: &str| {
+        configured_servers
+            .iter()
+            .any(|server| server.url.trim_end_matches('/') == base_url)
+    };
+
+    let servers = remote_servers.iter().map(|listing| {
+        let kernels = listing.kernels.iter().map(|kernel| {
+            let actions = [
+                (RemoteKernelAction::Interrupt, "Interrupt"),
+                (RemoteKernelAction::Restart, "Restart"),
+                (RemoteKernelAction::Shutdown, "Shut Down"),
+            ];
+
+            h_flex()
+                .w_full()
+                .gap_2()
+                .child(Label::new(listing.kernel_label(kernel)).truncate())
+                .children(
+                    kernel
+                        .execution_state
+                        .clone()
+                        .map(|state| Label::new(state).color(Color::Muted)),
+                )
+                .child(div().flex_1())
+                .children(actions.into_iter().map(|(action, label)| {
+                    let server = listing.server.clone();
+                    let kernel_id = kernel.id.clone();
+                    let id = SharedString::from(format!(
+                        "{label}-{}-{}",
+                        listing.server.base_url, kernel.id
+                    ));
+
+                    Button::new(id, label)
+                        .style(ButtonStyle::Subtle)
+                        .on_click(move |_, _, cx| {
+                            ReplStore::global(cx)
+                                .update(cx, |store, cx| {
+                                    store.manage_remote_kernel(
+                                        server.clone(),
+                                        kernel_id.clone(),
+                                        action,
+                                        cx,
+                                    )
+                                })
+                                .detach_and_log_err(cx);
+                        })
+                }))
+        });
+
+        v_flex()
+            .gap_1()
+            .pb_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(listing.server.name.clone()))
+                    .child(
+                        Label::new(listing.server.base_url.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(div().flex_1())
+                    .when(
+                        listing.server.is_authenticated()
+                            && is_configured(&listing.server.base_url),
+                        |this| {
+                            let url = listing.server.base_url.clone();
+                            this.child(
+                                Button::new(
+                                    SharedString::from(format!("sign-out-{url}")),
+                                    "Sign Out",
+                                )
+                                .style(ButtonStyle::Subtle)
+                                .on_click(move |_, _, cx| {
+                                    ReplStore::global(cx)
+                                        .update(cx, |store, cx| {
+                                            store.forget_jupyter_credentials(&url, cx)
+                                        })
+                                        .detach_and_log_err(cx);
+                                }),
+                            )
+                        },
+                    ),
+            )
+            .when(listing.kernels.is_empty(), |this| {
+                this.child(Label::new("No running kernels").color(Color::Muted))
+            })
+            .children(kernels)
+    });
+
+    // Servers that couldn't be reached or refused the credentials in the keychain.
+    let disconnected_servers = configured_servers
+        .iter()
+        .filter(|server| {
+            !remote_servers
+                .iter()
+                .any(|listing| listing.server.base_url == server.url.trim_end_matches('/'))
+        })
+        .map(|server| {
+            let server = server.clone();
+            h_flex()
+                .w_full()
+                .gap_2()
+                .pb_2()
+                .border_b_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(Label::new(server.name.clone()))
+                .child(
+                    Label::new(server.url.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(Label::new("Not connected").color(Color::Muted))
+                .child(div().flex_1())
+                .child(
+                    Button::new(
+                        SharedString::from(format!("sign-in-{}", server.url)),
+                        "Sign In",
+                    )
+                    .style(ButtonStyle::Subtle)
+                    .on_click(move |_, window, cx| {
+                        let Some(workspace) = Workspace::for_window(window, cx) else {
+                            return;
+                        };
+                        let server = server.clone();
+                        workspace.update(cx, |workspace, cx| {
+                            workspace.toggle_modal(window, cx, |window, cx| {
+                                JupyterSignInModal::new(server, window, cx)
+                            })
+                        });
+                    }),
+                )
+        });
+
+    Some(
+        v_flex()
+            .pt_4()
+            .gap_2()
+            .child(Label::new("Jupyter Servers").size(LabelSize::Large))
+            .children(servers)
+            .children(disconnected_servers)
+            .into_any_element(),
+    )
 }
 
 #[derive(IntoElement)]
@@ -280,3 +456,117 @@ impl RenderOnce for ReplSessionsContainer {
             .children(self.children)
     }
 }
+
+/// Asks for the token or password of a Jupyter server, which is kept in the system keychain
+/// rather than in settings.
+struct JupyterSignInModal {
+    server: JupyterServerContent,
+    kind: JupyterSecretKind,
+    editor: Entity<Editor>,
+}
+
+impl JupyterSignInModal {
+    fn new(server: JupyterServerContent, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_masked(true, cx);
+            editor.set_placeholder_text("Token", window, cx);
+            editor
+        });
+        Self {
+            server,
+            kind: JupyterSecretKind::Token,
+            editor,
+        }
+    }
+
+    fn set_kind(&mut self, kind: JupyterSecretKind, window: &mut Window, cx: &mut Context<Self>) {
+        self.kind = kind;
+        let placeholder = match kind {
+            JupyterSecretKind::Token => "Token",
+            JupyterSecretKind::Password => "Password",
+        };
+        self.editor.update(cx, |editor, cx| {
+            editor.set_placeholder_text(placeholder, window, cx)
+        });
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let secret = self.editor.update(cx, |editor, cx| {
+            let text = editor.text(cx);
+            editor.clear(window, cx);
+            text
+        });
+        if !secret.is_empty() {
+            let url = self.server.url.clone();
+            let kind = self.kind;
+            ReplStore::global(cx)
+                .update(cx, |store, cx| {
+                    store.store_jupyter_credentials(&url, kind, secret, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for JupyterSignInModal {}
+
+impl ModalView for JupyterSignInModal {}
+
+impl Focusable for JupyterSignInModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for JupyterSignInModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let uses_password = self.kind == JupyterSecretKind::Password;
+
+        v_flex()
+            .key_context("JupyterSignInModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                v_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .child(Label::new(format!("Sign in to {}", self.server.name)))
+                    .child(
+                        Label::new(self.server.url.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .px_3()
+                    .py_2()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.editor.clone())
+                    .child(
+                        Checkbox::new("jupyter-password", ToggleState::from(uses_password))
+                            .label("Log in with a password instead of a token")
+                            .on_click(cx.listener(|this, state: &ToggleState, window, cx| {
+                                let kind = if state.selected() {
+                                    JupyterSecretKind::Password
+                                } else {
+                                    JupyterSecretKind::Token
+                                };
+                                this.set_kind(kind, window, cx);
+                            })),
+                    ),
+            )
+    }
+}
@@ -3,14 +3,16 @@ use std::sync::Arc;
 use anyhow::{Context as _, Result};
 use collections::HashMap;
 use command_palette_hooks::CommandPaletteFilter;
+use credentials_provider::CredentialsProvider;
 use gpui::{App, Context, Entity, EntityId, Global, Subscription, Task, prelude::*};
-use jupyter_websocket_client::RemoteServer;
 use language::Language;
 use project::{Fs, Project, WorktreeId};
-use settings::{Settings, SettingsStore};
+use settings::{JupyterServerContent, Settings, SettingsStore};
+use util::ResultExt as _;
 
 use crate::kernels::{
-    list_remote_kernelspecs, local_kernel_specifications, python_env_kernel_specifications,
+    JupyterServer, RemoteServerListing, local_kernel_specifications,
+    python_env_kernel_specifications,
 };
 use crate::{JupyterSettings, KernelSpecification, Session};
 
@@ -25,9 +27,48 @@ pub struct ReplStore {
     kernel_specifications: Vec<KernelSpecification>,
     selected_kernel_for_worktree: HashMap<WorktreeId, KernelSpecification>,
     kernel_specifications_for_worktree: HashMap<WorktreeId, Vec<KernelSpecification>>,
+    configured_servers: Vec<JupyterServerContent>,
+    remote_servers: Vec<RemoteServerListing>,
     _subscriptions: Vec<Subscription>,
 }
 
+/// How the secret kept in the system keychain for a Jupyter server authenticates with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JupyterSecretKind {
+    Token,
+    Password,
+}
+
+impl JupyterSecretKind {
+    /// The keychain username the secret is stored under.
+    fn username(self) -> &'static str {
+        match self {
+            Self::Token => "token",
+            Self::Password => "password",
+        }
+    }
+
+    fn from_username(username: &str) -> Self {
+        match username {
+            "password" => Self::Password,
+            _ => Self::Token,
+        }
+    }
+}
+
+/// The keychain entry for a Jupyter server, which doesn't depend on a trailing slash in settings.
+fn credentials_url(url: &str) -> &str {
+    url.trim_end_matches('/')
+}
+
+/// An operation on a kernel running on a Jupyter server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteKernelAction {
+    Interrupt,
+    Restart,
+    Shutdown,
+}
+
 impl ReplStore {
     const NAMESPACE: &'static str = "repl";
 
@@ -49,6 +90,12 @@ impl ReplStore {
     pub fn new(fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Self {
         let subscriptions = vec![cx.observe_global::<SettingsStore>(move |this, cx| {
             this.set_enabled(JupyterSettings::enabled(cx), cx);
+
+            let servers = &JupyterSettings::get_global(cx).servers;
+            if servers != &this.configured_servers {
+                this.configured_servers = servers.clone();
+                this.refresh_kernelspecs(cx).detach_and_log_err(cx);
+            }
         })];
 
         let this = Self {
@@ -59,6 +106,8 @@ impl ReplStore {
             _subscriptions: subscriptions,
             kernel_specifications_for_worktree: HashMap::default(),
             selected_kernel_for_worktree: HashMap::default(),
+            configured_servers: JupyterSettings::get_global(cx).servers.clone(),
+            remote_servers: Vec::new(),
         };
         this.on_enabled_changed(cx);
         this
@@ -91,6 +140,10 @@ impl ReplStore {
         self.sessions.values()
     }
 
+    pub fn remote_servers(&self) -> &[RemoteServerListing] {
+        &self.remote_servers
+    }
+
     fn set_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
         if self.enabled == enabled {
             return;
@@ -136,66 +189,173 @@ impl ReplStore {
         })
     }
 
-    fn get_remote_kernel_specifications(
-        &self,
-        cx: &mut Context<Self>,
-    ) -> Option<Task<Result<Vec<KernelSpecification>>>> {
-        match (
-            std::env::var("JUPYTER_SERVER"),
-            std::env::var("JUPYTER_TOKEN"),
-        ) {
-            (Ok(server), Ok(token)) => {
-                let remote_server = RemoteServer {
-                    base_url: server,
-                    token,
+    pub fn configured_servers(&self) -> &[JupyterServerContent] {
+        &self.configured_servers
+    }
+
+    /// Connects to the Jupyter servers from settings, authenticating with the secrets kept for
+    /// them in the system keychain, along with the server set by the environment.
+    fn fetch_remote_servers(&self, cx: &mut Context<Self>) -> Task<Vec<RemoteServerListing>> {
+        let servers = self.configured_servers.clone();
+        let env_server = std::env::var("JUPYTER_SERVER")
+            .ok()
+            .zip(std::env::var("JUPYTER_TOKEN").ok());
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let http_client = cx.http_client();
+
+        cx.spawn(async move |_, cx| {
+            let mut connections = Vec::new();
+            for server in servers {
+                let secret = credentials_provider
+                    .read_credentials(credentials_url(&server.url), cx)
+                    .await
+                    .with_context(|| format!("Failed to read credentials for {}", server.url))
+                    .log_err()
+                    .flatten()
+                    .and_then(|(username, secret)| {
+                        Some((
+                            JupyterSecretKind::from_username(&username),
+                            String::from_utf8(secret).ok()?,
+                        ))
+                    });
+                let (token, password) = match secret {
+                    Some((JupyterSecretKind::Token, token)) => (Some(token), None),
+                    Some((JupyterSecretKind::Password, password)) => (None, Some(password)),
+                    None => (None, None),
                 };
-                let http_client = cx.http_client();
-                Some(cx.spawn(async move |_, _| {
-                    list_remote_kernelspecs(remote_server, http_client)
-                        .await
-                        .map(|specs| specs.into_iter().map(KernelSpecification::Remote).collect())
-                }))
+                connections.push((server.name, server.url, token, password));
             }
-            _ => None,
-        }
+            if let Some((url, token)) = env_server {
+                connections.push((url.clone(), url, Some(token), None));
+            }
+
+            let listings = connections.into_iter().map(|(name, url, token, password)| {
+                let http_client = http_client.clone();
+                async move {
+                    let listing = async {
+                        let server = JupyterServer::connect(
+                            name.clone(),
+                            &url,
+                            token,
+                            password,
+                            http_client,
+                        )
+                        .await?;
+                        RemoteServerListing::fetch(server).await
+                    };
+                    listing
+                        .await
+                        .with_context(|| format!("Failed to connect to Jupyter server {name}"))
+                        .log_err()
+                }
+            });
+
+            futures::future::join_all(listings)
+                .await
+                .into_iter()
+                .flatten()
+                .collect()
+        })
+    }
+
+    /// Keeps a token or password for the Jupyter server at `url` in the system keychain, then
+    /// reconnects to the configured servers.
+    pub fn store_jupyter_credentials(
+        &mut self,
+        url: &str,
+        kind: JupyterSecretKind,
+        secret: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let url = credentials_url(url).to_string();
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .write_credentials(&url, kind.username(), secret.as_bytes(), cx)
+                .await?;
+            this.update(cx, |this, cx| this.refresh_kernelspecs(cx))?
+                .await
+        })
+    }
+
+    /// Removes the secret kept for the Jupyter server at `url`, then reconnects to the configured
+    /// servers.
+    pub fn forget_jupyter_credentials(
+        &mut self,
+        url: &str,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let url = credentials_url(url).to_string();
+        cx.spawn(async move |this, cx| {
+            credentials_provider.delete_credentials(&url, cx).await?;
+            this.update(cx, |this, cx| this.refresh_kernelspecs(cx))?
+                .await
+        })
     }
 
     pub fn refresh_kernelspecs(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
         let local_kernel_specifications = local_kernel_specifications(self.fs.clone());
 
-        let remote_kernel_specifications = self.get_remote_kernel_specifications(cx);
+        let remote_servers = self.fetch_remote_servers(cx);
 
         let all_specs = cx.background_spawn(async move {
-            let mut all_specs = local_kernel_specifications
+            let all_specs = local_kernel_specifications
                 .await?
                 .into_iter()
                 .map(KernelSpecification::Jupyter)
                 .collect::<Vec<_>>();
 
-            if let Some(remote_task) = remote_kernel_specifications
-                && let Ok(remote_specs) = remote_task.await
-            {
-                all_specs.extend(remote_specs);
-            }
-
             anyhow::Ok(all_specs)
         });
 
         cx.spawn(async move |this, cx| {
             let all_specs = all_specs.await;
+            let remote_servers = remote_servers.await;
 
-            if let Ok(specs) = all_specs {
-                this.update(cx, |this, cx| {
+            this.update(cx, |this, cx| {
+                if let Ok(mut specs) = all_specs {
+                    for listing in &remote_servers {
+                        specs.extend(
+                            listing
+                                .kernelspecs
+                                .iter()
+                                .cloned()
+                                .chain(listing.running_kernel_specifications())
+                                .map(KernelSpecification::Remote),
+                        );
+                    }
                     this.kernel_specifications = specs;
-                    cx.notify();
-                })
-                .ok();
-            }
+                }
+                this.remote_servers = remote_servers;
+                cx.notify();
+            })
+            .ok();
 
             anyhow::Ok(())
         })
     }
 
+    /// Interrupts, restarts or shuts down a kernel running on a Jupyter server, then refreshes
+    /// the kernels the server reports.
+    pub fn manage_remote_kernel(
+        &mut self,
+        server: JupyterServer,
+        kernel_id: String,
+        action: RemoteKernelAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn(async move |this, cx| {
+            match action {
+                RemoteKernelAction::Interrupt => server.interrupt_kernel(&kernel_id).await?,
+                RemoteKernelAction::Restart => server.restart_kernel(&kernel_id).await?,
+                RemoteKernelAction::Shutdown => server.shutdown_kernel(&kernel_id).await?,
+            }
+            this.update(cx, |this, cx| this.refresh_kernelspecs(cx))?
+                .await
+        })
+    }
+
     pub fn set_active_kernelspec(
         &mut self,
         worktree_id: WorktreeId,
@@ -261,9 +421,11 @@ impl ReplStore {
                     runtime_specification.kernelspec.language.to_lowercase()
                         == language_at_cursor.code_fence_block_name().to_lowercase()
                 }
+                // Running kernels are only used when they're picked explicitly.
                 KernelSpecification::Remote(remote_spec) => {
-                    remote_spec.kernelspec.language.to_lowercase()
-                        == language_at_cursor.code_fence_block_name().to_lowercase()
+                    remote_spec.kernel_id.is_none()
+                        && remote_spec.kernelspec.language.to_lowercase()
+                            == language_at_cursor.code_fence_block_name().to_lowercase()
                 }
             })
             .cloned()
@@ -273,12 +435,19 @@ impl ReplStore {
         self.sessions.get(&entity_id)
     }
 
-    pub fn insert_session(&mut self, entity_id: EntityId, session: Entity<Session>) {
+    pub fn insert_session(
+        &mut self,
+        entity_id: EntityId,
+        session: Entity<Session>,
+        cx: &mut Context<Self>,
+    ) {
         self.sessions.insert(entity_id, session);
+        cx.notify();
     }
 
-    pub fn remove_session(&mut self, entity_id: EntityId) {
+    pub fn remove_session(&mut self, entity_id: EntityId, cx: &mut Context<Self>) {
         self.sessions.remove(&entity_id);
+        cx.notify();
     }
 
     #[cfg(test)]
@@ -1,6 +1,7 @@
 use crate::components::KernelListItem;
 use crate::kernels::RemoteRunningKernel;
 use crate::setup_editor_session_actions;
+use crate::variable_explorer::{self, Variable};
 use crate::{
     KernelStatus,
     kernels::{Kernel, KernelSession, KernelSpecification, NativeRunningKernel},
@@ -32,7 +33,7 @@ use language::Point;
 use project::Fs;
 use runtimelib::{
     ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
-    ShutdownRequest,
+    ShutdownRequest, Stdio,
 };
 use std::{env::temp_dir, ops::Range, sync::Arc, time::Duration};
 use theme::ActiveTheme;
@@ -49,9 +50,25 @@ pub struct Session {
     result_inlays: HashMap<String, (InlayId, Range<Anchor>, usize)>,
     next_inlay_id: usize,
 
+    variables: SessionVariables,
+
     _subscriptions: Vec<Subscription>,
 }
 
+/// The variables defined in the kernel, as shown by the variable explorer.
+#[derive(Default)]
+struct SessionVariables {
+    /// Whether the variable explorer is showing this session. Variables are only queried while it
+    /// is, to avoid running introspection code in every kernel.
+    tracked: bool,
+    variables: Vec<Variable>,
+    error: Option<String>,
+    pending_request: Option<String>,
+    output: String,
+    /// The error raised by the introspection code of the pending request, if any.
+    kernel_error: Option<String>,
+}
+
 struct EditorBlock {
     code_range: Range<Anchor>,
     invalidation_anchor: Anchor,
@@ -233,6 +250,7 @@ impl Session {
             blocks: HashMap::default(),
             result_inlays: HashMap::default(),
             next_inlay_id: 0,
+            variables: SessionVariables::default(),
             kernel_specification,
             _subscriptions: vec![subscription],
         };
@@ -648,10 +666,113 @@ impl Session {
         }
     }
 
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables.variables
+    }
+
+    pub fn variables_error(&self) -> Option<&str> {
+        self.variables.error.as_deref()
+    }
+
+    /// Starts keeping the variables up to date after each execution.
+    pub fn track_variables(&mut self, cx: &mut Context<Self>) {
+        if !self.variables.tracked {
+            self.variables.tracked = true;
+            self.refresh_variables(cx);
+        }
+    }
+
+    /// Stops querying the variables after each execution, once the variable explorer is closed.
+    pub fn stop_tracking_variables(&mut self) {
+        self.variables.tracked = false;
+    }
+
+    fn clear_variables(&mut self, cx: &mut Context<Self>) {
+        self.variables.variables.clear();
+        self.variables.pending_request = None;
+        cx.emit(SessionEvent::VariablesChanged);
+    }
+
+    pub fn refresh_variables(&mut self, cx: &mut Context<Self>) {
+        let language = self.kernel_specification.language();
+        let Some(code) = variable_explorer::introspection_code(&language) else {
+            self.variables.error = Some(format!(
+                "The variable explorer isn't available for {language} kernels"
+            ));
+            cx.notify();
+            return;
+        };
+
+        // Variables are refreshed once the kernel has started.
+        if !matches!(self.kernel, Kernel::RunningKernel(_)) {
+            return;
+        }
+
+        // Silent requests don't publish their output, so the request isn't silent, but it's kept
+        // out of the history so that it doesn't bump the execution count.
+        let message: JupyterMessage = ExecuteRequest {
+            code,
+            silent: false,
+            store_history: false,
+            ..ExecuteRequest::default()
+        }
+        .into();
+
+        self.variables.pending_request = Some(message.header.msg_id.clone());
+        self.variables.output.clear();
+        self.variables.kernel_error = None;
+        self.send(message, cx).ok();
+    }
+
+    fn handle_variables_message(
+        &mut self,
+        content: &JupyterMessageContent,
+        cx: &mut Context<Self>,
+    ) {
+        match content {
+            JupyterMessageContent::StreamContent(stream)
+                if matches!(stream.name, Stdio::Stdout) =>
+            {
+                self.variables.output.push_str(&stream.text);
+            }
+            JupyterMessageContent::ErrorOutput(error) => {
+                self.variables.kernel_error = Some(format!("{}: {}", error.ename, error.evalue));
+            }
+            JupyterMessageContent::Status(status)
+                if status.execution_state == ExecutionState::Idle =>
+            {
+                self.variables.pending_request = None;
+                let output = std::mem::take(&mut self.variables.output);
+                let variables = match self.variables.kernel_error.take() {
+                    Some(error) => Err(anyhow::anyhow!(error)),
+                    None => variable_explorer::parse_variables(&output),
+                };
+                // Variables that couldn't be refreshed are dropped rather than shown out of date.
+                match variables {
+                    Ok(variables) => {
+                        self.variables.variables = variables;
+                        self.variables.error = None;
+                    }
+                    Err(error) => {
+                        self.variables.variables.clear();
+                        self.variables.error = Some(format!("{error:#}"));
+                    }
+                }
+                cx.emit(SessionEvent::VariablesChanged);
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
+
     pub fn interrupt(&mut self, cx: &mut Context<Self>) {
         match &mut self.kernel {
-            Kernel::RunningKernel(_kernel) => {
-                self.send(InterruptRequest {}.into(), cx).ok();
+            Kernel::RunningKernel(kernel) => {
+                if let Some(interrupt) = kernel.interrupt(cx) {
+                    interrupt.detach_and_log_err(cx);
+                } else {
+                    self.send(InterruptRequest {}.into(), cx).ok();
+                }
             }
             Kernel::StartingKernel(_task) => {
                 // NOTE: If we switch to a literal queue instead of chaining on to the task, clear all queued executions
@@ -676,6 +797,15 @@ impl Session {
         );
 
         self.kernel = kernel;
+
+        if let Kernel::RunningKernel(_) = self.kernel {
+            if self.variables.tracked {
+                self.refresh_variables(cx);
+            }
+        } else {
+            // A new kernel starts out without any of the old kernel's variables.
+            self.clear_variables(cx);
+        }
     }
 
     pub fn shutdown(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -713,6 +843,26 @@ impl Session {
     }
 
     pub fn restart(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        // Kernels hosted on a server are restarted in place, keeping their connection.
+        if let Kernel::RunningKernel(kernel) = &mut self.kernel
+            && let Some(restart) = kernel.restart(cx)
+        {
+            self.clear_outputs(cx);
+            self.clear_variables(cx);
+            cx.spawn(async move |this, cx| {
+                let result = restart.await;
+                this.update(cx, |session, cx| match result {
+                    Ok(()) if session.variables.tracked => session.refresh_variables(cx),
+                    Ok(()) => {}
+                    Err(error) => session.kernel_errored(format!("{error:#}"), cx),
+                })
+                .ok();
+            })
+            .detach();
+            cx.notify();
+            return;
+        }
+
         let kernel = std::mem::replace(&mut self.kernel, Kernel::Restarting);
 
         match kernel {
@@ -757,6 +907,8 @@ impl Session {
 
 pub enum SessionEvent {
     Shutdown(WeakEntity<Editor>),
+    /// The variables were refreshed, or cleared along with the kernel.
+    VariablesChanged,
 }
 
 impl EventEmitter<SessionEvent> for Session {}
@@ -824,10 +976,22 @@ impl KernelSession for Session {
             None => return,
         };
 
+        if self.variables.pending_request.as_ref() == Some(parent_message_id) {
+            self.handle_variables_message(&message.content, cx);
+            return;
+        }
+
         match &message.content {
             JupyterMessageContent::Status(status) => {
                 self.kernel.set_execution_state(&status.execution_state);
 
+                if status.execution_state == ExecutionState::Idle
+                    && self.variables.tracked
+                    && self.blocks.contains_key(parent_message_id)
+                {
+                    self.refresh_variables(cx);
+                }
+
                 telemetry::event!(
                     "Kernel Status Changed",
                     kernel_language = self.kernel_specification.language(),
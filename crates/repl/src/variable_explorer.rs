@@ -0,0 +1,552 @@
+//! # Variable Explorer for REPL sessions
+//!
+//! A panel showing the names defined in the kernel of the active editor's [`Session`], along with
+//! their types, shapes and a short preview. DataFrames and arrays can be opened in a [`TableView`].
+//!
+//! Jupyter has no standard message for listing variables, so the explorer runs a small,
+//! kernel-specific introspection snippet, which prints the variables as JSON to stdout. The
+//! snippet runs without being stored in the kernel's history, and doesn't leave anything behind
+//! in the user's namespace.
+use anyhow::{Context as _, Result};
+use editor::Editor;
+use gpui::{
+    Action, App, AsyncWindowContext, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    Subscription, WeakEntity, prelude::*, px,
+};
+use runtimelib::media::datatable::TabularDataResource;
+use serde::{Deserialize, Deserializer};
+use ui::{Tooltip, prelude::*};
+use workspace::Workspace;
+use workspace::dock::{DockPosition, Panel, PanelEvent};
+
+use crate::outputs::TableView;
+use crate::repl_store::ReplStore;
+use crate::session::{Session, SessionEvent};
+use crate::{JupyterSettings, Variables};
+
+/// The number of rows of a DataFrame or array that are sent over for previewing.
+const TABLE_PREVIEW_ROWS: usize = 100;
+
+const PYTHON_INTROSPECTION: &str = r#"
+def _zed_variables():
+    import json, reprlib, types
+
+    try:
+        shell = get_ipython()
+        namespace, hidden = shell.user_ns, shell.user_ns_hidden
+    except NameError:
+        namespace, hidden = globals(), {}
+
+    short_repr = reprlib.Repr()
+    short_repr.maxstring = 80
+    short_repr.maxother = 80
+
+    skipped_types = (types.ModuleType, types.FunctionType, types.BuiltinFunctionType, type)
+    variables = []
+    for name, value in list(namespace.items()):
+        if name.startswith("_") or name in hidden or isinstance(value, skipped_types):
+            continue
+
+        module = type(value).__module__ or ""
+        shape = getattr(value, "shape", None)
+        if isinstance(shape, tuple):
+            shape = [int(size) for size in shape]
+        elif hasattr(value, "__len__") and not isinstance(value, (str, bytes)):
+            try:
+                shape = [len(value)]
+            except Exception:
+                shape = None
+        else:
+            shape = None
+
+        table = None
+        try:
+            if module.startswith("pandas") and hasattr(value, "to_json"):
+                table = json.loads(value.head(ROWS).to_json(orient="table"))
+            elif module == "numpy" and getattr(value, "ndim", 0) in (1, 2):
+                import pandas
+                table = json.loads(pandas.DataFrame(value[:ROWS]).to_json(orient="table"))
+        except Exception:
+            table = None
+
+        try:
+            preview = short_repr.repr(value).replace("\n", " ")
+        except Exception:
+            preview = "<unavailable>"
+
+        variables.append({
+            "name": name,
+            "type": type(value).__name__,
+            "shape": shape,
+            "preview": preview,
+            "table": table,
+        })
+
+    print(json.dumps(variables, default=str))
+
+_zed_variables()
+del _zed_variables
+"#;
+
+/// Returns the code that lists the variables defined in a kernel for the given language, if the
+/// variable explorer supports it.
+pub(crate) fn introspection_code(language: &str) -> Option<String> {
+    match language.to_lowercase().as_str() {
+        "python" => Some(PYTHON_INTROSPECTION.replace("ROWS", &TABLE_PREVIEW_ROWS.to_string())),
+        _ => None,
+    }
+}
+
+/// A variable defined in a kernel.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Variable {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(default)]
+    pub shape: Option<Vec<usize>>,
+    #[serde(default)]
+    pub preview: String,
+    /// The first rows of the variable's data, for DataFrames and arrays.
+    #[serde(default, deserialize_with = "deserialize_table")]
+    pub table: Option<TabularDataResource>,
+}
+
+impl Variable {
+    pub fn shape_label(&self) -> Option<String> {
+        let shape = self.shape.as_ref().filter(|shape| !shape.is_empty())?;
+        Some(
+            shape
+                .iter()
+                .map(|size| size.to_string())
+                .collect::<Vec<_>>()
+                .join(" × "),
+        )
+    }
+}
+
+/// Tables the [`TableView`] can't display are treated as missing, rather than failing to list
+/// the variable at all.
+fn deserialize_table<'de, D>(deserializer: D) -> Result<Option<TabularDataResource>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| serde_json::from_value(value).ok()))
+}
+
+/// Parses the output of an introspection snippet.
+pub(crate) fn parse_variables(output: &str) -> Result<Vec<Variable>> {
+    let line = output
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .context("Kernel didn't report any variables")?;
+    serde_json::from_str(line).context("Failed to parse variables reported by the kernel")
+}
+
+pub struct VariableExplorer {
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    position: DockPosition,
+    size: Option<Pixels>,
+    active: bool,
+    /// The session of the active editor.
+    session: Option<Entity<Session>>,
+    open_table: Option<(String, Entity<TableView>)>,
+    _session_subscriptions: Vec<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl VariableExplorer {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<Entity<Self>> {
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            Self::new(workspace, window, cx)
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let session = active_session(workspace, cx);
+        let workspace_handle = cx.entity();
+
+        cx.new(|cx| {
+            let subscriptions = vec![
+                cx.subscribe_in(&workspace_handle, window, |this, _, event, window, cx| {
+                    if let workspace::Event::ActiveItemChanged = event {
+                        this.update_session(window, cx);
+                    }
+                }),
+                // Sessions are started after their editor becomes active.
+                cx.observe_in(&ReplStore::global(cx), window, |this, _, window, cx| {
+                    this.update_session(window, cx)
+                }),
+            ];
+
+            let mut this = Self {
+                workspace: workspace_handle.downgrade(),
+                focus_handle: cx.focus_handle(),
+                position: DockPosition::Right,
+                size: None,
+                active: false,
+                session: None,
+                open_table: None,
+                _session_subscriptions: Vec::new(),
+                _subscriptions: subscriptions,
+            };
+            this.set_session(session, window, cx);
+            this
+        })
+    }
+
+    fn update_session(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let session = active_session(workspace.read(cx), cx);
+        if session != self.session {
+            self.set_session(session, window, cx);
+        }
+    }
+
+    fn set_session(
+        &mut self,
+        session: Option<Entity<Session>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.active
+            && let Some(previous) = self.session.as_ref()
+        {
+            previous.update(cx, |session, _| session.stop_tracking_variables());
+        }
+
+        self.open_table = None;
+        self._session_subscriptions = session
+            .iter()
+            .flat_map(|session| {
+                [
+                    cx.observe(session, |_, _, cx| cx.notify()),
+                    cx.subscribe_in(session, window, |this, _, event, window, cx| {
+                        if let SessionEvent::VariablesChanged = event {
+                            this.refresh_open_table(window, cx);
+                        }
+                    }),
+                ]
+            })
+            .collect();
+
+        if self.active
+            && let Some(session) = session.as_ref()
+        {
+            session.update(cx, |session, cx| session.track_variables(cx));
+        }
+        self.session = session;
+        cx.notify();
+    }
+
+    /// Shows the latest data of the variable whose table is open, or closes the table when the
+    /// variable is no longer defined.
+    fn refresh_open_table(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((name, _)) = self.open_table.take() else {
+            return;
+        };
+        let table = self.session.as_ref().and_then(|session| {
+            session
+                .read(cx)
+                .variables()
+                .iter()
+                .find(|variable| variable.name == name)
+                .and_then(|variable| variable.table.clone())
+        });
+        self.open_table =
+            table.map(|table| (name, cx.new(|cx| TableView::new(&table, window, cx))));
+        cx.notify();
+    }
+
+    fn toggle_table(&mut self, variable: &Variable, window: &mut Window, cx: &mut Context<Self>) {
+        if self
+            .open_table
+            .as_ref()
+            .is_some_and(|(name, _)| name == &variable.name)
+        {
+            self.open_table = None;
+        } else if let Some(table) = variable.table.as_ref() {
+            let table_view = cx.new(|cx| TableView::new(table, window, cx));
+            self.open_table = Some((variable.name.clone(), table_view));
+        }
+        cx.notify();
+    }
+
+    fn render_variable(
+        &self,
+        index: usize,
+        variable: &Variable,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_open = self
+            .open_table
+            .as_ref()
+            .is_some_and(|(name, _)| name == &variable.name);
+
+        h_flex()
+            .id(("variable", index))
+            .w_full()
+            .gap_4()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                div()
+                    .w_40()
+                    .child(Label::new(variable.name.clone()).truncate()),
+            )
+            .child(
+                div().w_32().child(
+                    Label::new(variable.type_name.clone())
+                        .color(Color::Muted)
+                        .truncate(),
+                ),
+            )
+            .child(
+                div().w_24().child(
+                    Label::new(variable.shape_label().unwrap_or_default())
+                        .color(Color::Muted)
+                        .truncate(),
+                ),
+            )
+            .child(
+                div().flex_1().min_w_0().child(
+                    Label::new(variable.preview.clone())
+                        .buffer_font(cx)
+                        .truncate(),
+                ),
+            )
+            .when(variable.table.is_some(), |row| {
+                let variable = variable.clone();
+                row.child(
+                    IconButton::new(("view-table", index), IconName::Eye)
+                        .toggle_state(is_open)
+                        .tooltip(Tooltip::text("View Data"))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.toggle_table(&variable, window, cx)
+                        })),
+                )
+            })
+    }
+}
+
+/// The REPL session of the workspace's active editor, if it has one.
+fn active_session(workspace: &Workspace, cx: &App) -> Option<Entity<Session>> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    ReplStore::global(cx)
+        .read(cx)
+        .get_session(editor.entity_id())
+        .cloned()
+}
+
+impl EventEmitter<PanelEvent> for VariableExplorer {}
+
+impl Focusable for VariableExplorer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for VariableExplorer {
+    fn persistent_name() -> &'static str {
+        "VariableExplorer"
+    }
+
+    fn panel_key() -> &'static str {
+        "VariableExplorer"
+    }
+
+    fn position(&self, _: &Window, _: &App) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        self.position = position;
+        cx.notify();
+    }
+
+    fn size(&self, _: &Window, _: &App) -> Pixels {
+        self.size.unwrap_or(px(360.))
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.size = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, _: &App) -> Option<IconName> {
+        // The panel is only offered while the active editor has a REPL session.
+        self.session.as_ref().map(|_| IconName::ReplNeutral)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Variable Explorer")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(Variables)
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        if self.active == active {
+            return;
+        }
+        self.active = active;
+        if let Some(session) = self.session.as_ref() {
+            session.update(cx, |session, cx| {
+                if active {
+                    session.track_variables(cx);
+                } else {
+                    session.stop_tracking_variables();
+                }
+            });
+        }
+    }
+
+    fn activation_priority(&self) -> u32 {
+        11
+    }
+
+    fn enabled(&self, cx: &App) -> bool {
+        JupyterSettings::enabled(cx)
+    }
+}
+
+impl Render for VariableExplorer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let panel = v_flex()
+            .id("variable-explorer")
+            .track_focus(&self.focus_handle)
+            .p_4()
+            .gap_2()
+            .size_full()
+            .bg(cx.theme().colors().panel_background);
+
+        let Some(session) = self.session.as_ref() else {
+            return panel.child(
+                Label::new("Run code in a REPL session to see its variables").color(Color::Muted),
+            );
+        };
+        let session = session.read(cx);
+        let variables = session.variables().to_vec();
+        let error = session.variables_error().map(|error| error.to_string());
+
+        panel
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Variables").size(LabelSize::Large))
+                    .child(
+                        IconButton::new("refresh-variables", IconName::RotateCw)
+                            .tooltip(Tooltip::text("Refresh Variables"))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                if let Some(session) = this.session.as_ref() {
+                                    session.update(cx, |session, cx| session.refresh_variables(cx));
+                                }
+                            })),
+                    ),
+            )
+            .children(error.map(|error| Label::new(error).color(Color::Error)))
+            .when(variables.is_empty(), |this| {
+                this.child(Label::new("No variables defined").color(Color::Muted))
+            })
+            .child(
+                v_flex()
+                    .id("variables")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(
+                        variables
+                            .iter()
+                            .enumerate()
+                            .map(|(index, variable)| self.render_variable(index, variable, cx)),
+                    ),
+            )
+            .children(self.open_table.as_ref().map(|(name, table_view)| {
+                v_flex()
+                    .id("variable-table")
+                    .max_h(relative(0.5))
+                    .gap_1()
+                    .overflow_scroll()
+                    .child(Label::new(name.clone()).color(Color::Muted))
+                    .child(table_view.clone())
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_variables() {
+        let output = r#"[
+            {"name": "x", "type": "int", "shape": null, "preview": "42", "table": null},
+            {"name": "values", "type": "list", "shape": [3], "preview": "[1, 2, 3]", "table": null},
+            {
+                "name": "df",
+                "type": "DataFrame",
+                "shape": [2, 1],
+                "preview": "   a 0  1 1  2",
+                "table": {
+                    "schema": {
+                        "fields": [
+                            {"name": "index", "type": "integer"},
+                            {"name": "a", "type": "integer"}
+                        ],
+                        "primaryKey": ["index"],
+                        "pandas_version": "1.4.0"
+                    },
+                    "data": [{"index": 0, "a": 1}, {"index": 1, "a": 2}]
+                }
+            },
+            {"name": "odd", "type": "Thing", "preview": "Thing()", "table": {"unexpected": true}}
+        ]"#
+        .replace('\n', " ");
+
+        let variables = parse_variables(&format!("{output}\n")).unwrap();
+        assert_eq!(variables.len(), 4);
+
+        assert_eq!(variables[0].name, "x");
+        assert_eq!(variables[0].type_name, "int");
+        assert_eq!(variables[0].shape_label(), None);
+
+        assert_eq!(variables[1].shape_label().as_deref(), Some("3"));
+
+        assert_eq!(variables[2].shape_label().as_deref(), Some("2 × 1"));
+        let table = variables[2].table.as_ref().unwrap();
+        assert_eq!(table.schema.fields.len(), 2);
+        assert_eq!(table.data.as_ref().unwrap().len(), 2);
+
+        assert_eq!(variables[3].shape, None);
+        assert!(variables[3].table.is_none());
+
+        assert!(parse_variables("").is_err());
+        assert!(parse_variables("Traceback (most recent call last):").is_err());
+    }
+
+    #[test]
+    fn test_introspection_code() {
+        let code = introspection_code("Python").unwrap();
+        assert!(code.contains("head(100)"));
+        assert!(!code.contains("ROWS"));
+        assert!(introspection_code("typescript").is_none());
+    }
+}
@@ -753,9 +753,11 @@ impl VsCodeSettings {
             max_scroll_history_lines: self.read_usize("terminal.integrated.scrollback"),
             minimum_contrast: None,
             option_as_meta: self.read_bool("terminal.integrated.macOptionIsMeta"),
+            persist_scrollback: self.read_bool("terminal.integrated.enablePersistentSessions"),
             project: self.project_terminal_settings_content(),
             scrollbar: None,
             scroll_multiplier: None,
+            shell_integration: self.read_bool("terminal.integrated.shellIntegration.enabled"),
             toolbar: None,
         })
     }
@@ -810,6 +810,24 @@ pub struct JupyterContent {
     ///
     /// Default: `{}`
     pub kernel_selections: Option<HashMap<String, String>>,
+
+    /// Jupyter servers to use kernels from, in addition to the one set by the
+    /// `JUPYTER_SERVER` and `JUPYTER_TOKEN` environment variables.
+    ///
+    /// Default: `[]`
+    pub servers: Option<Vec<JupyterServerContent>>,
+}
+
+/// A connection to a Jupyter server, such as one started with `jupyter server`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct JupyterServerContent {
+    /// The name to show for the server.
+    pub name: String,
+    /// The URL of the server, e.g. `http://localhost:8888`.
+    ///
+    /// The token or password for the server isn't kept in settings, but in the system keychain,
+    /// and is entered from the REPL sessions view.
+    pub url: String,
 }
 
 /// Whether to allow drag and drop text selection in buffer.
@@ -382,6 +382,8 @@ pub struct GitSettings {
     ///
     /// Default: file_name_first
     pub path_style: Option<GitPathStyle>,
+    /// How commits made from Zed are signed.
+    pub commit_signing: Option<CommitSigningSettings>,
 }
 
 #[with_fallible_options]
@@ -474,6 +476,67 @@ pub struct BranchPickerSettingsContent {
     pub show_author_name: Option<bool>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub struct CommitSigningSettings {
+    /// Whether to sign commits.
+    ///
+    /// Default: git_config
+    pub sign: Option<CommitSigningMode>,
+    /// The kind of key to sign commits with. When unset, git's `gpg.format`
+    /// config is used. The key itself is taken from `user.signingkey`.
+    ///
+    /// Default: null
+    pub format: Option<CommitSigningFormat>,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitSigningMode {
+    /// Sign commits if the repository's `commit.gpgsign` config is set.
+    #[default]
+    GitConfig,
+    /// Always sign commits.
+    Always,
+    /// Never sign commits.
+    Never,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitSigningFormat {
+    /// Sign with a GPG key.
+    Gpg,
+    /// Sign with an SSH key.
+    Ssh,
+    /// Sign with an X.509 certificate.
+    X509,
+}
+
 #[derive(
     Clone,
     Copy,
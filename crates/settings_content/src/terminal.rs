@@ -124,6 +124,14 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: true
     pub keep_selection_on_copy: Option<bool>,
+    /// Whether to inject Zed's shell integration into bash, zsh and fish shells
+    /// spawned in the terminal, so that their prompts and commands are marked
+    /// with OSC 133 sequences. Shells that emit these sequences themselves get
+    /// their commands marked regardless.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: false
+    pub shell_integration: Option<bool>,
     /// Whether to show the terminal button in the status bar.
     ///
     /// Default: true
@@ -147,6 +155,12 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// Whether to save each terminal's scrollback, with its styles, when Zed quits,
+    /// and replay it above the new shell's prompt when the terminal is restored.
+    /// Only the most recent megabyte of each terminal's scrollback is kept.
+    ///
+    /// Default: false
+    pub persist_scrollback: Option<bool>,
     /// The multiplier for scrolling with the mouse wheel.
     ///
     /// Default: 1.0
@@ -6087,7 +6087,7 @@ fn terminal_page() -> SettingsPage {
         ]
     }
 
-    fn behavior_settings_section() -> [SettingsPageItem; 4] {
+    fn behavior_settings_section() -> [SettingsPageItem; 5] {
         [
             SettingsPageItem::SectionHeader("Behavior Settings"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -6148,6 +6148,28 @@ fn terminal_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Shell Integration",
+                description: "Whether to inject Zed's shell integration into bash, zsh and fish, to mark the prompts and commands in the terminal.",
+                field: Box::new(SettingField {
+                    json_path: Some("terminal.shell_integration"),
+                    pick: |settings_content| {
+                        settings_content
+                            .terminal
+                            .as_ref()?
+                            .shell_integration
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .terminal
+                            .get_or_insert_default()
+                            .shell_integration = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
         ]
     }
 
@@ -6193,7 +6215,7 @@ fn terminal_page() -> SettingsPage {
         ]
     }
 
-    fn advanced_settings_section() -> [SettingsPageItem; 3] {
+    fn advanced_settings_section() -> [SettingsPageItem; 4] {
         [
             SettingsPageItem::SectionHeader("Advanced Settings"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -6218,6 +6240,28 @@ fn terminal_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Persist Scrollback",
+                description: "Whether to save the terminals' scrollback when quitting, and replay it when they're restored.",
+                field: Box::new(SettingField {
+                    json_path: Some("terminal.persist_scrollback"),
+                    pick: |settings_content| {
+                        settings_content
+                            .terminal
+                            .as_ref()?
+                            .persist_scrollback
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .terminal
+                            .get_or_insert_default()
+                            .persist_scrollback = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Scroll Multiplier",
                 description: "The multiplier for scrolling in the terminal with the mouse wheel",
@@ -6693,6 +6737,38 @@ fn version_control_page() -> SettingsPage {
         ]
     }
 
+    fn commit_signing_section() -> [SettingsPageItem; 2] {
+        [
+            SettingsPageItem::SectionHeader("Commit Signing"),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Sign Commits",
+                description: "Whether to sign commits, or follow the repository's commit.gpgsign config.",
+                field: Box::new(SettingField {
+                    json_path: Some("git.commit_signing.sign"),
+                    pick: |settings_content| {
+                        settings_content
+                            .git
+                            .as_ref()?
+                            .commit_signing
+                            .as_ref()?
+                            .sign
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .git
+                            .get_or_insert_default()
+                            .commit_signing
+                            .get_or_insert_default()
+                            .sign = value;
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
+        ]
+    }
+
     SettingsPage {
         title: "Version Control",
         items: concat_sections![
@@ -6702,6 +6778,7 @@ fn version_control_page() -> SettingsPage {
             git_blame_view_section(),
             branch_picker_section(),
             git_hunks_section(),
+            commit_signing_section(),
         ],
     }
 }
@@ -0,0 +1,595 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A way to find problems in the output of a task: either the name of a built-in problem matcher,
+/// such as `$rustc`, or a custom one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherTemplate {
+    /// One of the problem matchers Zed ships with, see [`ProblemMatcher::BUILT_IN_NAMES`].
+    BuiltIn(String),
+    Custom(ProblemMatcher),
+}
+
+impl ProblemMatcherTemplate {
+    /// Returns the problem matcher this template refers to, if it exists.
+    pub fn resolve(&self) -> Option<ProblemMatcher> {
+        match self {
+            Self::BuiltIn(name) => {
+                let matcher = ProblemMatcher::built_in(name);
+                if matcher.is_none() {
+                    log::warn!("Unknown problem matcher `{name}`");
+                }
+                matcher
+            }
+            Self::Custom(matcher) => Some(matcher.clone()),
+        }
+    }
+}
+
+/// Finds problems, such as compiler errors, in the output of a task, line by line.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcher {
+    /// The severity of the problems whose patterns don't capture one.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// The directory relative paths in the output are resolved against, defaults to the task's working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_directory: Option<String>,
+    /// The patterns a problem is reported with, each matching a single line of the output, in order.
+    /// This allows matching problems that span several lines, e.g. with the message and the location
+    /// of the problem on separate lines.
+    pub pattern: Vec<ProblemPattern>,
+}
+
+/// A regular expression matching a line of a task's output, along with the indices of its
+/// capture groups holding the details of the problem.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match a line with.
+    pub regexp: String,
+    /// The capture group holding the path of the file, either absolute or relative to the task's working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<usize>,
+    /// The capture group holding the 1-based line of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// The capture group holding the 1-based column of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// The capture group holding the 1-based line the problem ends at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    /// The capture group holding the 1-based column the problem ends at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+    /// The capture group holding the severity of the problem, such as `error` or `warning`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<usize>,
+    /// The capture group holding the code of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<usize>,
+    /// The capture group holding the message of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<usize>,
+    /// Whether the last pattern can match several lines in a row, reporting a problem for each of them.
+    #[serde(default)]
+    pub r#loop: bool,
+}
+
+/// How severe a problem is, shown as the severity of its diagnostic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "error" | "err" | "e" | "fatal" | "fatal error" => Some(Self::Error),
+            "warning" | "warn" | "w" => Some(Self::Warning),
+            "info" | "information" | "note" | "i" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem found in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The path of the file, resolved against the base directory of the problem matcher if it's relative.
+    pub file: PathBuf,
+    /// The 1-based line of the problem.
+    pub line: u32,
+    pub column: Option<u32>,
+    pub end_line: Option<u32>,
+    pub end_column: Option<u32>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+/// The details of a problem gathered so far, from the lines matched by a multi-line pattern.
+#[derive(Clone, Debug, Default)]
+struct MatchedProblem {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl MatchedProblem {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |index: Option<usize>| {
+            let text = captures.get(index?)?.as_str().trim();
+            (!text.is_empty()).then(|| text.to_string())
+        };
+        let number = |index: Option<usize>| group(index)?.parse::<u32>().ok();
+
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file);
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) =
+            group(pattern.severity).and_then(|text| ProblemSeverity::parse(&text))
+        {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code);
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message);
+        }
+    }
+
+    fn into_problem(
+        self,
+        default_severity: ProblemSeverity,
+        base_directory: Option<&Path>,
+    ) -> Option<Problem> {
+        let file = PathBuf::from(self.file?);
+        Some(Problem {
+            file: match base_directory {
+                Some(base_directory) if file.is_relative() => base_directory.join(file),
+                _ => file,
+            },
+            line: self.line?,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            severity: self.severity.unwrap_or(default_severity),
+            code: self.code,
+            message: self.message.unwrap_or_default(),
+        })
+    }
+}
+
+impl ProblemMatcher {
+    /// The names of the problem matchers Zed ships with.
+    pub const BUILT_IN_NAMES: &[&str] = &[
+        "$rustc",
+        "$tsc",
+        "$gcc",
+        "$eslint-stylish",
+        "$eslint-compact",
+        "$pytest",
+    ];
+
+    /// Returns the built-in problem matcher with the given name, e.g. `$rustc`.
+    pub fn built_in(name: &str) -> Option<Self> {
+        let pattern = match name {
+            // error[E0308]: mismatched types
+            //   --> src/main.rs:4:18
+            "$rustc" => vec![
+                ProblemPattern {
+                    regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s*--> (.*):(\d+):(\d+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+            // src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.
+            // src/index.ts:3:7 - error TS2322: Type 'string' is not assignable to type 'number'.
+            "$tsc" => vec![ProblemPattern {
+                regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }],
+            // main.c:3:5: error: expected ';' before 'return'
+            "$gcc" => vec![ProblemPattern {
+                regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }],
+            // /project/src/index.js
+            //   1:10  error  'foo' is defined but never used  no-unused-vars
+            "$eslint-stylish" => vec![
+                ProblemPattern {
+                    regexp: r"^((?:[a-zA-Z]:)*[./\\]+.*?)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(.*))?$"
+                        .to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    r#loop: true,
+                    ..ProblemPattern::default()
+                },
+            ],
+            // /project/src/index.js: line 1, col 10, Error - 'foo' is defined but never used. (no-unused-vars)
+            "$eslint-compact" => vec![ProblemPattern {
+                regexp: r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                code: Some(6),
+                ..ProblemPattern::default()
+            }],
+            // tests/test_sample.py:5: AssertionError
+            "$pytest" => vec![ProblemPattern {
+                regexp: r"^([^\s].*\.py):(\d+): ([A-Z][\w.]*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                message: Some(3),
+                ..ProblemPattern::default()
+            }],
+            _ => return None,
+        };
+        Some(Self {
+            severity: ProblemSeverity::Error,
+            base_directory: None,
+            pattern,
+        })
+    }
+
+    /// Finds all problems in the given output of a task, resolving relative paths against the
+    /// matcher's base directory, or the task's working directory if it has none.
+    pub fn find_problems(&self, output: &str, cwd: Option<&Path>) -> anyhow::Result<Vec<Problem>> {
+        let base_directory = self.base_directory.as_deref().map(Path::new).or(cwd);
+        let regexes = self
+            .pattern
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.regexp)
+                    .with_context(|| format!("Invalid problem pattern `{}`", pattern.regexp))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let Some(last_pattern_ix) = regexes.len().checked_sub(1) else {
+            return Ok(Vec::new());
+        };
+
+        let mut problems = Vec::new();
+        let mut next_pattern_ix = 0;
+        let mut matched = MatchedProblem::default();
+        for line in output.lines() {
+            let line = line.trim_end();
+            // Lines that don't continue a multi-line problem may start a new one.
+            let pattern_ixs = if next_pattern_ix == 0 {
+                &[0][..]
+            } else {
+                &[next_pattern_ix, 0][..]
+            };
+            let matching_pattern = pattern_ixs
+                .iter()
+                .find_map(|&pattern_ix| Some((pattern_ix, regexes[pattern_ix].captures(line)?)));
+            match matching_pattern {
+                Some((pattern_ix, captures)) => {
+                    let pattern = &self.pattern[pattern_ix];
+                    if pattern_ix == 0 {
+                        matched = MatchedProblem::default();
+                    }
+                    if pattern_ix == last_pattern_ix {
+                        let mut problem = matched.clone();
+                        problem.capture(pattern, &captures);
+                        problems.extend(problem.into_problem(self.severity, base_directory));
+                        next_pattern_ix = if pattern.r#loop && pattern_ix > 0 {
+                            pattern_ix
+                        } else {
+                            0
+                        };
+                    } else {
+                        matched.capture(pattern, &captures);
+                        next_pattern_ix = pattern_ix + 1;
+                    }
+                }
+                None => next_pattern_ix = 0,
+            }
+        }
+        Ok(problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(matcher: &str, output: &str) -> Vec<Problem> {
+        ProblemMatcher::built_in(matcher)
+            .unwrap()
+            .find_problems(output, None)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_built_in_problem_matchers_compile() {
+        for name in ProblemMatcher::BUILT_IN_NAMES {
+            let matcher = ProblemMatcher::built_in(name).unwrap();
+            assert!(
+                matcher.find_problems("", None).unwrap().is_empty(),
+                "{name}"
+            );
+        }
+        assert!(ProblemMatcher::built_in("$unknown").is_none());
+    }
+
+    #[test]
+    fn test_rustc_problem_matcher() {
+        let output = r#"
+   Compiling example v0.1.0 (/project)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 5;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+
+error[E0308]: mismatched types
+  --> src/lib.rs:10:18
+   |
+error: could not compile `example` (bin "example") due to 1 previous error
+"#;
+        assert_eq!(
+            problems("$rustc", output),
+            vec![
+                Problem {
+                    file: PathBuf::from("src/main.rs"),
+                    line: 2,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `x`".to_string(),
+                },
+                Problem {
+                    file: PathBuf::from("src/lib.rs"),
+                    line: 10,
+                    column: Some(18),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_problem_matchers() {
+        let tsc = problems(
+            "$tsc",
+            "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+             src/util.ts:12:1 - warning TS6133: 'x' is declared but its value is never read.",
+        );
+        assert_eq!(tsc.len(), 2);
+        assert_eq!(
+            (tsc[0].file.as_path(), tsc[0].line, tsc[0].column),
+            (Path::new("src/index.ts"), 3, Some(7))
+        );
+        assert_eq!(tsc[0].code.as_deref(), Some("TS2322"));
+        assert_eq!(tsc[1].severity, ProblemSeverity::Warning);
+
+        let gcc = problems(
+            "$gcc",
+            "main.c: In function 'main':\n\
+             main.c:3:5: error: expected ';' before 'return'\n\
+             /usr/include/stdio.h:12:: note: declared here",
+        );
+        assert_eq!(gcc.len(), 2);
+        assert_eq!(gcc[0].message, "expected ';' before 'return'");
+        assert_eq!(gcc[1].column, None);
+        assert_eq!(gcc[1].severity, ProblemSeverity::Info);
+
+        let eslint = problems(
+            "$eslint-compact",
+            "/project/index.js: line 1, col 10, Warning - 'foo' is defined but never used. (no-unused-vars)",
+        );
+        assert_eq!(eslint[0].code.as_deref(), Some("no-unused-vars"));
+        assert_eq!(eslint[0].severity, ProblemSeverity::Warning);
+
+        let pytest = problems(
+            "$pytest",
+            "    def test_answer():\n\
+             >       assert inc(3) == 5\n\
+             E       assert 4 == 5\n\
+             \n\
+             tests/test_sample.py:5: AssertionError\n\
+             tests/test_sample.py:5: in test_answer",
+        );
+        assert_eq!(pytest.len(), 1);
+        assert_eq!(
+            (
+                pytest[0].file.as_path(),
+                pytest[0].line,
+                pytest[0].message.as_str()
+            ),
+            (Path::new("tests/test_sample.py"), 5, "AssertionError")
+        );
+    }
+
+    #[test]
+    fn test_looping_problem_matcher() {
+        let output = r#"
+/project/src/index.js
+  1:10  error    'foo' is defined but never used  no-unused-vars
+  3:1   warning  Unexpected console statement     no-console
+
+/project/src/other.js
+  7:3  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)
+"#;
+        let problems = problems("$eslint-stylish", output);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.file.to_str().unwrap(),
+                    problem.line,
+                    problem.severity,
+                    problem.message.as_str(),
+                    problem.code.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/project/src/index.js",
+                    1,
+                    ProblemSeverity::Error,
+                    "'foo' is defined but never used",
+                    Some("no-unused-vars")
+                ),
+                (
+                    "/project/src/index.js",
+                    3,
+                    ProblemSeverity::Warning,
+                    "Unexpected console statement",
+                    Some("no-console")
+                ),
+                (
+                    "/project/src/other.js",
+                    7,
+                    ProblemSeverity::Error,
+                    "Missing semicolon",
+                    Some("semi")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_problem_matcher() {
+        let template: ProblemMatcherTemplate = serde_json::from_value(serde_json::json!({
+            "severity": "warning",
+            "pattern": [{
+                "regexp": "^(.*):(\\d+): (.*)$",
+                "file": 1,
+                "line": 2,
+                "message": 3
+            }]
+        }))
+        .unwrap();
+        let matcher = template.resolve().unwrap();
+        assert_eq!(
+            matcher
+                .find_problems(
+                    "lib/app.rb:4: undefined method",
+                    Some(Path::new("/project"))
+                )
+                .unwrap(),
+            vec![Problem {
+                file: PathBuf::from("/project/lib/app.rb"),
+                line: 4,
+                column: None,
+                end_line: None,
+                end_column: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "undefined method".to_string(),
+            }]
+        );
+
+        let template: ProblemMatcherTemplate = serde_json::from_value("$rustc".into()).unwrap();
+        assert_eq!(template.resolve(), ProblemMatcher::built_in("$rustc"));
+        assert_eq!(
+            ProblemMatcherTemplate::BuiltIn("$missing".to_string()).resolve(),
+            None
+        );
+
+        let invalid = ProblemMatcher {
+            pattern: vec![ProblemPattern {
+                regexp: "(".to_string(),
+                ..ProblemPattern::default()
+            }],
+            ..ProblemMatcher::default()
+        };
+        assert!(invalid.find_problems("output", None).is_err());
+
+        let matcher = ProblemMatcher {
+            base_directory: Some("/project/frontend".to_string()),
+            ..ProblemMatcher::built_in("$tsc").unwrap()
+        };
+        let problems = matcher
+            .find_problems(
+                "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+                 /elsewhere/index.ts(1,1): error TS1005: ';' expected.",
+                Some(Path::new("/project")),
+            )
+            .unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| problem.file.as_path())
+                .collect::<Vec<_>>(),
+            vec![
+                Path::new("/project/frontend/src/index.ts"),
+                Path::new("/elsewhere/index.ts")
+            ]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::bail;
+
+use crate::{DependsOrder, TaskTemplate};
+
+/// A task along with the tasks that have to finish successfully before it starts, as declared by
+/// [`TaskTemplate::depends_on`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskGraph<T> {
+    /// The task to run once all of its dependencies succeeded.
+    pub task: T,
+    /// How the dependencies are run.
+    pub order: DependsOrder,
+    /// The tasks this task depends on, in the order they're declared.
+    pub dependencies: Vec<TaskGraph<T>>,
+}
+
+impl<T> TaskGraph<T> {
+    /// Builds the graph of the tasks `root` depends on, recursively.
+    ///
+    /// `template` returns the template a task was created from, and `lookup` returns the task
+    /// with the given label. Fails if a dependency can't be found, or if tasks depend on each
+    /// other in a cycle.
+    pub fn build(
+        root: T,
+        template: impl Fn(&T) -> &TaskTemplate,
+        mut lookup: impl FnMut(&str) -> Option<T>,
+    ) -> anyhow::Result<Self> {
+        Self::build_inner(root, &template, &mut lookup, &mut Vec::new())
+    }
+
+    fn build_inner(
+        task: T,
+        template: &impl Fn(&T) -> &TaskTemplate,
+        lookup: &mut impl FnMut(&str) -> Option<T>,
+        ancestors: &mut Vec<String>,
+    ) -> anyhow::Result<Self> {
+        let task_template = template(&task);
+        let label = task_template.label.clone();
+        let order = task_template.depends_order;
+        let dependency_labels = task_template.depends_on.clone();
+
+        ancestors.push(label.clone());
+        let mut dependencies = Vec::with_capacity(dependency_labels.len());
+        for dependency_label in dependency_labels {
+            if ancestors.contains(&dependency_label) {
+                bail!(
+                    "Tasks depend on each other in a cycle: {} -> {dependency_label}",
+                    ancestors.join(" -> ")
+                );
+            }
+            let Some(dependency) = lookup(&dependency_label) else {
+                bail!("Task `{label}` depends on unknown task `{dependency_label}`");
+            };
+            dependencies.push(Self::build_inner(dependency, template, lookup, ancestors)?);
+        }
+        ancestors.pop();
+
+        Ok(Self {
+            task,
+            order,
+            dependencies,
+        })
+    }
+
+    /// Whether the task has to wait for other tasks before it runs.
+    pub fn has_dependencies(&self) -> bool {
+        !self.dependencies.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(label: &str, command: &str, depends_on: &[&str]) -> TaskTemplate {
+        TaskTemplate {
+            label: label.to_string(),
+            command: command.to_string(),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            ..TaskTemplate::default()
+        }
+    }
+
+    fn labels(graph: &TaskGraph<TaskTemplate>) -> String {
+        if graph.dependencies.is_empty() {
+            return graph.task.label.clone();
+        }
+        let separator = match graph.order {
+            DependsOrder::Sequence => ", ",
+            DependsOrder::Parallel => " | ",
+        };
+        let dependencies = graph
+            .dependencies
+            .iter()
+            .map(labels)
+            .collect::<Vec<_>>()
+            .join(separator);
+        format!("{}[{dependencies}]", graph.task.label)
+    }
+
+    #[test]
+    fn test_build_task_graph() {
+        let mut templates = vec![
+            template("lint", "cargo clippy", &[]),
+            template("build", "cargo build", &[]),
+            template("test", "cargo test", &["build"]),
+            template("ci", "", &["lint", "test"]),
+        ];
+        templates[3].depends_order = DependsOrder::Parallel;
+        assert!(templates[3].is_compound());
+        assert!(!templates[2].is_compound());
+
+        let lookup = |label: &str| {
+            templates
+                .iter()
+                .find(|template| template.label == label)
+                .cloned()
+        };
+
+        let graph = TaskGraph::build(templates[3].clone(), |task| task, lookup).unwrap();
+        assert_eq!(labels(&graph), "ci[lint | test[build]]");
+
+        let graph = TaskGraph::build(templates[0].clone(), |task| task, lookup).unwrap();
+        assert!(!graph.has_dependencies());
+
+        let error = TaskGraph::build(
+            template("release", "", &["build", "publish"]),
+            |task| task,
+            lookup,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task `release` depends on unknown task `publish`"
+        );
+    }
+
+    #[test]
+    fn test_task_graph_cycles() {
+        let templates = [
+            template("a", "echo a", &["b"]),
+            template("b", "echo b", &["c"]),
+            template("c", "echo c", &["a"]),
+        ];
+        let lookup = |label: &str| {
+            templates
+                .iter()
+                .find(|template| template.label == label)
+                .cloned()
+        };
+
+        let error = TaskGraph::build(templates[0].clone(), |task| task, lookup).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Tasks depend on each other in a cycle: a -> b -> c -> a"
+        );
+
+        // The same task may be depended on more than once, as long as there's no cycle.
+        let diamond = template("all", "", &["b", "b"]);
+        let templates = [template("b", "echo b", &[])];
+        let graph = TaskGraph::build(
+            diamond,
+            |task| task,
+            |label| {
+                templates
+                    .iter()
+                    .find(|template| template.label == label)
+                    .cloned()
+            },
+        )
+        .unwrap();
+        assert_eq!(labels(&graph), "all[b, b]");
+    }
+}
@@ -0,0 +1,345 @@
+use anyhow::bail;
+use collections::{HashMap, HashSet};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::{ResolvedTask, SpawnInTerminal};
+
+/// The prefix of the variables that refer to task inputs, as in `${input:name}`.
+const INPUT_REFERENCE_PREFIX: &str = "${input:";
+
+/// What's shown in the command line of the task instead of the values of password inputs.
+const MASKED_VALUE: &str = "******";
+
+/// A value the user is asked for before a task is spawned.
+/// Referenced as `${input:id}` in the task's `command`, `args`, `env` and `cwd`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskInput {
+    /// Free-form text, typed in by the user.
+    Prompt {
+        /// The name the input is referenced by.
+        id: String,
+        /// What the input is for, shown in the prompt.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// The text the prompt is filled with initially.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+        /// Whether the text typed in is hidden, both in the prompt and in the task's command line.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        password: bool,
+    },
+    /// One of a fixed list of options.
+    Pick {
+        /// The name the input is referenced by.
+        id: String,
+        /// What the input is for, shown in the prompt.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// The options to pick from.
+        options: Vec<TaskInputOption>,
+        /// The value of the option selected initially.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+    },
+    /// One of the lines a command prints, run in the task's working directory.
+    Command {
+        /// The name the input is referenced by.
+        id: String,
+        /// What the input is for, shown in the prompt.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// The command to run.
+        command: String,
+        /// Arguments to the command.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+    },
+}
+
+/// An option of a [`TaskInput::Pick`] input: either a value, or a value along with the label it's
+/// shown with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum TaskInputOption {
+    Value(String),
+    Labeled { label: String, value: String },
+}
+
+impl TaskInputOption {
+    /// The text the option is shown with.
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Value(value) => value,
+            Self::Labeled { label, .. } => label,
+        }
+    }
+
+    /// The text the input's references are substituted with when the option is picked.
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Value(value) | Self::Labeled { value, .. } => value,
+        }
+    }
+}
+
+impl TaskInput {
+    /// The name the input is referenced by.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Prompt { id, .. } | Self::Pick { id, .. } | Self::Command { id, .. } => id,
+        }
+    }
+
+    /// What the input is for, falling back to its name.
+    pub fn description(&self) -> &str {
+        match self {
+            Self::Prompt {
+                id, description, ..
+            }
+            | Self::Pick {
+                id, description, ..
+            }
+            | Self::Command {
+                id, description, ..
+            } => description.as_deref().unwrap_or(id),
+        }
+    }
+
+    /// Whether the value of the input is a secret.
+    pub fn is_password(&self) -> bool {
+        matches!(self, Self::Prompt { password: true, .. })
+    }
+
+    /// Turns the output of a [`TaskInput::Command`] input into the options to pick from, one per
+    /// non-empty line.
+    pub fn options_from_output(output: &str) -> Vec<TaskInputOption> {
+        output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| TaskInputOption::Value(line.to_string()))
+            .collect()
+    }
+}
+
+impl ResolvedTask {
+    /// The inputs the task needs values for before it's spawned, in the order they're declared.
+    pub fn inputs(&self) -> Vec<&TaskInput> {
+        let references = self.resolved.input_references();
+        self.original_task
+            .inputs
+            .iter()
+            .filter(|input| references.contains(input.id()))
+            .collect()
+    }
+
+    /// Whether the task refers to any inputs, so that it has to be spawned with
+    /// [`Self::resolved_with_inputs`].
+    pub fn refers_to_inputs(&self) -> bool {
+        !self.resolved.input_references().is_empty()
+    }
+
+    /// Returns the task to spawn, with all input references substituted with the given values,
+    /// keyed by the inputs' names.
+    pub fn resolved_with_inputs(
+        &self,
+        values: &HashMap<String, String>,
+    ) -> anyhow::Result<SpawnInTerminal> {
+        let mut resolved = self.resolved.clone();
+        for id in resolved.input_references() {
+            if !values.contains_key(id) {
+                if self
+                    .original_task
+                    .inputs
+                    .iter()
+                    .any(|input| input.id() == id)
+                {
+                    bail!(
+                        "No value given for input `{id}` of task `{}`",
+                        resolved.label
+                    );
+                }
+                bail!(
+                    "Task `{}` refers to an unknown input `{id}`",
+                    resolved.label
+                );
+            }
+        }
+
+        let masked_values = values
+            .iter()
+            .map(|(id, value)| {
+                let is_password = self
+                    .original_task
+                    .inputs
+                    .iter()
+                    .any(|input| input.id() == id && input.is_password());
+                let value = if is_password { MASKED_VALUE } else { value };
+                (id.clone(), value.to_string())
+            })
+            .collect::<HashMap<_, _>>();
+
+        resolved.command = resolved
+            .command
+            .map(|command| substitute_inputs(&command, values));
+        resolved.args = resolved
+            .args
+            .iter()
+            .map(|arg| substitute_inputs(arg, values))
+            .collect();
+        resolved.env = resolved
+            .env
+            .iter()
+            .map(|(key, value)| (key.clone(), substitute_inputs(value, values)))
+            .collect();
+        resolved.cwd = resolved
+            .cwd
+            .map(|cwd| PathBuf::from(substitute_inputs(&cwd.to_string_lossy(), values)));
+        resolved.command_label = substitute_inputs(&resolved.command_label, &masked_values);
+        Ok(resolved)
+    }
+}
+
+impl SpawnInTerminal {
+    /// The names of the inputs referenced in the task's command, arguments, environment and
+    /// working directory.
+    fn input_references(&self) -> HashSet<&str> {
+        let mut references = HashSet::default();
+        for text in self
+            .command
+            .iter()
+            .chain(&self.args)
+            .chain(self.env.values())
+            .map(String::as_str)
+            .chain(self.cwd.as_deref().and_then(|cwd| cwd.to_str()))
+        {
+            references.extend(input_references(text));
+        }
+        references
+    }
+}
+
+/// The names of the inputs referenced in the text.
+pub(crate) fn input_references(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices(INPUT_REFERENCE_PREFIX)
+        .filter_map(|(start, _)| {
+            let name = &text[start + INPUT_REFERENCE_PREFIX.len()..];
+            Some(&name[..name.find('}')?])
+        })
+}
+
+/// Substitutes the references to inputs with values in the text, leaving the references to other
+/// inputs as they are.
+fn substitute_inputs(text: &str, values: &HashMap<String, String>) -> String {
+    let mut substituted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(INPUT_REFERENCE_PREFIX) {
+        let (before, reference) = rest.split_at(start);
+        substituted.push_str(before);
+        let name = &reference[INPUT_REFERENCE_PREFIX.len()..];
+        match name.find('}') {
+            Some(end) if values.contains_key(&name[..end]) => {
+                substituted.push_str(&values[&name[..end]]);
+                rest = &name[end + 1..];
+            }
+            _ => {
+                substituted.push_str(INPUT_REFERENCE_PREFIX);
+                rest = name;
+            }
+        }
+    }
+    substituted.push_str(rest);
+    substituted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{TaskContext, TaskTemplate};
+
+    use super::*;
+
+    #[test]
+    fn test_resolving_task_inputs() {
+        let template = TaskTemplate {
+            label: "deploy".to_string(),
+            command: "deploy ${input:environment}".to_string(),
+            args: vec![
+                "--tag=${input:tag}".to_string(),
+                "--token=${input:token}".to_string(),
+            ],
+            env: HashMap::from_iter([("TOKEN".to_string(), "${input:token}".to_string())]),
+            cwd: Some("/deployments/${input:environment}".to_string()),
+            inputs: serde_json::from_value(serde_json::json!([
+                { "type": "prompt", "id": "token", "password": true },
+                { "type": "pick", "id": "environment", "options": ["staging", { "label": "Production", "value": "prod" }] },
+                { "type": "command", "id": "tag", "command": "git", "args": ["tag"] },
+                { "type": "prompt", "id": "unused" },
+            ]))
+            .unwrap(),
+            ..TaskTemplate::default()
+        };
+        let resolved = template
+            .resolve_task("test", &TaskContext::default())
+            .unwrap();
+        assert!(resolved.refers_to_inputs());
+        assert_eq!(
+            resolved
+                .inputs()
+                .into_iter()
+                .map(|input| input.id())
+                .collect::<Vec<_>>(),
+            vec!["token", "environment", "tag"]
+        );
+        let TaskInput::Pick { options, .. } = &template.inputs[1] else {
+            panic!("Expected a pick input, got {:?}", template.inputs[1]);
+        };
+        assert_eq!(options[1].label(), "Production");
+        assert_eq!(options[1].value(), "prod");
+
+        let values = HashMap::from_iter([
+            ("token".to_string(), "hunter2".to_string()),
+            ("environment".to_string(), "prod".to_string()),
+            ("tag".to_string(), "v1.0".to_string()),
+        ]);
+        let spawn = resolved.resolved_with_inputs(&values).unwrap();
+        assert_eq!(spawn.command.as_deref(), Some("deploy prod"));
+        assert_eq!(spawn.args, vec!["--tag=v1.0", "--token=hunter2"]);
+        assert_eq!(spawn.env["TOKEN"], "hunter2");
+        assert_eq!(spawn.cwd.as_deref(), Some(Path::new("/deployments/prod")));
+        assert_eq!(spawn.command_label, "deploy prod --tag=v1.0 --token=******");
+
+        let error = resolved
+            .resolved_with_inputs(&HashMap::default())
+            .unwrap_err();
+        assert!(error.to_string().starts_with("No value given for input"));
+    }
+
+    #[test]
+    fn test_substituting_inputs() {
+        let values = HashMap::from_iter([("name".to_string(), "world".to_string())]);
+        assert_eq!(
+            substitute_inputs("hello ${input:name}, ${input:name}!", &values),
+            "hello world, world!"
+        );
+        assert_eq!(
+            substitute_inputs("${input:other} ${input:name} ${input:", &values),
+            "${input:other} world ${input:"
+        );
+        assert_eq!(
+            input_references("${input:a} $HOME ${input:b:c} ${input:").collect::<Vec<_>>(),
+            vec!["a", "b:c"]
+        );
+        assert_eq!(
+            TaskInput::options_from_output("v1.0\n\n  v1.1  \n"),
+            vec![
+                TaskInputOption::Value("v1.0".to_string()),
+                TaskInputOption::Value("v1.1".to_string()),
+            ]
+        );
+    }
+}
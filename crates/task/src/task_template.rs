@@ -9,8 +9,9 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, ProblemMatcherTemplate, ResolvedTask, RevealTarget, Shell, SpawnInTerminal,
+    TaskContext, TaskId, TaskInput, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -25,6 +26,8 @@ pub struct TaskTemplate {
     /// Human readable name of the task to display in the UI.
     pub label: String,
     /// Executable command to spawn.
+    /// May be left out for compound tasks, which only run the tasks they depend on.
+    #[serde(default)]
     pub command: String,
     /// Arguments to the command.
     #[serde(default)]
@@ -72,6 +75,22 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks that have to finish successfully before this task starts.
+    /// A task with no command that depends on other tasks is a compound task, which only runs its dependencies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// How the tasks in `depends_on` are run:
+    /// * `sequence` — one after another, in the order they're listed (default)
+    /// * `parallel` — all at once
+    #[serde(default, skip_serializing_if = "DependsOrder::is_sequence")]
+    pub depends_order: DependsOrder,
+    /// Problem matchers to turn the task output into diagnostics with, once the task finishes.
+    /// Either names of built-in matchers, such as `$rustc`, or custom matchers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub problem_matchers: Vec<ProblemMatcherTemplate>,
+    /// Values to ask for before the task is spawned, referenced as `${input:id}` in `command`, `args`, `env` and `cwd`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<TaskInput>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +128,23 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How the tasks a task depends on are run.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run the tasks one after another, in the order they're listed.
+    #[default]
+    Sequence,
+    /// Run all the tasks at once.
+    Parallel,
+}
+
+impl DependsOrder {
+    fn is_sequence(&self) -> bool {
+        *self == Self::Sequence
+    }
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -132,7 +168,7 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty() || (self.command.trim().is_empty() && !self.is_compound()) {
             return None;
         }
 
@@ -211,6 +247,21 @@ impl TaskTemplate {
             &variable_names,
             &mut substituted_variables,
         )?;
+        let mut problem_matchers = Vec::with_capacity(self.problem_matchers.len());
+        for problem_matcher in &self.problem_matchers {
+            let Some(mut problem_matcher) = problem_matcher.resolve() else {
+                continue;
+            };
+            if let Some(base_directory) = problem_matcher.base_directory.as_deref() {
+                problem_matcher.base_directory = Some(substitute_all_template_variables_in_str(
+                    base_directory,
+                    &task_variables,
+                    &variable_names,
+                    &mut substituted_variables,
+                )?);
+            }
+            problem_matchers.push(problem_matcher);
+        }
 
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
@@ -243,6 +294,7 @@ impl TaskTemplate {
         Some(ResolvedTask {
             id: id.clone(),
             substituted_variables,
+            task_context: cx.clone(),
             original_task: self.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
@@ -270,10 +322,16 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers,
             },
         })
     }
 
+    /// Whether this task has no command of its own, and only runs the tasks it depends on.
+    pub fn is_compound(&self) -> bool {
+        self.command.trim().is_empty() && !self.depends_on.is_empty()
+    }
+
     /// Validates that all `$ZED_*` variables used in this template are known
     /// variable names, returning a vector with all of the unique unknown
     /// variables.
@@ -521,6 +579,18 @@ mod tests {
                 "should not resolve task with blank label and/or command: {task_with_blank_property:?}"
             );
         }
+
+        let compound_task = TaskTemplate {
+            label: "compound task".to_string(),
+            depends_on: vec!["test task".to_string()],
+            ..TaskTemplate::default()
+        };
+        assert!(
+            compound_task
+                .resolve_task(TEST_ID_BASE, &TaskContext::default())
+                .is_some(),
+            "should resolve compound tasks without a command"
+        );
     }
 
     #[test]
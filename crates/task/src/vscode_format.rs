@@ -1,9 +1,13 @@
 use anyhow::bail;
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    DependsOrder, EnvVariableReplacer, ProblemMatcher, ProblemMatcherTemplate, ProblemPattern,
+    ProblemSeverity, TaskInput, TaskInputOption, TaskTemplate, TaskTemplates, VariableName,
+    task_input::input_references,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +26,179 @@ struct VsCodeTaskDefinition {
     #[serde(flatten)]
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
+    #[serde(default)]
+    depends_on: Option<DependsOn>,
+    #[serde(default)]
+    depends_order: Option<VsCodeDependsOrder>,
+    #[serde(default)]
+    problem_matcher: Option<ProblemMatchers>,
+}
+
+/// Code accepts either a single task or a list of tasks to depend on.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum DependsOn {
+    Multiple(Vec<TaskReference>),
+    Single(TaskReference),
+}
+
+/// A task is referred to by its label, or by an object identifying a task contributed by an extension.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum TaskReference {
+    Label(String),
+    Other(serde_json_lenient::Value),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum VsCodeDependsOrder {
+    Parallel,
+    Sequence,
+}
+
+/// Code accepts either a single problem matcher or a list of them.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum ProblemMatchers {
+    Multiple(Vec<VsCodeProblemMatcher>),
+    Single(VsCodeProblemMatcher),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Name(String),
+    Definition(ProblemMatcherDefinition),
+    /// Problem matchers that can't be converted, which shouldn't prevent importing the task.
+    Other(serde_json_lenient::Value),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct ProblemMatcherDefinition {
+    base: Option<String>,
+    severity: Option<ProblemSeverity>,
+    file_location: Option<FileLocation>,
+    pattern: Option<ProblemPatterns>,
+}
+
+/// Either `absolute`, `relative` or `autoDetect`, optionally followed by the directory relative paths are resolved against.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum FileLocation {
+    Kind(String),
+    WithDirectory(Vec<String>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum ProblemPatterns {
+    Name(String),
+    Multiple(Vec<VsCodeProblemPattern>),
+    Single(VsCodeProblemPattern),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default)]
+    r#loop: bool,
+}
+
+impl From<VsCodeProblemPattern> for ProblemPattern {
+    fn from(pattern: VsCodeProblemPattern) -> Self {
+        Self {
+            regexp: pattern.regexp,
+            file: pattern.file,
+            line: pattern.line,
+            column: pattern.column,
+            end_line: pattern.end_line,
+            end_column: pattern.end_column,
+            severity: pattern.severity,
+            code: pattern.code,
+            message: pattern.message,
+            r#loop: pattern.r#loop,
+        }
+    }
+}
+
+/// Returns the name of the built-in problem matcher matching the output Code's problem matcher with the given name does.
+fn built_in_problem_matcher_name(name: &str) -> Option<&str> {
+    // Matchers of tasks running in the background ("watch" tasks) match the same lines.
+    [name, name.strip_suffix("-watch").unwrap_or(name)]
+        .into_iter()
+        .find(|name| ProblemMatcher::built_in(name).is_some())
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> Option<ProblemMatcherTemplate> {
+        let definition = match self {
+            Self::Name(name) => {
+                let Some(built_in_name) = built_in_problem_matcher_name(&name) else {
+                    log::warn!("Skipping unsupported problem matcher `{name}`");
+                    return None;
+                };
+                return Some(ProblemMatcherTemplate::BuiltIn(built_in_name.to_string()));
+            }
+            Self::Definition(definition) => definition,
+            Self::Other(value) => {
+                log::warn!("Skipping unsupported problem matcher {value}");
+                return None;
+            }
+        };
+
+        let mut matcher = match definition.base.as_deref() {
+            Some(base) => {
+                let Some(matcher) =
+                    built_in_problem_matcher_name(base).and_then(ProblemMatcher::built_in)
+                else {
+                    log::warn!("Skipping problem matcher based on unsupported `{base}`");
+                    return None;
+                };
+                matcher
+            }
+            None => ProblemMatcher::default(),
+        };
+        if let Some(severity) = definition.severity {
+            matcher.severity = severity;
+        }
+        if let Some(FileLocation::WithDirectory(file_location)) = definition.file_location {
+            matcher.base_directory = file_location
+                .get(1)
+                .map(|directory| replacer.replace(directory));
+        }
+        match definition.pattern {
+            Some(ProblemPatterns::Name(name)) => {
+                let Some(built_in) =
+                    built_in_problem_matcher_name(&name).and_then(ProblemMatcher::built_in)
+                else {
+                    log::warn!("Skipping problem matcher with unsupported pattern `{name}`");
+                    return None;
+                };
+                matcher.pattern = built_in.pattern;
+            }
+            Some(ProblemPatterns::Multiple(patterns)) => {
+                matcher.pattern = patterns.into_iter().map(ProblemPattern::from).collect();
+            }
+            Some(ProblemPatterns::Single(pattern)) => matcher.pattern = vec![pattern.into()],
+            None => {}
+        }
+        if matcher.pattern.is_empty() {
+            log::warn!("Skipping problem matcher without patterns");
+            return None;
+        }
+        Some(ProblemMatcherTemplate::Custom(matcher))
+    }
 }
 
 #[derive(Clone, Deserialize, PartialEq, Debug)]
@@ -46,33 +223,59 @@ impl VsCodeTaskDefinition {
         self,
         replacer: &EnvVariableReplacer,
     ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
+        let references = match self.depends_on {
+            None => Vec::new(),
+            Some(DependsOn::Single(reference)) => vec![reference],
+            Some(DependsOn::Multiple(references)) => references,
+        };
+        let mut depends_on = Vec::with_capacity(references.len());
+        for reference in references {
+            match reference {
+                TaskReference::Label(label) => depends_on.push(label),
+                TaskReference::Other(_) => {
+                    log::warn!(
+                        "Skipping deserializing of a task `{}` that depends on a task without a label",
+                        self.label
+                    );
+                    return Ok(None);
+                }
+            }
         }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+        // Code runs the tasks a task depends on in parallel, unless told otherwise.
+        let depends_order = match self.depends_order {
+            Some(VsCodeDependsOrder::Sequence) => DependsOrder::Sequence,
+            Some(VsCodeDependsOrder::Parallel) => DependsOrder::Parallel,
+            None if depends_on.is_empty() => DependsOrder::Sequence,
+            None => DependsOrder::Parallel,
         };
 
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // `type` isn't set in tasks that only run the tasks they depend on; we still want to deserialize the whole object though (hence command is an Option),
+        // as that way we can provide more specific description of why deserialization failed.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
-        // Per VSC docs, only `command`, `args` and `options` support variable substitution.
+        let problem_matchers = match self.problem_matcher {
+            None => Vec::new(),
+            Some(ProblemMatchers::Single(matcher)) => vec![matcher],
+            Some(ProblemMatchers::Multiple(matchers)) => matchers,
+        }
+        .into_iter()
+        .filter_map(|matcher| matcher.into_zed_format(replacer))
+        .collect();
+        // Per VSC docs, only `command`, `args`, `options` and the problem matchers' `fileLocation` support variable substitution.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
         let mut template = TaskTemplate {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matchers,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
@@ -83,10 +286,101 @@ impl VsCodeTaskDefinition {
     }
 }
 
+fn template_input_references(template: &TaskTemplate) -> HashSet<&str> {
+    std::iter::once(&template.command)
+        .chain(&template.args)
+        .chain(template.env.values())
+        .chain(&template.cwd)
+        .flat_map(|text| input_references(text))
+        .collect()
+}
+
 /// [`VsCodeTaskFile`] is a superset of Code's task definition format.
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeInput>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum VsCodeInput {
+    PromptString {
+        id: String,
+        description: Option<String>,
+        default: Option<String>,
+        #[serde(default)]
+        password: bool,
+    },
+    PickString {
+        id: String,
+        description: Option<String>,
+        options: Vec<TaskInputOption>,
+        default: Option<String>,
+    },
+    Command {
+        id: String,
+        command: String,
+        #[serde(default)]
+        args: Option<serde_json_lenient::Value>,
+    },
+}
+
+/// The command of the popular "Tasks Shell Input" extension, which provides the lines a shell command prints as options to pick from.
+const SHELL_COMMAND_INPUT: &str = "shellCommand.execute";
+
+impl VsCodeInput {
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> Option<TaskInput> {
+        match self {
+            Self::PromptString {
+                id,
+                description,
+                default,
+                password,
+            } => Some(TaskInput::Prompt {
+                id,
+                description,
+                default,
+                password,
+            }),
+            Self::PickString {
+                id,
+                description,
+                options,
+                default,
+            } => Some(TaskInput::Pick {
+                id,
+                description,
+                options,
+                default,
+            }),
+            Self::Command { id, command, args } => {
+                let shell_command = args
+                    .as_ref()
+                    .and_then(|args| args.get("command"))
+                    .and_then(|command| command.as_str());
+                match shell_command {
+                    Some(shell_command) if command == SHELL_COMMAND_INPUT => {
+                        Some(TaskInput::Command {
+                            id,
+                            description: args
+                                .as_ref()
+                                .and_then(|args| args.get("description"))
+                                .and_then(|description| description.as_str())
+                                .map(ToOwned::to_owned),
+                            command: replacer.replace(shell_command),
+                            args: Vec::new(),
+                        })
+                    }
+                    _ => {
+                        log::warn!("Skipping input `{id}` running unsupported command `{command}`");
+                        None
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
@@ -105,6 +399,11 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 VariableName::SelectedText.to_string(),
             ),
         ]));
+        let inputs = value
+            .inputs
+            .into_iter()
+            .filter_map(|input| input.into_zed_format(&replacer))
+            .collect::<Vec<_>>();
         let templates = value
             .tasks
             .into_iter()
@@ -114,6 +413,16 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                     .log_err()
                     .flatten()
             })
+            .map(|mut template| {
+                // Inputs are shared by all tasks in Code, each task only gets the ones it refers to.
+                let references = template_input_references(&template);
+                template.inputs = inputs
+                    .iter()
+                    .filter(|input| references.contains(input.id()))
+                    .cloned()
+                    .collect();
+                template
+            })
             .collect();
         Ok(Self(templates))
     }
@@ -124,8 +433,12 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
+        DependsOrder, ProblemMatcher, ProblemMatcherTemplate, TaskInput, TaskInputOption,
         TaskTemplate, TaskTemplates, VsCodeTaskFile,
-        vscode_format::{Command, VsCodeTaskDefinition},
+        vscode_format::{
+            Command, DependsOn, FileLocation, ProblemMatcherDefinition, ProblemMatchers,
+            TaskReference, VsCodeDependsOrder, VsCodeProblemMatcher, VsCodeTaskDefinition,
+        },
     };
 
     use super::EnvVariableReplacer;
@@ -173,6 +486,11 @@ mod tests {
         const TYPESCRIPT_TASKS: &str = include_str!("../test_data/typescript.json");
         let vscode_definitions: VsCodeTaskFile =
             serde_json_lenient::from_str(TYPESCRIPT_TASKS).unwrap();
+        let named = |name: &str| {
+            Some(ProblemMatchers::Multiple(vec![VsCodeProblemMatcher::Name(
+                name.to_string(),
+            )]))
+        };
 
         let expected = vec![
             VsCodeTaskDefinition {
@@ -182,6 +500,9 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: named("$tsc"),
             },
             VsCodeTaskDefinition {
                 label: "tsc: watch ./src".to_string(),
@@ -196,6 +517,9 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: named("$tsc-watch"),
             },
             VsCodeTaskDefinition {
                 label: "npm: build:compiler".to_string(),
@@ -204,6 +528,9 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: named("$tsc"),
             },
             VsCodeTaskDefinition {
                 label: "npm: build:tests".to_string(),
@@ -212,6 +539,9 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: named("$tsc"),
             },
         ];
 
@@ -227,6 +557,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -238,18 +569,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcherTemplate::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -258,11 +592,185 @@ mod tests {
         assert_eq!(tasks.0, expected);
     }
 
+    #[test]
+    fn can_deserialize_task_inputs() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "deploy",
+                        "type": "shell",
+                        "command": "./deploy.sh ${input:environment}",
+                        "args": ["${input:branch}"]
+                    },
+                    {
+                        "label": "greet",
+                        "type": "shell",
+                        "command": "echo",
+                        "args": ["${input:name}", "${input:unsupported}"]
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "name",
+                        "type": "promptString",
+                        "description": "Who to greet",
+                        "default": "world"
+                    },
+                    {
+                        "id": "environment",
+                        "type": "pickString",
+                        "options": ["staging", { "label": "Production", "value": "prod" }],
+                        "default": "staging"
+                    },
+                    {
+                        "id": "branch",
+                        "type": "command",
+                        "command": "shellCommand.execute",
+                        "args": { "command": "git branch --format='%(refname:short)'" }
+                    },
+                    {
+                        "id": "unsupported",
+                        "type": "command",
+                        "command": "extension.pickFile"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "deploy".to_string(),
+                    command: "./deploy.sh ${input:environment}".to_string(),
+                    args: vec!["${input:branch}".to_string()],
+                    inputs: vec![
+                        TaskInput::Pick {
+                            id: "environment".to_string(),
+                            description: None,
+                            options: vec![
+                                TaskInputOption::Value("staging".to_string()),
+                                TaskInputOption::Labeled {
+                                    label: "Production".to_string(),
+                                    value: "prod".to_string(),
+                                },
+                            ],
+                            default: Some("staging".to_string()),
+                        },
+                        TaskInput::Command {
+                            id: "branch".to_string(),
+                            description: None,
+                            command: "git branch --format='%(refname:short)'".to_string(),
+                            args: Vec::new(),
+                        },
+                    ],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "greet".to_string(),
+                    command: "echo".to_string(),
+                    args: vec![
+                        "${input:name}".to_string(),
+                        "${input:unsupported}".to_string(),
+                    ],
+                    inputs: vec![TaskInput::Prompt {
+                        id: "name".to_string(),
+                        description: Some("Who to greet".to_string()),
+                        default: Some("world".to_string()),
+                        password: false,
+                    }],
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_task_dependencies() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "build",
+                        "type": "shell",
+                        "command": "make"
+                    },
+                    {
+                        "label": "test",
+                        "type": "shell",
+                        "command": "make test",
+                        "dependsOn": "build"
+                    },
+                    {
+                        "label": "ci",
+                        "dependsOn": ["build", "test"],
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "watch",
+                        "dependsOn": [{ "type": "npm", "script": "watch" }]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            vscode_definitions.tasks[2].depends_order,
+            Some(VsCodeDependsOrder::Sequence)
+        );
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "build".to_string(),
+                    command: "make".to_string(),
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "test".to_string(),
+                    command: "make test".to_string(),
+                    depends_on: vec!["build".to_string()],
+                    depends_order: DependsOrder::Parallel,
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "ci".to_string(),
+                    depends_on: vec!["build".to_string(), "test".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
     #[test]
     fn can_deserialize_rust_analyzer_tasks() {
         const RUST_ANALYZER_TASKS: &str = include_str!("../test_data/rust-analyzer.json");
         let vscode_definitions: VsCodeTaskFile =
             serde_json_lenient::from_str(RUST_ANALYZER_TASKS).unwrap();
+        let based_on = |base: &str| {
+            Some(ProblemMatchers::Single(VsCodeProblemMatcher::Definition(
+                ProblemMatcherDefinition {
+                    base: Some(base.to_string()),
+                    severity: None,
+                    file_location: Some(FileLocation::WithDirectory(vec![
+                        "relative".to_string(),
+                        "${workspaceFolder}/editors/code/".to_string(),
+                    ])),
+                    pattern: None,
+                },
+            )))
+        };
+        let rustc = || {
+            Some(ProblemMatchers::Single(VsCodeProblemMatcher::Name(
+                "$rustc".to_string(),
+            )))
+        };
         let expected = vec![
             VsCodeTaskDefinition {
                 label: "Build Extension in Background".to_string(),
@@ -271,6 +779,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: based_on("$tsc-watch"),
             },
             VsCodeTaskDefinition {
                 label: "Build Extension".to_string(),
@@ -279,6 +790,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: based_on("$tsc"),
             },
             VsCodeTaskDefinition {
                 label: "Build Server".to_string(),
@@ -288,6 +802,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: rustc(),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release)".to_string(),
@@ -297,6 +814,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: rustc(),
             },
             VsCodeTaskDefinition {
                 label: "Pretest".to_string(),
@@ -305,18 +825,33 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: based_on("$tsc"),
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    TaskReference::Label("Build Server".to_string()),
+                    TaskReference::Label("Build Extension".to_string()),
+                ])),
+                depends_order: None,
+                problem_matcher: rustc(),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    TaskReference::Label("Build Server (Release)".to_string()),
+                    TaskReference::Label("Build Extension".to_string()),
+                ])),
+                depends_order: None,
+                problem_matcher: rustc(),
             },
         ];
         assert_eq!(vscode_definitions.tasks.len(), expected.len());
@@ -325,33 +860,60 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let tsc_matchers = vec![ProblemMatcherTemplate::Custom(ProblemMatcher {
+            base_directory: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+            ..ProblemMatcher::built_in("$tsc").unwrap()
+        })];
+        let rustc_matchers = vec![ProblemMatcherTemplate::BuiltIn("$rustc".to_string())];
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: tsc_matchers.clone(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: tsc_matchers.clone(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: rustc_matchers.clone(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: rustc_matchers.clone(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: tsc_matchers.clone(),
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: rustc_matchers.clone(),
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: rustc_matchers.clone(),
                 ..Default::default()
             },
         ];
@@ -0,0 +1,331 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{Context as _, Result, anyhow};
+use editor::{Editor, actions::SelectAll};
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, AppContext as _, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    Task, Window, rems,
+};
+use picker::{Picker, PickerDelegate, highlighted_match_with_paths::HighlightedMatch};
+use task::{Shell, ShellBuilder, TaskInput, TaskInputOption};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.set_prompt_for_task_input(Box::new(prompt_for_task_input));
+}
+
+/// Shows a prompt for the value of the input, or a list of options to pick it from.
+fn prompt_for_task_input(
+    workspace: &mut Workspace,
+    input: TaskInput,
+    cwd: Option<PathBuf>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<Result<Option<String>>> {
+    let (tx, rx) = oneshot::channel();
+    let options = match &input {
+        TaskInput::Prompt {
+            default, password, ..
+        } => {
+            let description = input.description().to_string().into();
+            let default = default.clone();
+            let password = *password;
+            workspace.toggle_modal(window, cx, |window, cx| {
+                TaskInputPrompt::new(description, default, password, tx, window, cx)
+            });
+            return cx.background_spawn(async move { Ok(rx.await.ok()) });
+        }
+        TaskInput::Pick { options, .. } => Task::ready(Ok(options.clone())),
+        TaskInput::Command { command, args, .. } => {
+            if !workspace.project().read(cx).is_local() {
+                return Task::ready(Err(anyhow!(
+                    "Command task inputs are only supported in local projects"
+                )));
+            }
+            let (program, args) = ShellBuilder::new(&Shell::System, cfg!(windows))
+                .non_interactive()
+                .build(Some(command.clone()), args);
+            cx.background_spawn(async move {
+                let mut command = util::command::new_smol_command(&program);
+                command.args(&args);
+                if let Some(cwd) = cwd {
+                    command.current_dir(cwd);
+                }
+                let output = command
+                    .output()
+                    .await
+                    .with_context(|| format!("running `{program}`"))?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Command of task input failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                Ok(TaskInput::options_from_output(&String::from_utf8_lossy(
+                    &output.stdout,
+                )))
+            })
+        }
+    };
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let options = options.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                let delegate = TaskInputPickerDelegate::new(&input, options, tx);
+                Picker::uniform_list(delegate, window, cx).width(rems(34.))
+            })
+        })?;
+        Ok(rx.await.ok())
+    })
+}
+
+/// Asks for free-form text.
+struct TaskInputPrompt {
+    description: SharedString,
+    editor: Entity<Editor>,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl TaskInputPrompt {
+    fn new(
+        description: SharedString,
+        default: Option<String>,
+        password: bool,
+        tx: oneshot::Sender<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_masked(password, cx);
+            if let Some(default) = default {
+                editor.set_text(default, window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+        Self {
+            description,
+            editor,
+            tx: Some(tx),
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(self.editor.read(cx).text(cx)).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskInputPrompt {}
+
+impl ModalView for TaskInputPrompt {}
+
+impl Focusable for TaskInputPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for TaskInputPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TaskInputPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .child(Label::new(self.description.clone()).color(Color::Muted)),
+            )
+            .child(
+                div()
+                    .px_3()
+                    .py_2()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.editor.clone()),
+            )
+    }
+}
+
+/// Asks to pick one of the options of a [`TaskInput::Pick`] or [`TaskInput::Command`] input.
+struct TaskInputPickerDelegate {
+    description: Arc<str>,
+    options: Vec<TaskInputOption>,
+    default_index: usize,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl TaskInputPickerDelegate {
+    fn new(input: &TaskInput, options: Vec<TaskInputOption>, tx: oneshot::Sender<String>) -> Self {
+        let default_index = match input {
+            TaskInput::Pick {
+                default: Some(default),
+                ..
+            } => options
+                .iter()
+                .position(|option| option.value() == default)
+                .unwrap_or(0),
+            _ => 0,
+        };
+        Self {
+            description: input.description().into(),
+            options,
+            default_index,
+            matches: Vec::new(),
+            selected_index: default_index,
+            tx: Some(tx),
+        }
+    }
+}
+
+impl PickerDelegate for TaskInputPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        self.description.clone()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.options.is_empty() {
+            Some("No options to pick from".into())
+        } else {
+            Some("No matching options".into())
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(id, option)| StringMatchCandidate::new(id, option.label()))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            // Without a query, keep the options in the order they're declared in.
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = if query.is_empty() {
+                        delegate.default_index
+                    } else {
+                        0
+                    }
+                    .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(option) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.options.get(hit.candidate_id))
+        else {
+            return;
+        };
+        if let Some(tx) = self.tx.take() {
+            tx.send(option.value().to_string()).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let option = self.options.get(hit.candidate_id)?;
+        let value = match option {
+            TaskInputOption::Labeled { value, .. } => {
+                Some(Label::new(value.clone()).color(Color::Muted))
+            }
+            TaskInputOption::Value(_) => None,
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .end_slot::<Label>(value)
+                .child(
+                    HighlightedMatch {
+                        text: hit.string.clone(),
+                        highlight_positions: hit.positions.clone(),
+                        color: Color::Default,
+                    }
+                    .render(window, cx),
+                ),
+        )
+    }
+}
@@ -0,0 +1,42 @@
+# Zed's shell integration for bash, marking the prompts and commands with OSC 133 sequences.
+#
+# Sourced by the first prompt command, which Zed sets to `. "$ZED_BASH_INTEGRATION"`.
+
+PROMPT_COMMAND="${PROMPT_COMMAND/'. "$ZED_BASH_INTEGRATION"'/}"
+PROMPT_COMMAND="${PROMPT_COMMAND#"${PROMPT_COMMAND%%[![:space:];]*}"}"
+unset ZED_BASH_INTEGRATION
+# The prompt command came from the environment, keep the shells started from this one out of it.
+export -n PROMPT_COMMAND
+
+if [[ $- == *i* && -z "$__zed_shell_integration" ]]; then
+    __zed_shell_integration=1
+
+    # Marking the end of a command that wasn't marked as executed does nothing, so there's no need
+    # to keep track of whether a command ran.
+    __zed_prompt_command() {
+        local exit_code=$?
+        __zed_command_marked=
+        printf '\e]133;D;%s\a\e]133;A\a' "$exit_code"
+        return $exit_code
+    }
+
+    PROMPT_COMMAND="__zed_prompt_command${PROMPT_COMMAND:+$'\n'$PROMPT_COMMAND}"
+    if (( BASH_VERSINFO[0] > 4 || (BASH_VERSINFO[0] == 4 && BASH_VERSINFO[1] >= 4) )); then
+        # Expanded after a command is read, right before it's executed.
+        PS0="${PS0}"'\e]133;C\a'
+    elif [[ -z "$(trap -p DEBUG)" ]]; then
+        # Older versions, like the bash 3.2 macOS comes with, don't have `PS0`, so the first
+        # command run after the prompt marks the output's start, leaving out the prompt command.
+        __zed_command_marked=1
+        __zed_debug_trap() {
+            if [[ -z "$__zed_command_marked" && "$PROMPT_COMMAND" != *"$BASH_COMMAND"* ]]; then
+                __zed_command_marked=1
+                printf '\e]133;C\a'
+            fi
+        }
+        trap '__zed_debug_trap' DEBUG
+    fi
+
+    printf '\e]133;A\a'
+    __zed_command_marked=
+fi
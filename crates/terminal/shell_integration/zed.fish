@@ -0,0 +1,27 @@
+# Zed's shell integration for fish, marking the prompts and commands with OSC 133 sequences.
+#
+# Zed prepends the directory of this file to `XDG_DATA_DIRS`, so restore the user's ones first.
+
+if set -q ZED_USER_XDG_DATA_DIRS
+    set -gx XDG_DATA_DIRS $ZED_USER_XDG_DATA_DIRS
+else
+    set -e XDG_DATA_DIRS
+end
+set -e ZED_USER_XDG_DATA_DIRS
+
+if not status is-interactive; or set -q __zed_shell_integration
+    exit
+end
+set -g __zed_shell_integration 1
+
+function __zed_prompt --on-event fish_prompt
+    printf '\e]133;A\a'
+end
+
+function __zed_preexec --on-event fish_preexec
+    printf '\e]133;C\a'
+end
+
+function __zed_postexec --on-event fish_postexec
+    printf '\e]133;D;%s\a' $status
+end
@@ -0,0 +1,421 @@
+//! Terminal sessions recorded in the [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/)
+//! format: a JSON header line, followed by a JSON line for each event, timed from the start of
+//! the recording.
+
+use std::{
+    fs::File,
+    io::{self, Write},
+    mem,
+    sync::{Arc, mpsc},
+    thread::JoinHandle,
+    time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::{Context as _, Result, ensure};
+use collections::HashMap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// The recorder of a terminal, shared with its PTY, which is only set while it's being recorded.
+pub(crate) type SharedRecorder = Arc<Mutex<Option<Recorder>>>;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    pub version: u8,
+    pub width: u16,
+    pub height: u16,
+    /// When the recording started, in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// Output of the program, as printed to the terminal.
+    Output,
+    /// Input typed into the terminal.
+    Input,
+    /// A resize of the terminal, with its data as `{columns}x{lines}`.
+    Resize,
+    /// A marker for a point of interest, labelled by its data.
+    Marker,
+}
+
+impl EventKind {
+    fn code(self) -> &'static str {
+        match self {
+            Self::Output => "o",
+            Self::Input => "i",
+            Self::Resize => "r",
+            Self::Marker => "m",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "o" => Some(Self::Output),
+            "i" => Some(Self::Input),
+            "r" => Some(Self::Resize),
+            "m" => Some(Self::Marker),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    pub time: Duration,
+    pub kind: EventKind,
+    pub data: String,
+}
+
+impl Event {
+    /// The columns and lines a resize event resizes the terminal to.
+    pub fn size(&self) -> Option<(u16, u16)> {
+        if self.kind != EventKind::Resize {
+            return None;
+        }
+        let (columns, lines) = self.data.split_once('x')?;
+        Some((columns.parse().ok()?, lines.parse().ok()?))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    pub header: Header,
+    /// The events in the order they're played in.
+    pub events: Vec<Event>,
+}
+
+impl Recording {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let (_, header) = lines.next().context("the recording is empty")?;
+        let header: Header =
+            serde_json::from_str(header).context("failed to parse the recording's header")?;
+        ensure!(
+            header.version == 2,
+            "unsupported asciicast version {}",
+            header.version
+        );
+
+        let mut events = Vec::new();
+        for (index, line) in lines {
+            let (time, code, data): (f64, String, String) = serde_json::from_str(line)
+                .with_context(|| format!("failed to parse the event on line {}", index + 1))?;
+            // Players are expected to skip the kinds of events they don't know.
+            let Some(kind) = EventKind::from_code(&code) else {
+                continue;
+            };
+            let time = Duration::try_from_secs_f64(time)
+                .with_context(|| format!("invalid time for the event on line {}", index + 1))?;
+            events.push(Event { time, kind, data });
+        }
+        // The times are meant to only ever increase, but don't have to be trusted to.
+        events.sort_by_key(|event| event.time);
+        Ok(Self { header, events })
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(Duration::ZERO, |event| event.time)
+    }
+}
+
+/// Writes the output and resizes of a terminal as an asciicast recording.
+///
+/// Input isn't recorded, as it's echoed back in the output when it's meant to be seen, unlike
+/// passwords.
+pub(crate) struct Recorder<W: Write = RecordingWriter> {
+    writer: W,
+    started: Instant,
+    /// The start of a character split across the output, to be completed by the next one.
+    incomplete: Vec<u8>,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(
+        mut writer: W,
+        columns: usize,
+        lines: usize,
+        title: Option<String>,
+        env: HashMap<String, String>,
+    ) -> Result<Self> {
+        let header = Header {
+            version: 2,
+            width: columns.try_into().unwrap_or(u16::MAX),
+            height: lines.try_into().unwrap_or(u16::MAX),
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .ok()
+                .map(|since_epoch| since_epoch.as_secs()),
+            title,
+            env,
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+        Ok(Self {
+            writer,
+            started: Instant::now(),
+            incomplete: Vec::new(),
+        })
+    }
+
+    pub fn output(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let time = self.started.elapsed();
+        self.incomplete.extend_from_slice(bytes);
+        let complete = complete_utf8_len(&self.incomplete);
+        if complete == 0 {
+            return Ok(());
+        }
+        let data = String::from_utf8_lossy(&self.incomplete[..complete]).into_owned();
+        self.incomplete.drain(..complete);
+        self.write_event(time, EventKind::Output, &data)
+    }
+
+    pub fn resize(&mut self, columns: u16, lines: u16) -> io::Result<()> {
+        let time = self.started.elapsed();
+        self.write_event(time, EventKind::Resize, &format!("{columns}x{lines}"))
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.incomplete.is_empty() {
+            let data = String::from_utf8_lossy(&self.incomplete).into_owned();
+            self.write_event(self.started.elapsed(), EventKind::Output, &data)?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_event(&mut self, time: Duration, kind: EventKind, data: &str) -> io::Result<()> {
+        // Microseconds are as precise as players go.
+        let time = time.as_micros() as f64 / 1_000_000.0;
+        serde_json::to_writer(&mut self.writer, &(time, kind.code(), data))?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+/// How much of a recording is buffered before it's handed to the thread writing it.
+const CHUNK_LEN: usize = 8 * 1024;
+
+/// Writes a recording to its file on a thread of its own, keeping file I/O off the PTY's thread.
+pub(crate) struct RecordingWriter {
+    buffer: Vec<u8>,
+    chunks: Option<mpsc::Sender<Vec<u8>>>,
+    thread: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl RecordingWriter {
+    pub fn new(file: File) -> io::Result<Self> {
+        let (chunks, chunks_rx) = mpsc::channel::<Vec<u8>>();
+        let thread = std::thread::Builder::new()
+            .name("terminal recorder".to_string())
+            .spawn(move || {
+                let mut file = io::BufWriter::new(file);
+                for chunk in chunks_rx {
+                    file.write_all(&chunk)?;
+                }
+                file.flush()
+            })?;
+        Ok(Self {
+            buffer: Vec::with_capacity(CHUNK_LEN),
+            chunks: Some(chunks),
+            thread: Some(thread),
+        })
+    }
+
+    /// Waits for everything written to reach the file.
+    pub fn close(mut self) -> io::Result<()> {
+        self.send()?;
+        self.chunks.take();
+        self.join()
+    }
+
+    fn send(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = mem::replace(&mut self.buffer, Vec::with_capacity(CHUNK_LEN));
+        let sent = self
+            .chunks
+            .as_ref()
+            .is_some_and(|chunks| chunks.send(chunk).is_ok());
+        if sent {
+            return Ok(());
+        }
+        // The thread only stops taking chunks when it failed to write one.
+        self.join()?;
+        Err(io::Error::other("the recording was already closed"))
+    }
+
+    fn join(&mut self) -> io::Result<()> {
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("the recording thread panicked"))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Write for RecordingWriter {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(bytes);
+        if self.buffer.len() >= CHUNK_LEN {
+            self.send()?;
+        }
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send()
+    }
+}
+
+/// Records with the recorder, if the terminal is being recorded, dropping the recorder when the
+/// recording can't be written to anymore.
+pub(crate) fn record(recorder: &SharedRecorder, f: impl FnOnce(&mut Recorder) -> io::Result<()>) {
+    let mut recorder = recorder.lock();
+    if let Some(active) = recorder.as_mut()
+        && let Err(error) = f(active)
+    {
+        log::error!("Failed to record the terminal, stopping the recording: {error}");
+        *recorder = None;
+    }
+}
+
+/// The length of the bytes, without a UTF-8 sequence cut short at their end.
+fn complete_utf8_len(bytes: &[u8]) -> usize {
+    for (index, byte) in bytes.iter().enumerate().rev().take(4) {
+        let sequence_len = match byte {
+            0x00..0x80 => return bytes.len(),
+            // Continuation bytes, which a lead byte may still come before.
+            0x80..0xC0 => continue,
+            0xC0..0xE0 => 2,
+            0xE0..0xF0 => 3,
+            _ => 4,
+        };
+        return if bytes.len() - index < sequence_len {
+            index
+        } else {
+            bytes.len()
+        };
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_round_trip() {
+        let mut recorder = Recorder::new(
+            Vec::new(),
+            80,
+            24,
+            Some("zsh".to_string()),
+            HashMap::from_iter([("TERM".to_string(), "xterm-256color".to_string())]),
+        )
+        .unwrap();
+        let crab = "🦀".as_bytes();
+        recorder.output(b"\x1b[1mhello\x1b[0m\r\n").unwrap();
+        // A character split across reads is only recorded once it's complete.
+        recorder.output(&crab[..1]).unwrap();
+        recorder.output(&crab[1..3]).unwrap();
+        recorder.resize(100, 30).unwrap();
+        recorder.output(&crab[3..]).unwrap();
+        let text = String::from_utf8(recorder.finish().unwrap()).unwrap();
+
+        let recording = Recording::parse(&text).unwrap();
+        assert_eq!(recording.header.width, 80);
+        assert_eq!(recording.header.height, 24);
+        assert_eq!(recording.header.title.as_deref(), Some("zsh"));
+        assert!(recording.header.timestamp.is_some());
+        let events = recording
+            .events
+            .iter()
+            .map(|event| (event.kind, event.data.as_str(), event.size()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                (EventKind::Output, "\x1b[1mhello\x1b[0m\r\n", None),
+                (EventKind::Resize, "100x30", Some((100, 30))),
+                (EventKind::Output, "🦀", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recording_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("terminal.cast");
+        let mut recorder = Recorder::new(
+            RecordingWriter::new(File::create(&path).unwrap()).unwrap(),
+            80,
+            24,
+            None,
+            HashMap::default(),
+        )
+        .unwrap();
+        let line = "x".repeat(CHUNK_LEN / 3);
+        for _ in 0..10 {
+            recorder.output(line.as_bytes()).unwrap();
+        }
+        recorder.finish().unwrap().close().unwrap();
+
+        let recording = Recording::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(recording.events.len(), 10);
+        assert!(recording.events.iter().all(|event| event.data == line));
+    }
+
+    #[test]
+    fn test_parse_recording() {
+        let text = r#"{"version": 2, "width": 10, "height": 5, "idle_time_limit": 2.5}
+[0.5, "o", "a"]
+[0.25, "x", "unknown"]
+
+[1.0, "m", "chapter"]
+[0.75, "o", "b"]
+"#;
+        let recording = Recording::parse(text).unwrap();
+        assert_eq!(recording.header.env, HashMap::default());
+        assert_eq!(
+            recording.events,
+            [
+                Event {
+                    time: Duration::from_millis(500),
+                    kind: EventKind::Output,
+                    data: "a".to_string(),
+                },
+                Event {
+                    time: Duration::from_millis(750),
+                    kind: EventKind::Output,
+                    data: "b".to_string(),
+                },
+                Event {
+                    time: Duration::from_secs(1),
+                    kind: EventKind::Marker,
+                    data: "chapter".to_string(),
+                },
+            ]
+        );
+        assert_eq!(recording.duration(), Duration::from_secs(1));
+
+        assert!(Recording::parse("").is_err());
+        assert!(Recording::parse(r#"{"version": 1, "width": 10, "height": 5}"#).is_err());
+        assert!(
+            Recording::parse("{\"version\": 2, \"width\": 10, \"height\": 5}\n[-1, \"o\", \"a\"]")
+                .is_err()
+        );
+    }
+}
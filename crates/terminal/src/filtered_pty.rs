@@ -0,0 +1,259 @@
+//! The PTY, with its output filtered on the way to the terminal's parser.
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::{
+    io::{self, Read as _, Write as _},
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::{Event as AlacTermEvent, EventListener as _, OnResize, WindowSize},
+    tty::{self, ChildEvent, EventedPty, EventedReadWrite},
+};
+use parking_lot::Mutex;
+use polling::{Event as PollingEvent, PollMode, Poller};
+
+use crate::{
+    ZedListener,
+    asciicast::{self, SharedRecorder},
+    shell_integration::{MarkScanner, ScannedEvent, ScannedOutput},
+    terminal_images::{ImageFilter, TerminalImages},
+};
+
+const READ_BUFFER_SIZE: usize = 0x1_0000;
+
+/// Wraps the PTY to take the images out of its output, to scan it for the marks of the shell,
+/// holding the output after each mark back until the terminal placed it, and to record it while
+/// the terminal's being recorded.
+pub(crate) struct FilteredPty {
+    pty: tty::Pty,
+    recorder: SharedRecorder,
+    images: ImageFilter,
+    scanner: MarkScanner,
+    scanned_output: Arc<Mutex<ScannedOutput>>,
+    read_buffer: Box<[u8]>,
+    filtered: Vec<u8>,
+    /// How much of the filtered output was passed on already.
+    passed: usize,
+    replies: Vec<u8>,
+}
+
+/// Keeps the PTY readable while there's filtered output left over, since the event loop only
+/// reads from it when the PTY itself has more.
+#[cfg(unix)]
+pub(crate) struct Waker {
+    sender: UnixStream,
+    receiver: UnixStream,
+    woken: bool,
+}
+
+#[cfg(unix)]
+impl Waker {
+    fn new() -> io::Result<Self> {
+        let (sender, receiver) = UnixStream::pair()?;
+        sender.set_nonblocking(true)?;
+        receiver.set_nonblocking(true)?;
+        Ok(Self {
+            sender,
+            receiver,
+            woken: false,
+        })
+    }
+
+    pub fn set_woken(&mut self, woken: bool) {
+        if woken == self.woken {
+            return;
+        }
+        if woken {
+            (&self.sender).write_all(&[0]).ok();
+        } else {
+            let mut buffer = [0; 16];
+            while matches!((&self.receiver).read(&mut buffer), Ok(read) if read > 0) {}
+        }
+        self.woken = woken;
+    }
+}
+
+impl FilteredPty {
+    pub fn new(
+        pty: tty::Pty,
+        recorder: SharedRecorder,
+        listener: ZedListener,
+    ) -> (Self, Arc<Mutex<ScannedOutput>>, Arc<Mutex<TerminalImages>>) {
+        let scanned_output = Arc::new(Mutex::new(ScannedOutput {
+            #[cfg(unix)]
+            waker: Waker::new()
+                .inspect_err(|error| log::error!("Failed to create the PTY's waker: {error}"))
+                .ok(),
+            ..ScannedOutput::default()
+        }));
+        let (images, terminal_images) =
+            ImageFilter::new(move || listener.send_event(AlacTermEvent::Wakeup));
+        let pty = Self {
+            pty,
+            recorder,
+            images,
+            scanner: MarkScanner::default(),
+            scanned_output: scanned_output.clone(),
+            read_buffer: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+            filtered: Vec::new(),
+            passed: 0,
+            replies: Vec::new(),
+        };
+        (pty, scanned_output, terminal_images)
+    }
+}
+
+impl FilteredPty {
+    /// Reads from the PTY, adding its filtered output to what's left to pass on.
+    fn read_pty(&mut self) -> io::Result<usize> {
+        let read = self.pty.reader().read(&mut self.read_buffer)?;
+        if read == 0 {
+            return Ok(0);
+        }
+        // Recordings get the output unfiltered, to show the images in other players.
+        asciicast::record(&self.recorder, |recorder| {
+            recorder.output(&self.read_buffer[..read])
+        });
+        self.filtered.drain(..self.passed);
+        self.passed = 0;
+        self.images.filter(
+            &self.read_buffer[..read],
+            &mut self.filtered,
+            &mut self.replies,
+        );
+        if !self.replies.is_empty() {
+            if let Err(error) = self.pty.writer().write_all(&self.replies) {
+                log::error!("Failed to reply to a terminal image: {error}");
+            }
+            self.replies.clear();
+        }
+        Ok(read)
+    }
+}
+
+impl io::Read for FilteredPty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.scanned_output.lock().paused {
+            // The PTY is still read from while the output is held back, or it'd keep the event
+            // loop busy.
+            match self.read_pty() {
+                Ok(_) => {}
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => {}
+                Err(error) => return Err(error),
+            }
+            let mut scanned_output = self.scanned_output.lock();
+            if scanned_output.paused {
+                #[cfg(unix)]
+                if let Some(waker) = &mut scanned_output.waker {
+                    waker.set_woken(false);
+                }
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+        }
+
+        // Reads that turn out to be all image data leave nothing to pass on, and mustn't be
+        // mistaken for the end of the output.
+        while self.passed == self.filtered.len() {
+            if self.read_pty()? == 0 {
+                return Ok(0);
+            }
+        }
+
+        // The output is passed on up to the end of the next mark, and held back after it until
+        // the terminal placed the mark at its cursor. The event loop parses all it read before
+        // the terminal can lock it fairly.
+        let mut scanned_output = self.scanned_output.lock();
+        let scanned_events = scanned_output.events.len();
+        let filtered = &self.filtered[self.passed..];
+        let len = filtered.len().min(buf.len());
+        let len = self
+            .scanner
+            .scan(&filtered[..len], &mut scanned_output.events);
+        buf[..len].copy_from_slice(&filtered[..len]);
+        self.passed += len;
+        if scanned_output.events[scanned_events..]
+            .iter()
+            .any(|event| matches!(event, ScannedEvent::Mark(_)))
+        {
+            scanned_output.paused = scanned_output.can_pause();
+        }
+        #[cfg(unix)]
+        {
+            let woken = !scanned_output.paused && self.passed < self.filtered.len();
+            if let Some(waker) = &mut scanned_output.waker {
+                waker.set_woken(woken);
+            }
+        }
+        Ok(len)
+    }
+}
+
+impl EventedReadWrite for FilteredPty {
+    type Reader = Self;
+    type Writer = <tty::Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.pty.register(poll, interest, mode)? };
+        #[cfg(unix)]
+        if let Some(waker) = &self.scanned_output.lock().waker {
+            let interest = PollingEvent::readable(interest.key);
+            unsafe { poll.add_with_mode(&waker.receiver, interest, mode)? };
+        }
+        Ok(())
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)?;
+        #[cfg(unix)]
+        if let Some(waker) = &self.scanned_output.lock().waker {
+            let interest = PollingEvent::readable(interest.key);
+            poll.modify_with_mode(&waker.receiver, interest, mode)?;
+        }
+        Ok(())
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)?;
+        #[cfg(unix)]
+        if let Some(waker) = &self.scanned_output.lock().waker {
+            poll.delete(&waker.receiver)?;
+        }
+        Ok(())
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for FilteredPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for FilteredPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.images.resize(&window_size);
+        asciicast::record(&self.recorder, |recorder| {
+            recorder.resize(window_size.num_cols, window_size.num_lines)
+        });
+        self.pty.on_resize(window_size)
+    }
+}
@@ -0,0 +1,192 @@
+//! The terminal's scrollback as styled text, to replay it when the terminal is restored.
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    grid::{Dimensions as _, Grid},
+    index::{Column, Line},
+    term::cell::{Cell, Flags},
+    vte::ansi::{Color, NamedColor},
+};
+
+use crate::terminal_images::IMAGE_PLACEHOLDER;
+
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+/// Serializes the lines above the cursor as text, with SGR sequences for their colors and styles.
+///
+/// Only the most recent lines fitting in `max_len` bytes are kept. Rows wrapped onto each other
+/// are kept together and joined, so that they rewrap to the width of the terminal replaying them.
+/// Images are left out, as their placeholders would refer to images that are gone.
+pub(crate) fn serialize_scrollback(grid: &Grid<Cell>, max_len: usize) -> String {
+    let topmost_line = grid.topmost_line().0;
+    let last_column = Column(grid.columns() - 1);
+    let mut lines = Vec::new();
+    let mut len = 0;
+    let mut end = grid.cursor.point.line.0;
+    while end > topmost_line {
+        let mut start = end - 1;
+        while start > topmost_line
+            && grid[Line(start - 1)][last_column]
+                .flags
+                .contains(Flags::WRAPLINE)
+        {
+            start -= 1;
+        }
+
+        let line = serialize_line(grid, start, end - 1);
+        len += line.len() + 1;
+        if len > max_len {
+            break;
+        }
+        lines.push(line);
+        end = start;
+    }
+
+    let mut scrollback = String::with_capacity(len);
+    for line in lines.iter().rev() {
+        scrollback.push_str(line);
+        scrollback.push('\n');
+    }
+    scrollback
+}
+
+/// Serializes the rows from `start` to `end`, wrapped onto each other, as one line. Each line
+/// resets its style at the end, so that they can be dropped from the top independently.
+fn serialize_line(grid: &Grid<Cell>, start: i32, end: i32) -> String {
+    let mut line = String::new();
+    let mut style = Style::default();
+    for line_index in start..=end {
+        let row = &grid[Line(line_index)];
+        let cells = &row[..Column(row.len())];
+        // The last row's trailing blanks would only pad the line, the wrapped rows' ones are text.
+        let len = if line_index == end {
+            cells
+                .iter()
+                .rposition(|cell| !Style::of(cell).is_blank(cell.c))
+                .map_or(0, |last| last + 1)
+        } else {
+            cells.len()
+        };
+
+        for cell in &cells[..len] {
+            if cell
+                .flags
+                .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+            {
+                continue;
+            }
+            let cell_style = Style::of(cell);
+            if cell_style != style {
+                cell_style.write_sgr(&mut line);
+                style = cell_style;
+            }
+            match cell.c {
+                // Tabs are recorded on the cell they start at, with the cells they skip holding spaces.
+                IMAGE_PLACEHOLDER | '\t' => line.push(' '),
+                c => {
+                    line.push(c);
+                    if let Some(zerowidth) = cell.zerowidth() {
+                        line.extend(zerowidth);
+                    }
+                }
+            }
+        }
+    }
+    if style != Style::default() {
+        line.push_str("\x1b[0m");
+    }
+    line
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Style {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl Style {
+    fn of(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags & STYLE_FLAGS,
+        }
+    }
+
+    fn is_blank(&self, c: char) -> bool {
+        c == ' '
+            && self.bg == Color::Named(NamedColor::Background)
+            && !self
+                .flags
+                .intersects(Flags::INVERSE | Flags::ALL_UNDERLINES | Flags::STRIKEOUT)
+    }
+
+    fn write_sgr(&self, output: &mut String) {
+        output.push_str("\x1b[0");
+        for (flag, parameter) in [
+            (Flags::BOLD, "1"),
+            (Flags::DIM, "2"),
+            (Flags::ITALIC, "3"),
+            (Flags::UNDERLINE, "4"),
+            (Flags::DOUBLE_UNDERLINE, "4:2"),
+            (Flags::UNDERCURL, "4:3"),
+            (Flags::DOTTED_UNDERLINE, "4:4"),
+            (Flags::DASHED_UNDERLINE, "4:5"),
+            (Flags::INVERSE, "7"),
+            (Flags::HIDDEN, "8"),
+            (Flags::STRIKEOUT, "9"),
+        ] {
+            if self.flags.contains(flag) {
+                output.push(';');
+                output.push_str(parameter);
+            }
+        }
+        write_color(output, self.fg, 30);
+        write_color(output, self.bg, 40);
+        output.push('m');
+    }
+}
+
+/// Writes the SGR parameters for a color, where `base` is the one for black: 30 for the
+/// foreground and 40 for the background.
+fn write_color(output: &mut String, color: Color, base: usize) {
+    match color {
+        Color::Named(named) => {
+            let index = named as usize;
+            let dim_black = NamedColor::DimBlack as usize;
+            let parameter = match index {
+                0..8 => base + index,
+                8..16 => base + 60 + index - 8,
+                // Dim colors come from the dim flag, which is set along with them.
+                _ if (dim_black..dim_black + 8).contains(&index) => base + index - dim_black,
+                // The default colors, which the reset at the start of the sequence sets.
+                _ => return,
+            };
+            write!(output, ";{parameter}").ok();
+        }
+        Color::Indexed(index) => {
+            write!(output, ";{};5;{index}", base + 8).ok();
+        }
+        Color::Spec(rgb) => {
+            write!(output, ";{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b).ok();
+        }
+    }
+}
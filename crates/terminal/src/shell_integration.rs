@@ -0,0 +1,630 @@
+//! Command boundaries marked by shells with the OSC 133 sequences of the FinalTerm protocol, and
+//! their OSC 633 variants emitted by VS Code's shell integration.
+//!
+//! Alacritty drops the OSC sequences it doesn't know, so the PTY output is scanned for the marks
+//! before it reaches the terminal's parser. The output after a mark is held back until the
+//! terminal placed it at its cursor, which is then where the mark was parsed.
+
+use std::{collections::VecDeque, path::Path};
+
+use alacritty_terminal::{grid::Dimensions as _, index::Line};
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+
+#[cfg(unix)]
+use crate::filtered_pty::Waker;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// Marks longer than this are not ours to parse.
+const MAX_MARK_LENGTH: usize = 256;
+
+/// The number of commands remembered per terminal.
+const MAX_SHELL_COMMANDS: usize = 10_000;
+
+/// A boundary of a command, marked by the shell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShellMark {
+    /// `A`: the prompt is about to be shown.
+    PromptStart,
+    /// `C`: the command line was submitted, the command's output follows.
+    CommandExecuted,
+    /// `D`: the command finished, with its exit code if the shell reported one.
+    CommandFinished { exit_code: Option<i32> },
+}
+
+impl ShellMark {
+    /// Parses the payload of an OSC 133 or 633 sequence: a letter, followed by `;`-separated
+    /// parameters.
+    fn parse(payload: &[u8]) -> Option<Self> {
+        let payload = std::str::from_utf8(payload).ok()?;
+        let mut params = payload.split(';');
+        match params.next()? {
+            "A" => Some(Self::PromptStart),
+            "C" => Some(Self::CommandExecuted),
+            "D" => Some(Self::CommandFinished {
+                exit_code: params.next().and_then(|code| code.parse().ok()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// What the terminal has to catch up with in the PTY output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ScannedEvent {
+    Mark(ShellMark),
+    /// The scrollback was erased, by `CSI 3 J` or a full reset.
+    Erased,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Csi,
+    OscCommand,
+    Osc,
+    Mark,
+    MarkEscape,
+}
+
+/// Finds the marks and the erasures of the scrollback in the PTY output, which may be split
+/// between reads at any byte.
+#[derive(Debug, Default)]
+pub(crate) struct MarkScanner {
+    state: ScanState,
+    buffer: Vec<u8>,
+    found_mark: bool,
+}
+
+impl MarkScanner {
+    /// Scans the output up to the end of the first mark in it, returning how much of it was
+    /// scanned.
+    pub fn scan(&mut self, bytes: &[u8], events: &mut Vec<ScannedEvent>) -> usize {
+        for (index, &byte) in bytes.iter().enumerate() {
+            self.state = match self.state {
+                ScanState::Ground => match byte {
+                    ESC => ScanState::Escape,
+                    _ => ScanState::Ground,
+                },
+                ScanState::Escape => self.escape(byte, events),
+                ScanState::Csi => match byte {
+                    0x20..=0x3f => {
+                        if self.buffer.len() < MAX_MARK_LENGTH {
+                            self.buffer.push(byte);
+                        }
+                        ScanState::Csi
+                    }
+                    ESC => ScanState::Escape,
+                    b'J' if self.buffer == b"3" => {
+                        events.push(ScannedEvent::Erased);
+                        ScanState::Ground
+                    }
+                    _ => ScanState::Ground,
+                },
+                ScanState::OscCommand => match byte {
+                    b'0'..=b'9' if self.buffer.len() < 3 => {
+                        self.buffer.push(byte);
+                        ScanState::OscCommand
+                    }
+                    b';' if matches!(self.buffer.as_slice(), b"133" | b"633") => {
+                        self.buffer.clear();
+                        ScanState::Mark
+                    }
+                    BEL => ScanState::Ground,
+                    ESC => ScanState::Escape,
+                    _ => ScanState::Osc,
+                },
+                ScanState::Osc => match byte {
+                    BEL => ScanState::Ground,
+                    ESC => ScanState::Escape,
+                    _ => ScanState::Osc,
+                },
+                ScanState::Mark => match byte {
+                    BEL => {
+                        self.finish_mark(events);
+                        ScanState::Ground
+                    }
+                    ESC => ScanState::MarkEscape,
+                    _ if self.buffer.len() < MAX_MARK_LENGTH => {
+                        self.buffer.push(byte);
+                        ScanState::Mark
+                    }
+                    _ => ScanState::Osc,
+                },
+                ScanState::MarkEscape if byte == b'\\' => {
+                    self.finish_mark(events);
+                    ScanState::Ground
+                }
+                ScanState::MarkEscape => self.escape(byte, events),
+            };
+            if std::mem::take(&mut self.found_mark) {
+                return index + 1;
+            }
+        }
+        bytes.len()
+    }
+
+    fn escape(&mut self, byte: u8, events: &mut Vec<ScannedEvent>) -> ScanState {
+        match byte {
+            b']' => {
+                self.buffer.clear();
+                ScanState::OscCommand
+            }
+            b'[' => {
+                self.buffer.clear();
+                ScanState::Csi
+            }
+            b'c' => {
+                events.push(ScannedEvent::Erased);
+                ScanState::Ground
+            }
+            ESC => ScanState::Escape,
+            _ => ScanState::Ground,
+        }
+    }
+
+    fn finish_mark(&mut self, events: &mut Vec<ScannedEvent>) {
+        if let Some(mark) = ShellMark::parse(&self.buffer) {
+            events.push(ScannedEvent::Mark(mark));
+            self.found_mark = true;
+        }
+        self.buffer.clear();
+    }
+}
+
+/// What was found in the PTY output, waiting for the terminal to catch up with it.
+#[derive(Default)]
+pub(crate) struct ScannedOutput {
+    pub events: Vec<ScannedEvent>,
+    /// Whether the output after a mark is held back until the terminal placed the mark, so that
+    /// its cursor is still where the mark was.
+    pub paused: bool,
+    /// Wakes the PTY's event loop up for the output left to pass on.
+    #[cfg(unix)]
+    pub waker: Option<Waker>,
+}
+
+impl ScannedOutput {
+    /// Whether the output can be held back after the marks, which needs the event loop to be
+    /// woken up once it's let through.
+    pub fn can_pause(&self) -> bool {
+        #[cfg(unix)]
+        return self.waker.is_some();
+        #[cfg(not(unix))]
+        return false;
+    }
+
+    /// Takes the events found so far, letting the output held back after them through.
+    pub fn take_events(&mut self) -> Vec<ScannedEvent> {
+        if std::mem::take(&mut self.paused) {
+            #[cfg(unix)]
+            if let Some(waker) = &mut self.waker {
+                waker.set_woken(true);
+            }
+        }
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// Where a marked command is at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// The prompt is shown and the command is being typed in.
+    Prompt,
+    Running,
+    /// The command finished, with its exit code if the shell reported one.
+    Finished {
+        exit_code: Option<i32>,
+    },
+}
+
+/// A command marked by the shell, located by the lines of the terminal's scrollback, counted from
+/// its top.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShellCommand {
+    pub prompt_line: usize,
+    /// The first line of the command's output, once the command runs.
+    pub output_start: Option<usize>,
+    /// The line after the command's output, once the command finished.
+    pub output_end: Option<usize>,
+    pub status: CommandStatus,
+}
+
+impl ShellCommand {
+    /// The lines of the command's output, once the command finished.
+    pub fn output_lines(&self) -> Option<std::ops::Range<usize>> {
+        Some(self.output_start?..self.output_end?)
+    }
+}
+
+/// A command's prompt shown in the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommandMark {
+    /// The line of the terminal's grid the prompt starts at.
+    pub line: Line,
+    pub status: CommandStatus,
+}
+
+/// The commands marked in a terminal, oldest first.
+#[derive(Debug, Default)]
+pub struct ShellCommands {
+    commands: VecDeque<ShellCommand>,
+}
+
+impl ShellCommands {
+    /// Records a mark placed at the given line, returning the command it finished, if any.
+    pub(crate) fn mark(
+        &mut self,
+        mark: ShellMark,
+        line: usize,
+        at_line_start: bool,
+    ) -> Option<ShellCommand> {
+        match mark {
+            ShellMark::PromptStart => {
+                let mut finished = None;
+                match self.commands.back_mut() {
+                    // The prompt is shown again without a command being run, as on Ctrl-C.
+                    Some(command) if command.status == CommandStatus::Prompt => {
+                        command.prompt_line = line;
+                        return None;
+                    }
+                    // Not every shell marks the ends of the commands, so the next prompt does.
+                    Some(command) if command.status == CommandStatus::Running => {
+                        command.output_end = Some(line);
+                        command.status = CommandStatus::Finished { exit_code: None };
+                        finished = Some(command.clone());
+                    }
+                    _ => {}
+                }
+                if self.commands.len() == MAX_SHELL_COMMANDS {
+                    self.commands.pop_front();
+                }
+                self.commands.push_back(ShellCommand {
+                    prompt_line: line,
+                    output_start: None,
+                    output_end: None,
+                    status: CommandStatus::Prompt,
+                });
+                finished
+            }
+            ShellMark::CommandExecuted => {
+                let command = self.commands.back_mut()?;
+                if command.status == CommandStatus::Prompt {
+                    command.output_start = Some(line.max(command.prompt_line));
+                    command.status = CommandStatus::Running;
+                }
+                None
+            }
+            ShellMark::CommandFinished { exit_code } => {
+                let command = self.commands.back_mut()?;
+                if command.status != CommandStatus::Running {
+                    return None;
+                }
+                // Output that doesn't end with a newline ends on the line of the mark.
+                let output_end = if at_line_start { line } else { line + 1 };
+                command.output_end = Some(output_end.max(command.output_start.unwrap_or(line)));
+                command.status = CommandStatus::Finished { exit_code };
+                Some(command.clone())
+            }
+        }
+    }
+
+    /// Moves the commands up as the given number of lines drop off the top of the scrollback,
+    /// forgetting the ones that dropped off entirely.
+    pub(crate) fn scroll_up(&mut self, lines: usize) {
+        if lines == 0 {
+            return;
+        }
+        self.commands.retain_mut(|command| {
+            let last_line = command
+                .output_end
+                .unwrap_or(command.prompt_line + 1)
+                .max(command.prompt_line + 1);
+            if last_line <= lines {
+                return false;
+            }
+            command.prompt_line = command.prompt_line.saturating_sub(lines);
+            command.output_start = command.output_start.map(|line| line.saturating_sub(lines));
+            command.output_end = command.output_end.map(|line| line.saturating_sub(lines));
+            true
+        });
+    }
+
+    /// Moves the commands down as lines are added above them.
+    pub(crate) fn scroll_down(&mut self, lines: usize) {
+        for command in &mut self.commands {
+            command.prompt_line += lines;
+            command.output_start = command.output_start.map(|line| line + lines);
+            command.output_end = command.output_end.map(|line| line + lines);
+        }
+    }
+
+    /// Forgets all the commands, as the lines they were on were erased.
+    pub(crate) fn forget(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Forgets the commands as the terminal is cleared, keeping the current one on the first line.
+    pub(crate) fn clear(&mut self) {
+        let current = self
+            .commands
+            .pop_back()
+            .filter(|command| !matches!(command.status, CommandStatus::Finished { .. }));
+        self.commands.clear();
+        if let Some(mut command) = current {
+            command.prompt_line = 0;
+            command.output_start = command.output_start.map(|_| 0);
+            self.commands.push_back(command);
+        }
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &ShellCommand> {
+        self.commands.iter()
+    }
+
+    /// The last command that finished.
+    pub fn last_finished(&self) -> Option<&ShellCommand> {
+        self.commands
+            .iter()
+            .rev()
+            .find(|command| matches!(command.status, CommandStatus::Finished { .. }))
+    }
+
+    /// The line of the last prompt above the given line.
+    pub fn previous_prompt(&self, line: usize) -> Option<usize> {
+        self.commands
+            .iter()
+            .rev()
+            .map(|command| command.prompt_line)
+            .find(|prompt_line| *prompt_line < line)
+    }
+
+    /// The line of the first prompt below the given line.
+    pub fn next_prompt(&self, line: usize) -> Option<usize> {
+        self.commands
+            .iter()
+            .map(|command| command.prompt_line)
+            .find(|prompt_line| *prompt_line > line)
+    }
+
+    /// The prompts shown in the terminal, given the size of its scrollback and how far it's
+    /// scrolled up.
+    pub(crate) fn marks_in_view(
+        &self,
+        history_size: usize,
+        display_offset: usize,
+        screen_lines: usize,
+    ) -> Vec<CommandMark> {
+        let top = history_size.saturating_sub(display_offset);
+        let bottom = top + screen_lines;
+        self.commands
+            .iter()
+            .filter(|command| (top..bottom).contains(&command.prompt_line))
+            .map(|command| CommandMark {
+                line: Line(command.prompt_line as i32 - history_size as i32),
+                status: command.status,
+            })
+            .collect()
+    }
+}
+
+/// The scrollback line the cursor of the terminal is on.
+pub(crate) fn cursor_line<T>(term: &alacritty_terminal::Term<T>) -> usize {
+    (term.history_size() as i32 + term.grid().cursor.point.line.0).max(0) as usize
+}
+
+const BASH_INTEGRATION: &str = include_str!("../shell_integration/zed.bash");
+const ZSH_INTEGRATION: &str = include_str!("../shell_integration/zed.zsh");
+const FISH_INTEGRATION: &str = include_str!("../shell_integration/zed.fish");
+
+/// The environment that makes bash, zsh and fish load Zed's shell integration from the given
+/// directory, writing the scripts there. Other shells are left as they are.
+///
+/// The environment is used rather than the arguments, so that login shells get integrated too.
+pub(crate) fn shell_integration_env(
+    program: &str,
+    env: &HashMap<String, String>,
+    dir: &Path,
+) -> Result<HashMap<String, String>> {
+    let inherited = |key: &str| {
+        env.get(key)
+            .cloned()
+            .or_else(|| std::env::var(key).ok())
+            .filter(|value| !value.is_empty())
+    };
+    let shell_name = Path::new(program)
+        .file_stem()
+        .and_then(|name| name.to_str())
+        .unwrap_or(program)
+        .trim_start_matches('-');
+
+    let mut integration_env = HashMap::default();
+    match shell_name {
+        // Bash can't be pointed at other startup files through the environment, so its first
+        // prompt command sources the script, which then restores the prompt command.
+        "bash" => {
+            let script = dir.join("zed.bash");
+            write_script(&script, BASH_INTEGRATION)?;
+            integration_env.insert(
+                "ZED_BASH_INTEGRATION".to_string(),
+                script.to_string_lossy().into_owned(),
+            );
+            let prompt_command = match inherited("PROMPT_COMMAND") {
+                Some(prompt_command) => format!(". \"$ZED_BASH_INTEGRATION\"\n{prompt_command}"),
+                None => ". \"$ZED_BASH_INTEGRATION\"".to_string(),
+            };
+            integration_env.insert("PROMPT_COMMAND".to_string(), prompt_command);
+        }
+        // Zsh reads its startup files from `ZDOTDIR`, the script restores it right away.
+        "zsh" => {
+            let zdotdir = dir.join("zsh");
+            write_script(&zdotdir.join(".zshenv"), ZSH_INTEGRATION)?;
+            if let Some(user_zdotdir) = inherited("ZDOTDIR") {
+                integration_env.insert("ZED_USER_ZDOTDIR".to_string(), user_zdotdir);
+            }
+            integration_env.insert(
+                "ZDOTDIR".to_string(),
+                zdotdir.to_string_lossy().into_owned(),
+            );
+        }
+        // Fish loads the vendor configuration in `XDG_DATA_DIRS`, the script restores them.
+        "fish" => {
+            let data_dir = dir.join("fish");
+            write_script(
+                &data_dir.join("fish/vendor_conf.d/zed.fish"),
+                FISH_INTEGRATION,
+            )?;
+            let user_data_dirs = inherited("XDG_DATA_DIRS");
+            let data_dirs = format!(
+                "{}:{}",
+                data_dir.to_string_lossy(),
+                user_data_dirs
+                    .as_deref()
+                    .unwrap_or("/usr/local/share:/usr/share")
+            );
+            if let Some(user_data_dirs) = user_data_dirs {
+                integration_env.insert("ZED_USER_XDG_DATA_DIRS".to_string(), user_data_dirs);
+            }
+            integration_env.insert("XDG_DATA_DIRS".to_string(), data_dirs);
+        }
+        _ => {}
+    }
+    Ok(integration_env)
+}
+
+fn write_script(path: &Path, contents: &str) -> Result<()> {
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating directory {parent:?}"))?;
+    }
+    std::fs::write(path, contents).with_context(|| format!("writing {path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scans all of the output, as the PTY passes it on a mark at a time.
+    fn scan(scanner: &mut MarkScanner, output: &str) -> Vec<(usize, ScannedEvent)> {
+        let mut output = output.as_bytes();
+        let mut scanned = Vec::new();
+        let mut events = Vec::new();
+        while !output.is_empty() {
+            let len = scanner.scan(output, &mut events);
+            scanned.extend(events.drain(..).map(|event| (len, event)));
+            output = &output[len..];
+        }
+        scanned
+    }
+
+    #[test]
+    fn test_scanning_marks() {
+        let mut scanner = MarkScanner::default();
+        let marks = scan(
+            &mut scanner,
+            "\x1b]133;A\x07\x1b[1;32m$\x1b[0m ls\r\n\x1b]133;C\x1b\\a.txt\r\nb.txt\r\n\x1b]2;title\x07\x1b]633;D;0\x07",
+        );
+        // The output is passed on up to the end of each mark.
+        assert_eq!(
+            marks,
+            vec![
+                (8, ScannedEvent::Mark(ShellMark::PromptStart)),
+                (26, ScannedEvent::Mark(ShellMark::CommandExecuted)),
+                (
+                    34,
+                    ScannedEvent::Mark(ShellMark::CommandFinished { exit_code: Some(0) })
+                ),
+            ]
+        );
+
+        // Marks split between reads, and ones that aren't ours.
+        assert_eq!(scan(&mut scanner, "partial\x1b]13"), Vec::new());
+        assert_eq!(scan(&mut scanner, "3;D;127\x1b"), Vec::new());
+        assert_eq!(
+            scan(&mut scanner, "\\\x1b]1337;A\x07\x1b]133;Z\x07"),
+            vec![(
+                1,
+                ScannedEvent::Mark(ShellMark::CommandFinished {
+                    exit_code: Some(127)
+                })
+            )]
+        );
+
+        // Erasing the scrollback, as `clear` does, and resetting the terminal.
+        assert_eq!(
+            scan(&mut scanner, "\x1b[H\x1b[2J\x1b[3J$ \x1bc"),
+            vec![(15, ScannedEvent::Erased), (15, ScannedEvent::Erased)]
+        );
+    }
+
+    #[test]
+    fn test_tracking_commands() {
+        let mut commands = ShellCommands::default();
+        assert_eq!(commands.mark(ShellMark::PromptStart, 0, true), None);
+        // Ctrl-C at the prompt shows it again.
+        assert_eq!(commands.mark(ShellMark::PromptStart, 1, true), None);
+        assert_eq!(commands.mark(ShellMark::CommandExecuted, 2, true), None);
+        let finished = commands
+            .mark(ShellMark::CommandFinished { exit_code: Some(1) }, 5, false)
+            .unwrap();
+        assert_eq!(finished.output_lines(), Some(2..6));
+        assert_eq!(
+            finished.status,
+            CommandStatus::Finished { exit_code: Some(1) }
+        );
+
+        // Without the command's end marked, the next prompt finishes it.
+        commands.mark(ShellMark::PromptStart, 6, true);
+        commands.mark(ShellMark::CommandExecuted, 7, true);
+        let finished = commands.mark(ShellMark::PromptStart, 9, true).unwrap();
+        assert_eq!(finished.output_lines(), Some(7..9));
+        assert_eq!(finished.status, CommandStatus::Finished { exit_code: None });
+
+        assert_eq!(
+            commands
+                .iter()
+                .map(|command| command.prompt_line)
+                .collect::<Vec<_>>(),
+            vec![1, 6, 9]
+        );
+        assert_eq!(commands.previous_prompt(6), Some(1));
+        assert_eq!(commands.next_prompt(6), Some(9));
+        assert_eq!(commands.next_prompt(9), None);
+        assert_eq!(
+            commands.marks_in_view(4, 0, 6),
+            vec![
+                CommandMark {
+                    line: Line(2),
+                    status: CommandStatus::Finished { exit_code: None },
+                },
+                CommandMark {
+                    line: Line(5),
+                    status: CommandStatus::Prompt,
+                },
+            ]
+        );
+
+        commands.scroll_up(6);
+        assert_eq!(
+            commands
+                .iter()
+                .map(|command| command.prompt_line)
+                .collect::<Vec<_>>(),
+            vec![0, 3]
+        );
+        commands.scroll_down(2);
+        assert_eq!(commands.previous_prompt(5), Some(2));
+        commands.clear();
+        assert_eq!(commands.iter().count(), 1);
+        assert_eq!(commands.last_finished(), None);
+        commands.forget();
+        assert_eq!(commands.iter().count(), 0);
+    }
+}
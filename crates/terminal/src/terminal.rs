@@ -1,9 +1,14 @@
+pub mod asciicast;
 pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod filtered_pty;
 mod pty_info;
+mod scrollback;
+mod shell_integration;
 mod terminal_hyperlinks;
+mod terminal_images;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -26,6 +31,7 @@ use alacritty_terminal::{
     },
 };
 use anyhow::{Context as _, Result, bail};
+use asciicast::{Recorder, RecordingWriter, SharedRecorder};
 use log::trace;
 
 use futures::{
@@ -40,17 +46,21 @@ use mappings::mouse::{
 };
 
 use collections::{HashMap, VecDeque};
+use filtered_pty::FilteredPty;
 use futures::StreamExt;
+use parking_lot::Mutex;
 use pty_info::{ProcessIdGetter, PtyProcessInfo};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{ScannedEvent, ScannedOutput};
 use smol::channel::{Receiver, Sender};
 use task::{HideStrategy, Shell, SpawnInTerminal};
 use terminal_hyperlinks::RegexSearches;
+use terminal_images::TerminalImages;
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
 use urlencoding;
-use util::{paths::PathStyle, truncate_and_trailoff};
+use util::{ResultExt as _, paths::PathStyle, truncate_and_trailoff};
 
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
@@ -73,6 +83,8 @@ use gpui::{
 };
 
 use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
+pub use crate::shell_integration::{CommandMark, CommandStatus, ShellCommand, ShellCommands};
+pub use crate::terminal_images::{IMAGE_PLACEHOLDER, ImageCell, TerminalImage};
 
 actions!(
     terminal,
@@ -107,6 +119,16 @@ actions!(
         ToggleViMode,
         /// Selects all text in the terminal.
         SelectAll,
+        /// Scrolls up to the prompt of the previous command marked by the shell.
+        ScrollToPreviousCommand,
+        /// Scrolls down to the prompt of the next command marked by the shell.
+        ScrollToNextCommand,
+        /// Selects the output of the last command marked by the shell.
+        SelectLastCommandOutput,
+        /// Copies the output of the last command marked by the shell to the clipboard.
+        CopyLastCommandOutput,
+        /// Starts recording the terminal to an asciicast file, or stops the recording in progress.
+        ToggleRecording,
     ]
 );
 
@@ -140,6 +162,10 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// A command marked by the shell finished.
+    CommandFinished {
+        exit_code: Option<i32>,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -178,6 +204,11 @@ enum InternalEvent {
     ToggleViMode,
     ViMotion(ViMotion),
     MoveViCursorToAlacPoint(AlacPoint),
+    // Shell integration events
+    ScrollToPreviousCommand,
+    ScrollToNextCommand,
+    SelectLastCommandOutput,
+    CopyLastCommandOutput,
 }
 
 ///A translation struct for Alacritty to communicate with us from their event loop
@@ -335,6 +366,9 @@ impl Display for TerminalError {
 
 // https://github.com/alacritty/alacritty/blob/cb3a79dbf6472740daca8440d5166c1d4af5029e/extra/man/alacritty.5.scd?plain=1#L207-L213
 const DEFAULT_SCROLL_HISTORY_LINES: usize = 10_000;
+/// How far the scrollback of a shell may grow past its limit before it's trimmed back, so that the
+/// lines dropping off its top can be counted to move the marked commands along with it.
+const SCROLLBACK_SLACK_LINES: usize = 1_000;
 pub const MAX_SCROLL_HISTORY_LINES: usize = 100_000;
 
 pub struct TerminalBuilder {
@@ -415,6 +449,13 @@ impl TerminalBuilder {
             event_loop_task: Task::ready(Ok(())),
             background_executor: background_executor.clone(),
             path_style,
+            scanned_output: None,
+            history_size: 0,
+            shell_commands: ShellCommands::default(),
+            images: None,
+            recorder: None,
+            replay_processor: ReplayProcessor::new(),
+            fixed_size: None,
         };
 
         Ok(TerminalBuilder {
@@ -439,8 +480,10 @@ impl TerminalBuilder {
         cx: &App,
         activation_script: Vec<String>,
         path_style: PathStyle,
+        restored_output: Option<String>,
     ) -> Task<Result<TerminalBuilder>> {
         let version = release_channel::AppVersion::global(cx);
+        let integrate_shell = TerminalSettings::get_global(cx).shell_integration;
         let background_executor = cx.background_executor().clone();
         let fut = async move {
             // Remove SHLVL so the spawned shell initializes it to 1, matching
@@ -518,6 +561,23 @@ impl TerminalBuilder {
             // supported remoting into windows.
             let shell_kind = shell.shell_kind(cfg!(windows));
 
+            // Only interactive shells spawned on this machine are integrated, the environment they
+            // get it through is not to be passed on to other terminals.
+            let mut pty_env = env.clone();
+            if integrate_shell && task.is_none() && !is_remote_terminal && cfg!(unix) {
+                let (program, args) = shell.program_and_args();
+                if args.is_empty() {
+                    match shell_integration::shell_integration_env(
+                        &program,
+                        &env,
+                        &paths::data_dir().join("shell_integration"),
+                    ) {
+                        Ok(integration_env) => pty_env.extend(integration_env),
+                        Err(error) => log::error!("Failed to set up shell integration: {error:#}"),
+                    }
+                }
+            }
+
             let pty_options = {
                 let alac_shell = shell_params.as_ref().map(|params| {
                     alacritty_terminal::tty::Shell::new(
@@ -530,7 +590,7 @@ impl TerminalBuilder {
                     shell: alac_shell,
                     working_directory: working_directory.clone(),
                     drain_on_exit: true,
-                    env: env.clone().into_iter().collect(),
+                    env: pty_env.into_iter().collect(),
                     #[cfg(windows)]
                     escape_args: shell_kind.tty_escape_args(),
                 }
@@ -548,7 +608,7 @@ impl TerminalBuilder {
                     .min(MAX_SCROLL_HISTORY_LINES)
             };
             let config = Config {
-                scrolling_history,
+                scrolling_history: scrolling_history + SCROLLBACK_SLACK_LINES,
                 default_cursor_style,
                 ..Config::default()
             };
@@ -582,9 +642,18 @@ impl TerminalBuilder {
                 term.unset_private_mode(PrivateMode::Named(NamedPrivateMode::AlternateScroll));
             }
 
+            // The restored output is only displayed, never sent to the shell. It's parsed before
+            // the event loop starts, to land above the shell's first output.
+            if let Some(output) = restored_output {
+                ReplayProcessor::new().advance(&mut term, &with_crlf(output.as_bytes()));
+            }
+
             let term = Arc::new(FairMutex::new(term));
 
             let pty_info = PtyProcessInfo::new(&pty);
+            let recorder = SharedRecorder::default();
+            let (pty, scanned_output, images) =
+                FilteredPty::new(pty, recorder.clone(), ZedListener(events_tx.clone()));
 
             //And connect them together
             let event_loop = EventLoop::new(
@@ -646,6 +715,13 @@ impl TerminalBuilder {
                 event_loop_task: Task::ready(Ok(())),
                 background_executor,
                 path_style,
+                scanned_output: Some(scanned_output),
+                history_size: 0,
+                shell_commands: ShellCommands::default(),
+                images: Some(images),
+                recorder: Some(recorder),
+                replay_processor: ReplayProcessor::new(),
+                fixed_size: None,
             };
 
             if !activation_script.is_empty() && no_task {
@@ -794,6 +870,10 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    /// The prompts of the commands marked by the shell that are in view.
+    pub command_marks: Vec<CommandMark>,
+    /// The images shown over the cells in view, by their ids.
+    pub images: HashMap<u32, TerminalImage>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -820,6 +900,8 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            command_marks: Vec::new(),
+            images: HashMap::default(),
         }
     }
 }
@@ -870,8 +952,44 @@ pub struct Terminal {
     event_loop_task: Task<Result<(), anyhow::Error>>,
     background_executor: BackgroundExecutor,
     path_style: PathStyle,
+    /// The marks found in the PTY output, for local terminals.
+    scanned_output: Option<Arc<Mutex<ScannedOutput>>>,
+    /// The size of the scrollback when the marked commands were last moved along with it.
+    history_size: usize,
+    shell_commands: ShellCommands,
+    images: Option<Arc<Mutex<TerminalImages>>>,
+    /// Where the PTY output is recorded to, for local terminals.
+    recorder: Option<SharedRecorder>,
+    /// Parses replayed output, keeping escape sequences split across replays.
+    replay_processor: ReplayProcessor,
+    /// The columns and lines the grid keeps regardless of the terminal's bounds.
+    fixed_size: Option<(usize, usize)>,
+}
+
+/// Converts LF to CRLF, to get the expected line wrapping in Alacritty.
+///
+/// When output comes from piped commands (not a PTY) such as codex-acp, and that
+/// output only contains LF (\n) without a CR (\r) after it, such as the output
+/// of the `ls` command when running outside a PTY, Alacritty moves the cursor
+/// cursor down a line but does not move it back to the initial column. This makes
+/// the rendered output look ridiculous. To prevent this, we insert a CR (\r) before
+/// each LF that didn't already have one. (Alacritty doesn't have a setting for this.)
+fn with_crlf(bytes: &[u8]) -> Vec<u8> {
+    let mut converted = Vec::with_capacity(bytes.len());
+    let mut prev_byte = 0u8;
+    for &byte in bytes {
+        if byte == b'\n' && prev_byte != b'\r' {
+            converted.push(b'\r');
+        }
+        converted.push(byte);
+        prev_byte = byte;
+    }
+    converted
 }
 
+type ReplayProcessor =
+    alacritty_terminal::vte::ansi::Processor<alacritty_terminal::vte::ansi::StdSyncHandler>;
+
 struct CopyTemplate {
     shell: Shell,
     env: HashMap<String, String>,
@@ -973,6 +1091,7 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                self.place_scanned_marks(cx);
                 cx.emit(Event::Wakeup);
 
                 if let TerminalType::Pty { info, .. } = &mut self.terminal_type {
@@ -1024,7 +1143,9 @@ impl Terminal {
                     pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
                 }
 
+                let cursor_line = shell_integration::cursor_line(&*term);
                 term.resize(new_bounds);
+                self.follow_reflow(term, cursor_line);
                 // If there are matches we need to emit a wake up event to
                 // invalidate the matches and recalculate their locations
                 // in the new terminal layout
@@ -1034,6 +1155,7 @@ impl Terminal {
             }
             InternalEvent::Clear => {
                 trace!("Clearing");
+                self.shell_commands.clear();
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -1062,6 +1184,7 @@ impl Terminal {
                 if (new_cursor.line.0 as usize) < term.screen_lines() - 1 {
                     term.grid_mut().reset_region((new_cursor.line + 1)..);
                 }
+                self.history_size = term.history_size();
 
                 cx.emit(Event::Wakeup);
             }
@@ -1202,7 +1325,143 @@ impl Terminal {
             InternalEvent::ProcessHyperlink(hyperlink, open) => {
                 self.process_hyperlink(hyperlink.clone(), *open, cx);
             }
+            InternalEvent::ScrollToPreviousCommand | InternalEvent::ScrollToNextCommand => {
+                trace!("Scrolling to command");
+                let history_size = term.history_size();
+                let top_line = history_size - term.grid().display_offset();
+                let scroll = if matches!(event, InternalEvent::ScrollToPreviousCommand) {
+                    self.shell_commands.previous_prompt(top_line)
+                } else {
+                    self.shell_commands.next_prompt(top_line)
+                }
+                .map(|prompt_line| AlacScroll::Delta(top_line as i32 - prompt_line as i32));
+                let Some(scroll) = scroll.or_else(|| {
+                    matches!(event, InternalEvent::ScrollToNextCommand)
+                        .then_some(AlacScroll::Bottom)
+                }) else {
+                    return;
+                };
+                self.process_terminal_event(&InternalEvent::Scroll(scroll), term, window, cx);
+            }
+            InternalEvent::SelectLastCommandOutput => {
+                trace!("Selecting last command output");
+                if let Some(output) = self.last_command_output(term) {
+                    let selection = Some((make_selection(&output), *output.end()));
+                    self.process_terminal_event(
+                        &InternalEvent::SetSelection(selection),
+                        term,
+                        window,
+                        cx,
+                    );
+                }
+            }
+            InternalEvent::CopyLastCommandOutput => {
+                trace!("Copying last command output");
+                if let Some(output) = self.last_command_output(term) {
+                    let text = term.bounds_to_string(*output.start(), *output.end());
+                    cx.write_to_clipboard(ClipboardItem::new_string(text));
+                }
+            }
+        }
+    }
+
+    /// Places the marks found in the PTY output since they were last placed at the cursor, where
+    /// the terminal parsed them.
+    ///
+    /// The fair lock is only taken once the event loop parsed all it read, and the output after a
+    /// mark is held back until the mark is taken, so the cursor is still where the mark was.
+    fn place_scanned_marks(&mut self, cx: &mut Context<Self>) {
+        let Some(scanned_output) = &self.scanned_output else {
+            return;
+        };
+        let term = self.term.clone();
+        let mut term = term.lock();
+        let events = scanned_output.lock().take_events();
+        // The commands are on the main screen, which the scrollback belongs to.
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        self.follow_scrollback(&mut term);
+
+        for event in events {
+            let mark = match event {
+                ScannedEvent::Mark(mark) => mark,
+                ScannedEvent::Erased => {
+                    self.shell_commands.forget();
+                    continue;
+                }
+            };
+            let cursor = &term.grid().cursor;
+            let at_line_start = cursor.point.column.0 == 0 && !cursor.input_needs_wrap;
+            let line = shell_integration::cursor_line(&*term);
+            if let Some(command) = self.shell_commands.mark(mark, line, at_line_start)
+                && let CommandStatus::Finished { exit_code } = command.status
+            {
+                cx.emit(Event::CommandFinished { exit_code });
+            }
+        }
+    }
+
+    /// Moves the marked commands up along with the lines dropping off the top of the scrollback.
+    ///
+    /// The scrollback of a shell may grow past its limit by some slack, and is trimmed back here,
+    /// so that the lines dropping off its top are told by its size. Once the slack is used up,
+    /// they can't be counted anymore, and the commands are forgotten.
+    fn follow_scrollback(&mut self, term: &mut Term<ZedListener>) {
+        let history_size = term.history_size();
+        if history_size < self.history_size {
+            self.shell_commands
+                .scroll_up(self.history_size - history_size);
+        } else if history_size == self.term_config.scrolling_history {
+            self.shell_commands.forget();
+        }
+
+        let limit = self
+            .term_config
+            .scrolling_history
+            .saturating_sub(SCROLLBACK_SLACK_LINES);
+        if history_size > limit + SCROLLBACK_SLACK_LINES / 2 {
+            let mut trimmed = self.term_config.clone();
+            trimmed.scrolling_history = limit;
+            term.set_options(trimmed);
+            term.set_options(self.term_config.clone());
+            self.shell_commands
+                .scroll_up(history_size - term.history_size());
+        }
+        self.history_size = term.history_size();
+    }
+
+    /// Keeps the marked commands as far above the cursor as they were before the terminal was
+    /// resized, as its lines were reflowed.
+    fn follow_reflow(&mut self, term: &Term<ZedListener>, cursor_line: usize) {
+        if self.shell_commands.iter().next().is_none() || term.mode().contains(TermMode::ALT_SCREEN)
+        {
+            return;
+        }
+        let new_cursor_line = shell_integration::cursor_line(term);
+        if new_cursor_line < cursor_line {
+            self.shell_commands.scroll_up(cursor_line - new_cursor_line);
+        } else {
+            self.shell_commands
+                .scroll_down(new_cursor_line - cursor_line);
+        }
+        self.history_size = term.history_size();
+    }
+
+    /// The bounds of the output of the last finished command, if it's still in the scrollback.
+    fn last_command_output(&self, term: &Term<ZedListener>) -> Option<RangeInclusive<AlacPoint>> {
+        let output = self.shell_commands.last_finished()?.output_lines()?;
+        if output.is_empty() {
+            return None;
         }
+        let history_size = term.history_size() as i32;
+        let start = AlacPoint::new(Line(output.start as i32 - history_size), Column(0));
+        let end = AlacPoint::new(
+            Line(output.end as i32 - 1 - history_size),
+            term.last_column(),
+        );
+        (start.line >= term.topmost_line() && end.line <= term.bottommost_line())
+            .then_some(start..=end)
     }
 
     fn process_hyperlink(
@@ -1288,34 +1547,103 @@ impl Terminal {
     pub fn write_output(&mut self, bytes: &[u8], cx: &mut Context<Self>) {
         // Inject bytes directly into the terminal emulator and refresh the UI.
         // This bypasses the PTY/event loop for display-only terminals.
-        //
-        // We first convert LF to CRLF, to get the expected line wrapping in Alacritty.
-        // When output comes from piped commands (not a PTY) such as codex-acp, and that
-        // output only contains LF (\n) without a CR (\r) after it, such as the output
-        // of the `ls` command when running outside a PTY, Alacritty moves the cursor
-        // cursor down a line but does not move it back to the initial column. This makes
-        // the rendered output look ridiculous. To prevent this, we insert a CR (\r) before
-        // each LF that didn't already have one. (Alacritty doesn't have a setting for this.)
-        let mut converted = Vec::with_capacity(bytes.len());
-        let mut prev_byte = 0u8;
-        for &byte in bytes {
-            if byte == b'\n' && prev_byte != b'\r' {
-                converted.push(b'\r');
-            }
-            converted.push(byte);
-            prev_byte = byte;
-        }
-
-        let mut processor = alacritty_terminal::vte::ansi::Processor::<
-            alacritty_terminal::vte::ansi::StdSyncHandler,
-        >::new();
+        self.replay_output(&with_crlf(bytes), cx);
+    }
+
+    /// Injects bytes into the terminal emulator as they are, like output that came from a PTY.
+    pub fn replay_output(&mut self, bytes: &[u8], cx: &mut Context<Self>) {
         {
             let mut term = self.term.lock();
-            processor.advance(&mut *term, &converted);
+            self.replay_processor.advance(&mut *term, bytes);
         }
         cx.emit(Event::Wakeup);
     }
 
+    /// Resets the terminal emulator to its initial state, dropping any escape sequence the
+    /// replayed output left unfinished.
+    pub fn reset_replay(&mut self, cx: &mut Context<Self>) {
+        self.replay_processor = ReplayProcessor::new();
+        self.replay_output(b"\x1bc", cx);
+    }
+
+    /// Starts recording the PTY output to `file` in the asciicast format, replacing any
+    /// recording in progress.
+    pub fn start_recording(&mut self, file: std::fs::File) -> Result<()> {
+        let recorder = self
+            .recorder
+            .as_ref()
+            .context("only terminals running a shell can be recorded")?;
+        let bounds = self.last_content.terminal_bounds;
+        let env = HashMap::from_iter([("TERM".to_string(), "xterm-256color".to_string())]);
+        let new_recorder = Recorder::new(
+            RecordingWriter::new(file)?,
+            bounds.num_columns(),
+            bounds.num_lines(),
+            Some(self.title(false)),
+            env,
+        )?;
+        if let Some(previous) = recorder.lock().replace(new_recorder) {
+            self.background_executor
+                .spawn(async move {
+                    if let Err(error) = previous.finish().and_then(RecordingWriter::close) {
+                        log::error!("Failed to save the terminal recording: {error}");
+                    }
+                })
+                .detach();
+        }
+        Ok(())
+    }
+
+    /// Stops the recording in progress, resolving once all of it was written.
+    pub fn stop_recording(&mut self) -> Task<Result<()>> {
+        let recorder = self
+            .recorder
+            .as_ref()
+            .and_then(|recorder| recorder.lock().take());
+        self.background_executor.spawn(async move {
+            if let Some(recorder) = recorder {
+                recorder.finish()?.close()?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn can_record(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder
+            .as_ref()
+            .is_some_and(|recorder| recorder.lock().is_some())
+    }
+
+    /// Keeps the grid at the given columns and lines however big the terminal's bounds are, or
+    /// lets it follow the bounds again when `None`.
+    pub fn set_fixed_size(&mut self, size: Option<(usize, usize)>) {
+        self.fixed_size = size;
+        // The grid is resized right away rather than on the next sync, for the output written
+        // after it to fill its new size.
+        let new_bounds = self.fixed_bounds(self.last_content.terminal_bounds);
+        if self.last_content.terminal_bounds != new_bounds {
+            self.last_content.terminal_bounds = new_bounds;
+            if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
+                pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
+            }
+            self.term.lock().resize(new_bounds);
+        }
+    }
+
+    fn fixed_bounds(&self, mut bounds: TerminalBounds) -> TerminalBounds {
+        if let Some((columns, lines)) = self.fixed_size {
+            bounds.bounds.size = Size {
+                width: bounds.cell_width * columns as f32,
+                height: bounds.line_height * lines as f32,
+            };
+        }
+        bounds
+    }
+
     pub fn total_lines(&self) -> usize {
         self.term.lock_unfair().total_lines()
     }
@@ -1417,6 +1745,37 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    pub fn scroll_to_previous_command(&mut self) {
+        self.events
+            .push_back(InternalEvent::ScrollToPreviousCommand);
+    }
+
+    pub fn scroll_to_next_command(&mut self) {
+        self.events.push_back(InternalEvent::ScrollToNextCommand);
+    }
+
+    pub fn select_last_command_output(&mut self) {
+        self.events
+            .push_back(InternalEvent::SelectLastCommandOutput);
+    }
+
+    pub fn copy_last_command_output(&mut self) {
+        self.events.push_back(InternalEvent::CopyLastCommandOutput);
+    }
+
+    /// The commands marked by the shell running in the terminal.
+    pub fn shell_commands(&self) -> &ShellCommands {
+        &self.shell_commands
+    }
+
+    /// The exit code of the last command marked by the shell, if it reported one.
+    pub fn last_command_exit_code(&self) -> Option<i32> {
+        match self.shell_commands.last_finished()?.status {
+            CommandStatus::Finished { exit_code } => exit_code,
+            CommandStatus::Prompt | CommandStatus::Running => None,
+        }
+    }
+
     pub fn scrolled_to_top(&self) -> bool {
         self.last_content.scrolled_to_top
     }
@@ -1427,6 +1786,7 @@ impl Terminal {
 
     ///Resize the terminal and the PTY.
     pub fn set_size(&mut self, new_bounds: TerminalBounds) {
+        let new_bounds = self.fixed_bounds(new_bounds);
         if self.last_content.terminal_bounds != new_bounds {
             self.events.push_back(InternalEvent::Resize(new_bounds))
         }
@@ -1605,6 +1965,26 @@ impl Terminal {
         }
 
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content.command_marks = self.shell_commands.marks_in_view(
+            terminal.history_size(),
+            self.last_content.display_offset,
+            terminal.screen_lines(),
+        );
+        if let Some(images) = &self.images {
+            let mut images = images.lock();
+            for image in images.take_evicted() {
+                window.drop_image(image).log_err();
+            }
+            self.last_content.images = self
+                .last_content
+                .cells
+                .iter()
+                .filter_map(|cell| {
+                    let image_id = ImageCell::from_cell(cell)?.image_id;
+                    Some((image_id, images.get(image_id)?.clone()))
+                })
+                .collect();
+        }
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1637,6 +2017,8 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            command_marks: Vec::new(),
+            images: HashMap::default(),
         }
     }
 
@@ -1647,6 +2029,12 @@ impl Terminal {
         term.bounds_to_string(start, end)
     }
 
+    /// The lines above the cursor, with the colors and styles of their text, keeping the most
+    /// recent ones that fit in `max_len` bytes. Passing them to [`Self::write_output`] replays them.
+    pub fn serialize_scrollback(&self, max_len: usize) -> String {
+        scrollback::serialize_scrollback(self.term.lock_unfair().grid(), max_len)
+    }
+
     pub fn last_n_non_empty_lines(&self, n: usize) -> Vec<String> {
         let term = self.term.clone();
         let terminal = term.lock_unfair();
@@ -2294,6 +2682,7 @@ impl Terminal {
             cx,
             self.activation_script.clone(),
             self.path_style,
+            None,
         )
     }
 }
@@ -2554,6 +2943,15 @@ mod tests {
         cx: &mut TestAppContext,
         command: &str,
         args: &[&str],
+    ) -> (Entity<Terminal>, Receiver<Option<ExitStatus>>) {
+        build_restored_test_terminal(cx, command, args, None).await
+    }
+
+    async fn build_restored_test_terminal(
+        cx: &mut TestAppContext,
+        command: &str,
+        args: &[&str],
+        restored_output: Option<String>,
     ) -> (Entity<Terminal>, Receiver<Option<ExitStatus>>) {
         let (completion_tx, completion_rx) = smol::channel::unbounded();
         let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
@@ -2581,6 +2979,7 @@ mod tests {
                     cx,
                     vec![],
                     PathStyle::local(),
+                    restored_output,
                 )
             })
             .await
@@ -2700,6 +3099,23 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_restored_output_precedes_shell_output(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let (terminal, completion_rx) =
+            build_restored_test_terminal(cx, "echo", &["hello"], Some("restored\n".to_string()))
+                .await;
+        assert_eq!(
+            completion_rx.recv().await.unwrap(),
+            Some(ExitStatus::default())
+        );
+        assert_eq!(
+            terminal.update(cx, |term, _| term.get_content()).trim(),
+            "restored\nhello"
+        );
+    }
+
     // TODO should be tested on Linux too, but does not work there well
     #[cfg(target_os = "macos")]
     #[gpui::test(iterations = 10)]
@@ -2727,6 +3143,7 @@ mod tests {
                     cx,
                     Vec::new(),
                     PathStyle::local(),
+                    None,
                 )
             })
             .await
@@ -2803,6 +3220,7 @@ mod tests {
                     cx,
                     Vec::new(),
                     PathStyle::local(),
+                    None,
                 )
             })
             .await
@@ -3113,6 +3531,84 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_serialize_scrollback(cx: &mut TestAppContext) {
+        let new_terminal = |cx: &mut TestAppContext| {
+            cx.new(|cx| {
+                TerminalBuilder::new_display_only(
+                    CursorShape::default(),
+                    AlternateScroll::On,
+                    None,
+                    0,
+                    cx.background_executor(),
+                    PathStyle::local(),
+                )
+                .unwrap()
+                .subscribe(cx)
+            })
+        };
+        let long_line = "x".repeat(150);
+        let output = format!(
+            "plain\n\x1b[1;31mbold red\x1b[0m and \x1b[38;2;1;2;3;48;5;100mcolored\x1b[0m\n{long_line}\n$ "
+        );
+
+        let terminal = new_terminal(cx);
+        let scrollback = terminal.update(cx, |terminal, cx| {
+            terminal.write_output(output.as_bytes(), cx);
+            terminal.serialize_scrollback(usize::MAX)
+        });
+        // The prompt on the cursor's line is left out, and the wrapped line is joined back.
+        let expected = format!(
+            "plain\n\x1b[0;1;31mbold red\x1b[0m and \x1b[0;38;2;1;2;3;48;5;100mcolored\x1b[0m\n{long_line}\n"
+        );
+        assert_eq!(scrollback, expected);
+
+        let replayed = new_terminal(cx);
+        let replayed_scrollback = replayed.update(cx, |terminal, cx| {
+            terminal.write_output(scrollback.as_bytes(), cx);
+            terminal.serialize_scrollback(usize::MAX)
+        });
+        assert_eq!(replayed_scrollback, expected);
+
+        // Only whole lines are kept, starting from the most recent ones.
+        let truncated = terminal.update(cx, |terminal, _| {
+            terminal.serialize_scrollback(long_line.len() + 10)
+        });
+        assert_eq!(truncated, format!("{long_line}\n"));
+    }
+
+    #[gpui::test]
+    async fn test_replay_output_split_escape_sequence(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                None,
+                0,
+                cx.background_executor(),
+                PathStyle::local(),
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+
+        let scrollback = terminal.update(cx, |terminal, cx| {
+            terminal.replay_output(b"\x1b[1;3", cx);
+            terminal.replay_output(b"1mred\x1b[0m\r\n$ ", cx);
+            terminal.serialize_scrollback(usize::MAX)
+        });
+        assert_eq!(scrollback, "\x1b[0;1;31mred\x1b[0m\n");
+
+        // A reset drops the sequence left unfinished before it.
+        let scrollback = terminal.update(cx, |terminal, cx| {
+            terminal.replay_output(b"\x1b[3", cx);
+            terminal.reset_replay(cx);
+            terminal.replay_output(b"1mplain\r\n$ ", cx);
+            terminal.serialize_scrollback(usize::MAX)
+        });
+        assert_eq!(scrollback, "1mplain\n");
+    }
+
     #[gpui::test]
     async fn test_hyperlink_ctrl_click_same_position(cx: &mut TestAppContext) {
         let terminal = init_ctrl_click_hyperlink_test(cx, b"Visit https://zed.dev/ for more\r\n");
@@ -3291,6 +3787,7 @@ mod tests {
                         cx,
                         vec![],
                         PathStyle::local(),
+                        None,
                     )
                 })
                 .await
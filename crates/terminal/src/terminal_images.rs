@@ -0,0 +1,1609 @@
+//! Inline images, printed with the Sixel, iTerm2 and Kitty graphics protocols.
+//!
+//! Alacritty drops the image sequences, so they're taken out of the PTY output before it reaches
+//! the terminal's parser. Each image is replaced with placeholder characters covering the cells it
+//! is shown over, which the terminal treats as any other text: the images scroll, get overwritten
+//! and get cleared along with the rest of the grid, and the terminal element paints them over the
+//! cells holding their placeholders. Only the sizes of the images are read as they're taken out,
+//! decoding them is left to a thread of its own so that big images don't hold up the output.
+
+use std::{
+    collections::VecDeque,
+    io::{Cursor, Read as _, Write as _},
+    sync::{Arc, OnceLock, mpsc},
+    thread,
+};
+
+use alacritty_terminal::{event::WindowSize, term::cell::Cell};
+use anyhow::{Context as _, Result, anyhow, bail};
+use base64::{
+    Engine as _, alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use collections::HashMap;
+use flate2::read::ZlibDecoder;
+use gpui::RenderImage;
+use image::{Frame, ImageFormat, ImageReader, RgbaImage};
+use itertools::Itertools as _;
+use parking_lot::Mutex;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// The character filling the cells an image is shown over, the same one Kitty uses for its
+/// Unicode placeholders.
+pub const IMAGE_PLACEHOLDER: char = '\u{10EEEE}';
+
+/// The placeholders are followed by combining diacritics, which alacritty keeps with the cell,
+/// encoding the image and the cell of it as two digits each.
+const FIRST_DIACRITIC: u32 = 0x0300;
+const DIACRITIC_COUNT: u32 = 112;
+const MAX_ENCODED: u32 = DIACRITIC_COUNT * DIACRITIC_COUNT;
+
+/// How much encoded image data a single image may take.
+const MAX_PAYLOAD_LENGTH: usize = 64 << 20;
+
+/// How many pixels an image may span in either direction.
+const MAX_IMAGE_SIDE: u32 = 10_000;
+
+/// How much memory the decoded images of a terminal may take, before the oldest ones are dropped.
+const MAX_IMAGES_SIZE: usize = 320 << 20;
+
+/// How many images transmitted with the Kitty protocol are kept for later placements.
+const MAX_KITTY_IMAGES: usize = 64;
+
+/// How many Kitty placements are remembered, for deleting them.
+const MAX_KITTY_PLACEMENTS: usize = 1024;
+
+/// How many images may wait to be decoded before the output is held up.
+const MAX_QUEUED_JOBS: usize = 16;
+
+/// How much of a compressed image is decompressed to read its size.
+const HEADER_LENGTH: u64 = 4096;
+
+/// The cell size assumed before the terminal is first laid out.
+const DEFAULT_CELL_WIDTH: f32 = 8.;
+const DEFAULT_CELL_HEIGHT: f32 = 16.;
+const DEFAULT_COLUMNS: usize = 80;
+const DEFAULT_LINES: usize = 24;
+
+const STANDARD_INDIFFERENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// An image shown in the terminal.
+#[derive(Clone, Debug)]
+pub struct TerminalImage {
+    pub image: Arc<RenderImage>,
+    /// How many columns the image spans, which may cover its last column partially.
+    pub columns: f32,
+    /// How many lines the image spans, which may cover its last line partially.
+    pub lines: f32,
+}
+
+/// A cell covered by an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageCell {
+    pub image_id: u32,
+    /// The line of the image the cell covers.
+    pub line: usize,
+    /// The column of the image the cell covers.
+    pub column: usize,
+}
+
+impl ImageCell {
+    /// Reads the image cell from a placeholder.
+    pub fn from_cell(cell: &Cell) -> Option<Self> {
+        if cell.c != IMAGE_PLACEHOLDER {
+            return None;
+        }
+        Self::decode(cell.zerowidth()?)
+    }
+
+    fn decode(diacritics: &[char]) -> Option<Self> {
+        let mut digits = diacritics.iter().map(|diacritic| {
+            (*diacritic as u32)
+                .checked_sub(FIRST_DIACRITIC)
+                .filter(|digit| *digit < DIACRITIC_COUNT)
+        });
+        let mut number = || Some(digits.next()?? * DIACRITIC_COUNT + digits.next()??);
+        Some(Self {
+            image_id: number()?,
+            line: number()? as usize,
+            column: number()? as usize,
+        })
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) {
+        let mut buffer = [0; 4];
+        output.extend_from_slice(IMAGE_PLACEHOLDER.encode_utf8(&mut buffer).as_bytes());
+        for number in [self.image_id, self.line as u32, self.column as u32] {
+            for digit in [number / DIACRITIC_COUNT, number % DIACRITIC_COUNT] {
+                let diacritic = char::from_u32(FIRST_DIACRITIC + digit).unwrap_or_default();
+                output.extend_from_slice(diacritic.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+    }
+}
+
+/// The images shown in a terminal by their ids, decoded on the decoder's thread and painted on
+/// the main one.
+#[derive(Debug, Default)]
+pub(crate) struct TerminalImages {
+    images: HashMap<u32, TerminalImage>,
+    order: VecDeque<u32>,
+    size: usize,
+    evicted: Vec<Arc<RenderImage>>,
+}
+
+impl TerminalImages {
+    pub fn get(&self, image_id: u32) -> Option<&TerminalImage> {
+        self.images.get(&image_id)
+    }
+
+    /// The images that were dropped since the last call, to be removed from the window too.
+    pub fn take_evicted(&mut self) -> Vec<Arc<RenderImage>> {
+        std::mem::take(&mut self.evicted)
+    }
+
+    fn insert(&mut self, image_id: u32, image: TerminalImage) {
+        self.remove(image_id);
+        self.size += image_size(&image.image);
+        self.images.insert(image_id, image);
+        self.order.push_back(image_id);
+        while self.size > MAX_IMAGES_SIZE
+            && let Some(oldest) = self.order.front().copied()
+        {
+            self.remove(oldest);
+        }
+    }
+
+    fn remove(&mut self, image_id: u32) {
+        if let Some(image) = self.images.remove(&image_id) {
+            self.size -= image_size(&image.image);
+            self.order.retain(|id| *id != image_id);
+            self.evicted.push(image.image);
+        }
+    }
+}
+
+fn image_size(image: &RenderImage) -> usize {
+    image.as_bytes(0).map_or(0, |bytes| bytes.len())
+}
+
+/// What an image is decoded from.
+enum ImageSource {
+    Encoded(Vec<u8>),
+    Pixels(RgbaImage),
+    Kitty(Arc<KittyImage>),
+}
+
+/// The work done on the decoder's thread, in the order it was sent in, so that images are never
+/// shown before they're decoded nor after they're removed.
+enum DecoderJob {
+    DecodeKitty {
+        command: KittyCommand,
+        data: Vec<u8>,
+        image: Arc<KittyImage>,
+    },
+    Show {
+        image_id: u32,
+        source: ImageSource,
+        columns: f32,
+        lines: f32,
+    },
+    Remove(u32),
+    #[cfg(test)]
+    Flush(mpsc::Sender<()>),
+}
+
+/// Decodes the images sent to it and adds them to the terminal's images, until the filter is
+/// dropped.
+fn run_decoder(
+    jobs: mpsc::Receiver<DecoderJob>,
+    images: Arc<Mutex<TerminalImages>>,
+    notify: impl Fn(),
+) {
+    while let Ok(job) = jobs.recv() {
+        match job {
+            DecoderJob::DecodeKitty {
+                command,
+                data,
+                image,
+            } => {
+                let pixels = command
+                    .decode(data)
+                    .inspect_err(|error| log::warn!("Failed to decode a terminal image: {error:#}"))
+                    .ok();
+                image.pixels.set(pixels).ok();
+            }
+            DecoderJob::Show {
+                image_id,
+                source,
+                columns,
+                lines,
+            } => {
+                let pixels = match source {
+                    ImageSource::Encoded(data) => decode_image(&data, None)
+                        .inspect_err(|error| {
+                            log::warn!("Failed to decode a terminal image: {error:#}")
+                        })
+                        .ok(),
+                    ImageSource::Pixels(pixels) => Some(pixels),
+                    ImageSource::Kitty(image) => image.pixels.get().cloned().flatten(),
+                };
+                let Some(mut pixels) = pixels else {
+                    continue;
+                };
+                // Convert from RGBA to BGRA.
+                for pixel in pixels.chunks_exact_mut(4) {
+                    pixel.swap(0, 2);
+                }
+                images.lock().insert(
+                    image_id,
+                    TerminalImage {
+                        image: Arc::new(RenderImage::new(vec![Frame::new(pixels)])),
+                        columns,
+                        lines,
+                    },
+                );
+                notify();
+            }
+            DecoderJob::Remove(image_id) => images.lock().remove(image_id),
+            #[cfg(test)]
+            DecoderJob::Flush(done) => {
+                done.send(()).ok();
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum FilterState {
+    #[default]
+    Ground,
+    Escape,
+    DcsParams,
+    Sixel,
+    SixelEscape,
+    OscCommand,
+    ITerm(ITermSequence),
+    ITermEscape(ITermSequence),
+    Apc,
+    Kitty,
+    KittyEscape,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ITermSequence {
+    File,
+    MultipartFile,
+    FilePart,
+    FileEnd,
+}
+
+const ITERM_SEQUENCES: [(&[u8], ITermSequence); 4] = [
+    (b"1337;File=", ITermSequence::File),
+    (b"1337;MultipartFile=", ITermSequence::MultipartFile),
+    (b"1337;FilePart=", ITermSequence::FilePart),
+    (b"1337;FileEnd", ITermSequence::FileEnd),
+];
+
+/// How big an image is requested to be shown, in cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct RequestedSize {
+    columns: Option<f32>,
+    lines: Option<f32>,
+    preserve_aspect_ratio: bool,
+}
+
+/// Takes the images out of the PTY output, which may be split between reads at any byte, and
+/// replaces them with their placeholders.
+pub(crate) struct ImageFilter {
+    state: FilterState,
+    /// The bytes of a sequence that may turn out to be an image, held back until it's known.
+    held: Vec<u8>,
+    payload: Vec<u8>,
+    overflowed: bool,
+    sixel: Option<SixelDecoder>,
+    iterm_multipart: Option<(Vec<u8>, Vec<u8>)>,
+    kitty: KittyState,
+    cell_width: f32,
+    cell_height: f32,
+    columns: usize,
+    lines: usize,
+    next_image_id: u32,
+    decoder: mpsc::SyncSender<DecoderJob>,
+}
+
+impl ImageFilter {
+    /// Creates the filter and the images it shows, calling `notify` whenever an image is ready to
+    /// be painted.
+    pub fn new(notify: impl Fn() + Send + 'static) -> (Self, Arc<Mutex<TerminalImages>>) {
+        let images = Arc::new(Mutex::new(TerminalImages::default()));
+        let (decoder, jobs) = mpsc::sync_channel(MAX_QUEUED_JOBS);
+        let decoded_images = images.clone();
+        thread::Builder::new()
+            .name("terminal image decoder".into())
+            .spawn(move || run_decoder(jobs, decoded_images, notify))
+            .inspect_err(|error| log::error!("Failed to start the terminal image decoder: {error}"))
+            .ok();
+        let filter = Self {
+            state: FilterState::Ground,
+            held: Vec::new(),
+            payload: Vec::new(),
+            overflowed: false,
+            sixel: None,
+            iterm_multipart: None,
+            kitty: KittyState::default(),
+            cell_width: DEFAULT_CELL_WIDTH,
+            cell_height: DEFAULT_CELL_HEIGHT,
+            columns: DEFAULT_COLUMNS,
+            lines: DEFAULT_LINES,
+            next_image_id: 1,
+            decoder,
+        };
+        (filter, images)
+    }
+
+    fn send(&self, job: DecoderJob) {
+        self.decoder.send(job).ok();
+    }
+
+    /// Waits for the images sent so far to be decoded.
+    #[cfg(test)]
+    fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        self.send(DecoderJob::Flush(done));
+        wait.recv().ok();
+    }
+
+    pub fn resize(&mut self, window_size: &WindowSize) {
+        if window_size.cell_width > 0 && window_size.cell_height > 0 {
+            self.cell_width = window_size.cell_width as f32;
+            self.cell_height = window_size.cell_height as f32;
+        }
+        if window_size.num_cols > 0 && window_size.num_lines > 0 {
+            self.columns = window_size.num_cols as usize;
+            self.lines = window_size.num_lines as usize;
+        }
+    }
+
+    /// Copies the PTY output to `output`, replacing the images in it with their placeholders.
+    /// The answers to the Kitty protocol's commands are written to `replies`, to be sent back to
+    /// the PTY.
+    pub fn filter(&mut self, bytes: &[u8], output: &mut Vec<u8>, replies: &mut Vec<u8>) {
+        for &byte in bytes {
+            self.state = match self.state {
+                FilterState::Ground => self.ground(byte, output),
+                FilterState::Escape => self.escape(byte, output),
+                FilterState::DcsParams => match byte {
+                    b'0'..=b'9' | b';' if self.held.len() < 32 => {
+                        self.held.push(byte);
+                        FilterState::DcsParams
+                    }
+                    b'q' => {
+                        self.sixel = Some(SixelDecoder::new());
+                        self.held.clear();
+                        FilterState::Sixel
+                    }
+                    _ => self.reject(byte, output),
+                },
+                FilterState::Sixel => {
+                    if byte == ESC {
+                        FilterState::SixelEscape
+                    } else {
+                        if let Some(sixel) = &mut self.sixel {
+                            sixel.feed(byte);
+                        }
+                        FilterState::Sixel
+                    }
+                }
+                FilterState::SixelEscape => {
+                    self.finish_sixel(output);
+                    if byte == b'\\' {
+                        FilterState::Ground
+                    } else {
+                        self.held.push(ESC);
+                        self.escape(byte, output)
+                    }
+                }
+                FilterState::OscCommand => {
+                    self.held.push(byte);
+                    let command = &self.held[2..];
+                    if let Some((_, sequence)) = ITERM_SEQUENCES
+                        .iter()
+                        .find(|(prefix, _)| *prefix == command)
+                    {
+                        self.held.clear();
+                        self.payload.clear();
+                        self.overflowed = false;
+                        FilterState::ITerm(*sequence)
+                    } else if ITERM_SEQUENCES
+                        .iter()
+                        .any(|(prefix, _)| prefix.starts_with(command))
+                    {
+                        FilterState::OscCommand
+                    } else {
+                        self.held.pop();
+                        self.reject(byte, output)
+                    }
+                }
+                FilterState::ITerm(sequence) => match byte {
+                    BEL => {
+                        self.finish_iterm(sequence, output);
+                        FilterState::Ground
+                    }
+                    ESC => FilterState::ITermEscape(sequence),
+                    _ => {
+                        self.push_payload(byte);
+                        FilterState::ITerm(sequence)
+                    }
+                },
+                FilterState::ITermEscape(sequence) => {
+                    if byte == b'\\' {
+                        self.finish_iterm(sequence, output);
+                        FilterState::Ground
+                    } else {
+                        self.held.push(ESC);
+                        self.escape(byte, output)
+                    }
+                }
+                FilterState::Apc => {
+                    if byte == b'G' {
+                        self.held.clear();
+                        self.payload.clear();
+                        self.overflowed = false;
+                        FilterState::Kitty
+                    } else {
+                        self.reject(byte, output)
+                    }
+                }
+                FilterState::Kitty => {
+                    if byte == ESC {
+                        FilterState::KittyEscape
+                    } else {
+                        self.push_payload(byte);
+                        FilterState::Kitty
+                    }
+                }
+                FilterState::KittyEscape => {
+                    if byte == b'\\' {
+                        self.finish_kitty(output, replies);
+                        FilterState::Ground
+                    } else {
+                        self.held.push(ESC);
+                        self.escape(byte, output)
+                    }
+                }
+            };
+        }
+    }
+
+    fn ground(&mut self, byte: u8, output: &mut Vec<u8>) -> FilterState {
+        if byte == ESC {
+            self.held.push(byte);
+            FilterState::Escape
+        } else {
+            output.push(byte);
+            FilterState::Ground
+        }
+    }
+
+    fn escape(&mut self, byte: u8, output: &mut Vec<u8>) -> FilterState {
+        match byte {
+            b'P' => {
+                self.held.push(byte);
+                FilterState::DcsParams
+            }
+            b']' => {
+                self.held.push(byte);
+                FilterState::OscCommand
+            }
+            b'_' => {
+                self.held.push(byte);
+                FilterState::Apc
+            }
+            _ => self.reject(byte, output),
+        }
+    }
+
+    /// Lets through the held back bytes of a sequence that turned out not to be an image.
+    fn reject(&mut self, byte: u8, output: &mut Vec<u8>) -> FilterState {
+        output.append(&mut self.held);
+        self.ground(byte, output)
+    }
+
+    fn push_payload(&mut self, byte: u8) {
+        if self.payload.len() < MAX_PAYLOAD_LENGTH {
+            self.payload.push(byte);
+        } else {
+            self.overflowed = true;
+        }
+    }
+
+    fn take_payload(&mut self) -> Option<Vec<u8>> {
+        let payload = std::mem::take(&mut self.payload);
+        if std::mem::take(&mut self.overflowed) {
+            log::warn!("Dropped a terminal image larger than {MAX_PAYLOAD_LENGTH} bytes");
+            None
+        } else {
+            Some(payload)
+        }
+    }
+
+    fn finish_sixel(&mut self, output: &mut Vec<u8>) {
+        if let Some(image) = self.sixel.take().and_then(SixelDecoder::finish) {
+            let (width, height) = image.dimensions();
+            let source = ImageSource::Pixels(image);
+            self.show(
+                source,
+                width,
+                height,
+                RequestedSize::default(),
+                true,
+                output,
+            );
+        }
+    }
+
+    fn finish_iterm(&mut self, sequence: ITermSequence, output: &mut Vec<u8>) {
+        let Some(payload) = self.take_payload() else {
+            self.iterm_multipart = None;
+            return;
+        };
+        let (args, data) = match sequence {
+            ITermSequence::File => match payload.iter().position(|byte| *byte == b':') {
+                Some(colon) => (payload[..colon].to_vec(), payload[colon + 1..].to_vec()),
+                None => return,
+            },
+            ITermSequence::MultipartFile => {
+                self.iterm_multipart = Some((payload, Vec::new()));
+                return;
+            }
+            ITermSequence::FilePart => {
+                if let Some((_, data)) = &mut self.iterm_multipart {
+                    if data.len() + payload.len() > MAX_PAYLOAD_LENGTH {
+                        log::warn!(
+                            "Dropped a terminal image larger than {MAX_PAYLOAD_LENGTH} bytes"
+                        );
+                        self.iterm_multipart = None;
+                    } else {
+                        data.extend_from_slice(&payload);
+                    }
+                }
+                return;
+            }
+            ITermSequence::FileEnd => match self.iterm_multipart.take() {
+                Some(multipart) => multipart,
+                None => return,
+            },
+        };
+
+        let file = ITermFile::parse(&args, self);
+        if !file.inline {
+            return;
+        }
+        let image = decode_base64(&data).and_then(|data| {
+            let (width, height) = image_dimensions(&data, None)?;
+            Ok((data, width, height))
+        });
+        match image {
+            Ok((data, width, height)) => {
+                let source = ImageSource::Encoded(data);
+                self.show(source, width, height, file.size, file.move_cursor, output);
+            }
+            Err(error) => log::warn!("Failed to show a terminal image: {error:#}"),
+        }
+    }
+
+    fn finish_kitty(&mut self, output: &mut Vec<u8>, replies: &mut Vec<u8>) {
+        let Some(payload) = self.take_payload() else {
+            self.kitty.pending = None;
+            return;
+        };
+        let (control, data) = match payload.iter().position(|byte| *byte == b';') {
+            Some(semicolon) => (&payload[..semicolon], &payload[semicolon + 1..]),
+            None => (payload.as_slice(), &[][..]),
+        };
+        let command = KittyCommand::parse(control);
+
+        // Only the first chunk of an image carries its keys, the rest only say if more follow.
+        let (command, data) = match self.kitty.pending.take() {
+            Some((pending_command, mut pending_data)) => {
+                if pending_data.len() + data.len() > MAX_PAYLOAD_LENGTH {
+                    log::warn!("Dropped a terminal image larger than {MAX_PAYLOAD_LENGTH} bytes");
+                    return;
+                }
+                pending_data.extend_from_slice(data);
+                if command.more {
+                    self.kitty.pending = Some((pending_command, pending_data));
+                    return;
+                }
+                (pending_command, pending_data)
+            }
+            None if command.more && matches!(command.action, b't' | b'T' | b'q') => {
+                self.kitty.pending = Some((command, data.to_vec()));
+                return;
+            }
+            None => (command, data.to_vec()),
+        };
+        self.run_kitty(command, &data, output, replies);
+    }
+
+    fn run_kitty(
+        &mut self,
+        mut command: KittyCommand,
+        data: &[u8],
+        output: &mut Vec<u8>,
+        replies: &mut Vec<u8>,
+    ) {
+        if command.image_id == 0
+            && command.image_number != 0
+            && matches!(command.action, b't' | b'T')
+        {
+            command.image_id = self.kitty.allocate_id();
+            self.kitty
+                .numbers
+                .insert(command.image_number, command.image_id);
+        }
+        let result = match command.action {
+            b'q' => command.load(data).map(|_| ()),
+            b't' => self
+                .load_kitty(&command, data)
+                .map(|image| self.kitty.insert(command.image_id, image)),
+            b'T' => self.load_kitty(&command, data).map(|image| {
+                self.kitty.insert(command.image_id, image.clone());
+                self.place_kitty(&command, image, output);
+            }),
+            b'p' => match self.kitty.get(command.image_id, command.image_number) {
+                Some(image) => {
+                    self.place_kitty(&command, image, output);
+                    Ok(())
+                }
+                None => Err(anyhow!("ENOENT:image not found")),
+            },
+            b'd' => {
+                self.delete_kitty(&command);
+                return;
+            }
+            _ => return,
+        };
+        command.reply(result, replies);
+    }
+
+    /// Checks the transmitted image and sends it to be decoded.
+    fn load_kitty(&mut self, command: &KittyCommand, data: &[u8]) -> Result<Arc<KittyImage>> {
+        let (width, height, data) = command.load(data)?;
+        let image = Arc::new(KittyImage {
+            width,
+            height,
+            pixels: OnceLock::new(),
+        });
+        self.send(DecoderJob::DecodeKitty {
+            command: command.clone(),
+            data,
+            image: image.clone(),
+        });
+        Ok(image)
+    }
+
+    fn place_kitty(
+        &mut self,
+        command: &KittyCommand,
+        image: Arc<KittyImage>,
+        output: &mut Vec<u8>,
+    ) {
+        let size = RequestedSize {
+            columns: (command.columns > 0).then_some(command.columns as f32),
+            lines: (command.lines > 0).then_some(command.lines as f32),
+            preserve_aspect_ratio: false,
+        };
+        let (width, height) = (image.width, image.height);
+        let image_id = self.show(
+            ImageSource::Kitty(image),
+            width,
+            height,
+            size,
+            command.move_cursor,
+            output,
+        );
+        if command.image_id != 0 {
+            let decoder = &self.decoder;
+            self.kitty.placements.retain(|placement| {
+                let replaced = command.placement_id != 0
+                    && placement.image_id == command.image_id
+                    && placement.placement_id == command.placement_id;
+                if replaced {
+                    decoder.send(DecoderJob::Remove(placement.id)).ok();
+                }
+                !replaced
+            });
+            if self.kitty.placements.len() == MAX_KITTY_PLACEMENTS {
+                self.kitty.placements.pop_front();
+            }
+            self.kitty.placements.push_back(KittyPlacement {
+                image_id: command.image_id,
+                placement_id: command.placement_id,
+                id: image_id,
+            });
+        }
+    }
+
+    fn delete_kitty(&mut self, command: &KittyCommand) {
+        let delete_data = command.delete.is_ascii_uppercase();
+        let image_id = match command.delete.to_ascii_lowercase() {
+            b'a' => None,
+            b'i' => Some(command.image_id),
+            b'n' => match self.kitty.numbers.get(&command.image_number) {
+                Some(image_id) => Some(*image_id),
+                None => return,
+            },
+            _ => return,
+        };
+        let decoder = &self.decoder;
+        self.kitty.placements.retain(|placement| {
+            let deleted = image_id.is_none_or(|image_id| {
+                placement.image_id == image_id
+                    && (command.placement_id == 0 || placement.placement_id == command.placement_id)
+            });
+            if deleted {
+                decoder.send(DecoderJob::Remove(placement.id)).ok();
+            }
+            !deleted
+        });
+        if delete_data {
+            match image_id {
+                Some(image_id) => self.kitty.remove(image_id),
+                None => self.kitty.clear(),
+            }
+        }
+    }
+
+    /// Sends the image to be shown and writes its placeholders over the cells it's shown on,
+    /// returning its id.
+    fn show(
+        &mut self,
+        source: ImageSource,
+        width: u32,
+        height: u32,
+        requested: RequestedSize,
+        move_cursor: bool,
+        output: &mut Vec<u8>,
+    ) -> u32 {
+        let (columns, lines) = self.size_in_cells(width, height, requested);
+        let image_id = self.next_image_id;
+        self.next_image_id = self.next_image_id % (MAX_ENCODED - 1) + 1;
+        self.send(DecoderJob::Show {
+            image_id,
+            source,
+            columns,
+            lines,
+        });
+
+        let columns = (columns.ceil() as usize).max(1);
+        let lines = (lines.ceil() as usize).max(1);
+        self.write_placeholders(image_id, columns, lines, move_cursor, output);
+        image_id
+    }
+
+    fn size_in_cells(&self, width: u32, height: u32, requested: RequestedSize) -> (f32, f32) {
+        let natural_columns = width as f32 / self.cell_width;
+        let natural_lines = height as f32 / self.cell_height;
+        let (mut columns, mut lines) = match (requested.columns, requested.lines) {
+            (None, None) => (natural_columns, natural_lines),
+            (Some(columns), None) => (columns, columns * natural_lines / natural_columns),
+            (None, Some(lines)) => (lines * natural_columns / natural_lines, lines),
+            (Some(columns), Some(lines)) if requested.preserve_aspect_ratio => {
+                let scale = (columns / natural_columns).min(lines / natural_lines);
+                (natural_columns * scale, natural_lines * scale)
+            }
+            (Some(columns), Some(lines)) => (columns, lines),
+        };
+
+        // Wider images would wrap, so they're scaled down to fit.
+        let max_columns = self.columns as f32;
+        if columns > max_columns {
+            lines *= max_columns / columns;
+            columns = max_columns;
+        }
+        (columns, lines.min((MAX_ENCODED - 1) as f32))
+    }
+
+    /// Writes the placeholders line by line, starting at the cursor. With the image ending on the
+    /// last column, moving back is off by one unless it spans the whole line, since the cursor
+    /// stays on the last column after writing to it.
+    fn write_placeholders(
+        &self,
+        image_id: u32,
+        columns: usize,
+        lines: usize,
+        move_cursor: bool,
+        output: &mut Vec<u8>,
+    ) {
+        let move_back = |output: &mut Vec<u8>| {
+            if columns >= self.columns {
+                output.push(b'\r');
+            } else {
+                write!(output, "\x1b[{columns}D").ok();
+            }
+        };
+        for line in 0..lines {
+            if line > 0 {
+                move_back(output);
+                // Line feeds scroll the screen when the image goes past its bottom.
+                output.push(b'\n');
+            }
+            for column in 0..columns {
+                ImageCell {
+                    image_id,
+                    line,
+                    column,
+                }
+                .encode(output);
+            }
+        }
+        if !move_cursor {
+            move_back(output);
+            if lines > 1 {
+                write!(output, "\x1b[{}A", lines - 1).ok();
+            }
+        }
+    }
+}
+
+fn decode_base64(data: &[u8]) -> Result<Vec<u8>> {
+    let data = data
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    Ok(STANDARD_INDIFFERENT.decode(data)?)
+}
+
+/// Reads the size of an encoded image, without decoding it.
+fn image_dimensions(data: &[u8], format: Option<ImageFormat>) -> Result<(u32, u32)> {
+    let reader = match format {
+        Some(format) => ImageReader::with_format(Cursor::new(data), format),
+        None => ImageReader::new(Cursor::new(data)).with_guessed_format()?,
+    };
+    let (width, height) = reader.into_dimensions()?;
+    check_image_size(width, height)?;
+    Ok((width, height))
+}
+
+fn decode_image(data: &[u8], format: Option<ImageFormat>) -> Result<RgbaImage> {
+    let format = match format {
+        Some(format) => format,
+        None => image::guess_format(data)?,
+    };
+    let image = image::load_from_memory_with_format(data, format)?;
+    check_image_size(image.width(), image.height())?;
+    Ok(image.into_rgba8())
+}
+
+fn check_image_size(width: u32, height: u32) -> Result<()> {
+    if width == 0 || height == 0 || width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+        bail!("unsupported image size {width}x{height}");
+    }
+    Ok(())
+}
+
+/// The arguments of an iTerm2 file.
+#[derive(Debug, PartialEq)]
+struct ITermFile {
+    inline: bool,
+    size: RequestedSize,
+    move_cursor: bool,
+}
+
+impl ITermFile {
+    fn parse(args: &[u8], filter: &ImageFilter) -> Self {
+        let mut file = Self {
+            inline: false,
+            size: RequestedSize {
+                preserve_aspect_ratio: true,
+                ..RequestedSize::default()
+            },
+            move_cursor: true,
+        };
+        let args = String::from_utf8_lossy(args);
+        for arg in args.split(';') {
+            let Some((key, value)) = arg.split_once('=') else {
+                continue;
+            };
+            match key {
+                "inline" => file.inline = value == "1",
+                "width" => {
+                    file.size.columns =
+                        Self::dimension(value, filter.cell_width, filter.columns as f32)
+                }
+                "height" => {
+                    file.size.lines =
+                        Self::dimension(value, filter.cell_height, filter.lines as f32)
+                }
+                "preserveAspectRatio" => file.size.preserve_aspect_ratio = value != "0",
+                "doNotMoveCursor" => file.move_cursor = value != "1",
+                _ => {}
+            }
+        }
+        file
+    }
+
+    /// Parses a dimension given in cells, pixels or a percentage of the terminal, into cells.
+    fn dimension(value: &str, cell_size: f32, cells: f32) -> Option<f32> {
+        let size = if let Some(pixels) = value.strip_suffix("px") {
+            pixels.parse::<f32>().ok()? / cell_size
+        } else if let Some(percent) = value.strip_suffix('%') {
+            percent.parse::<f32>().ok()? * cells / 100.
+        } else {
+            value.parse::<f32>().ok()?
+        };
+        (size > 0.).then_some(size)
+    }
+}
+
+/// The images and placements of the Kitty protocol, by the ids the programs gave them.
+#[derive(Default)]
+struct KittyState {
+    pending: Option<(KittyCommand, Vec<u8>)>,
+    images: HashMap<u32, Arc<KittyImage>>,
+    order: VecDeque<u32>,
+    numbers: HashMap<u32, u32>,
+    next_id: u32,
+    placements: VecDeque<KittyPlacement>,
+}
+
+impl KittyState {
+    /// Picks the id for an image given a number instead, from the top of the range so that it
+    /// doesn't collide with the ids programs pick themselves.
+    fn allocate_id(&mut self) -> u32 {
+        self.next_id = self.next_id.wrapping_sub(1).max(1 << 31);
+        self.next_id
+    }
+
+    fn get(&self, image_id: u32, image_number: u32) -> Option<Arc<KittyImage>> {
+        let image_id = if image_id == 0 {
+            *self.numbers.get(&image_number)?
+        } else {
+            image_id
+        };
+        self.images.get(&image_id).cloned()
+    }
+
+    fn insert(&mut self, image_id: u32, image: Arc<KittyImage>) {
+        if image_id == 0 {
+            return;
+        }
+        self.remove(image_id);
+        if self.order.len() == MAX_KITTY_IMAGES
+            && let Some(oldest) = self.order.front().copied()
+        {
+            self.remove(oldest);
+        }
+        self.images.insert(image_id, image);
+        self.order.push_back(image_id);
+    }
+
+    fn remove(&mut self, image_id: u32) {
+        if self.images.remove(&image_id).is_some() {
+            self.order.retain(|id| *id != image_id);
+            self.numbers.retain(|_, id| *id != image_id);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.images.clear();
+        self.order.clear();
+        self.numbers.clear();
+    }
+}
+
+/// An image transmitted with the Kitty protocol, which is decoded once the decoder gets to it.
+struct KittyImage {
+    width: u32,
+    height: u32,
+    /// The decoded image, or `None` if it failed to decode.
+    pixels: OnceLock<Option<RgbaImage>>,
+}
+
+/// A Kitty placement, and the id of the image shown for it.
+#[derive(Debug)]
+struct KittyPlacement {
+    image_id: u32,
+    placement_id: u32,
+    id: u32,
+}
+
+/// The keys of a Kitty graphics command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compressed: bool,
+    width: u32,
+    height: u32,
+    image_id: u32,
+    image_number: u32,
+    placement_id: u32,
+    more: bool,
+    quiet: u32,
+    columns: u32,
+    lines: u32,
+    move_cursor: bool,
+    delete: u8,
+}
+
+impl Default for KittyCommand {
+    fn default() -> Self {
+        Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            compressed: false,
+            width: 0,
+            height: 0,
+            image_id: 0,
+            image_number: 0,
+            placement_id: 0,
+            more: false,
+            quiet: 0,
+            columns: 0,
+            lines: 0,
+            move_cursor: true,
+            delete: b'a',
+        }
+    }
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Self {
+        let mut command = Self::default();
+        for pair in control.split(|byte| *byte == b',') {
+            let [key, b'=', value @ ..] = pair else {
+                continue;
+            };
+            let number = || {
+                std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse::<u32>().ok())
+                    .unwrap_or_default()
+            };
+            let character = value.first().copied().unwrap_or_default();
+            match *key {
+                b'a' => command.action = character,
+                b'f' => command.format = number(),
+                b't' => command.medium = character,
+                b'o' => command.compressed = character == b'z',
+                b's' => command.width = number(),
+                b'v' => command.height = number(),
+                b'i' => command.image_id = number(),
+                b'I' => command.image_number = number(),
+                b'p' => command.placement_id = number(),
+                b'm' => command.more = number() == 1,
+                b'q' => command.quiet = number(),
+                b'c' => command.columns = number(),
+                b'r' => command.lines = number(),
+                b'C' => command.move_cursor = number() != 1,
+                b'd' => command.delete = character,
+                _ => {}
+            }
+        }
+        command
+    }
+
+    /// Checks the image transmitted with the command, returning its size and its data. Only
+    /// images transmitted directly are supported: reading the files named in the output would let
+    /// anything printed to the terminal read and delete the user's files.
+    fn load(&self, data: &[u8]) -> Result<(u32, u32, Vec<u8>)> {
+        if self.medium != b'd' {
+            bail!("EINVAL:unsupported transmission medium");
+        }
+        let data = decode_base64(data).map_err(|error| anyhow!("EINVAL:{error}"))?;
+        let (width, height) = match self.format {
+            100 => {
+                let mut decompressed = Vec::new();
+                let header = if self.compressed {
+                    ZlibDecoder::new(data.as_slice())
+                        .take(HEADER_LENGTH)
+                        .read_to_end(&mut decompressed)
+                        .map_err(|error| anyhow!("EINVAL:{error}"))?;
+                    decompressed.as_slice()
+                } else {
+                    data.as_slice()
+                };
+                image_dimensions(header, Some(ImageFormat::Png))
+                    .map_err(|error| anyhow!("EBADPNG:{error}"))?
+            }
+            24 | 32 => {
+                check_image_size(self.width, self.height)
+                    .map_err(|error| anyhow!("EINVAL:{error}"))?;
+                let length = self.pixels_length();
+                if !self.compressed && data.len() < length {
+                    bail!("ENODATA:expected {length} bytes of pixels");
+                }
+                (self.width, self.height)
+            }
+            format => bail!("EINVAL:unsupported format {format}"),
+        };
+        Ok((width, height, data))
+    }
+
+    /// How many bytes the pixels of an image in the RGB or RGBA format take.
+    fn pixels_length(&self) -> usize {
+        let channels = if self.format == 24 { 3 } else { 4 };
+        self.width as usize * self.height as usize * channels
+    }
+
+    /// Decodes the data of an image checked with `load`.
+    fn decode(&self, data: Vec<u8>) -> Result<RgbaImage> {
+        let data = if self.compressed {
+            let mut decompressed = Vec::new();
+            ZlibDecoder::new(data.as_slice())
+                .take(MAX_IMAGE_SIDE as u64 * MAX_IMAGE_SIDE as u64 * 4)
+                .read_to_end(&mut decompressed)?;
+            decompressed
+        } else {
+            data
+        };
+
+        if self.format == 100 {
+            return decode_image(&data, Some(ImageFormat::Png));
+        }
+        let length = self.pixels_length();
+        if data.len() < length {
+            bail!("expected {length} bytes of pixels");
+        }
+        let rgba = if self.format == 24 {
+            data[..length]
+                .chunks_exact(3)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 0xff])
+                .collect()
+        } else {
+            data[..length].to_vec()
+        };
+        RgbaImage::from_raw(self.width, self.height, rgba).context("bad image size")
+    }
+
+    /// Answers the command, unless it asked to stay quiet or gave no id to answer to.
+    fn reply(&self, result: Result<()>, replies: &mut Vec<u8>) {
+        if self.image_id == 0 && self.image_number == 0 {
+            return;
+        }
+        let message = match &result {
+            Ok(()) if self.quiet == 0 => "OK".to_string(),
+            Err(error) if self.quiet < 2 => error.to_string(),
+            _ => return,
+        };
+        let keys = [
+            ("i", self.image_id),
+            ("I", self.image_number),
+            ("p", self.placement_id),
+        ]
+        .into_iter()
+        .filter(|(_, value)| *value != 0)
+        .map(|(key, value)| format!("{key}={value}"))
+        .join(",");
+        write!(replies, "\x1b_G{keys};{message}\x1b\\").ok();
+    }
+}
+
+/// The colors of the VT340, which Sixel images start with.
+const SIXEL_DEFAULT_PALETTE: [[u32; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SixelState {
+    Data,
+    Repeat,
+    Color,
+    Raster,
+}
+
+/// Decodes Sixel data as it comes in.
+struct SixelDecoder {
+    state: SixelState,
+    params: Vec<u32>,
+    palette: [[u8; 4]; 256],
+    color: usize,
+    x: usize,
+    y: usize,
+    /// The size of the image, as declared by its raster attributes or drawn.
+    width: usize,
+    height: usize,
+    /// The RGBA pixels drawn so far, in rows of `stride` pixels.
+    pixels: Vec<u8>,
+    stride: usize,
+    rows: usize,
+}
+
+impl SixelDecoder {
+    fn new() -> Self {
+        let mut palette = [[0, 0, 0, 0xff]; 256];
+        for (color, [r, g, b]) in palette.iter_mut().zip(SIXEL_DEFAULT_PALETTE) {
+            *color = [percent(r), percent(g), percent(b), 0xff];
+        }
+        Self {
+            state: SixelState::Data,
+            params: Vec::new(),
+            palette,
+            color: 0,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+            stride: 0,
+            rows: 0,
+        }
+    }
+
+    fn feed(&mut self, byte: u8) {
+        match self.state {
+            SixelState::Data => self.data(byte),
+            SixelState::Repeat => match byte {
+                b'0'..=b'9' => self.push_digit(byte),
+                b'?'..=b'~' => {
+                    let count = self.params.first().copied().unwrap_or(1).max(1) as usize;
+                    self.draw(byte - b'?', count);
+                    self.state = SixelState::Data;
+                }
+                _ => {
+                    self.state = SixelState::Data;
+                    self.data(byte);
+                }
+            },
+            SixelState::Color | SixelState::Raster => match byte {
+                b'0'..=b'9' => self.push_digit(byte),
+                b';' if self.params.len() < 8 => self.params.push(0),
+                b';' => {}
+                _ => {
+                    if self.state == SixelState::Color {
+                        self.set_color();
+                    } else {
+                        self.set_raster();
+                    }
+                    self.state = SixelState::Data;
+                    self.data(byte);
+                }
+            },
+        }
+    }
+
+    fn data(&mut self, byte: u8) {
+        match byte {
+            b'?'..=b'~' => self.draw(byte - b'?', 1),
+            b'!' => self.start_params(SixelState::Repeat),
+            b'#' => self.start_params(SixelState::Color),
+            b'"' => self.start_params(SixelState::Raster),
+            b'$' => self.x = 0,
+            b'-' => {
+                self.x = 0;
+                self.y += 6;
+            }
+            _ => {}
+        }
+    }
+
+    fn start_params(&mut self, state: SixelState) {
+        self.params.clear();
+        self.params.push(0);
+        self.state = state;
+    }
+
+    fn push_digit(&mut self, byte: u8) {
+        if let Some(param) = self.params.last_mut() {
+            *param = param
+                .saturating_mul(10)
+                .saturating_add((byte - b'0') as u32);
+        }
+    }
+
+    fn set_color(&mut self) {
+        let color = self.params[0] as usize % self.palette.len();
+        if let [_, space, x, y, z, ..] = self.params[..] {
+            let [r, g, b] = match space {
+                1 => hls_to_rgb(x, y, z),
+                2 => [percent(x), percent(y), percent(z)],
+                _ => return,
+            };
+            self.palette[color] = [r, g, b, 0xff];
+        }
+        self.color = color;
+    }
+
+    fn set_raster(&mut self) {
+        if let [_, _, width, height, ..] = self.params[..] {
+            let width = (width as usize).min(MAX_IMAGE_SIDE as usize);
+            let height = (height as usize).min(MAX_IMAGE_SIDE as usize);
+            self.grow(width, height);
+            self.width = self.width.max(width);
+            self.height = self.height.max(height);
+        }
+    }
+
+    /// Draws the six pixels of `bits` `count` times, left to right.
+    fn draw(&mut self, bits: u8, count: usize) {
+        let max = MAX_IMAGE_SIDE as usize;
+        let end = (self.x + count).min(max);
+        if bits != 0 && self.x < end && self.y < max {
+            let bottom = (self.y + 6).min(max);
+            self.grow(end, bottom);
+            let color = self.palette[self.color];
+            for y in self.y..bottom {
+                if bits & (1 << (y - self.y)) != 0 {
+                    let row = y * self.stride * 4;
+                    for pixel in self.pixels[row + self.x * 4..row + end * 4].chunks_exact_mut(4) {
+                        pixel.copy_from_slice(&color);
+                    }
+                    self.height = self.height.max(y + 1);
+                }
+            }
+            self.width = self.width.max(end);
+        }
+        self.x = end;
+    }
+
+    /// Makes room for drawing up to the given size, growing the buffer in steps.
+    fn grow(&mut self, width: usize, height: usize) {
+        if width <= self.stride && height <= self.rows {
+            return;
+        }
+        let max = MAX_IMAGE_SIDE as usize;
+        let stride = if width > self.stride {
+            width.max(self.stride * 2).min(max)
+        } else {
+            self.stride
+        };
+        let rows = if height > self.rows {
+            height.max(self.rows * 2).min(max)
+        } else {
+            self.rows
+        };
+        // Pixels that aren't drawn stay transparent, showing the terminal's background.
+        let mut pixels = vec![0; stride * rows * 4];
+        if self.stride > 0 {
+            for (row, old_row) in self.pixels.chunks_exact(self.stride * 4).enumerate() {
+                pixels[row * stride * 4..][..old_row.len()].copy_from_slice(old_row);
+            }
+        }
+        self.pixels = pixels;
+        self.stride = stride;
+        self.rows = rows;
+    }
+
+    fn finish(self) -> Option<RgbaImage> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let mut pixels = Vec::with_capacity(self.width * self.height * 4);
+        for row in self.pixels.chunks_exact(self.stride * 4).take(self.height) {
+            pixels.extend_from_slice(&row[..self.width * 4]);
+        }
+        RgbaImage::from_raw(self.width as u32, self.height as u32, pixels)
+    }
+}
+
+fn percent(value: u32) -> u8 {
+    (value.min(100) * 255 / 100) as u8
+}
+
+/// Converts a Sixel HLS color, whose hues start at blue rather than red.
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let hue = ((hue + 240) % 360) as f32 / 360.;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    let q = if lightness < 0.5 {
+        lightness * (1. + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2. * lightness - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.);
+        let value = if t < 1. / 6. {
+            p + (q - p) * 6. * t
+        } else if t < 1. / 2. {
+            q
+        } else if t < 2. / 3. {
+            p + (q - p) * (2. / 3. - t) * 6.
+        } else {
+            p
+        };
+        (value * 255.).round() as u8
+    };
+    [channel(hue + 1. / 3.), channel(hue), channel(hue - 1. / 3.)]
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+
+    use super::*;
+
+    fn filter() -> (ImageFilter, Arc<Mutex<TerminalImages>>) {
+        let (mut filter, images) = ImageFilter::new(|| {});
+        filter.resize(&WindowSize {
+            num_lines: 24,
+            num_cols: 80,
+            cell_width: 8,
+            cell_height: 16,
+        });
+        (filter, images)
+    }
+
+    /// Runs the output through the filter one byte at a time, writing the placeholders as the
+    /// cells they cover.
+    fn run(filter: &mut ImageFilter, output: &[u8]) -> (String, String) {
+        let mut filtered = Vec::new();
+        let mut replies = Vec::new();
+        for byte in output {
+            filter.filter(&[*byte], &mut filtered, &mut replies);
+        }
+        filter.flush();
+
+        let filtered = String::from_utf8(filtered).unwrap();
+        let mut text = String::new();
+        let mut chars = filtered.chars().peekable();
+        while let Some(char) = chars.next() {
+            if char == IMAGE_PLACEHOLDER {
+                let mut diacritics = Vec::new();
+                while let Some(diacritic) =
+                    chars.next_if(|char| ('\u{300}'..'\u{370}').contains(char))
+                {
+                    diacritics.push(diacritic);
+                }
+                let cell = ImageCell::decode(&diacritics).unwrap();
+                text.push_str(&format!(
+                    "[{}:{},{}]",
+                    cell.image_id, cell.line, cell.column
+                ));
+            } else {
+                text.push(char);
+            }
+        }
+        (text, String::from_utf8(replies).unwrap())
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = Vec::new();
+        RgbaImage::from_pixel(width, height, Rgba([0xff, 0, 0, 0xff]))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn test_filtering_images() {
+        let (mut filter, images) = filter();
+        let png = STANDARD_INDIFFERENT.encode(png(16, 32));
+
+        // Sequences that aren't images pass through untouched.
+        let other = "\x1b]0;title\x07\x1b]133;A\x1b\\\x1bP1$r\x1b\\\x1b_X\x1b\\\x1b[1m";
+        assert_eq!(run(&mut filter, other.as_bytes()).0, other);
+
+        let output = format!("a\x1b]1337;File=size=1;inline=1:{png}\x07b");
+        assert_eq!(
+            run(&mut filter, output.as_bytes()).0,
+            "a[1:0,0][1:0,1]\x1b[2D\n[1:1,0][1:1,1]b"
+        );
+        let image = images.lock().get(1).unwrap().clone();
+        assert_eq!((image.columns, image.lines), (2., 2.));
+
+        // Files that aren't inline are downloads, which aren't supported.
+        let output = format!("\x1b]1337;File=size=1:{png}\x1b\\c");
+        assert_eq!(run(&mut filter, output.as_bytes()).0, "c");
+
+        let output = format!(
+            "\x1b]1337;MultipartFile=inline=1;width=4;height=1\x07\x1b]1337;FilePart={}\x07\x1b]1337;FilePart={}\x07\x1b]1337;FileEnd\x07",
+            &png[..8],
+            &png[8..]
+        );
+        assert_eq!(
+            run(&mut filter, output.as_bytes()).0,
+            "[2:0,0]",
+            "the image is scaled down to fit a line, keeping its aspect ratio"
+        );
+
+        let (text, _) = run(&mut filter, b"\x1bPq#1!3~-!2~\x1b\\d");
+        assert_eq!(text, "[3:0,0]d");
+        let image = images.lock().get(3).unwrap().clone();
+        assert_eq!(image.image.data[0].buffer().dimensions(), (3, 12));
+    }
+
+    #[test]
+    fn test_wide_images() {
+        let (mut filter, images) = filter();
+        let png = STANDARD_INDIFFERENT.encode(png(8 * 160, 16));
+        let output = format!("\x1b]1337;File=inline=1;doNotMoveCursor=1:{png}\x07");
+        let (text, _) = run(&mut filter, output.as_bytes());
+        assert!(text.starts_with("[1:0,0][1:0,1]"));
+        assert!(text.ends_with("[1:0,79]\r"), "{text:?}");
+        let image = images.lock().get(1).unwrap().clone();
+        assert_eq!((image.columns, image.lines), (80., 0.5));
+    }
+
+    #[test]
+    fn test_kitty_commands() {
+        let (mut filter, images) = filter();
+        let pixels = STANDARD_INDIFFERENT.encode([0xffu8; 16 * 16 * 3]);
+
+        let query = "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\";
+        assert_eq!(
+            run(&mut filter, query.as_bytes()),
+            (String::new(), "\x1b_Gi=31;OK\x1b\\".to_string())
+        );
+
+        // Transmitted in chunks, and shown in two places.
+        let output = format!(
+            "\x1b_Gi=7,f=24,s=16,v=16,m=1;{}\x1b\\\x1b_Gm=1;{}\x1b\\\x1b_Gm=0;{}\x1b\\",
+            &pixels[..8],
+            &pixels[8..16],
+            &pixels[16..]
+        );
+        assert_eq!(
+            run(&mut filter, output.as_bytes()),
+            (String::new(), "\x1b_Gi=7;OK\x1b\\".to_string())
+        );
+        assert_eq!(
+            run(
+                &mut filter,
+                b"\x1b_Ga=p,i=7,q=1\x1b\\x\x1b_Ga=p,i=7,p=2,c=1,C=1,q=1\x1b\\"
+            ),
+            ("[1:0,0][1:0,1]x[2:0,0]\x1b[1D".to_string(), String::new())
+        );
+        assert_eq!(
+            run(&mut filter, b"\x1b_Ga=p,i=8\x1b\\"),
+            (
+                String::new(),
+                "\x1b_Gi=8;ENOENT:image not found\x1b\\".to_string()
+            )
+        );
+
+        // Images given numbers get their ids picked for them.
+        let output = format!("\x1b_Ga=T,I=3,f=24,s=16,v=16;{pixels}\x1b\\");
+        let (text, reply) = run(&mut filter, output.as_bytes());
+        assert_eq!(text, "[3:0,0][3:0,1]");
+        assert!(reply.starts_with("\x1b_Gi=") && reply.ends_with(",I=3;OK\x1b\\"));
+
+        run(&mut filter, b"\x1b_Ga=d,d=i,i=7,p=2\x1b\\");
+        assert!(images.lock().get(1).is_some());
+        assert!(images.lock().get(2).is_none());
+        run(&mut filter, b"\x1b_Ga=d,d=A\x1b\\");
+        assert!(images.lock().get(1).is_none());
+        assert!(images.lock().get(3).is_none());
+        assert_eq!(
+            run(&mut filter, b"\x1b_Ga=p,i=7\x1b\\").1,
+            "\x1b_Gi=7;ENOENT:image not found\x1b\\"
+        );
+
+        // Files named in the output are never read.
+        let path = STANDARD_INDIFFERENT.encode("/etc/passwd");
+        let output = format!("\x1b_Ga=T,i=9,t=f,f=100;{path}\x1b\\");
+        assert_eq!(
+            run(&mut filter, output.as_bytes()),
+            (
+                String::new(),
+                "\x1b_Gi=9;EINVAL:unsupported transmission medium\x1b\\".to_string()
+            )
+        );
+    }
+}
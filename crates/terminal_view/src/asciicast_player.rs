@@ -0,0 +1,505 @@
+//! Replays terminal sessions recorded in the asciicast format, in a read-only terminal.
+
+use std::{cell::Cell, path::PathBuf, rc::Rc, time::Duration};
+
+use anyhow::Context as _;
+use gpui::{
+    Bounds, Entity, EventEmitter, FocusHandle, Focusable, MouseButton, MouseDownEvent,
+    PathPromptOptions, Pixels, Task, WeakEntity, actions, canvas,
+};
+use project::Project;
+use terminal::{
+    Terminal, TerminalBuilder,
+    asciicast::{EventKind, Recording},
+    terminal_settings::{AlternateScroll, CursorShape},
+};
+use ui::{ProgressBar, Tooltip, prelude::*};
+use workspace::{Workspace, item::Item, notifications::DetachAndPromptErr};
+
+use crate::TerminalView;
+
+actions!(
+    terminal,
+    [
+        /// Opens an asciicast recording to replay it in a read-only terminal.
+        OpenRecording,
+    ]
+);
+
+actions!(
+    asciicast_player,
+    [
+        /// Plays the recording, or pauses it while it's playing.
+        TogglePlayback,
+        /// Skips the recording ahead by a few seconds.
+        SeekForward,
+        /// Skips the recording back by a few seconds.
+        SeekBackward,
+        /// Goes back to the start of the recording.
+        SeekToStart,
+    ]
+);
+
+const SEEK_STEP: Duration = Duration::from_secs(5);
+/// How often the position is updated while playing, between the recording's events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+        workspace.register_action(AsciicastPlayer::open);
+    })
+    .detach();
+}
+
+pub struct AsciicastPlayer {
+    recording: Recording,
+    path: PathBuf,
+    terminal: Entity<Terminal>,
+    terminal_view: Entity<TerminalView>,
+    focus_handle: FocusHandle,
+    /// How far into the recording the terminal shows it.
+    position: Duration,
+    /// The index of the next event to be played.
+    next_event: usize,
+    playing: bool,
+    playback: Task<()>,
+    progress_bar_bounds: Rc<Cell<Option<Bounds<Pixels>>>>,
+}
+
+impl AsciicastPlayer {
+    fn open(
+        workspace: &mut Workspace,
+        _: &OpenRecording,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Open Recording".into()),
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let recording = cx
+                .background_spawn({
+                    let path = path.clone();
+                    async move {
+                        let text = std::fs::read_to_string(&path)
+                            .with_context(|| format!("reading {path:?}"))?;
+                        Recording::parse(&text)
+                    }
+                })
+                .await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let project = workspace.project().clone();
+                let builder = TerminalBuilder::new_display_only(
+                    CursorShape::default(),
+                    AlternateScroll::On,
+                    None,
+                    0,
+                    cx.background_executor(),
+                    project.read(cx).path_style(cx),
+                )?;
+                let terminal = cx.new(|cx| builder.subscribe(cx));
+                let workspace_handle = workspace.weak_handle();
+                let player = cx.new(|cx| {
+                    Self::new(
+                        recording,
+                        path,
+                        terminal,
+                        workspace_handle,
+                        project.downgrade(),
+                        window,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(Box::new(player), None, true, window, cx);
+                anyhow::Ok(())
+            })?
+        })
+        .detach_and_prompt_err("Failed to open the recording", window, cx, |_, _, _| None);
+    }
+
+    /// Creates a player for the recording, which starts playing it in the display-only terminal.
+    pub fn new(
+        recording: Recording,
+        path: PathBuf,
+        terminal: Entity<Terminal>,
+        workspace: WeakEntity<Workspace>,
+        project: WeakEntity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let terminal_view =
+            cx.new(|cx| TerminalView::new(terminal.clone(), workspace, None, project, window, cx));
+        let mut this = Self {
+            recording,
+            path,
+            terminal,
+            terminal_view,
+            focus_handle: cx.focus_handle(),
+            position: Duration::ZERO,
+            next_event: 0,
+            playing: true,
+            playback: Task::ready(()),
+            progress_bar_bounds: Rc::default(),
+        };
+        this.rewind(cx);
+        this.start_playback(cx);
+        this
+    }
+
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Shows the recording as it was at the given time, skipping over the events in between.
+    pub fn seek(&mut self, time: Duration, cx: &mut Context<Self>) {
+        let time = time.min(self.recording.duration());
+        if time < self.position {
+            self.rewind(cx);
+        }
+        self.advance_to(time, cx);
+        if self.playing {
+            self.start_playback(cx);
+        }
+    }
+
+    fn toggle_playback(&mut self, _: &TogglePlayback, _: &mut Window, cx: &mut Context<Self>) {
+        if self.playing {
+            self.playing = false;
+            self.playback = Task::ready(());
+        } else {
+            if self.position >= self.recording.duration() {
+                self.rewind(cx);
+            }
+            self.playing = true;
+            self.start_playback(cx);
+        }
+        cx.notify();
+    }
+
+    fn seek_forward(&mut self, _: &SeekForward, _: &mut Window, cx: &mut Context<Self>) {
+        self.seek(self.position + SEEK_STEP, cx);
+    }
+
+    fn seek_backward(&mut self, _: &SeekBackward, _: &mut Window, cx: &mut Context<Self>) {
+        self.seek(self.position.saturating_sub(SEEK_STEP), cx);
+    }
+
+    fn seek_to_start(&mut self, _: &SeekToStart, _: &mut Window, cx: &mut Context<Self>) {
+        self.seek(Duration::ZERO, cx);
+    }
+
+    fn seek_to_click(&mut self, event: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(bounds) = self.progress_bar_bounds.get() else {
+            return;
+        };
+        let fraction = ((event.position.x - bounds.left()) / bounds.size.width).clamp(0., 1.);
+        self.seek(self.recording.duration().mul_f32(fraction), cx);
+    }
+
+    /// Clears the terminal, to play the recording from its start.
+    fn rewind(&mut self, cx: &mut Context<Self>) {
+        let size = (
+            self.recording.header.width as usize,
+            self.recording.header.height as usize,
+        );
+        self.terminal.update(cx, |terminal, cx| {
+            terminal.reset_replay(cx);
+            terminal.set_fixed_size(Some(size));
+        });
+        self.position = Duration::ZERO;
+        self.next_event = 0;
+        cx.notify();
+    }
+
+    fn start_playback(&mut self, cx: &mut Context<Self>) {
+        let executor = cx.background_executor().clone();
+        let started_at = executor.now();
+        let started_from = self.position;
+        self.playback = cx.spawn(async move |this, cx| {
+            loop {
+                let position = started_from + executor.now().duration_since(started_at);
+                let Ok(Some(next_event_time)) = this.update(cx, |this, cx| {
+                    this.advance_to(position, cx);
+                    let next_event_time = this
+                        .recording
+                        .events
+                        .get(this.next_event)
+                        .map(|event| event.time);
+                    if next_event_time.is_none() {
+                        this.playing = false;
+                    }
+                    next_event_time
+                }) else {
+                    return;
+                };
+                let position = started_from + executor.now().duration_since(started_at);
+                let wait = next_event_time
+                    .saturating_sub(position)
+                    .min(PROGRESS_INTERVAL);
+                executor.timer(wait).await;
+            }
+        });
+    }
+
+    /// Plays the events up to the given time.
+    fn advance_to(&mut self, time: Duration, cx: &mut Context<Self>) {
+        let mut output = Vec::new();
+        while let Some(event) = self.recording.events.get(self.next_event)
+            && event.time <= time
+        {
+            match event.kind {
+                EventKind::Output => output.extend_from_slice(event.data.as_bytes()),
+                EventKind::Resize => {
+                    if let Some((columns, lines)) = event.size() {
+                        let output = std::mem::take(&mut output);
+                        self.terminal.update(cx, |terminal, cx| {
+                            terminal.replay_output(&output, cx);
+                            terminal.set_fixed_size(Some((columns as usize, lines as usize)));
+                        });
+                    }
+                }
+                EventKind::Input | EventKind::Marker => {}
+            }
+            self.next_event += 1;
+        }
+        if !output.is_empty() {
+            self.terminal
+                .update(cx, |terminal, cx| terminal.replay_output(&output, cx));
+        }
+        self.position = time.min(self.recording.duration());
+        cx.notify();
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+impl Render for AsciicastPlayer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let duration = self.recording.duration();
+        let playing = self.playing;
+        let progress_bar_bounds = self.progress_bar_bounds.clone();
+
+        v_flex()
+            .key_context("AsciicastPlayer")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::toggle_playback))
+            .on_action(cx.listener(Self::seek_forward))
+            .on_action(cx.listener(Self::seek_backward))
+            .on_action(cx.listener(Self::seek_to_start))
+            .child(div().flex_1().min_h_0().child(self.terminal_view.clone()))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        IconButton::new(
+                            "toggle-playback",
+                            if playing {
+                                IconName::DebugPause
+                            } else {
+                                IconName::PlayFilled
+                            },
+                        )
+                        .icon_size(IconSize::Small)
+                        .tooltip(move |_window, cx| {
+                            Tooltip::for_action(
+                                if playing { "Pause" } else { "Play" },
+                                &TogglePlayback,
+                                cx,
+                            )
+                        })
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.toggle_playback(&TogglePlayback, window, cx)
+                        })),
+                    )
+                    .child(
+                        IconButton::new("seek-backward", IconName::ArrowLeft)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|_window, cx| {
+                                Tooltip::for_action("Seek Backward", &SeekBackward, cx)
+                            })
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.seek_backward(&SeekBackward, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("seek-forward", IconName::ArrowRight)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|_window, cx| {
+                                Tooltip::for_action("Seek Forward", &SeekForward, cx)
+                            })
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.seek_forward(&SeekForward, window, cx)
+                            })),
+                    )
+                    .child(
+                        div()
+                            .id("playback-progress")
+                            .relative()
+                            .flex_1()
+                            .mx_1()
+                            .py_1()
+                            .cursor_pointer()
+                            .child(ProgressBar::new(
+                                "playback-progress-bar",
+                                self.position.as_secs_f32(),
+                                duration.as_secs_f32().max(f32::EPSILON),
+                                cx,
+                            ))
+                            .child(
+                                canvas(
+                                    move |bounds, _window, _cx| {
+                                        progress_bar_bounds.set(Some(bounds));
+                                    },
+                                    |_bounds, _state, _window, _cx| {},
+                                )
+                                .absolute()
+                                .top_0()
+                                .left_0()
+                                .size_full(),
+                            )
+                            .on_mouse_down(MouseButton::Left, cx.listener(Self::seek_to_click)),
+                    )
+                    .child(
+                        Label::new(format!(
+                            "{} / {}",
+                            format_time(self.position),
+                            format_time(duration)
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+    }
+}
+
+impl Focusable for AsciicastPlayer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<()> for AsciicastPlayer {}
+
+impl Item for AsciicastPlayer {
+    type Event = ();
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::PlayFilled))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "Recording".into())
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(self.path.to_string_lossy().into_owned().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn to_item_events(_event: &Self::Event, _f: impl FnMut(workspace::item::ItemEvent)) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use util::paths::PathStyle;
+
+    use crate::tests::init_test;
+
+    #[gpui::test]
+    async fn test_replaying_a_recording(cx: &mut TestAppContext) {
+        let (project, workspace) = init_test(cx).await;
+        let recording = Recording::parse(
+            r#"{"version": 2, "width": 20, "height": 4}
+[0.5, "o", "first\r\n"]
+[1.0, "r", "30x5"]
+[1.5, "o", "second\r\n"]
+"#,
+        )
+        .unwrap();
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                None,
+                0,
+                cx.background_executor(),
+                PathStyle::local(),
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+        let player = cx
+            .add_window(|window, cx| {
+                AsciicastPlayer::new(
+                    recording,
+                    PathBuf::from("/recordings/session.cast"),
+                    terminal,
+                    workspace.downgrade(),
+                    project.downgrade(),
+                    window,
+                    cx,
+                )
+            })
+            .root(cx)
+            .unwrap();
+
+        let content = |cx: &mut TestAppContext| {
+            player.update(cx, |player, cx| player.terminal.read(cx).get_content())
+        };
+        let size = |cx: &mut TestAppContext| {
+            player.update(cx, |player, cx| {
+                let bounds = player.terminal.read(cx).last_content().terminal_bounds;
+                (bounds.num_columns(), bounds.num_lines())
+            })
+        };
+        assert!(player.read_with(cx, |player, _| player.is_playing()));
+        assert_eq!(size(cx), (20, 4));
+
+        player.update(cx, |player, cx| player.seek(Duration::from_secs(1), cx));
+        assert!(content(cx).contains("first"));
+        assert!(!content(cx).contains("second"));
+        assert_eq!(size(cx), (30, 5));
+
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+        assert!(content(cx).contains("second"));
+        assert!(!player.read_with(cx, |player, _| player.is_playing()));
+        assert_eq!(
+            player.read_with(cx, |player, _| player.position()),
+            Duration::from_millis(1500)
+        );
+
+        // Seeking back replays the recording from its start.
+        player.update(cx, |player, cx| player.seek(Duration::from_millis(600), cx));
+        assert!(content(cx).contains("first"));
+        assert!(!content(cx).contains("second"));
+        assert_eq!(size(cx), (20, 4));
+    }
+}
@@ -1,19 +1,21 @@
+use collections::HashMap;
 use editor::{CursorLayout, EditorSettings, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle, FontWeight,
-    GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity,
-    IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point, px, relative,
-    size,
+    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners,
+    DispatchPhase, Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle,
+    FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle,
+    Styled, TextRun, TextStyle, UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window,
+    div, fill, point, px, relative, size,
 };
 use itertools::Itertools;
 use language::CursorShape;
 use settings::Settings;
 use std::time::Instant;
 use terminal::{
-    IndexedCell, Terminal, TerminalBounds, TerminalContent,
+    CommandStatus, IMAGE_PLACEHOLDER, ImageCell, IndexedCell, Terminal, TerminalBounds,
+    TerminalContent, TerminalImage,
     alacritty_terminal::{
         grid::Dimensions,
         index::Point as AlacPoint,
@@ -41,6 +43,7 @@ pub struct LayoutState {
     hitbox: Hitbox,
     batched_text_runs: Vec<BatchedTextRun>,
     rects: Vec<LayoutRect>,
+    images: Vec<LayoutImage>,
     relative_highlighted_ranges: Vec<(RangeInclusive<AlacPoint>, Hsla)>,
     cursor: Option<CursorLayout>,
     ime_cursor_bounds: Option<Bounds<Pixels>>,
@@ -50,6 +53,9 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// The displayed lines of the prompts of the commands marked by the shell, along with the
+    /// colors of their statuses.
+    command_marks: Vec<(i32, Hsla)>,
     block_below_cursor_element: Option<AnyElement>,
     base_text_style: TextStyle,
     content_mode: ContentMode,
@@ -197,6 +203,55 @@ impl LayoutRect {
     }
 }
 
+/// The cells of an image on a line, over which the part of the image they cover is painted.
+#[derive(Clone, Debug)]
+pub struct LayoutImage {
+    point: AlacPoint<i32, i32>,
+    num_of_cells: usize,
+    /// The cell of the image at `point`.
+    image_cell: ImageCell,
+    image: TerminalImage,
+}
+
+impl LayoutImage {
+    pub fn paint(&self, origin: Point<Pixels>, dimensions: &TerminalBounds, window: &mut Window) {
+        let position = |line: i32, column: i32| {
+            point(
+                origin.x + column as f32 * dimensions.cell_width,
+                origin.y + line as f32 * dimensions.line_height,
+            )
+        };
+        let cells = Bounds::new(
+            position(self.point.line, self.point.column),
+            size(
+                dimensions.cell_width * self.num_of_cells as f32,
+                dimensions.line_height,
+            ),
+        );
+        let image = Bounds::new(
+            position(
+                self.point.line - self.image_cell.line as i32,
+                self.point.column - self.image_cell.column as i32,
+            ),
+            size(
+                dimensions.cell_width * self.image.columns,
+                dimensions.line_height * self.image.lines,
+            ),
+        );
+        window.with_content_mask(Some(ContentMask { bounds: cells }), |window| {
+            window
+                .paint_image(
+                    image,
+                    Corners::default(),
+                    self.image.image.clone(),
+                    0,
+                    false,
+                )
+                .log_err();
+        });
+    }
+}
+
 /// Represents a rectangular region with a specific background color
 #[derive(Debug, Clone)]
 struct BackgroundRegion {
@@ -391,6 +446,12 @@ impl TerminalElement {
                     continue;
                 }
 
+                // Images are painted over the cells holding their placeholders instead
+                if cell.c == IMAGE_PLACEHOLDER {
+                    previous_cell_had_extras = false;
+                    continue;
+                }
+
                 // Skip spaces that follow cells with extras (emoji variation sequences)
                 if cell.c == ' ' && previous_cell_had_extras {
                     previous_cell_had_extras = false;
@@ -499,6 +560,48 @@ impl TerminalElement {
         (rects, batched_runs)
     }
 
+    /// Lays out the parts of the images covering the cells, line by line.
+    fn layout_images(
+        cells: &[IndexedCell],
+        images: &HashMap<u32, TerminalImage>,
+    ) -> Vec<LayoutImage> {
+        let mut layout_images = Vec::<LayoutImage>::new();
+        if images.is_empty() {
+            return layout_images;
+        }
+        let lines = cells.iter().chunk_by(|cell| cell.point.line);
+        for (line_index, (_, line)) in lines.into_iter().enumerate() {
+            for cell in line {
+                let Some(image_cell) = ImageCell::from_cell(cell) else {
+                    continue;
+                };
+                let Some(image) = images.get(&image_cell.image_id) else {
+                    continue;
+                };
+                let point = AlacPoint::new(line_index as i32, cell.point.column.0 as i32);
+
+                // Extend the previous cells if this one continues the image on the same line.
+                if let Some(last) = layout_images.last_mut()
+                    && last.point.line == point.line
+                    && last.point.column + last.num_of_cells as i32 == point.column
+                    && last.image_cell.image_id == image_cell.image_id
+                    && last.image_cell.line == image_cell.line
+                    && last.image_cell.column + last.num_of_cells == image_cell.column
+                {
+                    last.num_of_cells += 1;
+                } else {
+                    layout_images.push(LayoutImage {
+                        point,
+                        num_of_cells: 1,
+                        image_cell,
+                        image: image.clone(),
+                    });
+                }
+            }
+        }
+        layout_images
+    }
+
     /// Computes the cursor position and expected block width, may return a zero width if x_for_index returns
     /// the same position for sequential indexes. Use em_width instead
     fn shape_cursor(
@@ -1044,11 +1147,31 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_marks,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let command_marks = command_marks
+                    .iter()
+                    .filter_map(|mark| {
+                        let color = match mark.status {
+                            CommandStatus::Prompt => return None,
+                            CommandStatus::Running => theme.status().info,
+                            CommandStatus::Finished { exit_code: None } => {
+                                theme.colors().text_muted
+                            }
+                            CommandStatus::Finished { exit_code: Some(0) } => {
+                                theme.status().success
+                            }
+                            CommandStatus::Finished { .. } => theme.status().error,
+                        };
+                        Some((mark.line.0 + display_offset as i32, color))
+                    })
+                    .collect();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -1129,6 +1252,8 @@ impl Element for TerminalElement {
                     )
                 };
 
+                let images = TerminalElement::layout_images(cells, images);
+
                 // Layout cursor. Rectangle is used for IME, so we should lay it out even
                 // if we don't end up showing it.
                 let cursor_point = DisplayCursor::from(cursor.point, display_offset);
@@ -1221,11 +1346,13 @@ impl Element for TerminalElement {
                     background_color,
                     dimensions,
                     rects,
+                    images,
                     relative_highlighted_ranges,
                     mode,
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    command_marks,
                     block_below_cursor_element,
                     base_text_style: text_style,
                     content_mode,
@@ -1309,6 +1436,23 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    for image in &layout.images {
+                        image.paint(origin, &layout.dimensions, window);
+                    }
+
+                    // Command marks go in the gutter, left of the prompts.
+                    let mark_width = (layout.gutter / 3.).max(px(1.));
+                    for (line, color) in &layout.command_marks {
+                        let position = point(
+                            bounds.origin.x + mark_width,
+                            origin.y + *line as f32 * layout.dimensions.line_height,
+                        );
+                        window.paint_quad(fill(
+                            Bounds::new(position, size(mark_width, layout.dimensions.line_height)),
+                            *color,
+                        ));
+                    }
+
                     for (relative_highlighted_range, color) in &layout.relative_highlighted_ranges {
                         if let Some((start_y, highlighted_range_lines)) =
                             to_highlighted_range_lines(relative_highlighted_range, layout, origin)
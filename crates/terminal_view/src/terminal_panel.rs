@@ -19,7 +19,7 @@ use itertools::Itertools;
 use project::{Fs, Project, ProjectEntryId};
 use search::{BufferSearchBar, buffer_search::DivRegistrar};
 use settings::{Settings, TerminalDockPosition};
-use task::{RevealStrategy, RevealTarget, Shell, ShellBuilder, SpawnInTerminal, TaskId};
+use task::{Problem, RevealStrategy, RevealTarget, Shell, ShellBuilder, SpawnInTerminal, TaskId};
 use terminal::{Terminal, terminal_settings::TerminalSettings};
 use ui::{
     ButtonLike, Clickable, ContextMenu, FluentBuilder, PopoverMenu, SplitButton, Toggleable,
@@ -627,6 +627,28 @@ impl TerminalPanel {
         cx.spawn(async move |_, _| rx.await?)
     }
 
+    /// Replaces the diagnostics the task reported in its previous run with the given problems, found in its output.
+    fn update_task_diagnostics(
+        &self,
+        task: &SpawnInTerminal,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let project = workspace.read(cx).project().read(cx);
+        if !project.is_local() {
+            return;
+        }
+        let lsp_store = project.lsp_store();
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_task_diagnostics(&task.full_label, problems, cx)
+                .log_err();
+        });
+    }
+
     fn spawn_in_new_terminal(
         &mut self,
         spawn_task: SpawnInTerminal,
@@ -679,6 +701,24 @@ impl TerminalPanel {
         &self,
         label: &str,
         cx: &mut App,
+    ) -> Vec<(usize, Entity<Pane>, Entity<TerminalView>)> {
+        self.terminals_matching(|task| task.full_label == label, cx)
+    }
+
+    fn kill_task(&mut self, task_id: &TaskId, cx: &mut Context<Self>) {
+        for (_, _, terminal_view) in self.terminals_matching(|task| &task.id == task_id, cx) {
+            terminal_view.update(cx, |terminal_view, cx| {
+                terminal_view
+                    .terminal()
+                    .update(cx, |terminal, _| terminal.kill_active_task())
+            });
+        }
+    }
+
+    fn terminals_matching(
+        &self,
+        predicate: impl Fn(&SpawnInTerminal) -> bool,
+        cx: &mut App,
     ) -> Vec<(usize, Entity<Pane>, Entity<TerminalView>)> {
         let Some(workspace) = self.workspace.upgrade() else {
             return Vec::new();
@@ -691,7 +731,7 @@ impl TerminalPanel {
                 .filter_map(|(index, item)| Some((index, item.act_as::<TerminalView>(cx)?)))
                 .filter_map(|(index, terminal_view)| {
                     let task_state = terminal_view.read(cx).terminal().read(cx).task()?;
-                    if &task_state.spawned_task.full_label == label {
+                    if predicate(&task_state.spawned_task) {
                         Some((index, terminal_view))
                     } else {
                         None
@@ -1780,6 +1820,14 @@ impl workspace::TerminalProvider for TerminalProvider {
     ) -> Task<Option<Result<ExitStatus>>> {
         let terminal_panel = self.0.clone();
         window.spawn(cx, async move |cx| {
+            let reports_problems = !task.problem_matchers.is_empty();
+            if reports_problems {
+                terminal_panel
+                    .update(cx, |terminal_panel, cx| {
+                        terminal_panel.update_task_diagnostics(&task, Vec::new(), cx)
+                    })
+                    .ok();
+            }
             let terminal = terminal_panel
                 .update_in(cx, |terminal_panel, window, cx| {
                     terminal_panel.spawn_task(&task, window, cx)
@@ -1792,12 +1840,41 @@ impl workspace::TerminalProvider for TerminalProvider {
                         .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
                         .ok()?
                         .await?;
+                    if reports_problems {
+                        let output = terminal
+                            .read_with(cx, |terminal, _| terminal.get_content())
+                            .ok()?;
+                        let problem_matchers = task.problem_matchers.clone();
+                        let cwd = task.cwd.clone();
+                        let problems = cx
+                            .background_spawn(async move {
+                                problem_matchers
+                                    .iter()
+                                    .filter_map(|matcher| {
+                                        matcher.find_problems(&output, cwd.as_deref()).log_err()
+                                    })
+                                    .flatten()
+                                    .collect::<Vec<_>>()
+                            })
+                            .await;
+                        terminal_panel
+                            .update(cx, |terminal_panel, cx| {
+                                terminal_panel.update_task_diagnostics(&task, problems, cx)
+                            })
+                            .ok();
+                    }
                     Some(Ok(exit_status))
                 }
                 Err(e) => Some(Err(e)),
             }
         })
     }
+
+    fn kill(&self, task_id: &TaskId, _: &mut Window, cx: &mut App) {
+        self.0.update(cx, |terminal_panel, cx| {
+            terminal_panel.kill_task(task_id, cx)
+        });
+    }
 }
 
 struct InlineAssistTabBarButton {
@@ -1825,8 +1902,11 @@ mod tests {
     use super::*;
     use gpui::{TestAppContext, UpdateGlobal as _};
     use pretty_assertions::assert_eq;
-    use project::FakeFs;
+    use project::{FakeFs, lsp_store::task_diagnostics::TASK_DIAGNOSTICS_SERVER_ID};
+    use serde_json::json;
     use settings::SettingsStore;
+    use task::{ProblemMatcher, ProblemPattern, ProblemSeverity};
+    use util::path;
 
     #[test]
     fn test_prepare_empty_task() {
@@ -2011,6 +2091,95 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_rerunning_task_clears_its_diagnostics(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.rs": "one two three" }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let workspace =
+            cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let (window_handle, terminal_panel) = workspace
+            .update(cx, |workspace, window, cx| {
+                let window_handle = window.window_handle();
+                let terminal_panel = cx.new(|cx| TerminalPanel::new(workspace, window, cx));
+                (window_handle, terminal_panel)
+            })
+            .unwrap();
+
+        let task_error_count = |cx: &mut TestAppContext| {
+            project.read_with(cx, |project, cx| {
+                project
+                    .diagnostic_summaries(false, cx)
+                    .filter(|(_, server_id, _)| *server_id == TASK_DIAGNOSTICS_SERVER_ID)
+                    .map(|(_, _, summary)| summary.error_count)
+                    .sum::<usize>()
+            })
+        };
+
+        // Diagnostics reported by the previous run of the task.
+        let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_task_diagnostics(
+                    "check",
+                    vec![Problem {
+                        file: PathBuf::from(path!("/dir/a.rs")),
+                        line: 1,
+                        column: None,
+                        end_line: None,
+                        end_column: None,
+                        severity: ProblemSeverity::Error,
+                        code: None,
+                        message: "mismatched types".to_string(),
+                    }],
+                    cx,
+                )
+                .unwrap();
+        });
+        assert_eq!(task_error_count(cx), 1);
+
+        let task = SpawnInTerminal {
+            id: TaskId("check".to_string()),
+            label: "check".to_string(),
+            full_label: "check".to_string(),
+            command: Some("true".to_string()),
+            problem_matchers: vec![ProblemMatcher {
+                pattern: vec![ProblemPattern {
+                    regexp: r"^(.*):(\d+): (.*)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                }],
+                ..ProblemMatcher::default()
+            }],
+            ..SpawnInTerminal::default()
+        };
+        window_handle
+            .update(cx, |_, window, cx| {
+                workspace::TerminalProvider::spawn(
+                    &TerminalProvider(terminal_panel.clone()),
+                    task,
+                    window,
+                    cx,
+                )
+            })
+            .unwrap()
+            .detach();
+        cx.run_until_parked();
+
+        assert_eq!(
+            task_error_count(cx),
+            0,
+            "re-running a task should clear the diagnostics of its previous run"
+        );
+    }
+
     fn set_max_tabs(cx: &mut TestAppContext, value: Option<usize>) {
         cx.update_global(|store: &mut SettingsStore, cx| {
             store.update_user_settings(cx, |settings| {
@@ -1,3 +1,4 @@
+pub mod asciicast_player;
 mod persistence;
 pub mod terminal_element;
 pub mod terminal_panel;
@@ -21,9 +22,11 @@ use serde::Deserialize;
 use settings::{Settings, SettingsStore, TerminalBlink, WorkingDirectory};
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
-    TaskStatus, Terminal, TerminalBounds, ToggleViMode,
+    Clear, Copy, CopyLastCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste,
+    ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom,
+    ScrollToNextCommand, ScrollToPreviousCommand, ScrollToTop, SelectLastCommandOutput,
+    ShowCharacterPalette, TaskState, TaskStatus, Terminal, TerminalBounds, ToggleRecording,
+    ToggleViMode,
     alacritty_terminal::{
         index::Point as AlacPoint,
         term::{TermMode, point_to_viewport, search::RegexSearch},
@@ -47,6 +50,7 @@ use workspace::{
     item::{
         BreadcrumbText, Item, ItemEvent, SerializableItem, TabContentParams, TabTooltipContent,
     },
+    notifications::DetachAndPromptErr,
     register_serializable_item,
     searchable::{Direction, SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
 };
@@ -66,6 +70,8 @@ struct ImeState {
 }
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+/// How much of each terminal's scrollback is saved when `persist_scrollback` is on.
+const MAX_PERSISTED_SCROLLBACK_LEN: usize = 1024 * 1024;
 
 /// Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -97,6 +103,7 @@ pub struct RenameTerminal;
 pub fn init(cx: &mut App) {
     assistant_slash_command::init(cx);
     terminal_panel::init(cx);
+    asciicast_player::init(cx);
 
     register_serializable_item::<TerminalView>(cx);
 
@@ -264,6 +271,7 @@ impl TerminalView {
             focus_out,
             cx.observe(&blink_manager, |_, _, cx| cx.notify()),
             cx.observe_global::<SettingsStore>(Self::settings_changed),
+            cx.on_app_quit(Self::save_scrollback),
         ];
 
         Self {
@@ -496,6 +504,8 @@ impl TerminalView {
             .upgrade()
             .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
             .is_some_and(|terminal_panel| terminal_panel.read(cx).assistant_enabled());
+        let can_record = self.terminal.read(cx).can_record();
+        let is_recording = self.terminal.read(cx).is_recording();
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal::default()))
@@ -504,6 +514,16 @@ impl TerminalView {
                 .action("Paste", Box::new(Paste))
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
+                .when(can_record, |menu| {
+                    menu.action(
+                        if is_recording {
+                            "Stop Recording"
+                        } else {
+                            "Start Recording"
+                        },
+                        Box::new(ToggleRecording),
+                    )
+                })
                 .when(assistant_enabled, |menu| {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
@@ -536,6 +556,30 @@ impl TerminalView {
         self.context_menu = Some((context_menu, position, subscription));
     }
 
+    fn save_scrollback(&mut self, cx: &mut Context<Self>) -> impl Future<Output = ()> + use<> {
+        let terminal = self.terminal.read(cx);
+        let scrollback = self
+            .workspace_id
+            .filter(|_| {
+                TerminalSettings::get_global(cx).persist_scrollback && terminal.task().is_none()
+            })
+            .map(|workspace_id| {
+                (
+                    workspace_id,
+                    terminal.serialize_scrollback(MAX_PERSISTED_SCROLLBACK_LEN),
+                )
+            });
+        let item_id = cx.entity_id().as_u64();
+        async move {
+            if let Some((workspace_id, scrollback)) = scrollback {
+                TERMINAL_DB
+                    .save_scrollback(item_id, workspace_id, scrollback)
+                    .await
+                    .log_err();
+            }
+        }
+    }
+
     fn settings_changed(&mut self, cx: &mut Context<Self>) {
         let settings = TerminalSettings::get_global(cx);
         let breadcrumb_visibility_changed = self.show_breadcrumbs != settings.toolbar.breadcrumbs;
@@ -735,6 +779,99 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_command(
+        &mut self,
+        _: &ScrollToPreviousCommand,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_command());
+        cx.notify();
+    }
+
+    fn scroll_to_next_command(
+        &mut self,
+        _: &ScrollToNextCommand,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_command());
+        cx.notify();
+    }
+
+    fn select_last_command_output(
+        &mut self,
+        _: &SelectLastCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.select_last_command_output());
+        cx.notify();
+    }
+
+    fn copy_last_command_output(
+        &mut self,
+        _: &CopyLastCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.copy_last_command_output());
+        cx.notify();
+    }
+
+    fn toggle_recording(
+        &mut self,
+        _: &ToggleRecording,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.terminal.read(cx).is_recording() {
+            let stopped = self
+                .terminal
+                .update(cx, |terminal, _| terminal.stop_recording());
+            cx.emit(ItemEvent::UpdateTab);
+            stopped.detach_and_prompt_err(
+                "Failed to save the terminal recording",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+            return;
+        }
+
+        let directory = self
+            .terminal
+            .read(cx)
+            .working_directory()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default();
+        let path = cx.prompt_for_new_path(&directory, Some("terminal.cast"));
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            let file = cx
+                .background_spawn(async move { std::fs::File::create(path) })
+                .await?;
+            this.update(cx, |this, cx| {
+                this.terminal
+                    .update(cx, |terminal, _| terminal.start_recording(file))?;
+                cx.emit(ItemEvent::UpdateTab);
+                anyhow::Ok(())
+            })?
+        })
+        .detach_and_prompt_err(
+            "Failed to start recording the terminal",
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -1075,6 +1212,10 @@ fn subscribe_for_terminal_events(
                     window.invalidate_character_coordinates();
                     cx.emit(SearchEvent::ActiveMatchChanged)
                 }
+                Event::CommandFinished { .. } => {
+                    cx.notify();
+                    cx.emit(ItemEvent::UpdateTab);
+                }
             }
         },
     );
@@ -1209,6 +1350,11 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_command))
+            .on_action(cx.listener(TerminalView::scroll_to_next_command))
+            .on_action(cx.listener(TerminalView::select_last_command_output))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
+            .on_action(cx.listener(TerminalView::toggle_recording))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
@@ -1279,6 +1425,7 @@ impl Item for TerminalView {
             let terminal = self.terminal().read(cx);
             let title = terminal.title(false);
             let pid = terminal.pid_getter()?.fallback_pid();
+            let last_exit_code = terminal.last_command_exit_code();
 
             move |_, _| {
                 v_flex()
@@ -1290,6 +1437,17 @@ impl Item for TerminalView {
                             .color(Color::Muted)
                             .size(LabelSize::Small),
                     )
+                    .when_some(last_exit_code, |this, exit_code| {
+                        this.child(
+                            Label::new(format!("Last command exit code: {exit_code}"))
+                                .color(if exit_code == 0 {
+                                    Color::Muted
+                                } else {
+                                    Color::Error
+                                })
+                                .size(LabelSize::Small),
+                        )
+                    })
                     .into_any_element()
             }
         }))))
@@ -1326,6 +1484,7 @@ impl Item for TerminalView {
                     }
                 }
             },
+            None if terminal.is_recording() => (IconName::Circle, Color::Error, None),
             None => (IconName::Terminal, Color::Muted, None),
         };
 
@@ -1582,7 +1741,7 @@ impl SerializableItem for TerminalView {
         cx: &mut App,
     ) -> Task<anyhow::Result<Entity<Self>>> {
         window.spawn(cx, async move |cx| {
-            let (cwd, custom_title) = cx
+            let (cwd, custom_title, scrollback) = cx
                 .update(|_window, cx| {
                     let from_db = TERMINAL_DB
                         .get_working_directory(item_id, workspace_id)
@@ -1603,13 +1762,25 @@ impl SerializableItem for TerminalView {
                         .log_err()
                         .flatten()
                         .filter(|title| !title.trim().is_empty());
-                    (cwd, custom_title)
+                    let scrollback = TerminalSettings::get_global(cx)
+                        .persist_scrollback
+                        .then(|| {
+                            TERMINAL_DB
+                                .get_scrollback(item_id, workspace_id)
+                                .log_err()
+                                .flatten()
+                        })
+                        .flatten()
+                        .filter(|scrollback| !scrollback.is_empty());
+                    (cwd, custom_title, scrollback)
                 })
                 .ok()
-                .unwrap_or((None, None));
+                .unwrap_or((None, None, None));
 
             let terminal = project
-                .update(cx, |project, cx| project.create_terminal_shell(cwd, cx))
+                .update(cx, |project, cx| {
+                    project.restore_terminal_shell(cwd, scrollback, cx)
+                })
                 .await?;
             cx.update(|window, cx| {
                 cx.new(|cx| {
@@ -0,0 +1,413 @@
+//! Vim's argument list, a list of files to edit, and the `:argdo`, `:bufdo` and `:windo` commands
+//! that run ex commands across files.
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashSet;
+use editor::Editor;
+use gpui::{Action, App, Context, Entity, Window};
+use itertools::Itertools as _;
+use project::ProjectPath;
+use util::paths::PathMatcher;
+use workspace::{
+    Toast, Workspace,
+    item::ItemHandle as _,
+    notifications::{NotificationId, NotifyTaskExt as _},
+};
+
+use crate::{
+    Vim,
+    command::{parse_ex_commands, run_ex_commands},
+};
+
+/// Shows the files of the argument list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct ShowArgList;
+
+/// Replaces the argument list with the given files, and edits the first one.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct SetArgList {
+    pub(crate) patterns: Vec<String>,
+}
+
+/// Adds the given files, or the current one, to the argument list after its current file.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct AddToArgList {
+    pub(crate) patterns: Vec<String>,
+}
+
+/// Removes the files matching the given patterns from the argument list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct DeleteFromArgList {
+    pub(crate) patterns: Vec<String>,
+}
+
+/// Runs an ex command in each file of the argument list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct ArgDo {
+    pub(crate) command: String,
+}
+
+/// Runs an ex command in each open file.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct BufDo {
+    pub(crate) command: String,
+}
+
+/// Runs an ex command in the active editor of each pane.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct WinDo {
+    pub(crate) command: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ArgList {
+    pub paths: Vec<ProjectPath>,
+    /// The index of the file last edited from the list.
+    pub current: usize,
+}
+
+/// Where `:argdo`, `:bufdo` and `:windo` run their command.
+enum Target {
+    /// The file at the given index of the argument list.
+    Arg(usize, ProjectPath),
+    Editor(Entity<Editor>),
+}
+
+pub(crate) fn register(
+    workspace: &mut Workspace,
+    _: Option<&mut Window>,
+    _: &mut Context<Workspace>,
+) {
+    workspace.register_action(|workspace, _: &ShowArgList, _, cx| {
+        show_arg_list(workspace, cx);
+    });
+    workspace.register_action(|workspace, action: &SetArgList, window, cx| {
+        if action.patterns.is_empty() {
+            show_arg_list(workspace, cx);
+            return;
+        }
+        let paths = match expand_patterns(workspace, &action.patterns, cx) {
+            Ok(paths) => paths,
+            Err(err) => {
+                workspace.show_error(&err, cx);
+                return;
+            }
+        };
+        let first = paths.first().cloned();
+        let workspace_id = cx.entity_id();
+        Vim::globals(cx)
+            .arg_lists
+            .insert(workspace_id, ArgList { paths, current: 0 });
+        if let Some(path) = first {
+            workspace
+                .open_path(path, None, true, window, cx)
+                .detach_and_notify_err(window, cx);
+        }
+    });
+    workspace.register_action(|workspace, action: &AddToArgList, _, cx| {
+        let paths = if action.patterns.is_empty() {
+            workspace
+                .active_item(cx)
+                .and_then(|item| item.project_path(cx))
+                .into_iter()
+                .collect()
+        } else {
+            match expand_patterns(workspace, &action.patterns, cx) {
+                Ok(paths) => paths,
+                Err(err) => {
+                    workspace.show_error(&err, cx);
+                    return;
+                }
+            }
+        };
+        let workspace_id = cx.entity_id();
+        let arg_list = Vim::globals(cx).arg_lists.entry(workspace_id).or_default();
+        let index = (arg_list.current + 1).min(arg_list.paths.len());
+        let new_paths = paths
+            .into_iter()
+            .filter(|path| !arg_list.paths.contains(path))
+            .collect::<Vec<_>>();
+        arg_list.paths.splice(index..index, new_paths);
+    });
+    workspace.register_action(|workspace, action: &DeleteFromArgList, _, cx| {
+        let path_style = workspace.project().read(cx).path_style(cx);
+        let matcher = match PathMatcher::new(&action.patterns, path_style) {
+            Ok(matcher) => matcher,
+            Err(err) => {
+                workspace.show_error(&err, cx);
+                return;
+            }
+        };
+        let workspace_id = cx.entity_id();
+        if let Some(arg_list) = Vim::globals(cx).arg_lists.get_mut(&workspace_id) {
+            arg_list.paths.retain(|path| !matcher.is_match(&path.path));
+            arg_list.current = arg_list.current.min(arg_list.paths.len().saturating_sub(1));
+        }
+    });
+    workspace.register_action(|workspace, action: &ArgDo, window, cx| {
+        let workspace_id = cx.entity_id();
+        let targets = Vim::globals(cx)
+            .arg_lists
+            .get(&workspace_id)
+            .map(|arg_list| {
+                arg_list
+                    .paths
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .map(|(index, path)| Target::Arg(index, path))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if targets.is_empty() {
+            workspace.show_error(&anyhow!("The argument list is empty"), cx);
+            return;
+        }
+        run_in_targets(workspace, targets, &action.command, window, cx);
+    });
+    workspace.register_action(|workspace, action: &BufDo, window, cx| {
+        let mut buffers = HashSet::default();
+        let targets = workspace
+            .items_of_type::<Editor>(cx)
+            .filter(|editor| {
+                editor
+                    .read(cx)
+                    .buffer()
+                    .read(cx)
+                    .as_singleton()
+                    .is_some_and(|buffer| buffers.insert(buffer.entity_id()))
+            })
+            .map(Target::Editor)
+            .collect();
+        run_in_targets(workspace, targets, &action.command, window, cx);
+    });
+    workspace.register_action(|workspace, action: &WinDo, window, cx| {
+        let targets = workspace
+            .panes()
+            .iter()
+            .filter_map(|pane| pane.read(cx).active_item()?.downcast::<Editor>())
+            .map(Target::Editor)
+            .collect();
+        run_in_targets(workspace, targets, &action.command, window, cx);
+    });
+}
+
+fn show_arg_list(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let path_style = workspace.project().read(cx).path_style(cx);
+    let workspace_id = cx.entity_id();
+    let message = match Vim::globals(cx).arg_lists.get(&workspace_id) {
+        Some(arg_list) if !arg_list.paths.is_empty() => arg_list
+            .paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                let path = path.path.display(path_style);
+                if index == arg_list.current {
+                    format!("[{path}]")
+                } else {
+                    path.into_owned()
+                }
+            })
+            .join(" "),
+        _ => "The argument list is empty".to_string(),
+    };
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<ShowArgList>(), message),
+        cx,
+    );
+}
+
+/// Expands the arguments of `:args` and `:argadd` to the project's files: `%` stands for the
+/// current file, and globs are matched against the paths in each worktree.
+fn expand_patterns(
+    workspace: &Workspace,
+    patterns: &[String],
+    cx: &App,
+) -> Result<Vec<ProjectPath>> {
+    let project = workspace.project().read(cx);
+    let path_style = project.path_style(cx);
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        if pattern == "%" {
+            paths.push(
+                workspace
+                    .active_item(cx)
+                    .and_then(|item| item.project_path(cx))
+                    .context("No file is open")?,
+            );
+        } else if pattern.contains(['*', '?', '[', '{']) {
+            let matcher = PathMatcher::new([pattern], path_style)?;
+            let matched = paths.len();
+            for worktree in project.visible_worktrees(cx) {
+                let worktree = worktree.read(cx);
+                paths.extend(
+                    worktree
+                        .files(false, 0)
+                        .filter(|entry| matcher.is_match(&entry.path))
+                        .map(|entry| ProjectPath {
+                            worktree_id: worktree.id(),
+                            path: entry.path.clone(),
+                        }),
+                );
+            }
+            anyhow::ensure!(paths.len() > matched, "No match: {pattern}");
+        } else {
+            paths.push(
+                project
+                    .find_project_path(pattern, cx)
+                    .with_context(|| format!("Can't find file \"{pattern}\" in the project"))?,
+            );
+        }
+    }
+    Ok(paths.into_iter().unique().collect())
+}
+
+fn run_in_targets(
+    workspace: &mut Workspace,
+    targets: Vec<Target>,
+    command: &str,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let workspace_id = cx.entity_id();
+    let pane = workspace.active_pane().downgrade();
+    let commands = parse_ex_commands(command, cx.weak_entity(), cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let commands = commands.await?;
+        for target in targets {
+            let editor = match target {
+                Target::Arg(index, path) => {
+                    let item = workspace
+                        .update_in(cx, |workspace, window, cx| {
+                            Vim::update_globals(cx, |globals, _| {
+                                if let Some(arg_list) = globals.arg_lists.get_mut(&workspace_id) {
+                                    arg_list.current = index;
+                                }
+                            });
+                            workspace.open_path(path, Some(pane.clone()), true, window, cx)
+                        })?
+                        .await?;
+                    cx.update(|_, cx| item.act_as::<Editor>(cx))?
+                        .context("The argument isn't a text file")?
+                }
+                Target::Editor(editor) => {
+                    let activated = workspace.update_in(cx, |workspace, window, cx| {
+                        workspace.activate_item(&editor, true, true, window, cx)
+                    })?;
+                    // An earlier command might have closed it.
+                    if !activated {
+                        continue;
+                    }
+                    editor
+                }
+            };
+            run_ex_commands(&editor, &commands, cx).await?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_notify_err(window, cx);
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use gpui::TestAppContext;
+    use util::path;
+
+    use super::*;
+    use crate::test::VimTestContext;
+
+    fn editor_texts(cx: &mut VimTestContext) -> Vec<String> {
+        cx.workspace(|workspace, _, cx| {
+            workspace
+                .items_of_type::<Editor>(cx)
+                .map(|editor| editor.read(cx).text(cx))
+                .sorted()
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_argdo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), "foo\nbar foo\n".into())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), "foo\n".into())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/c.txt"), "foo\n".into())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": a r g s space d i r / ? . r s enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, _, cx| {
+            let arg_list = &Vim::globals(cx).arg_lists[&cx.entity_id()];
+            assert_eq!(arg_list.paths.len(), 2);
+            let editor = workspace.active_item_as::<Editor>(cx).unwrap();
+            assert_eq!(editor.read(cx).text(cx), "foo\nbar foo\n");
+        });
+
+        cx.simulate_keystrokes(": a r g a d d space d i r / c . t x t enter");
+        cx.simulate_keystrokes(": a r g d space d i r / b . r s enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(
+            ": a r g d o space % s / f o o / b a z / space | space u p d a t e enter",
+        );
+        cx.run_until_parked();
+        assert_eq!(
+            fs.load(Path::new(path!("/root/dir/a.rs"))).await.unwrap(),
+            "baz\nbar baz\n"
+        );
+        assert_eq!(
+            fs.load(Path::new(path!("/root/dir/b.rs"))).await.unwrap(),
+            "foo\n"
+        );
+        assert_eq!(
+            fs.load(Path::new(path!("/root/dir/c.txt"))).await.unwrap(),
+            "baz\n"
+        );
+        cx.workspace(|_, _, cx| {
+            assert_eq!(Vim::globals(cx).arg_lists[&cx.entity_id()].current, 1);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_bufdo_and_windo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), "one\n".into())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), "two\n".into())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": e space d i r / a . r s enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": v s p l i t space d i r / b . r s enter");
+        cx.run_until_parked();
+        assert_eq!(editor_texts(&mut cx), ["", "one\n", "two\n"]);
+
+        cx.simulate_keystrokes(": b u f d o space n o r m space A x enter");
+        cx.run_until_parked();
+        assert_eq!(editor_texts(&mut cx), ["x", "onex\n", "twox\n"]);
+
+        // Only the active editor of each pane is changed, which is the last one `:bufdo` edited.
+        cx.simulate_keystrokes(": w i n d o space n o r m space I y enter");
+        cx.run_until_parked();
+        assert_eq!(editor_texts(&mut cx), ["x", "yonex\n", "ytwox\n"]);
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
 use command_palette_hooks::{CommandInterceptItem, CommandInterceptResult};
 use editor::{
@@ -8,7 +8,8 @@ use editor::{
 };
 use futures::AsyncWriteExt as _;
 use gpui::{
-    Action, App, AppContext as _, Context, Global, Keystroke, Task, WeakEntity, Window, actions,
+    Action, App, AppContext as _, AsyncWindowContext, Context, Entity, Global, Keystroke, Task,
+    WeakEntity, Window, actions,
 };
 use itertools::Itertools;
 use language::Point;
@@ -40,13 +41,19 @@ use workspace::{SplitDirection, notifications::DetachAndPromptErr};
 use zed_actions::{OpenDocs, RevealTarget};
 
 use crate::{
-    ToggleMarksView, ToggleRegistersView, Vim, VimSettings,
+    ToggleMarksView, ToggleRegistersView, Vim, VimAddon, VimSettings,
+    arg_list::{AddToArgList, ArgDo, BufDo, DeleteFromArgList, SetArgList, ShowArgList, WinDo},
+    mapping::{MapMode, SaveMappings, VimAbbreviate, VimMap, VimUnabbreviate, VimUnmap},
     motion::{EndOfDocument, Motion, MotionKind, StartOfDocument},
     normal::{
         JoinLines,
         search::{FindCommand, ReplaceCommand, Replacement},
     },
     object::Object,
+    quickfix::{
+        CloseQuickfixList, EntryTarget, GoToQuickfixEntry, GrepArgs, ListKind, OpenQuickfixList,
+        QuickfixDo, QuickfixFromDiagnostics, QuickfixFromProjectSearch, VimGrep, VimMake,
+    },
     state::{Mark, Mode},
     visual::VisualDeleteLine,
 };
@@ -775,83 +782,8 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     });
 
     Vim::action(editor, cx, |vim, action: &VimNorm, window, cx| {
-        let keystrokes = action
-            .command
-            .chars()
-            .map(|c| Keystroke::parse(&c.to_string()).unwrap())
-            .collect();
-        vim.switch_mode(Mode::Normal, true, window, cx);
-        if let Some(override_rows) = &action.override_rows {
-            vim.update_editor(cx, |_, editor, cx| {
-                editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
-                    s.replace_cursors_with(|map| {
-                        override_rows
-                            .iter()
-                            .map(|row| Point::new(*row, 0).to_display_point(map))
-                            .collect()
-                    });
-                });
-            });
-        } else if let Some(range) = &action.range {
-            let result = vim.update_editor(cx, |vim, editor, cx| {
-                let range = range.buffer_range(vim, editor, window, cx)?;
-                editor.change_selections(
-                    SelectionEffects::no_scroll().nav_history(false),
-                    window,
-                    cx,
-                    |s| {
-                        s.select_ranges(
-                            (range.start.0..=range.end.0)
-                                .map(|line| Point::new(line, 0)..Point::new(line, 0)),
-                        );
-                    },
-                );
-                anyhow::Ok(())
-            });
-            if let Some(Err(err)) = result {
-                log::error!("Error selecting range: {}", err);
-                return;
-            }
-        };
-
-        let Some(workspace) = vim.workspace(window) else {
-            return;
-        };
-        let task = workspace.update(cx, |workspace, cx| {
-            workspace.send_keystrokes_impl(keystrokes, window, cx)
-        });
-        let had_range = action.range.is_some();
-        let had_override = action.override_rows.is_some();
-
-        cx.spawn_in(window, async move |vim, cx| {
-            task.await;
-            vim.update_in(cx, |vim, window, cx| {
-                if matches!(vim.mode, Mode::Insert | Mode::Replace) {
-                    vim.normal_before(&Default::default(), window, cx);
-                } else {
-                    vim.switch_mode(Mode::Normal, true, window, cx);
-                }
-                if had_override || had_range {
-                    vim.update_editor(cx, |_, editor, cx| {
-                        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
-                            s.select_anchor_ranges([s.newest_anchor().range()]);
-                        });
-                        if let Some(tx_id) = editor
-                            .buffer()
-                            .update(cx, |multi, cx| multi.last_transaction_id(cx))
-                        {
-                            let last_sel = editor.selections.disjoint_anchors_arc();
-                            editor.modify_transaction_selection_history(tx_id, |old| {
-                                old.0 = old.0.get(..1).unwrap_or(&[]).into();
-                                old.1 = Some(last_sel);
-                            });
-                        }
-                    });
-                }
-            })
-            .log_err();
-        })
-        .detach();
+        vim.normal_command(action, window, cx)
+            .detach_and_log_err(cx)
     });
 
     Vim::action(editor, cx, |vim, _: &CountCommand, window, cx| {
@@ -1014,6 +946,198 @@ struct ParsedQuery {
     has_space: bool,
 }
 
+impl Vim {
+    /// Runs a `:normal` command, resolving once its keystrokes have been handled.
+    fn normal_command(
+        &mut self,
+        action: &VimNorm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let keystrokes = action
+            .command
+            .chars()
+            .map(|c| Keystroke::parse(&c.to_string()).unwrap())
+            .collect();
+        self.switch_mode(Mode::Normal, true, window, cx);
+        if let Some(override_rows) = &action.override_rows {
+            self.update_editor(cx, |_, editor, cx| {
+                editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                    s.replace_cursors_with(|map| {
+                        override_rows
+                            .iter()
+                            .map(|row| Point::new(*row, 0).to_display_point(map))
+                            .collect()
+                    });
+                });
+            });
+        } else if let Some(range) = &action.range {
+            let result = self.update_editor(cx, |vim, editor, cx| {
+                let range = range.buffer_range(vim, editor, window, cx)?;
+                editor.change_selections(
+                    SelectionEffects::no_scroll().nav_history(false),
+                    window,
+                    cx,
+                    |s| {
+                        s.select_ranges(
+                            (range.start.0..=range.end.0)
+                                .map(|line| Point::new(line, 0)..Point::new(line, 0)),
+                        );
+                    },
+                );
+                anyhow::Ok(())
+            });
+            if let Some(Err(err)) = result {
+                log::error!("Error selecting range: {}", err);
+                return Task::ready(Ok(()));
+            }
+        };
+
+        let Some(workspace) = self.workspace(window) else {
+            return Task::ready(Ok(()));
+        };
+        let task = workspace.update(cx, |workspace, cx| {
+            workspace.send_keystrokes_impl(keystrokes, window, cx)
+        });
+        let had_range = action.range.is_some();
+        let had_override = action.override_rows.is_some();
+
+        cx.spawn_in(window, async move |vim, cx| {
+            task.await;
+            vim.update_in(cx, |vim, window, cx| {
+                if matches!(vim.mode, Mode::Insert | Mode::Replace) {
+                    vim.normal_before(&Default::default(), window, cx);
+                } else {
+                    vim.switch_mode(Mode::Normal, true, window, cx);
+                }
+                if had_override || had_range {
+                    vim.update_editor(cx, |_, editor, cx| {
+                        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                            s.select_anchor_ranges([s.newest_anchor().range()]);
+                        });
+                        if let Some(tx_id) = editor
+                            .buffer()
+                            .update(cx, |multi, cx| multi.last_transaction_id(cx))
+                        {
+                            let last_sel = editor.selections.disjoint_anchors_arc();
+                            editor.modify_transaction_selection_history(tx_id, |old| {
+                                old.0 = old.0.get(..1).unwrap_or(&[]).into();
+                                old.1 = Some(last_sel);
+                            });
+                        }
+                    });
+                }
+            })
+        })
+    }
+
+    /// Runs the action of an ex command on this editor, resolving once it has been handled.
+    ///
+    /// The editor has to have been drawn since it was focused for the actions dispatched to it to
+    /// reach it.
+    pub(crate) fn run_command(
+        &mut self,
+        action: Box<dyn Action>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        // These finish asynchronously, so they're run directly to know when they're done.
+        if let Some(action) = action.as_any().downcast_ref::<ReplaceCommand>() {
+            return self.replace_command(action, window, cx);
+        }
+        if let Some(action) = action.as_any().downcast_ref::<VimNorm>() {
+            return self.normal_command(action, window, cx);
+        }
+        window.dispatch_action(action, cx);
+        Task::ready(Ok(()))
+    }
+}
+
+/// Parses the ex commands run by `:cdo`, `:argdo` and the like, which can be separated by `|`,
+/// as in `:argdo %s/foo/bar/ | update`.
+pub(crate) fn parse_ex_commands(
+    text: &str,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut App,
+) -> Task<Result<Vec<Box<dyn Action>>>> {
+    let commands = split_ex_commands(text)
+        .into_iter()
+        .map(|command| {
+            (
+                command_interceptor(&command, workspace.clone(), cx),
+                command,
+            )
+        })
+        .collect::<Vec<_>>();
+    cx.spawn(async move |_| {
+        anyhow::ensure!(!commands.is_empty(), "Argument required");
+        let mut actions = Vec::with_capacity(commands.len());
+        for (result, command) in commands {
+            let action = result
+                .await
+                .results
+                .into_iter()
+                .next()
+                .with_context(|| format!("Not an editor command: {command}"))?
+                .action;
+            actions.push(action);
+        }
+        Ok(actions)
+    })
+}
+
+/// Splits ex commands at each unescaped `|`, except in the keystrokes of `:normal`, which take
+/// the rest of the line.
+fn split_ex_commands(text: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                current.extend(chars.next());
+            }
+            '|' if !VimCommand::parse_range(current.trim_start())
+                .1
+                .trim_start()
+                .starts_with("norm") =>
+            {
+                commands.push(std::mem::take(&mut current));
+            }
+            c => current.push(c),
+        }
+    }
+    commands.push(current);
+    commands
+        .into_iter()
+        .map(|command| command.trim().to_string())
+        .filter(|command| !command.is_empty())
+        .collect()
+}
+
+/// Runs parsed ex commands in an editor, which has to have just been focused.
+pub(crate) async fn run_ex_commands(
+    editor: &Entity<Editor>,
+    commands: &[Box<dyn Action>],
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    let vim = editor
+        .read_with(cx, |editor, _| editor.addon::<VimAddon>().cloned())
+        .context("Vim mode is disabled")?
+        .entity;
+    // Actions are dispatched to the focused editor as of the last frame, which the editor might
+    // have only just been opened and focused after.
+    cx.update(|window, cx| window.draw(cx).clear())?;
+    for command in commands {
+        vim.update_in(cx, |vim, window, cx| {
+            vim.run_command(command.boxed_clone(), window, cx)
+        })?
+        .await?;
+    }
+    Ok(())
+}
+
 impl VimCommand {
     fn new(pattern: (&'static str, &'static str), action: impl Action) -> Self {
         Self {
@@ -1700,30 +1824,133 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
             save_intent: Some(SaveIntent::Skip),
         }),
         VimCommand::str(("cl", "ist"), "diagnostics::Deploy"),
-        VimCommand::new(("cc", ""), editor::actions::Hover),
-        VimCommand::new(("ll", ""), editor::actions::Hover),
-        VimCommand::new(("cn", "ext"), editor::actions::GoToDiagnostic::default())
-            .range(wrap_count),
         VimCommand::new(
-            ("cp", "revious"),
-            editor::actions::GoToPreviousDiagnostic::default(),
-        )
-        .range(wrap_count),
+            ("cope", "n"),
+            OpenQuickfixList {
+                kind: ListKind::Quickfix,
+            },
+        ),
         VimCommand::new(
-            ("cN", "ext"),
-            editor::actions::GoToPreviousDiagnostic::default(),
-        )
-        .range(wrap_count),
+            ("lop", "en"),
+            OpenQuickfixList {
+                kind: ListKind::Location,
+            },
+        ),
+        VimCommand::new(
+            ("ccl", "ose"),
+            CloseQuickfixList {
+                kind: ListKind::Quickfix,
+            },
+        ),
         VimCommand::new(
+            ("lcl", "ose"),
+            CloseQuickfixList {
+                kind: ListKind::Location,
+            },
+        ),
+        go_to_entry(("cc", ""), ListKind::Quickfix, EntryTarget::Current),
+        go_to_entry(("ll", ""), ListKind::Location, EntryTarget::Current),
+        go_to_entry(("cn", "ext"), ListKind::Quickfix, EntryTarget::Next(1)),
+        go_to_entry(("lne", "xt"), ListKind::Location, EntryTarget::Next(1)),
+        go_to_entry(
+            ("cp", "revious"),
+            ListKind::Quickfix,
+            EntryTarget::Previous(1),
+        ),
+        go_to_entry(("cN", "ext"), ListKind::Quickfix, EntryTarget::Previous(1)),
+        go_to_entry(
             ("lp", "revious"),
-            editor::actions::GoToPreviousDiagnostic::default(),
-        )
-        .range(wrap_count),
+            ListKind::Location,
+            EntryTarget::Previous(1),
+        ),
+        go_to_entry(("lN", "ext"), ListKind::Location, EntryTarget::Previous(1)),
+        go_to_entry(("cfir", "st"), ListKind::Quickfix, EntryTarget::Nth(1)),
+        go_to_entry(("cr", "ewind"), ListKind::Quickfix, EntryTarget::Nth(1)),
+        go_to_entry(("lfir", "st"), ListKind::Location, EntryTarget::Nth(1)),
+        go_to_entry(("lr", "ewind"), ListKind::Location, EntryTarget::Nth(1)),
+        go_to_entry(("cla", "st"), ListKind::Quickfix, EntryTarget::Last),
+        go_to_entry(("lla", "st"), ListKind::Location, EntryTarget::Last),
+        quickfix_do(("cdo", ""), ListKind::Quickfix, false),
+        quickfix_do(("cfd", "o"), ListKind::Quickfix, true),
+        quickfix_do(("ld", "o"), ListKind::Location, false),
+        quickfix_do(("lfd", "o"), ListKind::Location, true),
+        vim_grep(("vim", "grep"), ListKind::Quickfix),
+        vim_grep(("lv", "imgrep"), ListKind::Location),
+        VimCommand::new(("mak", "e"), ArgumentRequired).args(|_, command| {
+            Some(
+                VimMake {
+                    kind: ListKind::Quickfix,
+                    command: command.trim().to_string(),
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(("lmak", "e"), ArgumentRequired).args(|_, command| {
+            Some(
+                VimMake {
+                    kind: ListKind::Location,
+                    command: command.trim().to_string(),
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(
+            ("cdia", "gnostics"),
+            QuickfixFromDiagnostics {
+                kind: ListKind::Quickfix,
+            },
+        ),
+        VimCommand::new(
+            ("ldia", "gnostics"),
+            QuickfixFromDiagnostics {
+                kind: ListKind::Location,
+            },
+        ),
+        VimCommand::new(
+            ("csea", "rch"),
+            QuickfixFromProjectSearch {
+                kind: ListKind::Quickfix,
+            },
+        ),
+        VimCommand::new(
+            ("lsea", "rch"),
+            QuickfixFromProjectSearch {
+                kind: ListKind::Location,
+            },
+        ),
+        VimCommand::new(("ar", "gs"), ShowArgList).args(|_, args| {
+            Some(
+                SetArgList {
+                    patterns: args.split_whitespace().map(Into::into).collect(),
+                }
+                .boxed_clone(),
+            )
+        }),
         VimCommand::new(
-            ("lN", "ext"),
-            editor::actions::GoToPreviousDiagnostic::default(),
+            ("arga", "dd"),
+            AddToArgList {
+                patterns: Vec::new(),
+            },
         )
-        .range(wrap_count),
+        .args(|_, args| {
+            Some(
+                AddToArgList {
+                    patterns: args.split_whitespace().map(Into::into).collect(),
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(("argd", "elete"), ArgumentRequired).args(|_, args| {
+            Some(
+                DeleteFromArgList {
+                    patterns: args.split_whitespace().map(Into::into).collect(),
+                }
+                .boxed_clone(),
+            )
+        }),
+        batch_command(("argdo", ""), |command| ArgDo { command }.boxed_clone()),
+        batch_command(("bufdo", ""), |command| BufDo { command }.boxed_clone()),
+        batch_command(("windo", ""), |command| WinDo { command }.boxed_clone()),
         VimCommand::new(("j", "oin"), JoinLines).range(select_range),
         VimCommand::new(("fo", "ld"), editor::actions::FoldSelectedRanges).range(act_on_range),
         VimCommand::new(("foldo", "pen"), editor::actions::UnfoldLines)
@@ -1775,7 +2002,43 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::new(("ex", ""), editor::actions::ReloadFile).bang(editor::actions::ReloadFile),
         VimCommand::new(("cpp", "link"), editor::actions::CopyPermalinkToLine).range(act_on_range),
         VimCommand::str(("opt", "ions"), "zed::OpenDefaultSettings"),
-        VimCommand::str(("map", ""), "vim::OpenDefaultKeymap"),
+        map_command(("map", ""), MapMode::NormalVisualOperator, false),
+        map_command(("no", "remap"), MapMode::NormalVisualOperator, true),
+        map_command(("nm", "ap"), MapMode::Normal, false),
+        map_command(("nn", "oremap"), MapMode::Normal, true),
+        map_command(("vm", "ap"), MapMode::Visual, false),
+        map_command(("vn", "oremap"), MapMode::Visual, true),
+        map_command(("xm", "ap"), MapMode::Visual, false),
+        map_command(("xn", "oremap"), MapMode::Visual, true),
+        map_command(("om", "ap"), MapMode::Operator, false),
+        map_command(("ono", "remap"), MapMode::Operator, true),
+        map_command(("im", "ap"), MapMode::Insert, false),
+        map_command(("ino", "remap"), MapMode::Insert, true),
+        unmap_command(("unm", "ap"), MapMode::NormalVisualOperator),
+        unmap_command(("nun", "map"), MapMode::Normal),
+        unmap_command(("vu", "nmap"), MapMode::Visual),
+        unmap_command(("xu", "nmap"), MapMode::Visual),
+        unmap_command(("ou", "nmap"), MapMode::Operator),
+        unmap_command(("iu", "nmap"), MapMode::Insert),
+        VimCommand::new(
+            ("ab", "breviate"),
+            VimAbbreviate {
+                args: String::new(),
+            },
+        )
+        .args(|_, args| Some(VimAbbreviate { args }.boxed_clone())),
+        VimCommand::new(
+            ("ia", "bbrev"),
+            VimAbbreviate {
+                args: String::new(),
+            },
+        )
+        .args(|_, args| Some(VimAbbreviate { args }.boxed_clone())),
+        VimCommand::new(("una", "bbreviate"), ArgumentRequired)
+            .args(|_, abbreviation| Some(VimUnabbreviate { abbreviation }.boxed_clone())),
+        VimCommand::new(("iuna", "bbrev"), ArgumentRequired)
+            .args(|_, abbreviation| Some(VimUnabbreviate { abbreviation }.boxed_clone())),
+        VimCommand::new(("mkv", "imrc"), SaveMappings).bang(SaveMappings),
         VimCommand::new(("h", "elp"), OpenDocs),
     ]
 }
@@ -1825,6 +2088,118 @@ fn wrap_count(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn A
     })
 }
 
+fn go_to_entry(
+    pattern: (&'static str, &'static str),
+    kind: ListKind,
+    target: EntryTarget,
+) -> VimCommand {
+    let command =
+        VimCommand::new(pattern, GoToQuickfixEntry { kind, target }).range(move |_, range| {
+            let count = range.as_count()? as usize;
+            Some(
+                GoToQuickfixEntry {
+                    kind,
+                    target: target.with_count(count),
+                }
+                .boxed_clone(),
+            )
+        });
+    if target != EntryTarget::Current {
+        return command;
+    }
+    // `:cc {nr}` and `:ll {nr}` go to the entry with the given number.
+    command.args(move |_, args| {
+        Some(
+            GoToQuickfixEntry {
+                kind,
+                target: EntryTarget::Nth(args.trim().parse().ok()?),
+            }
+            .boxed_clone(),
+        )
+    })
+}
+
+fn quickfix_do(
+    pattern: (&'static str, &'static str),
+    kind: ListKind,
+    per_file: bool,
+) -> VimCommand {
+    VimCommand::new(pattern, ArgumentRequired).args(move |_, command| {
+        Some(
+            QuickfixDo {
+                kind,
+                per_file,
+                command: command.trim().to_string(),
+            }
+            .boxed_clone(),
+        )
+    })
+}
+
+/// A command that runs the ex commands given as its arguments across files, like `:argdo`.
+fn batch_command(
+    pattern: (&'static str, &'static str),
+    action: fn(String) -> Box<dyn Action>,
+) -> VimCommand {
+    VimCommand::new(pattern, ArgumentRequired)
+        .args(move |_, command| Some(action(command.trim().to_string())))
+}
+
+/// A command that maps keys in the given mode, or in insert mode when followed by `!`, like `:map!`.
+fn map_command(pattern: (&'static str, &'static str), mode: MapMode, noremap: bool) -> VimCommand {
+    VimCommand::new(
+        pattern,
+        VimMap {
+            mode,
+            noremap,
+            args: String::new(),
+        },
+    )
+    .bang(VimMap {
+        mode: MapMode::Insert,
+        noremap,
+        args: String::new(),
+    })
+    .args(|action, args| {
+        let action = action.as_any().downcast_ref::<VimMap>()?;
+        Some(
+            VimMap {
+                mode: action.mode,
+                noremap: action.noremap,
+                args,
+            }
+            .boxed_clone(),
+        )
+    })
+}
+
+fn unmap_command(pattern: (&'static str, &'static str), mode: MapMode) -> VimCommand {
+    VimCommand::new(pattern, ArgumentRequired)
+        .bang(VimUnmap {
+            mode: MapMode::Insert,
+            keys: String::new(),
+        })
+        .args(move |action, keys| {
+            let mode = match action.as_any().downcast_ref::<VimUnmap>() {
+                Some(action) => action.mode,
+                None => mode,
+            };
+            Some(VimUnmap { mode, keys }.boxed_clone())
+        })
+}
+
+fn vim_grep(pattern: (&'static str, &'static str), kind: ListKind) -> VimCommand {
+    VimCommand::new(pattern, ArgumentRequired).args(move |_, args| {
+        Some(
+            VimGrep {
+                kind,
+                args: GrepArgs::parse(&args)?,
+            }
+            .boxed_clone(),
+        )
+    })
+}
+
 pub fn command_interceptor(
     mut input: &str,
     workspace: WeakEntity<Workspace>,
@@ -2479,6 +2854,7 @@ impl ShellExec {
                     show_summary: false,
                     show_command: false,
                     show_rerun: false,
+                    problem_matchers: Vec::new(),
                 };
 
                 let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
@@ -2642,6 +3018,19 @@ mod test {
     use util::path;
     use workspace::{OpenOptions, Workspace};
 
+    #[test]
+    fn test_split_ex_commands() {
+        assert_eq!(
+            super::split_ex_commands("%s/a\\|b/c/g | update|"),
+            ["%s/a\\|b/c/g", "update"]
+        );
+        assert_eq!(
+            super::split_ex_commands("s/x/y/ | %norm A|B"),
+            ["s/x/y/", "%norm A|B"]
+        );
+        assert!(super::split_ex_commands(" | ").is_empty());
+    }
+
     #[gpui::test]
     async fn test_command_basics(cx: &mut TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
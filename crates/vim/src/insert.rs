@@ -24,6 +24,10 @@ actions!(
 pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::normal_before);
     Vim::action(editor, cx, Vim::temporary_normal);
+    Vim::action(editor, cx, |vim, _: &editor::actions::Newline, _, cx| {
+        vim.expand_abbreviation_at_cursors(cx);
+        cx.propagate();
+    });
     Vim::action(editor, cx, |vim, _: &InsertFromAbove, window, cx| {
         vim.insert_around(Direction::Prev, window, cx)
     });
@@ -44,6 +48,7 @@ impl Vim {
             self.sync_vim_settings(window, cx);
             return;
         }
+        self.expand_abbreviation_at_cursors(cx);
         let count = Vim::take_count(cx).unwrap_or(1);
         Vim::take_forced_motion(cx);
         self.stop_recording_immediately(action.boxed_clone(), cx);
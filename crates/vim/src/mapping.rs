@@ -0,0 +1,610 @@
+//! Key mappings and insert mode abbreviations defined at runtime, with `:map`, `:abbreviate` and
+//! their variants.
+
+use anyhow::{Context as _, Result, anyhow};
+use editor::Editor;
+use gpui::{
+    Action, App, Context, KeyBinding, KeyBindingContextPredicate, Keystroke, NoAction, Window,
+};
+use itertools::Itertools as _;
+use language::Point;
+use settings::{
+    KeybindSource, KeybindUpdateOperation, KeybindUpdateTarget, KeymapFile, infer_json_indent_size,
+};
+use util::ResultExt as _;
+use workspace::{
+    SendKeystrokes, Toast, Workspace,
+    notifications::{NotificationId, NotifyTaskExt as _},
+};
+
+use crate::{
+    Vim,
+    state::{Mode, VimGlobals},
+};
+
+/// The modes a key mapping applies in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapMode {
+    /// Normal, visual and operator pending mode, like `:map`.
+    NormalVisualOperator,
+    Normal,
+    Visual,
+    Operator,
+    Insert,
+}
+
+impl MapMode {
+    /// The context of the key bindings of the mappings in this mode.
+    fn context(self) -> &'static str {
+        match self {
+            Self::NormalVisualOperator => "VimControl && !menu",
+            Self::Normal => "vim_mode == normal && !menu",
+            Self::Visual => "vim_mode == visual && !menu",
+            Self::Operator => "vim_mode == operator",
+            Self::Insert => "vim_mode == insert",
+        }
+    }
+
+    /// Whether `:unmap` in this mode removes a mapping of the given mode.
+    fn unmaps(self, mode: MapMode) -> bool {
+        self == mode
+            || self == Self::NormalVisualOperator
+                && matches!(mode, Self::Normal | Self::Visual | Self::Operator)
+    }
+}
+
+/// Maps keys to other keys, or opens the default keymap when given no keys.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct VimMap {
+    pub(crate) mode: MapMode,
+    /// Whether the mapping is defined with a `nore` variant, like `:noremap`.
+    pub(crate) noremap: bool,
+    pub(crate) args: String,
+}
+
+/// Removes a key mapping.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct VimUnmap {
+    pub(crate) mode: MapMode,
+    pub(crate) keys: String,
+}
+
+/// Defines an insert mode abbreviation, or shows them when given no arguments.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct VimAbbreviate {
+    pub(crate) args: String,
+}
+
+/// Removes an insert mode abbreviation.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct VimUnabbreviate {
+    pub(crate) abbreviation: String,
+}
+
+/// Sends the keys a key mapping is mapped to.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct SendMappedKeys {
+    pub(crate) keys: String,
+    pub(crate) noremap: bool,
+}
+
+/// Adds the key mappings defined with `:map` and its variants to the user keymap.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct SaveMappings;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mapping {
+    pub mode: MapMode,
+    /// The mapped keystrokes, separated by spaces.
+    pub keys: String,
+    /// The keystrokes they're mapped to, or `None` for `<Nop>`.
+    pub target: Option<String>,
+    /// Whether the other mappings don't apply to the target, like with `:noremap`.
+    pub noremap: bool,
+}
+
+impl Mapping {
+    /// The binding of the mapping's keys while the mapping is defined.
+    fn key_binding(&self, cx: &App) -> Result<KeyBinding> {
+        let action = match &self.target {
+            Some(target) => SendMappedKeys {
+                keys: target.clone(),
+                noremap: self.noremap,
+            }
+            .boxed_clone(),
+            None => NoAction.boxed_clone(),
+        };
+        self.binding_to(action, cx)
+    }
+
+    /// The binding of the mapping's keys that `:mkvimrc` adds to the user keymap.
+    fn saved_key_binding(&self, cx: &App) -> Result<KeyBinding> {
+        let action = match &self.target {
+            Some(target) => SendKeystrokes(target.clone()).boxed_clone(),
+            None => NoAction.boxed_clone(),
+        };
+        self.binding_to(action, cx)
+    }
+
+    fn binding_to(&self, action: Box<dyn Action>, cx: &App) -> Result<KeyBinding> {
+        let predicate = KeyBindingContextPredicate::parse(self.mode.context())?;
+        Ok(KeyBinding::load(
+            &self.keys,
+            action,
+            Some(predicate.into()),
+            false,
+            None,
+            cx.keyboard_mapper().as_ref(),
+        )?)
+    }
+}
+
+pub(crate) fn register(
+    workspace: &mut Workspace,
+    _: Option<&mut Window>,
+    _: &mut Context<Workspace>,
+) {
+    workspace.register_action(|workspace, action: &VimMap, window, cx| {
+        if action.args.trim().is_empty() {
+            window.dispatch_action(zed_actions::vim::OpenDefaultKeymap.boxed_clone(), cx);
+            return;
+        }
+        if let Err(err) = map(action, cx) {
+            workspace.show_error(&err, cx);
+        }
+    });
+    workspace.register_action(|workspace, action: &VimUnmap, _, cx| {
+        let keys = parse_keys(action.keys.trim()).join(" ");
+        let mappings = &mut Vim::globals(cx).mappings;
+        let count = mappings.len();
+        mappings.retain(|mapping| !(action.mode.unmaps(mapping.mode) && mapping.keys == keys));
+        if mappings.len() == count {
+            workspace.show_error(&anyhow!("No such mapping"), cx);
+            return;
+        }
+        bind_mappings(cx);
+    });
+    workspace.register_action(|workspace, action: &VimAbbreviate, _, cx| {
+        let args = action.args.trim();
+        if args.is_empty() {
+            show_abbreviations(workspace, cx);
+            return;
+        }
+        let Some((abbreviation, expansion)) = args.split_once(char::is_whitespace) else {
+            workspace.show_error(&anyhow!("Argument required"), cx);
+            return;
+        };
+        Vim::globals(cx)
+            .abbreviations
+            .insert(abbreviation.into(), expansion.trim_start().into());
+    });
+    workspace.register_action(|workspace, action: &VimUnabbreviate, _, cx| {
+        if Vim::globals(cx)
+            .abbreviations
+            .remove(action.abbreviation.trim())
+            .is_none()
+        {
+            workspace.show_error(&anyhow!("No such abbreviation"), cx);
+        }
+    });
+    workspace.register_action(|workspace, action: &SendMappedKeys, window, cx| {
+        // The keys sent for a `:noremap` mapping skip the mappings, falling back to the bindings
+        // of the keymap.
+        if Vim::globals(cx).sending_noremap_keys > 0 {
+            cx.propagate();
+            return;
+        }
+        let keystrokes = action
+            .keys
+            .split(' ')
+            .flat_map(|key| Keystroke::parse(key).log_err())
+            .map(|keystroke| {
+                cx.keyboard_mapper()
+                    .map_key_equivalent(keystroke, false)
+                    .inner()
+                    .clone()
+            })
+            .collect();
+        let sent = workspace.send_keystrokes_impl(keystrokes, window, cx);
+        if action.noremap {
+            Vim::globals(cx).sending_noremap_keys += 1;
+            cx.spawn(async move |_, cx| {
+                sent.await;
+                cx.update(|cx| Vim::globals(cx).sending_noremap_keys -= 1);
+            })
+            .detach();
+        }
+    });
+    workspace.register_action(|workspace, _: &SaveMappings, window, cx| {
+        save_mappings(workspace, window, cx);
+    });
+}
+
+fn map(action: &VimMap, cx: &mut App) -> Result<()> {
+    let (keys, target) = action
+        .args
+        .trim_start()
+        .split_once(char::is_whitespace)
+        .context("Argument required")?;
+    let target = target.trim_start();
+    let mapping = Mapping {
+        mode: action.mode,
+        keys: parse_keys(keys).join(" "),
+        target: (!target.eq_ignore_ascii_case("<nop>")).then(|| parse_keys(target).join(" ")),
+        noremap: action.noremap,
+    };
+    mapping.key_binding(cx)?;
+
+    let mappings = &mut Vim::globals(cx).mappings;
+    mappings.retain(|existing| !(existing.mode == mapping.mode && existing.keys == mapping.keys));
+    mappings.push(mapping);
+    bind_mappings(cx);
+    Ok(())
+}
+
+/// Binds the keys of the mappings defined with `:map` and its variants, replacing the bindings of
+/// earlier ones. This has to be called again whenever the keymap is reloaded, after the user's key
+/// bindings, which the mappings take precedence over.
+pub fn bind_mappings(cx: &mut App) {
+    let Some(globals) = cx.try_global::<VimGlobals>() else {
+        return;
+    };
+    if globals.mappings.is_empty() && globals.mapping_bindings.is_empty() {
+        return;
+    }
+    let previous = globals.mapping_bindings.clone();
+    let bindings = globals
+        .mappings
+        .iter()
+        .filter_map(|mapping| mapping.key_binding(cx).log_err())
+        .collect::<Vec<_>>();
+
+    // Key bindings can't be removed one by one, so the keymap is rebuilt without those of the
+    // mappings bound before. Unlike those, the bindings loaded from keymap files have a source,
+    // and are kept even when they're the same, like after `:mkvimrc`.
+    let keymap = cx.key_bindings();
+    let kept = keymap
+        .borrow()
+        .bindings()
+        .filter(|binding| {
+            binding.meta().is_some() || !previous.iter().any(|other| same_binding(binding, other))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    cx.clear_key_bindings();
+    cx.bind_keys(kept);
+    cx.bind_keys(bindings.iter().cloned());
+    Vim::globals(cx).mapping_bindings = bindings;
+}
+
+fn same_binding(a: &KeyBinding, b: &KeyBinding) -> bool {
+    a.keystrokes() == b.keystrokes()
+        && a.predicate() == b.predicate()
+        && a.action().partial_eq(b.action())
+}
+
+/// Converts keys written in Vim's notation, like `<C-w>j` or `:w<CR>`, to keystrokes. A `<` that
+/// doesn't start a known key is taken literally, like in Vim.
+fn parse_keys(notation: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut rest = notation;
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some(end) = rest.find('>')
+            && let Some(key) = parse_special_key(&rest[1..end])
+        {
+            keys.push(key);
+            rest = &rest[end + 1..];
+            continue;
+        }
+        keys.push(match c {
+            ' ' => "space".into(),
+            c => c.to_string(),
+        });
+        rest = &rest[c.len_utf8()..];
+    }
+    keys
+}
+
+/// Converts the name of a key between `<` and `>`, with its modifiers, to a keystroke.
+fn parse_special_key(name: &str) -> Option<String> {
+    let mut keystroke = String::new();
+    let mut key = name;
+    while key.len() > 2 && key.as_bytes()[1] == b'-' {
+        keystroke.push_str(match key.as_bytes()[0].to_ascii_lowercase() {
+            b'c' => "ctrl-",
+            b's' => "shift-",
+            b'a' | b'm' => "alt-",
+            b'd' => "cmd-",
+            _ => return None,
+        });
+        key = &key[2..];
+    }
+    let lowercase = key.to_ascii_lowercase();
+    let key = match lowercase.as_str() {
+        "cr" | "enter" | "return" => "enter",
+        "esc" => "escape",
+        "bs" => "backspace",
+        "del" => "delete",
+        "lt" => "<",
+        "bar" => "|",
+        "bslash" | "leader" => "\\",
+        "tab" | "space" | "up" | "down" | "left" | "right" | "home" | "end" | "insert"
+        | "pageup" | "pagedown" => lowercase.as_str(),
+        function
+            if function
+                .strip_prefix('f')
+                .and_then(|number| number.parse::<u8>().ok())
+                .is_some_and(|number| (1..=12).contains(&number)) =>
+        {
+            function
+        }
+        // Like in Vim, `<C-W>` is the same as `<C-w>`.
+        _ if !keystroke.is_empty() && key.chars().count() == 1 => lowercase.as_str(),
+        _ => return None,
+    };
+    keystroke.push_str(key);
+    Some(keystroke)
+}
+
+fn show_abbreviations(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let abbreviations = &Vim::globals(cx).abbreviations;
+    let message = if abbreviations.is_empty() {
+        "No abbreviation found".to_string()
+    } else {
+        abbreviations
+            .iter()
+            .sorted()
+            .map(|(abbreviation, expansion)| format!("{abbreviation} {expansion}"))
+            .join(", ")
+    };
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<VimAbbreviate>(), message),
+        cx,
+    );
+}
+
+fn save_mappings(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let keymap = cx.key_bindings();
+    let user_bindings = keymap
+        .borrow()
+        .bindings()
+        .filter(|binding| binding.meta() == Some(KeybindSource::User.meta()))
+        .cloned()
+        .collect::<Vec<_>>();
+    // The mappings already saved are bound by the user keymap too.
+    let mappings = Vim::globals(cx)
+        .mappings
+        .clone()
+        .into_iter()
+        .filter_map(|mapping| {
+            let binding = mapping.saved_key_binding(cx).log_err()?;
+            let is_saved = user_bindings
+                .iter()
+                .any(|user_binding| same_binding(user_binding, &binding));
+            (!is_saved).then_some((mapping, binding))
+        })
+        .collect::<Vec<_>>();
+    if mappings.is_empty() {
+        workspace.show_error(&anyhow!("No key mappings to save"), cx);
+        return;
+    }
+
+    let fs = workspace.app_state().fs.clone();
+    let keyboard_mapper = cx.keyboard_mapper().clone();
+    cx.spawn_in(window, async move |workspace, cx| {
+        let mut keymap_contents = KeymapFile::load_keymap_file(&fs)
+            .await
+            .context("Failed to load keymap file")?;
+        let tab_size = infer_json_indent_size(&keymap_contents);
+        for (mapping, binding) in &mappings {
+            let arguments = mapping
+                .target
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?;
+            let target = KeybindUpdateTarget {
+                context: Some(mapping.mode.context()),
+                keystrokes: binding.keystrokes(),
+                action_name: binding.action().name(),
+                action_arguments: arguments.as_deref(),
+            };
+            keymap_contents = KeymapFile::update_keybinding(
+                KeybindUpdateOperation::add(target),
+                keymap_contents,
+                tab_size,
+                keyboard_mapper.as_ref(),
+            )?;
+        }
+        fs.write(paths::keymap_file(), keymap_contents.as_bytes())
+            .await?;
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<SaveMappings>(),
+                    format!("Saved {} key mappings to the keymap", mappings.len()),
+                ),
+                cx,
+            );
+        })
+    })
+    .detach_and_notify_err(window, cx);
+}
+
+impl Vim {
+    /// Expands the abbreviation before the cursors when a character that can't be part of a word
+    /// has just been typed after it.
+    pub(crate) fn expand_abbreviation(&mut self, text: &str, cx: &mut Context<Self>) {
+        let mut chars = text.chars();
+        if let (Some(typed), None) = (chars.next(), chars.next()) {
+            self.expand_abbreviation_before(Some(typed), cx);
+        }
+    }
+
+    /// Expands the abbreviation right before the cursors, when a new line is started or insert
+    /// mode is left after it.
+    pub(crate) fn expand_abbreviation_at_cursors(&mut self, cx: &mut Context<Self>) {
+        self.expand_abbreviation_before(None, cx);
+    }
+
+    fn expand_abbreviation_before(&mut self, typed: Option<char>, cx: &mut Context<Self>) {
+        if !matches!(self.mode, Mode::Insert | Mode::Replace) {
+            return;
+        }
+        let abbreviations = &Vim::globals(cx).abbreviations;
+        if abbreviations.is_empty() {
+            return;
+        }
+        let abbreviations = abbreviations.clone();
+
+        self.update_editor(cx, |_, editor: &mut Editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut edits = Vec::new();
+            for selection in editor.selections.all::<Point>(&editor.display_snapshot(cx)) {
+                let head = selection.head();
+                let typed_len = typed.map_or(0, |typed| typed.len_utf8() as u32);
+                if !selection.is_empty() || head.column < typed_len {
+                    continue;
+                }
+                let end = Point::new(head.row, head.column - typed_len);
+                let classifier = snapshot.char_classifier_at(end);
+                if let Some(typed) = typed
+                    && (classifier.is_word(typed) || snapshot.chars_at(end).next() != Some(typed))
+                {
+                    continue;
+                }
+                let mut start = end;
+                for c in snapshot.reversed_chars_at(end) {
+                    if !classifier.is_word(c) {
+                        break;
+                    }
+                    start.column -= c.len_utf8() as u32;
+                }
+                let word = snapshot.text_for_range(start..end).collect::<String>();
+                if let Some(expansion) = abbreviations.get(&word) {
+                    edits.push((start..end, expansion.clone()));
+                }
+            }
+            editor.edit(edits, cx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gpui::TestAppContext;
+
+    use super::*;
+    use crate::test::VimTestContext;
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(parse_keys("dd"), ["d", "d"]);
+        assert_eq!(parse_keys("<C-w>j"), ["ctrl-w", "j"]);
+        assert_eq!(parse_keys(":w<CR>"), [":", "w", "enter"]);
+        assert_eq!(parse_keys("<leader>a b"), ["\\", "a", "space", "b"]);
+        assert_eq!(parse_keys("<S-Tab><M-F12>"), ["shift-tab", "alt-f12"]);
+        assert_eq!(parse_keys("<lt>x>"), ["<", "x", ">"]);
+        assert_eq!(parse_keys("<foo>"), ["<", "f", "o", "o", ">"]);
+    }
+
+    #[gpui::test]
+    async fn test_map_and_unmap(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.simulate_keystrokes(": n m a p space shift-q space d d enter");
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes("shift-q");
+        cx.assert_state("ˇtwo", Mode::Normal);
+
+        cx.simulate_keystrokes(": n u n m a p space shift-q enter");
+        cx.simulate_keystrokes("shift-q");
+        cx.assert_state("ˇtwo", Mode::Normal);
+
+        cx.simulate_keystrokes(": i n o r e m a p space j k space < e s c > enter");
+        cx.simulate_keystrokes("i x j k");
+        cx.assert_state("ˇxtwo", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_noremap(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.simulate_keystrokes(": n m a p space x space d d enter");
+        cx.simulate_keystrokes(": n n o r e m a p space shift-q space x enter");
+        cx.simulate_keystrokes(": n m a p space shift-w space x enter");
+        cx.set_state("ˇone\ntwo\nthree", Mode::Normal);
+
+        // The `x` mapping applies to the keys of `:nmap`, but not to those of `:nnoremap`.
+        cx.simulate_keystrokes("shift-q");
+        cx.assert_state("ˇne\ntwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes("shift-w");
+        cx.assert_state("ˇtwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes("x");
+        cx.assert_state("ˇthree", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_abbreviate(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.simulate_keystrokes(": i a b b r e v space t e h space t h e enter");
+        cx.set_state("ˇ", Mode::Normal);
+        cx.simulate_keystrokes("i t e h space a t e h .");
+        cx.assert_state("the ateh.ˇ", Mode::Insert);
+
+        cx.simulate_keystrokes("escape : i u n a space t e h enter");
+        cx.simulate_keystrokes("o t e h space");
+        cx.assert_state("the ateh.\nteh ˇ", Mode::Insert);
+
+        // Starting a new line or leaving insert mode ends the abbreviation too.
+        cx.simulate_keystrokes("escape : i a b b r e v space t e h space t h e enter");
+        cx.simulate_keystrokes("shift-a t e h enter t e h escape");
+        cx.assert_state("the ateh.\nteh the\nthˇe", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_save_mappings(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(paths::keymap_file(), "[]".into())
+            .await;
+
+        cx.simulate_keystrokes(": n m a p space shift-q space d d enter");
+        cx.simulate_keystrokes(": m k v i m r c enter");
+        cx.run_until_parked();
+        let keymap = fs.load(paths::keymap_file()).await.unwrap();
+        assert!(keymap.contains("vim_mode == normal && !menu"));
+        assert!(keymap.contains("workspace::SendKeystrokes"));
+        assert!(keymap.contains(r#""d d""#));
+
+        // Binding the mappings again, as when the keymap is reloaded, keeps the saved bindings.
+        cx.update(|_, cx| {
+            let mut saved = KeyBinding::new(
+                "shift-q",
+                SendKeystrokes("d d".into()),
+                Some(MapMode::Normal.context()),
+            );
+            saved.set_meta(KeybindSource::User.meta());
+            cx.bind_keys([saved]);
+            bind_mappings(cx);
+            let user_bindings = cx
+                .key_bindings()
+                .borrow()
+                .bindings()
+                .filter(|binding| binding.meta() == Some(KeybindSource::User.meta()))
+                .count();
+            assert_eq!(user_bindings, 1);
+        });
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes("shift-q");
+        cx.assert_state("ˇtwo", Mode::Normal);
+    }
+}
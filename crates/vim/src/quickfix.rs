@@ -0,0 +1,1337 @@
+//! Vim's quickfix list, and the location list of each pane: lists of positions in the project,
+//! like search matches or diagnostics, to step through and run ex commands on.
+
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::{HashMap, HashSet};
+use editor::{Bias, Editor, EditorSettings, SelectionEffects, ToPoint as _, scroll::Autoscroll};
+use gpui::{
+    Action, App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, ParentElement as _, Render, SharedString, Styled as _,
+    Subscription, Task, WeakEntity, Window,
+};
+use itertools::Itertools as _;
+use language::{Buffer, DiagnosticSeverity, Point, ToPoint as _};
+use picker::{Picker, PickerDelegate};
+use project::{
+    Project, ProjectItem as _, ProjectPath,
+    lsp_store::task_diagnostics::TASK_DIAGNOSTICS_SERVER_ID,
+    search::{SearchQuery, SearchResult},
+};
+use search::ProjectSearchView;
+use settings::Settings as _;
+use task::{HideStrategy, ProblemMatcher, RevealStrategy, SpawnInTerminal, TaskId};
+use ui::{
+    Color, Icon, IconName, IconSize, Label, LabelCommon as _, ListItem, ListItemSpacing,
+    Toggleable as _, div, h_flex,
+};
+use util::paths::{PathMatcher, PathStyle};
+use workspace::{
+    Pane, SaveIntent, SplitDirection, Workspace,
+    item::{Item, ItemEvent, ItemHandle as _},
+    notifications::NotifyTaskExt as _,
+};
+use zed_actions::RevealTarget;
+
+use crate::{
+    Vim,
+    command::{parse_ex_commands, run_ex_commands},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListKind {
+    /// The quickfix list, shared by the panes of a workspace.
+    Quickfix,
+    /// The location list of the active pane.
+    Location,
+}
+
+impl ListKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Quickfix => "quickfix list",
+            Self::Location => "location list",
+        }
+    }
+}
+
+/// Which entry of a list to jump to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryTarget {
+    Current,
+    Next(usize),
+    Previous(usize),
+    /// The entry with the given 1-based number.
+    Nth(usize),
+    Last,
+}
+
+impl EntryTarget {
+    /// The target when a count is given to the command, as in `:3cnext` or `:3cc`.
+    pub(crate) fn with_count(self, count: usize) -> Self {
+        match self {
+            Self::Next(_) => Self::Next(count),
+            Self::Previous(_) => Self::Previous(count),
+            Self::Current | Self::Nth(_) | Self::Last => Self::Nth(count),
+        }
+    }
+}
+
+/// Opens the quickfix or location list in a pane below the active one.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct OpenQuickfixList {
+    pub(crate) kind: ListKind,
+}
+
+/// Closes the quickfix or location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct CloseQuickfixList {
+    pub(crate) kind: ListKind,
+}
+
+/// Jumps to an entry of the quickfix or location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct GoToQuickfixEntry {
+    pub(crate) kind: ListKind,
+    pub(crate) target: EntryTarget,
+}
+
+/// Runs an ex command on each entry, or on the first entry of each file, of the quickfix or
+/// location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct QuickfixDo {
+    pub(crate) kind: ListKind,
+    pub(crate) per_file: bool,
+    pub(crate) command: String,
+}
+
+/// Fills the quickfix or location list with the matches of a regex in the project.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct VimGrep {
+    pub(crate) kind: ListKind,
+    pub(crate) args: GrepArgs,
+}
+
+/// Runs a command in the terminal and fills the quickfix or location list with the problems
+/// found in its output.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct VimMake {
+    pub(crate) kind: ListKind,
+    pub(crate) command: String,
+}
+
+/// Fills the quickfix list with the project's diagnostics, or the location list with the
+/// current file's.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct QuickfixFromDiagnostics {
+    pub(crate) kind: ListKind,
+}
+
+/// Fills the quickfix or location list with the results of the project search.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct QuickfixFromProjectSearch {
+    pub(crate) kind: ListKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuickfixEntry {
+    pub path: ProjectPath,
+    /// Where the entry was when the list was filled.
+    pub position: Point,
+    /// Where the entry is in its buffer, following the buffer's edits while it's open.
+    pub anchor: Option<(WeakEntity<Buffer>, text::Anchor)>,
+    pub text: String,
+    pub severity: Option<DiagnosticSeverity>,
+}
+
+impl QuickfixEntry {
+    fn new(
+        buffer: &Entity<Buffer>,
+        path: ProjectPath,
+        position: Point,
+        text: String,
+        severity: Option<DiagnosticSeverity>,
+        cx: &App,
+    ) -> Self {
+        let anchor = buffer.read(cx).anchor_before(position);
+        Self {
+            path,
+            position,
+            anchor: Some((buffer.downgrade(), anchor)),
+            text,
+            severity,
+        }
+    }
+
+    /// Where the entry is now, after the edits made to its buffer since the list was filled.
+    pub fn current_position(&self, cx: &App) -> Point {
+        self.anchor
+            .as_ref()
+            .and_then(|(buffer, anchor)| Some(anchor.to_point(&buffer.upgrade()?.read(cx))))
+            .unwrap_or(self.position)
+    }
+}
+
+pub struct QuickfixList {
+    kind: ListKind,
+    /// What filled the list, like the command that did.
+    title: SharedString,
+    entries: Vec<QuickfixEntry>,
+    /// The index of the entry last jumped to.
+    current: usize,
+}
+
+impl QuickfixList {
+    fn new(kind: ListKind) -> Self {
+        Self {
+            kind,
+            title: SharedString::default(),
+            entries: Vec::new(),
+            current: 0,
+        }
+    }
+
+    fn set_entries(
+        &mut self,
+        title: impl Into<SharedString>,
+        entries: Vec<QuickfixEntry>,
+        cx: &mut Context<Self>,
+    ) {
+        self.title = title.into();
+        self.entries = entries;
+        self.current = 0;
+        cx.notify();
+    }
+
+    fn target_index(&self, target: EntryTarget) -> Result<usize> {
+        let Some(last) = self.entries.len().checked_sub(1) else {
+            anyhow::bail!("The {} is empty", self.kind.name());
+        };
+        let no_more_entries = || anyhow!("No more entries in the {}", self.kind.name());
+        Ok(match target {
+            EntryTarget::Current => self.current.min(last),
+            EntryTarget::Next(count) => {
+                if self.current >= last {
+                    return Err(no_more_entries());
+                }
+                self.current.saturating_add(count).min(last)
+            }
+            EntryTarget::Previous(count) => {
+                if self.current == 0 {
+                    return Err(no_more_entries());
+                }
+                self.current.saturating_sub(count)
+            }
+            EntryTarget::Nth(number) => number.saturating_sub(1).min(last),
+            EntryTarget::Last => last,
+        })
+    }
+}
+
+/// The arguments of `:vimgrep`: `/{pattern}/[g][j] {file} ...` or `{pattern} {file} ...`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrepArgs {
+    pub pattern: String,
+    /// Whether to list every match, instead of the first one of each line.
+    pub all_matches: bool,
+    /// Whether to jump to the first match.
+    pub jump: bool,
+    /// Globs of the files to search, all of the project's when empty.
+    pub files: Vec<String>,
+}
+
+impl GrepArgs {
+    pub(crate) fn parse(args: &str) -> Option<Self> {
+        let args = args.trim_start();
+        let mut chars = args.chars();
+        let delimiter = chars.next()?;
+        // Like in Vim, a pattern starting with an identifier character is delimited by whitespace.
+        if delimiter.is_alphanumeric() || delimiter == '_' {
+            let (pattern, files) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+            return Some(Self {
+                pattern: convert_pattern(pattern, None),
+                all_matches: false,
+                jump: true,
+                files: files.split_whitespace().map(Into::into).collect(),
+            });
+        }
+
+        let rest = chars.as_str();
+        let mut end = None;
+        let mut escaped = false;
+        for (ix, c) in rest.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == delimiter {
+                end = Some(ix);
+                break;
+            }
+        }
+        let (pattern, rest) = match end {
+            Some(end) => (&rest[..end], &rest[end + delimiter.len_utf8()..]),
+            None => (rest, ""),
+        };
+        if pattern.is_empty() {
+            return None;
+        }
+        let (flags, files) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if !flags.chars().all(|flag| matches!(flag, 'g' | 'j')) {
+            return None;
+        }
+        Some(Self {
+            pattern: convert_pattern(pattern, Some(delimiter)),
+            all_matches: flags.contains('g'),
+            jump: !flags.contains('j'),
+            files: files.split_whitespace().map(Into::into).collect(),
+        })
+    }
+}
+
+/// Converts the parentheses of a Vim pattern, where escaped ones group, to those of a regex, and
+/// unescapes its delimiter.
+fn convert_pattern(pattern: &str, delimiter: Option<char>) -> String {
+    let mut converted = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ ('(' | ')')) => converted.push(c),
+                Some(c) if Some(c) == delimiter => converted.push(c),
+                Some(c) => {
+                    converted.push('\\');
+                    converted.push(c);
+                }
+                None => converted.push('\\'),
+            },
+            '(' | ')' => {
+                converted.push('\\');
+                converted.push(c);
+            }
+            c => converted.push(c),
+        }
+    }
+    converted
+}
+
+pub(crate) fn register(
+    workspace: &mut Workspace,
+    _: Option<&mut Window>,
+    _: &mut Context<Workspace>,
+) {
+    workspace.register_action(|workspace, action: &OpenQuickfixList, window, cx| {
+        open_list_view(workspace, action.kind, window, cx);
+    });
+    workspace.register_action(|workspace, action: &CloseQuickfixList, window, cx| {
+        let (list, _) = resolve_list(workspace, action.kind, cx);
+        for view in workspace
+            .items_of_type::<QuickfixView>(cx)
+            .collect::<Vec<_>>()
+        {
+            if view.read(cx).list == list
+                && let Some(pane) = workspace.pane_for(&view)
+            {
+                pane.update(cx, |pane, cx| {
+                    pane.close_item_by_id(view.entity_id(), SaveIntent::Skip, window, cx)
+                })
+                .detach_and_log_err(cx);
+            }
+        }
+    });
+    workspace.register_action(|workspace, action: &GoToQuickfixEntry, window, cx| {
+        go_to_entry(workspace, action, window, cx);
+    });
+    workspace.register_action(|workspace, action: &QuickfixDo, window, cx| {
+        run_on_entries(workspace, action, window, cx);
+    });
+    workspace.register_action(|workspace, action: &VimGrep, window, cx| {
+        grep(workspace, action, window, cx);
+    });
+    workspace.register_action(|workspace, action: &VimMake, window, cx| {
+        make(workspace, action, window, cx);
+    });
+    workspace.register_action(|workspace, action: &QuickfixFromDiagnostics, window, cx| {
+        let (list, pane) = resolve_list(workspace, action.kind, cx);
+        let project = workspace.project().clone();
+        // The location list only gets the diagnostics of the file shown in its pane.
+        let paths = match action.kind {
+            ListKind::Quickfix => None,
+            ListKind::Location => {
+                let Some(path) = pane
+                    .read(cx)
+                    .active_item()
+                    .and_then(|item| item.project_path(cx))
+                else {
+                    workspace.show_error(&anyhow!("No file is open"), cx);
+                    return;
+                };
+                Some(vec![path])
+            }
+        };
+        let entries = diagnostic_entries(&project, paths, None, None, cx);
+        let title = match action.kind {
+            ListKind::Quickfix => "Diagnostics",
+            ListKind::Location => "File Diagnostics",
+        };
+        let kind = action.kind;
+        cx.spawn_in(window, async move |workspace, cx| {
+            let entries = entries.await?;
+            list.update(cx, |list, cx| list.set_entries(title, entries, cx));
+            workspace.update_in(cx, |workspace, window, cx| {
+                open_list_view(workspace, kind, window, cx)
+            })
+        })
+        .detach_and_notify_err(window, cx);
+    });
+    workspace.register_action(
+        |workspace, action: &QuickfixFromProjectSearch, window, cx| {
+            match entries_from_project_search(workspace, cx) {
+                Ok((title, entries)) => {
+                    let (list, _) = resolve_list(workspace, action.kind, cx);
+                    list.update(cx, |list, cx| list.set_entries(title, entries, cx));
+                    open_list_view(workspace, action.kind, window, cx);
+                }
+                Err(error) => workspace.show_error(&error, cx),
+            }
+        },
+    );
+}
+
+/// Returns the list the commands of the given kind act on, along with the pane its entries open
+/// in: the active one, or the one the list was opened from when the active pane is showing it.
+fn resolve_list(
+    workspace: &Workspace,
+    kind: ListKind,
+    cx: &mut Context<Workspace>,
+) -> (Entity<QuickfixList>, Entity<Pane>) {
+    let active_pane = workspace.active_pane().clone();
+    let pane = active_pane
+        .read(cx)
+        .active_item()
+        .and_then(|item| item.downcast::<QuickfixView>())
+        .and_then(|view| view.read(cx).target_pane.upgrade())
+        .unwrap_or(active_pane);
+
+    let owner = match kind {
+        ListKind::Quickfix => cx.entity_id(),
+        ListKind::Location => pane.entity_id(),
+    };
+    let globals = Vim::globals(cx);
+    let lists = match kind {
+        ListKind::Quickfix => &globals.quickfix_lists,
+        ListKind::Location => &globals.location_lists,
+    };
+    if let Some(list) = lists.get(&owner) {
+        return (list.clone(), pane);
+    }
+
+    let list = cx.new(|_| QuickfixList::new(kind));
+    match kind {
+        ListKind::Quickfix => {
+            Vim::globals(cx).quickfix_lists.insert(owner, list.clone());
+        }
+        ListKind::Location => {
+            Vim::globals(cx).location_lists.insert(owner, list.clone());
+            cx.observe_release(&pane, move |_, _, cx| {
+                Vim::update_globals(cx, |globals, _| {
+                    globals.location_lists.remove(&owner);
+                })
+            })
+            .detach();
+        }
+    }
+    (list, pane)
+}
+
+fn open_list_view(
+    workspace: &mut Workspace,
+    kind: ListKind,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let (list, pane) = resolve_list(workspace, kind, cx);
+    if let Some(view) = workspace
+        .items_of_type::<QuickfixView>(cx)
+        .find(|view| view.read(cx).list == list)
+    {
+        workspace.activate_item(&view, true, true, window, cx);
+        return;
+    }
+
+    let path_style = workspace.project().read(cx).path_style(cx);
+    let view = cx.new(|cx| {
+        QuickfixView::new(
+            list,
+            pane.downgrade(),
+            cx.weak_entity(),
+            path_style,
+            window,
+            cx,
+        )
+    });
+    // Like Vim's quickfix window, the list opens below the window it belongs to.
+    let new_pane = workspace.split_pane(pane, SplitDirection::Down, window, cx);
+    workspace.add_item(new_pane, Box::new(view), None, true, true, window, cx);
+}
+
+fn go_to_entry(
+    workspace: &mut Workspace,
+    action: &GoToQuickfixEntry,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let (list, pane) = resolve_list(workspace, action.kind, cx);
+    // Without a list to go through, step through the diagnostics instead, like before there were
+    // any lists.
+    if list.read(cx).entries.is_empty() {
+        let (diagnostic_action, count) = match action.target {
+            EntryTarget::Next(count) => (
+                editor::actions::GoToDiagnostic::default().boxed_clone(),
+                count,
+            ),
+            EntryTarget::Previous(count) => (
+                editor::actions::GoToPreviousDiagnostic::default().boxed_clone(),
+                count,
+            ),
+            EntryTarget::Current => (editor::actions::Hover.boxed_clone(), 1),
+            EntryTarget::Nth(_) | EntryTarget::Last => {
+                workspace.show_error(&anyhow!("The {} is empty", action.kind.name()), cx);
+                return;
+            }
+        };
+        for _ in 0..count {
+            window.dispatch_action(diagnostic_action.boxed_clone(), cx);
+        }
+        return;
+    }
+
+    let index = list.read(cx).target_index(action.target);
+    match index {
+        Ok(index) => {
+            open_entry(workspace, &list, index, &pane, window, cx)
+                .detach_and_notify_err(window, cx);
+        }
+        Err(error) => workspace.show_error(&error, cx),
+    }
+}
+
+/// Makes the entry the list's current one, and opens it in the pane.
+fn open_entry(
+    workspace: &mut Workspace,
+    list: &Entity<QuickfixList>,
+    index: usize,
+    pane: &Entity<Pane>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<Result<Entity<Editor>>> {
+    let Some(entry) = list.update(cx, |list, cx| {
+        let entry = list.entries.get(index).cloned()?;
+        list.current = index;
+        cx.notify();
+        Some(entry)
+    }) else {
+        return Task::ready(Err(anyhow!("No entry {}", index + 1)));
+    };
+
+    let open = workspace.open_path(entry.path.clone(), Some(pane.downgrade()), true, window, cx);
+    cx.spawn_in(window, async move |_, cx| {
+        let item = open.await?;
+        let editor = cx
+            .update(|_, cx| item.act_as::<Editor>(cx))?
+            .context("The entry isn't in a text file")?;
+        editor.update_in(cx, |editor, window, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let position = snapshot.clip_point(entry.current_position(cx), Bias::Left);
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |s| s.select_ranges([position..position]),
+            );
+        })?;
+        Ok(editor)
+    })
+}
+
+fn run_on_entries(
+    workspace: &mut Workspace,
+    action: &QuickfixDo,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let (list, pane) = resolve_list(workspace, action.kind, cx);
+    let indices = {
+        let entries = &list.read(cx).entries;
+        if action.per_file {
+            let mut paths = HashSet::default();
+            (0..entries.len())
+                .filter(|&ix| paths.insert(entries[ix].path.clone()))
+                .collect::<Vec<_>>()
+        } else {
+            (0..entries.len()).collect()
+        }
+    };
+    if indices.is_empty() {
+        workspace.show_error(&anyhow!("The {} is empty", action.kind.name()), cx);
+        return;
+    }
+
+    let commands = parse_ex_commands(&action.command, cx.weak_entity(), cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let commands = commands.await?;
+        for index in indices {
+            let editor = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    open_entry(workspace, &list, index, &pane, window, cx)
+                })?
+                .await?;
+            run_ex_commands(&editor, &commands, cx).await?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_notify_err(window, cx);
+}
+
+fn grep(
+    workspace: &mut Workspace,
+    action: &VimGrep,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let (list, pane) = resolve_list(workspace, action.kind, cx);
+    let project = workspace.project().clone();
+    let path_style = project.read(cx).path_style(cx);
+    let args = &action.args;
+
+    // `%` stands for the current file.
+    let current_path = pane
+        .read(cx)
+        .active_item()
+        .and_then(|item| item.project_path(cx));
+    let mut files = Vec::with_capacity(args.files.len());
+    for file in &args.files {
+        if file == "%" {
+            let Some(path) = &current_path else {
+                workspace.show_error(&anyhow!("No file is open"), cx);
+                return;
+            };
+            files.push(path.path.display(path_style).into_owned());
+        } else {
+            files.push(file.clone());
+        }
+    }
+
+    let settings = EditorSettings::get_global(cx);
+    let case_sensitive = if settings.use_smartcase_search {
+        args.pattern.chars().any(char::is_uppercase)
+    } else {
+        settings.search.case_sensitive
+    };
+    let query = PathMatcher::new(&files, path_style)
+        .map_err(anyhow::Error::from)
+        .and_then(|files_to_include| {
+            SearchQuery::regex(
+                &args.pattern,
+                false,
+                case_sensitive,
+                false,
+                !args.all_matches,
+                files_to_include,
+                PathMatcher::default(),
+                false,
+                None,
+            )
+        });
+    let query = match query {
+        Ok(query) => query,
+        Err(error) => {
+            workspace.show_error(&error, cx);
+            return;
+        }
+    };
+
+    let title = format!(
+        ":{} /{}/ {}",
+        match action.kind {
+            ListKind::Quickfix => "vimgrep",
+            ListKind::Location => "lvimgrep",
+        },
+        args.pattern,
+        files.join(" ")
+    );
+    let jump = args.jump;
+    let pattern = args.pattern.clone();
+    let search = project.update(cx, |project, cx| project.search(query, cx));
+    cx.spawn_in(window, async move |workspace, cx| {
+        // The search is cancelled when its task is dropped.
+        let _search_task = search._task_handle;
+        let mut entries = Vec::new();
+        while let Ok(result) = search.rx.recv().await {
+            let SearchResult::Buffer { buffer, ranges } = result else {
+                break;
+            };
+            cx.update(|_, cx| {
+                let Some(path) = buffer.read(cx).project_path(cx) else {
+                    return;
+                };
+                let snapshot = buffer.read(cx).snapshot();
+                for range in ranges {
+                    let position = range.start.to_point(&snapshot);
+                    let line = Point::new(position.row, 0)
+                        ..Point::new(position.row, snapshot.line_len(position.row));
+                    let text = snapshot
+                        .text_for_range(line)
+                        .collect::<String>()
+                        .trim()
+                        .into();
+                    entries.push(QuickfixEntry::new(
+                        &buffer,
+                        path.clone(),
+                        position,
+                        text,
+                        None,
+                        cx,
+                    ));
+                }
+            })?;
+        }
+        // The files are searched in parallel, so their matches come in any order.
+        entries.sort_by(|a, b| (&a.path, a.position).cmp(&(&b.path, b.position)));
+        anyhow::ensure!(!entries.is_empty(), "No match: {pattern}");
+
+        list.update(cx, |list, cx| list.set_entries(title, entries, cx));
+        if jump {
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    open_entry(workspace, &list, 0, &pane, window, cx)
+                })?
+                .await?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_notify_err(window, cx);
+}
+
+fn make(
+    workspace: &mut Workspace,
+    action: &VimMake,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let (list, pane) = resolve_list(workspace, action.kind, cx);
+    let project = workspace.project().clone();
+    let command = action.command.clone();
+
+    let spawn_in_terminal = {
+        let project = project.read(cx);
+        let cwd = project.first_project_directory(cx);
+        let shell = project.terminal_settings(&cwd, cx).shell.clone();
+        SpawnInTerminal {
+            id: TaskId("vim-make".to_string()),
+            full_label: command.clone(),
+            label: command.clone(),
+            command: Some(command.clone()),
+            args: Vec::new(),
+            command_label: command.clone(),
+            cwd,
+            env: HashMap::default(),
+            use_new_terminal: false,
+            allow_concurrent_runs: false,
+            reveal: RevealStrategy::NoFocus,
+            reveal_target: RevealTarget::Dock,
+            hide: HideStrategy::Never,
+            shell,
+            show_summary: true,
+            show_command: true,
+            show_rerun: false,
+            problem_matchers: ProblemMatcher::BUILT_IN_NAMES
+                .iter()
+                .filter_map(|name| ProblemMatcher::built_in(name))
+                .collect(),
+        }
+    };
+    let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
+    let title = format!(
+        ":{} {command}",
+        match action.kind {
+            ListKind::Quickfix => "make",
+            ListKind::Location => "lmake",
+        }
+    );
+    cx.spawn_in(window, async move |workspace, cx| {
+        // The command failing is expected when it reports problems, which are what's listed.
+        task_status.await;
+        // The problems found in the task's output are reported as its diagnostics, with the
+        // task's label as their source.
+        let entries = cx
+            .update(|_, cx| {
+                diagnostic_entries(
+                    &project,
+                    None,
+                    Some(TASK_DIAGNOSTICS_SERVER_ID),
+                    Some(command),
+                    cx,
+                )
+            })?
+            .await?;
+        let is_empty = entries.is_empty();
+        list.update(cx, |list, cx| list.set_entries(title, entries, cx));
+        if !is_empty {
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    open_entry(workspace, &list, 0, &pane, window, cx)
+                })?
+                .await?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_notify_err(window, cx);
+}
+
+/// Collects the errors and warnings reported for the files at the given paths, or for all the
+/// files with diagnostics, optionally only those of a language server with the given source.
+fn diagnostic_entries(
+    project: &Entity<Project>,
+    paths: Option<Vec<ProjectPath>>,
+    server_id: Option<language::LanguageServerId>,
+    source: Option<String>,
+    cx: &mut App,
+) -> Task<Result<Vec<QuickfixEntry>>> {
+    let paths = paths.unwrap_or_else(|| {
+        project
+            .read(cx)
+            .diagnostic_summaries(false, cx)
+            .filter(|(_, summary_server_id, summary)| {
+                server_id.is_none_or(|server_id| *summary_server_id == server_id)
+                    && (summary.error_count > 0 || summary.warning_count > 0)
+            })
+            .map(|(path, _, _)| path)
+            .unique()
+            .collect()
+    });
+    let buffers = paths
+        .into_iter()
+        .map(|path| project.update(cx, |project, cx| project.open_buffer(path, cx)))
+        .collect::<Vec<_>>();
+
+    cx.spawn(async move |cx| {
+        let mut entries = Vec::new();
+        for buffer in buffers {
+            // A file that can't be opened anymore just has its entries left out.
+            let buffer = match buffer.await {
+                Ok(buffer) => buffer,
+                Err(error) => {
+                    log::error!("failed to open a buffer with diagnostics: {error:#}");
+                    continue;
+                }
+            };
+            cx.update(|cx| {
+                let Some(path) = buffer.read(cx).project_path(cx) else {
+                    return;
+                };
+                let snapshot = buffer.read(cx).snapshot();
+                for (_, group) in snapshot.diagnostic_groups(server_id) {
+                    let Some(primary) = group.entries.get(group.primary_ix) else {
+                        continue;
+                    };
+                    let diagnostic = &primary.diagnostic;
+                    if diagnostic.severity > DiagnosticSeverity::WARNING
+                        || source
+                            .as_ref()
+                            .is_some_and(|source| diagnostic.source.as_ref() != Some(source))
+                    {
+                        continue;
+                    }
+                    entries.push(QuickfixEntry::new(
+                        &buffer,
+                        path.clone(),
+                        primary.range.start.to_point(&snapshot),
+                        diagnostic.message.lines().next().unwrap_or("").into(),
+                        Some(diagnostic.severity),
+                        cx,
+                    ));
+                }
+            });
+        }
+        entries.sort_by(|a, b| (&a.path, a.position).cmp(&(&b.path, b.position)));
+        Ok(entries)
+    })
+}
+
+/// The matches of the active project search, or of the first one open.
+fn entries_from_project_search(
+    workspace: &Workspace,
+    cx: &App,
+) -> Result<(String, Vec<QuickfixEntry>)> {
+    let search_view = workspace
+        .active_item_as::<ProjectSearchView>(cx)
+        .or_else(|| workspace.items_of_type::<ProjectSearchView>(cx).next())
+        .context("No project search is open")?;
+    let search_view = search_view.read(cx);
+    let multi_buffer = search_view.results_editor().read(cx).buffer().read(cx);
+    let snapshot = multi_buffer.snapshot(cx);
+
+    let mut entries = Vec::new();
+    for range in search_view.get_matches(cx) {
+        let point = range.start.to_point(&snapshot);
+        let Some((buffer, position, _)) = multi_buffer.point_to_buffer_point(point, cx) else {
+            continue;
+        };
+        let Some(path) = buffer.read(cx).project_path(cx) else {
+            continue;
+        };
+        let line = Point::new(position.row, 0)
+            ..Point::new(position.row, buffer.read(cx).line_len(position.row));
+        let text = buffer
+            .read(cx)
+            .text_for_range(line)
+            .collect::<String>()
+            .trim()
+            .into();
+        entries.push(QuickfixEntry::new(&buffer, path, position, text, None, cx));
+    }
+    Ok((search_view.search_query_text(cx), entries))
+}
+
+/// Shows a quickfix or location list, opening its entries in the pane it was opened from.
+pub struct QuickfixView {
+    list: Entity<QuickfixList>,
+    target_pane: WeakEntity<Pane>,
+    picker: Entity<Picker<QuickfixDelegate>>,
+    _subscription: Subscription,
+}
+
+impl QuickfixView {
+    fn new(
+        list: Entity<QuickfixList>,
+        target_pane: WeakEntity<Pane>,
+        workspace: WeakEntity<Workspace>,
+        path_style: PathStyle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = QuickfixDelegate {
+            list: list.clone(),
+            target_pane: target_pane.clone(),
+            workspace,
+            path_style,
+            entries: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| {
+            Picker::nonsearchable_uniform_list(delegate, window, cx)
+                .max_height(None)
+                .modal(false)
+        });
+        let subscription = cx.observe_in(&list, window, |this, _, window, cx| {
+            this.picker
+                .update(cx, |picker, cx| picker.refresh(window, cx));
+            cx.notify();
+        });
+        Self {
+            list,
+            target_pane,
+            picker,
+            _subscription: subscription,
+        }
+    }
+}
+
+impl EventEmitter<ItemEvent> for QuickfixView {}
+
+impl Focusable for QuickfixView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Item for QuickfixView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        match self.list.read(cx).kind {
+            ListKind::Quickfix => "Quickfix List".into(),
+            ListKind::Location => "Location List".into(),
+        }
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let title = &self.list.read(cx).title;
+        (!title.is_empty()).then(|| title.clone())
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for QuickfixView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .key_context("QuickfixView")
+            .size_full()
+            .child(self.picker.clone())
+    }
+}
+
+pub struct QuickfixDelegate {
+    list: Entity<QuickfixList>,
+    target_pane: WeakEntity<Pane>,
+    workspace: WeakEntity<Workspace>,
+    path_style: PathStyle,
+    entries: Vec<QuickfixEntry>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for QuickfixDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+        cx.notify();
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        Arc::default()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, cx: &mut App) -> Option<SharedString> {
+        Some(format!("The {} is empty", self.list.read(cx).kind.name()).into())
+    }
+
+    fn update_matches(
+        &mut self,
+        _: String,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let list = self.list.read(cx);
+        self.entries = list.entries.clone();
+        self.selected_index = list.current.min(self.entries.len().saturating_sub(1));
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some((workspace, pane)) = self.workspace.upgrade().zip(self.target_pane.upgrade())
+        else {
+            return;
+        };
+        let index = self.selected_index;
+        workspace
+            .update(cx, |workspace, cx| {
+                open_entry(workspace, &self.list, index, &pane, window, cx)
+            })
+            .detach_and_notify_err(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, _: &mut Context<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.entries.get(ix)?;
+        let position = entry.current_position(cx);
+        let location = format!(
+            "{}:{}:{}",
+            entry.path.path.display(self.path_style),
+            position.row + 1,
+            position.column + 1
+        );
+        let icon = entry.severity.map(|severity| match severity {
+            DiagnosticSeverity::ERROR => Icon::new(IconName::XCircle).color(Color::Error),
+            DiagnosticSeverity::WARNING => Icon::new(IconName::Warning).color(Color::Warning),
+            _ => Icon::new(IconName::Info).color(Color::Info),
+        });
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(icon.map(|icon| icon.size(IconSize::Small)))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(location).color(Color::Muted))
+                        .child(Label::new(entry.text.clone()).truncate()),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use util::path;
+
+    use super::*;
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[test]
+    fn test_parse_grep_args() {
+        assert_eq!(
+            GrepArgs::parse("/fo\\(o\\|ba\\/r\\)(/gj src/*.rs %"),
+            Some(GrepArgs {
+                pattern: "fo(o\\|ba/r)\\(".into(),
+                all_matches: true,
+                jump: false,
+                files: vec!["src/*.rs".into(), "%".into()],
+            })
+        );
+        assert_eq!(
+            GrepArgs::parse("foo_bar **/*.md"),
+            Some(GrepArgs {
+                pattern: "foo_bar".into(),
+                all_matches: false,
+                jump: true,
+                files: vec!["**/*.md".into()],
+            })
+        );
+        assert_eq!(
+            GrepArgs::parse("#a b"),
+            Some(GrepArgs {
+                pattern: "a b".into(),
+                all_matches: false,
+                jump: true,
+                files: Vec::new(),
+            })
+        );
+        assert_eq!(GrepArgs::parse("//"), None);
+        assert_eq!(GrepArgs::parse("/a/x"), None);
+    }
+
+    fn assert_active_position(cx: &mut VimTestContext, expected_path: &str, expected: Point) {
+        cx.workspace(|workspace, _, cx| {
+            let editor = workspace.active_item_as::<Editor>(cx).unwrap();
+            let (path, position) = editor.update(cx, |editor, cx| {
+                let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+                let path = buffer
+                    .read(cx)
+                    .file()
+                    .unwrap()
+                    .as_local()
+                    .unwrap()
+                    .abs_path(cx);
+                let snapshot = editor.display_snapshot(cx);
+                (path, editor.selections.newest::<Point>(&snapshot).head())
+            });
+            assert_eq!(path, Path::new(expected_path));
+            assert_eq!(position, expected);
+        });
+    }
+
+    fn editor_texts(cx: &mut VimTestContext) -> Vec<String> {
+        cx.workspace(|workspace, _, cx| {
+            workspace
+                .items_of_type::<Editor>(cx)
+                .map(|editor| editor.read(cx).text(cx))
+                .sorted()
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_vimgrep(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), "one\nfoo two foo\n".into())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), "  foo\nthree\nfoo\n".into())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/c.txt"), "foo\n".into())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": v i m g r e p space / f o o / space * . r s enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, path!("/root/dir/a.rs"), Point::new(1, 0));
+
+        cx.simulate_keystrokes(": c n enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, path!("/root/dir/b.rs"), Point::new(0, 2));
+
+        cx.simulate_keystrokes(": c l a s t enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, path!("/root/dir/b.rs"), Point::new(2, 0));
+
+        cx.simulate_keystrokes(": c p enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, path!("/root/dir/b.rs"), Point::new(0, 2));
+
+        cx.simulate_keystrokes(": c f i r s t enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, path!("/root/dir/a.rs"), Point::new(1, 0));
+
+        cx.simulate_keystrokes(": c c space 2 enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, path!("/root/dir/b.rs"), Point::new(0, 2));
+
+        // With `g`, every match of a line is listed.
+        cx.simulate_keystrokes(": v i m g r e p space / f o o / g space % enter");
+        cx.run_until_parked();
+        cx.workspace(|_, _, cx| {
+            let list = Vim::globals(cx).quickfix_lists[&cx.entity_id()].clone();
+            let positions = list
+                .read(cx)
+                .entries
+                .iter()
+                .map(|entry| entry.position)
+                .collect::<Vec<_>>();
+            assert_eq!(positions, [Point::new(0, 2), Point::new(2, 0)]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_cdo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), "foo\nbar\nfoo foo\n".into())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), "bar\nfoo\n".into())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": v i m g r e p space f o o space * . r s enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": c d o space s / o / 0 / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            editor_texts(&mut cx),
+            ["", "bar\nf0o\n", "f0o\nbar\nf0o foo\n"]
+        );
+
+        cx.simulate_keystrokes(": c f d o space n o r m space x enter");
+        cx.run_until_parked();
+        assert_eq!(
+            editor_texts(&mut cx),
+            ["", "0o\nbar\nf0o foo\n", "bar\n0o\n"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_cdo_follows_edits(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), "foo\nbar\nfoo\nbaz\nfoo\n".into())
+            .await;
+        cx.run_until_parked();
+
+        // Each deleted line moves the entries below it up.
+        cx.simulate_keystrokes(": v i m g r e p space f o o space * . r s enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": c d o space n o r m space d d enter");
+        cx.run_until_parked();
+        assert_eq!(editor_texts(&mut cx), ["", "bar\nbaz\n"]);
+    }
+
+    #[gpui::test]
+    async fn test_location_list(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state(
+            indoc! {"
+                ˇone foo
+                two
+                foo three
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": w enter");
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": l v i m g r e p space / f o o / space % enter");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+                one ˇfoo
+                two
+                foo three
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": l n e enter");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+                one foo
+                two
+                ˇfoo three
+            "},
+            Mode::Normal,
+        );
+
+        // The quickfix list is empty, so it still steps through diagnostics, and the location
+        // list belongs to the pane.
+        cx.workspace(|workspace, _, cx| {
+            let pane_id = workspace.active_pane().entity_id();
+            let globals = Vim::globals(cx);
+            let quickfix_list = globals.quickfix_lists.get(&cx.entity_id()).cloned();
+            let location_list = globals.location_lists[&pane_id].clone();
+            assert!(quickfix_list.is_none_or(|list| list.read(cx).entries.is_empty()));
+            assert_eq!(location_list.read(cx).entries.len(), 2);
+        });
+
+        cx.simulate_keystrokes(": l o p e n enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, _, cx| {
+            let view = workspace.active_item_as::<QuickfixView>(cx).unwrap();
+            assert_eq!(view.read(cx).picker.read(cx).delegate.entries.len(), 2);
+            assert_eq!(view.read(cx).picker.read(cx).delegate.selected_index, 1);
+            assert_eq!(workspace.panes().len(), 2);
+        });
+
+        // The list's commands act on the pane it was opened from.
+        cx.simulate_keystrokes(": l f i r s t enter");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+                one ˇfoo
+                two
+                foo three
+            "},
+            Mode::Normal,
+        );
+        cx.workspace(|workspace, _, cx| {
+            assert_eq!(workspace.items_of_type::<QuickfixView>(cx).count(), 1);
+        });
+
+        cx.simulate_keystrokes(": l c l enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, _, cx| {
+            assert_eq!(workspace.items_of_type::<QuickfixView>(cx).count(), 0);
+        });
+    }
+}
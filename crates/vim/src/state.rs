@@ -1,6 +1,9 @@
+use crate::arg_list::ArgList;
 use crate::command::command_interceptor;
+use crate::mapping::Mapping;
 use crate::motion::MotionKind;
 use crate::normal::repeat::Replayer;
+use crate::quickfix::QuickfixList;
 use crate::surrounds::SurroundsType;
 use crate::{ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings};
 use crate::{motion::Motion, object::Object};
@@ -15,7 +18,8 @@ use editor::display_map::{is_invisible, replacement};
 use editor::{Anchor, ClipboardSelection, Editor, MultiBuffer, ToPoint as EditorToPoint};
 use gpui::{
     Action, App, AppContext, BorrowAppContext, ClipboardEntry, ClipboardItem, DismissEvent, Entity,
-    EntityId, Global, HighlightStyle, StyledText, Subscription, Task, TextStyle, WeakEntity,
+    EntityId, Global, HighlightStyle, KeyBinding, StyledText, Subscription, Task, TextStyle,
+    WeakEntity,
 };
 use language::{Buffer, BufferEvent, BufferId, Chunk, Point};
 use multi_buffer::MultiBufferRow;
@@ -240,6 +244,22 @@ pub struct VimGlobals {
     pub focused_vim: Option<WeakEntity<Vim>>,
 
     pub marks: HashMap<EntityId, Entity<MarksState>>,
+
+    /// The quickfix list of each workspace.
+    pub quickfix_lists: HashMap<EntityId, Entity<QuickfixList>>,
+    /// The location list of each pane.
+    pub location_lists: HashMap<EntityId, Entity<QuickfixList>>,
+    /// The argument list of each workspace.
+    pub arg_lists: HashMap<EntityId, ArgList>,
+
+    /// The key mappings defined with `:map` and its variants.
+    pub mappings: Vec<Mapping>,
+    /// The key bindings currently bound for `mappings`.
+    pub mapping_bindings: Vec<KeyBinding>,
+    /// How many `:noremap` mappings are sending their keys, which the mappings don't apply to.
+    pub sending_noremap_keys: usize,
+    /// Insert mode abbreviations defined with `:abbreviate`.
+    pub abbreviations: HashMap<String, String>,
 }
 
 pub struct MarksState {
@@ -763,6 +783,8 @@ impl VimGlobals {
         cx.observe_release(&cx.entity(), move |_, _, cx| {
             Vim::update_globals(cx, |globals, _| {
                 globals.marks.remove(&entity_id);
+                globals.quickfix_lists.remove(&entity_id);
+                globals.arg_lists.remove(&entity_id);
             })
         })
         .detach();
@@ -3,16 +3,19 @@
 #[cfg(test)]
 mod test;
 
+mod arg_list;
 mod change_list;
 mod command;
 mod digraph;
 mod helix;
 mod indent;
 mod insert;
+mod mapping;
 mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod quickfix;
 mod replace;
 mod rewrap;
 mod state;
@@ -36,6 +39,7 @@ use insert::{NormalBefore, TemporaryNormal};
 use language::{
     CharKind, CharScopeContext, CursorShape, Point, Selection, SelectionGoal, TransactionId,
 };
+pub use mapping::bind_mappings;
 pub use mode_indicator::ModeIndicator;
 use motion::Motion;
 use multi_buffer::ToPoint as _;
@@ -279,6 +283,9 @@ pub fn init(cx: &mut App) {
     VimGlobals::register(cx);
 
     cx.observe_new(Vim::register).detach();
+    cx.observe_new(quickfix::register).detach();
+    cx.observe_new(arg_list::register).detach();
+    cx.observe_new(mapping::register).detach();
 
     cx.observe_new(|workspace: &mut Workspace, _, _| {
         workspace.register_action(|workspace, _: &ToggleVimMode, _, cx| {
@@ -1085,7 +1092,10 @@ impl Vim {
             EditorEvent::InputHandled {
                 text,
                 utf16_range_to_replace: range_to_replace,
-            } => Vim::globals(cx).observe_insertion(text, range_to_replace.clone()),
+            } => {
+                Vim::globals(cx).observe_insertion(text, range_to_replace.clone());
+                self.expand_abbreviation(text, cx);
+            }
             EditorEvent::TransactionBegun { transaction_id } => {
                 self.transaction_begun(*transaction_id, window, cx)
             }
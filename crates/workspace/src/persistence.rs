@@ -18,7 +18,7 @@ use db::{
 };
 use gpui::{Axis, Bounds, Task, WindowBounds, WindowId, point, size};
 use project::{
-    debugger::breakpoint_store::{BreakpointState, SourceBreakpoint},
+    debugger::breakpoint_store::{BreakpointState, FunctionBreakpoint, SourceBreakpoint},
     trusted_worktrees::{DbTrustedPaths, RemoteHostLocation},
 };
 
@@ -903,6 +903,18 @@ impl Domain for WorkspaceDb {
         sql!(
             ALTER TABLE remote_connections ADD COLUMN use_podman BOOLEAN;
         ),
+        sql!(
+            CREATE TABLE function_breakpoints (
+                workspace_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                condition TEXT,
+                hit_condition TEXT,
+                state INTEGER DEFAULT(0) NOT NULL,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            );
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -1038,6 +1050,7 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            function_breakpoints: self.function_breakpoints(workspace_id),
             window_id,
             user_toolchains: self.user_toolchains(workspace_id, remote_connection_id),
         })
@@ -1128,6 +1141,7 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            function_breakpoints: self.function_breakpoints(workspace_id),
             window_id,
             user_toolchains: self.user_toolchains(workspace_id, remote_connection_id),
         })
@@ -1179,6 +1193,41 @@ impl WorkspaceDb {
         }
     }
 
+    fn function_breakpoints(&self, workspace_id: WorkspaceId) -> Vec<FunctionBreakpoint> {
+        let breakpoints: Result<
+            Vec<(
+                String,
+                Option<String>,
+                Option<String>,
+                BreakpointStateWrapper<'static>,
+            )>,
+        > = self
+            .select_bound(sql! {
+                SELECT name, condition, hit_condition, state
+                FROM function_breakpoints
+                WHERE workspace_id = ?
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match breakpoints {
+            Ok(breakpoints) => breakpoints
+                .into_iter()
+                .map(
+                    |(name, condition, hit_condition, state)| FunctionBreakpoint {
+                        name: name.into(),
+                        condition: condition.map(Arc::from),
+                        hit_condition: hit_condition.map(Arc::from),
+                        state: state.0.into_owned(),
+                    },
+                )
+                .collect(),
+            Err(msg) => {
+                log::error!("Function breakpoints query failed with msg: {msg}");
+                Default::default()
+            }
+        }
+    }
+
     fn user_toolchains(
         &self,
         workspace_id: WorkspaceId,
@@ -1307,6 +1356,29 @@ impl WorkspaceDb {
                     }
                 }
 
+                conn.exec_bound(
+                    sql!(
+                        DELETE FROM function_breakpoints WHERE workspace_id = ?1;
+                    )
+                )?(workspace.id).context("Clearing old function breakpoints")?;
+
+                for bp in workspace.function_breakpoints {
+                    let state = BreakpointStateWrapper::from(bp.state);
+                    if let Err(err) = conn.exec_bound(sql!(
+                        INSERT INTO function_breakpoints (workspace_id, name, condition, hit_condition, state)
+                        VALUES (?1, ?2, ?3, ?4, ?5);))?
+
+                    ((
+                        workspace.id,
+                        bp.name.as_ref(),
+                        bp.condition,
+                        bp.hit_condition,
+                        state,
+                    )) {
+                        log::error!("{err}");
+                    }
+                }
+
                 conn.exec_bound(
                     sql!(
                         DELETE FROM user_toolchains WHERE workspace_id = ?1;
@@ -2383,6 +2455,15 @@ mod tests {
                 );
                 map
             },
+            function_breakpoints: vec![
+                FunctionBreakpoint::new("main".into()),
+                FunctionBreakpoint {
+                    name: "parse_args".into(),
+                    condition: Some("argc > 1".into()),
+                    hit_condition: Some("3".into()),
+                    state: BreakpointState::Disabled,
+                },
+            ],
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2391,6 +2472,8 @@ mod tests {
         db.save_workspace(workspace.clone()).await;
 
         let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.function_breakpoints, workspace.function_breakpoints);
+
         let loaded_breakpoints = loaded.breakpoints.get(&Arc::from(path)).unwrap();
 
         assert_eq!(loaded_breakpoints.len(), 5);
@@ -2504,6 +2587,7 @@ mod tests {
                 );
                 map
             },
+            function_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2538,6 +2622,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: collections::BTreeMap::default(),
+            function_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2636,6 +2721,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2651,6 +2737,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2755,6 +2842,7 @@ mod tests {
             center_group,
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2789,6 +2877,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2807,6 +2896,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             session_id: None,
             window_id: Some(2),
             user_toolchains: Default::default(),
@@ -2846,6 +2936,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2887,6 +2978,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(10),
             user_toolchains: Default::default(),
@@ -2902,6 +2994,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(20),
             user_toolchains: Default::default(),
@@ -2917,6 +3010,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(30),
             user_toolchains: Default::default(),
@@ -2932,6 +3026,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2958,6 +3053,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(50),
             user_toolchains: Default::default(),
@@ -2970,6 +3066,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -3029,6 +3126,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             centered_layout: false,
             session_id: None,
             window_id: None,
@@ -3066,6 +3164,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             window_id: Some(window_id),
             user_toolchains: Default::default(),
         })
@@ -3170,6 +3269,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             window_id: Some(window_id),
             user_toolchains: Default::default(),
         })
@@ -3524,6 +3624,7 @@ mod tests {
             display: None,
             docks: Default::default(),
             breakpoints: Default::default(),
+            function_breakpoints: Default::default(),
             centered_layout: false,
             session_id: None,
             window_id: None,
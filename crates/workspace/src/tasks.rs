@@ -1,11 +1,19 @@
-use std::process::ExitStatus;
+use std::{cell::RefCell, mem, path::PathBuf, process::ExitStatus, rc::Rc, sync::Arc};
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Context as _, Result, anyhow, bail};
+use collections::{HashMap, HashSet};
+use futures::{
+    FutureExt as _,
+    future::{self, LocalBoxFuture, Shared},
+};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task, WeakEntity};
 use language::Buffer;
 use project::{TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{
+    DebugScenario, DependsOrder, ResolvedTask, SpawnInTerminal, TaskContext, TaskGraph, TaskId,
+    TaskInput, TaskTemplate,
+};
 use ui::Window;
 
 use crate::{Toast, Workspace, notifications::NotificationId};
@@ -55,6 +63,27 @@ impl Workspace {
         cx: &mut Context<Workspace>,
     ) {
         let spawn_in_terminal = resolved_task.resolved.clone();
+        let dependency_graph = if resolved_task.original_task().depends_on.is_empty() {
+            None
+        } else {
+            self.project
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .map(|inventory| {
+                    inventory.read(cx).task_dependency_graph(
+                        task_source_kind.clone(),
+                        resolved_task.clone(),
+                        cx,
+                    )
+                })
+        };
+        let is_compound = resolved_task.original_task().is_compound();
+        let task_with_inputs = resolved_task
+            .refers_to_inputs()
+            .then(|| resolved_task.clone());
+
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -71,28 +100,50 @@ impl Workspace {
             });
         }
 
+        if let Some(dependency_graph) = dependency_graph {
+            let task = cx.spawn_in(window, async move |workspace, cx| {
+                let result = match dependency_graph.await {
+                    Ok(graph) => run_task_graph(workspace.clone(), graph, cx.clone()).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    log::error!("Task failed: {e:#}");
+                    _ = workspace.update(cx, |workspace, cx| {
+                        let id = NotificationId::unique::<ResolvedTask>();
+                        workspace.show_toast(Toast::new(id, format!("{e:#}")), cx);
+                    });
+                }
+            });
+            self.scheduled_tasks.push(task);
+            return;
+        }
+        if is_compound {
+            log::warn!(
+                "Cannot run the dependencies of task `{}` without a task inventory",
+                spawn_in_terminal.label
+            );
+            return;
+        }
+
+        if let Some(resolved_task) = task_with_inputs {
+            let task = cx.spawn_in(window, async move |workspace, cx| {
+                let res = match spawn_with_inputs(&workspace, &resolved_task, cx).await {
+                    Ok(Some(task_status)) => task_status.await,
+                    Ok(None) => None,
+                    Err(e) => Some(Err(e)),
+                };
+                report_task_status(res, &workspace, cx);
+            });
+            self.scheduled_tasks.push(task);
+            return;
+        }
+
         if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
 
             let task = cx.spawn(async |w, cx| {
                 let res = cx.background_spawn(task_status).await;
-                match res {
-                    Some(Ok(status)) => {
-                        if status.success() {
-                            log::debug!("Task spawn succeeded");
-                        } else {
-                            log::debug!("Task spawn failed, code: {:?}", status.code());
-                        }
-                    }
-                    Some(Err(e)) => {
-                        log::error!("Task spawn failed: {e:#}");
-                        _ = w.update(cx, |w, cx| {
-                            let id = NotificationId::unique::<ResolvedTask>();
-                            w.show_toast(Toast::new(id, format!("Task spawn failed: {e}")), cx);
-                        })
-                    }
-                    None => log::debug!("Task spawn got cancelled"),
-                };
+                report_task_status(res, &w, cx);
             });
             self.scheduled_tasks.push(task);
         }
@@ -119,6 +170,26 @@ impl Workspace {
         }
     }
 
+    /// Asks the user for the value of a task input, with the prompt registered by
+    /// [`Workspace::set_prompt_for_task_input`].
+    pub fn prompt_for_task_input(
+        &mut self,
+        input: TaskInput,
+        cwd: Option<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<String>>> {
+        let Some(prompt) = self.on_prompt_for_task_input.take() else {
+            return Task::ready(Err(anyhow!(
+                "Can't ask for the value of task input `{}`",
+                input.id()
+            )));
+        };
+        let value = prompt(self, input, cwd, window, cx);
+        self.on_prompt_for_task_input = Some(prompt);
+        value
+    }
+
     pub fn spawn_in_terminal(
         self: &mut Workspace,
         spawn_in_terminal: SpawnInTerminal,
@@ -131,4 +202,391 @@ impl Workspace {
             Task::ready(None)
         }
     }
+
+    pub fn kill_task_in_terminal(
+        &mut self,
+        task_id: &TaskId,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if let Some(terminal_provider) = self.terminal_provider.as_ref() {
+            terminal_provider.kill(task_id, window, cx);
+        }
+    }
+}
+
+type TaskRun = Shared<LocalBoxFuture<'static, Result<(), Arc<anyhow::Error>>>>;
+
+#[derive(Default)]
+struct TaskGraphRun {
+    /// The run of every task of the graph that got started, so that a task several others depend
+    /// on only runs once.
+    runs: HashMap<TaskId, TaskRun>,
+    /// The tasks whose process is still running.
+    running: HashSet<TaskId>,
+}
+
+/// Runs the dependencies of a task, each in its own terminal, then the task itself.
+/// Stops at the first task that fails, without starting the tasks that depend on it, and kills
+/// the tasks that are still running.
+async fn run_task_graph(
+    workspace: WeakEntity<Workspace>,
+    graph: TaskGraph<(TaskSourceKind, ResolvedTask)>,
+    mut cx: AsyncWindowContext,
+) -> Result<()> {
+    let graph_run = Rc::new(RefCell::new(TaskGraphRun::default()));
+    let result = run_task_graph_node(workspace.clone(), graph, graph_run.clone(), cx.clone())
+        .await
+        .map_err(|e| anyhow!("{e:#}"));
+
+    // The runs refer to the graph run, drop them to break the cycle.
+    let runs = mem::take(&mut graph_run.borrow_mut().runs);
+    drop(runs);
+    let running = mem::take(&mut graph_run.borrow_mut().running);
+    if result.is_err() && !running.is_empty() {
+        workspace
+            .update_in(&mut cx, |workspace, window, cx| {
+                for task_id in &running {
+                    workspace.kill_task_in_terminal(task_id, window, cx);
+                }
+            })
+            .ok();
+    }
+    result
+}
+
+fn run_task_graph_node(
+    workspace: WeakEntity<Workspace>,
+    graph: TaskGraph<(TaskSourceKind, ResolvedTask)>,
+    graph_run: Rc<RefCell<TaskGraphRun>>,
+    mut cx: AsyncWindowContext,
+) -> TaskRun {
+    let task_id = graph.task.1.id.clone();
+    if let Some(run) = graph_run.borrow().runs.get(&task_id) {
+        return run.clone();
+    }
+
+    let run = {
+        let graph_run = graph_run.clone();
+        async move {
+            let TaskGraph {
+                task: (_, task),
+                order,
+                dependencies,
+            } = graph;
+            match order {
+                DependsOrder::Sequence => {
+                    for dependency in dependencies {
+                        run_task_graph_node(
+                            workspace.clone(),
+                            dependency,
+                            graph_run.clone(),
+                            cx.clone(),
+                        )
+                        .await?;
+                    }
+                }
+                DependsOrder::Parallel => {
+                    future::try_join_all(dependencies.into_iter().map(|dependency| {
+                        run_task_graph_node(
+                            workspace.clone(),
+                            dependency,
+                            graph_run.clone(),
+                            cx.clone(),
+                        )
+                    }))
+                    .await?;
+                }
+            }
+            if task.original_task().is_compound() {
+                return Ok(());
+            }
+            run_graph_task(&workspace, &task, &graph_run, &mut cx)
+                .await
+                .map_err(Arc::new)
+        }
+        .boxed_local()
+        .shared()
+    };
+    graph_run.borrow_mut().runs.insert(task_id, run.clone());
+    run
+}
+
+async fn run_graph_task(
+    workspace: &WeakEntity<Workspace>,
+    task: &ResolvedTask,
+    graph_run: &RefCell<TaskGraphRun>,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    let label = task.display_label().to_string();
+    let Some(task_status) = spawn_with_inputs(workspace, task, cx).await? else {
+        bail!("Task `{label}` got cancelled");
+    };
+    graph_run.borrow_mut().running.insert(task.id.clone());
+    let task_status = task_status.await;
+    graph_run.borrow_mut().running.remove(&task.id);
+    match task_status {
+        Some(Ok(status)) if status.success() => Ok(()),
+        Some(Ok(status)) => match status.code() {
+            Some(code) => bail!("Task `{label}` failed with exit code {code}"),
+            None => bail!("Task `{label}` failed"),
+        },
+        Some(Err(e)) => Err(e).with_context(|| format!("Task `{label}` failed to spawn")),
+        None => bail!("Task `{label}` got cancelled"),
+    }
+}
+
+/// Asks the user for the values of the inputs the task refers to, one after another, then spawns
+/// the task with them. Resolves to `None` without spawning the task if any prompt got dismissed.
+async fn spawn_with_inputs(
+    workspace: &WeakEntity<Workspace>,
+    task: &ResolvedTask,
+    cx: &mut AsyncWindowContext,
+) -> Result<Option<Task<Option<Result<ExitStatus>>>>> {
+    let mut values = HashMap::default();
+    for input in task.inputs() {
+        let value = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.prompt_for_task_input(
+                    input.clone(),
+                    task.resolved.cwd.clone(),
+                    window,
+                    cx,
+                )
+            })?
+            .await?;
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        values.insert(input.id().to_string(), value);
+    }
+
+    let spawn_in_terminal = task.resolved_with_inputs(&values)?;
+    workspace
+        .update_in(cx, |workspace, window, cx| {
+            workspace.spawn_in_terminal(spawn_in_terminal, window, cx)
+        })
+        .map(Some)
+}
+
+fn report_task_status(
+    status: Option<Result<ExitStatus>>,
+    workspace: &WeakEntity<Workspace>,
+    cx: &mut impl AppContext,
+) {
+    match status {
+        Some(Ok(status)) => {
+            if status.success() {
+                log::debug!("Task spawn succeeded");
+            } else {
+                log::debug!("Task spawn failed, code: {:?}", status.code());
+            }
+        }
+        Some(Err(e)) => {
+            log::error!("Task spawn failed: {e:#}");
+            _ = workspace.update(cx, |workspace, cx| {
+                let id = NotificationId::unique::<ResolvedTask>();
+                workspace.show_toast(Toast::new(id, format!("Task spawn failed: {e}")), cx);
+            })
+        }
+        None => log::debug!("Task spawn got cancelled"),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use fs::FakeFs;
+    use futures::channel::oneshot;
+    use gpui::{App, TestAppContext};
+    use project::Project;
+
+    use super::*;
+    use crate::{TerminalProvider, tests::init_test};
+
+    #[derive(Default)]
+    struct FakeTerminals {
+        spawned: Vec<String>,
+        running: HashMap<TaskId, (String, oneshot::Sender<Option<Result<ExitStatus>>>)>,
+        killed: Vec<String>,
+    }
+
+    impl FakeTerminals {
+        fn finish(&mut self, label: &str, status: Result<ExitStatus>) {
+            let task_id = self
+                .running
+                .iter()
+                .find_map(|(task_id, (running_label, _))| {
+                    (running_label == label).then(|| task_id.clone())
+                })
+                .unwrap_or_else(|| panic!("task `{label}` isn't running"));
+            let (_, tx) = self.running.remove(&task_id).unwrap();
+            tx.send(Some(status)).ok();
+        }
+
+        fn running_labels(&self) -> Vec<String> {
+            let mut labels = self
+                .running
+                .values()
+                .map(|(label, _)| label.clone())
+                .collect::<Vec<_>>();
+            labels.sort();
+            labels
+        }
+    }
+
+    struct FakeTerminalProvider(Rc<RefCell<FakeTerminals>>);
+
+    impl TerminalProvider for FakeTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            cx: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            let (tx, rx) = oneshot::channel();
+            let mut terminals = self.0.borrow_mut();
+            terminals.spawned.push(task.label.clone());
+            terminals.running.insert(task.id, (task.label, tx));
+            cx.foreground_executor()
+                .spawn(async move { rx.await.ok().flatten() })
+        }
+
+        fn kill(&self, task_id: &TaskId, _: &mut Window, _: &mut App) {
+            let mut terminals = self.0.borrow_mut();
+            if let Some((label, _)) = terminals.running.remove(task_id) {
+                terminals.killed.push(label);
+            }
+        }
+    }
+
+    fn graph(
+        label: &str,
+        command: &str,
+        order: DependsOrder,
+        dependencies: Vec<TaskGraph<(TaskSourceKind, ResolvedTask)>>,
+    ) -> TaskGraph<(TaskSourceKind, ResolvedTask)> {
+        let template = TaskTemplate {
+            label: label.to_string(),
+            command: command.to_string(),
+            depends_on: dependencies
+                .iter()
+                .map(|dependency| dependency.task.1.original_task().label.clone())
+                .collect(),
+            depends_order: order,
+            ..TaskTemplate::default()
+        };
+        let task = template
+            .resolve_task("test", &TaskContext::default())
+            .unwrap();
+        TaskGraph {
+            task: (TaskSourceKind::UserInput, task),
+            order,
+            dependencies,
+        }
+    }
+
+    async fn init_workspace(
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<Workspace>,
+        Rc<RefCell<FakeTerminals>>,
+        &mut gpui::VisualTestContext,
+    ) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let terminals = Rc::new(RefCell::new(FakeTerminals::default()));
+        workspace.update(cx, |workspace, _| {
+            workspace.set_terminal_provider(FakeTerminalProvider(terminals.clone()));
+        });
+        (workspace, terminals, cx)
+    }
+
+    #[gpui::test]
+    async fn test_shared_dependency_runs_once(cx: &mut TestAppContext) {
+        let (workspace, terminals, cx) = init_workspace(cx).await;
+
+        // ci -> (lint | test), and both lint and test depend on build.
+        let build = || graph("build", "cargo build", DependsOrder::Sequence, Vec::new());
+        let ci = graph(
+            "ci",
+            "",
+            DependsOrder::Parallel,
+            vec![
+                graph(
+                    "lint",
+                    "cargo clippy",
+                    DependsOrder::Sequence,
+                    vec![build()],
+                ),
+                graph("test", "cargo test", DependsOrder::Sequence, vec![build()]),
+            ],
+        );
+        let run = cx.update(|window, cx| {
+            let workspace = workspace.downgrade();
+            window.spawn(cx, async move |cx| {
+                run_task_graph(workspace, ci, cx.clone()).await
+            })
+        });
+
+        cx.run_until_parked();
+        assert_eq!(terminals.borrow().running_labels(), ["build"]);
+
+        terminals
+            .borrow_mut()
+            .finish("build", Ok(ExitStatus::default()));
+        cx.run_until_parked();
+        assert_eq!(terminals.borrow().running_labels(), ["lint", "test"]);
+
+        terminals
+            .borrow_mut()
+            .finish("lint", Ok(ExitStatus::default()));
+        terminals
+            .borrow_mut()
+            .finish("test", Ok(ExitStatus::default()));
+        run.await.unwrap();
+
+        let terminals = terminals.borrow();
+        assert_eq!(terminals.spawned, ["build", "lint", "test"]);
+        assert!(terminals.killed.is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_failing_task_kills_running_siblings(cx: &mut TestAppContext) {
+        let (workspace, terminals, cx) = init_workspace(cx).await;
+
+        let ci = graph(
+            "ci",
+            "",
+            DependsOrder::Parallel,
+            vec![
+                graph("lint", "cargo clippy", DependsOrder::Sequence, Vec::new()),
+                graph("test", "cargo test", DependsOrder::Sequence, Vec::new()),
+            ],
+        );
+        let run = cx.update(|window, cx| {
+            let workspace = workspace.downgrade();
+            window.spawn(cx, async move |cx| {
+                run_task_graph(workspace, ci, cx.clone()).await
+            })
+        });
+
+        cx.run_until_parked();
+        assert_eq!(terminals.borrow().running_labels(), ["lint", "test"]);
+
+        terminals
+            .borrow_mut()
+            .finish("lint", Err(anyhow!("clippy crashed")));
+        let error = run.await.unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "Task `lint` failed to spawn: clippy crashed"
+        );
+
+        let terminals = terminals.borrow();
+        assert_eq!(terminals.killed, ["test"]);
+        assert!(terminals.running.is_empty());
+    }
 }
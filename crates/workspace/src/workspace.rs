@@ -116,7 +116,7 @@ use std::{
     },
     time::Duration,
 };
-use task::{DebugScenario, SpawnInTerminal, TaskContext};
+use task::{DebugScenario, SpawnInTerminal, TaskContext, TaskId, TaskInput};
 use theme::{ActiveTheme, GlobalTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 pub use ui;
@@ -171,6 +171,8 @@ pub trait TerminalProvider {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Option<Result<ExitStatus>>>;
+    /// Kills the process of the task with the given id, if it's still running.
+    fn kill(&self, task_id: &TaskId, window: &mut Window, cx: &mut App);
 }
 
 pub trait DebuggerProvider {
@@ -1162,6 +1164,18 @@ type PromptForOpenPath = Box<
     ) -> oneshot::Receiver<Option<Vec<PathBuf>>>,
 >;
 
+/// Asks the user for the value of a task input, given the working directory of the task.
+/// Resolves to `None` if the prompt got dismissed.
+type PromptForTaskInput = Box<
+    dyn Fn(
+        &mut Workspace,
+        TaskInput,
+        Option<PathBuf>,
+        &mut Window,
+        &mut Context<Workspace>,
+    ) -> Task<Result<Option<String>>>,
+>;
+
 #[derive(Default)]
 struct DispatchingKeystrokes {
     dispatched: HashSet<Vec<Keystroke>>,
@@ -1219,6 +1233,7 @@ pub struct Workspace {
     bounds_save_task_queued: Option<Task<()>>,
     on_prompt_for_new_path: Option<PromptForNewPath>,
     on_prompt_for_open_path: Option<PromptForOpenPath>,
+    on_prompt_for_task_input: Option<PromptForTaskInput>,
     terminal_provider: Option<Box<dyn TerminalProvider>>,
     debugger_provider: Option<Arc<dyn DebuggerProvider>>,
     serializable_items_tx: UnboundedSender<Box<dyn SerializableItemHandle>>,
@@ -1396,7 +1411,8 @@ impl Workspace {
             window,
             |workspace, _, event, window, cx| match event {
                 BreakpointStoreEvent::BreakpointsUpdated(_, _)
-                | BreakpointStoreEvent::BreakpointsCleared(_) => {
+                | BreakpointStoreEvent::BreakpointsCleared(_)
+                | BreakpointStoreEvent::FunctionBreakpointsUpdated => {
                     workspace.serialize_workspace(window, cx);
                 }
                 BreakpointStoreEvent::SetDebugLine | BreakpointStoreEvent::ClearDebugLines => {}
@@ -1635,6 +1651,7 @@ impl Workspace {
             bounds_save_task_queued: None,
             on_prompt_for_new_path: None,
             on_prompt_for_open_path: None,
+            on_prompt_for_task_input: None,
             terminal_provider: None,
             debugger_provider: None,
             serializable_items_tx,
@@ -2323,6 +2340,10 @@ impl Workspace {
         self.on_prompt_for_open_path = Some(prompt)
     }
 
+    pub fn set_prompt_for_task_input(&mut self, prompt: PromptForTaskInput) {
+        self.on_prompt_for_task_input = Some(prompt)
+    }
+
     pub fn set_terminal_provider(&mut self, provider: impl TerminalProvider + 'static) {
         self.terminal_provider = Some(Box::new(provider));
     }
@@ -5866,11 +5887,13 @@ impl Workspace {
 
         match self.serialize_workspace_location(cx) {
             WorkspaceLocation::Location(location, paths) => {
-                let breakpoints = self.project.update(cx, |project, cx| {
-                    project
-                        .breakpoint_store()
-                        .read(cx)
-                        .all_source_breakpoints(cx)
+                let (breakpoints, function_breakpoints) = self.project.update(cx, |project, cx| {
+                    let breakpoint_store = project.breakpoint_store();
+                    let breakpoint_store = breakpoint_store.read(cx);
+                    (
+                        breakpoint_store.all_source_breakpoints(cx),
+                        breakpoint_store.function_breakpoints().to_vec(),
+                    )
                 });
                 let user_toolchains = self
                     .project
@@ -5893,6 +5916,7 @@ impl Workspace {
                     centered_layout: self.centered_layout,
                     session_id: self.session_id.clone(),
                     breakpoints,
+                    function_breakpoints,
                     window_id: Some(window.window_handle().window_id().as_u64()),
                     user_toolchains,
                 };
@@ -6102,6 +6126,10 @@ impl Workspace {
                     project
                         .breakpoint_store()
                         .update(cx, |breakpoint_store, cx| {
+                            breakpoint_store.with_serialized_function_breakpoints(
+                                serialized_workspace.function_breakpoints,
+                                cx,
+                            );
                             breakpoint_store
                                 .with_serialized_breakpoints(serialized_workspace.breakpoints, cx)
                         })
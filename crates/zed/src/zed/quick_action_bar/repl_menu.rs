@@ -172,6 +172,15 @@ impl QuickActionBar {
                             }
                         },
                     )
+                    .custom_entry(
+                        move |_window, _cx| {
+                            Label::new("View Variables")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        move |window, cx| window.dispatch_action(Box::new(repl::Variables), cx),
+                    )
                     .separator()
                     .custom_entry(
                         move |_window, _cx| {